@@ -27,6 +27,14 @@ pub struct OverallStatistics {
     pub total_profit_usd: f64,
     pub today_trades: i64,
     pub today_profit_usd: f64,
+    /// 已对比预估/实际利润的成功交易数
+    pub profit_delta_samples: i64,
+    /// 平均利润偏差 (实际 - 预估, USD)，负数表示系统性高估
+    pub avg_profit_delta_usd: f64,
+    /// 平均偏差占预估利润的百分比
+    pub avg_profit_error_pct: f64,
+    /// 本次运行各链的收益分布 (利润直方图、最大回撤、滚动胜率)
+    pub session_profit: Vec<strategies::ProfitReport>,
 }
 
 /// 获取总体统计
@@ -78,6 +86,22 @@ pub async fn get_statistics(
     .await
     .unwrap_or(0.0);
 
+    // 预估利润与实际利润偏差
+    let (profit_delta_samples, avg_profit_delta_usd, avg_profit_error_pct): (i64, f64, f64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*),
+            CAST(COALESCE(AVG(delta_usd), 0) AS DOUBLE),
+            CAST(COALESCE(AVG(error_pct), 0) AS DOUBLE)
+        FROM trade_profit_deltas
+        "#
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or((0, 0.0, 0.0));
+
+    let session_profit = state.strategy_manager.profit_reports().await;
+
     Json(ApiResponse::success(OverallStatistics {
         total_strategies,
         running_strategies,
@@ -85,6 +109,10 @@ pub async fn get_statistics(
         total_profit_usd,
         today_trades,
         today_profit_usd,
+        profit_delta_samples,
+        avg_profit_delta_usd,
+        avg_profit_error_pct,
+        session_profit,
     }))
 }
 
@@ -305,3 +305,73 @@ pub async fn delete_strategy(
         Err(e) => Json(ApiResponse::error(e.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::routes::create_server;
+    use crate::state::AppState;
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use services::Database;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use strategies::StrategyControl;
+    use tower::ServiceExt;
+
+    /// 桩策略管理器: 记录持久化的状态 (执行开关的联动由 ArbitrageStrategyManager 的测试覆盖)
+    #[derive(Default)]
+    struct StubManager {
+        statuses: Mutex<HashMap<i64, String>>,
+    }
+
+    #[async_trait]
+    impl StrategyControl for StubManager {
+        async fn start_strategy(&self, strategy_id: i64) -> anyhow::Result<()> {
+            self.statuses.lock().unwrap().insert(strategy_id, "running".to_string());
+            Ok(())
+        }
+
+        async fn stop_strategy(&self, strategy_id: i64) -> anyhow::Result<()> {
+            let mut statuses = self.statuses.lock().unwrap();
+            match statuses.get_mut(&strategy_id) {
+                Some(status) => {
+                    *status = "stopped".to_string();
+                    Ok(())
+                }
+                None => Err(anyhow::anyhow!("策略 {} 不存在", strategy_id)),
+            }
+        }
+
+        async fn get_running_strategy_ids(&self) -> Vec<i64> {
+            let statuses = self.statuses.lock().unwrap();
+            statuses.iter().filter(|(_, s)| *s == "running").map(|(id, _)| *id).collect()
+        }
+    }
+
+    async fn post(manager: Arc<StubManager>, uri: &str) -> StatusCode {
+        // 惰性连接池: 启停接口不访问数据库
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .connect_lazy("mysql://root@127.0.0.1:3306/test")
+            .unwrap();
+        let state = AppState::new(Database::from_pool(pool), manager);
+        let app = create_server(state, "127.0.0.1", 0).await;
+        let request = Request::builder().method("POST").uri(uri).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_stop_strategy_routes_to_manager_and_persists_status() {
+        let manager = Arc::new(StubManager::default());
+
+        assert_eq!(post(manager.clone(), "/api/strategies/5/start").await, StatusCode::OK);
+        assert_eq!(manager.get_running_strategy_ids().await, vec![5]);
+
+        assert_eq!(post(manager.clone(), "/api/strategies/5/stop").await, StatusCode::OK);
+        assert_eq!(manager.statuses.lock().unwrap()[&5], "stopped");
+        assert!(manager.get_running_strategy_ids().await.is_empty());
+
+        // 不存在的策略返回错误
+        assert_eq!(post(manager, "/api/strategies/9/stop").await, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,8 +1,10 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     Json,
 };
-use serde::Serialize;
+use executor::{global_revert_log, RevertRecord};
+use serde::{Deserialize, Serialize};
+use utils::{get_api_stats, SwapStreamSnapshot};
 
 use crate::state::AppState;
 use super::strategy::ApiResponse;
@@ -14,6 +16,8 @@ pub struct SystemStatus {
     pub database_connected: bool,
     pub uptime_seconds: u64,
     pub version: String,
+    /// 各链 Swap 订阅状态 (距上次 Swap 的秒数、看门狗重新订阅次数)
+    pub swap_streams: Vec<SwapStreamSnapshot>,
 }
 
 #[derive(Serialize)]
@@ -27,6 +31,11 @@ pub struct PoolInfo {
     pub last_updated_block: i64,
 }
 
+#[derive(Deserialize)]
+pub struct RecentRevertsQuery {
+    pub limit: Option<usize>,
+}
+
 /// 获取系统状态
 pub async fn get_system_status(
     State(state): State<AppState>,
@@ -47,6 +56,7 @@ pub async fn get_system_status(
         database_connected,
         uptime_seconds: 0, // TODO: 实现 uptime 追踪
         version: env!("CARGO_PKG_VERSION").to_string(),
+        swap_streams: get_api_stats().swap_streams,
     }))
 }
 
@@ -80,3 +90,11 @@ pub async fn list_pools(
         Err(e) => Json(ApiResponse::error(e.to_string())),
     }
 }
+
+/// 获取最近的 revert 记录 (已解码，最新的在前)
+pub async fn get_recent_reverts(
+    Query(query): Query<RecentRevertsQuery>,
+) -> Json<ApiResponse<Vec<RevertRecord>>> {
+    let limit = query.limit.unwrap_or(20).min(200);
+    Json(ApiResponse::success(global_revert_log().recent(limit)))
+}
@@ -37,9 +37,12 @@ pub async fn create_server(state: AppState, _host: &str, _port: u16) -> Router {
         .route("/api/statistics/:strategy_id", get(handlers::get_strategy_statistics))
         // 套利机会
         .route("/api/opportunities", get(handlers::list_opportunities))
+        .route("/api/opportunity", get(handlers::query_opportunities))
+        .route("/api/opportunity/:id/execute", post(handlers::execute_opportunity))
         // 系统状态
         .route("/api/system/status", get(handlers::get_system_status))
         .route("/api/system/pools", get(handlers::list_pools))
+        .route("/api/system/recent-reverts", get(handlers::get_recent_reverts))
         .layer(cors)
         .with_state(state);
 
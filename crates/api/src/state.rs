@@ -1,31 +1,51 @@
-use ethers::providers::{Provider, Middleware};
+use axum::http::{header, HeaderMap, StatusCode};
 use services::Database;
 use sqlx::{MySql, Pool};
 use std::sync::Arc;
-use strategies::ArbitrageStrategyManager;
-use utils::StatsHttp;
+use strategies::StrategyControl;
 
-/// API 应用状态 (使用带统计的 Provider)
-pub type AppState = AppStateGeneric<Provider<StatsHttp>>;
-
-/// 泛型 API 应用状态
+/// API 应用状态
 #[derive(Clone)]
-pub struct AppStateGeneric<M: Middleware + 'static> {
+pub struct AppState {
     pub db: Pool<MySql>,
     pub database: Arc<Database>,
-    /// 套利策略管理器
-    pub strategy_manager: Arc<ArbitrageStrategyManager<M>>,
+    /// 套利策略管理器 (启停策略并联动各链扫描器的自动执行)
+    pub strategy_manager: Arc<dyn StrategyControl>,
+    /// 会发送交易的接口所需的管理令牌 (未配置时这些接口禁用)
+    pub admin_token: Option<String>,
 }
 
-impl<M: Middleware + 'static> AppStateGeneric<M> {
+impl AppState {
     pub fn new(
         database: Database,
-        strategy_manager: Arc<ArbitrageStrategyManager<M>>,
+        strategy_manager: Arc<dyn StrategyControl>,
     ) -> Self {
         Self {
             db: database.pool().clone(),
             database: Arc::new(database),
             strategy_manager,
+            admin_token: None,
+        }
+    }
+
+    /// 设置管理令牌
+    pub fn with_admin_token(mut self, admin_token: Option<String>) -> Self {
+        self.admin_token = admin_token.filter(|token| !token.is_empty());
+        self
+    }
+
+    /// 校验请求头 `Authorization: Bearer <API_ADMIN_TOKEN>`
+    pub fn authorize_admin(&self, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+        let Some(expected) = self.admin_token.as_deref() else {
+            return Err((StatusCode::FORBIDDEN, "未配置 API_ADMIN_TOKEN，该接口已禁用".to_string()));
+        };
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if provided != Some(expected) {
+            return Err((StatusCode::UNAUTHORIZED, "管理令牌无效".to_string()));
         }
+        Ok(())
     }
 }
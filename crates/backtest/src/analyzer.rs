@@ -4,7 +4,7 @@ use anyhow::Result;
 use chrono::{TimeZone, Utc};
 use chrono_tz::Asia::Shanghai;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tracing::{info, debug};
 
@@ -42,21 +42,34 @@ fn get_token_decimals(symbol: &str) -> u8 {
 /// 从 sqrtPriceX96 计算价格
 /// 返回 token1/token0 的价格（以 token0 为单位计价 token1）
 fn sqrt_price_x96_to_price(sqrt_price_x96: &str, decimals0: u8, decimals1: u8) -> Option<f64> {
-    let sqrt_price_big: u128 = sqrt_price_x96.parse().ok()?;
-
-    // Q96 = 2^96
-    let q96: f64 = (1u128 << 96) as f64;
-
-    // sqrtPrice = sqrtPriceX96 / 2^96
-    let sqrt_price = sqrt_price_big as f64 / q96;
-
-    // price = sqrtPrice^2
-    let raw_price = sqrt_price * sqrt_price;
+    crate::price::sqrt_price_x96_to_price(sqrt_price_x96, decimals0, decimals1)
+}
 
-    // 调整小数位数: token1/token0 需要调整 10^(decimals0 - decimals1)
-    let decimal_adjustment = 10f64.powi(decimals0 as i32 - decimals1 as i32);
+/// SplitMix64: 由种子确定性地生成伪随机数 (回测只需要可复现，不需要密码学强度)
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
 
-    Some(raw_price * decimal_adjustment)
+/// 区块抽样: 从 start_block 起每 interval 个区块为一个窗口，每个窗口在有交易的区块中
+/// 按种子抽取一个；interval <= 1 时返回全部区块。blocks 需按升序排列，结果同样升序
+pub fn sample_blocks(blocks: &[u64], start_block: u64, interval: u64, seed: u64) -> Vec<u64> {
+    if interval <= 1 {
+        return blocks.to_vec();
+    }
+    let mut windows: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+    for &block in blocks {
+        windows.entry(block.saturating_sub(start_block) / interval).or_default().push(block);
+    }
+    windows
+        .into_iter()
+        .map(|(window, candidates)| {
+            let index = splitmix64(seed ^ window) % candidates.len() as u64;
+            candidates[index as usize]
+        })
+        .collect()
 }
 
 /// 三角套利分析器
@@ -144,11 +157,17 @@ impl ArbitrageAnalyzer {
 
         info!("共加载 {} 条 Swap 记录", swaps.len());
 
+        Ok(self.analyze_swaps(swaps, start_block, end_block))
+    }
+
+    /// 基于已加载的 Swap 数据分析 (按区块升序遍历，结果只取决于输入数据和随机种子)
+    fn analyze_swaps(&self, swaps: Vec<SwapRecord>, start_block: u64, end_block: u64) -> BacktestStatistics {
         // 按区块分组 Swap 数据
-        let mut block_swaps: HashMap<u64, Vec<SwapRecord>> = HashMap::new();
+        let mut block_swaps: BTreeMap<u64, Vec<SwapRecord>> = BTreeMap::new();
         for swap in swaps {
             block_swaps.entry(swap.block_number).or_default().push(swap);
         }
+        self.retain_sampled_blocks(&mut block_swaps, start_block);
 
         let blocks_with_swaps = block_swaps.len() as u64;
         info!("有交易的区块数: {}", blocks_with_swaps);
@@ -399,19 +418,42 @@ impl ArbitrageAnalyzer {
         info!("总分析机会数: {}", all_opportunities.len());
         info!("盈利机会数: {}", profitable_opportunities.len());
 
-        let stats = BacktestStatistics {
+        BacktestStatistics {
             start_block,
             end_block,
             start_timestamp: 0,
             end_timestamp: 0,
             total_blocks: end_block - start_block,
+            sample_interval: self.config.analysis_sample_interval,
+            seed: self.config.seed,
+            chain_id: self.config.chain_id,
+            slippage_bps: self.config.slippage_bps,
             blocks_with_swaps,
             total_volume_usd: total_volume,
-            path_stats: path_stats_map.into_values().collect(),
+            path_stats: self.ordered_path_stats(path_stats_map),
             profitable_opportunities,
-        };
+        }
+    }
 
-        Ok(stats)
+    /// 按采样配置只保留抽中的区块
+    fn retain_sampled_blocks<T>(&self, blocks: &mut BTreeMap<u64, T>, start_block: u64) {
+        let interval = self.config.analysis_sample_interval;
+        if interval <= 1 {
+            return;
+        }
+        let all: Vec<u64> = blocks.keys().copied().collect();
+        let sampled = sample_blocks(&all, start_block, interval, self.config.seed);
+        info!("区块采样: 每 {} 个区块抽取 1 个 (种子 {}): {} -> {} 个区块", interval, self.config.seed, all.len(), sampled.len());
+        let sampled: std::collections::HashSet<u64> = sampled.into_iter().collect();
+        blocks.retain(|block, _| sampled.contains(block));
+    }
+
+    /// 路径统计按配置顺序输出 (HashMap 的遍历顺序每次运行都不同)
+    fn ordered_path_stats(&self, mut path_stats_map: HashMap<String, PathStatistics>) -> Vec<PathStatistics> {
+        self.paths
+            .iter()
+            .filter_map(|path| path_stats_map.remove(&path.path_name))
+            .collect()
     }
 
     /// 真实的三跳套利计算 - 追踪实际代币数量
@@ -573,7 +615,8 @@ impl ArbitrageAnalyzer {
         let zero_for_one = token0 == token_in;
 
         let fee_rate = pool.fee as f64 / 1_000_000.0;
-        let amount_after_fee = amount_in * (1.0 - fee_rate);
+        let slippage_rate = self.config.slippage_bps as f64 / 10_000.0;
+        let amount_after_fee = amount_in * (1.0 - fee_rate) * (1.0 - slippage_rate);
 
         let output = if zero_for_one {
             // token0 -> token1: output = input * price
@@ -704,7 +747,8 @@ impl ArbitrageAnalyzer {
         info!("共有 {} 条 Swap 记录", swaps.len());
 
         // 按区块分组
-        let mut block_volumes: HashMap<u64, HashMap<String, f64>> = HashMap::new();
+        // 内层也用 BTreeMap: 浮点累加的顺序固定，两次运行的总交易量逐位一致
+        let mut block_volumes: BTreeMap<u64, BTreeMap<String, f64>> = BTreeMap::new();
         let mut block_timestamps: HashMap<u64, u64> = HashMap::new();
 
         for (block_number, block_timestamp, pool_address, usd_volume) in &swaps {
@@ -712,6 +756,7 @@ impl ArbitrageAnalyzer {
             *pool_volumes.entry(pool_address.to_lowercase()).or_default() += usd_volume;
             block_timestamps.entry(*block_number).or_insert(*block_timestamp);
         }
+        self.retain_sampled_blocks(&mut block_volumes, start_block);
 
         let blocks_with_swaps = block_volumes.len() as u64;
         let total_volume: f64 = block_volumes.values()
@@ -770,7 +815,8 @@ impl ArbitrageAnalyzer {
                 let fee3 = self.get_lowest_fee(&token_c, &token_a)
                     .unwrap_or(trigger_pool_config.fee_percent());
 
-                let total_fee_pct = fee1 + fee2 + fee3;
+                // 三跳各按配置滑点折损
+                let total_fee_pct = fee1 + fee2 + fee3 + 3.0 * self.config.slippage_bps as f64 / 100.0;
 
                 // 保守的价格偏差估计
                 let price_diff_pct = 0.01; // 1个基点
@@ -878,9 +924,13 @@ impl ArbitrageAnalyzer {
             start_timestamp: 0,
             end_timestamp: 0,
             total_blocks: end_block - start_block,
+            sample_interval: self.config.analysis_sample_interval,
+            seed: self.config.seed,
+            chain_id: self.config.chain_id,
+            slippage_bps: self.config.slippage_bps,
             blocks_with_swaps,
             total_volume_usd: total_volume,
-            path_stats: path_stats_map.into_values().collect(),
+            path_stats: self.ordered_path_stats(path_stats_map),
             profitable_opportunities,
         };
 
@@ -893,3 +943,124 @@ impl ArbitrageAnalyzer {
         pools.first().map(|p| p.fee_percent())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    fn pool(address: &str, token0: (&str, &str), token1: (&str, &str), fee: i32) -> PoolConfig {
+        PoolConfig {
+            id: 0,
+            chain_id: 1,
+            address: address.to_string(),
+            dex_type: "uniswap_v3".to_string(),
+            token0: token0.0.to_string(),
+            token0_symbol: token0.1.to_string(),
+            token1: token1.0.to_string(),
+            token1_symbol: token1.1.to_string(),
+            fee,
+            enabled: true,
+        }
+    }
+
+    fn path(name: &str, trigger_pool: &str, tokens: [&str; 3], pools: [&str; 3]) -> PoolPathConfig {
+        PoolPathConfig {
+            id: 0,
+            chain_id: 1,
+            trigger_pool: trigger_pool.to_string(),
+            path_name: name.to_string(),
+            triangle_name: "DAI-USDC-USDT".to_string(),
+            token_a: tokens[0].to_string(),
+            token_b: tokens[1].to_string(),
+            token_c: tokens[2].to_string(),
+            pool1: pools[0].to_string(),
+            pool2: pools[1].to_string(),
+            pool3: pools[2].to_string(),
+            priority: 100,
+            enabled: true,
+        }
+    }
+
+    fn swap(block_number: u64, pool_address: &str, sqrt_price_x96: &str, usd_volume: f64) -> SwapRecord {
+        SwapRecord {
+            block_number,
+            block_timestamp: 1_700_000_000 + block_number * 12,
+            pool_address: pool_address.to_string(),
+            amount0: "1000000000000000000000".to_string(),
+            amount1: "-1000000000".to_string(),
+            sqrt_price_x96: sqrt_price_x96.to_string(),
+            tick: 0,
+            liquidity: "1000000000000000000".to_string(),
+            usd_volume,
+        }
+    }
+
+    fn analyzer(config: BacktestConfig) -> ArbitrageAnalyzer {
+        let (dai, usdc, usdt) = (("0xdai", "DAI"), ("0xusdc", "USDC"), ("0xusdt", "USDT"));
+        let pools = vec![
+            pool("0xp1", dai, usdc, 100),
+            pool("0xp2", usdc, usdt, 100),
+            pool("0xp3", dai, usdt, 500),
+        ];
+        let paths = vec![
+            path("DAI->USDC->USDT->DAI", "0xp1", ["0xdai", "0xusdc", "0xusdt"], ["0xp1", "0xp2", "0xp3"]),
+            path("DAI->USDT->USDC->DAI", "0xp1", ["0xdai", "0xusdt", "0xusdc"], ["0xp3", "0xp2", "0xp1"]),
+            path("USDC->DAI->USDT->USDC", "0xp2", ["0xusdc", "0xdai", "0xusdt"], ["0xp1", "0xp3", "0xp2"]),
+        ];
+        let db = MySqlPoolOptions::new().connect_lazy("mysql://root@127.0.0.1:3306/test").unwrap();
+        ArbitrageAnalyzer::new(config, Arc::new(BacktestDatabase::from_pool(db)), pools, paths)
+    }
+
+    fn sample_swaps() -> Vec<SwapRecord> {
+        (0..40u64)
+            .flat_map(|i| {
+                let block = 19_000_000 + i * 3;
+                vec![
+                    swap(block, "0xp1", "79225718686740701537553", 50_000.0 + i as f64 * 17.3),
+                    swap(block, "0xp2", "79228162514264337593543950336", 20_000.0 + i as f64 * 0.1),
+                    swap(block, "0xp3", "79231000000000000000000", 7_000.5),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_blocks_is_reproducible_per_seed() {
+        let blocks: Vec<u64> = (0..300).collect();
+
+        let picked = sample_blocks(&blocks, 0, 100, 42);
+        assert_eq!(picked.len(), 3);
+        assert_eq!(picked, sample_blocks(&blocks, 0, 100, 42));
+        // 每个窗口抽一个
+        for (window, block) in picked.iter().enumerate() {
+            assert_eq!(*block / 100, window as u64);
+        }
+        // 换种子会换一批区块
+        assert!((0..8).any(|seed| sample_blocks(&blocks, 0, 100, seed) != picked));
+        // 不采样时返回全部
+        assert_eq!(sample_blocks(&blocks, 0, 0, 42), blocks);
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_produces_identical_reports() {
+        let config = BacktestConfig { analysis_sample_interval: 10, seed: 7, ..Default::default() };
+        let (start, end) = (19_000_000, 19_000_120);
+
+        let first = analyzer(config.clone()).analyze_swaps(sample_swaps(), start, end);
+        let second = analyzer(config).analyze_swaps(sample_swaps(), start, end);
+        assert!(first.blocks_with_swaps < 40);
+
+        let dir = std::env::temp_dir().join(format!("backtest_seed_{}", std::process::id()));
+        let (dir_a, dir_b) = (dir.join("a"), dir.join("b"));
+        let template = crate::report::DEFAULT_REPORT_TEMPLATE;
+        crate::report::generate_report(&first, dir_a.to_str().unwrap(), template).unwrap();
+        crate::report::generate_report(&second, dir_b.to_str().unwrap(), template).unwrap();
+        for file in ["backtest_report.txt", "backtest_report.json"] {
+            let a = std::fs::read(dir_a.join(file)).unwrap();
+            let b = std::fs::read(dir_b.join(file)).unwrap();
+            assert_eq!(a, b, "{} 不一致", file);
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
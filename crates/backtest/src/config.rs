@@ -16,6 +16,12 @@ pub struct BacktestConfig {
     pub days: u64,
     /// 采样间隔（每 N 个区块采样一次）
     pub sample_interval: u64,
+    /// 分析时的区块采样窗口（每 N 个区块抽取一个有交易的区块，0 表示分析全部区块）
+    pub analysis_sample_interval: u64,
+    /// 随机种子（区块抽样等随机选择都由它决定，相同种子的两次分析结果完全一致）
+    pub seed: u64,
+    /// 每跳额外计入的滑点 (bps)，用于参数扫描，0 表示只计手续费
+    pub slippage_bps: u32,
     /// 捕获比例列表
     pub capture_percentages: Vec<u32>,
     /// ETH 价格（用于计算 Gas 成本）
@@ -58,6 +64,9 @@ impl BacktestConfig {
             chain_id: 1, // Ethereum mainnet
             days: 90,
             sample_interval: 100, // 每 100 个区块采样一次（约 20 分钟）
+            analysis_sample_interval: 0,
+            seed: 0,
+            slippage_bps: 0,
             capture_percentages: vec![10, 25, 50, 100],
             eth_price_usd: 3800.0,
             gas_per_swap: 180_000,
@@ -87,6 +96,9 @@ impl Default for BacktestConfig {
             chain_id: 1,
             days: 90,
             sample_interval: 100,
+            analysis_sample_interval: 0,
+            seed: 0,
+            slippage_bps: 0,
             capture_percentages: vec![10, 25, 50, 100],
             eth_price_usd: 3800.0,
             gas_per_swap: 180_000,
@@ -30,6 +30,11 @@ impl BacktestDatabase {
         Ok(Self { pool })
     }
 
+    /// 使用已有连接池创建
+    pub fn from_pool(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
     /// 初始化回测相关表
     pub async fn initialize_tables(&self) -> Result<()> {
         // 回测 Swap 数据表
@@ -5,6 +5,7 @@
 //! 2. 从数据库读取池子配置
 //! 3. 分析 24 条三角套利路径
 //! 4. 生成分析报告
+//! 5. 按指定区块复现 QuoterV2 报价
 
 pub mod config;
 pub mod database;
@@ -13,6 +14,7 @@ pub mod analyzer;
 pub mod models;
 pub mod price;
 pub mod report;
+pub mod replay;
 
 pub use config::BacktestConfig;
 pub use database::BacktestDatabase;
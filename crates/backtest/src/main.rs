@@ -7,11 +7,22 @@
 //!   # 分析数据
 //!   cargo run -p backtest -- analyze
 //!
+//!   # 抽样分析 (每 100 个区块抽 1 个，相同种子可复现)
+//!   cargo run -p backtest -- analyze --sample-interval 100 --seed 42
+//!
+//!   # 滑点参数扫描 (每次运行的报告按模板命名，互不覆盖，output/report_index.json 汇总所有运行)
+//!   cargo run -p backtest -- analyze --slippage-bps 10 --report-template "{chain}_{date}_s{slippage}_seed{seed}"
+//!
 //!   # 一次性下载并分析
 //!   cargo run -p backtest -- all
+//!
+//!   # 按历史区块复现 QuoterV2 报价 (需要归档节点)
+//!   cargo run -p backtest -- quote --token-in 0x... --token-out 0x... --fee 500 --amount 1000000000000000000 --block 19000000
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use ethers::providers::{Http, Provider};
+use ethers::types::{Address, U256};
 use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -22,6 +33,7 @@ use backtest::{
     downloader::SwapDataDownloader,
     analyzer::ArbitrageAnalyzer,
     report::generate_report,
+    replay::quote_exact_input_at_block,
 };
 
 #[derive(Parser)]
@@ -62,6 +74,22 @@ enum Commands {
         /// 使用简化模型（不使用真实价格）
         #[arg(long, default_value = "false")]
         simple: bool,
+
+        /// 区块采样窗口（每 N 个区块抽取 1 个有交易的区块分析，默认分析全部区块）
+        #[arg(long, default_value = "0")]
+        sample_interval: u64,
+
+        /// 随机种子（相同种子 + 相同数据的两次分析生成完全相同的报告）
+        #[arg(long, default_value = "0")]
+        seed: u64,
+
+        /// 每跳额外计入的滑点（bps）
+        #[arg(long, default_value = "0")]
+        slippage_bps: u32,
+
+        /// 报告文件名模板（不含扩展名），支持 {chain} {date} {slippage} {seed}
+        #[arg(long, default_value = backtest::report::DEFAULT_REPORT_TEMPLATE)]
+        report_template: String,
     },
 
     /// 下载并分析
@@ -77,10 +105,49 @@ enum Commands {
         /// 输出目录
         #[arg(short, long, default_value = "backtest_data")]
         output: String,
+
+        /// 随机种子（用于分析阶段的随机选择）
+        #[arg(long, default_value = "0")]
+        seed: u64,
+
+        /// 每跳额外计入的滑点（bps）
+        #[arg(long, default_value = "0")]
+        slippage_bps: u32,
+
+        /// 报告文件名模板（不含扩展名），支持 {chain} {date} {slippage} {seed}
+        #[arg(long, default_value = backtest::report::DEFAULT_REPORT_TEMPLATE)]
+        report_template: String,
     },
 
     /// 显示池子和路径配置
     Show,
+
+    /// 按指定区块调用 QuoterV2 报价（复现历史报价，较早的区块需要归档节点）
+    Quote {
+        /// 输入代币地址
+        #[arg(long)]
+        token_in: String,
+
+        /// 输出代币地址
+        #[arg(long)]
+        token_out: String,
+
+        /// 池子费率（如 500 表示 0.05%）
+        #[arg(long, default_value = "3000")]
+        fee: u32,
+
+        /// 输入数量（最小单位）
+        #[arg(long)]
+        amount: String,
+
+        /// 报价区块（默认最新区块）
+        #[arg(long)]
+        block: Option<u64>,
+
+        /// QuoterV2 地址（默认 Ethereum 主网）
+        #[arg(long)]
+        quoter: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -101,6 +168,33 @@ async fn main() -> Result<()> {
     // 加载配置
     let mut config = BacktestConfig::from_env()?;
 
+    // 报价复现只需要 RPC，不连接数据库
+    if let Commands::Quote { token_in, token_out, fee, amount, block, quoter } = &cli.command {
+        let provider = Arc::new(Provider::<Http>::try_from(config.eth_rpc_url.as_str())?);
+        let quoter = match quoter {
+            Some(quoter) => quoter.parse::<Address>()?,
+            None => *dex::v3_addresses::QUOTER_V2,
+        };
+        let amount_in = U256::from_dec_str(amount)?;
+
+        let quote = quote_exact_input_at_block(
+            provider,
+            quoter,
+            token_in.parse()?,
+            token_out.parse()?,
+            *fee,
+            amount_in,
+            *block,
+        ).await?;
+
+        println!("\n=== QuoterV2 报价 (区块 {}) ===", quote.block);
+        println!("输入数量:       {}", amount_in);
+        println!("输出数量:       {}", quote.amount_out);
+        println!("报价后 sqrtP:   {}", quote.sqrt_price_x96_after);
+        println!("Gas 估算:       {}", quote.gas_estimate);
+        return Ok(());
+    }
+
     // 创建数据库连接
     let db = Arc::new(BacktestDatabase::new(&config.database_url).await?);
     db.initialize_tables().await?;
@@ -122,7 +216,11 @@ async fn main() -> Result<()> {
             info!("下载完成，共 {} 条记录", count);
         }
 
-        Commands::Analyze { start_block, end_block, output, simple } => {
+        Commands::Analyze { start_block, end_block, output, simple, sample_interval, seed, slippage_bps, report_template } => {
+            config.analysis_sample_interval = sample_interval;
+            config.seed = seed;
+            config.slippage_bps = slippage_bps;
+
             // 获取区块范围
             let (start, end) = if let (Some(s), Some(e)) = (start_block, end_block) {
                 (s, e)
@@ -148,12 +246,14 @@ async fn main() -> Result<()> {
                 analyzer.analyze(start, end).await?
             };
 
-            generate_report(&stats, &output)?;
+            generate_report(&stats, &output, &report_template)?;
         }
 
-        Commands::All { days, interval, output } => {
+        Commands::All { days, interval, output, seed, slippage_bps, report_template } => {
             config.days = days;
             config.sample_interval = interval;
+            config.seed = seed;
+            config.slippage_bps = slippage_bps;
 
             // 下载
             info!("=== 阶段 1: 下载数据 ===");
@@ -175,9 +275,11 @@ async fn main() -> Result<()> {
             let analyzer = ArbitrageAnalyzer::new(config, db, pools, paths);
             let stats = analyzer.analyze(start, end).await?;
 
-            generate_report(&stats, &output)?;
+            generate_report(&stats, &output, &report_template)?;
         }
 
+        Commands::Quote { .. } => unreachable!("quote 子命令已在连接数据库前处理"),
+
         Commands::Show => {
             println!("\n=== 池子配置 ({} 个) ===", pools.len());
             println!("{:-<100}", "");
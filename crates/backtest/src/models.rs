@@ -249,6 +249,18 @@ pub struct BacktestStatistics {
     pub end_timestamp: u64,
     /// 分析的区块数
     pub total_blocks: u64,
+    /// 区块采样窗口 (0 表示分析全部区块)
+    #[serde(default)]
+    pub sample_interval: u64,
+    /// 随机种子 (用于复现)
+    #[serde(default)]
+    pub seed: u64,
+    /// 链 ID
+    #[serde(default)]
+    pub chain_id: u64,
+    /// 每跳额外滑点 (bps)
+    #[serde(default)]
+    pub slippage_bps: u32,
     /// 有交易的区块数
     pub blocks_with_swaps: u64,
     /// 总交易量
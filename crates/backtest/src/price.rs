@@ -2,57 +2,19 @@
 
 use ethers::types::U256;
 use rust_decimal::Decimal;
-use std::str::FromStr;
-
-/// Q96 常量 (2^96)
-const Q96: u128 = 1u128 << 96;
 
 /// 从 sqrtPriceX96 计算价格
 /// 价格 = (sqrtPriceX96 / 2^96)^2
 /// 返回 token1/token0 的价格
 pub fn sqrt_price_x96_to_price(sqrt_price_x96: &str, decimals0: u8, decimals1: u8) -> Option<f64> {
     let sqrt_price = U256::from_dec_str(sqrt_price_x96).ok()?;
-
-    // 转换为 f64 进行计算
-    // sqrtPrice = sqrtPriceX96 / 2^96
-    let sqrt_price_f64 = sqrt_price.as_u128() as f64 / Q96 as f64;
-
-    // price = sqrtPrice^2
-    let raw_price = sqrt_price_f64 * sqrt_price_f64;
-
-    // 调整小数位数: price * 10^(decimals0 - decimals1)
-    let decimal_adjustment = 10f64.powi(decimals0 as i32 - decimals1 as i32);
-
-    Some(raw_price * decimal_adjustment)
+    Some(dex::sqrt_price_x96_to_price(sqrt_price, decimals0, decimals1))
 }
 
-/// 从 sqrtPriceX96 计算高精度价格 (使用 Decimal)
+/// 从 sqrtPriceX96 计算高精度价格 (使用 Decimal，整数运算无精度损失)
 pub fn sqrt_price_x96_to_price_decimal(sqrt_price_x96: &str, decimals0: u8, decimals1: u8) -> Option<Decimal> {
-    let sqrt_price_big = U256::from_dec_str(sqrt_price_x96).ok()?;
-
-    // 使用字符串转换来保持精度
-    let sqrt_price_str = sqrt_price_big.to_string();
-    let sqrt_price_dec = Decimal::from_str(&sqrt_price_str).ok()?;
-
-    let q96_dec = Decimal::from_str(&Q96.to_string()).ok()?;
-
-    // sqrtPrice = sqrtPriceX96 / 2^96
-    let sqrt_price = sqrt_price_dec / q96_dec;
-
-    // price = sqrtPrice^2
-    let raw_price = sqrt_price * sqrt_price;
-
-    // 调整小数位数
-    let decimal_diff = decimals0 as i32 - decimals1 as i32;
-    let adjustment = Decimal::from(10i64.pow(decimal_diff.unsigned_abs()));
-
-    let price = if decimal_diff >= 0 {
-        raw_price * adjustment
-    } else {
-        raw_price / adjustment
-    };
-
-    Some(price)
+    let sqrt_price = U256::from_dec_str(sqrt_price_x96).ok()?;
+    dex::sqrt_price_x96_to_price_decimal(sqrt_price, decimals0, decimals1)
 }
 
 /// 计算 V3 swap 输出金额 (精确计算)
@@ -0,0 +1,138 @@
+//! 历史区块报价复现
+//!
+//! 把 QuoterV2 的 eth_call 固定到指定区块执行，拿到该区块状态下的报价，
+//! 用于核对历史机会当时的报价是否可信。查询较早的区块需要归档节点
+
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use ethers::types::{Address, U256};
+use std::sync::Arc;
+
+// Uniswap V3 QuoterV2 ABI (单跳报价)
+abigen!(
+    ReplayQuoterV2,
+    r#"[
+        struct QuoteExactInputSingleParams { address tokenIn; address tokenOut; uint256 amountIn; uint24 fee; uint160 sqrtPriceLimitX96; }
+        function quoteExactInputSingle(QuoteExactInputSingleParams memory params) external returns (uint256 amountOut, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate)
+    ]"#
+);
+
+/// 历史区块报价结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoricalQuote {
+    /// 报价所在区块
+    pub block: u64,
+    pub amount_out: U256,
+    pub sqrt_price_x96_after: U256,
+    pub gas_estimate: U256,
+}
+
+/// 在指定区块调用 QuoterV2.quoteExactInputSingle
+///
+/// `block` 为 None 时先取最新区块号再固定到该区块，保证输出的区块号和报价对应
+pub async fn quote_exact_input_at_block<M: Middleware + 'static>(
+    provider: Arc<M>,
+    quoter: Address,
+    token_in: Address,
+    token_out: Address,
+    fee: u32,
+    amount_in: U256,
+    block: Option<u64>,
+) -> Result<HistoricalQuote> {
+    let block = match block {
+        Some(block) => block,
+        None => provider
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow::anyhow!("获取最新区块失败: {}", e))?
+            .as_u64(),
+    };
+
+    let contract = ReplayQuoterV2::new(quoter, provider);
+    let params = QuoteExactInputSingleParams {
+        token_in,
+        token_out,
+        amount_in,
+        fee,
+        sqrt_price_limit_x96: U256::zero(),
+    };
+
+    let (amount_out, sqrt_price_x96_after, _ticks_crossed, gas_estimate) = contract
+        .quote_exact_input_single(params)
+        .block(BlockId::from(block))
+        .call()
+        .await
+        .with_context(|| format!("区块 {} 报价失败 (较早的区块需要归档节点)", block))?;
+
+    Ok(HistoricalQuote {
+        block,
+        amount_out,
+        sqrt_price_x96_after,
+        gas_estimate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+    use executor::testing::MockMiddleware;
+
+    const QUOTE_SIG: &str = "quoteExactInputSingle((address,address,uint256,uint24,uint160))";
+
+    /// 按区块号返回不同报价的节点 (模拟归档节点)，quotes 为 (区块号, amountOut)
+    fn archive_node(latest: u64, quotes: &[(u64, u64)]) -> MockMiddleware {
+        let quotes: std::collections::HashMap<u64, U256> =
+            quotes.iter().map(|(b, q)| (*b, U256::from(*q))).collect();
+        let mock = MockMiddleware::new();
+        mock.set_block_numbers(&[latest]);
+        mock.on_call_fn(QUOTE_SIG, move |call| {
+            let number = match call.block {
+                Some(BlockId::Number(BlockNumber::Number(n))) => n.as_u64(),
+                other => return Err(format!("未固定区块: {:?}", other)),
+            };
+            let amount_out = quotes.get(&number).ok_or_else(|| format!("missing trie node (区块 {})", number))?;
+            Ok(vec![
+                Token::Uint(*amount_out),
+                Token::Uint(U256::one() << 96),
+                Token::Uint(U256::from(1u64)),
+                Token::Uint(U256::from(90_000u64)),
+            ])
+        });
+        mock
+    }
+
+    async fn quote(mock: MockMiddleware, block: Option<u64>) -> Result<HistoricalQuote> {
+        quote_exact_input_at_block(
+            Arc::new(mock),
+            Address::repeat_byte(0x61),
+            Address::repeat_byte(0xa0),
+            Address::repeat_byte(0xb0),
+            500,
+            U256::exp10(18),
+            block,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_quote_pinned_to_historical_block() {
+        let quotes = [(100, 3_000_000_000), (200, 2_500_000_000)];
+
+        let historical = quote(archive_node(200, &quotes), Some(100)).await.unwrap();
+        assert_eq!(historical.block, 100);
+        assert_eq!(historical.amount_out, U256::from(3_000_000_000u64));
+        assert_eq!(historical.gas_estimate, U256::from(90_000u64));
+
+        // 未指定区块时固定到最新区块
+        let latest = quote(archive_node(200, &quotes), None).await.unwrap();
+        assert_eq!(latest.block, 200);
+        assert_eq!(latest.amount_out, U256::from(2_500_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_quote_missing_state_reports_block() {
+        let err = quote(archive_node(200, &[(200, 1)]), Some(50)).await.unwrap_err();
+        assert!(err.to_string().contains("区块 50"));
+    }
+}
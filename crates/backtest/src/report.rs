@@ -1,34 +1,131 @@
 //! 回测报告生成
+//!
+//! 报告文件名由模板决定，支持 `{chain}` `{date}` `{slippage}` `{seed}` 占位符，
+//! 参数扫描时每次运行的报告互不覆盖；输出目录下的 report_index.json 记录所有运行
 
 use anyhow::Result;
+use chrono::Utc;
+use chrono_tz::Asia::Shanghai;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::models::BacktestStatistics;
 
+/// 默认报告文件名 (不含扩展名)
+pub const DEFAULT_REPORT_TEMPLATE: &str = "backtest_report";
+/// 运行索引文件名
+pub const REPORT_INDEX_FILE: &str = "report_index.json";
+
+/// 运行索引中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportIndexEntry {
+    /// 报告文件名 (不含扩展名)
+    pub name: String,
+    /// 生成时间 (上海时间)
+    pub generated_at: String,
+    pub chain_id: u64,
+    pub start_block: u64,
+    pub end_block: u64,
+    pub slippage_bps: u32,
+    pub seed: u64,
+    pub sample_interval: u64,
+    /// 盈利机会数
+    pub profitable_count: usize,
+    /// 盈利机会的净利润合计
+    pub total_net_profit_usd: f64,
+}
+
 /// 生成回测报告
-pub fn generate_report(stats: &BacktestStatistics, output_dir: &str) -> Result<()> {
+///
+/// `filename_template` 为报告文件名模板 (不含扩展名)，见 [`report_file_stem`]
+pub fn generate_report(stats: &BacktestStatistics, output_dir: &str, filename_template: &str) -> Result<()> {
     fs::create_dir_all(output_dir)?;
 
+    let now = Utc::now().with_timezone(&Shanghai);
+    let stem = report_file_stem(filename_template, stats, &now.format("%Y%m%d").to_string());
+
     // 生成文本报告
     let report = format_text_report(stats);
-    let report_path = Path::new(output_dir).join("backtest_report.txt");
+    let report_path = Path::new(output_dir).join(format!("{}.txt", stem));
     fs::write(&report_path, &report)?;
     info!("文本报告已保存: {:?}", report_path);
 
     // 生成 JSON 报告
     let json_report = serde_json::to_string_pretty(stats)?;
-    let json_path = Path::new(output_dir).join("backtest_report.json");
+    let json_path = Path::new(output_dir).join(format!("{}.json", stem));
     fs::write(&json_path, &json_report)?;
     info!("JSON 报告已保存: {:?}", json_path);
 
+    let entry = ReportIndexEntry {
+        name: stem,
+        generated_at: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        chain_id: stats.chain_id,
+        start_block: stats.start_block,
+        end_block: stats.end_block,
+        slippage_bps: stats.slippage_bps,
+        seed: stats.seed,
+        sample_interval: stats.sample_interval,
+        profitable_count: stats.profitable_opportunities.len(),
+        total_net_profit_usd: stats.profitable_opportunities.iter().map(|o| o.net_profit_usd).sum(),
+    };
+    update_report_index(Path::new(output_dir), entry)?;
+
     // 打印摘要
     println!("{}", report);
 
     Ok(())
 }
 
+/// 按模板生成报告文件名 (不含扩展名)
+///
+/// 占位符: `{chain}` 链名 (未知链为链 ID)、`{date}` 生成日期、`{slippage}` 每跳滑点 bps、`{seed}` 随机种子。
+/// 模板展开为空时使用默认文件名
+pub fn report_file_stem(template: &str, stats: &BacktestStatistics, date: &str) -> String {
+    let stem = template
+        .replace("{chain}", &chain_label(stats.chain_id))
+        .replace("{date}", date)
+        .replace("{slippage}", &stats.slippage_bps.to_string())
+        .replace("{seed}", &stats.seed.to_string())
+        .replace(['/', '\\'], "_");
+    if stem.trim().is_empty() {
+        DEFAULT_REPORT_TEMPLATE.to_string()
+    } else {
+        stem
+    }
+}
+
+fn chain_label(chain_id: u64) -> String {
+    match chain_id {
+        1 => "eth".to_string(),
+        56 => "bsc".to_string(),
+        137 => "polygon".to_string(),
+        42161 => "arbitrum".to_string(),
+        8453 => "base".to_string(),
+        10 => "optimism".to_string(),
+        43114 => "avalanche".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 把本次运行写入索引 (同名报告重新生成时替换旧记录)
+fn update_report_index(output_dir: &Path, entry: ReportIndexEntry) -> Result<()> {
+    let index_path = output_dir.join(REPORT_INDEX_FILE);
+    let mut entries: Vec<ReportIndexEntry> = match fs::read_to_string(&index_path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("报告索引 {:?} 无法解析 ({})，重新生成", index_path, e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+    entries.retain(|e| e.name != entry.name);
+    entries.push(entry);
+    fs::write(&index_path, serde_json::to_string_pretty(&entries)?)?;
+    info!("报告索引已更新: {:?} (共 {} 次运行)", index_path, entries.len());
+    Ok(())
+}
+
 /// 格式化文本报告
 fn format_text_report(stats: &BacktestStatistics) -> String {
     let mut report = String::new();
@@ -44,6 +141,12 @@ fn format_text_report(stats: &BacktestStatistics) -> String {
     report.push_str(&format!("  起始区块: {}\n", stats.start_block));
     report.push_str(&format!("  结束区块: {}\n", stats.end_block));
     report.push_str(&format!("  总区块数: {}\n", stats.total_blocks));
+    if stats.sample_interval > 1 {
+        report.push_str(&format!("  区块采样: 每 {} 个区块抽取 1 个 (随机种子: {})\n", stats.sample_interval, stats.seed));
+    }
+    if stats.slippage_bps > 0 {
+        report.push_str(&format!("  每跳滑点: {} bps\n", stats.slippage_bps));
+    }
     report.push('\n');
 
     // 交易统计
@@ -249,3 +352,61 @@ fn truncate_string(s: &str, max_len: usize) -> String {
         format!("{}...", &s[..max_len - 3])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(slippage_bps: u32) -> BacktestStatistics {
+        BacktestStatistics {
+            start_block: 19_000_000,
+            end_block: 19_000_100,
+            start_timestamp: 0,
+            end_timestamp: 0,
+            total_blocks: 100,
+            sample_interval: 0,
+            seed: 7,
+            chain_id: 1,
+            slippage_bps,
+            blocks_with_swaps: 0,
+            total_volume_usd: 0.0,
+            path_stats: Vec::new(),
+            profitable_opportunities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_report_file_stem_placeholders() {
+        assert_eq!(report_file_stem("{chain}_{date}_s{slippage}_seed{seed}", &stats(30), "20240101"), "eth_20240101_s30_seed7");
+        assert_eq!(report_file_stem(DEFAULT_REPORT_TEMPLATE, &stats(30), "20240101"), "backtest_report");
+        assert_eq!(report_file_stem("", &stats(30), "20240101"), DEFAULT_REPORT_TEMPLATE);
+
+        let avalanche = BacktestStatistics { chain_id: 43114, ..stats(30) };
+        assert_eq!(report_file_stem("{chain}", &avalanche, "20240101"), "avalanche");
+    }
+
+    #[test]
+    fn test_slippage_sweep_writes_distinct_reports_and_index() {
+        let dir = std::env::temp_dir().join(format!("backtest_sweep_{}", std::process::id()));
+        let output = dir.to_str().unwrap();
+        let template = "{chain}_s{slippage}_seed{seed}";
+
+        for slippage in [0, 10, 30] {
+            generate_report(&stats(slippage), output, template).unwrap();
+        }
+        // 同一组参数重跑只替换索引中的旧记录
+        generate_report(&stats(10), output, template).unwrap();
+
+        for name in ["eth_s0_seed7", "eth_s10_seed7", "eth_s30_seed7"] {
+            assert!(dir.join(format!("{}.txt", name)).exists(), "{}", name);
+            assert!(dir.join(format!("{}.json", name)).exists(), "{}", name);
+        }
+        let index: Vec<ReportIndexEntry> =
+            serde_json::from_str(&fs::read_to_string(dir.join(REPORT_INDEX_FILE)).unwrap()).unwrap();
+        let names: Vec<&str> = index.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["eth_s0_seed7", "eth_s30_seed7", "eth_s10_seed7"]);
+        assert_eq!(index.iter().map(|e| e.slippage_bps).collect::<Vec<_>>(), vec![0, 30, 10]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -24,6 +24,10 @@ pub struct AppConfig {
 pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
+    /// 关闭时是否把会话汇总写入 session_summaries 表
+    pub persist_session_summary: bool,
+    /// RPC 用量快照写入 rpc_stats_snapshots 表的间隔 (秒)，0 表示不写入
+    pub rpc_stats_snapshot_secs: u64,
 }
 
 /// 支持的区块链枚举
@@ -148,6 +152,28 @@ impl ChainContracts {
         }
     }
 
+    /// 获取 Optimism 主网合约地址
+    pub fn optimism() -> Self {
+        Self {
+            quoter_v2: "0x61fFE014bA17989E743c5F6cB21bF9697530B21e".to_string(), // Uniswap V3 QuoterV2
+            multicall3: "0xcA11bde05977b3631167028862bE2a173976CA11".to_string(),
+            wrapped_native: "0x4200000000000000000000000000000000000006".to_string(), // WETH on Optimism
+            swap_router: Some("0xE592427A0AEce92De3Edee1F18E0157C05861564".to_string()),
+            flash_loan_pool: None,
+        }
+    }
+
+    /// 获取 Avalanche C-Chain 合约地址
+    pub fn avalanche() -> Self {
+        Self {
+            quoter_v2: "0xbe0F5544EC67e9B3b2D979aaA43f18Fd87E6257F".to_string(), // Uniswap V3 QuoterV2 on Avalanche
+            multicall3: "0xcA11bde05977b3631167028862bE2a173976CA11".to_string(),
+            wrapped_native: "0xB31f66AA3C1e785363F0875A1B74E27b85FD66c7".to_string(), // WAVAX
+            swap_router: Some("0xbb00FF08d01D300023C629E8fFfFcb65A5a578cE".to_string()), // Uniswap SwapRouter02 on Avalanche
+            flash_loan_pool: None,
+        }
+    }
+
     /// 根据 chain_id 获取合约地址
     pub fn for_chain(chain_id: u64) -> Option<Self> {
         match chain_id {
@@ -156,6 +182,8 @@ impl ChainContracts {
             137 => Some(Self::polygon()),
             42161 => Some(Self::arbitrum()),
             8453 => Some(Self::base()),
+            10 => Some(Self::optimism()),
+            43114 => Some(Self::avalanche()),
             _ => None,
         }
     }
@@ -174,10 +202,62 @@ pub struct ChainConfig {
     pub contracts: ChainContracts,
     /// 原生代币符号 (ETH/BNB/MATIC 等)
     pub native_token: String,
+    /// USD 估值的参考稳定币符号 (BSC 上 USDT 为主，Base 等链上 USDC 为主)
+    pub reference_stablecoin: String,
     /// 区块时间 (秒)
     pub block_time_secs: u64,
     /// 该链的套利合约地址 (可选，覆盖全局配置)
     pub arbitrage_contract: Option<String>,
+    /// Swap 订阅看门狗阈值 (秒)，超过该时间未收到 Swap 事件则重新订阅，0 表示关闭
+    pub swap_stall_timeout_secs: u64,
+    /// 固定 gas limit，设置后执行时跳过 estimate_gas (gas 消耗稳定的链可节省一次 RPC)
+    pub fixed_gas_limit: Option<u64>,
+    /// 是否支持 EIP-1559 (type-2) 交易，BSC 等链仍按 legacy gas price 计费
+    pub eip1559: bool,
+    /// 禁止交易的代币地址 (包含这些代币的池子和路径不刷新、不报价、不执行)
+    pub token_blocklist: Vec<String>,
+    /// 禁止交易的池子地址
+    pub pool_blocklist: Vec<String>,
+    /// 执行后余额快照等待的确认区块数 (0 = 收到回执后立即读取)
+    pub balance_snapshot_confirmations: u64,
+    /// 指定代币对允许报价的费率档位，格式 `0xTokenA/0xTokenB:100|500`
+    pub fee_tier_whitelist: Vec<String>,
+    /// Gas 价格来源: node (eth_gasPrice) / base_fee (最新区块 base fee) / oracle (外部 gas oracle API)
+    pub gas_price_source: String,
+    /// gas oracle API 地址 (Blocknative 风格，gas_price_source = oracle 时必填)
+    pub gas_oracle_url: Option<String>,
+    /// gas oracle API key (放在 Authorization 请求头)
+    pub gas_oracle_api_key: Option<String>,
+    /// 合约上保持的 Wrapped Native 余额 (原生代币单位，如 0.5 WETH)，低于该值时用合约内的 ETH 自动 wrap，0 表示关闭
+    pub wrapped_native_buffer: f64,
+    /// 模拟执行的状态覆盖 JSON (`{"0x地址": {"balance": "...", "storage": {"0xslot": "0xvalue"}}}`)
+    pub simulation_state_overrides: Option<String>,
+    /// 与主合约并行运行的其它套利合约 (合约升级灰度)，路径通过 arbitrage_contract 字段选用
+    pub extra_arbitrage_contracts: Vec<String>,
+    /// 借入代币的首选闪电贷池，格式 `0xToken:0xPool`，路径没有固定闪电贷池时优先于自动选择
+    pub preferred_flash_pools: Vec<String>,
+    /// 没有路径映射的池子触发时是否回退到动态枚举 (关闭后直接忽略)
+    pub legacy_enumeration_enabled: bool,
+    /// 利润结算代币地址 (合约在还款后把利润换成该代币，None 表示保留起始代币)
+    pub profit_token: Option<String>,
+    /// 利润转换池费率 (None 表示自动选择流动性最高的档位)
+    pub profit_convert_fee: Option<u32>,
+    /// 闪电贷借入代币地址 (与路径起始代币不同时，合约借入后先换成起始代币，None 表示借起始代币)
+    pub flash_token: Option<String>,
+    /// 借入代币转换池费率 (None 表示自动选择流动性最高的档位)
+    pub flash_convert_fee: Option<u32>,
+    /// 模拟后到 mempool 发送前出现新区块时放弃发送 (Flashbots Bundle 绑定目标区块，不检查)
+    pub abort_if_block_advanced: bool,
+    /// 执行批次收集窗口 (毫秒)，窗口内到达的机会统一评分排序后再执行，0 表示不等待
+    pub execution_batch_window_ms: u64,
+    /// 动态枚举时每个方向最多报价的第三池数量 (按深度取最深的)，0 表示不限制
+    pub legacy_max_pool3_candidates: usize,
+    /// 单跳成交价相对价格服务现货价的最大偏离 (%)，超过则放弃路径，0 表示不检查
+    pub price_sanity_max_deviation_pct: f64,
+    /// 启动预热: 至少成功刷新多少个区块后才自动执行，0 表示不等待刷新
+    pub startup_warmup_blocks: u64,
+    /// 本地估算平均偏差超过该值 (基点) 的池子按比例降低跳过本地计算阈值，0 表示固定阈值
+    pub local_calc_error_tolerance_bps: u32,
 }
 
 impl ChainConfig {
@@ -191,8 +271,34 @@ impl ChainConfig {
             enabled: true,
             contracts: ChainContracts::ethereum(),
             native_token: "ETH".to_string(),
+            reference_stablecoin: "USDC".to_string(),
             block_time_secs: 12,
             arbitrage_contract: None,
+            swap_stall_timeout_secs: 600,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
         }
     }
 
@@ -206,8 +312,34 @@ impl ChainConfig {
             enabled: true,
             contracts: ChainContracts::bsc(),
             native_token: "BNB".to_string(),
+            reference_stablecoin: "USDT".to_string(),
             block_time_secs: 3,
             arbitrage_contract: None,
+            swap_stall_timeout_secs: 180,
+            fixed_gas_limit: None,
+            eip1559: false,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
         }
     }
 
@@ -221,8 +353,34 @@ impl ChainConfig {
             enabled: true,
             contracts: ChainContracts::polygon(),
             native_token: "MATIC".to_string(),
+            reference_stablecoin: "USDC".to_string(),
             block_time_secs: 2,
             arbitrage_contract: None,
+            swap_stall_timeout_secs: 180,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
         }
     }
 
@@ -236,8 +394,34 @@ impl ChainConfig {
             enabled: true,
             contracts: ChainContracts::arbitrum(),
             native_token: "ETH".to_string(),
+            reference_stablecoin: "USDC".to_string(),
             block_time_secs: 1, // Arbitrum 出块很快
             arbitrage_contract: None,
+            swap_stall_timeout_secs: 120,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
         }
     }
 
@@ -251,12 +435,255 @@ impl ChainConfig {
             enabled: true,
             contracts: ChainContracts::base(),
             native_token: "ETH".to_string(),
+            reference_stablecoin: "USDC".to_string(),
             block_time_secs: 2,
             arbitrage_contract: None,
+            swap_stall_timeout_secs: 180,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
+        }
+    }
+
+    /// 创建 Optimism 主网配置
+    pub fn optimism(rpc_url: String, ws_url: String) -> Self {
+        Self {
+            chain_id: 10,
+            name: "Optimism".to_string(),
+            rpc_url,
+            ws_url,
+            enabled: true,
+            contracts: ChainContracts::optimism(),
+            native_token: "ETH".to_string(),
+            reference_stablecoin: "USDC".to_string(),
+            block_time_secs: 2,
+            arbitrage_contract: None,
+            swap_stall_timeout_secs: 180,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
+        }
+    }
+
+    /// 创建 Avalanche C-Chain 配置
+    pub fn avalanche(rpc_url: String, ws_url: String) -> Self {
+        Self {
+            chain_id: 43114,
+            name: "Avalanche".to_string(),
+            rpc_url,
+            ws_url,
+            enabled: true,
+            contracts: ChainContracts::avalanche(),
+            native_token: "AVAX".to_string(),
+            reference_stablecoin: "USDC".to_string(),
+            block_time_secs: 2,
+            arbitrage_contract: None,
+            swap_stall_timeout_secs: 180,
+            fixed_gas_limit: None,
+            eip1559: true,
+            token_blocklist: Vec::new(),
+            pool_blocklist: Vec::new(),
+            balance_snapshot_confirmations: 0,
+            fee_tier_whitelist: Vec::new(),
+            gas_price_source: "node".to_string(),
+            gas_oracle_url: None,
+            gas_oracle_api_key: None,
+            wrapped_native_buffer: 0.0,
+            simulation_state_overrides: None,
+            extra_arbitrage_contracts: Vec::new(),
+            preferred_flash_pools: Vec::new(),
+            legacy_enumeration_enabled: true,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            abort_if_block_advanced: true,
+            execution_batch_window_ms: 0,
+            legacy_max_pool3_candidates: 0,
+            price_sanity_max_deviation_pct: 0.0,
+            startup_warmup_blocks: 0,
+            local_calc_error_tolerance_bps: 0,
+        }
+    }
+
+    /// 该链环境变量前缀 (ETH_/BSC_/POLYGON_/ARBITRUM_/BASE_/OPTIMISM_/AVALANCHE_)
+    pub fn env_prefix(&self) -> &'static str {
+        match self.chain_id {
+            1 => "ETH",
+            56 => "BSC",
+            137 => "POLYGON",
+            42161 => "ARBITRUM",
+            8453 => "BASE",
+            10 => "OPTIMISM",
+            43114 => "AVALANCHE",
+            _ => "CHAIN",
+        }
+    }
+
+    /// 该链使用的套利合约地址: 链级别配置优先，其次是全局的 ARBITRAGE_CONTRACT_ADDRESS
+    pub fn arbitrage_contract_or<'a>(&'a self, global: Option<&'a str>) -> Option<&'a str> {
+        self.arbitrage_contract.as_deref().or(global).filter(|s| !s.is_empty())
+    }
+
+    /// 读取带链前缀的环境变量
+    fn chain_env(&self, key: &str) -> Option<String> {
+        env::var(format!("{}_{}", self.env_prefix(), key))
+            .ok()
+            .filter(|s| !s.is_empty())
+    }
+
+    /// 应用链级别的环境变量覆盖
+    fn apply_env_overrides(&mut self) {
+        if let Some(v) = self.chain_env("SWAP_STALL_TIMEOUT_SECS").and_then(|s| s.parse().ok()) {
+            self.swap_stall_timeout_secs = v;
+        }
+        if let Some(v) = self.chain_env("FIXED_GAS_LIMIT").and_then(|s| s.parse().ok()) {
+            self.fixed_gas_limit = Some(v).filter(|limit| *limit > 0);
+        }
+        if let Some(v) = self.chain_env("EIP1559").and_then(|s| s.parse().ok()) {
+            self.eip1559 = v;
+        }
+        if let Some(v) = self.chain_env("TOKEN_BLOCKLIST") {
+            self.token_blocklist = parse_comma_list(&v);
+        }
+        if let Some(v) = self.chain_env("POOL_BLOCKLIST") {
+            self.pool_blocklist = parse_comma_list(&v);
+        }
+        if let Some(v) = self.chain_env("BALANCE_SNAPSHOT_CONFIRMATIONS").and_then(|s| s.parse().ok()) {
+            self.balance_snapshot_confirmations = v;
+        }
+        if let Some(v) = self.chain_env("FEE_TIER_WHITELIST") {
+            self.fee_tier_whitelist = parse_comma_list(&v);
+        }
+        if let Some(v) = self.chain_env("GAS_PRICE_SOURCE") {
+            self.gas_price_source = v;
+        }
+        if let Some(v) = self.chain_env("GAS_ORACLE_URL") {
+            self.gas_oracle_url = Some(v);
+        }
+        if let Some(v) = self.chain_env("GAS_ORACLE_API_KEY") {
+            self.gas_oracle_api_key = Some(v);
+        }
+        if let Some(v) = self.chain_env("WRAPPED_NATIVE_BUFFER").and_then(|s| s.parse().ok()) {
+            self.wrapped_native_buffer = v;
+        }
+        if let Some(v) = self.chain_env("SIMULATION_STATE_OVERRIDES") {
+            self.simulation_state_overrides = Some(v);
+        }
+        if let Some(v) = self.chain_env("ARBITRAGE_CONTRACT") {
+            self.arbitrage_contract = Some(v);
+        }
+        if let Some(v) = self.chain_env("EXTRA_ARBITRAGE_CONTRACTS") {
+            self.extra_arbitrage_contracts = parse_comma_list(&v);
+        }
+        if let Some(v) = self.chain_env("PREFERRED_FLASH_POOLS") {
+            self.preferred_flash_pools = parse_comma_list(&v);
+        }
+        if let Some(v) = self.chain_env("LEGACY_ENUMERATION_ENABLED").and_then(|s| s.parse().ok()) {
+            self.legacy_enumeration_enabled = v;
+        }
+        if let Some(v) = self.chain_env("ABORT_IF_BLOCK_ADVANCED").and_then(|s| s.parse().ok()) {
+            self.abort_if_block_advanced = v;
+        }
+        if let Some(v) = self.chain_env("EXECUTION_BATCH_WINDOW_MS").and_then(|s| s.parse().ok()) {
+            self.execution_batch_window_ms = v;
+        }
+        if let Some(v) = self.chain_env("LEGACY_MAX_POOL3_CANDIDATES").and_then(|s| s.parse().ok()) {
+            self.legacy_max_pool3_candidates = v;
+        }
+        if let Some(v) = self.chain_env("PRICE_SANITY_MAX_DEVIATION_PCT").and_then(|s| s.parse().ok()) {
+            self.price_sanity_max_deviation_pct = v;
+        }
+        if let Some(v) = self.chain_env("STARTUP_WARMUP_BLOCKS").and_then(|s| s.parse().ok()) {
+            self.startup_warmup_blocks = v;
+        }
+        if let Some(v) = self.chain_env("LOCAL_CALC_ERROR_TOLERANCE_BPS").and_then(|s| s.parse().ok()) {
+            self.local_calc_error_tolerance_bps = v;
+        }
+        if let Some(v) = self.chain_env("PROFIT_TOKEN") {
+            self.profit_token = Some(v);
+        }
+        if let Some(v) = self.chain_env("PROFIT_CONVERT_FEE").and_then(|s| s.parse().ok()) {
+            self.profit_convert_fee = Some(v);
+        }
+        if let Some(v) = self.chain_env("FLASH_TOKEN") {
+            self.flash_token = Some(v);
+        }
+        if let Some(v) = self.chain_env("FLASH_CONVERT_FEE").and_then(|s| s.parse().ok()) {
+            self.flash_convert_fee = Some(v);
+        }
+        if let Some(v) = self.chain_env("MULTICALL3") {
+            self.contracts.multicall3 = v;
+        }
+        // 链级设置优先，其次是所有链共用的 REFERENCE_STABLECOIN，都没有时保留链默认值
+        let reference_stablecoin = self
+            .chain_env("REFERENCE_STABLECOIN")
+            .or_else(|| env::var("REFERENCE_STABLECOIN").ok().filter(|s| !s.is_empty()));
+        if let Some(v) = reference_stablecoin {
+            self.reference_stablecoin = v.to_uppercase();
         }
     }
 }
 
+/// 解析逗号分隔的费率档位 (如 "100,500")，无法解析的项忽略
+fn parse_fee_tiers(value: &str) -> Vec<u32> {
+    parse_comma_list(value)
+        .iter()
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// 解析逗号分隔的列表 (去掉空白和空项)
+fn parse_comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 // 保持向后兼容的类型别名
 pub type EthereumConfig = ChainConfig;
 pub type BscConfig = ChainConfig;
@@ -268,21 +695,66 @@ pub struct ArbitrageConfig {
     pub max_path_hops: u32,          // 最大路径跳数
     pub gas_price_multiplier: f64,   // Gas 价格倍数
     pub max_gas_price_gwei: Option<f64>, // 最大 Gas 价格 (Gwei) - 支持小数，如 0.08
+    pub max_gas_cost_usd: Option<f64>, // 单笔交易 gas 费上限 (USD)，gas limit 异常大时放弃发送
     pub dry_run: Option<bool>,       // 是否干运行模式
     pub auto_execute: Option<bool>,  // 是否自动执行套利
+    pub detection_only: bool,        // 仅检测模式: 只记录机会，不做任何执行准备 (优先于 AUTO_EXECUTE)
     pub min_swap_value_usd: f64,     // 最小交易金额过滤阈值 (USD)
     pub skip_local_calc_threshold_usd: f64, // 超过该阈值跳过本地计算直接链上计算 (USD)，默认 5000
+    pub local_calc_min_samples: u64,    // 池子至少积累多少个偏差样本后才调整阈值
     // 动态利润门槛配置 (根据 Gas 价格调整最小利润要求)
     pub min_profit_ultra_low_gas: f64,  // Gas < 1 Gwei 时的最小利润 (USD)
     pub min_profit_low_gas: f64,        // Gas 1-5 Gwei 时的最小利润 (USD)
     pub min_profit_normal_gas: f64,     // Gas 5-20 Gwei 时的最小利润 (USD)
     pub min_profit_high_gas: f64,       // Gas 20-50 Gwei 时的最小利润 (USD)
     pub min_profit_very_high_gas: f64,  // Gas >= 50 Gwei 时的最小利润 (USD)
+    pub multi_hop_cross_check: bool,    // 是否用多跳 quoteExactInput 交叉验证逐跳报价
+    pub multi_hop_tolerance_bps: u32,   // 多跳报价允许偏差 (基点)，超过则采用多跳报价
+    pub dead_pool_after_blocks: u64,    // 连续多少个区块无 Swap 事件视为死池 (0 = 不检测)
+    pub dead_pool_refresh_interval_blocks: u64, // 死池刷新间隔 (区块数，0 = 不再刷新)
+    pub startup_warmup_secs: u64,       // 启动预热: 启动后至少等待的秒数
+    pub send_timeout_secs: u64,         // 发送交易超时 (秒)，超时后放弃本次执行
+    pub max_price_impact_pct: f64,      // 单跳价格影响上限 (%)，超过则放弃该机会，0 = 不限制
+    pub max_liquidity_fraction: f64,    // 输入金额不超过路径最浅池子深度的该比例 (如 0.1 = 10%)，0 = 不限制
+    pub swap_event_channel_capacity: usize, // Swap 事件广播通道容量
+    pub swap_event_formats: Vec<String>, // 订阅的 Swap 事件格式 (v2 / v3)
+    pub swap_lag_newest_only: bool,     // 接收端落后时丢弃积压，每个池子只处理最新的 Swap 事件
+    pub stable_swap_pools_enabled: bool, // 加载 PancakeSwap StableSwap 池子 (仅检测，合约不能执行)
+    pub quote_retry_attempts: u32,      // 报价遇到超时/限流等临时错误时的最多重试次数
+    pub quote_retry_delay_ms: u64,      // 报价重试间隔 (毫秒)
+    pub stable_pair_fee_tiers: Vec<u32>, // 稳定币对只报价这些费率档位 (空 = 不限制)
+    pub volatile_pair_fee_tiers: Vec<u32>, // 其他代币对只报价这些费率档位 (空 = 不限制)
+    pub path_eval_budget_ms: u64,       // 单个 Swap 事件的路径检测时间预算 (毫秒)，0 = 不限制
+    pub path_eval_concurrency: usize,   // 单个 Swap 事件同时检查的候选路径数 (1 = 逐条检查)
+    pub static_path_both_directions: bool, // 预定义路径同时检查反方向 (A->C->B->A)，取净利润高的方向
+    pub balance_fetch_enabled: bool,    // 执行前后是否查询钱包余额 (盈亏统计、邮件对比用)
+    pub max_concurrent_balance_fetches: usize, // 同时进行的余额查询任务上限，避免突发执行时挤占发送/报价 RPC
+    pub max_concurrent_executions: usize, // 每条链同时在途的套利执行上限 (占用 nonce 和资金)，检测不受限制，0 = 不限制
+    pub priority_min_profit_usd: Option<f64>, // 净利润不低于该值的机会不排队，使用保留的执行许可立即执行
+    pub ab_candidate_min_profit_usd: Option<f64>, // A/B 对比: 候选组的固定最小利润门槛 (USD)，候选组只做纸面交易，不设置则不对比
+    pub simulation_gas_limit_multiplier: f64, // 模拟执行时 gas 估算值的倍数 (发送时另有倍数)，默认 1.0 不加价
+    pub wrapped_native_check_secs: u64, // 检查合约 Wrapped Native 缓冲的间隔 (秒)
+    pub path_failure_threshold: u32,    // 路径连续执行失败多少次后暂停 (0 = 不暂停)
+    pub path_failure_cooldown_secs: u64, // 路径暂停时长 (秒)，到期后重新放行
+    pub post_execution_pool_cooldown_secs: u64, // 成功执行后涉及池子的冷却时长 (秒)，0 = 不冷却
+    pub profit_deviation_alert_usd: f64, // 实际利润与预估相差超过该值 (USD) 时发高优先级告警，0 = 不告警
+    pub no_opportunity_alert_secs: u64, // 正常出块但超过该时长 (秒) 没有任何机会时告警，0 = 不检查
+    pub native_denomination: bool,      // 按原生代币计价利润和 gas (价格服务不可用时的降级模式)
+    pub native_min_profit: f64,         // 原生代币计价模式的最小净利润 (原生代币，如 0.001 ETH)
+    pub native_min_swap_value: f64,     // 原生代币计价模式的小额交易过滤 (原生代币)
+    /// 按起始代币指定执行数量策略: 代币符号 (大写) -> 策略描述 (如 "full", "max_usd:5000")
+    pub amount_strategy_by_token: HashMap<String, String>,
+    /// 按代币对 / 三角覆盖最小利润 (USD): "USDC/USDT/DAI" 或 "WBTC/WETH" (大写) -> 门槛
+    pub min_profit_by_pair: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct FlashLoanConfig {
     pub provider: FlashLoanProvider,
+    /// 各提供商的闪电贷费率 (以 1e6 为基数，Uniswap V3 的费率即借贷池 fee tier，不在此配置)
+    pub uniswap_v4_fee: u32,
+    pub aave_v3_fee: u32,
+    pub balancer_fee: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -308,30 +780,124 @@ pub struct MevConfig {
     /// 是否同时使用公开 mempool（Both 模式）
     /// 当 use_flashbots=true 且 use_public_mempool=true 时，同时发送到两个渠道
     pub use_public_mempool: bool,
+    /// 仅模拟: Bundle 只提交中继 eth_callBundle 模拟，不真实发送 (优先于以上发送模式)
+    pub flashbots_simulate_only: bool,
+    /// 每条链同时在途的 Bundle 上限，占满时按净利润排队 (0 = 不限制)
+    pub max_in_flight_bundles: usize,
     /// 优先费（Gwei）- 支持小数，如 0.005
     pub priority_fee_gwei: Option<f64>,
     /// Flashbots Bundle 签名私钥（可选，默认使用交易私钥）
     pub flashbots_signer_key: Option<String>,
     /// 最大重试区块数
     pub max_block_retries: Option<u64>,
+    /// 额外的 builder RPC 地址，Bundle 同时发送到中继和这些 builder
+    pub flashbots_builders: Vec<String>,
+    /// Bundle 默认隐私级别: default / full / backrun (非 default 时中继走 MEV-Share mev_sendBundle)
+    pub flashbots_privacy: String,
+    /// 按链覆盖的中继配置 (chain_id -> 中继 URL / 签名私钥)，未配置的链使用上面的全局值
+    pub chain_relays: HashMap<u64, ChainRelayConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl MevConfig {
+    /// 获取某条链使用的中继 URL 和 Bundle 签名私钥，链级别配置优先于全局配置
+    pub fn relay_for_chain(&self, chain_id: u64) -> (Option<String>, Option<String>) {
+        let chain = self.chain_relays.get(&chain_id);
+        let relay_url = chain
+            .and_then(|c| c.relay_url.clone())
+            .or_else(|| self.flashbots_rpc.clone());
+        let signer_key = chain
+            .and_then(|c| c.signer_key.clone())
+            .or_else(|| self.flashbots_signer_key.clone());
+        (relay_url, signer_key)
+    }
+}
+
+/// 单条链的私有中继配置 (<PREFIX>_FLASHBOTS_RELAY_URL / <PREFIX>_FLASHBOTS_SIGNER_KEY)
+#[derive(Clone, Default, Deserialize)]
+pub struct ChainRelayConfig {
+    pub relay_url: Option<String>,
+    pub signer_key: Option<String>,
+}
+
+impl std::fmt::Debug for ChainRelayConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainRelayConfig")
+            .field("relay_url", &self.relay_url)
+            .field("signer_key", &self.signer_key.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+#[derive(Clone, Deserialize)]
 pub struct WalletConfig {
     pub private_key: Option<String>,
+    /// 加密私钥文件路径 (以太坊 V3 keystore JSON)，启动时解密，优先于 private_key
+    pub keystore_path: Option<String>,
+    /// keystore 解密密码
+    pub keystore_password: Option<String>,
     pub arbitrage_contract_address: Option<String>,
 }
 
+/// 私钥和密码不输出到 Debug (AppConfig 会被整体打印)
+impl std::fmt::Debug for WalletConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redact = |value: &Option<String>| value.as_ref().map(|_| "***");
+        f.debug_struct("WalletConfig")
+            .field("private_key", &redact(&self.private_key))
+            .field("keystore_path", &self.keystore_path)
+            .field("keystore_password", &redact(&self.keystore_password))
+            .field("arbitrage_contract_address", &self.arbitrage_contract_address)
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ApiConfig {
     pub host: String,
     pub port: u16,
+    /// 手动执行机会等会发送交易的接口所需的管理令牌 (为空时这些接口禁用)
+    pub admin_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct LogConfig {
     pub level: String,
     pub file_path: String,
+    /// 滚动周期: hourly / daily / never
+    pub rotation: String,
+    /// 单个日志文件最大大小 (MB)，0 表示按时间滚动
+    pub max_size_mb: u64,
+    /// 每类日志保留的历史文件数量
+    pub max_files: usize,
+    /// OTLP traces 接收地址 (为空时不导出链路追踪)
+    pub otlp_endpoint: Option<String>,
+    /// 链路追踪上报的服务名
+    pub otlp_service_name: String,
+}
+
+impl LogConfig {
+    /// 从环境变量读取日志配置 (日志系统要在 AppConfig 之前初始化)
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+
+        Self {
+            level: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            file_path: env::var("LOG_FILE_PATH")
+                .unwrap_or_else(|_| "./logs/dex_arbitrage.log".to_string()),
+            rotation: env::var("LOG_ROTATION").unwrap_or_else(|_| "daily".to_string()),
+            max_size_mb: env::var("LOG_MAX_SIZE_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            max_files: env::var("LOG_MAX_FILES")
+                .unwrap_or_else(|_| "14".to_string())
+                .parse()
+                .unwrap_or(14),
+            otlp_endpoint: env::var("OTLP_TRACES_ENDPOINT").ok().filter(|s| !s.is_empty()),
+            otlp_service_name: env::var("OTLP_SERVICE_NAME")
+                .unwrap_or_else(|_| "chainfusion-arbitrage".to_string()),
+        }
+    }
 }
 
 impl AppConfig {
@@ -420,6 +986,39 @@ impl AppConfig {
             None
         };
 
+        // Optimism 配置 (可选)
+        let optimism_rpc = env::var("OPTIMISM_RPC_URL").ok();
+        let optimism_ws = env::var("OPTIMISM_WS_URL").ok();
+        let optimism = if let (Some(rpc), Some(ws)) = (optimism_rpc, optimism_ws) {
+            let mut cfg = ChainConfig::optimism(rpc, ws);
+            cfg.enabled = enabled_chains.contains(&10);
+            cfg.arbitrage_contract = env::var("OPTIMISM_ARBITRAGE_CONTRACT").ok().filter(|s| !s.is_empty());
+            Some(cfg)
+        } else {
+            None
+        };
+
+        // Avalanche 配置 (可选)
+        let avalanche_rpc = env::var("AVALANCHE_RPC_URL").ok();
+        let avalanche_ws = env::var("AVALANCHE_WS_URL").ok();
+        let avalanche = if let (Some(rpc), Some(ws)) = (avalanche_rpc, avalanche_ws) {
+            let mut cfg = ChainConfig::avalanche(rpc, ws);
+            cfg.enabled = enabled_chains.contains(&43114);
+            cfg.arbitrage_contract = env::var("AVALANCHE_ARBITRAGE_CONTRACT").ok().filter(|s| !s.is_empty());
+            Some(cfg)
+        } else {
+            None
+        };
+
+        // 链级别的可选覆盖项 (例如 ETH_SWAP_STALL_TIMEOUT_SECS)
+        ethereum.apply_env_overrides();
+        bsc.apply_env_overrides();
+        let polygon = polygon.map(|mut cfg| { cfg.apply_env_overrides(); cfg });
+        let arbitrum = arbitrum.map(|mut cfg| { cfg.apply_env_overrides(); cfg });
+        let base = base.map(|mut cfg| { cfg.apply_env_overrides(); cfg });
+        let optimism = optimism.map(|mut cfg| { cfg.apply_env_overrides(); cfg });
+        let avalanche = avalanche.map(|mut cfg| { cfg.apply_env_overrides(); cfg });
+
         // 构建链配置 HashMap
         let mut chains: HashMap<u64, ChainConfig> = HashMap::new();
         chains.insert(1, ethereum.clone());
@@ -433,6 +1032,12 @@ impl AppConfig {
         if let Some(cfg) = base {
             chains.insert(8453, cfg);
         }
+        if let Some(cfg) = optimism {
+            chains.insert(10, cfg);
+        }
+        if let Some(cfg) = avalanche {
+            chains.insert(43114, cfg);
+        }
 
         // 套利配置
         let arbitrage = ArbitrageConfig {
@@ -455,12 +1060,19 @@ impl AppConfig {
             max_gas_price_gwei: env::var("MAX_GAS_PRICE_GWEI")
                 .ok()
                 .and_then(|s| s.parse().ok()),
+            max_gas_cost_usd: env::var("MAX_GAS_COST_USD")
+                .ok()
+                .and_then(|s| s.parse().ok()),
             dry_run: env::var("DRY_RUN")
                 .ok()
                 .and_then(|s| s.parse().ok()),
             auto_execute: env::var("AUTO_EXECUTE")
                 .ok()
                 .and_then(|s| s.parse().ok()),
+            detection_only: env::var("DETECTION_ONLY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
             min_swap_value_usd: env::var("MIN_SWAP_VALUE_USD")
                 .unwrap_or_else(|_| "1.0".to_string())
                 .parse()
@@ -469,6 +1081,10 @@ impl AppConfig {
                 .unwrap_or_else(|_| "5000.0".to_string())
                 .parse()
                 .unwrap_or(5000.0),
+            local_calc_min_samples: env::var("LOCAL_CALC_MIN_SAMPLES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
             // 动态利润门槛配置
             min_profit_ultra_low_gas: env::var("MIN_PROFIT_ULTRA_LOW_GAS")
                 .unwrap_or_else(|_| "1.0".to_string())
@@ -490,6 +1106,139 @@ impl AppConfig {
                 .unwrap_or_else(|_| "30.0".to_string())
                 .parse()
                 .unwrap_or(30.0),
+            multi_hop_cross_check: env::var("MULTI_HOP_CROSS_CHECK")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            multi_hop_tolerance_bps: env::var("MULTI_HOP_TOLERANCE_BPS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            dead_pool_after_blocks: env::var("DEAD_POOL_AFTER_BLOCKS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            dead_pool_refresh_interval_blocks: env::var("DEAD_POOL_REFRESH_INTERVAL_BLOCKS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            startup_warmup_secs: env::var("STARTUP_WARMUP_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            send_timeout_secs: env::var("SEND_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            max_price_impact_pct: env::var("MAX_PRICE_IMPACT_PCT")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            max_liquidity_fraction: env::var("MAX_LIQUIDITY_FRACTION")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            swap_event_channel_capacity: env::var("SWAP_EVENT_CHANNEL_CAPACITY")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            swap_event_formats: parse_comma_list(&env::var("SWAP_EVENT_FORMATS").unwrap_or_else(|_| "v3".to_string())),
+            swap_lag_newest_only: env::var("SWAP_LAG_NEWEST_ONLY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            stable_swap_pools_enabled: env::var("STABLE_SWAP_POOLS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            quote_retry_attempts: env::var("QUOTE_RETRY_ATTEMPTS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            quote_retry_delay_ms: env::var("QUOTE_RETRY_DELAY_MS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .unwrap_or(100),
+            stable_pair_fee_tiers: parse_fee_tiers(&env::var("STABLE_PAIR_FEE_TIERS").unwrap_or_default()),
+            volatile_pair_fee_tiers: parse_fee_tiers(&env::var("VOLATILE_PAIR_FEE_TIERS").unwrap_or_default()),
+            path_eval_budget_ms: env::var("PATH_EVAL_BUDGET_MS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            path_eval_concurrency: env::var("PATH_EVAL_CONCURRENCY")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            static_path_both_directions: env::var("STATIC_PATH_BOTH_DIRECTIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            balance_fetch_enabled: env::var("BALANCE_FETCH_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            max_concurrent_balance_fetches: env::var("MAX_CONCURRENT_BALANCE_FETCHES")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            max_concurrent_executions: env::var("MAX_CONCURRENT_EXECUTIONS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            priority_min_profit_usd: env::var("PRIORITY_MIN_PROFIT_USD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|v: &f64| *v > 0.0),
+            ab_candidate_min_profit_usd: env::var("AB_CANDIDATE_MIN_PROFIT_USD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|v: &f64| *v >= 0.0),
+            simulation_gas_limit_multiplier: env::var("SIMULATION_GAS_LIMIT_MULTIPLIER")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            wrapped_native_check_secs: env::var("WRAPPED_NATIVE_CHECK_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
+            path_failure_threshold: env::var("PATH_FAILURE_THRESHOLD")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            path_failure_cooldown_secs: env::var("PATH_FAILURE_COOLDOWN_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap_or(300),
+            post_execution_pool_cooldown_secs: env::var("POST_EXECUTION_POOL_COOLDOWN_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            no_opportunity_alert_secs: env::var("NO_OPPORTUNITY_ALERT_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            profit_deviation_alert_usd: env::var("PROFIT_DEVIATION_ALERT_USD")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            native_denomination: env::var("PROFIT_DENOMINATION")
+                .map(|v| v.trim().eq_ignore_ascii_case("native"))
+                .unwrap_or(false),
+            native_min_profit: env::var("NATIVE_MIN_PROFIT")
+                .unwrap_or_else(|_| "0.001".to_string())
+                .parse()
+                .unwrap_or(0.001),
+            native_min_swap_value: env::var("NATIVE_MIN_SWAP_VALUE")
+                .unwrap_or_else(|_| "0.001".to_string())
+                .parse()
+                .unwrap_or(0.001),
+            amount_strategy_by_token: parse_symbol_map(
+                &env::var("AMOUNT_STRATEGY_BY_TOKEN").unwrap_or_default(),
+            ),
+            min_profit_by_pair: parse_symbol_map(&env::var("MIN_PROFIT_BY_PAIR").unwrap_or_default())
+                .into_iter()
+                .filter_map(|(pair, value)| Some((pair, value.parse().ok()?)))
+                .collect(),
         };
 
         // 闪电贷配置
@@ -507,6 +1256,18 @@ impl AppConfig {
 
         let flash_loan = FlashLoanConfig {
             provider: flash_loan_provider,
+            uniswap_v4_fee: env::var("FLASH_FEE_UNISWAP_V4")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            aave_v3_fee: env::var("FLASH_FEE_AAVE_V3")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .unwrap_or(900),
+            balancer_fee: env::var("FLASH_FEE_BALANCER")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
         };
 
         // MEV 保护配置
@@ -520,6 +1281,14 @@ impl AppConfig {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .unwrap_or(false),
+            flashbots_simulate_only: env::var("FLASHBOTS_SIMULATE_ONLY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            max_in_flight_bundles: env::var("FLASHBOTS_MAX_IN_FLIGHT_BUNDLES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
             priority_fee_gwei: env::var("PRIORITY_FEE_GWEI")
                 .ok()
                 .and_then(|s| s.parse().ok()),
@@ -527,11 +1296,26 @@ impl AppConfig {
             max_block_retries: env::var("FLASHBOTS_MAX_BLOCK_RETRIES")
                 .ok()
                 .and_then(|s| s.parse().ok()),
+            flashbots_builders: parse_comma_list(&env::var("FLASHBOTS_BUILDERS").unwrap_or_default()),
+            flashbots_privacy: env::var("FLASHBOTS_PRIVACY").unwrap_or_else(|_| "default".to_string()),
+            chain_relays: chains
+                .values()
+                .filter_map(|chain| {
+                    let relay = ChainRelayConfig {
+                        relay_url: chain.chain_env("FLASHBOTS_RELAY_URL"),
+                        signer_key: chain.chain_env("FLASHBOTS_SIGNER_KEY"),
+                    };
+                    (relay.relay_url.is_some() || relay.signer_key.is_some())
+                        .then_some((chain.chain_id, relay))
+                })
+                .collect(),
         };
 
         // 钱包配置 (全局默认，可被链级别覆盖)
         let wallet = WalletConfig {
             private_key: env::var("PRIVATE_KEY").ok().filter(|s| !s.is_empty()),
+            keystore_path: env::var("KEYSTORE_PATH").ok().filter(|s| !s.is_empty()),
+            keystore_password: env::var("KEYSTORE_PASSWORD").ok(),
             arbitrage_contract_address: env::var("ARBITRAGE_CONTRACT_ADDRESS")
                 .ok()
                 .filter(|s| !s.is_empty()),
@@ -544,19 +1328,23 @@ impl AppConfig {
                 .unwrap_or_else(|_| "9530".to_string())
                 .parse()
                 .context("Invalid SERVER_PORT")?,
+            admin_token: env::var("API_ADMIN_TOKEN").ok().filter(|s| !s.is_empty()),
         };
 
         // 日志配置
-        let log = LogConfig {
-            level: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
-            file_path: env::var("LOG_FILE_PATH")
-                .unwrap_or_else(|_| "./logs/dex_arbitrage.log".to_string()),
-        };
+        let log = LogConfig::from_env();
 
         Ok(Self {
             database: DatabaseConfig {
                 url: database_url,
                 max_connections: db_max_connections,
+                persist_session_summary: env::var("PERSIST_SESSION_SUMMARY")
+                    .map(|v| v.to_lowercase() == "true")
+                    .unwrap_or(false),
+                rpc_stats_snapshot_secs: env::var("RPC_STATS_SNAPSHOT_SECS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .unwrap_or(0),
             },
             ethereum,
             bsc,
@@ -571,3 +1359,39 @@ impl AppConfig {
         })
     }
 }
+
+/// 解析 "SYMBOL=value,SYMBOL=value" 形式的映射，符号统一转大写，格式错误的项跳过
+fn parse_symbol_map(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|item| {
+            let (symbol, value) = item.split_once('=')?;
+            let (symbol, value) = (symbol.trim(), value.trim());
+            if symbol.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some((symbol.to_uppercase(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_stablecoin_defaults_per_chain() {
+        let bsc = ChainConfig::bsc(String::new(), String::new());
+        let base = ChainConfig::base(String::new(), String::new());
+        assert_eq!(bsc.reference_stablecoin, "USDT");
+        assert_eq!(base.reference_stablecoin, "USDC");
+    }
+
+    #[test]
+    fn test_chain_arbitrage_contract_overrides_global() {
+        let mut chain = ChainConfig::base(String::new(), String::new());
+        assert_eq!(chain.arbitrage_contract_or(Some("0xglobal")), Some("0xglobal"));
+        assert_eq!(chain.arbitrage_contract_or(None), None);
+        chain.arbitrage_contract = Some("0xchain".to_string());
+        assert_eq!(chain.arbitrage_contract_or(Some("0xglobal")), Some("0xchain"));
+    }
+}
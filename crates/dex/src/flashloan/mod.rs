@@ -8,7 +8,7 @@ pub use executor::{
     TriangularArbitrageBuilder, CrossDexArbitrageBuilder, DexInfo,
 };
 pub use providers::{
-    FlashLoanProvider, FlashLoanRequest, SwapOperation, FlashLoanOperation,
+    FlashLoanProvider, FlashFeeTable, FlashLoanRequest, SwapOperation, FlashLoanOperation,
     UniswapV3FlashProvider, UniswapV4FlashProvider, AaveV3FlashProvider, BalancerFlashProvider,
 };
 pub use selector::{
@@ -39,14 +39,7 @@ impl FlashLoanProvider {
     /// 或使用 `fee_rate_with_pool_fee()` 方法传入具体费率。
     /// 这里返回的 3000 (0.3%) 仅作为默认估算值。
     pub fn fee_rate(&self) -> u32 {
-        match self {
-            // V3: 费率 = 池子 fee tier，需从路径动态获取
-            // 可选费率: 100 (0.01%), 500 (0.05%), 3000 (0.3%), 10000 (1%)
-            FlashLoanProvider::UniswapV3 => 3000,   // 默认估算值，实际应从池子获取
-            FlashLoanProvider::UniswapV4 => 0,      // V4 flash accounting 无费用
-            FlashLoanProvider::AaveV3 => 500,       // 固定 0.05%
-            FlashLoanProvider::Balancer => 0,       // Balancer flash 无费用
-        }
+        self.fee_rate_with_pool_fee(DEFAULT_V3_FLASH_FEE)
     }
 
     /// 获取指定池子费率的闪电贷费用 (用于精确计算)
@@ -57,11 +50,10 @@ impl FlashLoanProvider {
     ///   - 500 = 0.05%
     ///   - 3000 = 0.3%
     ///   - 10000 = 1%
+    ///
+    /// 按默认费率表查询；执行路径使用配置加载的 `FlashFeeTable`
     pub fn fee_rate_with_pool_fee(&self, pool_fee: u32) -> u32 {
-        match self {
-            FlashLoanProvider::UniswapV3 => pool_fee, // V3 费率等于池子费率
-            _ => self.fee_rate(),                     // 其他提供商使用固定费率
-        }
+        FlashFeeTable::default().fee_rate(*self, pool_fee)
     }
 
     /// 是否支持多资产闪电贷
@@ -75,6 +67,47 @@ impl FlashLoanProvider {
     }
 }
 
+/// 池子费率未知时 Uniswap V3 闪电贷的估算费率 (0.3%)
+const DEFAULT_V3_FLASH_FEE: u32 = 3000;
+
+/// 各闪电贷提供商的费率表 (以 1e6 为基数)
+///
+/// 新增提供商或调整费率只需改这里，费用计算统一经过 `fee_rate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashFeeTable {
+    /// Uniswap V4 flash accounting 费率 (目前为 0，留作配置)
+    pub uniswap_v4: u32,
+    /// Aave V3 闪电贷费率 (FLASHLOAN_PREMIUM_TOTAL，默认 0.09%)
+    pub aave_v3: u32,
+    /// Balancer 闪电贷费率
+    pub balancer: u32,
+}
+
+impl Default for FlashFeeTable {
+    fn default() -> Self {
+        Self {
+            uniswap_v4: 0,
+            aave_v3: 900,
+            balancer: 0,
+        }
+    }
+}
+
+impl FlashFeeTable {
+    /// 查询提供商的闪电贷费率
+    ///
+    /// Uniswap V3 的费率等于借贷池的 fee tier，由调用方传入 `pool_fee`；
+    /// 其他提供商忽略 `pool_fee`
+    pub fn fee_rate(&self, provider: FlashLoanProvider, pool_fee: u32) -> u32 {
+        match provider {
+            FlashLoanProvider::UniswapV3 => pool_fee,
+            FlashLoanProvider::UniswapV4 => self.uniswap_v4,
+            FlashLoanProvider::AaveV3 => self.aave_v3,
+            FlashLoanProvider::Balancer => self.balancer,
+        }
+    }
+}
+
 // Uniswap V3 闪电贷 ABI
 abigen!(
     UniswapV3FlashPool,
@@ -7,11 +7,13 @@
 //! 2. 池子不能与 swap 路径中的池子重复
 //! 3. 优先选择流动性最高的池子
 //! 4. 优先选择费率最低的池子
+//!
+//! 为借入代币配置了首选闪电贷池时，该池子排在最前 (仍需满足 1、2)，其余池子按上述规则排在后面作为回退
 
 use anyhow::{anyhow, Result};
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
@@ -85,6 +87,8 @@ pub struct FlashPoolSelectorConfig {
     pub preferred_fees: Vec<u32>,
     /// 是否验证池子存在
     pub verify_pools: bool,
+    /// 借入代币 → 首选闪电贷池 (经过验证的最佳来源，优先于自动选择)
+    pub preferred_pools: HashMap<Address, Address>,
 }
 
 impl Default for FlashPoolSelectorConfig {
@@ -98,6 +102,7 @@ impl Default for FlashPoolSelectorConfig {
             // 优先低费率池子以减少闪电贷成本
             preferred_fees: vec![100, 500, 3000, 10000],
             verify_pools: true,
+            preferred_pools: HashMap::new(),
         }
     }
 }
@@ -113,6 +118,7 @@ impl FlashPoolSelectorConfig {
             min_liquidity: 100_000_000_000_000_000, // 1e17
             preferred_fees: vec![100, 500, 2500, 10000],
             verify_pools: true,
+            preferred_pools: HashMap::new(),
         }
     }
 }
@@ -154,6 +160,12 @@ impl<M: Middleware + 'static> FlashPoolSelector<M> {
         }
     }
 
+    /// 设置借入代币的首选闪电贷池
+    pub fn with_preferred_pools(mut self, preferred_pools: HashMap<Address, Address>) -> Self {
+        self.config.preferred_pools = preferred_pools;
+        self
+    }
+
     /// 为套利路径选择最优闪电贷池
     ///
     /// # 参数
@@ -178,6 +190,34 @@ impl<M: Middleware + 'static> FlashPoolSelector<M> {
             swap_pools.len()
         );
 
+        let ranked = self
+            .rank_flash_pools(borrow_token, borrow_amount, swap_pools, available_pair_tokens)
+            .await?;
+        let selection = ranked.into_iter().next().ok_or_else(|| {
+            anyhow!("找不到合适的闪电贷池: borrow_token={:?}", borrow_token)
+        })?;
+
+        info!(
+            "选择闪电贷池: {:?}, 费率={}bps, 流动性={}, 预估费用={}",
+            selection.pool_address,
+            selection.pool_fee as f64 / 100.0,
+            selection.liquidity,
+            selection.estimated_fee
+        );
+
+        Ok(selection)
+    }
+
+    /// 列出所有可用的闪电贷池，按优先级排序 (首选池子，然后流动性高优先，其次费率低)
+    ///
+    /// `swap_pools` 中的池子会被排除；传空切片可以得到完整排名，由调用方自行过滤
+    pub async fn rank_flash_pools(
+        &self,
+        borrow_token: Address,
+        borrow_amount: U256,
+        swap_pools: &[Address],
+        available_pair_tokens: &[Address],
+    ) -> Result<Vec<FlashPoolSelection>> {
         let excluded_pools: HashSet<Address> = swap_pools.iter().cloned().collect();
         let mut candidates: Vec<V3PoolInfo> = Vec::new();
 
@@ -219,13 +259,6 @@ impl<M: Middleware + 'static> FlashPoolSelector<M> {
             }
         }
 
-        if candidates.is_empty() {
-            return Err(anyhow!(
-                "找不到合适的闪电贷池: borrow_token={:?}",
-                borrow_token
-            ));
-        }
-
         // 选择最优池子: 优先流动性高，其次费率低
         candidates.sort_by(|a, b| {
             // 首先按流动性降序
@@ -237,31 +270,33 @@ impl<M: Middleware + 'static> FlashPoolSelector<M> {
             a.fee.cmp(&b.fee)
         });
 
-        let best = &candidates[0];
-        let is_token0 = best.is_token0(borrow_token);
-
-        // 计算预估闪电贷费用
-        let fee_rate = best.fee as u128;
-        let estimated_fee = borrow_amount * U256::from(fee_rate) / U256::from(1_000_000);
+        // 首选池子排在最前
+        if let Some(preferred) = self.preferred_pool_info(borrow_token, &excluded_pools).await {
+            candidates.retain(|pool| pool.address != preferred.address);
+            candidates.insert(0, preferred);
+        }
 
-        let selection = FlashPoolSelection {
-            pool_address: best.address,
-            pool_fee: best.fee,
-            is_token0,
-            liquidity: best.liquidity,
-            provider: FlashLoanProvider::UniswapV3,
-            estimated_fee,
-        };
+        if candidates.is_empty() {
+            return Err(anyhow!(
+                "找不到合适的闪电贷池: borrow_token={:?}",
+                borrow_token
+            ));
+        }
 
-        info!(
-            "选择闪电贷池: {:?}, 费率={}bps, 流动性={}, 预估费用={}",
-            selection.pool_address,
-            selection.pool_fee as f64 / 100.0,
-            selection.liquidity,
-            selection.estimated_fee
-        );
+        let ranked = candidates
+            .iter()
+            .map(|pool| FlashPoolSelection {
+                pool_address: pool.address,
+                pool_fee: pool.fee,
+                is_token0: pool.is_token0(borrow_token),
+                liquidity: pool.liquidity,
+                provider: FlashLoanProvider::UniswapV3,
+                // 预估闪电贷费用
+                estimated_fee: borrow_amount * U256::from(pool.fee as u128) / U256::from(1_000_000),
+            })
+            .collect();
 
-        Ok(selection)
+        Ok(ranked)
     }
 
     /// 为三角套利选择闪电贷池
@@ -288,6 +323,113 @@ impl<M: Middleware + 'static> FlashPoolSelector<M> {
             .await
     }
 
+    /// 借入代币配置的首选闪电贷池
+    ///
+    /// 池子在排除列表中、读取失败或不包含借入代币时返回 None，由自动选择接手
+    async fn preferred_pool_info(&self, borrow_token: Address, excluded_pools: &HashSet<Address>) -> Option<V3PoolInfo> {
+        let pool_address = *self.config.preferred_pools.get(&borrow_token)?;
+        if excluded_pools.contains(&pool_address) {
+            debug!("首选闪电贷池 {:?} 在 swap 路径中，改为自动选择", pool_address);
+            return None;
+        }
+        match self.get_pool_info(pool_address).await {
+            Ok(pool) if pool.contains_token(borrow_token) => Some(pool),
+            Ok(_) => {
+                warn!("首选闪电贷池 {:?} 不包含借入代币 {:?}，改为自动选择", pool_address, borrow_token);
+                None
+            }
+            Err(e) => {
+                warn!("读取首选闪电贷池 {:?} 失败: {}，改为自动选择", pool_address, e);
+                None
+            }
+        }
+    }
+
+    /// 三角套利的完整闪电贷池排名 (不排除 swap 池子)
+    pub async fn rank_for_triangular(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        token_c: Address,
+        borrow_amount: U256,
+    ) -> Result<Vec<FlashPoolSelection>> {
+        let pair_tokens = vec![token_b, token_c];
+
+        self.rank_flash_pools(token_a, borrow_amount, &[], &pair_tokens)
+            .await
+    }
+
+    /// 使用指定的闪电贷池 (跳过 factory 查询)
+    ///
+    /// 只读取该池子的代币、费率和流动性，校验包含借入代币且流动性满足最低要求
+    pub async fn selection_for_pool(
+        &self,
+        pool_address: Address,
+        borrow_token: Address,
+        borrow_amount: U256,
+    ) -> Result<FlashPoolSelection> {
+        let pool = self.get_pool_info(pool_address).await?;
+
+        if !pool.contains_token(borrow_token) {
+            return Err(anyhow!(
+                "闪电贷池 {:?} 不包含借入代币 {:?}",
+                pool_address,
+                borrow_token
+            ));
+        }
+        if pool.liquidity < self.config.min_liquidity {
+            return Err(anyhow!(
+                "闪电贷池 {:?} 流动性不足: {} < {}",
+                pool_address,
+                pool.liquidity,
+                self.config.min_liquidity
+            ));
+        }
+
+        Ok(FlashPoolSelection {
+            pool_address,
+            pool_fee: pool.fee,
+            is_token0: pool.is_token0(borrow_token),
+            liquidity: pool.liquidity,
+            provider: FlashLoanProvider::UniswapV3,
+            estimated_fee: borrow_amount * U256::from(pool.fee as u128) / U256::from(1_000_000),
+        })
+    }
+
+    /// 查找 token_a/token_b 之间可用的 V3 池子费率
+    ///
+    /// 指定 fee 时只检查该档位的池子是否存在；否则在优先费率中选流动性最高的一档 (相同时取低费率)
+    pub async fn find_pool_fee(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        fee: Option<u32>,
+    ) -> Result<Option<u32>> {
+        let tiers = match fee {
+            Some(fee) => vec![fee],
+            None => self.config.preferred_fees.clone(),
+        };
+
+        let mut best: Option<(u128, u32)> = None;
+        for fee in tiers {
+            let pool_address = self.factory.get_pool(token_a, token_b, fee).call().await?;
+            if pool_address == Address::zero() {
+                continue;
+            }
+            let liquidity = self.get_pool_info(pool_address).await?.liquidity;
+            if best.is_none_or(|(best_liquidity, _)| liquidity > best_liquidity) {
+                best = Some((liquidity, fee));
+            }
+        }
+        Ok(best.map(|(_, fee)| fee))
+    }
+
+    /// token_a/token_b 在该费率档位的 V3 池子地址 (不存在时为 None)
+    pub async fn pool_address(&self, token_a: Address, token_b: Address, fee: u32) -> Result<Option<Address>> {
+        let pool_address = self.factory.get_pool(token_a, token_b, fee).call().await?;
+        Ok((!pool_address.is_zero()).then_some(pool_address))
+    }
+
     /// 从 ArbitragePath 中提取 swap 池子地址
     pub fn extract_swap_pools(path: &models::ArbitragePath) -> Vec<Address> {
         path.hops.iter().map(|hop| hop.pool_address).collect()
@@ -3,6 +3,7 @@ pub mod curve;
 pub mod pancakeswap;
 pub mod common;
 pub mod flashloan;
+pub mod price_math;
 
 // Re-export main types to avoid ambiguous glob re-exports
 pub use uniswap::{
@@ -11,8 +12,12 @@ pub use uniswap::{
     v2_addresses, v3_addresses, v4_addresses,
 };
 pub use curve::CurveProtocol;
-pub use pancakeswap::{PancakeSwapV2Protocol, PancakeSwapV3Protocol, pancake_v2_addresses, pancake_v3_addresses};
+pub use pancakeswap::{
+    PancakeSwapV2Protocol, PancakeSwapV3Protocol, pancake_v2_addresses, pancake_v3_addresses,
+    StableSwapPool, PancakeStableSwapPool,
+};
 pub use common::DexProtocol;
+pub use price_math::{sqrt_price_x96_to_price, sqrt_price_x96_to_price_decimal};
 pub use flashloan::{
     FlashLoanExecutor, FlashLoanProvider, ArbitrageRequest, ArbitrageResult,
     SwapStep, SimulationResult, ArbitrageTransaction,
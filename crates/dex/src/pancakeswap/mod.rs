@@ -1,8 +1,10 @@
 pub mod v2;
 pub mod v3;
+pub mod stable;
 pub mod contracts;
 
 // Re-export main protocol types
 pub use v2::PancakeSwapV2Protocol;
 pub use v3::PancakeSwapV3Protocol;
+pub use stable::{StableSwapPool, PancakeStableSwapPool, STABLE_FEE_DENOMINATOR};
 pub use contracts::{pancake_v2_addresses, pancake_v3_addresses};
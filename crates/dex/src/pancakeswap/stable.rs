@@ -0,0 +1,231 @@
+use ethers::prelude::*;
+use ethers::types::U256;
+
+// PancakeSwap StableSwap 两币池 ABI (索引使用 uint256，与 Curve 的 int128 不同)
+abigen!(
+    PancakeStableSwapPool,
+    r#"[
+        function coins(uint256 i) external view returns (address)
+        function balances(uint256 i) external view returns (uint256)
+        function A() external view returns (uint256)
+        function fee() external view returns (uint256)
+        function get_dy(uint256 i, uint256 j, uint256 dx) external view returns (uint256)
+    ]"#
+);
+
+/// StableSwap 币数 (PancakeSwap 稳定币池为两币池)
+const N_COINS: usize = 2;
+/// 手续费分母 (fee 以 1e10 为基数, 4000000 = 0.04%)
+pub const STABLE_FEE_DENOMINATOR: u64 = 10_000_000_000;
+/// 牛顿迭代最大次数 (与合约一致)
+const MAX_ITERATIONS: usize = 255;
+
+/// PancakeSwap StableSwap 池状态 (Curve 风格放大系数不变量)
+///
+/// 不变量: A·n^n·Σx + D = A·D·n^n + D^(n+1) / (n^n·Πx)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StableSwapPool {
+    /// 各币余额 (原始精度)
+    pub balances: [U256; 2],
+    /// 放大系数 A (合约 A() 的返回值)
+    pub amp: U256,
+    /// 手续费 (以 1e10 为基数)
+    pub fee: U256,
+    /// 精度补齐系数: 10^(18 - decimals)
+    pub precision_mul: [U256; 2],
+}
+
+impl StableSwapPool {
+    pub fn new(balances: [U256; 2], amp: U256, fee: U256, decimals: [u8; 2]) -> Self {
+        let precision_mul = [
+            U256::exp10(18usize.saturating_sub(decimals[0] as usize)),
+            U256::exp10(18usize.saturating_sub(decimals[1] as usize)),
+        ];
+        Self { balances, amp, fee, precision_mul }
+    }
+
+    /// 余额统一到 18 位精度
+    fn xp(&self) -> [U256; 2] {
+        [
+            self.balances[0] * self.precision_mul[0],
+            self.balances[1] * self.precision_mul[1],
+        ]
+    }
+
+    /// 计算不变量 D
+    pub fn get_d(xp: &[U256; 2], amp: U256) -> Option<U256> {
+        let n = U256::from(N_COINS);
+        let s = xp[0].checked_add(xp[1])?;
+        if s.is_zero() {
+            return Some(U256::zero());
+        }
+        if xp.iter().any(|x| x.is_zero()) || amp.is_zero() {
+            return None;
+        }
+
+        let ann = amp * n;
+        let mut d = s;
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = d;
+            for x in xp {
+                d_p = d_p.checked_mul(d)? / (*x * n);
+            }
+            let d_prev = d;
+            let numerator = (ann * s + d_p * n).checked_mul(d)?;
+            let denominator = (ann - U256::one()) * d + (n + U256::one()) * d_p;
+            d = numerator / denominator;
+
+            if abs_diff(d, d_prev) <= U256::one() {
+                return Some(d);
+            }
+        }
+        Some(d)
+    }
+
+    /// 给定输入币新余额 x，求输出币余额 y (保持 D 不变)
+    fn get_y(i: usize, j: usize, x: U256, xp: &[U256; 2], amp: U256) -> Option<U256> {
+        if i == j || i >= N_COINS || j >= N_COINS {
+            return None;
+        }
+
+        let n = U256::from(N_COINS);
+        let d = Self::get_d(xp, amp)?;
+        let ann = amp * n;
+
+        let mut c = d;
+        let mut s = U256::zero();
+        for (k, xp_k) in xp.iter().enumerate() {
+            let x_k = if k == i {
+                x
+            } else if k != j {
+                *xp_k
+            } else {
+                continue;
+            };
+            s += x_k;
+            c = c.checked_mul(d)? / (x_k * n);
+        }
+        c = c.checked_mul(d)? / (ann * n);
+        let b = s + d / ann;
+
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            let denominator = (U256::from(2) * y + b).checked_sub(d)?;
+            y = (y * y + c) / denominator;
+
+            if abs_diff(y, y_prev) <= U256::one() {
+                return Some(y);
+            }
+        }
+        Some(y)
+    }
+
+    /// 本地计算输出数量 (扣除手续费，结果为输出币原始精度)
+    ///
+    /// 与合约 get_dy(i, j, dx) 的计算一致；币索引越界或相同时返回 None
+    pub fn get_amount_out(&self, i: usize, j: usize, dx: U256) -> Option<U256> {
+        if dx.is_zero() || i >= N_COINS || j >= N_COINS || i == j {
+            return None;
+        }
+
+        let xp = self.xp();
+        let x = xp[i].checked_add(dx.checked_mul(self.precision_mul[i])?)?;
+        let y = Self::get_y(i, j, x, &xp, self.amp)?;
+
+        let dy = xp[j].checked_sub(y)?.checked_sub(U256::one())? / self.precision_mul[j];
+        let fee = self.fee * dy / U256::from(STABLE_FEE_DENOMINATOR);
+        Some(dy - fee)
+    }
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b { a - b } else { b - a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e18(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(18)
+    }
+
+    #[test]
+    fn test_get_d_balanced_pool() {
+        // 平衡池的 D 等于余额之和
+        let d = StableSwapPool::get_d(&[e18(1_000_000), e18(1_000_000)], U256::from(1000)).unwrap();
+        assert_eq!(d, e18(2_000_000));
+    }
+
+    #[test]
+    fn test_swap_preserves_invariant() {
+        // 不收手续费时，换后余额的 D 不小于换前 (取整偏向池子)，且只多出取整误差
+        let pool = StableSwapPool::new([e18(2_000_000), e18(500_000)], U256::from(100), U256::zero(), [18, 18]);
+        let d_before = StableSwapPool::get_d(&pool.balances, pool.amp).unwrap();
+        let dx = e18(100_000);
+        let dy = pool.get_amount_out(0, 1, dx).unwrap();
+        let after = [pool.balances[0] + dx, pool.balances[1] - dy];
+        let d_after = StableSwapPool::get_d(&after, pool.amp).unwrap();
+        assert!(d_after >= d_before);
+        assert!(d_after - d_before < U256::exp10(6));
+    }
+
+    #[test]
+    fn test_fee_is_taken_from_output() {
+        let balances = [e18(1_000_000), e18(1_000_000)];
+        let no_fee = StableSwapPool::new(balances, U256::from(1000), U256::zero(), [18, 18]);
+        let with_fee = StableSwapPool::new(balances, U256::from(1000), U256::from(4_000_000), [18, 18]);
+        let gross = no_fee.get_amount_out(0, 1, e18(1000)).unwrap();
+        let fee = gross * U256::from(4_000_000) / U256::from(STABLE_FEE_DENOMINATOR);
+        assert_eq!(with_fee.get_amount_out(0, 1, e18(1000)).unwrap(), gross - fee);
+    }
+
+    #[test]
+    fn test_price_follows_balance_and_amplification() {
+        let dx = e18(10_000);
+        // 平衡池、高 A: 接近 1:1 但不超过输入
+        let balanced = StableSwapPool::new([e18(1_000_000), e18(1_000_000)], U256::from(1000), U256::zero(), [18, 18]);
+        let dy = balanced.get_amount_out(0, 1, dx).unwrap();
+        assert!(dy < dx && dy > dx * 9999 / 10000);
+
+        // 失衡池: 卖出多的一边换到的少于 1:1，卖出少的一边换到的多于 1:1
+        let imbalanced = StableSwapPool::new([e18(2_000_000), e18(500_000)], U256::from(100), U256::zero(), [18, 18]);
+        assert!(imbalanced.get_amount_out(0, 1, dx).unwrap() < dx);
+        assert!(imbalanced.get_amount_out(1, 0, dx).unwrap() > dx);
+
+        // 同样的失衡，A 越低偏离越大
+        let low_amp = StableSwapPool::new([e18(2_000_000), e18(500_000)], U256::from(10), U256::zero(), [18, 18]);
+        assert!(low_amp.get_amount_out(0, 1, dx).unwrap() < imbalanced.get_amount_out(0, 1, dx).unwrap());
+    }
+
+    #[test]
+    fn test_get_amount_out_mixed_decimals() {
+        // token1 为 6 位精度: 结果与全 18 位的池子只差精度换算
+        let uniform = StableSwapPool::new([e18(1_000_000), e18(1_000_000)], U256::from(1000), U256::from(4_000_000), [18, 18]);
+        let mixed = StableSwapPool::new(
+            [e18(1_000_000), U256::from(1_000_000u64) * U256::exp10(6)],
+            U256::from(1000),
+            U256::from(4_000_000),
+            [18, 6],
+        );
+        let expected = uniform.get_amount_out(0, 1, e18(1000)).unwrap() / U256::exp10(12);
+        let actual = mixed.get_amount_out(0, 1, e18(1000)).unwrap();
+        assert!(abs_diff(actual, expected) <= U256::one());
+    }
+
+    #[test]
+    fn test_invalid_coin_indices_have_no_quote() {
+        let pool = StableSwapPool::new([e18(1_000_000), e18(1_000_000)], U256::from(1000), U256::zero(), [18, 18]);
+        assert_eq!(pool.get_amount_out(0, 0, e18(1)), None);
+        assert_eq!(pool.get_amount_out(0, 2, e18(1)), None);
+        assert_eq!(pool.get_amount_out(2, 1, e18(1)), None);
+        assert!(pool.get_amount_out(1, 0, e18(1)).is_some());
+    }
+
+    #[test]
+    fn test_empty_pool_has_no_quote() {
+        let empty = StableSwapPool::new([U256::zero(), U256::zero()], U256::from(1000), U256::zero(), [18, 18]);
+        assert_eq!(empty.get_amount_out(0, 1, e18(1)), None);
+    }
+}
@@ -0,0 +1,99 @@
+//! sqrtPriceX96 价格换算
+//!
+//! sqrtPriceX96 是 uint160，平方后最多 320 位，不能先转成 u128/f64 再计算
+//! (超过 u128 会 panic，转 f64 会丢精度)。这里全部用 U512 整数运算，
+//! 只在最后一步转换成 Decimal 或 f64
+
+use ethers::types::{U256, U512};
+use rust_decimal::Decimal;
+
+/// Decimal 最大小数位数
+const DECIMAL_MAX_SCALE: u32 = 28;
+
+/// price = sqrtPriceX96^2 / 2^192 * 10^(decimals0 - decimals1) 的分子和分母
+fn price_ratio(sqrt_price_x96: U256, decimals0: u8, decimals1: u8) -> Option<(U512, U512)> {
+    let sqrt = U512::from(sqrt_price_x96);
+    let numerator = (sqrt * sqrt).checked_mul(U512::exp10(decimals0 as usize))?;
+    let denominator = (U512::one() << 192).checked_mul(U512::exp10(decimals1 as usize))?;
+    Some((numerator, denominator))
+}
+
+/// 计算 token1/token0 价格 (已按精度调整)，截断到 Decimal 可表示的最多位数
+///
+/// 价格超过 Decimal 最大值时返回 None；小于 1e-28 的价格返回 0
+pub fn sqrt_price_x96_to_price_decimal(sqrt_price_x96: U256, decimals0: u8, decimals1: u8) -> Option<Decimal> {
+    let (numerator, denominator) = price_ratio(sqrt_price_x96, decimals0, decimals1)?;
+
+    let integer = numerator / denominator;
+    if integer > U512::from(u128::MAX) {
+        return None;
+    }
+    let integer_digits = if integer.is_zero() { 0 } else { integer.to_string().len() as u32 };
+    // Decimal 尾数最多 96 位 (约 28 位十进制有效数字)
+    let scale = DECIMAL_MAX_SCALE.saturating_sub(integer_digits);
+
+    let scaled = numerator.checked_mul(U512::exp10(scale as usize))? / denominator;
+    if scaled > U512::from(u128::MAX) {
+        return None;
+    }
+    Decimal::try_from_i128_with_scale(scaled.low_u128() as i128, scale).ok()
+}
+
+/// 计算 token1/token0 价格 (f64，用于日志和粗略比较)
+///
+/// 分子分母各自转换为 f64 后相除，相对误差在 f64 精度以内
+pub fn sqrt_price_x96_to_price(sqrt_price_x96: U256, decimals0: u8, decimals1: u8) -> f64 {
+    let sqrt = U512::from(sqrt_price_x96);
+    let squared = u512_to_f64(sqrt * sqrt);
+    let raw_price = squared / 2f64.powi(192);
+    raw_price * 10f64.powi(decimals0 as i32 - decimals1 as i32)
+}
+
+/// U512 转 f64 (经十进制字符串转换，结果为最接近的 f64)
+fn u512_to_f64(value: U512) -> f64 {
+    value.to_string().parse().unwrap_or(f64::INFINITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_price_above_u128_range() {
+        // WBTC(8)/SHIB(18): 1 WBTC ≈ 60 亿 SHIB，sqrtPriceX96 超过 u128 上限
+        let sqrt_price = U256::from_dec_str("613698707936721051257405563935529819467").unwrap();
+        assert!(sqrt_price > U256::from(u128::MAX));
+
+        // 参考值由精确分数运算得出: floor(sqrt^2 * 10^8 * 10^18 / (2^192 * 10^18)) / 10^18
+        let price = sqrt_price_x96_to_price_decimal(sqrt_price, 8, 18).unwrap();
+        assert_eq!(price, Decimal::from_str("5999999999.999999999999999999").unwrap());
+
+        let price_f64 = sqrt_price_x96_to_price(sqrt_price, 8, 18);
+        assert!((price_f64 - 6_000_000_000.0).abs() / 6_000_000_000.0 < 1e-12);
+    }
+
+    #[test]
+    fn test_price_exact_for_large_and_tiny_values() {
+        // sqrtPriceX96 = 2^140, 同精度: price = 2^88
+        let large = sqrt_price_x96_to_price_decimal(U256::one() << 140, 18, 18).unwrap();
+        assert_eq!(large, Decimal::from_str("309485009821345068724781056").unwrap());
+
+        // sqrtPriceX96 = 2^40, 精度 18/6: price = 2^-112 * 10^12
+        let tiny = sqrt_price_x96_to_price_decimal(U256::one() << 40, 18, 6).unwrap();
+        assert_eq!(tiny, Decimal::from_str("0.0000000000000000000001925929").unwrap());
+
+        // 超过 Decimal 表示范围
+        assert!(sqrt_price_x96_to_price_decimal(U256::one() << 159, 18, 6).is_none());
+    }
+
+    #[test]
+    fn test_price_at_parity() {
+        // sqrtPriceX96 = 2^96 表示 1:1
+        let parity = U256::one() << 96;
+        assert_eq!(sqrt_price_x96_to_price_decimal(parity, 18, 18).unwrap(), Decimal::ONE);
+        assert_eq!(sqrt_price_x96_to_price(parity, 18, 18), 1.0);
+        // USDC(6)/WETH(18): 原始 1:1 调整后为 1e-12
+        assert_eq!(sqrt_price_x96_to_price_decimal(parity, 6, 18).unwrap(), Decimal::new(1, 12));
+    }
+}
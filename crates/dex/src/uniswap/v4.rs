@@ -373,10 +373,7 @@ impl<M: Middleware + 'static> UniswapV4Protocol<M> {
 
     /// 从 sqrtPriceX96 计算价格
     pub fn sqrt_price_x96_to_price(sqrt_price_x96: U256, decimals0: u8, decimals1: u8) -> f64 {
-        let sqrt_price = sqrt_price_x96.as_u128() as f64 / (2_u128.pow(96) as f64);
-        let price = sqrt_price * sqrt_price;
-        let decimal_adjustment = 10_f64.powi(decimals0 as i32 - decimals1 as i32);
-        price * decimal_adjustment
+        crate::price_math::sqrt_price_x96_to_price(sqrt_price_x96, decimals0, decimals1)
     }
 
     /// 获取 Flash Accounting 模块
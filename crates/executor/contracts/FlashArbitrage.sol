@@ -18,6 +18,11 @@ interface IUniswapV3Pool {
     ) external;
 }
 
+interface IWETH {
+    function deposit() external payable;
+    function withdraw(uint256 amount) external;
+}
+
 interface IUniswapV3FlashCallback {
     function uniswapV3FlashCallback(
         uint256 fee0,
@@ -38,15 +43,31 @@ interface ISwapRouter {
         uint160 sqrtPriceLimitX96;
     }
 
+    struct ExactOutputSingleParams {
+        address tokenIn;
+        address tokenOut;
+        uint24 fee;
+        address recipient;
+        uint256 deadline;
+        uint256 amountOut;
+        uint256 amountInMaximum;
+        uint160 sqrtPriceLimitX96;
+    }
+
     function exactInputSingle(ExactInputSingleParams calldata params)
         external
         payable
         returns (uint256 amountOut);
+
+    function exactOutputSingle(ExactOutputSingleParams calldata params)
+        external
+        payable
+        returns (uint256 amountIn);
 }
 
 /// @title FlashArbitrage - 闪电贷三角套利合约
 /// @notice 使用 Uniswap V3 闪电贷执行三角套利
-/// @dev 支持 A -> B -> C -> A 的套利路径
+/// @dev 支持 A -> B -> C -> A 的套利路径；借入代币与 A 不同时，借入后先换成 A，还款前再换回
 contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
     using SafeERC20 for IERC20;
 
@@ -56,6 +77,11 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
     /// @notice 最小利润阈值 (wei)
     uint256 public minProfitThreshold;
 
+    /// @notice 接口版本，执行器调用的合约接口变化时递增 (链下执行器启动时校验)
+    /// @dev 版本 2: ArbitrageParams 增加 minOut1..3; 版本 3: 增加 wrapNative / unwrapNative;
+    ///      版本 4: ArbitrageParams 增加 flashToken / flashConvertFee / flashAmount
+    uint256 public constant INTERFACE_VERSION = 4;
+
     /// @notice 套利执行事件
     event ArbitrageExecuted(
         address indexed tokenA,
@@ -90,7 +116,7 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
 
     /// @notice 每步交换执行事件 (用于追踪每一步的输入输出)
     event SwapStepExecuted(
-        uint8 indexed step,        // 步骤: 1, 2, 3
+        uint8 indexed step,        // 步骤: 1, 2, 3 (借入代币转换: 0 为换入, 4 为换回)
         address tokenIn,           // 输入代币
         address tokenOut,          // 输出代币
         uint256 amountIn,          // 输入数量
@@ -140,6 +166,12 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         uint256 amount0;     // 借入的 token0 数量
         uint256 amount1;     // 借入的 token1 数量
         address flashPool;   // 闪电贷来源池
+        uint256 minOut1;     // A -> B 最小输出
+        uint256 minOut2;     // B -> C 最小输出
+        uint256 minOut3;     // C -> A 最小输出
+        address flashToken;  // 借入代币 (与 tokenA 相同表示不转换)
+        uint24 flashConvertFee; // 借入代币 <-> tokenA 转换池费率
+        uint256 amountIn;    // 转换后 tokenA 最少数量 (不转换时等于借入金额)
     }
 
     /// @notice 套利参数结构
@@ -155,6 +187,12 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         uint256 minProfit;   // 最小利润要求
         address profitToken; // 利润结算代币 (address(0) 表示不转换，保留原始代币)
         uint24 profitConvertFee; // 利润转换池费率 (tokenA -> profitToken)
+        uint256 minOut1;     // A -> B 最小输出 (0 表示不检查)
+        uint256 minOut2;     // B -> C 最小输出 (0 表示不检查)
+        uint256 minOut3;     // C -> A 最小输出 (0 表示不检查)
+        address flashToken;  // 闪电贷借入代币 (address(0) 或 tokenA 表示直接借 tokenA)
+        uint24 flashConvertFee; // 借入代币 <-> tokenA 转换池费率
+        uint256 flashAmount; // 借入代币数量 (仅借入代币与 tokenA 不同时使用，换成 tokenA 后不少于 amountIn)
     }
 
     constructor(address _swapRouter) Ownable(msg.sender) {
@@ -176,21 +214,24 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         require(params.flashPool != address(0), "Invalid flash pool");
 
         IUniswapV3Pool pool = IUniswapV3Pool(params.flashPool);
-        address token0 = pool.token0();
-        address token1 = pool.token1();
 
-        // 确定借入哪个代币
+        // 确定借入哪个代币及数量
+        address borrowToken = params.flashToken == address(0) ? params.tokenA : params.flashToken;
+        uint256 borrowAmount = params.amountIn;
+        if (borrowToken != params.tokenA) {
+            require(params.flashAmount > 0, "Flash amount must be > 0");
+            borrowAmount = params.flashAmount;
+        }
+
         uint256 amount0;
         uint256 amount1;
 
-        if (params.tokenA == token0) {
-            amount0 = params.amountIn;
-            amount1 = 0;
-        } else if (params.tokenA == token1) {
-            amount0 = 0;
-            amount1 = params.amountIn;
+        if (borrowToken == pool.token0()) {
+            amount0 = borrowAmount;
+        } else if (borrowToken == pool.token1()) {
+            amount1 = borrowAmount;
         } else {
-            revert("Token A not in flash pool");
+            revert("Flash token not in flash pool");
         }
 
         // 记录套利前余额
@@ -206,7 +247,13 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
             fee3: params.fee3,
             amount0: amount0,
             amount1: amount1,
-            flashPool: params.flashPool
+            flashPool: params.flashPool,
+            minOut1: params.minOut1,
+            minOut2: params.minOut2,
+            minOut3: params.minOut3,
+            flashToken: borrowToken,
+            flashConvertFee: params.flashConvertFee,
+            amountIn: params.amountIn
         }));
 
         // 发起闪电贷
@@ -291,26 +338,27 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         uint256 fee = decoded.amount0 > 0 ? fee0 : fee1;
         uint256 amountOwed = amountBorrowed + fee;
 
+        // 借入代币与起始代币不同时先换成起始代币 (第 0 跳)
+        uint256 loopIn = _convertBorrowed(decoded, amountBorrowed);
+
         // 执行三角套利: A -> B -> C -> A (返回每步结果)
         (uint256 amountOut, uint256 step1Out, uint256 step2Out) = _executeTriangularSwapWithDetails(
-            decoded.tokenA,
-            decoded.tokenB,
-            decoded.tokenC,
-            decoded.fee1,
-            decoded.fee2,
-            decoded.fee3,
-            amountBorrowed
+            decoded,
+            loopIn
         );
 
+        // 还款所需的起始代币 (转换失败时按投入的 loopIn 计算亏损)
+        (bool repayable, uint256 repayCost) = _prepareRepayment(decoded, amountOut, amountOwed, loopIn);
+
         // 计算盈亏 (可能为负)
-        // casting to 'int256' is safe because amountOut and amountOwed are token amounts
+        // casting to 'int256' is safe because amountOut and repayCost are token amounts
         // which are always far below int256.max (~2^255)
         // forge-lint: disable-next-line(unsafe-typecast)
-        int256 profitOrLoss = int256(amountOut) - int256(amountOwed);
+        int256 profitOrLoss = int256(amountOut) - int256(repayCost);
 
         // 发出详细结果事件 (即使失败也会在 error 中携带这些信息)
         emit ArbitrageResult(
-            amountBorrowed,
+            loopIn,
             step1Out,
             step2Out,
             amountOut,
@@ -319,13 +367,13 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         );
 
         // 确保有足够的代币归还闪电贷 (使用自定义 error 携带详细信息)
-        if (amountOut < amountOwed) {
+        if (!repayable) {
             revert ArbitrageFailed_Detailed(
                 "Insufficient output for repayment",
                 decoded.tokenA,
                 decoded.tokenB,
                 decoded.tokenC,
-                amountBorrowed,
+                loopIn,
                 step1Out,
                 step2Out,
                 amountOut,
@@ -335,19 +383,80 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         }
 
         // 归还闪电贷
-        IERC20(decoded.tokenA).safeTransfer(msg.sender, amountOwed);
+        IERC20(decoded.flashToken).safeTransfer(msg.sender, amountOwed);
+    }
+
+    /// @notice 把借入代币换成起始代币 (第 0 跳)，借入的就是起始代币时原样返回
+    /// @dev 换出的起始代币不少于 d.amountIn，否则 revert
+    function _convertBorrowed(
+        FlashCallbackData memory d,
+        uint256 amountBorrowed
+    ) internal returns (uint256 loopIn) {
+        if (d.flashToken == d.tokenA) {
+            return amountBorrowed;
+        }
+
+        IERC20(d.flashToken).forceApprove(address(SWAP_ROUTER), amountBorrowed);
+        loopIn = SWAP_ROUTER.exactInputSingle(
+            ISwapRouter.ExactInputSingleParams({
+                tokenIn: d.flashToken,
+                tokenOut: d.tokenA,
+                fee: d.flashConvertFee,
+                recipient: address(this),
+                deadline: block.timestamp,
+                amountIn: amountBorrowed,
+                amountOutMinimum: d.amountIn,
+                sqrtPriceLimitX96: 0
+            })
+        );
+        emit SwapStepExecuted(0, d.flashToken, d.tokenA, amountBorrowed, loopIn);
+    }
+
+    /// @notice 准备还款: 借入代币与起始代币不同时，用起始代币按精确输出换回 amountOwed 的借入代币
+    /// @return repayable 是否足以还款
+    /// @return repayCost 还款花费的起始代币 (转换失败时为 loopIn)
+    function _prepareRepayment(
+        FlashCallbackData memory d,
+        uint256 amountOut,
+        uint256 amountOwed,
+        uint256 loopIn
+    ) internal returns (bool repayable, uint256 repayCost) {
+        if (d.flashToken == d.tokenA) {
+            return (amountOut >= amountOwed, amountOwed);
+        }
+
+        IERC20(d.tokenA).forceApprove(address(SWAP_ROUTER), amountOut);
+        try SWAP_ROUTER.exactOutputSingle(
+            ISwapRouter.ExactOutputSingleParams({
+                tokenIn: d.tokenA,
+                tokenOut: d.flashToken,
+                fee: d.flashConvertFee,
+                recipient: address(this),
+                deadline: block.timestamp,
+                amountOut: amountOwed,
+                amountInMaximum: amountOut,
+                sqrtPriceLimitX96: 0
+            })
+        ) returns (uint256 spent) {
+            emit SwapStepExecuted(4, d.tokenA, d.flashToken, spent, amountOwed);
+            repayable = true;
+            repayCost = spent;
+        } catch {
+            repayCost = loopIn;
+        }
+        IERC20(d.tokenA).forceApprove(address(SWAP_ROUTER), 0);
     }
 
     /// @notice 执行三角交换并返回每步详情 A -> B -> C -> A
+    /// @dev 每一跳以 minOutN 作为 amountOutMinimum，任何一跳滑点过大都会提前 revert
     function _executeTriangularSwapWithDetails(
-        address tokenA,
-        address tokenB,
-        address tokenC,
-        uint24 fee1,
-        uint24 fee2,
-        uint24 fee3,
+        FlashCallbackData memory d,
         uint256 amountIn
     ) internal returns (uint256 amountOut, uint256 step1Out, uint256 step2Out) {
+        address tokenA = d.tokenA;
+        address tokenB = d.tokenB;
+        address tokenC = d.tokenC;
+
         // 授权 SwapRouter
         IERC20(tokenA).forceApprove(address(SWAP_ROUTER), amountIn);
 
@@ -356,11 +465,11 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
             ISwapRouter.ExactInputSingleParams({
                 tokenIn: tokenA,
                 tokenOut: tokenB,
-                fee: fee1,
+                fee: d.fee1,
                 recipient: address(this),
                 deadline: block.timestamp,
                 amountIn: amountIn,
-                amountOutMinimum: 0,
+                amountOutMinimum: d.minOut1,
                 sqrtPriceLimitX96: 0
             })
         );
@@ -372,11 +481,11 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
             ISwapRouter.ExactInputSingleParams({
                 tokenIn: tokenB,
                 tokenOut: tokenC,
-                fee: fee2,
+                fee: d.fee2,
                 recipient: address(this),
                 deadline: block.timestamp,
                 amountIn: step1Out,
-                amountOutMinimum: 0,
+                amountOutMinimum: d.minOut2,
                 sqrtPriceLimitX96: 0
             })
         );
@@ -388,11 +497,11 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
             ISwapRouter.ExactInputSingleParams({
                 tokenIn: tokenC,
                 tokenOut: tokenA,
-                fee: fee3,
+                fee: d.fee3,
                 recipient: address(this),
                 deadline: block.timestamp,
                 amountIn: step2Out,
-                amountOutMinimum: 0,
+                amountOutMinimum: d.minOut3,
                 sqrtPriceLimitX96: 0
             })
         );
@@ -484,6 +593,21 @@ contract FlashArbitrage is IUniswapV3FlashCallback, Ownable, ReentrancyGuard {
         }
     }
 
+    /// @notice 把合约持有的 ETH 包装成 WETH (补充 WETH 起点路径的余额)
+    /// @param weth Wrapped Native 代币地址
+    /// @param amount 包装数量
+    function wrapNative(address weth, uint256 amount) external onlyOwner {
+        require(address(this).balance >= amount, "Insufficient ETH");
+        IWETH(weth).deposit{value: amount}();
+    }
+
+    /// @notice 把合约持有的 WETH 解包成 ETH
+    /// @param weth Wrapped Native 代币地址
+    /// @param amount 解包数量
+    function unwrapNative(address weth, uint256 amount) external onlyOwner {
+        IWETH(weth).withdraw(amount);
+    }
+
     /// @notice 接收 ETH
     receive() external payable {}
 }
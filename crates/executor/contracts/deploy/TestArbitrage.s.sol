@@ -64,7 +64,15 @@ contract SimulateArbitrage is Script {
         console.log("    fee2: 500,   // 0.05%");
         console.log("    fee3: 3000,  // 0.3%");
         console.log("    amountIn: 1 ether,");
-        console.log("    minProfit: 0.01 ether");
+        console.log("    minProfit: 0.01 ether,");
+        console.log("    profitToken: address(0),");
+        console.log("    profitConvertFee: 0,");
+        console.log("    minOut1: 0,  // 0 = no per-hop check");
+        console.log("    minOut2: 0,");
+        console.log("    minOut3: 0,");
+        console.log("    flashToken: address(0),  // address(0) = borrow tokenA");
+        console.log("    flashConvertFee: 0,");
+        console.log("    flashAmount: 0");
         console.log("}));");
     }
 }
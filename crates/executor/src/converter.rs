@@ -7,24 +7,67 @@ use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use models::{ArbitrageOpportunity, DexType};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::types::ArbitrageParams;
+use crate::types::{ArbitrageParams, ExecutionError};
+
+abigen!(
+    FlashTokenBalance,
+    r#"[function balanceOf(address account) external view returns (uint256)]"#
+);
+
+// 只解码 slot0 的第一个字段 (Uniswap / PancakeSwap V3 的 feeProtocol 类型不同)
+abigen!(
+    FlashConvertPool,
+    r#"[function slot0() external view returns (uint160 sqrtPriceX96)]"#
+);
+
+/// 闪电贷池余额需要超出借入金额的比例 (bps)，留出同一区块内其它交易取走流动性的余量
+const FLASH_LIQUIDITY_BUFFER_BPS: u64 = 500;
+
+/// 借入代币按现价折算后多借的比例 (bps)，覆盖第 0 跳的价格影响，保证换出的起始代币不少于 amount_in
+const FLASH_CONVERSION_BUFFER_BPS: u64 = 50;
+
+/// 借入代币与起始代币之间的转换
+#[derive(Debug, Clone, Copy)]
+struct FlashConversion {
+    /// 借入代币
+    token: Address,
+    /// 转换池费率
+    fee: u32,
+    /// 转换池 (合约在闪电贷回调里经它 swap，不能同时作为闪电贷池)
+    pool: Address,
+    /// 借入数量 (借入代币最小单位)
+    amount: U256,
+}
 
 // 重新导出 dex crate 的闪电贷选择器
 pub use dex::flashloan::{
     FlashPoolSelector, CachedFlashPoolSelector, FlashPoolSelection,
     FlashPoolSelectorConfig, V3PoolInfo,
 };
+pub use dex::flashloan::{FlashFeeTable, FlashLoanProvider};
 
 /// 套利参数构建器
 pub struct ArbitrageParamsBuilder<M: Middleware> {
-    #[allow(dead_code)]
     provider: Arc<M>,
     flash_selector: FlashPoolSelector<M>,
     /// 默认最小利润 (wei)
     default_min_profit: U256,
+    /// 固定使用的闪电贷池 (设置后不再自动选择)
+    pinned_flash_pool: Option<Address>,
+    /// 利润结算代币 (None 表示保留起始代币)
+    profit_token: Option<Address>,
+    /// 利润转换池费率 (None 表示自动选择)
+    profit_convert_fee: Option<u32>,
+    /// 闪电贷借入代币 (None 表示借起始代币)
+    flash_token: Option<Address>,
+    /// 借入代币转换池费率 (None 表示自动选择)
+    flash_convert_fee: Option<u32>,
+    /// 闪电贷费率表 (估算闪电贷费用)
+    flash_fee_table: FlashFeeTable,
 }
 
 impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
@@ -39,15 +82,122 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
             provider: provider.clone(),
             flash_selector: FlashPoolSelector::new(provider, config),
             default_min_profit: U256::zero(),
+            pinned_flash_pool: None,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
+            flash_fee_table: FlashFeeTable::default(),
         }
     }
 
+    /// 设置闪电贷费率表 (与检测阶段使用同一份配置)
+    pub fn with_flash_fee_table(mut self, table: FlashFeeTable) -> Self {
+        self.flash_fee_table = table;
+        self
+    }
+
     /// 设置默认最小利润
     pub fn with_min_profit(mut self, min_profit: U256) -> Self {
         self.default_min_profit = min_profit;
         self
     }
 
+    /// 固定闪电贷池 (None 表示自动选择)
+    pub fn with_flash_pool(mut self, flash_pool: Option<Address>) -> Self {
+        self.pinned_flash_pool = flash_pool;
+        self
+    }
+
+    /// 设置借入代币的首选闪电贷池 (借入代币 → 池子)
+    ///
+    /// 自动选择时首选池子排在最前；它在 swap 路径中或余额不足时回退到其余候选
+    pub fn with_preferred_flash_pools(mut self, preferred_pools: HashMap<Address, Address>) -> Self {
+        self.flash_selector = self.flash_selector.with_preferred_pools(preferred_pools);
+        self
+    }
+
+    /// 设置利润结算代币 (None 表示保留起始代币)
+    ///
+    /// 构建参数时校验起始代币与结算代币之间存在该费率的池子；`convert_fee` 为 None 时
+    /// 自动选择流动性最高的费率档位，找不到池子则拒绝构建，避免执行到最后一步才 revert
+    pub fn with_profit_token(mut self, profit_token: Option<Address>, convert_fee: Option<u32>) -> Self {
+        self.profit_token = profit_token;
+        self.profit_convert_fee = convert_fee;
+        self
+    }
+
+    /// 设置闪电贷借入代币 (None 表示借起始代币)
+    ///
+    /// 借入代币与路径起始代币不同时，合约借入后先经转换池换成起始代币 (第 0 跳)，还款前再换回；
+    /// 往返两次的转换手续费计入 `min_profit`。`convert_fee` 为 None 时自动选择流动性最高的档位，
+    /// 找不到转换池则拒绝构建
+    pub fn with_flash_token(mut self, flash_token: Option<Address>, convert_fee: Option<u32>) -> Self {
+        self.flash_token = flash_token;
+        self.flash_convert_fee = convert_fee;
+        self
+    }
+
+    /// 解析借入代币转换: 查找转换池，按池子现价算出需要借入的数量；借入代币就是起始代币时不转换
+    async fn resolve_flash_conversion(&self, token_a: Address, amount_in: U256) -> Result<Option<FlashConversion>> {
+        let Some(flash_token) = self.flash_token.filter(|token| *token != token_a && !token.is_zero()) else {
+            return Ok(None);
+        };
+        let missing = ExecutionError::NoFlashConversionPool {
+            flash_token,
+            token_a,
+            fee: self.flash_convert_fee,
+        };
+        let Some(fee) = self.flash_selector.find_pool_fee(flash_token, token_a, self.flash_convert_fee).await? else {
+            return Err(missing.into());
+        };
+        let Some(pool) = self.flash_selector.pool_address(flash_token, token_a, fee).await? else {
+            return Err(missing.into());
+        };
+        let sqrt_price_x96 = FlashConvertPool::new(pool, self.provider.clone())
+            .slot_0()
+            .call()
+            .await
+            .map_err(|e| anyhow!("读取转换池 {:?} 价格失败: {}", pool, e))?;
+        // V3 池子的 token0 是地址较小的代币
+        let amount = flash_borrow_amount(amount_in, token_a < flash_token, sqrt_price_x96, fee)
+            .ok_or_else(|| anyhow!("转换池 {:?} 价格无效，无法折算借入数量", pool))?;
+        info!(
+            "闪电贷借入 {:?} ({})，先经 {}bps 池子 {:?} 转换为起始代币 {:?}",
+            flash_token,
+            amount,
+            fee as f64 / 100.0,
+            pool,
+            token_a
+        );
+        Ok(Some(FlashConversion { token: flash_token, fee, pool, amount }))
+    }
+
+    /// 最小利润要求: 需要转换借入代币时加上往返转换手续费
+    fn min_profit_for(&self, conversion: Option<&FlashConversion>, amount_in: U256) -> U256 {
+        match conversion {
+            Some(conversion) => self.default_min_profit + flash_conversion_cost(amount_in, conversion.fee),
+            None => self.default_min_profit,
+        }
+    }
+
+    /// 解析利润结算代币及转换池费率，结算代币就是起始代币时不转换
+    async fn resolve_profit_conversion(&self, token_a: Address) -> Result<(Option<Address>, u32)> {
+        let Some(profit_token) = self.profit_token.filter(|token| *token != token_a && !token.is_zero()) else {
+            return Ok((None, 0));
+        };
+        let fee = self
+            .flash_selector
+            .find_pool_fee(token_a, profit_token, self.profit_convert_fee)
+            .await?
+            .ok_or(ExecutionError::NoProfitConversionPool {
+                token_a,
+                profit_token,
+                fee: self.profit_convert_fee,
+            })?;
+        Ok((Some(profit_token), fee))
+    }
+
     /// 从 ArbitrageOpportunity 构建 ArbitrageParams
     ///
     /// 自动选择最优闪电贷池
@@ -88,15 +238,9 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
         let fee3 = hops[2].fee;
 
         // 选择闪电贷池
+        let conversion = self.resolve_flash_conversion(token_a, opportunity.input_amount).await?;
         let flash_selection = self
-            .flash_selector
-            .select_for_triangular(
-                token_a,
-                token_b,
-                token_c,
-                opportunity.input_amount,
-                &swap_pools,
-            )
+            .select_flash_pool(token_a, token_b, token_c, opportunity.input_amount, &swap_pools, conversion.as_ref())
             .await?;
 
         info!(
@@ -108,10 +252,14 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
             flash_selection.pool_address,
             flash_selection.pool_fee as f64 / 100.0
         );
+        let (profit_token, profit_convert_fee) = self.resolve_profit_conversion(token_a).await?;
 
         Ok(ArbitrageParams {
             flash_pool: flash_selection.pool_address,
             flash_pool_fee: flash_selection.pool_fee,
+            flash_token: conversion.map(|c| c.token),
+            flash_convert_fee: conversion.map(|c| c.fee).unwrap_or(0),
+            flash_amount: conversion.map(|c| c.amount).unwrap_or_default(),
             token_a,
             token_b,
             token_c,
@@ -119,13 +267,15 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
             fee2,
             fee3,
             amount_in: opportunity.input_amount,
-            min_profit: self.default_min_profit,
+            min_profit: self.min_profit_for(conversion.as_ref(), opportunity.input_amount),
             estimated_profit_usd: opportunity.expected_profit_usd,
             estimated_gas_cost_usd: opportunity.gas_cost_usd,
-            estimated_flash_fee: flash_selection.estimated_fee,
-            profit_token: None,
-            profit_convert_fee: 0,
+            estimated_flash_fee: self.flash_fee(&flash_selection, conversion.as_ref(), opportunity.input_amount),
+            profit_token,
+            profit_convert_fee,
+            hop_quotes: None,
             swap_pools,
+            bundle_privacy: None,
         })
     }
 
@@ -144,14 +294,18 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
         estimated_gas_cost_usd: Decimal,
     ) -> Result<ArbitrageParams> {
         // 选择闪电贷池
+        let conversion = self.resolve_flash_conversion(token_a, amount_in).await?;
         let flash_selection = self
-            .flash_selector
-            .select_for_triangular(token_a, token_b, token_c, amount_in, &swap_pools)
+            .select_flash_pool(token_a, token_b, token_c, amount_in, &swap_pools, conversion.as_ref())
             .await?;
+        let (profit_token, profit_convert_fee) = self.resolve_profit_conversion(token_a).await?;
 
         Ok(ArbitrageParams {
             flash_pool: flash_selection.pool_address,
             flash_pool_fee: flash_selection.pool_fee,
+            flash_token: conversion.map(|c| c.token),
+            flash_convert_fee: conversion.map(|c| c.fee).unwrap_or(0),
+            flash_amount: conversion.map(|c| c.amount).unwrap_or_default(),
             token_a,
             token_b,
             token_c,
@@ -159,15 +313,151 @@ impl<M: Middleware + 'static> ArbitrageParamsBuilder<M> {
             fee2,
             fee3,
             amount_in,
-            min_profit: self.default_min_profit,
+            min_profit: self.min_profit_for(conversion.as_ref(), amount_in),
             estimated_profit_usd,
             estimated_gas_cost_usd,
-            estimated_flash_fee: flash_selection.estimated_fee,
-            profit_token: None,
-            profit_convert_fee: 0,
+            estimated_flash_fee: self.flash_fee(&flash_selection, conversion.as_ref(), amount_in),
+            profit_token,
+            profit_convert_fee,
+            hop_quotes: None,
             swap_pools,
+            bundle_privacy: None,
         })
     }
+
+    /// 按费率表估算所选闪电贷池的费用 (借入代币计价)
+    fn flash_fee(&self, selection: &FlashPoolSelection, conversion: Option<&FlashConversion>, amount_in: U256) -> U256 {
+        let borrow_amount = conversion.map_or(amount_in, |c| c.amount);
+        calculate_flash_fee(&self.flash_fee_table, selection.provider, borrow_amount, selection.pool_fee)
+    }
+
+    /// 选择与 swap 路径不重叠的闪电贷池
+    ///
+    /// 闪电贷池同时出现在 swap 路径中时，合约在 flash 回调里再次 swap 同一个池子会因重入锁回滚。
+    /// 配置了固定闪电贷池时直接校验并使用它。
+    /// 借入代币与起始代币不同时，按借入代币和借入数量选择，转换池同样不能作为闪电贷池
+    async fn select_flash_pool(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        token_c: Address,
+        amount_in: U256,
+        swap_pools: &[Address],
+        conversion: Option<&FlashConversion>,
+    ) -> Result<FlashPoolSelection> {
+        let (borrow_token, borrow_amount) = conversion.map_or((token_a, amount_in), |c| (c.token, c.amount));
+        let mut swap_pools = swap_pools.to_vec();
+        swap_pools.extend(conversion.map(|c| c.pool));
+
+        if let Some(pool) = self.pinned_flash_pool {
+            if swap_pools.contains(&pool) {
+                return Err(anyhow!("固定的闪电贷池 {:?} 在 swap 路径中", pool));
+            }
+            let selection = self.flash_selector.selection_for_pool(pool, borrow_token, borrow_amount).await?;
+            let available = self.flash_pool_balance(pool, borrow_token).await?;
+            check_flash_liquidity(pool, borrow_token, available, borrow_amount)?;
+            info!(
+                "使用固定闪电贷池: {:?}, 费率: {}bps",
+                selection.pool_address,
+                selection.pool_fee as f64 / 100.0
+            );
+            return Ok(selection);
+        }
+
+        let ranked = self
+            .flash_selector
+            .rank_for_triangular(borrow_token, token_b, token_c, borrow_amount)
+            .await?;
+
+        self.choose_liquid_flash_pool(ranked, &swap_pools, borrow_token, borrow_amount).await
+    }
+
+    /// 在不与 swap 路径重叠的候选中，选出第一个余额足够借出 amount_in (含缓冲) 的池子
+    ///
+    /// 排名靠前的池子太浅时回退到下一个；没有一个够深时返回最优候选的 `InsufficientFlashLiquidity`
+    async fn choose_liquid_flash_pool(
+        &self,
+        mut ranked: Vec<FlashPoolSelection>,
+        swap_pools: &[Address],
+        borrow_token: Address,
+        amount_in: U256,
+    ) -> Result<FlashPoolSelection> {
+        let mut shallow: Option<ExecutionError> = None;
+        loop {
+            let candidate = match choose_disjoint_flash_pool(ranked.clone(), swap_pools, borrow_token) {
+                Ok(candidate) => candidate,
+                Err(e) => return Err(shallow.unwrap_or(e).into()),
+            };
+            let available = self.flash_pool_balance(candidate.pool_address, borrow_token).await?;
+            match check_flash_liquidity(candidate.pool_address, borrow_token, available, amount_in) {
+                Ok(()) => return Ok(candidate),
+                Err(e) => {
+                    warn!("{}，尝试下一个闪电贷池", e);
+                    shallow.get_or_insert(e);
+                    ranked.retain(|c| c.pool_address != candidate.pool_address && !swap_pools.contains(&c.pool_address));
+                }
+            }
+        }
+    }
+
+    /// 闪电贷池持有的借入代币余额
+    async fn flash_pool_balance(&self, pool: Address, token: Address) -> Result<U256> {
+        FlashTokenBalance::new(token, self.provider.clone())
+            .balance_of(pool)
+            .call()
+            .await
+            .map_err(|e| anyhow!("读取闪电贷池 {:?} 余额失败: {}", pool, e))
+    }
+}
+
+/// 检查闪电贷池余额是否足以借出 amount (另加 FLASH_LIQUIDITY_BUFFER_BPS 缓冲)
+pub fn check_flash_liquidity(
+    pool: Address,
+    token: Address,
+    available: U256,
+    amount: U256,
+) -> std::result::Result<(), ExecutionError> {
+    let required = amount + amount * U256::from(FLASH_LIQUIDITY_BUFFER_BPS) / U256::from(10_000u64);
+    if available < required {
+        return Err(ExecutionError::InsufficientFlashLiquidity { pool, token, available, required });
+    }
+    Ok(())
+}
+
+/// 从排好序的候选闪电贷池中选出第一个不在 swap 路径中的池子
+///
+/// 最优池子与路径重叠时依次回退到下一个，全部重叠返回 `NoDisjointFlashPool`
+pub fn choose_disjoint_flash_pool(
+    ranked: Vec<FlashPoolSelection>,
+    swap_pools: &[Address],
+    borrow_token: Address,
+) -> std::result::Result<FlashPoolSelection, ExecutionError> {
+    let total = ranked.len();
+
+    for (rank, candidate) in ranked.into_iter().enumerate() {
+        if swap_pools.contains(&candidate.pool_address) {
+            warn!(
+                "闪电贷池 {:?} (排名 #{}) 在 swap 路径中，尝试下一个",
+                candidate.pool_address,
+                rank + 1
+            );
+            continue;
+        }
+        if rank > 0 {
+            info!(
+                "闪电贷池回退到排名 #{}: {:?}, 费率: {}bps",
+                rank + 1,
+                candidate.pool_address,
+                candidate.pool_fee as f64 / 100.0
+            );
+        }
+        return Ok(candidate);
+    }
+
+    Err(ExecutionError::NoDisjointFlashPool {
+        borrow_token,
+        overlapping: total,
+    })
 }
 
 /// 验证套利路径是否为纯 V3 路径
@@ -195,8 +485,50 @@ pub fn extract_tokens(opportunity: &ArbitrageOpportunity) -> Vec<Address> {
 }
 
 /// 计算闪电贷费用 (wei)
-pub fn calculate_flash_fee(amount: U256, fee_bps: u32) -> U256 {
-    amount * U256::from(fee_bps) / U256::from(1_000_000)
+///
+/// 费率按费率表查询；`pool_fee` 为 Uniswap V3 借贷池的 fee tier，其他提供商忽略
+pub fn calculate_flash_fee(
+    table: &FlashFeeTable,
+    provider: FlashLoanProvider,
+    amount: U256,
+    pool_fee: u32,
+) -> U256 {
+    amount * U256::from(table.fee_rate(provider, pool_fee)) / U256::from(1_000_000)
+}
+
+/// 借入多少 flash_token 才能在转换后得到 amount_in 的起始代币
+///
+/// 按转换池现价 (sqrtPriceX96，token1/token0) 折算，再加上转换手续费和
+/// `FLASH_CONVERSION_BUFFER_BPS` 的价格影响余量；价格为 0 或结果溢出时返回 None
+pub fn flash_borrow_amount(
+    amount_in: U256,
+    token_a_is_token0: bool,
+    sqrt_price_x96: U256,
+    convert_fee: u32,
+) -> Option<U256> {
+    if sqrt_price_x96.is_zero() || convert_fee >= 1_000_000 {
+        return None;
+    }
+    let amount = U512::from(amount_in);
+    let sqrt_price = U512::from(sqrt_price_x96);
+    let spot = if token_a_is_token0 {
+        // 借入 token1: amount_in × 价格
+        (((amount * sqrt_price) >> 96) * sqrt_price) >> 96
+    } else {
+        // 借入 token0: amount_in ÷ 价格
+        (amount << 192) / (sqrt_price * sqrt_price)
+    };
+    let with_fee = spot * U512::from(1_000_000u64) / U512::from(1_000_000u64 - convert_fee as u64);
+    let borrow = with_fee * U512::from(10_000 + FLASH_CONVERSION_BUFFER_BPS) / U512::from(10_000u64);
+    U256::try_from(borrow).ok()
+}
+
+/// 借入代币往返转换的手续费 (按起始代币计)
+///
+/// 借入后换成起始代币、还款前换回，两次都经过同一个转换池；价格影响不在这里估算，
+/// 由合约按起始代币的实际余额变化校验 minProfit (不足时 revert)
+pub fn flash_conversion_cost(amount: U256, convert_fee: u32) -> U256 {
+    amount * U256::from(convert_fee) / U256::from(1_000_000) * 2
 }
 
 /// 检查套利是否仍然盈利 (考虑闪电贷费用)
@@ -217,15 +549,361 @@ pub fn is_still_profitable(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{sample_params, MockMiddleware};
+    use ethers::abi::Token;
+
+    const GET_POOL_SIG: &str = "getPool(address,address,uint24)";
 
     #[test]
     fn test_calculate_flash_fee() {
         // 1000 USDT * 0.05% = 0.5 USDT
         let amount = U256::from(1000) * U256::exp10(6); // 1000 USDT (6 decimals)
-        let fee = calculate_flash_fee(amount, 500); // 0.05%
+        let table = FlashFeeTable::default();
+        let fee = calculate_flash_fee(&table, FlashLoanProvider::UniswapV3, amount, 500); // 0.05%
         assert_eq!(fee, U256::from(500_000)); // 0.5 USDT
     }
 
+    #[test]
+    fn test_calculate_flash_fee_per_provider() {
+        let amount = U256::from(1000) * U256::exp10(6);
+        let default_table = FlashFeeTable::default();
+        let v4_table = FlashFeeTable { uniswap_v4: 100, ..FlashFeeTable::default() };
+
+        // (费率表, 提供商, 池子费率, 期望费用)
+        let cases = [
+            (default_table, FlashLoanProvider::UniswapV3, 100, 100_000),
+            (default_table, FlashLoanProvider::UniswapV3, 3000, 3_000_000),
+            (default_table, FlashLoanProvider::UniswapV4, 3000, 0),
+            (v4_table, FlashLoanProvider::UniswapV4, 3000, 100_000),
+            (default_table, FlashLoanProvider::AaveV3, 3000, 900_000),
+            (default_table, FlashLoanProvider::Balancer, 3000, 0),
+        ];
+        for (table, provider, pool_fee, expected) in cases {
+            assert_eq!(
+                calculate_flash_fee(&table, provider, amount, pool_fee),
+                U256::from(expected),
+                "{} pool_fee={}",
+                provider.name(),
+                pool_fee
+            );
+        }
+        assert_eq!(FlashLoanProvider::AaveV3.fee_rate(), 900);
+        assert_eq!(FlashLoanProvider::UniswapV3.fee_rate(), 3000);
+    }
+
+    fn flash_candidate(pool: u8, fee: u32) -> FlashPoolSelection {
+        FlashPoolSelection {
+            pool_address: Address::repeat_byte(pool),
+            pool_fee: fee,
+            is_token0: true,
+            liquidity: 1_000_000,
+            provider: dex::flashloan::FlashLoanProvider::UniswapV3,
+            estimated_fee: U256::zero(),
+        }
+    }
+
+    #[test]
+    fn test_choose_disjoint_flash_pool_falls_back() {
+        let borrow_token = Address::repeat_byte(0xaa);
+        // 最优 (最便宜) 的闪电贷池正好是第一跳 swap 池子
+        let ranked = vec![flash_candidate(1, 100), flash_candidate(9, 500)];
+        let swap_pools = vec![Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3)];
+
+        let selected = choose_disjoint_flash_pool(ranked, &swap_pools, borrow_token).unwrap();
+        assert_eq!(selected.pool_address, Address::repeat_byte(9));
+        assert_eq!(selected.pool_fee, 500);
+
+        // 所有候选都在路径中
+        let ranked = vec![flash_candidate(1, 100), flash_candidate(2, 500)];
+        let err = choose_disjoint_flash_pool(ranked, &swap_pools, borrow_token).unwrap_err();
+        assert!(matches!(
+            err,
+            ExecutionError::NoDisjointFlashPool { borrow_token: t, overlapping: 2 } if t == borrow_token
+        ));
+    }
+
+    fn script_flash_pool(mock: &MockMiddleware, token0: Address, token1: Address, liquidity: u128) {
+        mock.on_call("balanceOf(address)", &[Token::Uint(U256::exp10(24))]);
+        mock.on_call("token0()", &[Token::Address(token0)]);
+        mock.on_call("token1()", &[Token::Address(token1)]);
+        mock.on_call("fee()", &[Token::Uint(U256::from(100u64))]);
+        mock.on_call("liquidity()", &[Token::Uint(U256::from(liquidity))]);
+    }
+
+    async fn build_with_pinned(mock: Arc<MockMiddleware>, flash_pool: Address) -> Result<ArbitrageParams> {
+        let params = sample_params();
+        ArbitrageParamsBuilder::new(mock, 1)
+            .with_flash_pool(Some(flash_pool))
+            .build_manual(
+                params.token_a,
+                params.token_b,
+                params.token_c,
+                params.fee1,
+                params.fee2,
+                params.fee3,
+                params.amount_in,
+                params.swap_pools,
+                Decimal::from(10),
+                Decimal::ONE,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_pinned_flash_pool_skips_selection() {
+        let mock = Arc::new(MockMiddleware::new());
+        let params = sample_params();
+        let flash_pool = Address::repeat_byte(0xf9);
+        script_flash_pool(&mock, params.token_a, params.token_b, 10u128.pow(20));
+
+        let built = build_with_pinned(mock.clone(), flash_pool).await.unwrap();
+
+        // 没有任何 factory 查询，只读取了固定池子的信息
+        assert_eq!(mock.call_count(GET_POOL_SIG), 0);
+        assert_eq!(mock.call_count("liquidity()"), 1);
+        assert_eq!(built.flash_pool, flash_pool);
+        assert_eq!(built.flash_pool_fee, 100);
+        assert_eq!(built.estimated_flash_fee, params.amount_in / 10_000);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_flash_pool_is_validated() {
+        let params = sample_params();
+
+        // 与 swap 路径重叠
+        let mock = Arc::new(MockMiddleware::new());
+        script_flash_pool(&mock, params.token_a, params.token_b, 10u128.pow(20));
+        assert!(build_with_pinned(mock.clone(), params.swap_pools[0]).await.is_err());
+        assert_eq!(mock.call_count("liquidity()"), 0);
+
+        // 流动性不足
+        let mock = Arc::new(MockMiddleware::new());
+        script_flash_pool(&mock, params.token_a, params.token_b, 1);
+        assert!(build_with_pinned(mock.clone(), Address::repeat_byte(0xf9)).await.is_err());
+
+        // 不包含借入代币
+        let mock = Arc::new(MockMiddleware::new());
+        script_flash_pool(&mock, params.token_b, params.token_c, 10u128.pow(20));
+        assert!(build_with_pinned(mock.clone(), Address::repeat_byte(0xf9)).await.is_err());
+        assert_eq!(mock.call_count(GET_POOL_SIG), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shallow_flash_pool_falls_back_or_is_rejected() {
+        let params = sample_params();
+        let (borrow_token, amount_in) = (params.token_a, params.amount_in);
+        let (shallow, deep) = (Address::repeat_byte(0xf4), Address::repeat_byte(0xf5));
+        let mock = Arc::new(MockMiddleware::new());
+        // 浅池余额刚好等于借入金额，不够缓冲
+        mock.on_call_with_args("balanceOf(address)", &[Token::Address(shallow)], &[Token::Uint(amount_in)]);
+        mock.on_call_with_args("balanceOf(address)", &[Token::Address(deep)], &[Token::Uint(amount_in * 2)]);
+        let builder = ArbitrageParamsBuilder::new(mock, 1);
+
+        // 最优池子太浅，回退到排名第二的池子
+        let ranked = vec![flash_candidate(0xf4, 100), flash_candidate(0xf5, 500)];
+        let selected = builder
+            .choose_liquid_flash_pool(ranked, &params.swap_pools, borrow_token, amount_in)
+            .await
+            .unwrap();
+        assert_eq!(selected.pool_address, deep);
+
+        // 唯一候选太浅时类型化拒绝
+        let err = builder
+            .choose_liquid_flash_pool(vec![flash_candidate(0xf4, 100)], &params.swap_pools, borrow_token, amount_in)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::InsufficientFlashLiquidity { pool, available, .. })
+                if *pool == shallow && *available == amount_in
+        ));
+    }
+
+    #[test]
+    fn test_flash_borrow_amount_follows_pool_price() {
+        // sqrtPriceX96 = 2^97: 1 个 token0 值 4 个 token1
+        let sqrt_price = U256::one() << 97;
+        // 起始代币是 token0: 借 4 倍的 token1，另加 0.5% 余量
+        assert_eq!(flash_borrow_amount(U256::from(1000), true, sqrt_price, 0), Some(U256::from(4020)));
+        // 起始代币是 token1: 借 1/4 的 token0
+        assert_eq!(flash_borrow_amount(U256::from(1000), false, sqrt_price, 0), Some(U256::from(251)));
+        // 转换手续费 1%: 多借 1/0.99
+        assert_eq!(flash_borrow_amount(U256::from(99_000), true, U256::one() << 96, 10_000), Some(U256::from(100_500)));
+        assert_eq!(flash_borrow_amount(U256::from(1000), true, U256::zero(), 500), None);
+    }
+
+    #[tokio::test]
+    async fn test_borrow_usdc_for_weth_start_loop() {
+        let params = sample_params();
+        let weth = params.token_a;
+        let usdc = Address::repeat_byte(0xd1);
+        let (flash_pool, convert_pool) = (Address::repeat_byte(0xf9), Address::repeat_byte(0xe5));
+        let base_min_profit = U256::exp10(15);
+        let build = |mock: Arc<MockMiddleware>, flash_token: Address, pinned: Address| {
+            let params = params.clone();
+            async move {
+                ArbitrageParamsBuilder::new(mock, 1)
+                    .with_min_profit(base_min_profit)
+                    .with_flash_pool(Some(pinned))
+                    .with_flash_token(Some(flash_token), Some(500))
+                    .build_manual(
+                        weth,
+                        params.token_b,
+                        params.token_c,
+                        params.fee1,
+                        params.fee2,
+                        params.fee3,
+                        params.amount_in,
+                        params.swap_pools,
+                        Decimal::from(10),
+                        Decimal::ONE,
+                    )
+                    .await
+            }
+        };
+        let mock_with_convert_pool = |flash_pool_token: Address| {
+            let mock = Arc::new(MockMiddleware::new());
+            // 固定的闪电贷池持有 USDC
+            script_flash_pool(&mock, flash_pool_token, params.token_b, 10u128.pow(20));
+            mock.on_call(GET_POOL_SIG, &[Token::Address(Address::zero())]);
+            mock.on_call_with_args(
+                GET_POOL_SIG,
+                &[Token::Address(usdc), Token::Address(weth), Token::Uint(U256::from(500u64))],
+                &[Token::Address(convert_pool)],
+            );
+            // 转换池价格 1:1 (按最小单位)
+            mock.on_call("slot0()", &[Token::Uint(U256::one() << 96)]);
+            mock
+        };
+
+        let built = build(mock_with_convert_pool(usdc), usdc, flash_pool).await.unwrap();
+        assert_eq!(built.token_a, weth);
+        assert_eq!(built.amount_in, params.amount_in);
+        assert_eq!(built.flash_pool, flash_pool);
+        assert_eq!(built.flash_token, Some(usdc));
+        assert_eq!(built.flash_convert_fee, 500);
+        // WETH 地址较小是 token0: 按现价折算，加上 0.05% 手续费和价格影响余量
+        assert_eq!(built.flash_amount, flash_borrow_amount(params.amount_in, true, U256::one() << 96, 500).unwrap());
+        assert!(built.flash_amount > params.amount_in);
+        // 1 WETH 往返两次 0.05% 转换 = 0.001 WETH
+        assert_eq!(flash_conversion_cost(params.amount_in, 500), U256::exp10(15));
+        assert_eq!(built.min_profit, base_min_profit + U256::exp10(15));
+
+        // 转换池不能同时作为闪电贷池 (回调里还要经它 swap)
+        assert!(build(mock_with_convert_pool(usdc), usdc, convert_pool).await.is_err());
+
+        // 没有转换池的借入代币拒绝构建
+        let dai = Address::repeat_byte(0xda);
+        let err = build(mock_with_convert_pool(dai), dai, flash_pool).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::NoFlashConversionPool { flash_token, fee: Some(500), .. }) if *flash_token == dai
+        ));
+
+        // 借入代币就是起始代币时不转换
+        let built = build(mock_with_convert_pool(weth), weth, flash_pool).await.unwrap();
+        assert_eq!(built.flash_token, None);
+        assert_eq!(built.flash_amount, U256::zero());
+        assert_eq!(built.min_profit, base_min_profit);
+    }
+
+    #[tokio::test]
+    async fn test_profit_token_conversion_fee_is_validated() {
+        let params = sample_params();
+        let usdc = Address::repeat_byte(0xd1);
+        let build = |mock: Arc<MockMiddleware>, convert_fee: Option<u32>| {
+            let params = params.clone();
+            async move {
+                ArbitrageParamsBuilder::new(mock, 1)
+                    .with_flash_pool(Some(Address::repeat_byte(0xf9)))
+                    .with_profit_token(Some(usdc), convert_fee)
+                    .build_manual(
+                        params.token_a,
+                        params.token_b,
+                        params.token_c,
+                        params.fee1,
+                        params.fee2,
+                        params.fee3,
+                        params.amount_in,
+                        params.swap_pools,
+                        Decimal::from(10),
+                        Decimal::ONE,
+                    )
+                    .await
+            }
+        };
+        let mock_with_pools = |tiers: &[u32]| {
+            let mock = Arc::new(MockMiddleware::new());
+            script_flash_pool(&mock, params.token_a, params.token_b, 10u128.pow(20));
+            mock.on_call(GET_POOL_SIG, &[Token::Address(Address::zero())]);
+            for fee in tiers {
+                mock.on_call_with_args(
+                    GET_POOL_SIG,
+                    &[Token::Address(params.token_a), Token::Address(usdc), Token::Uint(U256::from(*fee))],
+                    &[Token::Address(Address::repeat_byte(0xe5))],
+                );
+            }
+            mock
+        };
+
+        // 未指定费率: 只有 0.05% 档位存在，自动选中
+        let built = build(mock_with_pools(&[500]), None).await.unwrap();
+        assert_eq!(built.profit_token, Some(usdc));
+        assert_eq!(built.profit_convert_fee, 500);
+
+        // 指定的 0.3% 档位没有池子
+        let err = build(mock_with_pools(&[500]), Some(3000)).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::NoProfitConversionPool { fee: Some(3000), .. })
+        ));
+
+        // 任何档位都没有池子
+        let err = build(mock_with_pools(&[]), None).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::NoProfitConversionPool { fee: None, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_preferred_flash_pool_for_borrow_token() {
+        let params = sample_params();
+        let (auto_pool, preferred_pool) = (Address::repeat_byte(0xe1), Address::repeat_byte(0xf7));
+        let build = |preferred: Option<Address>| {
+            let params = params.clone();
+            async move {
+                let mock = Arc::new(MockMiddleware::new());
+                script_flash_pool(&mock, params.token_a, params.token_b, 10u128.pow(20));
+                mock.on_call(GET_POOL_SIG, &[Token::Address(auto_pool)]);
+                let preferred_pools = preferred.map(|pool| (params.token_a, pool)).into_iter().collect();
+                ArbitrageParamsBuilder::new(mock, 1)
+                    .with_preferred_flash_pools(preferred_pools)
+                    .build_manual(
+                        params.token_a,
+                        params.token_b,
+                        params.token_c,
+                        params.fee1,
+                        params.fee2,
+                        params.fee3,
+                        params.amount_in,
+                        params.swap_pools,
+                        Decimal::from(10),
+                        Decimal::ONE,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // 借入代币配置了首选池子时总是选中它
+        assert_eq!(build(Some(preferred_pool)).await.flash_pool, preferred_pool);
+        // 未配置时自动选择
+        assert_eq!(build(None).await.flash_pool, auto_pool);
+        // 首选池子在 swap 路径中时回退到自动选择
+        assert_eq!(build(Some(params.swap_pools[0])).await.flash_pool, auto_pool);
+    }
+
     #[test]
     fn test_is_still_profitable() {
         let profit = U256::from(100);
@@ -12,7 +12,7 @@ use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 use chrono::{DateTime, Utc};
 use chrono_tz::Asia::Shanghai;
 
@@ -38,6 +38,26 @@ pub struct ExecutionSnapshot {
     pub actual: Option<ActualResult>,
     /// 错误信息 (如果失败)
     pub error: Option<ErrorSnapshot>,
+    /// 将要发送的合约调用 (失败时复制到错误快照，用于复现)
+    #[serde(default)]
+    pub call: Option<CallSnapshot>,
+}
+
+/// 合约调用快照: 可直接用 `cast call <to> <calldata> --from <from>` 复现
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSnapshot {
+    pub from: String,
+    /// 目标合约
+    pub to: String,
+    /// 0x 开头的十六进制 calldata
+    pub calldata: String,
+}
+
+impl CallSnapshot {
+    /// 复现命令 (calldata 含完整交易参数，只在 debug 级别输出)
+    pub fn cast_command(&self) -> String {
+        format!("cast call {} {} --from {}", self.to, self.calldata, self.from)
+    }
 }
 
 /// 套利参数快照
@@ -190,6 +210,9 @@ pub struct ErrorSnapshot {
     pub is_retryable: bool,
     /// 价格变化分析
     pub price_change_analysis: Option<PriceChangeAnalysis>,
+    /// 失败的合约调用 (目标合约 + calldata)
+    #[serde(default)]
+    pub call: Option<CallSnapshot>,
 }
 
 /// 价格变化分析
@@ -334,9 +357,19 @@ impl<M: Middleware + 'static> ExecutionDebugger<M> {
             },
             actual: None,
             error: None,
+            call: None,
         }
     }
 
+    /// 记录将要发送的合约调用，失败时随错误快照一起保存
+    pub fn record_call(&self, snapshot: &mut ExecutionSnapshot, from: Address, to: Address, calldata: &Bytes) {
+        snapshot.call = Some(CallSnapshot {
+            from: format!("{:?}", from),
+            to: format!("{:?}", to),
+            calldata: format!("0x{}", hex::encode(calldata)),
+        });
+    }
+
     /// 查询池子价格 (返回价格和 sqrtPriceX96)
     async fn get_pool_price_with_sqrt(&self, pool_address: Address, decimals_in: u8, decimals_out: u8) -> (Option<Decimal>, Option<String>) {
         abigen!(
@@ -429,6 +462,7 @@ impl<M: Middleware + 'static> ExecutionDebugger<M> {
                 .unwrap_or_default(),
             is_retryable: analysis.map(|a| a.is_retryable).unwrap_or(false),
             price_change_analysis,
+            call: snapshot.call.clone(),
         });
 
         // 打印详细的错误报告
@@ -533,6 +567,11 @@ impl<M: Middleware + 'static> ExecutionDebugger<M> {
         );
         error!("║    预期利润: ${}", snapshot.expected.profit_usd);
         error!("╠══════════════════════════════════════════════════════════════════════════════╣");
+        if let Some(ref call) = snapshot.call {
+            error!("║ 🎯 目标合约: {} (calldata 见 debug 日志)", call.to);
+            debug!("复现失败调用: {}", call.cast_command());
+            error!("╠══════════════════════════════════════════════════════════════════════════════╣");
+        }
         error!("║ 🏊 闪电贷池:");
         error!("║    地址: {}", snapshot.params.flash_pool);
         error!("║    费率: {} ({:.4}%)",
@@ -733,6 +772,25 @@ pub fn log_execution_start(params: &ArbitrageParams) {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_failed_execution_snapshot_keeps_calldata() {
+        let mock = Arc::new(crate::testing::MockMiddleware::new());
+        let debugger = ExecutionDebugger::new(mock, 1);
+        let mut snapshot = debugger.create_snapshot(&crate::testing::sample_params(), None).await;
+
+        let (from, to) = (Address::repeat_byte(0x11), Address::repeat_byte(0xcc));
+        debugger.record_call(&mut snapshot, from, to, &Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]));
+        debugger.record_error(&mut snapshot, "execution reverted: Insufficient profit", None, None);
+
+        let call = snapshot.error.unwrap().call.unwrap();
+        assert_eq!(call.to, format!("{:?}", to));
+        assert_eq!(call.calldata, "0xdeadbeef");
+        assert_eq!(
+            call.cast_command(),
+            format!("cast call {:?} 0xdeadbeef --from {:?}", to, from)
+        );
+    }
+
     #[test]
     fn test_format_wei() {
         let wei = U256::from(1_500_000_000_000_000_000u64);
@@ -1,27 +1,33 @@
 //! 套利执行器核心实现
 //!
-//! 支持三种交易发送模式：
+//! 支持以下交易发送模式：
 //! 1. 普通模式：通过公开 mempool 发送交易
 //! 2. Flashbots 模式：通过 Flashbots 私密发送，防止 MEV 攻击
 //! 3. Both 模式：同时通过 Flashbots 和公开 mempool 发送，提高成功率
+//! 4. 仅模拟模式：签名 Bundle 并提交中继模拟，不做任何真实发送
 
 use anyhow::Result;
+use ethers::abi::Detokenize;
 use ethers::prelude::*;
+use ethers::providers::call_raw::{spoof, RawCall};
 use ethers::types::{Address, H256, U256};
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::utils::keccak256;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{info, warn, debug, error};
 
 use crate::flash_arbitrage::{FlashArbitrageContract, ArbitrageContractParams};
-use crate::flashbots::{FlashbotsClient, FlashbotsConfig, FlashbotsSendResult, BundleBuilder};
-use crate::types::{ArbitrageParams, ExecutionResult, ExecutionError, GasStrategy};
+use crate::flashbots::{FlashbotsClient, FlashbotsConfig, FlashbotsSendResult, BundleBuilder, BundlePrivacy, BundleSimulation};
+use crate::types::{ArbitrageParams, ExecutionOutcome, ExecutionResult, ExecutionError, GasStrategy};
 use crate::debug_info::{ExecutionDebugger, TokenInfoSnapshot, TokenDetail, log_execution_start};
 use crate::revert_decoder::RevertDecoder;
+use crate::nonce::NonceAllocator;
+use crate::revert_log::{global_revert_log, RevertRecord};
 use services::SharedPriceService;
+use config_crate::SupportedChain;
 
 /// 交易发送模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,6 +38,15 @@ pub enum SendMode {
     Flashbots,
     /// Both 模式：同时通过 Flashbots 和公开 mempool 发送，提高成功率
     Both,
+    /// 仅模拟模式：签名 Bundle 后只提交中继 eth_callBundle 模拟，不发送
+    FlashbotsSimulateOnly,
+}
+
+impl SendMode {
+    /// 是否需要 Flashbots 客户端
+    fn uses_flashbots(self) -> bool {
+        matches!(self, Self::Flashbots | Self::Both | Self::FlashbotsSimulateOnly)
+    }
 }
 
 impl Default for SendMode {
@@ -47,20 +62,35 @@ pub struct ExecutorConfig {
     pub contract_address: Address,
     /// 链 ID
     pub chain_id: u64,
-    /// Gas 策略
-    pub gas_strategy: GasStrategy,
+    /// 发送交易的 Gas 策略
+    pub execution_gas: GasStrategy,
+    /// 模拟执行的 Gas 策略 (只决定模拟时的 gas limit，gas price 不参与 eth_call)
+    pub simulation_gas: GasStrategy,
     /// 交易确认超时 (秒)
     pub confirmation_timeout_secs: u64,
+    /// 发送交易超时 (秒)，RPC 无响应时放弃发送，避免长期占用并发名额
+    pub send_timeout_secs: u64,
     /// 需要的确认数
     pub confirmations: usize,
     /// 是否启用模拟执行
     pub simulate_before_execute: bool,
+    /// 模拟后到 mempool 发送前出现新区块时放弃发送
+    ///
+    /// 只在做过模拟、且交易会进入公开 mempool (Both 模式) 时检查；
+    /// Flashbots / 仅模拟模式的 Bundle 绑定目标区块，不受影响
+    pub abort_if_block_advanced: bool,
+    /// 每跳允许的最大滑点 (如 0.005 = 0.5%)，用于从报价计算每跳最小输出
+    pub max_slippage: Decimal,
     /// 私钥 (用于签名交易)
     pub private_key: Option<String>,
+    /// 已解密的签名钱包，优先于 private_key (keystore 加载时不会有明文私钥)
+    pub wallet: Option<LocalWallet>,
     /// 交易发送模式
     pub send_mode: SendMode,
     /// Flashbots 配置
     pub flashbots_config: FlashbotsConfig,
+    /// 模拟执行时的状态覆盖 (余额/storage)，用于模拟闪电贷到账后的状态，None 表示不覆盖
+    pub state_overrides: Option<spoof::State>,
 }
 
 impl Default for ExecutorConfig {
@@ -68,13 +98,19 @@ impl Default for ExecutorConfig {
         Self {
             contract_address: Address::zero(),
             chain_id: 1,
-            gas_strategy: GasStrategy::default(),
+            execution_gas: GasStrategy::default(),
+            simulation_gas: GasStrategy::simulation(),
             confirmation_timeout_secs: 120,
+            send_timeout_secs: 30,
             confirmations: 1,
             simulate_before_execute: true,
+            abort_if_block_advanced: true,
+            max_slippage: Decimal::new(5, 3), // 0.5%
             private_key: None,
+            wallet: None,
             send_mode: SendMode::Normal,
             flashbots_config: FlashbotsConfig::default(),
+            state_overrides: None,
         }
     }
 }
@@ -90,6 +126,8 @@ pub struct ArbitrageExecutor<M: Middleware + 'static> {
     flashbots_client: Option<FlashbotsClient<M>>,
     /// 执行调试器
     debugger: ExecutionDebugger<M>,
+    /// 钱包 nonce 分配器 (同一钱包的执行器共享)
+    nonces: Arc<NonceAllocator>,
 }
 
 impl<M: Middleware + 'static> ArbitrageExecutor<M> {
@@ -97,25 +135,27 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     pub fn new(config: ExecutorConfig, provider: Arc<M>) -> Result<Self> {
         let contract = FlashArbitrageContract::new(config.contract_address, provider.clone());
 
-        let wallet = if let Some(ref key) = config.private_key {
-            Some(key.parse::<LocalWallet>()?.with_chain_id(config.chain_id))
-        } else {
-            None
+        let wallet = match (&config.wallet, &config.private_key) {
+            (Some(wallet), _) => Some(wallet.clone().with_chain_id(config.chain_id)),
+            (None, Some(key)) => Some(key.parse::<LocalWallet>()?.with_chain_id(config.chain_id)),
+            (None, None) => None,
         };
 
-        // 如果启用 Flashbots 或 Both 模式，初始化客户端
-        let flashbots_client = if config.send_mode == SendMode::Flashbots || config.send_mode == SendMode::Both {
-            if let Some(ref key) = config.private_key {
+        // 如果启用 Flashbots、Both 或仅模拟模式，初始化客户端
+        let flashbots_client = if config.send_mode.uses_flashbots() {
+            if let Some(ref tx_signer) = wallet {
                 let mut fb_config = config.flashbots_config.clone();
                 fb_config.enabled = true;
                 fb_config.chain_id = config.chain_id;
+                // 提交 Bundle 与提交 mempool 交易使用同一个超时
+                fb_config.submit_timeout_secs = config.send_timeout_secs;
                 // 如果未配置 relay_url，则自动选择对应链的中继 URL
                 if fb_config.relay_url.is_empty() {
                     fb_config.relay_url = FlashbotsConfig::relay_url_for_chain(config.chain_id).to_string();
                 }
                 info!("📡 Flashbots relay URL: {}", fb_config.relay_url);
 
-                match FlashbotsClient::new(fb_config, provider.clone(), key) {
+                match FlashbotsClient::from_wallet(fb_config, provider.clone(), tx_signer.clone()) {
                     Ok(client) => {
                         info!("Flashbots 客户端已初始化，链 ID: {}, 模式: {:?}", config.chain_id, config.send_mode);
                         Some(client)
@@ -126,7 +166,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
                     }
                 }
             } else {
-                warn!("Flashbots/Both 模式需要私钥，将使用普通模式");
+                warn!("Flashbots/Both 模式需要签名钱包，将使用普通模式");
                 None
             }
         } else {
@@ -144,6 +184,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             price_service: None,
             flashbots_client,
             debugger,
+            nonces: Arc::new(NonceAllocator::new()),
         })
     }
 
@@ -153,8 +194,14 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         self
     }
 
+    /// 使用共享的 nonce 分配器 (同一钱包并发执行时共用一个，避免发出相同 nonce)
+    pub fn with_nonce_allocator(mut self, nonces: Arc<NonceAllocator>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
     /// 执行套利
-    pub async fn execute(&self, params: ArbitrageParams) -> Result<ExecutionResult, ExecutionError> {
+    pub async fn execute(&self, params: ArbitrageParams) -> Result<ExecutionOutcome, ExecutionError> {
         // 打印执行开始信息
         log_execution_start(&params);
 
@@ -188,6 +235,16 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         // 创建执行快照
         let mut snapshot = self.debugger.create_snapshot(&params, Some(token_info)).await;
 
+        // 每跳最小输出: 报价扣除滑点，任何一跳滑点过大时合约提前 revert
+        let min_amounts_out = hop_min_amounts_out(params.hop_quotes, self.config.max_slippage);
+        if params.hop_quotes.is_some() {
+            info!(
+                target: "arbitrage_execution",
+                "每跳最小输出 (滑点 {}): {} / {} / {}",
+                self.config.max_slippage, min_amounts_out[0], min_amounts_out[1], min_amounts_out[2]
+            );
+        }
+
         // 构建合约调用参数
         let contract_params = ArbitrageContractParams {
             flash_pool: params.flash_pool,
@@ -201,16 +258,40 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             min_profit: params.min_profit,
             profit_token: params.profit_token.unwrap_or(Address::zero()),
             profit_convert_fee: params.profit_convert_fee,
+            min_amounts_out,
+            flash_token: params.flash_token.unwrap_or(Address::zero()),
+            flash_convert_fee: params.flash_convert_fee,
+            flash_amount: params.flash_amount,
         };
 
-        // 模拟执行 (仅 Flashbots 和 Both 模式需要)
+        // 记录 calldata，失败时随错误快照保存以便复现
+        if let (Some(wallet), Some(calldata)) = (
+            self.wallet.as_ref(),
+            self.contract.execute_arbitrage(contract_params.clone().into_tuple()).calldata(),
+        ) {
+            self.debugger.record_call(&mut snapshot, wallet.address(), self.config.contract_address, &calldata);
+        }
+
+        // 模拟执行 (仅 Flashbots、Both 和仅模拟模式需要)
         // - Normal 模式：不需要模拟，直接发送到 mempool
-        // - Flashbots 模式：必须模拟成功才能发送
+        // - Flashbots / 仅模拟模式：必须模拟成功才能继续
         // - Both 模式：模拟失败时仍可发送 mempool，只跳过 Flashbots
-        let simulation_passed = if self.config.simulate_before_execute
-            && (self.config.send_mode == SendMode::Flashbots || self.config.send_mode == SendMode::Both)
-        {
-            match self.simulate_execution(&contract_params).await {
+        let mut simulated_block: Option<u64> = None;
+        // 模拟阶段 estimate_gas 的原始结果，发送阶段按执行策略复用
+        let mut gas_estimate: Option<U256> = None;
+        let flashbots_only = matches!(self.config.send_mode, SendMode::Flashbots | SendMode::FlashbotsSimulateOnly);
+        let simulation_passed = if self.config.simulate_before_execute && self.config.send_mode.uses_flashbots() {
+            // 记录模拟时的区块号，发送前再比对
+            simulated_block = self.current_block_number().await;
+
+            let simulation = match self.simulation_gas_limit(&contract_params).await {
+                Ok((gas_limit, estimate)) => {
+                    gas_estimate = estimate;
+                    self.simulate_execution(&contract_params, gas_limit).await
+                }
+                Err(e) => Err(e),
+            };
+            match simulation {
                 Ok(estimated_profit) => {
                     info!(target: "arbitrage_execution", "模拟执行成功, 预估利润: {}", estimated_profit);
                     if estimated_profit < params.min_profit {
@@ -221,7 +302,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
                         self.debugger.record_error(&mut snapshot, &format!("{:?}", err), None, None);
 
                         // Flashbots 模式下模拟失败直接返回错误
-                        if self.config.send_mode == SendMode::Flashbots {
+                        if flashbots_only {
                             return Err(err);
                         }
                         // Both 模式下继续执行，但标记模拟失败
@@ -238,11 +319,14 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
 
                     // 额外打印解码后的错误
                     let decoded = RevertDecoder::decode_from_error_string(&error_str);
+                    global_revert_log().record(RevertRecord::from_decoded(
+                        self.config.chain_id, "simulation", None, &decoded,
+                    ));
                     warn!(target: "arbitrage_execution", "模拟执行失败 - 详细错误:");
                     warn!(target: "arbitrage_execution", "{}", decoded);
 
                     // Flashbots 模式下模拟失败直接返回错误
-                    if self.config.send_mode == SendMode::Flashbots {
+                    if flashbots_only {
                         return Err(ExecutionError::ContractError(format!("Simulation failed: {}", decoded.message)));
                     }
                     // Both 模式下继续执行，但标记模拟失败
@@ -255,8 +339,31 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             true
         };
 
-        // 执行实际交易
-        let tx_hash = match self.send_transaction(&contract_params, simulation_passed).await {
+        // 区块一致性检查: 模拟基于的状态已过期则放弃 mempool 发送 (mempool 交易无法绑定区块)
+        if self.config.abort_if_block_advanced && !flashbots_only {
+            if let Some(current_block) = self.current_block_number().await {
+                if let Err(e) = check_block_consistency(simulated_block, current_block) {
+                    warn!(target: "arbitrage_execution", "⏭️ block advanced after simulation: {}", e);
+                    self.debugger.record_error(&mut snapshot, &format!("{:?}", e), None, None);
+                    return Err(e);
+                }
+            }
+        }
+
+        // 仅模拟模式: 返回中继模拟结果，不进入发送流程
+        if self.config.send_mode == SendMode::FlashbotsSimulateOnly {
+            return match self.simulate_via_flashbots(&contract_params, gas_estimate).await {
+                Ok(simulation) => Ok(ExecutionOutcome::SimulatedOnly(simulation)),
+                Err(e) => {
+                    self.debugger.record_error(&mut snapshot, &format!("{:?}", e), None, None);
+                    Err(e)
+                }
+            };
+        }
+
+        // 执行实际交易 (每次提交受 send_timeout_secs 限制，Flashbots 等待打包不计入)
+        let send_result = self.send_transaction(&contract_params, simulation_passed, gas_estimate, params.bundle_privacy).await;
+        let tx_hash = match send_result {
             Ok(hash) => {
                 info!("交易已发送: {:?}", hash);
                 hash
@@ -279,7 +386,18 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         };
 
         // 解析结果
-        self.parse_execution_result(tx_hash, receipt, &params).await
+        self.parse_execution_result(tx_hash, receipt, &params).await.map(ExecutionOutcome::Executed)
+    }
+
+    /// 获取当前区块号，失败时返回 None (不阻塞执行)
+    async fn current_block_number(&self) -> Option<u64> {
+        match self.provider.get_block_number().await {
+            Ok(n) => Some(n.as_u64()),
+            Err(e) => {
+                debug!("获取区块号失败: {:?}", e);
+                None
+            }
+        }
     }
 
     /// 构建代币信息用于调试
@@ -391,7 +509,9 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     /// - `.send()`: eth_sendTransaction，真正发送交易，消耗 gas
     ///
     /// 重要：必须设置 from 参数为 owner 地址，否则 onlyOwner 等权限检查会失败
-    async fn simulate_execution(&self, params: &ArbitrageContractParams) -> Result<U256, ExecutionError> {
+    ///
+    /// gas_limit 为 None 时不指定，由节点按区块上限模拟
+    async fn simulate_execution(&self, params: &ArbitrageContractParams, gas_limit: Option<U256>) -> Result<U256, ExecutionError> {
         debug!("模拟执行套利 (eth_call)...");
 
         // 获取发送者地址（必须是合约 owner）
@@ -402,8 +522,25 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
 
         debug!("模拟执行 from 地址: {:?}", from_address);
 
-        let call = self.contract.execute_arbitrage(params.clone().into_tuple())
+        let mut call = self.contract.execute_arbitrage(params.clone().into_tuple())
             .from(from_address);  // 关键：设置 from 为 owner 地址
+        if let Some(gas_limit) = gas_limit {
+            call = call.gas(gas_limit);
+        }
+
+        // 配置了状态覆盖时走带 override 参数的 eth_call，再按函数 ABI 解码返回值
+        if let Some(overrides) = &self.config.state_overrides {
+            debug!("模拟执行使用状态覆盖");
+            let raw = self.provider.provider()
+                .call_raw(&call.tx)
+                .state(overrides)
+                .await
+                .map_err(|e| ExecutionError::ContractError(format!("{:?}", e)))?;
+            let tokens = call.function.decode_output(raw.as_ref())
+                .map_err(|e| ExecutionError::ContractError(format!("解码模拟结果失败: {:?}", e)))?;
+            return U256::from_tokens(tokens)
+                .map_err(|e| ExecutionError::ContractError(format!("解码模拟结果失败: {:?}", e)));
+        }
 
         // .call() 是静态调用，不会上链，只是模拟执行获取返回值
         match call.call().await {
@@ -412,6 +549,52 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         }
     }
 
+    /// 模拟阶段的 gas limit (按 simulation_gas 策略)，同时返回原始估算值供发送阶段复用
+    ///
+    /// 配置了状态覆盖且没有固定 gas limit 时不估算: estimate_gas 不带覆盖参数，会和不带覆盖的 eth_call 一样误判失败
+    async fn simulation_gas_limit(
+        &self,
+        params: &ArbitrageContractParams,
+    ) -> Result<(Option<U256>, Option<U256>), ExecutionError> {
+        let strategy = &self.config.simulation_gas;
+        if strategy.fixed_gas_limit.is_none() && self.config.state_overrides.is_some() {
+            return Ok((None, None));
+        }
+        let from_address = self.wallet.as_ref()
+            .map(|w| w.address())
+            .ok_or_else(|| ExecutionError::WalletError("模拟执行需要配置钱包以设置 from 地址".to_string()))?;
+        let call = self.contract.execute_arbitrage(params.clone().into_tuple())
+            .from(from_address);
+        let (gas_limit, estimate) = self.resolve_gas_limit(&call, strategy, None).await?;
+        Ok((Some(gas_limit), estimate))
+    }
+
+    /// 按 Gas 策略确定 gas limit: 有固定值时直接使用，否则为估算值乘以倍数
+    ///
+    /// estimate 为之前已经拿到的原始估算值 (如模拟阶段)，有则不再调用 estimate_gas；
+    /// 返回 (gas limit, 原始估算值)
+    async fn resolve_gas_limit(
+        &self,
+        call: &ContractCall<M, U256>,
+        strategy: &GasStrategy,
+        estimate: Option<U256>,
+    ) -> Result<(U256, Option<U256>), ExecutionError> {
+        if let Some(fixed_limit) = strategy.fixed_gas_limit {
+            debug!("使用固定 Gas Limit: {} (跳过估算)", fixed_limit);
+            return Ok((U256::from(fixed_limit), estimate));
+        }
+
+        // estimate_gas 底层也是 eth_call，需要正确的 from
+        let estimate = match estimate {
+            Some(estimate) => estimate,
+            None => call.estimate_gas().await
+                .map_err(|e| ExecutionError::GasEstimationFailed(format!("{:?}", e)))?,
+        };
+        let limit = U256::from((estimate.as_u64() as f64 * strategy.gas_limit_multiplier) as u64);
+        debug!("Gas 估算: {} | Gas 限制: {} (倍数 {})", estimate, limit, strategy.gas_limit_multiplier);
+        Ok((limit, Some(estimate)))
+    }
+
     /// 发送交易
     ///
     /// 根据配置选择发送模式：
@@ -420,22 +603,33 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     /// - Both: 同时通过 Flashbots 和公开 mempool 发送
     ///
     /// simulation_passed: 模拟是否通过，用于 Both 模式决定是否发送 Flashbots
-    async fn send_transaction(&self, params: &ArbitrageContractParams, simulation_passed: bool) -> Result<H256, ExecutionError> {
+    /// gas_estimate: 模拟阶段已拿到的 gas 估算值 (None 时发送前重新估算)
+    /// privacy: 本次 Bundle 的隐私级别 (None 时使用 Flashbots 配置的默认值)
+    async fn send_transaction(
+        &self,
+        params: &ArbitrageContractParams,
+        simulation_passed: bool,
+        gas_estimate: Option<U256>,
+        privacy: Option<BundlePrivacy>,
+    ) -> Result<H256, ExecutionError> {
         // 根据发送模式选择不同的发送方式
         match self.config.send_mode {
             SendMode::Flashbots => {
                 if self.flashbots_client.is_some() {
-                    self.send_via_flashbots(params).await
+                    self.send_via_flashbots(params, gas_estimate, privacy).await
                 } else {
                     warn!("Flashbots 客户端未初始化，回退到普通模式");
-                    self.send_via_mempool(params).await
+                    self.send_via_mempool(params, gas_estimate).await
                 }
             }
             SendMode::Both => {
-                self.send_via_both(params, simulation_passed).await
+                self.send_via_both(params, simulation_passed, gas_estimate, privacy).await
             }
             SendMode::Normal => {
-                self.send_via_mempool(params).await
+                self.send_via_mempool(params, gas_estimate).await
+            }
+            SendMode::FlashbotsSimulateOnly => {
+                Err(ExecutionError::FlashbotsError("仅模拟模式不发送交易".to_string()))
             }
         }
     }
@@ -448,28 +642,33 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     /// simulation_passed: 模拟是否通过
     /// - true: 并行发送到两个通道
     /// - false: 仅发送到 Mempool，跳过 Flashbots
-    async fn send_via_both(&self, params: &ArbitrageContractParams, simulation_passed: bool) -> Result<H256, ExecutionError> {
+    async fn send_via_both(
+        &self,
+        params: &ArbitrageContractParams,
+        simulation_passed: bool,
+        gas_estimate: Option<U256>,
+        privacy: Option<BundlePrivacy>,
+    ) -> Result<H256, ExecutionError> {
         let wallet = self.wallet.as_ref()
             .ok_or_else(|| ExecutionError::WalletError("No wallet configured".to_string()))?;
         let from_address = wallet.address();
 
-        // 获取当前 nonce
-        let base_nonce = self.provider.get_transaction_count(from_address, None).await
-            .map_err(|e| ExecutionError::NonceError(format!("{:?}", e)))?;
-
         // 如果模拟失败或 Flashbots 客户端未初始化，仅使用 Mempool 发送
         if !simulation_passed {
-            info!(target: "arbitrage_execution", "🚀 Both 模式：模拟失败，仅使用 Mempool 发送 (nonce={})", base_nonce);
-            return self.send_via_mempool_with_nonce(params, base_nonce).await;
+            info!(target: "arbitrage_execution", "🚀 Both 模式：模拟失败，仅使用 Mempool 发送");
+            return self.send_via_mempool(params, gas_estimate).await;
         }
 
         if self.flashbots_client.is_none() {
             warn!(target: "arbitrage_execution", "⚠️ Flashbots 客户端未初始化，仅使用 Mempool 发送");
-            return self.send_via_mempool_with_nonce(params, base_nonce).await;
+            return self.send_via_mempool(params, gas_estimate).await;
         }
 
         info!(target: "arbitrage_execution", "🚀 Both 模式：并行发送到 Mempool 和 Flashbots（两边都执行）");
 
+        // 一次分配两个连续 nonce，其他并发执行不会拿到其中任何一个
+        let base_nonce = self.nonces.allocate(&*self.provider, from_address, 2).await?;
+
         let mempool_nonce = base_nonce;
         let flashbots_nonce = base_nonce + 1;
 
@@ -481,12 +680,20 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         info!(target: "arbitrage_execution", "📤 并行发送交易到 Mempool 和 Flashbots...");
 
         // 并行发送
-        let mempool_future = self.send_via_mempool_with_nonce(params, mempool_nonce);
-        let flashbots_future = self.send_via_flashbots_with_nonce(params, flashbots_nonce);
+        let mempool_future = self.send_via_mempool_with_nonce(params, mempool_nonce, gas_estimate);
+        let flashbots_future = self.send_via_flashbots_with_nonce(params, flashbots_nonce, gas_estimate, privacy);
 
         let (mempool_result, flashbots_result) = tokio::join!(mempool_future, flashbots_future);
+        // 只归还没用掉的 nonce: mempool 已广播时 nonce N 已被占用，之后的交易不能再拿到它
+        match (mempool_result.is_ok(), flashbots_result.is_ok()) {
+            (true, false) => self.nonces.release(flashbots_nonce, 1).await,
+            (false, true) => self.nonces.release(mempool_nonce, 1).await,
+            (false, false) => self.nonces.release(base_nonce, 2).await,
+            (true, true) => {}
+        }
 
         // 处理 Mempool 结果
+        let mut mempool_error = None;
         let mempool_hash = match mempool_result {
             Ok(hash) => {
                 info!(target: "arbitrage_execution", "✅ Mempool 广播成功: {:?}", hash);
@@ -494,6 +701,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             }
             Err(e) => {
                 error!(target: "arbitrage_execution", "❌ Mempool 发送失败: {:?}", e);
+                mempool_error = Some(e);
                 None
             }
         };
@@ -527,14 +735,21 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
                 info!(target: "arbitrage_execution", "📦 仅 Flashbots 成功，返回: {:?}", f_hash);
                 Ok(f_hash)
             }
-            (None, None) => {
-                Err(ExecutionError::ContractError("Both 模式：两个通道都发送失败".to_string()))
-            }
+            (None, None) => match mempool_error {
+                // gas 费超限时两个通道都不会发送，保留具体原因
+                Some(e @ ExecutionError::GasCostTooHigh { .. }) => Err(e),
+                _ => Err(ExecutionError::ContractError("Both 模式：两个通道都发送失败".to_string())),
+            },
         }
     }
 
     /// 通过公开 mempool 发送交易（指定 nonce）
-    async fn send_via_mempool_with_nonce(&self, params: &ArbitrageContractParams, nonce: U256) -> Result<H256, ExecutionError> {
+    async fn send_via_mempool_with_nonce(
+        &self,
+        params: &ArbitrageContractParams,
+        nonce: U256,
+        gas_estimate: Option<U256>,
+    ) -> Result<H256, ExecutionError> {
         let wallet = self.wallet.as_ref()
             .ok_or_else(|| ExecutionError::WalletError("No wallet configured".to_string()))?;
 
@@ -542,44 +757,86 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         debug!("发送交易 from 地址: {:?}, nonce: {}", from_address, nonce);
 
         // 构建交易调用
-        let call = self.contract.execute_arbitrage(params.clone().into_tuple())
-            .from(from_address);
+        let call = self.contract.execute_arbitrage(params.clone().into_tuple()).from(from_address);
 
         // 获取 gas limit
-        let gas_limit = if let Some(fixed_limit) = self.config.gas_strategy.fixed_gas_limit {
-            U256::from(fixed_limit)
-        } else {
-            let gas_estimate = call.estimate_gas().await
-                .map_err(|e| ExecutionError::GasEstimationFailed(format!("{:?}", e)))?;
-            U256::from((gas_estimate.as_u64() as f64 * self.config.gas_strategy.gas_limit_multiplier) as u64)
-        };
+        let (gas_limit, _) = self.resolve_gas_limit(&call, &self.config.execution_gas, gas_estimate).await?;
 
         // 获取 gas price
         let gas_price = self.get_gas_price().await?;
+        self.check_gas_cost(gas_limit, gas_price).await?;
 
         // 检查 gas price 上限
-        let max_gas_price = U256::from((self.config.gas_strategy.max_gas_price_gwei * 1_000_000_000.0) as u128);
+        let max_gas_price = U256::from((self.config.execution_gas.max_gas_price_gwei * 1_000_000_000.0) as u128);
         if gas_price > max_gas_price {
-            return Err(ExecutionError::GasEstimationFailed(
-                format!("Gas price {} exceeds max {}", gas_price, max_gas_price)
-            ));
+            return Err(ExecutionError::GasPriceTooHigh { gas_price, max_gas_price });
         }
 
         // 构建交易，指定 nonce
-        let tx = call
-            .gas(gas_limit)
-            .gas_price(gas_price)
-            .nonce(nonce);
+        let tx = self.build_transaction(from_address, call.calldata().unwrap_or_default(), gas_limit, gas_price, nonce);
 
         // 发送交易
-        let pending_tx = tx.send().await
-            .map_err(|e| ExecutionError::ContractError(format!("{:?}", e)))?;
+        self.submit_transaction(tx).await
+    }
 
-        Ok(pending_tx.tx_hash())
+    /// 向节点提交交易，节点无响应时超时放弃 (只限制提交本身，不包括等待确认)
+    async fn submit_transaction(&self, tx: TypedTransaction) -> Result<H256, ExecutionError> {
+        let send_timeout = Duration::from_secs(self.config.send_timeout_secs);
+        match tokio::time::timeout(send_timeout, self.provider.send_transaction(tx, None)).await {
+            Ok(Ok(pending_tx)) => Ok(pending_tx.tx_hash()),
+            Ok(Err(e)) => Err(ExecutionError::ContractError(format!("{:?}", e))),
+            Err(_) => {
+                warn!(target: "arbitrage_execution", "⏱️ 发送交易超时 ({}s)，放弃本次执行", self.config.send_timeout_secs);
+                Err(ExecutionError::SendTimeout(self.config.send_timeout_secs))
+            }
+        }
+    }
+
+    /// 是否发送 EIP-1559 (type-2) 交易
+    ///
+    /// 已知的 legacy gas 链 (BSC) 不论配置都发送 legacy 交易
+    fn uses_eip1559(&self) -> bool {
+        self.config.execution_gas.use_eip1559 && !is_legacy_gas_chain(self.config.chain_id)
+    }
+
+    /// 按链的 gas 模型构建套利交易
+    ///
+    /// EIP-1559 链: max fee = gas_price，优先费取配置值 (不超过 max fee)；
+    /// legacy 链: gas_price
+    fn build_transaction(
+        &self,
+        from: Address,
+        data: Bytes,
+        gas_limit: U256,
+        gas_price: U256,
+        nonce: U256,
+    ) -> TypedTransaction {
+        let mut tx: TypedTransaction = if self.uses_eip1559() {
+            let priority_fee = U256::from((self.config.execution_gas.priority_fee_gwei * 1_000_000_000.0) as u128);
+            Eip1559TransactionRequest::new()
+                .max_fee_per_gas(gas_price)
+                .max_priority_fee_per_gas(priority_fee.min(gas_price))
+                .into()
+        } else {
+            TransactionRequest::new().gas_price(gas_price).into()
+        };
+        tx.set_to(self.config.contract_address);
+        tx.set_from(from);
+        tx.set_data(data);
+        tx.set_gas(gas_limit);
+        tx.set_chain_id(self.config.chain_id);
+        tx.set_nonce(nonce);
+        tx
     }
 
     /// 通过 Flashbots 发送交易（指定 nonce）
-    async fn send_via_flashbots_with_nonce(&self, params: &ArbitrageContractParams, nonce: U256) -> Result<H256, ExecutionError> {
+    async fn send_via_flashbots_with_nonce(
+        &self,
+        params: &ArbitrageContractParams,
+        nonce: U256,
+        gas_estimate: Option<U256>,
+        privacy: Option<BundlePrivacy>,
+    ) -> Result<H256, ExecutionError> {
         let flashbots = self.flashbots_client.as_ref()
             .ok_or_else(|| ExecutionError::FlashbotsError("Flashbots client not initialized".to_string()))?;
 
@@ -594,35 +851,21 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             .from(from_address);
 
         // 获取 gas limit
-        let gas_limit = if let Some(fixed_limit) = self.config.gas_strategy.fixed_gas_limit {
-            U256::from(fixed_limit)
-        } else {
-            let gas_estimate = call.estimate_gas().await
-                .map_err(|e| ExecutionError::GasEstimationFailed(format!("{:?}", e)))?;
-            U256::from((gas_estimate.as_u64() as f64 * self.config.gas_strategy.gas_limit_multiplier) as u64)
-        };
+        let (gas_limit, _) = self.resolve_gas_limit(&call, &self.config.execution_gas, gas_estimate).await?;
 
         // 获取 gas price
         let gas_price = self.get_gas_price().await?;
+        self.check_gas_cost(gas_limit, gas_price).await?;
 
         // 构建完整交易
-        let tx_request = TransactionRequest::new()
-            .to(self.config.contract_address)
-            .from(from_address)
-            .data(call.calldata().unwrap_or_default())
-            .gas(gas_limit)
-            .gas_price(gas_price)
-            .nonce(nonce)
-            .chain_id(self.config.chain_id);
+        let typed_tx = self.build_transaction(from_address, call.calldata().unwrap_or_default(), gas_limit, gas_price, nonce);
 
         // 签名交易
-        let typed_tx: TypedTransaction = tx_request.into();
         let signed_tx = flashbots.sign_transaction(&typed_tx).await
             .map_err(|e| ExecutionError::FlashbotsError(format!("Failed to sign transaction: {:?}", e)))?;
 
         // 构建 Bundle 并发送
-        let bundle = BundleBuilder::new()
-            .push_transaction(signed_tx);
+        let bundle = with_privacy(BundleBuilder::new().push_transaction(signed_tx), privacy);
 
         let result = flashbots.send_bundle(bundle).await;
 
@@ -645,7 +888,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     }
 
     /// 通过公开 mempool 发送交易（普通模式）
-    async fn send_via_mempool(&self, params: &ArbitrageContractParams) -> Result<H256, ExecutionError> {
+    async fn send_via_mempool(&self, params: &ArbitrageContractParams, gas_estimate: Option<U256>) -> Result<H256, ExecutionError> {
         let wallet = self.wallet.as_ref()
             .ok_or_else(|| ExecutionError::WalletError("No wallet configured".to_string()))?;
 
@@ -653,47 +896,31 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         debug!("发送交易 from 地址: {:?}", from_address);
 
         // 构建交易，必须设置 from 地址
-        let call = self.contract.execute_arbitrage(params.clone().into_tuple())
-            .from(from_address);  // 关键：设置 from 为 owner 地址
+        let call = self.contract.execute_arbitrage(params.clone().into_tuple()).from(from_address); // 关键：设置 from 为 owner 地址
 
         // 获取 gas limit (固定值或动态估算)
-        let gas_limit = if let Some(fixed_limit) = self.config.gas_strategy.fixed_gas_limit {
-            // 使用固定 gas limit，跳过估算
-            debug!("使用固定 Gas Limit: {} (跳过估算)", fixed_limit);
-            U256::from(fixed_limit)
-        } else {
-            // 动态估算 gas (estimate_gas 底层也是 eth_call，需要正确的 from)
-            let gas_estimate = call.estimate_gas().await
-                .map_err(|e| ExecutionError::GasEstimationFailed(format!("{:?}", e)))?;
-
-            let limit = U256::from(
-                (gas_estimate.as_u64() as f64 * self.config.gas_strategy.gas_limit_multiplier) as u64
-            );
-            debug!("Gas 估算: {} | Gas 限制: {}", gas_estimate, limit);
-            limit
-        };
+        let (gas_limit, _) = self.resolve_gas_limit(&call, &self.config.execution_gas, gas_estimate).await?;
 
         // 获取 gas price
         let gas_price = self.get_gas_price().await?;
+        self.check_gas_cost(gas_limit, gas_price).await?;
 
         // 检查 gas price 是否超过最大限制 (支持小数 Gwei)
-        let max_gas_price = U256::from((self.config.gas_strategy.max_gas_price_gwei * 1_000_000_000.0) as u128);
+        let max_gas_price = U256::from((self.config.execution_gas.max_gas_price_gwei * 1_000_000_000.0) as u128);
         if gas_price > max_gas_price {
-            return Err(ExecutionError::GasEstimationFailed(
-                format!("Gas price {} exceeds max {}", gas_price, max_gas_price)
-            ));
+            return Err(ExecutionError::GasPriceTooHigh { gas_price, max_gas_price });
         }
 
-        // 构建并签名交易
-        let tx = call
-            .gas(gas_limit)
-            .gas_price(gas_price);
+        // 构建并签名交易 (nonce 由共享分配器分配，并发执行不会重复)
+        let nonce = self.nonces.allocate(&*self.provider, from_address, 1).await?;
+        let tx = self.build_transaction(from_address, call.calldata().unwrap_or_default(), gas_limit, gas_price, nonce);
 
         // 发送交易
-        let pending_tx = tx.send().await
-            .map_err(|e| ExecutionError::ContractError(format!("{:?}", e)))?;
-
-        Ok(pending_tx.tx_hash())
+        let result = self.submit_transaction(tx).await;
+        if result.is_err() {
+            self.nonces.release(nonce, 1).await;
+        }
+        result
     }
 
     /// 通过 Flashbots 私密发送交易
@@ -703,83 +930,120 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     /// 2. 包装成 Bundle
     /// 3. 发送到 Flashbots 中继
     /// 4. 等待打包确认
-    async fn send_via_flashbots(&self, params: &ArbitrageContractParams) -> Result<H256, ExecutionError> {
+    async fn send_via_flashbots(
+        &self,
+        params: &ArbitrageContractParams,
+        gas_estimate: Option<U256>,
+        privacy: Option<BundlePrivacy>,
+    ) -> Result<H256, ExecutionError> {
+        let flashbots = self.flashbots_client.as_ref()
+            .ok_or_else(|| ExecutionError::FlashbotsError("Flashbots client not initialized".to_string()))?;
+
+        info!("通过 Flashbots 发送私密交易");
+        let (bundle, nonce) = self.signed_flashbots_bundle(flashbots, params, gas_estimate).await?;
+
+        let result = flashbots.send_bundle(with_privacy(bundle, privacy)).await;
+        if !matches!(result, FlashbotsSendResult::Included { .. }) {
+            // Bundle 没有上链，nonce 没有被用掉
+            self.nonces.release(nonce, 1).await;
+        }
+
+        match result {
+            FlashbotsSendResult::Included { tx_hash, block_number, .. } => {
+                info!("Flashbots 交易成功打包！区块: {}, 交易哈希: {:?}", block_number, tx_hash);
+                Ok(tx_hash)
+            }
+            FlashbotsSendResult::NotIncluded { reason, .. } => {
+                warn!("Flashbots Bundle 未被打包: {}", reason);
+                Err(ExecutionError::FlashbotsNotIncluded(reason))
+            }
+            FlashbotsSendResult::SimulationFailed { error } => {
+                warn!("Flashbots 模拟失败: {}", error);
+                Err(ExecutionError::FlashbotsSimulationFailed(error))
+            }
+            FlashbotsSendResult::SendFailed { error } => {
+                warn!("Flashbots 发送失败: {}", error);
+                Err(ExecutionError::FlashbotsError(error))
+            }
+        }
+    }
+
+    /// 仅模拟: 签名 Bundle 后只提交中继 eth_callBundle，返回中继的模拟结果
+    async fn simulate_via_flashbots(
+        &self,
+        params: &ArbitrageContractParams,
+        gas_estimate: Option<U256>,
+    ) -> Result<BundleSimulation, ExecutionError> {
         let flashbots = self.flashbots_client.as_ref()
             .ok_or_else(|| ExecutionError::FlashbotsError("Flashbots client not initialized".to_string()))?;
 
+        let (bundle, nonce) = self.signed_flashbots_bundle(flashbots, params, gas_estimate).await?;
+        let simulation = flashbots.simulate_only(bundle).await;
+        // 仅模拟不会上链
+        self.nonces.release(nonce, 1).await;
+        simulation.map_err(|e| ExecutionError::FlashbotsError(format!("Simulation request failed: {:?}", e)))
+    }
+
+    /// 构建并签名单笔套利交易的 Bundle (交易类型与公开 mempool 一致，按链的 gas 模型)
+    ///
+    /// 返回 Bundle 和分配给交易的 nonce (Bundle 没有上链时调用方负责归还)
+    async fn signed_flashbots_bundle(
+        &self,
+        flashbots: &FlashbotsClient<M>,
+        params: &ArbitrageContractParams,
+        gas_estimate: Option<U256>,
+    ) -> Result<(BundleBuilder, U256), ExecutionError> {
         let wallet = self.wallet.as_ref()
             .ok_or_else(|| ExecutionError::WalletError("No wallet configured".to_string()))?;
 
         let from_address = wallet.address();
-        info!("通过 Flashbots 发送私密交易, from: {:?}", from_address);
+        debug!("构建 Flashbots Bundle, from: {:?}", from_address);
 
         // 构建交易调用，必须设置 from 地址
         let call = self.contract.execute_arbitrage(params.clone().into_tuple())
             .from(from_address);  // 关键：设置 from 为 owner 地址
 
         // 获取 gas limit (固定值或动态估算)
-        let gas_limit = if let Some(fixed_limit) = self.config.gas_strategy.fixed_gas_limit {
-            // 使用固定 gas limit，跳过估算
-            debug!("Flashbots: 使用固定 Gas Limit: {} (跳过估算)", fixed_limit);
-            U256::from(fixed_limit)
-        } else {
-            // 动态估算 gas (estimate_gas 底层也是 eth_call，需要正确的 from)
-            let gas_estimate = call.estimate_gas().await
-                .map_err(|e| ExecutionError::GasEstimationFailed(format!("{:?}", e)))?;
-
-            let limit = U256::from(
-                (gas_estimate.as_u64() as f64 * self.config.gas_strategy.gas_limit_multiplier) as u64
-            );
-            debug!("Flashbots: Gas 估算: {} | Gas 限制: {}", gas_estimate, limit);
-            limit
-        };
+        let (gas_limit, _) = self.resolve_gas_limit(&call, &self.config.execution_gas, gas_estimate).await?;
 
         // 获取 gas price
         let gas_price = self.get_gas_price().await?;
+        self.check_gas_cost(gas_limit, gas_price).await?;
 
-        // 获取 nonce
-        let nonce = self.provider.get_transaction_count(from_address, None).await
-            .map_err(|e| ExecutionError::NonceError(format!("{:?}", e)))?;
+        // 分配 nonce
+        let nonce = self.nonces.allocate(&*self.provider, from_address, 1).await?;
 
         // 构建完整的交易，显式设置 from 地址
-        let tx_request = TransactionRequest::new()
-            .to(self.config.contract_address)
-            .from(from_address)  // 关键：显式设置 from
-            .data(call.calldata().unwrap_or_default())
-            .gas(gas_limit)
-            .gas_price(gas_price)
-            .nonce(nonce)
-            .chain_id(self.config.chain_id);
+        let typed_tx = self.build_transaction(from_address, call.calldata().unwrap_or_default(), gas_limit, gas_price, nonce);
 
         // 签名交易
-        let typed_tx: TypedTransaction = tx_request.into();
-        let signed_tx = flashbots.sign_transaction(&typed_tx).await
-            .map_err(|e| ExecutionError::FlashbotsError(format!("Failed to sign transaction: {:?}", e)))?;
+        let signed_tx = match flashbots.sign_transaction(&typed_tx).await {
+            Ok(signed_tx) => signed_tx,
+            Err(e) => {
+                self.nonces.release(nonce, 1).await;
+                return Err(ExecutionError::FlashbotsError(format!("Failed to sign transaction: {:?}", e)));
+            }
+        };
 
-        // 构建 Bundle 并发送
-        let bundle = BundleBuilder::new()
-            .push_transaction(signed_tx);
+        Ok((BundleBuilder::new().push_transaction(signed_tx), nonce))
+    }
 
-        let result = flashbots.send_bundle(bundle).await;
+    /// 检查本次交易最多花费的 gas 费 (gas_limit * gas_price) 是否超过上限
+    async fn check_gas_cost(&self, gas_limit: U256, gas_price: U256) -> Result<(), ExecutionError> {
+        let Some(max_usd) = self.config.execution_gas.max_gas_cost_usd.and_then(Decimal::from_f64) else {
+            return Ok(());
+        };
 
-        match result {
-            FlashbotsSendResult::Included { tx_hash, block_number, .. } => {
-                info!("Flashbots 交易成功打包！区块: {}, 交易哈希: {:?}", block_number, tx_hash);
-                Ok(tx_hash)
-            }
-            FlashbotsSendResult::NotIncluded { reason, .. } => {
-                warn!("Flashbots Bundle 未被打包: {}", reason);
-                Err(ExecutionError::FlashbotsNotIncluded(reason))
-            }
-            FlashbotsSendResult::SimulationFailed { error } => {
-                warn!("Flashbots 模拟失败: {}", error);
-                Err(ExecutionError::FlashbotsSimulationFailed(error))
-            }
-            FlashbotsSendResult::SendFailed { error } => {
-                warn!("Flashbots 发送失败: {}", error);
-                Err(ExecutionError::FlashbotsError(error))
-            }
+        let cost_usd = gas_cost_usd(gas_limit, gas_price, self.get_native_token_price().await);
+        if cost_usd > max_usd {
+            warn!(
+                target: "arbitrage_execution",
+                "⛽ Gas 费超过上限，放弃发送: gas_limit={}, gas_price={} wei, 约 ${:.2} > ${}",
+                gas_limit, gas_price, cost_usd, max_usd
+            );
+            return Err(ExecutionError::GasCostTooHigh { cost_usd: cost_usd.round_dp(2), max_usd });
         }
+        Ok(())
     }
 
     /// 获取 gas price
@@ -788,7 +1052,7 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
             .map_err(|e| ExecutionError::ProviderError(format!("{:?}", e)))?;
 
         let adjusted_price = U256::from(
-            (base_price.as_u128() as f64 * self.config.gas_strategy.gas_price_multiplier) as u128
+            (base_price.as_u128() as f64 * self.config.execution_gas.gas_price_multiplier) as u128
         );
 
         Ok(adjusted_price)
@@ -823,6 +1087,9 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
                         // 解码 revert 原因
                         let decoded = RevertDecoder::decode_from_error_string(&revert_reason);
                         error!("   解码后: {}", decoded);
+                        global_revert_log().record(RevertRecord::from_decoded(
+                            self.config.chain_id, "receipt", Some(format!("{:?}", tx_hash)), &decoded,
+                        ));
 
                         return Err(ExecutionError::TransactionReverted(
                             format!("Transaction reverted in block {}: {}", block_num, decoded)
@@ -931,19 +1198,18 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
         })
     }
 
-    /// 获取原生代币价格 (ETH/BNB)
+    /// 获取原生代币价格 (ETH/BNB/MATIC/AVAX)
     async fn get_native_token_price(&self) -> Decimal {
+        let symbol = native_token_symbol(self.config.chain_id);
         if let Some(ref price_service) = self.price_service {
-            // 根据链 ID 判断是 ETH 还是 BNB
-            match self.config.chain_id {
-                56 | 97 => price_service.get_bnb_price().await,  // BSC Mainnet / Testnet
-                _ => price_service.get_eth_price().await,        // 默认 ETH
-            }
+            price_service.get_native_token_price(symbol).await
         } else {
             // 无价格服务时使用默认值
-            match self.config.chain_id {
-                56 | 97 => Decimal::from(300),  // BNB 默认价格
-                _ => Decimal::from(2000),       // ETH 默认价格
+            match symbol {
+                "BNB" => Decimal::from(300),
+                "MATIC" => Decimal::new(5, 1),
+                "AVAX" => Decimal::from(25),
+                _ => Decimal::from(2000),
             }
         }
     }
@@ -1047,10 +1313,589 @@ impl<M: Middleware + 'static> ArbitrageExecutor<M> {
     }
 }
 
+/// 按执行参数覆盖 Bundle 隐私级别 (None 时保留客户端配置的默认值)
+fn with_privacy(bundle: BundleBuilder, privacy: Option<BundlePrivacy>) -> BundleBuilder {
+    match privacy {
+        Some(privacy) => bundle.privacy(privacy),
+        None => bundle,
+    }
+}
+
+/// 不支持 EIP-1559 的链 (BSC 主网 / 测试网)
+fn is_legacy_gas_chain(chain_id: u64) -> bool {
+    matches!(chain_id, 56 | 97)
+}
+
+/// 检查模拟之后是否出现了新区块
+///
+/// 未做模拟 (simulated_block 为 None) 时不检查
+pub fn check_block_consistency(simulated_block: Option<u64>, current_block: u64) -> Result<(), ExecutionError> {
+    match simulated_block {
+        Some(simulated) if current_block > simulated => Err(ExecutionError::BlockAdvanced {
+            simulated,
+            current: current_block,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// gas_limit * gas_price 折算成 USD
+pub fn gas_cost_usd(gas_limit: U256, gas_price: U256, native_price_usd: Decimal) -> Decimal {
+    let cost_wei = gas_limit.saturating_mul(gas_price);
+    let cost_native = cost_wei
+        .to_string()
+        .parse::<Decimal>()
+        .map(|wei| wei / Decimal::from(1_000_000_000_000_000_000u64))
+        .unwrap_or(Decimal::MAX);
+    cost_native.saturating_mul(native_price_usd)
+}
+
+/// 链 ID 对应的原生代币符号 (BSC 测试网按 BNB，未知链按 ETH)
+fn native_token_symbol(chain_id: u64) -> &'static str {
+    match chain_id {
+        97 => "BNB",
+        _ => SupportedChain::from_chain_id(chain_id).map_or("ETH", |chain| chain.native_token()),
+    }
+}
+
+/// 由各跳报价和最大滑点计算每跳最小输出
+///
+/// 没有报价时返回全 0 (合约不做逐跳检查，只校验整体 min_profit)
+pub fn hop_min_amounts_out(hop_quotes: Option<[U256; 3]>, max_slippage: Decimal) -> [U256; 3] {
+    let Some(quotes) = hop_quotes else {
+        return [U256::zero(); 3];
+    };
+
+    let slippage_bps = (max_slippage * Decimal::from(10_000))
+        .round()
+        .to_u64()
+        .unwrap_or(0)
+        .min(10_000);
+    let keep_bps = U256::from(10_000 - slippage_bps);
+    quotes.map(|quote| quote * keep_bps / U256::from(10_000))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::testing::{mock_endpoint_sequence, mock_endpoint, receipt, sample_params, MockMiddleware, EXECUTE_ARBITRAGE_SIG, TEST_PRIVATE_KEY};
+    use ethers::abi::Token;
+    use std::sync::Mutex;
+
+    /// Both 模式 + 无效的 Bundle 签名私钥: Flashbots 客户端初始化失败，
+    /// 执行流程仍会模拟，但只走 mempool，测试不会访问中继
+    fn mock_executor(mock: Arc<MockMiddleware>) -> ArbitrageExecutor<MockMiddleware> {
+        ArbitrageExecutor::new(mock_executor_config(), mock).unwrap()
+    }
+
+    fn mock_executor_config() -> ExecutorConfig {
+        ExecutorConfig {
+            contract_address: Address::repeat_byte(0xcc),
+            private_key: Some(TEST_PRIVATE_KEY.to_string()),
+            send_mode: SendMode::Both,
+            confirmation_timeout_secs: 5,
+            flashbots_config: FlashbotsConfig {
+                signer_key: Some("invalid".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn script_owner(mock: &MockMiddleware) {
+        let owner = TEST_PRIVATE_KEY.parse::<LocalWallet>().unwrap().address();
+        mock.on_call("owner()", &[Token::Address(owner)]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_happy_path_with_mock_middleware() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x42);
+        let profit = U256::exp10(16);
+
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(profit)]);
+        mock.set_nonce(U256::from(7));
+        mock.set_estimate_gas(Ok(U256::from(200_000u64)));
+        mock.set_gas_price(U256::from(2_000_000_000u64));
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, profit));
+
+        let executor = mock_executor(mock.clone());
+        let outcome = executor.execute(sample_params()).await.unwrap();
+        let result = outcome.executed().unwrap();
+
+        // owner 校验 -> 模拟 -> 发送 -> 确认
+        assert_eq!(mock.call_count("owner()"), 1);
+        assert_eq!(mock.call_count(EXECUTE_ARBITRAGE_SIG), 1);
+        let sent = mock.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].nonce(), Some(&U256::from(7)));
+        assert_eq!(sent[0].to_addr(), Some(&Address::repeat_byte(0xcc)));
+        // gas limit = 估算 * 1.2, gas price = 基础价格 * 1.1
+        assert_eq!(mock.estimate_gas_count(), 1);
+        assert_eq!(sent[0].gas(), Some(&U256::from(240_000u64)));
+        assert_eq!(sent[0].gas_price(), Some(U256::from(2_200_000_000u64)));
+
+        assert!(result.success);
+        assert_eq!(result.tx_hash, tx_hash);
+        assert_eq!(result.profit, profit);
+        assert_eq!(result.block_number, 101);
+    }
+
+    /// 中继 eth_callBundle 模拟成功的响应
+    const RELAY_SIMULATED: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"results":[{"gasUsed":180000}],"coinbaseDiff":"0x0","gasUsed":180000,"totalGasFees":"0x0"}}"#;
+
+    #[tokio::test]
+    async fn test_bsc_sends_legacy_transaction_regardless_of_flag() {
+        let send_on_chain = |chain_id: u64| async move {
+            let mock = Arc::new(MockMiddleware::new());
+            let tx_hash = H256::repeat_byte(0x45);
+            script_owner(&mock);
+            mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+            mock.set_gas_price(U256::from(2_000_000_000u64));
+            mock.set_send_hash(tx_hash);
+            mock.set_receipt(receipt(tx_hash, true, U256::exp10(16)));
+
+            // 全局默认 use_eip1559 = true
+            let config = ExecutorConfig { chain_id, ..mock_executor_config() };
+            assert!(config.execution_gas.use_eip1559);
+            ArbitrageExecutor::new(config, mock.clone()).unwrap().execute(sample_params()).await.unwrap();
+            mock.sent_transactions().remove(0)
+        };
+
+        let bsc_tx = send_on_chain(56).await;
+        assert!(matches!(bsc_tx, TypedTransaction::Legacy(_)), "{:?}", bsc_tx);
+        assert_eq!(bsc_tx.gas_price(), Some(U256::from(2_200_000_000u64)));
+
+        // type-2 交易: max fee = 加价后的 gas price，优先费取配置的 0.01 Gwei
+        let mainnet_tx = send_on_chain(1).await;
+        let TypedTransaction::Eip1559(ref mainnet_tx) = mainnet_tx else { panic!("{:?}", mainnet_tx) };
+        assert_eq!(mainnet_tx.max_fee_per_gas, Some(U256::from(2_200_000_000u64)));
+        assert_eq!(mainnet_tx.max_priority_fee_per_gas, Some(U256::from(10_000_000u64)));
+
+        // Flashbots Bundle 中签名的交易同样按链选择类型 (type-2 原始交易以 0x02 开头，legacy 为 RLP 列表)
+        let bundle_tx_on_chain = |chain_id: u64| async move {
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let relay = mock_endpoint(200, RELAY_SIMULATED, received.clone()).await;
+            let mock = Arc::new(MockMiddleware::new());
+            script_owner(&mock);
+            mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+            mock.set_gas_price(U256::from(2_000_000_000u64));
+            let config = ExecutorConfig {
+                chain_id,
+                send_mode: SendMode::FlashbotsSimulateOnly,
+                flashbots_config: FlashbotsConfig { relay_url: relay, chain_id, ..Default::default() },
+                ..mock_executor_config()
+            };
+            ArbitrageExecutor::new(config, mock).unwrap().execute(sample_params()).await.unwrap();
+            let body: serde_json::Value = serde_json::from_str(&received.lock().unwrap()[0]).unwrap();
+            body["params"][0]["txs"][0].as_str().unwrap().to_string()
+        };
+        assert!(bundle_tx_on_chain(1).await.starts_with("0x02"));
+        assert!(bundle_tx_on_chain(56).await.starts_with("0xf"));
+
+        // 配置关闭 EIP-1559 的链同样发送 legacy 交易
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+        mock.set_send_hash(H256::repeat_byte(0x46));
+        mock.set_receipt(receipt(H256::repeat_byte(0x46), true, U256::exp10(16)));
+        let mut config = ExecutorConfig { chain_id: 137, ..mock_executor_config() };
+        config.execution_gas.use_eip1559 = false;
+        ArbitrageExecutor::new(config, mock.clone()).unwrap().execute(sample_params()).await.unwrap();
+        assert!(matches!(mock.sent_transactions()[0], TypedTransaction::Legacy(_)));
+    }
+
+    #[tokio::test]
+    async fn test_simulate_only_reports_relay_result_without_sending() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let relay = mock_endpoint(200, RELAY_SIMULATED, received.clone()).await;
+
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+        mock.set_estimate_gas(Ok(U256::from(200_000u64)));
+        mock.set_send_hash(H256::repeat_byte(0x47));
+
+        let config = ExecutorConfig {
+            send_mode: SendMode::FlashbotsSimulateOnly,
+            flashbots_config: FlashbotsConfig { relay_url: relay, ..Default::default() },
+            ..mock_executor_config()
+        };
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap();
+        let outcome = executor.execute(sample_params()).await.unwrap();
+
+        // 本地模拟通过后，已签名的 Bundle 只提交给中继模拟
+        let ExecutionOutcome::SimulatedOnly(simulation) = outcome else {
+            panic!("unexpected outcome: {:?}", outcome);
+        };
+        assert!(simulation.succeeded());
+        assert_eq!((simulation.target_block, simulation.gas_used), (101, 180000));
+        assert!(mock.sent_transactions().is_empty());
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(received[0].contains("eth_callBundle"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_reverted_transaction_with_mock_middleware() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x43);
+
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, false, U256::zero()));
+
+        let executor = mock_executor(mock.clone());
+        let err = executor.execute(sample_params()).await.unwrap_err();
+
+        assert!(matches!(err, ExecutionError::TransactionReverted(_)));
+        assert_eq!(mock.sent_transactions().len(), 1);
+
+        // 回执 revert 写入最近 revert 记录
+        let tx_hash_str = format!("{:?}", tx_hash);
+        let recorded = crate::revert_log::global_revert_log().recent(200);
+        assert!(recorded.iter().any(|r| r.source == "receipt" && r.tx_hash.as_deref() == Some(tx_hash_str.as_str())));
+    }
+
+    #[tokio::test]
+    async fn test_execute_simulation_revert_falls_back_to_mempool() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x44);
+
+        script_owner(&mock);
+        mock.on_call_revert(EXECUTE_ARBITRAGE_SIG, "Insufficient profit");
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, U256::from(5)));
+
+        // Both 模式下模拟失败仍通过 mempool 发送
+        let executor = mock_executor(mock.clone());
+        let outcome = executor.execute(sample_params()).await.unwrap();
+        let result = outcome.executed().unwrap();
+        assert_eq!(result.profit, U256::from(5));
+        assert_eq!(mock.sent_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_aborts_when_block_advances_after_simulation() {
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        // 快照、模拟时在 100 区块，发送前已到 101
+        mock.set_block_numbers(&[100, 100, 101]);
+
+        let executor = mock_executor(mock.clone());
+        let err = executor.execute(sample_params()).await.unwrap_err();
+
+        assert!(matches!(err, ExecutionError::BlockAdvanced { simulated: 100, current: 101 }));
+        assert!(mock.sent_transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_block_advance_only_aborts_mempool_send_after_simulation() {
+        // Normal 模式不模拟: 报价之后出了新区块也照常发送 (排队的机会不会被误杀)
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_block_numbers(&[100, 101]);
+        mock.set_send_hash(H256::repeat_byte(0x42));
+        mock.set_receipt(receipt(H256::repeat_byte(0x42), true, U256::from(1)));
+        let config = ExecutorConfig { send_mode: SendMode::Normal, ..mock_executor_config() };
+        ArbitrageExecutor::new(config, mock.clone()).unwrap().execute(sample_params()).await.unwrap();
+        assert_eq!(mock.sent_transactions().len(), 1);
+
+        // 仅模拟模式: Bundle 绑定目标区块，模拟之后出了新区块仍提交中继模拟
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let relay = mock_endpoint(200, RELAY_SIMULATED, received.clone()).await;
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+        mock.set_estimate_gas(Ok(U256::from(200_000u64)));
+        mock.set_block_numbers(&[100, 100, 101]);
+        let config = ExecutorConfig {
+            send_mode: SendMode::FlashbotsSimulateOnly,
+            flashbots_config: FlashbotsConfig { relay_url: relay, ..Default::default() },
+            ..mock_executor_config()
+        };
+        let outcome = ArbitrageExecutor::new(config, mock.clone()).unwrap().execute(sample_params()).await.unwrap();
+        assert!(matches!(outcome, ExecutionOutcome::SimulatedOnly(_)));
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // 关闭检查后 Both 模式模拟之后出了新区块也照常发送
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_block_numbers(&[100, 100, 101]);
+        mock.set_send_hash(H256::repeat_byte(0x42));
+        mock.set_receipt(receipt(H256::repeat_byte(0x42), true, U256::from(1)));
+        let config = ExecutorConfig { abort_if_block_advanced: false, ..mock_executor_config() };
+        let result = ArbitrageExecutor::new(config, mock.clone()).unwrap().execute(sample_params()).await;
+        assert!(!matches!(result, Err(ExecutionError::BlockAdvanced { .. })));
+        assert!(!mock.sent_transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_aborts_when_send_hangs() {
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_send_hangs(true);
+
+        let config = ExecutorConfig {
+            send_timeout_secs: 1,
+            ..mock_executor_config()
+        };
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap();
+
+        // 发送无响应时应在超时后返回，而不是一直挂起
+        let err = tokio::time::timeout(Duration::from_secs(10), executor.execute(sample_params()))
+            .await
+            .expect("执行未在发送超时后返回")
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::SendTimeout(1)));
+        assert!(mock.sent_transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slow_bundle_inclusion_keeps_mempool_hash() {
+        const ACCEPTED: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"bundleHash":"0x1111111111111111111111111111111111111111111111111111111111111111"}}"#;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let relay = mock_endpoint_sequence(vec![(200, RELAY_SIMULATED), (200, ACCEPTED)], received.clone()).await;
+
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x48);
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::exp10(16))]);
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, U256::exp10(16)));
+        // 执行前的检查和 Bundle 目标区块都在 100，等待打包时已到 101
+        mock.set_block_numbers(&[100, 100, 100, 100, 100, 101]);
+        mock.set_nonce(U256::from(7));
+
+        // Both 模式: 两笔交易都很快提交，但等待 Bundle 打包 (至少 2s) 超过 1s 的发送超时
+        let config = ExecutorConfig {
+            send_timeout_secs: 1,
+            flashbots_config: FlashbotsConfig { relay_url: relay, max_block_retries: 1, ..Default::default() },
+            ..mock_executor_config()
+        };
+        let nonces = Arc::new(NonceAllocator::new());
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap().with_nonce_allocator(nonces.clone());
+        let outcome = executor.execute(sample_params()).await.unwrap();
+        let result = outcome.executed().unwrap();
+
+        // 超时只限制提交: Bundle 未打包也不会丢掉已广播的 mempool 交易
+        assert!(result.success);
+        assert_eq!(result.tx_hash, tx_hash);
+        assert_eq!(mock.sent_transactions().len(), 1);
+        assert!(received.lock().unwrap()[1].contains("eth_sendBundle"));
+
+        // 只归还 Flashbots 没用掉的 nonce 8，mempool 已广播的 nonce 7 不会再分配出去
+        assert_eq!(mock.sent_transactions()[0].nonce(), Some(&U256::from(7)));
+        let wallet = TEST_PRIVATE_KEY.parse::<LocalWallet>().unwrap().address();
+        assert_eq!(nonces.allocate(&*mock, wallet, 1).await.unwrap(), U256::from(8));
+    }
+
+    #[tokio::test]
+    async fn test_simulation_with_state_overrides() {
+        let contract = Address::repeat_byte(0xcc);
+        // 调用方本身没有代币，只有带状态覆盖 (给合约打入余额) 的 eth_call 才能执行成功
+        let node = Arc::new(MockMiddleware::new());
+        node.on_call_fn(EXECUTE_ARBITRAGE_SIG, move |call| {
+            let funded = call
+                .state_override
+                .as_ref()
+                .and_then(|state| state.get(format!("{:?}", contract)))
+                .and_then(|account| account.get("balance"))
+                .is_some();
+            if funded { Ok(vec![Token::Uint(U256::from(1234))]) } else { Err("execution reverted: STF".to_string()) }
+        });
+        let params = ArbitrageContractParams::default();
+
+        // 不覆盖状态: 模拟失败 (假阴性)
+        let executor = ArbitrageExecutor::new(mock_executor_config(), node.clone()).unwrap();
+        assert!(executor.simulate_execution(&params, None).await.is_err());
+
+        // 给合约预置余额后模拟通过，并按 ABI 解码出利润
+        let overrides = crate::state_overrides::parse_state_overrides(&format!(
+            r#"{{"{:?}": {{"balance": "1000000000000000000"}}}}"#,
+            contract
+        ))
+        .unwrap();
+        let config = ExecutorConfig { state_overrides: Some(overrides), ..mock_executor_config() };
+        let executor = ArbitrageExecutor::new(config, node).unwrap();
+        assert_eq!(executor.simulate_execution(&params, None).await.unwrap(), U256::from(1234));
+    }
+
+    #[test]
+    fn test_hop_min_amounts_out_from_quotes_and_slippage() {
+        let quotes = [U256::from(1_000_000u64), U256::exp10(18), U256::from(3_333u64)];
+
+        // 0.5% 滑点
+        let mins = hop_min_amounts_out(Some(quotes), Decimal::new(5, 3));
+        assert_eq!(mins, [U256::from(995_000u64), U256::exp10(15) * 995, U256::from(3_316u64)]);
+
+        // 没有报价时不做逐跳检查
+        assert_eq!(hop_min_amounts_out(None, Decimal::new(5, 3)), [U256::zero(); 3]);
+        // 滑点超过 100% 时下限为 0
+        assert_eq!(hop_min_amounts_out(Some(quotes), Decimal::from(2)), [U256::zero(); 3]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_passes_hop_minimums_to_contract() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x46);
+
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, U256::from(1)));
+
+        let mut params = sample_params();
+        params.hop_quotes = Some([U256::from(2_000u64), U256::from(4_000u64), U256::from(10_000u64)]);
+        // 借入 USDC 执行起始代币为 token_a 的环路
+        let usdc = Address::repeat_byte(0xd1);
+        params.flash_token = Some(usdc);
+        params.flash_convert_fee = 500;
+        params.flash_amount = U256::from(2_100u64);
+        let executor = mock_executor(mock.clone());
+        executor.execute(params).await.unwrap();
+
+        // 参数元组按字段顺序编码: 第 11..13 个字段为每跳最小输出 (默认 0.5% 滑点)，最后三个为借入代币转换
+        let data = mock.sent_transactions()[0].data().cloned().unwrap();
+        let words: Vec<U256> = data[4..].chunks(32).map(U256::from_big_endian).collect();
+        assert_eq!(words.len(), 17);
+        assert_eq!(words[11..14], [U256::from(1_990u64), U256::from(3_980u64), U256::from(9_950u64)]);
+        assert_eq!(words[14], U256::from_big_endian(H256::from(usdc).as_bytes()));
+        assert_eq!(words[15..], [U256::from(500u64), U256::from(2_100u64)]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_fixed_gas_limit_skips_estimation() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x45);
+
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, U256::from(1)));
+
+        let mut config = mock_executor_config();
+        config.execution_gas.fixed_gas_limit = Some(900_000);
+        config.simulation_gas.fixed_gas_limit = Some(900_000);
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap();
+        executor.execute(sample_params()).await.unwrap();
+
+        assert_eq!(mock.estimate_gas_count(), 0);
+        assert_eq!(mock.sent_transactions()[0].gas(), Some(&U256::from(900_000u64)));
+    }
+
+    #[tokio::test]
+    async fn test_simulation_and_send_use_separate_gas_strategies() {
+        let mock = Arc::new(MockMiddleware::new());
+        let tx_hash = H256::repeat_byte(0x47);
+
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        mock.set_estimate_gas(Ok(U256::from(200_000u64)));
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, U256::from(1)));
+
+        let mut config = mock_executor_config();
+        config.simulation_gas.gas_limit_multiplier = 1.0;
+        config.execution_gas.gas_limit_multiplier = 1.5;
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap();
+        executor.execute(sample_params()).await.unwrap();
+
+        // 模拟按估算值原样，发送按执行倍数加价，且复用模拟阶段的估算
+        assert_eq!(mock.call_gas_limits(EXECUTE_ARBITRAGE_SIG), vec![Some(U256::from(200_000u64))]);
+        assert_eq!(mock.sent_transactions()[0].gas(), Some(&U256::from(300_000u64)));
+        assert_eq!(mock.estimate_gas_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_aborts_when_gas_cost_exceeds_ceiling() {
+        let mock = Arc::new(MockMiddleware::new());
+        script_owner(&mock);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(U256::from(1))]);
+        // 估算异常大: 2,000,000 * 1.2 * 1.1 gwei ≈ 0.00264 ETH ≈ $5.28 (无价格服务按 $2000)
+        mock.set_estimate_gas(Ok(U256::from(2_000_000u64)));
+
+        let mut config = mock_executor_config();
+        config.execution_gas.max_gas_cost_usd = Some(1.0);
+        let executor = ArbitrageExecutor::new(config, mock.clone()).unwrap();
+        let err = executor.execute(sample_params()).await.unwrap_err();
+
+        match err {
+            ExecutionError::GasCostTooHigh { cost_usd, max_usd } => {
+                assert_eq!(cost_usd, Decimal::new(528, 2));
+                assert_eq!(max_usd, Decimal::ONE);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(mock.sent_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_gas_cost_usd() {
+        // 300,000 gas * 20 gwei = 0.006 ETH, ETH $2000 -> $12
+        let cost = gas_cost_usd(U256::from(300_000u64), U256::from(20_000_000_000u64), Decimal::from(2000));
+        assert_eq!(cost, Decimal::from(12));
+        assert_eq!(gas_cost_usd(U256::zero(), U256::from(1u64), Decimal::from(2000)), Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_native_token_price_follows_chain() {
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        price_service.set_price("AVAX", Decimal::from(30)).await;
+        price_service.set_price("MATIC", Decimal::new(7, 1)).await;
+
+        for (chain_id, expected) in [
+            (43114, Decimal::from(30)),
+            (137, Decimal::new(7, 1)),
+            (56, Decimal::from(300)),
+            (8453, Decimal::from(2000)),
+        ] {
+            let config = ExecutorConfig { chain_id, ..mock_executor_config() };
+            let executor = ArbitrageExecutor::new(config, Arc::new(MockMiddleware::new()))
+                .unwrap()
+                .with_price_service(price_service.clone());
+            assert_eq!(executor.get_native_token_price().await, expected, "chain {}", chain_id);
+        }
+
+        // 没有价格服务时按各自原生代币的默认价
+        let config = ExecutorConfig { chain_id: 43114, ..mock_executor_config() };
+        let executor = ArbitrageExecutor::new(config, Arc::new(MockMiddleware::new())).unwrap();
+        assert_eq!(executor.get_native_token_price().await, Decimal::from(25));
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_non_owner_wallet() {
+        let mock = Arc::new(MockMiddleware::new());
+        mock.on_call("owner()", &[Token::Address(Address::repeat_byte(0x99))]);
+
+        let executor = mock_executor(mock.clone());
+        let err = executor.execute(sample_params()).await.unwrap_err();
+
+        assert!(matches!(err, ExecutionError::ContractError(_)));
+        assert_eq!(mock.call_count(EXECUTE_ARBITRAGE_SIG), 0);
+        assert!(mock.sent_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_block_advanced_after_simulation_aborts() {
+        // 模拟在 100 区块，发送前已到 101
+        let err = check_block_consistency(Some(100), 101).unwrap_err();
+        assert!(matches!(err, ExecutionError::BlockAdvanced { simulated: 100, current: 101 }));
+        assert!(err.to_string().contains("Block advanced after simulation"));
+
+        // 同一区块内发送、未做模拟均放行
+        assert!(check_block_consistency(Some(100), 100).is_ok());
+        assert!(check_block_consistency(None, 101).is_ok());
+    }
+
     #[test]
     fn test_default_config() {
         let config = ExecutorConfig::default();
@@ -1,6 +1,11 @@
 //! FlashArbitrage 合约 ABI 绑定
 
 use ethers::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::types::ExecutionError;
 
 // 生成合约绑定 - 使用 JSON ABI 格式
 abigen!(
@@ -20,7 +25,13 @@ abigen!(
                         {"name": "amountIn", "type": "uint256"},
                         {"name": "minProfit", "type": "uint256"},
                         {"name": "profitToken", "type": "address"},
-                        {"name": "profitConvertFee", "type": "uint24"}
+                        {"name": "profitConvertFee", "type": "uint24"},
+                        {"name": "minOut1", "type": "uint256"},
+                        {"name": "minOut2", "type": "uint256"},
+                        {"name": "minOut3", "type": "uint256"},
+                        {"name": "flashToken", "type": "address"},
+                        {"name": "flashConvertFee", "type": "uint24"},
+                        {"name": "flashAmount", "type": "uint256"}
                     ],
                     "name": "params",
                     "type": "tuple"
@@ -73,6 +84,26 @@ abigen!(
             "stateMutability": "nonpayable",
             "type": "function"
         },
+        {
+            "inputs": [
+                {"name": "weth", "type": "address"},
+                {"name": "amount", "type": "uint256"}
+            ],
+            "name": "wrapNative",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "inputs": [
+                {"name": "weth", "type": "address"},
+                {"name": "amount", "type": "uint256"}
+            ],
+            "name": "unwrapNative",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
         {
             "inputs": [],
             "name": "owner",
@@ -94,6 +125,13 @@ abigen!(
             "stateMutability": "view",
             "type": "function"
         },
+        {
+            "inputs": [],
+            "name": "INTERFACE_VERSION",
+            "outputs": [{"name": "", "type": "uint256"}],
+            "stateMutability": "view",
+            "type": "function"
+        },
         {
             "anonymous": false,
             "inputs": [
@@ -192,6 +230,88 @@ abigen!(
     ]"#
 );
 
+/// 当前绑定期望的合约接口版本 (与合约 INTERFACE_VERSION 对应)
+///
+/// 版本 2: ArbitrageParams 增加 minOut1..3
+/// 版本 3: 增加 wrapNative / unwrapNative (WETH 缓冲维护)
+/// 版本 4: ArbitrageParams 增加 flashToken / flashConvertFee / flashAmount (借入代币与起始代币不同)
+pub const EXPECTED_INTERFACE_VERSION: u64 = 4;
+
+/// 接口校验遇到 RPC 错误时的最多尝试次数
+const INTERFACE_CHECK_ATTEMPTS: u32 = 3;
+
+/// 接口校验两次尝试之间的等待时间
+const INTERFACE_CHECK_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// 校验已部署合约的接口版本与当前绑定一致
+///
+/// 旧版合约没有 INTERFACE_VERSION，调用 revert 或返回值无法解码同样视为不兼容；
+/// 其它错误 (节点超时、连接断开) 说明不了合约本身，重试几次仍失败时返回 `ProviderError`
+pub async fn check_contract_interface<M: Middleware + 'static>(
+    contract_address: Address,
+    provider: Arc<M>,
+) -> Result<u64, ExecutionError> {
+    let contract = FlashArbitrageContract::new(contract_address, provider);
+    let mut attempt = 1;
+    loop {
+        let e = match contract.interface_version().call().await {
+            Ok(version) if version == U256::from(EXPECTED_INTERFACE_VERSION) => {
+                return Ok(EXPECTED_INTERFACE_VERSION)
+            }
+            Ok(version) => {
+                return Err(ExecutionError::ContractAbiMismatch {
+                    expected: EXPECTED_INTERFACE_VERSION,
+                    found: Some(version.low_u64()),
+                })
+            }
+            Err(e) => e,
+        };
+        debug!("读取合约 INTERFACE_VERSION 失败: {:?}", e);
+        if is_missing_interface(&e) {
+            return Err(ExecutionError::ContractAbiMismatch {
+                expected: EXPECTED_INTERFACE_VERSION,
+                found: None,
+            });
+        }
+        if attempt >= INTERFACE_CHECK_ATTEMPTS {
+            return Err(ExecutionError::ProviderError(format!(
+                "读取合约 INTERFACE_VERSION 失败 ({} 次尝试): {}",
+                attempt, e
+            )));
+        }
+        warn!(
+            "⚠️ 读取合约 INTERFACE_VERSION 失败 (第 {}/{} 次)，{:?} 后重试: {}",
+            attempt, INTERFACE_CHECK_ATTEMPTS, INTERFACE_CHECK_RETRY_DELAY, e
+        );
+        tokio::time::sleep(INTERFACE_CHECK_RETRY_DELAY).await;
+        attempt += 1;
+    }
+}
+
+/// 调用失败是否说明合约没有 INTERFACE_VERSION: revert (没有该函数) 或返回值无法解码 (地址上没有合约代码)
+fn is_missing_interface<M: Middleware>(error: &ContractError<M>) -> bool {
+    matches!(
+        error,
+        ContractError::Revert(_) | ContractError::DecodingError(_) | ContractError::AbiError(_)
+    ) || error.to_string().contains("revert")
+}
+
+/// 读取合约的全局利润阈值 minProfitThreshold (起始代币最小单位，0 表示不限制)
+///
+/// 合约要求利润同时不低于调用参数里的 minProfit 和该阈值，阈值比链下门槛高时，
+/// 利润介于两者之间的机会发出去必然 revert
+pub async fn read_contract_min_profit<M: Middleware + 'static>(
+    contract_address: Address,
+    provider: Arc<M>,
+) -> Result<U256, ExecutionError> {
+    let contract = FlashArbitrageContract::new(contract_address, provider);
+    contract
+        .min_profit_threshold()
+        .call()
+        .await
+        .map_err(|e| ExecutionError::ContractError(format!("读取 minProfitThreshold 失败: {}", e)))
+}
+
 /// 套利参数 - 用于调用 executeArbitrage 函数
 /// 这个结构体与合约中的 ArbitrageParams 结构体一一对应
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -209,10 +329,20 @@ pub struct ArbitrageContractParams {
     pub profit_token: Address,
     /// 利润转换池费率 (tokenA -> profitToken)
     pub profit_convert_fee: u32,
+    /// 每跳最小输出 [A->B, B->C, C->A]，0 表示该跳不检查
+    pub min_amounts_out: [U256; 3],
+    /// 闪电贷借入代币 (Address::zero() 表示直接借 tokenA)
+    pub flash_token: Address,
+    /// 借入代币与 tokenA 之间的转换池费率
+    pub flash_convert_fee: u32,
+    /// 借入代币数量 (仅借入代币与 tokenA 不同时使用)
+    pub flash_amount: U256,
 }
 
 /// abigen 生成的 executeArbitrage 函数期望的参数类型
-pub type ExecuteArbitrageParams = (Address, Address, Address, Address, u32, u32, u32, U256, U256, Address, u32);
+pub type ExecuteArbitrageParams = (
+    Address, Address, Address, Address, u32, u32, u32, U256, U256, Address, u32, U256, U256, U256, Address, u32, U256,
+);
 
 impl ArbitrageContractParams {
     /// 转换为 abigen 生成的元组格式
@@ -229,6 +359,12 @@ impl ArbitrageContractParams {
             self.min_profit,
             self.profit_token,
             self.profit_convert_fee,
+            self.min_amounts_out[0],
+            self.min_amounts_out[1],
+            self.min_amounts_out[2],
+            self.flash_token,
+            self.flash_convert_fee,
+            self.flash_amount,
         )
     }
 }
@@ -237,9 +373,78 @@ impl ArbitrageContractParams {
 mod tests {
     use super::*;
 
+    use crate::testing::MockMiddleware;
+    use ethers::abi::Token;
+
     #[test]
     fn test_contract_binding() {
         // 验证合约绑定正确生成
         let _: Address = Address::zero();
     }
+
+    #[tokio::test]
+    async fn test_check_contract_interface() {
+        let address = Address::repeat_byte(0xcc);
+
+        let compatible = Arc::new(MockMiddleware::new());
+        compatible.on_call("INTERFACE_VERSION()", &[Token::Uint(U256::from(EXPECTED_INTERFACE_VERSION))]);
+        assert_eq!(check_contract_interface(address, compatible).await.unwrap(), EXPECTED_INTERFACE_VERSION);
+
+        // 版本不一致 (部署的是不支持借入代币转换的版本 3 合约)
+        let outdated = Arc::new(MockMiddleware::new());
+        outdated.on_call("INTERFACE_VERSION()", &[Token::Uint(U256::from(3))]);
+        let err = check_contract_interface(address, outdated).await.unwrap_err();
+        assert!(matches!(err, ExecutionError::ContractAbiMismatch { expected: 4, found: Some(3) }));
+        assert!(err.to_string().starts_with("contract ABI mismatch"));
+
+        // 旧版合约没有 INTERFACE_VERSION
+        let legacy = Arc::new(MockMiddleware::new());
+        legacy.on_call_revert("INTERFACE_VERSION()", "");
+        let err = check_contract_interface(address, legacy.clone()).await.unwrap_err();
+        assert!(matches!(err, ExecutionError::ContractAbiMismatch { found: None, .. }));
+        // revert 是确定的结论，不重试
+        assert_eq!(legacy.call_count("INTERFACE_VERSION()"), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_check_contract_interface_retries_rpc_errors() {
+        let address = Address::repeat_byte(0xcc);
+
+        // 启动时节点短暂不可用，第二次读取成功
+        let flaky = Arc::new(MockMiddleware::new());
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = attempts.clone();
+        flaky.on_call_fn("INTERFACE_VERSION()", move |_| {
+            if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Err("connection reset by peer".to_string())
+            } else {
+                Ok(vec![Token::Uint(U256::from(EXPECTED_INTERFACE_VERSION))])
+            }
+        });
+        assert_eq!(check_contract_interface(address, flaky.clone()).await.unwrap(), EXPECTED_INTERFACE_VERSION);
+        assert_eq!(flaky.call_count("INTERFACE_VERSION()"), 2);
+
+        // 一直失败: 用完尝试次数后报告 RPC 错误，而不是 ABI 不兼容
+        let down = Arc::new(MockMiddleware::new());
+        down.on_call_fn("INTERFACE_VERSION()", |_| Err("request timed out".to_string()));
+        let err = check_contract_interface(address, down.clone()).await.unwrap_err();
+        assert!(matches!(err, ExecutionError::ProviderError(_)));
+        assert_eq!(down.call_count("INTERFACE_VERSION()"), INTERFACE_CHECK_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn test_read_contract_min_profit() {
+        let address = Address::repeat_byte(0xcc);
+
+        let configured = Arc::new(MockMiddleware::new());
+        configured.on_call("minProfitThreshold()", &[Token::Uint(U256::from(2_000_000u64))]);
+        assert_eq!(read_contract_min_profit(address, configured).await.unwrap(), U256::from(2_000_000u64));
+
+        let unreadable = Arc::new(MockMiddleware::new());
+        unreadable.on_call_revert("minProfitThreshold()", "");
+        assert!(matches!(
+            read_contract_min_profit(address, unreadable).await,
+            Err(ExecutionError::ContractError(_))
+        ));
+    }
 }
@@ -3,7 +3,8 @@
 //! 用于构建 Flashbots Bundle（交易包）
 
 use ethers::types::{Bytes, H256};
-use super::types::BundleRequest;
+use ethers::utils::keccak256;
+use super::types::{BundlePrivacy, BundleRequest, MevShareBundleRequest, MevShareBundleTx, MevShareInclusion};
 
 /// Bundle 构建器
 #[derive(Debug, Clone, Default)]
@@ -18,6 +19,8 @@ pub struct BundleBuilder {
     max_timestamp: Option<u64>,
     /// 允许回滚的交易哈希
     reverting_tx_hashes: Vec<H256>,
+    /// 隐私级别 (None 时使用客户端配置的默认值)
+    privacy: Option<BundlePrivacy>,
 }
 
 impl BundleBuilder {
@@ -63,8 +66,21 @@ impl BundleBuilder {
         self
     }
 
+    /// 设置本次 Bundle 的隐私级别 (覆盖配置默认值，比如单个机会要求完全私密)
+    pub fn privacy(mut self, privacy: BundlePrivacy) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    /// 未单独设置隐私级别时使用给定的默认值
+    pub fn default_privacy(mut self, privacy: BundlePrivacy) -> Self {
+        self.privacy.get_or_insert(privacy);
+        self
+    }
+
     /// 构建 Bundle 请求
     pub fn build(self) -> BundleRequest {
+        let mev_share = self.mev_share_request();
         BundleRequest {
             txs: self.txs.iter().map(|tx| format!("0x{}", hex::encode(tx))).collect(),
             block_number: format!("0x{:x}", self.target_block),
@@ -74,9 +90,37 @@ impl BundleBuilder {
                 .iter()
                 .map(|h| format!("{:?}", h))
                 .collect(),
+            mev_share,
         }
     }
 
+    /// 带隐私提示时的 MEV-Share 请求 (允许回滚的交易标记为 canRevert)
+    fn mev_share_request(&self) -> Option<MevShareBundleRequest> {
+        let privacy = self.privacy?.to_request()?;
+        let body = self.txs
+            .iter()
+            .zip(self.tx_hashes())
+            .map(|(tx, hash)| MevShareBundleTx {
+                tx: format!("0x{}", hex::encode(tx)),
+                can_revert: self.reverting_tx_hashes.contains(&hash),
+            })
+            .collect();
+        Some(MevShareBundleRequest {
+            version: "v0.1",
+            inclusion: MevShareInclusion {
+                block: format!("0x{:x}", self.target_block),
+                max_block: None,
+            },
+            body,
+            privacy,
+        })
+    }
+
+    /// 签名交易的哈希 (用于在区块中确认是否被打包)
+    pub fn tx_hashes(&self) -> Vec<H256> {
+        self.txs.iter().map(|tx| H256::from(keccak256(tx))).collect()
+    }
+
     /// 获取交易数量
     pub fn tx_count(&self) -> usize {
         self.txs.len()
@@ -109,5 +153,6 @@ mod tests {
         assert_eq!(bundle.block_number, "0xbc614e");
         assert_eq!(bundle.min_timestamp, Some(1000));
         assert_eq!(bundle.max_timestamp, Some(2000));
+        assert!(bundle.mev_share.is_none());
     }
 }
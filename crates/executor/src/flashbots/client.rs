@@ -7,6 +7,7 @@ use ethers::prelude::*;
 use ethers::types::{Bytes, H256};
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::utils::keccak256;
+use futures_util::future::join_all;
 use reqwest::Client;
 use std::sync::Arc;
 use std::time::Duration;
@@ -44,21 +45,34 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
         config: FlashbotsConfig,
         provider: Arc<M>,
         tx_private_key: &str,
+    ) -> Result<Self> {
+        let tx_signer: LocalWallet = tx_private_key.parse::<LocalWallet>()?;
+        Self::from_wallet(config, provider, tx_signer)
+    }
+
+    /// 用已加载的交易钱包创建客户端 (keystore 解密后的钱包走这里)
+    ///
+    /// 未配置 `signer_key` 时 Bundle 也用交易钱包签名
+    pub fn from_wallet(
+        config: FlashbotsConfig,
+        provider: Arc<M>,
+        tx_signer: LocalWallet,
     ) -> Result<Self> {
         let http_client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()?;
 
-        // Bundle 签名钱包
-        let signer_key_str = config.signer_key.clone().unwrap_or_else(|| tx_private_key.to_string());
-        let signer: LocalWallet = signer_key_str.parse::<LocalWallet>()?
-            .with_chain_id(config.chain_id);
-
         // 交易签名钱包
-        let tx_signer: LocalWallet = tx_private_key.parse::<LocalWallet>()?
-            .with_chain_id(config.chain_id);
+        let tx_signer = tx_signer.with_chain_id(config.chain_id);
+
+        // Bundle 签名钱包
+        let signer: LocalWallet = match config.signer_key {
+            Some(ref key) => key.parse::<LocalWallet>()?.with_chain_id(config.chain_id),
+            None => tx_signer.clone(),
+        };
 
-        info!("🔒 Flashbots 客户端初始化: relay_url={}, chain_id={}", config.relay_url, config.chain_id);
+        info!("🔒 Flashbots 客户端初始化: relay_url={}, chain_id={}, builders={}",
+            config.relay_url, config.chain_id, config.builders.len());
 
         Ok(Self {
             config,
@@ -73,8 +87,8 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
     ///
     /// # 流程
     /// 1. 模拟执行 Bundle
-    /// 2. 发送 Bundle 到 Flashbots 中继
-    /// 3. 等待 Bundle 被打包（在目标区块或后续区块）
+    /// 2. 同时发送 Bundle 到 Flashbots 中继和配置的所有 builder (任一成功即可)
+    /// 3. 等待 Bundle 被打包（在目标区块或后续区块，不论由哪个 builder 出块）
     ///
     /// # 返回
     /// - `FlashbotsSendResult::Included`: 成功打包
@@ -83,6 +97,7 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
     /// - `FlashbotsSendResult::SendFailed`: 发送失败
     pub async fn send_bundle(&self, bundle: BundleBuilder) -> FlashbotsSendResult {
         let target_block = self.get_next_block_number().await;
+        let tx_hashes = bundle.tx_hashes();
 
         info!(
             "准备发送 Flashbots Bundle: {} 笔交易, 目标区块 {}",
@@ -94,7 +109,7 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
         for block_offset in 0..self.config.max_block_retries {
             let current_target = target_block + block_offset;
 
-            let bundle_request = bundle.clone().target_block(current_target).build();
+            let bundle_request = self.build_request(&bundle, current_target);
 
             // 1. 模拟执行
             match self.simulate_bundle(&bundle_request).await {
@@ -103,21 +118,8 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
                         sim_result.gas_used, sim_result.coinbase_diff);
 
                     // 检查是否有交易失败
-                    for result in &sim_result.results {
-                        // revert 为空 (0x) 表示成功，只有非空的 revert 才是失败
-                        let has_revert = result.revert.as_ref()
-                            .map(|r| !r.is_empty())
-                            .unwrap_or(false);
-
-                        if result.error.is_some() || has_revert {
-                            let error_msg = result.error.clone()
-                                .or_else(|| result.revert.as_ref().map(|r| format!("{:?}", r)))
-                                .unwrap_or_else(|| "Unknown error".to_string());
-
-                            return FlashbotsSendResult::SimulationFailed {
-                                error: error_msg,
-                            };
-                        }
+                    if let Some(error) = simulation_error(&sim_result) {
+                        return FlashbotsSendResult::SimulationFailed { error };
                     }
                 }
                 Err(e) => {
@@ -129,12 +131,12 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
             }
 
             // 2. 发送 Bundle
-            match self.send_bundle_request(&bundle_request).await {
+            match self.broadcast_bundle(&bundle_request).await {
                 Ok(response) => {
                     info!("Bundle 已发送: {:?}, 目标区块 {}", response.bundle_hash, current_target);
 
                     // 3. 等待打包
-                    match self.wait_for_inclusion(response.bundle_hash, current_target).await {
+                    match self.wait_for_inclusion(response.bundle_hash, &tx_hashes, current_target).await {
                         Ok(Some(tx_hash)) => {
                             return FlashbotsSendResult::Included {
                                 bundle_hash: response.bundle_hash,
@@ -166,6 +168,41 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
         }
     }
 
+    /// 仅模拟 Bundle: 只向中继提交 eth_callBundle，永远不会调用 eth_sendBundle
+    ///
+    /// 交易失败 (revert) 时仍返回 Ok，失败原因记录在结果的 revert 字段中；
+    /// 只有中继请求本身失败时才返回错误
+    pub async fn simulate_only(&self, bundle: BundleBuilder) -> Result<BundleSimulation> {
+        let target_block = self.get_next_block_number().await;
+        let bundle_request = self.build_request(&bundle, target_block);
+
+        let sim_result = self.simulate_bundle(&bundle_request).await?;
+        let simulation = BundleSimulation {
+            target_block,
+            gas_used: sim_result.gas_used,
+            coinbase_diff: sim_result.coinbase_diff,
+            total_gas_fees: sim_result.total_gas_fees,
+            revert: simulation_error(&sim_result),
+        };
+        debug!(
+            "Bundle 仅模拟完成 (未发送): 目标区块 {}, gas_used={}, coinbase_diff={}, 结果: {}",
+            target_block,
+            simulation.gas_used,
+            simulation.coinbase_diff,
+            simulation.revert.as_deref().unwrap_or("成功")
+        );
+        Ok(simulation)
+    }
+
+    /// 构建发往指定区块的请求 (Bundle 未指定隐私级别时用配置默认值)
+    fn build_request(&self, bundle: &BundleBuilder, target_block: u64) -> BundleRequest {
+        bundle
+            .clone()
+            .target_block(target_block)
+            .default_privacy(self.config.privacy)
+            .build()
+    }
+
     /// 发送单笔交易通过 Flashbots
     ///
     /// 这是最常用的方法，将单笔交易包装成 Bundle 发送
@@ -211,22 +248,82 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
         Ok(response)
     }
 
-    /// 发送 Bundle 请求到 Flashbots 中继
-    async fn send_bundle_request(&self, bundle: &BundleRequest) -> Result<SendBundleResponse> {
+    /// Bundle 的发送目标: Flashbots 中继 + 配置的 builder (去重)
+    fn bundle_endpoints(&self) -> Vec<&str> {
+        let mut endpoints: Vec<&str> = vec![self.config.relay_url.as_str()];
+        for builder in &self.config.builders {
+            let builder = builder.trim();
+            if !builder.is_empty() && !endpoints.contains(&builder) {
+                endpoints.push(builder);
+            }
+        }
+        endpoints
+    }
+
+    /// 并发发送 Bundle 到中继和所有 builder
+    ///
+    /// 任一端点接受即视为发送成功，优先返回中继的响应 (bundle_hash 用于查询中继状态)
+    ///
+    /// 带隐私提示的 Bundle 发给中继时走 MEV-Share mev_sendBundle，builder 不认识隐私提示，仍然用 eth_sendBundle
+    async fn broadcast_bundle(&self, bundle: &BundleRequest) -> Result<SendBundleResponse> {
         let request = JsonRpcRequest::new(
             "eth_sendBundle",
             vec![bundle],
         );
+        let mev_share_request = bundle.mev_share.as_ref().map(|mev_share| JsonRpcRequest::new(
+            "mev_sendBundle",
+            vec![mev_share],
+        ));
+
+        // 只限制提交本身: 端点无响应时超时放弃，不影响之后等待打包
+        let submit_timeout = Duration::from_secs(self.config.submit_timeout_secs);
+        let endpoints = self.bundle_endpoints();
+        let results = join_all(endpoints.iter().enumerate().map(|(i, url)| {
+            let mev_share_request = mev_share_request.as_ref().filter(|_| i == 0);
+            let request = &request;
+            async move {
+                let submit = async {
+                    match mev_share_request {
+                        Some(mev_share_request) => self.send_signed_request_to::<SendBundleResponse>(url, mev_share_request).await,
+                        None => self.send_signed_request_to::<SendBundleResponse>(url, request).await,
+                    }
+                };
+                tokio::time::timeout(submit_timeout, submit)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow!("提交超时 ({}s)", self.config.submit_timeout_secs)))
+            }
+        }))
+        .await;
 
-        let response = self.send_signed_request::<SendBundleResponse>(&request).await?;
+        let mut accepted = None;
+        let mut errors = Vec::new();
+        for (url, result) in endpoints.iter().zip(results) {
+            match result {
+                Ok(response) => {
+                    debug!("Bundle 已被 {} 接受: {:?}", url, response.bundle_hash);
+                    accepted.get_or_insert(response);
+                }
+                Err(e) => {
+                    warn!("发送 Bundle 到 {} 失败: {:?}", url, e);
+                    errors.push(format!("{}: {}", url, e));
+                }
+            }
+        }
 
-        Ok(response)
+        if endpoints.len() > 1 {
+            info!("📡 Bundle 已广播: {}/{} 个端点接受", endpoints.len() - errors.len(), endpoints.len());
+        }
+
+        accepted.ok_or_else(|| anyhow!("所有端点发送 Bundle 失败: {}", errors.join("; ")))
     }
 
     /// 等待 Bundle 被打包
+    ///
+    /// 按交易哈希在目标区块中查找，任何 builder 出的块包含了 Bundle 都算打包成功
     async fn wait_for_inclusion(
         &self,
         bundle_hash: H256,
+        tx_hashes: &[H256],
         target_block: u64,
     ) -> Result<Option<H256>> {
         // 等待目标区块
@@ -244,26 +341,18 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
         // 额外等待一点时间确保区块已传播
         tokio::time::sleep(Duration::from_secs(2)).await;
 
-        // 查询 Bundle 状态
+        // 中继的 Bundle 状态只用于排查 (其他 builder 出的块中继不知道)
         match self.get_bundle_stats(bundle_hash, target_block).await {
-            Ok(stats) => {
-                debug!("Bundle 状态: {:?}", stats);
-
-                // 检查是否被打包（通过查询区块内的交易）
-                if let Ok(Some(block)) = self.provider.get_block_with_txs(target_block).await {
-                    // 尝试找到我们的交易
-                    // 注意：这里需要知道交易哈希才能匹配，暂时返回成功
-                    if !block.transactions.is_empty() {
-                        // 简单返回第一个交易的哈希作为示例
-                        // 实际应该比较交易内容
-                        return Ok(Some(block.transactions[0].hash));
-                    }
-                }
+            Ok(stats) => debug!("Bundle 状态: {:?}", stats),
+            Err(e) => debug!("获取 Bundle 状态失败: {:?}", e),
+        }
 
-                Ok(None)
-            }
+        // 检查目标区块是否包含我们的交易
+        match self.provider.get_block(target_block).await {
+            Ok(Some(block)) => Ok(find_included_tx(&block.transactions, tx_hashes)),
+            Ok(None) => Ok(None),
             Err(e) => {
-                warn!("获取 Bundle 状态失败: {:?}", e);
+                warn!("获取区块 {} 失败: {:?}", target_block, e);
                 Ok(None)
             }
         }
@@ -294,6 +383,15 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
     async fn send_signed_request<T: serde::de::DeserializeOwned + Default>(
         &self,
         request: &JsonRpcRequest<impl serde::Serialize>,
+    ) -> Result<T> {
+        self.send_signed_request_to(&self.config.relay_url, request).await
+    }
+
+    /// 发送签名的请求到指定端点 (中继或 builder，签名方式相同)
+    async fn send_signed_request_to<T: serde::de::DeserializeOwned + Default>(
+        &self,
+        url: &str,
+        request: &JsonRpcRequest<impl serde::Serialize>,
     ) -> Result<T> {
         let body = serde_json::to_string(request)?;
 
@@ -320,14 +418,14 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
             hex::encode(&sig_bytes)
         );
 
-        info!("📡 Flashbots 请求 URL: {}", self.config.relay_url);
+        info!("📡 Flashbots 请求 URL: {}", url);
         info!("🔑 签名地址: {}", signer_addr);
         info!("🔐 签名长度: {} bytes, v={}", sig_bytes.len(), sig_bytes.get(64).unwrap_or(&0));
         debug!("📝 X-Flashbots-Signature: {}", auth_header);
         debug!("📤 请求体: {}", body);
 
         let response = self.http_client
-            .post(&self.config.relay_url)
+            .post(url)
             .header("Content-Type", "application/json")
             .header("X-Flashbots-Signature", auth_header)
             .body(body)
@@ -377,9 +475,153 @@ impl<M: Middleware + 'static> FlashbotsClient<M> {
     }
 }
 
+/// 模拟结果中第一笔失败交易的错误信息
+fn simulation_error(sim_result: &SimulateBundleResponse) -> Option<String> {
+    sim_result.results.iter().find_map(|result| {
+        // revert 为空 (0x) 表示成功，只有非空的 revert 才是失败
+        let has_revert = result.revert.as_ref()
+            .map(|r| !r.is_empty())
+            .unwrap_or(false);
+
+        (result.error.is_some() || has_revert).then(|| {
+            result.error.clone()
+                .or_else(|| result.revert.as_ref().map(|r| format!("{:?}", r)))
+                .unwrap_or_else(|| "Unknown error".to_string())
+        })
+    })
+}
+
+/// 在区块交易列表中查找 Bundle 的交易
+fn find_included_tx(block_txs: &[H256], bundle_txs: &[H256]) -> Option<H256> {
+    bundle_txs.iter().find(|tx| block_txs.contains(tx)).copied()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{mock_endpoint, TEST_PRIVATE_KEY};
+    use ethers::providers::MockProvider;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_bundle_broadcast_to_all_builders() {
+        const ACCEPTED: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"bundleHash":"0x1111111111111111111111111111111111111111111111111111111111111111"}}"#;
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        // 中继拒绝，两个 builder 接受
+        let relay = mock_endpoint(500, "{}", received.clone()).await;
+        let builder1 = mock_endpoint(200, ACCEPTED, received.clone()).await;
+        let builder2 = mock_endpoint(200, ACCEPTED, received.clone()).await;
+
+        let config = FlashbotsConfig {
+            relay_url: relay,
+            builders: vec![builder1.clone(), builder2, builder1],
+            ..Default::default()
+        };
+        let (provider, _) = Provider::<MockProvider>::mocked();
+        let client = FlashbotsClient::new(config, Arc::new(provider), TEST_PRIVATE_KEY).unwrap();
+        assert_eq!(client.bundle_endpoints().len(), 3);
+
+        let bundle = BundleBuilder::new()
+            .push_transaction(Bytes::from(vec![0x01, 0x02]))
+            .target_block(100)
+            .build();
+        let response = client.broadcast_bundle(&bundle).await.unwrap();
+        assert_eq!(response.bundle_hash, H256::repeat_byte(0x11));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        assert!(received.iter().all(|body| body.contains("eth_sendBundle") && body.contains("0x0102")));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_request_body_reflects_privacy() {
+        const ACCEPTED: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"bundleHash":"0x1111111111111111111111111111111111111111111111111111111111111111"}}"#;
+
+        /// 返回 (中继收到的请求体, builder 收到的请求体)
+        async fn sent_bodies(config_privacy: BundlePrivacy, bundle: BundleBuilder) -> (serde_json::Value, serde_json::Value) {
+            let relay_received = Arc::new(Mutex::new(Vec::new()));
+            let builder_received = Arc::new(Mutex::new(Vec::new()));
+            let relay = mock_endpoint(200, ACCEPTED, relay_received.clone()).await;
+            let builder = mock_endpoint(200, ACCEPTED, builder_received.clone()).await;
+            let config = FlashbotsConfig { relay_url: relay, builders: vec![builder], privacy: config_privacy, ..Default::default() };
+            let (provider, _) = Provider::<MockProvider>::mocked();
+            let client = FlashbotsClient::new(config, Arc::new(provider), TEST_PRIVATE_KEY).unwrap();
+
+            let request = client.build_request(&bundle, 100);
+            client.broadcast_bundle(&request).await.unwrap();
+            let relay_body = relay_received.lock().unwrap()[0].clone();
+            let builder_body = builder_received.lock().unwrap()[0].clone();
+            (serde_json::from_str(&relay_body).unwrap(), serde_json::from_str(&builder_body).unwrap())
+        }
+        let bundle = || BundleBuilder::new().push_transaction(Bytes::from(vec![0x01, 0x02]));
+
+        // 配置默认: 允许 backrun，中继收到 MEV-Share mev_sendBundle
+        let (relay, builder) = sent_bodies(BundlePrivacy::AllowBackrun, bundle()).await;
+        assert_eq!(relay["method"], "mev_sendBundle");
+        assert_eq!(
+            relay["params"][0],
+            serde_json::json!({
+                "version": "v0.1",
+                "inclusion": {"block": "0x64"},
+                "body": [{"tx": "0x0102", "canRevert": false}],
+                "privacy": {"hints": ["hash", "contract_address", "function_selector", "logs"]},
+            })
+        );
+        // builder 不认识隐私提示，仍然收到 eth_sendBundle
+        assert_eq!(builder["method"], "eth_sendBundle");
+        assert!(builder["params"][0].get("privacy").is_none());
+
+        // 单个 Bundle 覆盖为完全私密
+        let (relay, _) = sent_bodies(BundlePrivacy::AllowBackrun, bundle().privacy(BundlePrivacy::Full)).await;
+        assert_eq!(relay["method"], "mev_sendBundle");
+        assert_eq!(relay["params"][0]["privacy"], serde_json::json!({"hints": []}));
+
+        // 中继默认: eth_sendBundle，不带 privacy 字段
+        let (relay, _) = sent_bodies(BundlePrivacy::RelayDefault, bundle()).await;
+        assert_eq!(relay["method"], "eth_sendBundle");
+        assert!(relay["params"][0].get("privacy").is_none());
+        assert_eq!(relay["params"][0]["blockNumber"], "0x64");
+    }
+
+    #[tokio::test]
+    async fn test_simulate_only_never_sends_bundle() {
+        const SIMULATED: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"results":[{"txHash":"0x2222222222222222222222222222222222222222222222222222222222222222","gasUsed":21000}],"coinbaseDiff":"0x3e8","gasUsed":21000,"totalGasFees":"0x3e8"}}"#;
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        // 中继和 builder 各只接受一次连接: 若发生真实发送，请求会落到这里并被记录
+        let relay = mock_endpoint(200, SIMULATED, received.clone()).await;
+        let builder = mock_endpoint(200, "{}", received.clone()).await;
+        let config = FlashbotsConfig { relay_url: relay, builders: vec![builder], ..Default::default() };
+        let (provider, mock) = Provider::<MockProvider>::mocked();
+        mock.push(U64::from(99)).unwrap();
+        mock.push(U64::from(99)).unwrap();
+        let client = FlashbotsClient::new(config, Arc::new(provider), TEST_PRIVATE_KEY).unwrap();
+
+        let bundle = BundleBuilder::new().push_transaction(Bytes::from(vec![0x01, 0x02]));
+        let simulation = client.simulate_only(bundle).await.unwrap();
+        assert!(simulation.succeeded());
+        assert_eq!(simulation.target_block, 100);
+        assert_eq!(simulation.gas_used, 21000);
+        assert_eq!(simulation.coinbase_diff, U256::from(1000));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(received[0].contains("eth_callBundle") && received[0].contains("0x0102"));
+        assert!(!received[0].contains("eth_sendBundle"));
+    }
+
+    #[test]
+    fn test_inclusion_found_by_tx_hash() {
+        let bundle = BundleBuilder::new().push_transaction(Bytes::from(vec![0x01, 0x02]));
+        let ours = bundle.tx_hashes()[0];
+        let others = vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)];
+
+        // 任意 builder 出的块里有我们的交易即视为打包
+        assert_eq!(find_included_tx(&[others.clone(), vec![ours]].concat(), &[ours]), Some(ours));
+        assert_eq!(find_included_tx(&others, &[ours]), None);
+    }
 
     #[test]
     fn test_flashbots_config_default() {
@@ -387,6 +629,10 @@ mod tests {
         assert_eq!(config.relay_url, "https://relay.flashbots.net");
         assert_eq!(config.chain_id, 1);
         assert!(!config.enabled);
+        assert_eq!(config.privacy, BundlePrivacy::RelayDefault);
+        assert_eq!(BundlePrivacy::parse("Backrun"), Some(BundlePrivacy::AllowBackrun));
+        assert_eq!(BundlePrivacy::parse("full"), Some(BundlePrivacy::Full));
+        assert_eq!(BundlePrivacy::parse("public"), None);
     }
 
     #[test]
@@ -16,6 +16,12 @@ pub struct FlashbotsConfig {
     pub max_block_retries: u64,
     /// Bundle 签名私钥（用于向 Flashbots 证明身份，可以和交易私钥不同）
     pub signer_key: Option<String>,
+    /// 额外的 builder RPC 地址 (如 beaverbuild、rsync、Titan)，Bundle 会同时发送到中继和这些 builder
+    pub builders: Vec<String>,
+    /// 默认隐私级别 (Bundle 未单独指定时使用，非中继默认时走 MEV-Share mev_sendBundle)
+    pub privacy: BundlePrivacy,
+    /// 单次提交 Bundle (eth_sendBundle) 的超时秒数，不包括等待打包
+    pub submit_timeout_secs: u64,
 }
 
 impl Default for FlashbotsConfig {
@@ -27,6 +33,9 @@ impl Default for FlashbotsConfig {
             enabled: false,
             max_block_retries: 3,
             signer_key: None,
+            builders: Vec::new(),
+            privacy: BundlePrivacy::default(),
+            submit_timeout_secs: 30,
         }
     }
 }
@@ -43,6 +52,85 @@ impl FlashbotsConfig {
     }
 }
 
+/// Bundle 隐私级别 (决定发给 MEV-Share 的 privacy.hints)
+///
+/// 只有 MEV-Share 的 mev_sendBundle 认识隐私提示，eth_sendBundle 会忽略，
+/// 所以非中继默认的级别都改走 mev_sendBundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundlePrivacy {
+    /// 按中继默认处理: 走 eth_sendBundle，不带 privacy 字段
+    #[default]
+    RelayDefault,
+    /// 完全私密: 不向搜索者暴露任何提示 (放弃 backrun 返利)
+    Full,
+    /// 暴露交易哈希、目标合约、函数选择器和日志，允许别人 backrun 并拿返利
+    AllowBackrun,
+}
+
+impl BundlePrivacy {
+    /// 从配置解析: `default` / `full` / `backrun`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "default" => Some(Self::RelayDefault),
+            "full" | "private" => Some(Self::Full),
+            "backrun" | "allow_backrun" => Some(Self::AllowBackrun),
+            _ => None,
+        }
+    }
+
+    /// mev_sendBundle 的 privacy 字段 (RelayDefault 时不走 MEV-Share)
+    pub fn to_request(self) -> Option<BundlePrivacyRequest> {
+        let hints = match self {
+            Self::RelayDefault => return None,
+            Self::Full => Vec::new(),
+            Self::AllowBackrun => vec!["hash", "contract_address", "function_selector", "logs"],
+        };
+        Some(BundlePrivacyRequest { hints })
+    }
+}
+
+/// MEV-Share Bundle 的隐私设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundlePrivacyRequest {
+    /// 允许向搜索者暴露的提示，空列表表示完全私密
+    pub hints: Vec<&'static str>,
+}
+
+/// MEV-Share mev_sendBundle 请求参数
+#[derive(Debug, Clone, Serialize)]
+pub struct MevShareBundleRequest {
+    /// 协议版本
+    pub version: &'static str,
+    /// 打包区块范围
+    pub inclusion: MevShareInclusion,
+    /// Bundle 中的交易
+    pub body: Vec<MevShareBundleTx>,
+    /// 隐私设置
+    pub privacy: BundlePrivacyRequest,
+}
+
+/// MEV-Share 打包区块范围
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareInclusion {
+    /// 目标区块号（十六进制）
+    pub block: String,
+    /// 最后有效区块号（可选，十六进制）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<String>,
+}
+
+/// MEV-Share Bundle 中的单笔交易
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevShareBundleTx {
+    /// 签名后的交易（十六进制字符串）
+    pub tx: String,
+    /// 交易失败时 Bundle 是否仍然有效
+    pub can_revert: bool,
+}
+
 /// Bundle 请求参数
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -60,6 +148,9 @@ pub struct BundleRequest {
     /// 回滚交易哈希列表（如果这些交易失败，整个 bundle 回滚）
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub reverting_tx_hashes: Vec<String>,
+    /// 带隐私提示时发往中继的 MEV-Share 请求 (None 时发送 eth_sendBundle)
+    #[serde(skip)]
+    pub mev_share: Option<MevShareBundleRequest>,
 }
 
 /// Bundle 模拟请求
@@ -208,6 +299,28 @@ pub enum FlashbotsSendResult {
     },
 }
 
+/// 仅模拟模式下中继返回的 Bundle 模拟结果 (Bundle 未发送)
+#[derive(Debug, Clone)]
+pub struct BundleSimulation {
+    /// 模拟的目标区块
+    pub target_block: u64,
+    /// 总 gas 使用
+    pub gas_used: u64,
+    /// coinbase 收益差（验证者收益）
+    pub coinbase_diff: U256,
+    /// 总 gas 费
+    pub total_gas_fees: U256,
+    /// 交易失败原因，None 表示全部交易执行成功
+    pub revert: Option<String>,
+}
+
+impl BundleSimulation {
+    /// 模拟是否全部成功
+    pub fn succeeded(&self) -> bool {
+        self.revert.is_none()
+    }
+}
+
 /// JSON-RPC 请求
 #[derive(Debug, Serialize)]
 pub struct JsonRpcRequest<T: Serialize> {
@@ -9,6 +9,10 @@
 //! - `flash_arbitrage`: 套利合约 ABI 绑定
 //! - `types`: 类型定义
 //! - `converter`: 套利机会转换器，自动选择闪电贷池
+//! - `revert_log`: 最近 revert 记录 (环形缓冲区)
+//! - `weth_buffer`: 合约 WETH 缓冲维护 (ETH 自动 wrap)
+//! - `state_overrides`: 模拟执行的状态覆盖配置
+//! - `nonce`: 同一钱包并发发送时的 nonce 分配
 
 mod flash_arbitrage;
 mod executor;
@@ -16,18 +20,30 @@ mod types;
 pub mod flashbots;
 pub mod converter;
 pub mod revert_decoder;
+pub mod revert_log;
 pub mod debug_info;
+pub mod weth_buffer;
+pub mod state_overrides;
+pub mod nonce;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-pub use flash_arbitrage::{FlashArbitrageContract, ArbitrageContractParams};
-pub use executor::{ArbitrageExecutor, ExecutorConfig, SendMode};
-pub use types::{ArbitrageParams, ExecutionResult, ExecutionError, GasStrategy};
-pub use flashbots::{FlashbotsClient, FlashbotsConfig, FlashbotsSendResult, BundleBuilder};
+pub use flash_arbitrage::{
+    FlashArbitrageContract, ArbitrageContractParams, check_contract_interface, read_contract_min_profit, EXPECTED_INTERFACE_VERSION,
+};
+pub use executor::{ArbitrageExecutor, ExecutorConfig, SendMode, check_block_consistency};
+pub use types::{ArbitrageParams, ExecutionOutcome, ExecutionResult, ExecutionError, GasStrategy};
+pub use flashbots::{FlashbotsClient, FlashbotsConfig, FlashbotsSendResult, BundleBuilder, BundlePrivacy, BundleSimulation};
 pub use converter::{
     ArbitrageParamsBuilder, FlashPoolSelector, FlashPoolSelectorConfig,
-    FlashPoolSelection, is_v3_only_path, extract_tokens,
-    calculate_flash_fee, is_still_profitable,
+    FlashPoolSelection, choose_disjoint_flash_pool, is_v3_only_path, extract_tokens,
+    calculate_flash_fee, is_still_profitable, FlashFeeTable, FlashLoanProvider,
 };
 pub use revert_decoder::{RevertDecoder, DecodedRevertError, RevertErrorType, ErrorAnalysis};
+pub use revert_log::{RevertLog, RevertRecord, global_revert_log};
+pub use state_overrides::parse_state_overrides;
+pub use nonce::NonceAllocator;
+pub use weth_buffer::{WethBufferAction, WethBufferConfig, WethBufferManager, WethBufferOutcome, plan_weth_buffer};
 pub use debug_info::{
     ExecutionDebugger, ExecutionSnapshot, ErrorSnapshot, log_execution_start,
     TokenInfoSnapshot, TokenDetail, PoolStateSnapshot, PoolRole, SwapPoolInfo,
@@ -0,0 +1,99 @@
+//! 钱包 nonce 分配
+//!
+//! 同一个钱包的交易可能并发发送 (多个执行许可、优先通道、Both 模式的 N/N+1、WETH 缓冲维护)，
+//! 各自读取 `get_transaction_count` 会拿到同一个 nonce。分配器在锁内取节点 pending nonce
+//! 与本地已分配位置中较大的一个，保证同一钱包的交易 nonce 连续且不重复；
+//! 分配出去的 nonce 没有被用掉 (发送失败、Bundle 未打包) 时调用 `release` 归还
+
+use ethers::prelude::*;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::types::ExecutionError;
+
+/// 单个钱包的 nonce 分配器 (同一钱包的所有发送方共享一个实例)
+#[derive(Debug, Default)]
+pub struct NonceAllocator {
+    /// 下一个可分配的 nonce (None 表示以节点为准)
+    next: Mutex<Option<U256>>,
+}
+
+impl NonceAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 分配 `count` 个连续 nonce，返回第一个
+    pub async fn allocate<M: Middleware>(
+        &self,
+        provider: &M,
+        address: Address,
+        count: u64,
+    ) -> Result<U256, ExecutionError> {
+        let mut next = self.next.lock().await;
+        let pending = provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| ExecutionError::NonceError(format!("{:?}", e)))?;
+        // 本地已分配的 nonce 可能还没进入节点的 pending 池 (如 Flashbots Bundle)
+        let base = match *next {
+            Some(local) if local > pending => local,
+            _ => pending,
+        };
+        *next = Some(base + count);
+        debug!("分配 nonce: {:?} 起 {} 个 (节点 pending={})", address, count, pending);
+        Ok(base)
+    }
+
+    /// 归还没有用掉的 nonce (发送失败、Bundle 未打包、仅模拟)
+    ///
+    /// 归还的是最近一次分配时回退到它；之后又分配过的话，后面的交易会卡在空出的 nonce 上，
+    /// 直接丢弃本地位置，下次分配以节点 pending nonce 为准
+    pub async fn release(&self, nonce: U256, count: u64) {
+        let mut next = self.next.lock().await;
+        *next = match *next {
+            Some(local) if local == nonce + count => Some(nonce),
+            _ => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockMiddleware;
+
+    #[tokio::test]
+    async fn test_allocations_are_sequential_until_node_catches_up() {
+        let mock = MockMiddleware::new();
+        mock.set_nonce(U256::from(7));
+        let nonces = NonceAllocator::new();
+        let address = Address::repeat_byte(0x11);
+
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(7));
+        assert_eq!(nonces.allocate(&mock, address, 2).await.unwrap(), U256::from(8));
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(10));
+
+        // 钱包在别处发过交易，节点 pending nonce 超过本地位置
+        mock.set_nonce(U256::from(20));
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(20));
+    }
+
+    #[tokio::test]
+    async fn test_released_nonce_is_reused() {
+        let mock = MockMiddleware::new();
+        mock.set_nonce(U256::from(3));
+        let nonces = NonceAllocator::new();
+        let address = Address::repeat_byte(0x11);
+
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(3));
+        // nonce 3 没有被用掉 (如 Bundle 未打包)
+        nonces.release(U256::from(3), 1).await;
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(3));
+
+        // 归还之前已经分配了后面的 nonce: 以节点为准
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(4));
+        nonces.release(U256::from(3), 1).await;
+        assert_eq!(nonces.allocate(&mock, address, 1).await.unwrap(), U256::from(3));
+    }
+}
@@ -0,0 +1,140 @@
+//! 最近 revert 记录
+//!
+//! 执行器在模拟失败、交易回执 revert 时写入解码后的错误，
+//! 固定容量的环形缓冲区只保留最新的记录，供 API 快速排查
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::revert_decoder::DecodedRevertError;
+
+/// 全局缓冲区容量
+const GLOBAL_REVERT_LOG_CAPACITY: usize = 200;
+
+lazy_static::lazy_static! {
+    static ref GLOBAL_REVERT_LOG: RevertLog = RevertLog::new(GLOBAL_REVERT_LOG_CAPACITY);
+}
+
+/// 进程内共享的 revert 记录 (所有链的执行器共用)
+pub fn global_revert_log() -> &'static RevertLog {
+    &GLOBAL_REVERT_LOG
+}
+
+/// 单条 revert 记录
+#[derive(Debug, Clone, Serialize)]
+pub struct RevertRecord {
+    pub timestamp: DateTime<Utc>,
+    pub chain_id: u64,
+    /// 发生阶段: simulation / receipt
+    pub source: String,
+    /// 交易哈希 (模拟阶段为空)
+    pub tx_hash: Option<String>,
+    /// RevertErrorType
+    pub error_type: String,
+    /// 原始错误选择器 (如 0x08c379a0)
+    pub selector: Option<String>,
+    pub message: String,
+    pub raw_data: String,
+    pub possible_causes: Vec<String>,
+    pub suggestions: Vec<String>,
+    pub is_retryable: bool,
+}
+
+impl RevertRecord {
+    pub fn from_decoded(
+        chain_id: u64,
+        source: &str,
+        tx_hash: Option<String>,
+        decoded: &DecodedRevertError,
+    ) -> Self {
+        // raw_data 形如 0x + hex，前 4 字节为选择器
+        let selector = decoded
+            .raw_data
+            .get(0..10)
+            .filter(|s| s.starts_with("0x"))
+            .map(|s| s.to_string());
+        let analysis = decoded.analysis.as_ref();
+
+        Self {
+            timestamp: Utc::now(),
+            chain_id,
+            source: source.to_string(),
+            tx_hash,
+            error_type: format!("{:?}", decoded.error_type),
+            selector,
+            message: decoded.message.clone(),
+            raw_data: decoded.raw_data.clone(),
+            possible_causes: analysis.map(|a| a.possible_causes.clone()).unwrap_or_default(),
+            suggestions: analysis.map(|a| a.suggestions.clone()).unwrap_or_default(),
+            is_retryable: analysis.map(|a| a.is_retryable).unwrap_or(false),
+        }
+    }
+}
+
+/// 固定容量的 revert 环形缓冲区
+#[derive(Debug)]
+pub struct RevertLog {
+    capacity: usize,
+    entries: Mutex<VecDeque<RevertRecord>>,
+}
+
+impl RevertLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// 写入一条记录，超出容量时丢弃最旧的
+    pub fn record(&self, record: RevertRecord) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(record);
+    }
+
+    /// 最近 limit 条记录 (最新的在前)
+    pub fn recent(&self, limit: usize) -> Vec<RevertRecord> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revert_decoder::RevertDecoder;
+
+    #[test]
+    fn test_ring_buffer_keeps_latest() {
+        let log = RevertLog::new(3);
+        // Error(string) "Insufficient profit"
+        let data = ethers::abi::encode(&[ethers::abi::Token::String("Insufficient profit".to_string())]);
+        let mut raw = vec![0x08, 0xc3, 0x79, 0xa0];
+        raw.extend(data);
+        let decoded = RevertDecoder::decode_revert_data(&raw);
+
+        for i in 0..5u64 {
+            log.record(RevertRecord::from_decoded(i, "simulation", None, &decoded));
+        }
+
+        let recent = log.recent(20);
+        assert_eq!(log.len(), 3);
+        assert_eq!(recent.iter().map(|r| r.chain_id).collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!(recent[0].error_type, "ErrorString");
+        assert_eq!(recent[0].selector.as_deref(), Some("0x08c379a0"));
+        assert_eq!(log.recent(1).len(), 1);
+    }
+}
@@ -0,0 +1,90 @@
+//! 模拟执行的状态覆盖 (eth_call 第三个参数)
+//!
+//! 部分合约流程假设调用方已经持有代币 (闪电贷到账后的状态)，直接 eth_call 会误判为失败。
+//! 这里把配置中的 JSON 解析成 geth 风格的 state override，模拟时一并传给节点
+//!
+//! 配置格式 (余额支持十进制或 0x 十六进制，storage 的 slot/value 为 32 字节十六进制):
+//!
+//! ```json
+//! {
+//!   "0xContract": {
+//!     "balance": "1000000000000000000",
+//!     "storage": { "0x...slot": "0x...value" }
+//!   }
+//! }
+//! ```
+
+use ethers::providers::call_raw::spoof;
+use ethers::types::{Address, H256, U256};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct AccountOverride {
+    balance: Option<String>,
+    #[serde(default)]
+    storage: HashMap<String, String>,
+}
+
+/// 解析状态覆盖配置
+pub fn parse_state_overrides(json: &str) -> Result<spoof::State, String> {
+    let accounts: HashMap<String, AccountOverride> =
+        serde_json::from_str(json).map_err(|e| format!("状态覆盖 JSON 无效: {}", e))?;
+
+    let mut state = spoof::state();
+    for (address, account) in accounts {
+        let address: Address = address
+            .parse()
+            .map_err(|_| format!("状态覆盖地址无效: {}", address))?;
+        let entry = state.account(address);
+        if let Some(balance) = account.balance {
+            let parsed = if let Some(hex) = balance.strip_prefix("0x") {
+                U256::from_str_radix(hex, 16).ok()
+            } else {
+                U256::from_dec_str(&balance).ok()
+            };
+            entry.balance(parsed.ok_or_else(|| format!("{:?} 的余额无效: {}", address, balance))?);
+        }
+        for (slot, value) in account.storage {
+            let slot: H256 = slot
+                .parse()
+                .map_err(|_| format!("{:?} 的 storage slot 无效: {}", address, slot))?;
+            let value: H256 = value
+                .parse()
+                .map_err(|_| format!("{:?} 的 storage 值无效: {}", address, value))?;
+            entry.store(slot, value);
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state_overrides() {
+        let json = r#"{
+            "0x00000000000000000000000000000000000000cc": {
+                "balance": "0xde0b6b3a7640000",
+                "storage": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000003": "0x00000000000000000000000000000000000000000000000000000000000003e8"
+                }
+            },
+            "0x00000000000000000000000000000000000000dd": { "balance": "5" }
+        }"#;
+        let state = parse_state_overrides(json).unwrap();
+        let encoded = serde_json::to_value(&state).unwrap();
+        let contract = &encoded["0x00000000000000000000000000000000000000cc"];
+        assert_eq!(contract["balance"], "0xde0b6b3a7640000");
+        assert_eq!(
+            contract["stateDiff"]["0x0000000000000000000000000000000000000000000000000000000000000003"],
+            "0x00000000000000000000000000000000000000000000000000000000000003e8"
+        );
+        assert_eq!(encoded["0x00000000000000000000000000000000000000dd"]["balance"], "0x5");
+
+        assert!(parse_state_overrides("not json").is_err());
+        assert!(parse_state_overrides(r#"{"0xzz": {}}"#).is_err());
+        assert!(parse_state_overrides(r#"{"0x00000000000000000000000000000000000000cc": {"balance": "abc"}}"#).is_err());
+    }
+}
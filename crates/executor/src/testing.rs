@@ -0,0 +1,589 @@
+//! 测试用 Mock Middleware 与夹具
+//!
+//! 按 RPC 方法脚本化返回值 (call 按函数选择器区分)，
+//! 让 `ArbitrageExecutor::execute` 可以在没有节点的情况下跑完整个流程。
+//! 其他 crate 的测试通过 `testing` feature 复用同一个 Mock，不再各自手写 `impl Middleware`
+
+use async_trait::async_trait;
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::providers::{JsonRpcClient, JsonRpcError, MockError, ProviderError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::{id, keccak256};
+use rust_decimal::Decimal;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::types::ArbitrageParams;
+
+/// 测试私钥 (Hardhat/Anvil 默认账户 #0)
+pub const TEST_PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// executeArbitrage 的函数签名 (参数为 ArbitrageContractParams 元组)
+pub const EXECUTE_ARBITRAGE_SIG: &str =
+    "executeArbitrage((address,address,address,address,uint24,uint24,uint24,uint256,uint256,address,uint24,uint256,uint256,uint256,address,uint24,uint256))";
+
+/// 收到的一次 eth_call
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    pub to: Option<Address>,
+    pub data: Bytes,
+    pub gas: Option<U256>,
+    pub block: Option<BlockId>,
+    /// 状态覆盖参数 (只有通过 call_raw 发出的调用才有)
+    pub state_override: Option<serde_json::Value>,
+}
+
+impl MockCall {
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&self.data[0..4]);
+        selector
+    }
+
+    /// 按参数类型解码选择器之后的参数
+    pub fn decode_args(&self, types: &[ParamType]) -> Vec<Token> {
+        abi::decode(types, &self.data[4..]).expect("calldata 与参数类型不匹配")
+    }
+}
+
+/// 按调用内容计算返回值，Err 原样作为 RPC 错误信息
+pub type CallHandler = Box<dyn Fn(&MockCall) -> Result<Vec<Token>, String> + Send + Sync>;
+
+enum CallResponse {
+    /// 固定返回值 (Err 为 revert 原因)
+    Fixed(Result<Bytes, String>),
+    Handler(CallHandler),
+}
+
+impl fmt::Debug for CallResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(result) => f.debug_tuple("Fixed").field(result).finish(),
+            Self::Handler(_) => f.write_str("Handler(..)"),
+        }
+    }
+}
+
+/// eth_call 的脚本 (Middleware::call 和 call_raw 发出的原始请求共用)
+#[derive(Debug, Default)]
+struct CallScript {
+    /// 函数选择器 -> eth_call 返回值
+    responses: Mutex<HashMap<[u8; 4], CallResponse>>,
+    /// 完整 calldata -> eth_call 返回值，优先于只按选择器的返回值
+    calldata_responses: Mutex<HashMap<Bytes, Bytes>>,
+    /// 已收到的 eth_call
+    log: Mutex<Vec<MockCall>>,
+    /// 每次 eth_call 的耗时
+    delay: Mutex<Option<Duration>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl CallScript {
+    async fn call(&self, call: MockCall) -> Result<Bytes, String> {
+        if call.data.len() < 4 {
+            return Err("calldata 太短".to_string());
+        }
+        self.log.lock().unwrap().push(call.clone());
+
+        let delay = *self.delay.lock().unwrap();
+        if let Some(delay) = delay {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(delay).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        if let Some(bytes) = self.calldata_responses.lock().unwrap().get(&call.data) {
+            return Ok(bytes.clone());
+        }
+        let selector = call.selector();
+        match self.responses.lock().unwrap().get(&selector) {
+            Some(CallResponse::Fixed(Ok(bytes))) => Ok(bytes.clone()),
+            Some(CallResponse::Fixed(Err(reason))) => Err(format!("execution reverted: {}", reason)),
+            Some(CallResponse::Handler(handler)) => handler(&call).map(|tokens| Bytes::from(abi::encode(&tokens))),
+            None => Err(format!("未配置 mock 返回值: selector=0x{}", hex::encode(selector))),
+        }
+    }
+}
+
+/// Mock 的底层 JSON-RPC 传输: 只响应 eth_call (call_raw 带状态覆盖时走这里)，其余方法报错
+#[derive(Debug, Clone)]
+pub struct MockRpc {
+    script: Arc<CallScript>,
+}
+
+#[async_trait]
+impl JsonRpcClient for MockRpc {
+    type Error = MockError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        if method != "eth_call" {
+            return Err(MockError::EmptyResponses);
+        }
+        let params = serde_json::to_value(params)?;
+        let tx = &params[0];
+        let call = MockCall {
+            to: serde_json::from_value(tx["to"].clone()).ok(),
+            data: serde_json::from_value(tx.get("data").or_else(|| tx.get("input")).cloned().unwrap_or_default())
+                .unwrap_or_default(),
+            gas: serde_json::from_value(tx["gas"].clone()).ok(),
+            block: serde_json::from_value(params[1].clone()).ok(),
+            state_override: params.get(2).cloned(),
+        };
+        match self.script.call(call).await {
+            Ok(bytes) => Ok(serde_json::from_value(serde_json::to_value(bytes)?)?),
+            Err(message) => Err(MockError::JsonRpcError(JsonRpcError { code: 3, message, data: None })),
+        }
+    }
+}
+
+/// 可脚本化的 Middleware
+#[derive(Debug)]
+pub struct MockMiddleware {
+    inner: Provider<MockRpc>,
+    script: Arc<CallScript>,
+    estimate_gas: Mutex<Result<U256, String>>,
+    estimate_gas_calls: AtomicUsize,
+    gas_price: Mutex<U256>,
+    nonce: Mutex<U256>,
+    /// 依次返回的区块号，最后一个会一直重复
+    block_numbers: Mutex<VecDeque<u64>>,
+    block_number_calls: AtomicUsize,
+    /// 地址 -> 合约代码 (未设置的地址没有代码)
+    code: Mutex<HashMap<Address, Bytes>>,
+    /// send_transaction / send_raw_transaction 返回的交易哈希
+    send_hash: Mutex<H256>,
+    /// send_transaction 永不返回 (模拟 RPC 无响应)
+    send_hangs: AtomicBool,
+    /// 已发送的交易 (签名交易解码后记录)
+    sent: Mutex<Vec<TypedTransaction>>,
+    receipts: Mutex<HashMap<H256, TransactionReceipt>>,
+    /// get_balance 返回的原生代币余额
+    native_balance: Mutex<U256>,
+}
+
+impl Default for MockMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockMiddleware {
+    pub fn new() -> Self {
+        let script = Arc::new(CallScript::default());
+        Self {
+            inner: Provider::new(MockRpc { script: script.clone() }),
+            script,
+            estimate_gas: Mutex::new(Ok(U256::from(300_000u64))),
+            estimate_gas_calls: AtomicUsize::new(0),
+            gas_price: Mutex::new(U256::from(1_000_000_000u64)),
+            nonce: Mutex::new(U256::zero()),
+            block_numbers: Mutex::new(VecDeque::from([100])),
+            block_number_calls: AtomicUsize::new(0),
+            code: Mutex::new(HashMap::new()),
+            send_hash: Mutex::new(H256::repeat_byte(0xab)),
+            send_hangs: AtomicBool::new(false),
+            sent: Mutex::new(Vec::new()),
+            receipts: Mutex::new(HashMap::new()),
+            native_balance: Mutex::new(U256::zero()),
+        }
+    }
+
+    /// 设置某个函数 (按签名) 的 eth_call 返回值
+    pub fn on_call(&self, signature: &str, tokens: &[Token]) {
+        self.script
+            .responses
+            .lock()
+            .unwrap()
+            .insert(id(signature), CallResponse::Fixed(Ok(Bytes::from(abi::encode(tokens)))));
+    }
+
+    /// 按调用内容 (参数、目标地址、区块、状态覆盖) 计算某个函数的返回值
+    pub fn on_call_fn(
+        &self,
+        signature: &str,
+        handler: impl Fn(&MockCall) -> Result<Vec<Token>, String> + Send + Sync + 'static,
+    ) {
+        self.script
+            .responses
+            .lock()
+            .unwrap()
+            .insert(id(signature), CallResponse::Handler(Box::new(handler)));
+    }
+
+    /// 按参数设置某个函数的 eth_call 返回值 (如不同账户的 balanceOf)
+    pub fn on_call_with_args(&self, signature: &str, args: &[Token], tokens: &[Token]) {
+        let mut calldata = id(signature).to_vec();
+        calldata.extend(abi::encode(args));
+        self.script
+            .calldata_responses
+            .lock()
+            .unwrap()
+            .insert(Bytes::from(calldata), Bytes::from(abi::encode(tokens)));
+    }
+
+    /// 设置某个函数的 eth_call 为 revert
+    pub fn on_call_revert(&self, signature: &str, reason: &str) {
+        self.script
+            .responses
+            .lock()
+            .unwrap()
+            .insert(id(signature), CallResponse::Fixed(Err(reason.to_string())));
+    }
+
+    /// 每次 eth_call 耗时 delay (用于观察并发)
+    pub fn set_call_delay(&self, delay: Duration) {
+        *self.script.delay.lock().unwrap() = Some(delay);
+    }
+
+    /// 设置了 eth_call 耗时后，同时在途的 eth_call 最大数量
+    pub fn max_concurrent_calls(&self) -> usize {
+        self.script.max_in_flight.load(Ordering::SeqCst)
+    }
+
+    pub fn set_code(&self, address: Address, code: Bytes) {
+        self.code.lock().unwrap().insert(address, code);
+    }
+
+    pub fn set_estimate_gas(&self, result: Result<U256, String>) {
+        *self.estimate_gas.lock().unwrap() = result;
+    }
+
+    pub fn set_gas_price(&self, gas_price: U256) {
+        *self.gas_price.lock().unwrap() = gas_price;
+    }
+
+    pub fn set_nonce(&self, nonce: U256) {
+        *self.nonce.lock().unwrap() = nonce;
+    }
+
+    /// 设置 get_block_number 依次返回的区块号
+    pub fn set_block_numbers(&self, block_numbers: &[u64]) {
+        *self.block_numbers.lock().unwrap() = block_numbers.iter().copied().collect();
+    }
+
+    pub fn set_send_hash(&self, hash: H256) {
+        *self.send_hash.lock().unwrap() = hash;
+    }
+
+    /// 让 send_transaction 永不返回
+    pub fn set_send_hangs(&self, hangs: bool) {
+        self.send_hangs.store(hangs, Ordering::Relaxed);
+    }
+
+    pub fn set_native_balance(&self, balance: U256) {
+        *self.native_balance.lock().unwrap() = balance;
+    }
+
+    pub fn set_receipt(&self, receipt: TransactionReceipt) {
+        self.receipts.lock().unwrap().insert(receipt.transaction_hash, receipt);
+    }
+
+    /// 某个函数收到的所有 eth_call
+    pub fn calls(&self, signature: &str) -> Vec<MockCall> {
+        let selector = id(signature);
+        self.script.log.lock().unwrap().iter().filter(|c| c.selector() == selector).cloned().collect()
+    }
+
+    /// 收到的所有 eth_call
+    pub fn all_calls(&self) -> Vec<MockCall> {
+        self.script.log.lock().unwrap().clone()
+    }
+
+    /// 某个函数被 eth_call 的次数
+    pub fn call_count(&self, signature: &str) -> usize {
+        self.calls(signature).len()
+    }
+
+    /// 某个函数每次被 eth_call 时指定的 gas limit
+    pub fn call_gas_limits(&self, signature: &str) -> Vec<Option<U256>> {
+        self.calls(signature).iter().map(|c| c.gas).collect()
+    }
+
+    /// get_block_number 被调用的次数
+    pub fn block_number_count(&self) -> usize {
+        self.block_number_calls.load(Ordering::Relaxed)
+    }
+
+    /// estimate_gas 被调用的次数
+    pub fn estimate_gas_count(&self) -> usize {
+        self.estimate_gas_calls.load(Ordering::Relaxed)
+    }
+
+    /// 已发送的交易
+    pub fn sent_transactions(&self) -> Vec<TypedTransaction> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Middleware for MockMiddleware {
+    type Error = ProviderError;
+    type Provider = MockRpc;
+    type Inner = Provider<MockRpc>;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn get_block_number(&self) -> Result<U64, Self::Error> {
+        self.block_number_calls.fetch_add(1, Ordering::Relaxed);
+        let mut block_numbers = self.block_numbers.lock().unwrap();
+        let block = if block_numbers.len() > 1 {
+            block_numbers.pop_front()
+        } else {
+            block_numbers.front().copied()
+        };
+        Ok(U64::from(block.unwrap_or_default()))
+    }
+
+    async fn get_balance<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        _from: T,
+        _block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        Ok(*self.native_balance.lock().unwrap())
+    }
+
+    async fn get_code<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        at: T,
+        _block: Option<BlockId>,
+    ) -> Result<Bytes, Self::Error> {
+        let code = match at.into() {
+            NameOrAddress::Address(address) => self.code.lock().unwrap().get(&address).cloned(),
+            NameOrAddress::Name(_) => None,
+        };
+        Ok(code.unwrap_or_default())
+    }
+
+    async fn get_gas_price(&self) -> Result<U256, Self::Error> {
+        Ok(*self.gas_price.lock().unwrap())
+    }
+
+    async fn get_transaction_count<T: Into<NameOrAddress> + Send + Sync>(
+        &self,
+        _from: T,
+        _block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        Ok(*self.nonce.lock().unwrap())
+    }
+
+    async fn estimate_gas(
+        &self,
+        _tx: &TypedTransaction,
+        _block: Option<BlockId>,
+    ) -> Result<U256, Self::Error> {
+        self.estimate_gas_calls.fetch_add(1, Ordering::Relaxed);
+        self.estimate_gas
+            .lock()
+            .unwrap()
+            .clone()
+            .map_err(|reason| ProviderError::CustomError(format!("execution reverted: {}", reason)))
+    }
+
+    /// 用脚本化的 nonce / gas / gas price 补全交易 (外层 SignerMiddleware 签名前调用)
+    async fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        if tx.nonce().is_none() {
+            tx.set_nonce(*self.nonce.lock().unwrap());
+        }
+        if tx.gas().is_none() {
+            let gas = self.estimate_gas(tx, block).await?;
+            tx.set_gas(gas);
+        }
+        let gas_price = *self.gas_price.lock().unwrap();
+        match tx {
+            TypedTransaction::Eip1559(inner) => {
+                inner.max_fee_per_gas.get_or_insert(gas_price);
+                inner.max_priority_fee_per_gas.get_or_insert(gas_price);
+            }
+            _ if tx.gas_price().is_none() => {
+                tx.set_gas_price(gas_price);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn call(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<Bytes, Self::Error> {
+        let call = MockCall {
+            to: tx.to_addr().copied(),
+            data: tx.data().cloned().unwrap_or_default(),
+            gas: tx.gas().copied(),
+            block,
+            state_override: None,
+        };
+        self.script.call(call).await.map_err(ProviderError::CustomError)
+    }
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        _block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        if self.send_hangs.load(Ordering::Relaxed) {
+            std::future::pending::<()>().await;
+        }
+        self.sent.lock().unwrap().push(tx.into());
+        let hash = *self.send_hash.lock().unwrap();
+        Ok(PendingTransaction::new(hash, &self.inner))
+    }
+
+    /// 外层 SignerMiddleware 签名后的交易: 解码后和 send_transaction 一样记录
+    async fn send_raw_transaction<'a>(
+        &'a self,
+        tx: Bytes,
+    ) -> Result<PendingTransaction<'a, Self::Provider>, Self::Error> {
+        if self.send_hangs.load(Ordering::Relaxed) {
+            std::future::pending::<()>().await;
+        }
+        let (tx, _) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(tx.as_ref()))
+            .map_err(|e| ProviderError::CustomError(format!("无法解码签名交易: {}", e)))?;
+        self.sent.lock().unwrap().push(tx);
+        let hash = *self.send_hash.lock().unwrap();
+        Ok(PendingTransaction::new(hash, &self.inner))
+    }
+
+    async fn get_transaction_receipt<T: Send + Sync + Into<TxHash>>(
+        &self,
+        transaction_hash: T,
+    ) -> Result<Option<TransactionReceipt>, Self::Error> {
+        let hash: TxHash = transaction_hash.into();
+        Ok(self.receipts.lock().unwrap().get(&hash).cloned())
+    }
+
+    async fn get_transaction<T: Send + Sync + Into<TxHash>>(
+        &self,
+        _transaction_hash: T,
+    ) -> Result<Option<Transaction>, Self::Error> {
+        Ok(None)
+    }
+}
+
+/// 三角套利参数夹具
+pub fn sample_params() -> ArbitrageParams {
+    ArbitrageParams {
+        flash_pool: Address::repeat_byte(0xf1),
+        flash_pool_fee: 500,
+        flash_token: None,
+        flash_convert_fee: 0,
+        flash_amount: U256::zero(),
+        token_a: Address::repeat_byte(0xa1),
+        token_b: Address::repeat_byte(0xb1),
+        token_c: Address::repeat_byte(0xc1),
+        fee1: 500,
+        fee2: 3000,
+        fee3: 500,
+        amount_in: U256::exp10(18),
+        min_profit: U256::zero(),
+        estimated_profit_usd: Decimal::from(10),
+        estimated_gas_cost_usd: Decimal::from(1),
+        estimated_flash_fee: U256::zero(),
+        profit_token: None,
+        profit_convert_fee: 0,
+        hop_quotes: None,
+        swap_pools: vec![
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        ],
+        bundle_privacy: None,
+    }
+}
+
+/// 模拟中继 / builder 端点: 只接受一次请求，记录请求体后按给定状态码返回
+pub async fn mock_endpoint(status: u16, body: &'static str, received: Arc<Mutex<Vec<String>>>) -> String {
+    mock_endpoint_sequence(vec![(status, body)], received).await
+}
+
+/// 模拟中继 / builder 端点: 依次接受 responses.len() 个请求，每个请求按顺序返回对应的响应，之后关闭
+pub async fn mock_endpoint_sequence(
+    responses: Vec<(u16, &'static str)>,
+    received: Arc<Mutex<Vec<String>>>,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        for (status, body) in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        received.lock().unwrap().push(text[header_end + 4..].to_string());
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, body.len(), body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    url
+}
+
+/// 交易回执夹具: 成功时带 ArbitrageExecuted 事件
+pub fn receipt(tx_hash: H256, success: bool, profit: U256) -> TransactionReceipt {
+    let event_signature = H256::from(keccak256(
+        b"ArbitrageExecuted(address,address,address,uint256,uint256,uint256)",
+    ));
+    let logs = if success {
+        vec![Log {
+            topics: vec![event_signature],
+            data: Bytes::from(abi::encode(&[
+                Token::Uint(U256::exp10(18)),
+                Token::Uint(U256::exp10(18) + profit),
+                Token::Uint(profit),
+            ])),
+            ..Default::default()
+        }]
+    } else {
+        vec![]
+    };
+
+    TransactionReceipt {
+        transaction_hash: tx_hash,
+        block_number: Some(U64::from(101)),
+        status: Some(U64::from(if success { 1 } else { 0 })),
+        gas_used: Some(U256::from(250_000u64)),
+        effective_gas_price: Some(U256::from(1_000_000_000u64)),
+        logs,
+        ..Default::default()
+    }
+}
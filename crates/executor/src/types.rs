@@ -5,6 +5,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::flashbots::{BundlePrivacy, BundleSimulation};
+
 /// 套利执行参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageParams {
@@ -14,6 +16,17 @@ pub struct ArbitrageParams {
     /// 用于精确计算闪电贷成本
     #[serde(default = "default_flash_pool_fee")]
     pub flash_pool_fee: u32,
+    /// 闪电贷借入代币 (None 表示直接借起始代币 token_a)
+    ///
+    /// 与 token_a 不同时，合约借入后先经转换池换成 token_a (第 0 跳)，还款前再换回
+    #[serde(default)]
+    pub flash_token: Option<Address>,
+    /// 转换池费率 (flash_token <-> token_a)
+    #[serde(default)]
+    pub flash_convert_fee: u32,
+    /// 借入的 flash_token 数量 (换成 token_a 后不少于 amount_in，不转换时为 0)
+    #[serde(default)]
+    pub flash_amount: U256,
     /// 起始代币 (借入并归还)
     pub token_a: Address,
     /// 中间代币 1
@@ -34,16 +47,22 @@ pub struct ArbitrageParams {
     pub estimated_profit_usd: Decimal,
     /// 预估 gas 成本 (USD)
     pub estimated_gas_cost_usd: Decimal,
-    /// 预估闪电贷费用 (wei)
+    /// 预估闪电贷费用 (借入代币最小单位)
     #[serde(default)]
     pub estimated_flash_fee: U256,
     /// 利润结算代币 (None 或 Address::zero() 表示不转换，保留原始代币)
     pub profit_token: Option<Address>,
     /// 利润转换池费率 (tokenA -> profitToken)
     pub profit_convert_fee: u32,
+    /// 各跳报价输出 [A->B, B->C, C->A]，执行器据此扣除滑点得到每跳最小输出
+    #[serde(default)]
+    pub hop_quotes: Option<[U256; 3]>,
     /// swap 路径中的池子地址 (用于验证闪电贷池不重复)
     #[serde(default)]
     pub swap_pools: Vec<Address>,
+    /// 本次 Bundle 的隐私级别 (None 时使用 Flashbots 配置的默认值，如高价值机会单独要求完全私密)
+    #[serde(default)]
+    pub bundle_privacy: Option<BundlePrivacy>,
 }
 
 fn default_flash_pool_fee() -> u32 {
@@ -71,6 +90,25 @@ pub struct ExecutionResult {
     pub block_number: u64,
 }
 
+/// 一次执行的结果
+#[derive(Debug, Clone)]
+pub enum ExecutionOutcome {
+    /// 交易已发送并等到回执 (含链上 revert)
+    Executed(ExecutionResult),
+    /// 仅模拟模式: Bundle 已提交中继模拟但按设计未发送，不是执行失败
+    SimulatedOnly(BundleSimulation),
+}
+
+impl ExecutionOutcome {
+    /// 已上链的执行结果，仅模拟时为 None
+    pub fn executed(&self) -> Option<&ExecutionResult> {
+        match self {
+            ExecutionOutcome::Executed(result) => Some(result),
+            ExecutionOutcome::SimulatedOnly(_) => None,
+        }
+    }
+}
+
 /// 执行错误类型
 #[derive(Debug, Error)]
 pub enum ExecutionError {
@@ -98,6 +136,12 @@ pub enum ExecutionError {
     #[error("Timeout waiting for transaction")]
     Timeout,
 
+    #[error("Timed out sending transaction after {0}s")]
+    SendTimeout(u64),
+
+    #[error("contract ABI mismatch: expected interface version {expected}, found {}", .found.map(|v| v.to_string()).unwrap_or_else(|| "none".to_string()))]
+    ContractAbiMismatch { expected: u64, found: Option<u64> },
+
     #[error("Flashbots error: {0}")]
     FlashbotsError(String),
 
@@ -107,10 +151,45 @@ pub enum ExecutionError {
     #[error("Flashbots simulation failed: {0}")]
     FlashbotsSimulationFailed(String),
 
+    #[error("Estimated gas cost ${cost_usd} exceeds max ${max_usd}")]
+    GasCostTooHigh { cost_usd: Decimal, max_usd: Decimal },
+
+    #[error("Gas price {gas_price} exceeds max {max_gas_price}")]
+    GasPriceTooHigh { gas_price: U256, max_gas_price: U256 },
+
+    #[error("Block advanced after simulation: simulated at {simulated}, now {current}")]
+    BlockAdvanced { simulated: u64, current: u64 },
+
+    #[error("No flash pool disjoint from swap path: borrow_token={borrow_token:?}, {overlapping} candidate(s) overlap")]
+    NoDisjointFlashPool { borrow_token: Address, overlapping: usize },
+
+    #[error("Insufficient flash liquidity in {pool:?}: holds {available} of {token:?}, needs {required}")]
+    InsufficientFlashLiquidity { pool: Address, token: Address, available: U256, required: U256 },
+
+    #[error("No pool to convert profit from {token_a:?} to {profit_token:?} (fee tier {fee:?})")]
+    NoProfitConversionPool { token_a: Address, profit_token: Address, fee: Option<u32> },
+
+    #[error("No pool to convert flash token {flash_token:?} to {token_a:?} (fee tier {fee:?})")]
+    NoFlashConversionPool { flash_token: Address, token_a: Address, fee: Option<u32> },
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
+impl ExecutionError {
+    /// 是否为路径本身的失败 (模拟或上链 revert、利润不足)；
+    /// RPC、钱包、中继、Gas 价格 / 费用上限、区块前进等与路径无关的问题返回 false
+    pub fn is_path_failure(&self) -> bool {
+        matches!(
+            self,
+            ExecutionError::TransactionReverted(_)
+                | ExecutionError::InsufficientProfit { .. }
+                | ExecutionError::GasEstimationFailed(_)
+                | ExecutionError::FlashbotsSimulationFailed(_)
+        )
+    }
+}
+
 /// 交易状态
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -140,6 +219,19 @@ pub struct GasStrategy {
     pub priority_fee_gwei: f64,
     /// 固定 gas limit (如果设置，跳过 gas 估算，强制使用此值)
     pub fixed_gas_limit: Option<u64>,
+    /// 单笔交易 gas 费上限 (USD)，按 gas_limit * gas_price 计算，超过则放弃发送 (与利润无关)
+    pub max_gas_cost_usd: Option<f64>,
+}
+
+impl GasStrategy {
+    /// 模拟阶段的默认策略: 估算值原样作为 gas limit，不额外加价
+    pub fn simulation() -> Self {
+        Self {
+            gas_price_multiplier: 1.0,
+            gas_limit_multiplier: 1.0,
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for GasStrategy {
@@ -151,6 +243,7 @@ impl Default for GasStrategy {
             use_eip1559: true,
             priority_fee_gwei: 0.01,   // 当前低 Gas 环境
             fixed_gas_limit: None,     // 默认动态估算
+            max_gas_cost_usd: None,    // 默认不限制
         }
     }
 }
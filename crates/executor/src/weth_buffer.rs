@@ -0,0 +1,216 @@
+//! 合约 WETH 缓冲维护
+//!
+//! 以 WETH 为起点的路径需要合约上有足够的 WETH。合约持有的 ETH (直接转账、`unwrapNative` 解包)
+//! 无法直接参与套利，这里定期检查 WETH 余额，低于缓冲值时调用合约的
+//! `wrapNative` 把 ETH 包装成 WETH；合约没有 ETH 可用时只告警。
+//! wrap 交易与套利执行共用同一个钱包，nonce 从共享的 [`NonceAllocator`] 分配
+
+use ethers::prelude::*;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::flash_arbitrage::FlashArbitrageContract;
+use crate::nonce::NonceAllocator;
+use crate::types::ExecutionError;
+
+abigen!(
+    WethBalance,
+    r#"[function balanceOf(address account) external view returns (uint256)]"#
+);
+
+/// WETH 缓冲配置
+#[derive(Debug, Clone)]
+pub struct WethBufferConfig {
+    /// Wrapped Native 代币地址 (WETH/WBNB 等)
+    pub weth: Address,
+    /// 合约上希望保持的 WETH 数量 (wei)
+    pub target_buffer: U256,
+    /// 干运行: 只检查和告警，不发送 wrap 交易
+    pub dry_run: bool,
+}
+
+/// 单次检查的结论
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WethBufferAction {
+    /// WETH 余额不低于缓冲值
+    Sufficient,
+    /// 需要把这么多 ETH 包装成 WETH (ETH 不够时只包装现有的部分)
+    Wrap(U256),
+    /// 低于缓冲值但合约没有可包装的 ETH
+    Short { shortfall: U256 },
+}
+
+/// 根据合约当前的 WETH / ETH 余额决定是否需要 wrap
+pub fn plan_weth_buffer(weth_balance: U256, native_balance: U256, target_buffer: U256) -> WethBufferAction {
+    if weth_balance >= target_buffer {
+        return WethBufferAction::Sufficient;
+    }
+    let shortfall = target_buffer - weth_balance;
+    if native_balance.is_zero() {
+        WethBufferAction::Short { shortfall }
+    } else {
+        WethBufferAction::Wrap(shortfall.min(native_balance))
+    }
+}
+
+/// 单次检查结果
+#[derive(Debug, Clone)]
+pub struct WethBufferOutcome {
+    pub weth_balance: U256,
+    pub native_balance: U256,
+    pub action: WethBufferAction,
+    /// 实际发出的 wrap 交易 (干运行或无需 wrap 时为 None)
+    pub tx_hash: Option<H256>,
+}
+
+/// 合约 WETH 缓冲管理器
+pub struct WethBufferManager<M: Middleware> {
+    contract_address: Address,
+    contract: FlashArbitrageContract<M>,
+    provider: Arc<M>,
+    config: WethBufferConfig,
+    nonces: Arc<NonceAllocator>,
+}
+
+impl<M: Middleware + 'static> WethBufferManager<M> {
+    /// provider 需要能签名发送交易 (合约 owner 钱包)
+    pub fn new(contract_address: Address, provider: Arc<M>, config: WethBufferConfig) -> Self {
+        Self {
+            contract_address,
+            contract: FlashArbitrageContract::new(contract_address, provider.clone()),
+            provider,
+            config,
+            nonces: Arc::new(NonceAllocator::new()),
+        }
+    }
+
+    /// 使用与套利执行共享的 nonce 分配器 (同一个钱包)
+    pub fn with_nonce_allocator(mut self, nonces: Arc<NonceAllocator>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    /// 检查一次，必要时发送 wrap 交易
+    pub async fn rebalance(&self) -> Result<WethBufferOutcome, ExecutionError> {
+        let weth_balance = WethBalance::new(self.config.weth, self.provider.clone())
+            .balance_of(self.contract_address)
+            .call()
+            .await
+            .map_err(|e| ExecutionError::ContractError(format!("{:?}", e)))?;
+        let native_balance = self
+            .provider
+            .get_balance(self.contract_address, None)
+            .await
+            .map_err(|e| ExecutionError::ProviderError(e.to_string()))?;
+
+        let action = plan_weth_buffer(weth_balance, native_balance, self.config.target_buffer);
+        let mut tx_hash = None;
+        match &action {
+            WethBufferAction::Sufficient => {}
+            WethBufferAction::Short { shortfall } => {
+                warn!(
+                    "⚠️ 合约 WETH 余额 {} 低于缓冲值 {} (缺口 {})，合约没有可包装的 ETH",
+                    weth_balance, self.config.target_buffer, shortfall
+                );
+            }
+            WethBufferAction::Wrap(amount) if self.config.dry_run => {
+                warn!(
+                    "🔸 干运行: 合约 WETH 余额 {} 低于缓冲值 {}，需要包装 {} wei ETH",
+                    weth_balance, self.config.target_buffer, amount
+                );
+            }
+            WethBufferAction::Wrap(amount) => {
+                let sender = self
+                    .provider
+                    .default_sender()
+                    .ok_or_else(|| ExecutionError::WalletError("provider 没有签名钱包".to_string()))?;
+                let nonce = self.nonces.allocate(self.provider.as_ref(), sender, 1).await?;
+                let mut call = self.contract.wrap_native(self.config.weth, *amount);
+                call.tx.set_nonce(nonce);
+                let pending = match call.send().await {
+                    Ok(pending) => pending,
+                    Err(e) => {
+                        self.nonces.release(nonce, 1).await;
+                        return Err(ExecutionError::ContractError(format!("{:?}", e)));
+                    }
+                };
+                info!(
+                    "🔁 合约 WETH 余额 {} 低于缓冲值 {}，已发送 wrap 交易: {} wei, tx={:?}",
+                    weth_balance, self.config.target_buffer, amount, *pending
+                );
+                tx_hash = Some(*pending);
+            }
+        }
+
+        Ok(WethBufferOutcome { weth_balance, native_balance, action, tx_hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockMiddleware, TEST_PRIVATE_KEY};
+    use ethers::abi::Token;
+
+    #[test]
+    fn test_plan_weth_buffer() {
+        let target = U256::exp10(18);
+        assert_eq!(plan_weth_buffer(target, U256::zero(), target), WethBufferAction::Sufficient);
+        assert_eq!(
+            plan_weth_buffer(U256::exp10(17), U256::exp10(18), target),
+            WethBufferAction::Wrap(target - U256::exp10(17))
+        );
+        // ETH 不够补齐缺口: 只包装现有的
+        assert_eq!(
+            plan_weth_buffer(U256::zero(), U256::exp10(17), target),
+            WethBufferAction::Wrap(U256::exp10(17))
+        );
+        assert_eq!(
+            plan_weth_buffer(U256::zero(), U256::zero(), target),
+            WethBufferAction::Short { shortfall: target }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrap_sent_when_contract_weth_below_buffer() {
+        let contract = Address::repeat_byte(0xc0);
+        let weth = Address::repeat_byte(0xee);
+        let config = |dry_run| WethBufferConfig { weth, target_buffer: U256::exp10(18), dry_run };
+
+        // mock WETH: 合约只有 0.2 WETH，但有 5 ETH
+        let mock = Arc::new(MockMiddleware::new());
+        mock.on_call("balanceOf(address)", &[Token::Uint(U256::exp10(17) * 2)]);
+        mock.set_native_balance(U256::exp10(18) * 5);
+        mock.set_nonce(U256::from(7));
+        let wallet = TEST_PRIVATE_KEY.parse::<LocalWallet>().unwrap().with_chain_id(1u64);
+        let signer = Arc::new(SignerMiddleware::new(mock.clone(), wallet.clone()));
+
+        // 干运行只告警
+        let outcome = WethBufferManager::new(contract, signer.clone(), config(true)).rebalance().await.unwrap();
+        assert_eq!(outcome.action, WethBufferAction::Wrap(U256::exp10(17) * 8));
+        assert!(outcome.tx_hash.is_none());
+        assert!(mock.sent_transactions().is_empty());
+
+        // 套利执行已经从共享分配器拿走了 nonce 7
+        let nonces = Arc::new(NonceAllocator::new());
+        assert_eq!(nonces.allocate(mock.as_ref(), wallet.address(), 1).await.unwrap(), U256::from(7));
+
+        // 非干运行: 向合约发送 wrapNative(weth, 0.8 ETH)，nonce 接在套利交易之后
+        let outcome = WethBufferManager::new(contract, signer, config(false))
+            .with_nonce_allocator(nonces)
+            .rebalance()
+            .await
+            .unwrap();
+        assert!(outcome.tx_hash.is_some());
+        let sent = mock.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to_addr(), Some(&contract));
+        assert_eq!(sent[0].nonce(), Some(&U256::from(8)));
+        let data = sent[0].data().unwrap();
+        assert_eq!(&data[0..4], &ethers::utils::id("wrapNative(address,uint256)"));
+        assert_eq!(
+            ethers::abi::decode(&[ethers::abi::ParamType::Address, ethers::abi::ParamType::Uint(256)], &data[4..]).unwrap(),
+            vec![Token::Address(weth), Token::Uint(U256::exp10(17) * 8)]
+        );
+    }
+}
@@ -11,7 +11,7 @@ use models::DexType;
 use rust_decimal::Decimal;
 use services::{
     BlockSubscriber, BlockSubscriberConfig, Database, PriceService, PriceServiceConfig,
-    ArbitrageConfigDb,
+    ArbitrageConfigDb, SharedTokenMetadataCache, TokenMetadataCache, get_event_sink, GasPriceSource, SwapLogFormat,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -26,13 +26,20 @@ use ::utils::{RpcStatsProvider, StatsHttp};
 // Solana 模块
 use solana_arb::{SolanaConfig, EventDrivenSolanaScanner};
 
+use crate::startup_checks;
+use crate::wallet::{self, WalletSource};
+
+/// 关闭时等待扫描器退出的最长时间
+const SCANNER_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// 单链服务句柄
 pub struct ChainServiceHandles {
-    #[allow(dead_code)]
     pub chain_id: u64,
     pub chain_name: String,
     pub block_handle: Option<JoinHandle<()>>,
     pub scanner_handle: Option<JoinHandle<()>>,
+    /// 事件驱动扫描器 (关闭时读取会话统计)
+    pub scanner: Option<Arc<EventDrivenScanner<Provider<Http>>>>,
 }
 
 /// 应用程序实例
@@ -46,7 +53,10 @@ pub struct Application {
     /// 各链的 RPC Provider
     #[allow(dead_code)]
     chain_providers: HashMap<u64, Arc<Provider<Http>>>,
+    /// 价格服务 (所有链共享一个实例)
     price_service: Arc<PriceService>,
+    /// 代币元数据缓存 (所有链共享，按 chain_id 隔离)
+    token_metadata: SharedTokenMetadataCache,
     strategy_manager: Arc<ArbitrageStrategyManager<Provider<StatsHttp>>>,
 
     // 后台任务句柄
@@ -68,9 +78,18 @@ impl Application {
         info!("加载配置文件...");
         let config = AppConfig::load()?;
         Self::log_config(&config);
+        startup_checks::ensure_chains_enabled(
+            &startup_checks::active_evm_chains(&config),
+            SolanaConfig::from_env().enabled,
+        )?;
 
         // 初始化数据库
         let database = Self::init_database(&config).await?;
+        startup_checks::warn_unconfigured_chains(
+            &config,
+            &ArbitrageConfigDb::new(database.pool().clone()),
+        )
+        .await;
 
         // 初始化钱包
         let wallet = Self::init_wallet(&config);
@@ -102,13 +121,16 @@ impl Application {
             }
         }
 
-        // 启动价格服务
+        // 启动价格服务 (所有链共享一个实例，相同符号只请求一次)
         let price_service = Arc::new(PriceService::new(PriceServiceConfig {
             update_interval_secs: 10,
             ..Default::default()
         }));
         let price_handle = Self::spawn_price_service(price_service.clone());
 
+        // 代币元数据缓存同样只创建一份，注入到每条链的扫描器
+        let token_metadata: SharedTokenMetadataCache = Arc::new(TokenMetadataCache::new());
+
         // 启动各链的事件驱动服务
         let mut chain_handles = Vec::new();
 
@@ -123,12 +145,13 @@ impl Application {
                 info!("========================================");
 
                 if let Some(provider) = chain_providers.get(chain_id) {
-                    let (block_handle, scanner_handle) = Self::start_chain_services(
+                    let (block_handle, scanner_handle, scanner) = Self::start_chain_services(
                         chain_config,
                         &config,
                         &database,
                         provider.clone(),
                         price_service.clone(),
+                        token_metadata.clone(),
                         wallet.clone(),
                     )
                     .await;
@@ -138,11 +161,18 @@ impl Application {
                         chain_name: chain_config.name.clone(),
                         block_handle,
                         scanner_handle,
+                        scanner,
                     });
                 }
             }
         }
 
+        info!(
+            "共享服务: 1 个价格服务实例 / 1 个代币元数据缓存 ({} 个代币) 服务 {} 条链",
+            token_metadata.len().await,
+            chain_handles.len()
+        );
+
         // 创建策略管理器 (暂时只支持主链)
         let strategy_manager = Self::create_strategy_manager(
             &config,
@@ -150,6 +180,18 @@ impl Application {
             eth_provider.clone(),
             wallet,
         );
+        // 策略启停时联动对应链扫描器的自动执行
+        for handles in &chain_handles {
+            if let Some(ref scanner) = handles.scanner {
+                strategy_manager
+                    .register_execution_control(handles.chain_id, scanner.clone())
+                    .await;
+            }
+        }
+        // 数据库里已停止的策略: 所在链的策略全部停止时，扫描器从启动起就不自动执行
+        if let Err(e) = strategy_manager.load_execution_states().await {
+            warn!("⚠️ 加载策略运行状态失败，自动执行按配置进行: {}", e);
+        }
 
         // 启动 Solana 扫描器（如果启用）
         let solana_scanner_handle = Self::start_solana_scanner(&config).await;
@@ -160,6 +202,7 @@ impl Application {
             rpc_stats_provider,
             chain_providers,
             price_service,
+            token_metadata,
             strategy_manager,
             price_handle,
             chain_handles,
@@ -175,7 +218,8 @@ impl Application {
             // 需要 clone database，因为 AppState::new 需要所有权
             Database::from_pool(self.database.pool().clone()),
             self.strategy_manager.clone(),
-        );
+        )
+        .with_admin_token(self.config.api.admin_token.clone());
 
         let app = api::create_server(
             app_state,
@@ -196,6 +240,11 @@ impl Application {
 
         self.strategy_manager.stop_all().await?;
         self.price_service.stop().await;
+        info!(
+            "价格服务累计上游请求 {} 次, 共享代币元数据 {} 个",
+            self.price_service.upstream_fetch_count(),
+            self.token_metadata.len().await
+        );
         self.rpc_stats_provider.stop();
 
         let _ = self.price_handle.await;
@@ -203,11 +252,33 @@ impl Application {
         // 停止所有 EVM 链的服务
         for handles in self.chain_handles {
             info!("停止 {} 链服务...", handles.chain_name);
+            if let Some(scanner) = &handles.scanner {
+                let summary = scanner.session_summary().await;
+                info!("\n{}", summary.format_report());
+                if let Some(report) = scanner.ab_report().await {
+                    info!("[{}] {}", handles.chain_name, report);
+                }
+                if self.config.database.persist_session_summary {
+                    match self.database.insert_session_summary(&summary).await {
+                        Ok(id) => info!("[{}] 会话汇总已写入数据库 (id={})", handles.chain_name, id),
+                        Err(e) => warn!("[{}] 会话汇总写入数据库失败: {}", handles.chain_name, e),
+                    }
+                }
+            }
+            // 订阅循环阻塞在 WebSocket 流上、只在重连间隙检查停止标记，先停止区块订阅再停止扫描器
             if let Some(handle) = handles.block_handle {
+                handle.abort();
                 let _ = handle.await;
             }
-            if let Some(handle) = handles.scanner_handle {
-                let _ = handle.await;
+            if let Some(scanner) = &handles.scanner {
+                scanner.stop().await;
+            }
+            if let Some(mut handle) = handles.scanner_handle {
+                // 扫描器收到下一个事件或通道关闭时才退出循环，等待超时后直接中止
+                if tokio::time::timeout(SCANNER_STOP_TIMEOUT, &mut handle).await.is_err() {
+                    warn!("[{}] 扫描器 {} 秒内未停止，强制中止", handles.chain_name, SCANNER_STOP_TIMEOUT.as_secs());
+                    handle.abort();
+                }
             }
         }
 
@@ -271,21 +342,23 @@ impl Application {
     }
 
     fn init_wallet(config: &AppConfig) -> Option<LocalWallet> {
-        if let Some(ref private_key) = config.wallet.private_key {
-            match private_key.parse::<LocalWallet>() {
-                Ok(w) => {
-                    let w = w.with_chain_id(config.ethereum.chain_id);
-                    info!("✅ 钱包私钥已配置");
-                    Some(w)
-                }
-                Err(e) => {
-                    warn!("⚠️  解析私钥失败: {} - 将以只读模式运行", e);
-                    None
+        match wallet::load_wallet(&config.wallet) {
+            Ok(Some((w, source))) => {
+                let w = w.with_chain_id(config.ethereum.chain_id);
+                match source {
+                    WalletSource::Keystore => info!("✅ 钱包已从 keystore 解密: {}", wallet::wallet_address(&w)),
+                    WalletSource::PrivateKey => info!("✅ 钱包私钥已配置: {}", wallet::wallet_address(&w)),
                 }
+                Some(w)
+            }
+            Ok(None) => {
+                warn!("⚠️  钱包私钥未配置 - 系统将以只读模式运行");
+                None
+            }
+            Err(e) => {
+                warn!("⚠️  {} - 将以只读模式运行", e);
+                None
             }
-        } else {
-            warn!("⚠️  钱包私钥未配置 - 系统将以只读模式运行");
-            None
         }
     }
 
@@ -315,6 +388,97 @@ impl Application {
         })
     }
 
+    /// 把按代币符号配置的执行数量策略解析为该链上的代币地址
+    async fn resolve_token_amount_strategies(
+        config_db: &ArbitrageConfigDb,
+        chain_id: u64,
+        chain_name: &str,
+        by_symbol: &HashMap<String, String>,
+    ) -> HashMap<ethers::types::Address, strategies::ExecutionAmountStrategy> {
+        let mut resolved = HashMap::new();
+        if by_symbol.is_empty() {
+            return resolved;
+        }
+
+        let tokens = match config_db.get_enabled_tokens(chain_id).await {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                warn!("[{}] 加载代币配置失败，按代币执行数量策略不生效: {}", chain_name, e);
+                return resolved;
+            }
+        };
+
+        for token in &tokens {
+            let Some(spec) = by_symbol.get(&token.symbol.to_uppercase()) else {
+                continue;
+            };
+            let Ok(address) = token.address.parse::<ethers::types::Address>() else {
+                continue;
+            };
+            match strategies::ExecutionAmountStrategy::parse(spec) {
+                Some(strategy) => {
+                    info!("[{}]    {} 执行数量策略: {:?}", chain_name, token.symbol, strategy);
+                    resolved.insert(address, strategy);
+                }
+                None => warn!("[{}] ⚠️ 无法解析 {} 的执行数量策略: {}", chain_name, token.symbol, spec),
+            }
+        }
+        resolved
+    }
+
+    /// 配置的闪电贷费率表 (扫描器和策略管理器共用)
+    fn flash_fee_table(config: &AppConfig) -> executor::FlashFeeTable {
+        executor::FlashFeeTable {
+            uniswap_v4: config.flash_loan.uniswap_v4_fee,
+            aave_v3: config.flash_loan.aave_v3_fee,
+            balancer: config.flash_loan.balancer_fee,
+        }
+    }
+
+    /// 把按代币符号配置的代币对 / 三角最小利润覆盖解析为本链代币地址 (本链缺少其中任一代币时跳过)
+    async fn resolve_min_profit_overrides(
+        config_db: &ArbitrageConfigDb,
+        chain_id: u64,
+        chain_name: &str,
+        by_pair: &HashMap<String, f64>,
+    ) -> strategies::MinProfitOverrides {
+        let mut overrides = strategies::MinProfitOverrides::default();
+        if by_pair.is_empty() {
+            return overrides;
+        }
+
+        let tokens = match config_db.get_enabled_tokens(chain_id).await {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                warn!("[{}] 加载代币配置失败，代币对最小利润覆盖不生效: {}", chain_name, e);
+                return overrides;
+            }
+        };
+        let address_of = |symbol: &str| {
+            tokens
+                .iter()
+                .find(|t| t.symbol.eq_ignore_ascii_case(symbol))
+                .and_then(|t| t.address.parse::<ethers::types::Address>().ok())
+        };
+
+        for (pair, min_profit) in by_pair {
+            let symbols: Vec<&str> = pair.split('/').map(str::trim).collect();
+            if !(2..=3).contains(&symbols.len()) {
+                warn!("[{}] ⚠️ 代币对最小利润覆盖需要 2 或 3 个代币: {}", chain_name, pair);
+                continue;
+            }
+            let Some(addresses) = symbols.iter().map(|s| address_of(s)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            let Some(min_profit) = Decimal::from_f64_retain(*min_profit) else {
+                continue;
+            };
+            info!("[{}]    {} 最小利润覆盖: ${}", chain_name, pair, min_profit);
+            overrides.insert(&addresses, min_profit);
+        }
+        overrides
+    }
+
     /// 启动单链的事件驱动服务 (区块订阅器 + 套利扫描器)
     async fn start_chain_services(
         chain_config: &ChainConfig,
@@ -322,24 +486,37 @@ impl Application {
         database: &Database,
         provider: Arc<Provider<Http>>,
         price_service: Arc<PriceService>,
+        token_metadata: SharedTokenMetadataCache,
         wallet: Option<LocalWallet>,
-    ) -> (Option<JoinHandle<()>>, Option<JoinHandle<()>>) {
+    ) -> (Option<JoinHandle<()>>, Option<JoinHandle<()>>, Option<Arc<EventDrivenScanner<Provider<Http>>>>) {
         let chain_id = chain_config.chain_id;
         let chain_name = &chain_config.name;
 
         if chain_config.ws_url.is_empty() {
             warn!("[{}] ⚠️ 未配置 WebSocket URL - 区块订阅器未启动", chain_name);
-            return (None, None);
+            return (None, None, None);
         }
 
         // 获取链合约配置
-        let chain_contracts = match ChainContractsConfig::for_chain(chain_id) {
+        let mut chain_contracts = match ChainContractsConfig::for_chain(chain_id) {
             Some(contracts) => contracts,
             None => {
                 warn!("[{}] ⚠️ 不支持的链 chain_id={}", chain_name, chain_id);
-                return (None, None);
+                return (None, None, None);
             }
         };
+        // Multicall3 以链配置为准 (部分链没有部署在通用地址)
+        match chain_config.contracts.multicall3.parse::<ethers::types::Address>() {
+            Ok(multicall) if multicall != chain_contracts.multicall_address => {
+                info!("[{}] 使用自定义 Multicall3 地址: {:?}", chain_name, multicall);
+                chain_contracts.multicall_address = multicall;
+            }
+            Ok(_) => {}
+            Err(_) => warn!(
+                "[{}] ⚠️ Multicall3 地址无效: {}，使用默认地址 {:?}",
+                chain_name, chain_config.contracts.multicall3, chain_contracts.multicall_address
+            ),
+        }
 
         // 从 arbitrage_pools 表加载套利池子
         info!("[{}] 从数据库加载套利池子配置...", chain_name);
@@ -352,6 +529,17 @@ impl Application {
 
         info!("[{}] 将监控 {} 个池子的 Swap 事件", chain_name, monitored_pool_addresses.len());
 
+        let swap_log_formats: Vec<SwapLogFormat> = app_config.arbitrage.swap_event_formats
+            .iter()
+            .filter_map(|value| {
+                let format = SwapLogFormat::parse(value);
+                if format.is_none() {
+                    warn!("[{}] ⚠️ 未知的 Swap 事件格式: {}，已忽略", chain_name, value);
+                }
+                format
+            })
+            .collect();
+
         // 创建区块订阅器
         info!("[{}] 启动区块订阅器...", chain_name);
         let block_subscriber = Arc::new(BlockSubscriber::new(BlockSubscriberConfig {
@@ -359,6 +547,9 @@ impl Application {
             chain_id,
             reconnect_delay_secs: 5,
             monitored_pools: monitored_pool_addresses,
+            swap_stall_timeout_secs: chain_config.swap_stall_timeout_secs,
+            swap_channel_capacity: app_config.arbitrage.swap_event_channel_capacity,
+            swap_log_formats,
         }));
 
         let swap_rx = block_subscriber.subscribe_swaps();
@@ -397,7 +588,7 @@ impl Application {
         // 如果没有配置任何代币，跳过该链
         if target_tokens.is_empty() {
             warn!("[{}] ⚠️ 没有配置任何代币，跳过扫描器启动", chain_name);
-            return (Some(block_handle), None);
+            return (Some(block_handle), None, None);
         }
 
         // 创建事件驱动扫描器
@@ -406,6 +597,37 @@ impl Application {
             .unwrap_or_else(|| Decimal::from(1));
         let skip_local_calc_threshold = Decimal::from_f64_retain(app_config.arbitrage.skip_local_calc_threshold_usd)
             .unwrap_or_else(|| Decimal::from(5000));
+        let max_price_impact = Decimal::from_f64_retain(app_config.arbitrage.max_price_impact_pct)
+            .unwrap_or(Decimal::ZERO);
+        let max_liquidity_fraction = Decimal::from_f64_retain(app_config.arbitrage.max_liquidity_fraction)
+            .unwrap_or(Decimal::ZERO);
+        let max_spot_deviation = Decimal::from_f64_retain(chain_config.price_sanity_max_deviation_pct)
+            .unwrap_or(Decimal::ZERO);
+        let profit_deviation_alert = Decimal::from_f64_retain(app_config.arbitrage.profit_deviation_alert_usd)
+            .unwrap_or(Decimal::ZERO);
+        let native_denomination = if app_config.arbitrage.native_denomination {
+            match chain_config.contracts.wrapped_native.parse() {
+                Ok(wrapped_native) => {
+                    warn!(
+                        "[{}] ⚠️ 原生代币计价模式: 利润门槛 {} {}，只有以 Wrapped Native 为起点的路径不依赖 USD 价格",
+                        chain_name, app_config.arbitrage.native_min_profit, chain_config.native_token
+                    );
+                    Some(strategies::NativeDenomination {
+                        wrapped_native,
+                        min_profit: Decimal::from_f64_retain(app_config.arbitrage.native_min_profit)
+                            .unwrap_or(Decimal::ZERO),
+                        min_swap_value: Decimal::from_f64_retain(app_config.arbitrage.native_min_swap_value)
+                            .unwrap_or(Decimal::ZERO),
+                    })
+                }
+                Err(_) => {
+                    warn!("[{}] Wrapped Native 地址无效，原生代币计价模式未启用", chain_name);
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
         // 构建动态利润门槛配置
         let dynamic_profit_config = strategies::DynamicProfitConfig {
@@ -422,28 +644,141 @@ impl Application {
         };
 
         // 构建执行器配置
-        let auto_execute = app_config.arbitrage.auto_execute.unwrap_or(false);
+        // 仅检测模式下不做任何执行准备: 不校验合约、不设置钱包、不维护 WETH 缓冲
+        let detection_only = app_config.arbitrage.detection_only;
+        let mut auto_execute = app_config.arbitrage.auto_execute.unwrap_or(false) && !detection_only;
         let dry_run = app_config.arbitrage.dry_run.unwrap_or(true);
+        let arbitrage_contract_str = chain_config.arbitrage_contract_or(app_config.wallet.arbitrage_contract_address.as_deref());
+        let arbitrage_contract: Option<ethers::types::Address> = arbitrage_contract_str.and_then(|s| s.parse().ok());
+
+        // 校验已部署合约的接口版本，不兼容时禁用自动执行 (否则每次执行都会莫名 revert)
+        // RPC 错误已在校验内重试，仍失败时同样禁用 (无法确认合约可用)
+        if auto_execute {
+            if let Some(contract) = arbitrage_contract {
+                match executor::check_contract_interface(contract, provider.clone()).await {
+                    Ok(version) => info!("[{}] ✅ 套利合约接口版本校验通过: v{}", chain_name, version),
+                    Err(e @ executor::ExecutionError::ProviderError(_)) => {
+                        error!("[{}] ❌ 无法校验套利合约接口 (合约 {:?}): {}，自动执行已禁用，请检查 RPC 后重启", chain_name, contract, e);
+                        auto_execute = false;
+                    }
+                    Err(e) => {
+                        error!("[{}] ❌ {} (合约 {:?})，自动执行已禁用，请重新部署合约", chain_name, e, contract);
+                        auto_execute = false;
+                    }
+                }
+            }
+        }
+
+        // 灰度中的其它合约: 同样校验接口，未通过的不允许路径选用
+        let mut path_contracts: Vec<ethers::types::Address> = arbitrage_contract.into_iter().collect();
+        for value in &chain_config.extra_arbitrage_contracts {
+            let Ok(contract) = value.parse::<ethers::types::Address>() else {
+                warn!("[{}] ⚠️ 无效的额外套利合约地址: {}", chain_name, value);
+                continue;
+            };
+            if auto_execute {
+                if let Err(e) = executor::check_contract_interface(contract, provider.clone()).await {
+                    error!("[{}] ❌ {} (合约 {:?})，该合约不会被路径使用", chain_name, e, contract);
+                    continue;
+                }
+            }
+            info!("[{}] 🧪 额外套利合约: {:?}", chain_name, contract);
+            path_contracts.push(contract);
+        }
+
+        // 借入代币的首选闪电贷池: 0xToken:0xPool
+        let mut preferred_flash_pools = std::collections::HashMap::new();
+        for entry in &chain_config.preferred_flash_pools {
+            let parsed = entry.split_once(':').and_then(|(token, pool)| {
+                let token = token.trim().parse::<ethers::types::Address>().ok()?;
+                let pool = pool.trim().parse::<ethers::types::Address>().ok()?;
+                Some((token, pool))
+            });
+            match parsed {
+                Some((token, pool)) => {
+                    info!("[{}] 🏦 首选闪电贷池: {:?} → {:?}", chain_name, token, pool);
+                    preferred_flash_pools.insert(token, pool);
+                }
+                None => warn!("[{}] ⚠️ 无效的首选闪电贷池配置: {}", chain_name, entry),
+            }
+        }
+
+        // 利润结算代币: 格式错误时保留起始代币
+        let profit_token = chain_config.profit_token.as_deref().and_then(|token| match token.trim().parse::<ethers::types::Address>() {
+            Ok(address) => {
+                info!("[{}] 💱 利润结算代币: {:?} (转换费率: {:?})", chain_name, address, chain_config.profit_convert_fee);
+                Some(address)
+            }
+            Err(_) => {
+                warn!("[{}] ⚠️ 无效的利润结算代币配置: {}", chain_name, token);
+                None
+            }
+        });
+
+        // 闪电贷借入代币: 格式错误时直接借起始代币
+        let flash_token = chain_config.flash_token.as_deref().and_then(|token| match token.trim().parse::<ethers::types::Address>() {
+            Ok(address) => {
+                info!("[{}] 🏦 闪电贷借入代币: {:?} (转换费率: {:?})", chain_name, address, chain_config.flash_convert_fee);
+                Some(address)
+            }
+            Err(_) => {
+                warn!("[{}] ⚠️ 无效的闪电贷借入代币配置: {}", chain_name, token);
+                None
+            }
+        });
 
+        let flashbots_privacy = executor::BundlePrivacy::parse(&app_config.mev.flashbots_privacy).unwrap_or_else(|| {
+            warn!("[{}] ⚠️ 未知的 FLASHBOTS_PRIVACY: {}，使用中继默认", chain_name, app_config.mev.flashbots_privacy);
+            executor::BundlePrivacy::default()
+        });
+        // 每条链使用自己的私有中继，未单独配置时回退到全局 FLASHBOTS_RPC_URL / FLASHBOTS_SIGNER_KEY
+        let (relay_url, flashbots_signer_key) = app_config.mev.relay_for_chain(chain_id);
+        let simulation_state_overrides = chain_config.simulation_state_overrides.as_deref().and_then(|json| {
+            executor::parse_state_overrides(json)
+                .map_err(|e| warn!("[{}] ⚠️ {}，模拟执行不使用状态覆盖", chain_name, e))
+                .ok()
+        });
         let executor_config = strategies::ScannerExecutorConfig {
             auto_execute,
-            arbitrage_contract: app_config.wallet.arbitrage_contract_address
-                .as_ref()
-                .and_then(|s| s.parse().ok()),
+            arbitrage_contract,
             max_gas_price_gwei: app_config.arbitrage.max_gas_price_gwei.unwrap_or(0.08),
             use_flashbots: app_config.mev.use_flashbots,
-            flashbots_rpc_url: if app_config.mev.use_flashbots {
-                Some(app_config.mev.flashbots_rpc.clone()
-                    .unwrap_or_else(|| "https://relay.flashbots.net".to_string()))
+            flashbots_rpc_url: if app_config.mev.use_flashbots || app_config.mev.flashbots_simulate_only {
+                Some(relay_url.unwrap_or_else(|| "https://relay.flashbots.net".to_string()))
             } else {
                 None
             },
+            flashbots_builders: app_config.mev.flashbots_builders.clone(),
+            flashbots_privacy,
+            flashbots_signer_key,
             use_public_mempool: app_config.mev.use_public_mempool,
+            flashbots_simulate_only: app_config.mev.flashbots_simulate_only,
             dry_run,
+            detection_only,
             priority_fee_gwei: app_config.mev.priority_fee_gwei.unwrap_or(0.005),
             // 默认使用 80% 的最优输入金额
             amount_strategy: strategies::ExecutionAmountStrategy::Percentage(0.8),
+            token_amount_strategies: Self::resolve_token_amount_strategies(
+                &config_db, chain_id, chain_name, &app_config.arbitrage.amount_strategy_by_token,
+            ).await,
             simulate_before_execute: true,
+            abort_if_block_advanced: chain_config.abort_if_block_advanced,
+            fixed_gas_limit: chain_config.fixed_gas_limit,
+            use_eip1559: chain_config.eip1559,
+            simulation_gas_limit_multiplier: app_config.arbitrage.simulation_gas_limit_multiplier,
+            send_timeout_secs: app_config.arbitrage.send_timeout_secs,
+            max_gas_cost_usd: app_config.arbitrage.max_gas_cost_usd,
+            balance_snapshot_confirmations: chain_config.balance_snapshot_confirmations,
+            fetch_balances: app_config.arbitrage.balance_fetch_enabled,
+            max_concurrent_balance_fetches: app_config.arbitrage.max_concurrent_balance_fetches,
+            max_concurrent_executions: app_config.arbitrage.max_concurrent_executions,
+            priority_min_profit_usd: app_config.arbitrage.priority_min_profit_usd,
+            max_in_flight_bundles: app_config.mev.max_in_flight_bundles,
+            simulation_state_overrides,
+            profit_token,
+            profit_convert_fee: chain_config.profit_convert_fee,
+            flash_token,
+            flash_convert_fee: chain_config.flash_convert_fee,
         };
 
         // 输出配置
@@ -451,12 +786,41 @@ impl Application {
         info!("[{}]    最大滑点: {}% ({})", chain_name, app_config.arbitrage.max_slippage * 100.0, app_config.arbitrage.max_slippage);
         info!("[{}]    最小交易金额过滤阈值: ${}", chain_name, min_swap_value);
         info!("[{}]    跳过本地计算阈值: ${} (超过此金额直接链上计算)", chain_name, skip_local_calc_threshold);
+        if chain_config.local_calc_error_tolerance_bps > 0 {
+            info!("[{}]    自适应阈值: 本地估算平均偏差 > {}bp 的池子 (至少 {} 个样本) 按比例下调", chain_name,
+                chain_config.local_calc_error_tolerance_bps, app_config.arbitrage.local_calc_min_samples);
+        }
+        info!("[{}]    多跳报价交叉验证: {} (容忍 {}bp)", chain_name, app_config.arbitrage.multi_hop_cross_check, app_config.arbitrage.multi_hop_tolerance_bps);
+        if detection_only {
+            info!("[{}]    仅检测模式: 只记录机会，不构建执行参数", chain_name);
+        }
         info!("[{}]    自动执行: {}", chain_name, auto_execute);
+        if auto_execute && (chain_config.startup_warmup_blocks > 0 || app_config.arbitrage.startup_warmup_secs > 0) {
+            info!("[{}]    启动预热: {} 个区块 / {} 秒", chain_name,
+                chain_config.startup_warmup_blocks, app_config.arbitrage.startup_warmup_secs);
+        }
+        match chain_config.fixed_gas_limit {
+            Some(limit) => info!("[{}]    Gas Limit: 固定 {} (跳过 estimate_gas)", chain_name, limit),
+            None => info!("[{}]    Gas Limit: 动态估算", chain_name),
+        }
+        info!("[{}]    交易类型: {}", chain_name, if chain_config.eip1559 { "EIP-1559 (type-2)" } else { "legacy" });
+        let gas_price_source = GasPriceSource::from_config(
+            &chain_config.gas_price_source,
+            chain_config.gas_oracle_url.as_deref(),
+            chain_config.gas_oracle_api_key.as_deref(),
+        )
+        .unwrap_or_else(|e| {
+            warn!("[{}] ⚠️ {}，改用节点 gas price", chain_name, e);
+            GasPriceSource::Node
+        });
+        info!("[{}]    Gas Price 来源: {}", chain_name, gas_price_source.name());
         info!("[{}]    干运行模式: {}", chain_name, dry_run);
         info!("[{}]    使用Flashbots: {}", chain_name, app_config.mev.use_flashbots);
         info!("[{}]    使用公开Mempool: {}", chain_name, app_config.mev.use_public_mempool);
         // 计算发送模式
-        let send_mode_desc = if app_config.mev.use_flashbots && app_config.mev.use_public_mempool {
+        let send_mode_desc = if app_config.mev.flashbots_simulate_only {
+            "Flashbots 仅模拟 (只提交中继模拟，不发送)"
+        } else if app_config.mev.use_flashbots && app_config.mev.use_public_mempool {
             "Both (同时发送到Flashbots和公开Mempool)"
         } else if app_config.mev.use_flashbots {
             "Flashbots (仅私密发送)"
@@ -464,7 +828,7 @@ impl Application {
             "Normal (仅公开Mempool)"
         };
         info!("[{}]    发送模式: {}", chain_name, send_mode_desc);
-        if let Some(ref addr) = app_config.wallet.arbitrage_contract_address {
+        if let Some(addr) = arbitrage_contract_str {
             info!("[{}]    套利合约: {}", chain_name, addr);
         }
 
@@ -487,17 +851,101 @@ impl Application {
             enable_dynamic_profit: true,
             min_swap_value_usd: min_swap_value,
             skip_local_calc_threshold_usd: skip_local_calc_threshold,
+            local_calc_error_tolerance_bps: chain_config.local_calc_error_tolerance_bps,
+            local_calc_min_samples: app_config.arbitrage.local_calc_min_samples,
             executor_config,
             max_concurrent_handlers: 5, // 最多同时处理 5 个 swap 事件
+            multi_hop_cross_check: app_config.arbitrage.multi_hop_cross_check,
+            multi_hop_tolerance_bps: app_config.arbitrage.multi_hop_tolerance_bps,
+            execution_batch_window_ms: chain_config.execution_batch_window_ms,
+            dead_pool_after_blocks: app_config.arbitrage.dead_pool_after_blocks,
+            dead_pool_refresh_interval: app_config.arbitrage.dead_pool_refresh_interval_blocks,
+            startup_warmup_blocks: chain_config.startup_warmup_blocks,
+            startup_warmup_secs: app_config.arbitrage.startup_warmup_secs,
+            native_token_symbol: chain_config.native_token.clone(),
+            reference_stablecoin: chain_config.reference_stablecoin.clone(),
+            max_price_impact_pct: max_price_impact,
+            swap_lag_newest_only: app_config.arbitrage.swap_lag_newest_only,
+            quote_retry_attempts: app_config.arbitrage.quote_retry_attempts,
+            quote_retry_delay_ms: app_config.arbitrage.quote_retry_delay_ms,
+            fee_tier_whitelist: strategies::FeeTierWhitelist::from_config(
+                &chain_config.fee_tier_whitelist,
+                &app_config.arbitrage.stable_pair_fee_tiers,
+                &app_config.arbitrage.volatile_pair_fee_tiers,
+            ),
+            path_eval_budget_ms: app_config.arbitrage.path_eval_budget_ms,
+            path_eval_concurrency: app_config.arbitrage.path_eval_concurrency,
+            evaluate_both_directions: app_config.arbitrage.static_path_both_directions,
+            legacy_enumeration_enabled: chain_config.legacy_enumeration_enabled,
+            legacy_max_pool3_candidates: chain_config.legacy_max_pool3_candidates,
+            gas_price_source,
+            max_liquidity_fraction,
+            path_failure_threshold: app_config.arbitrage.path_failure_threshold,
+            path_failure_cooldown_secs: app_config.arbitrage.path_failure_cooldown_secs,
+            no_opportunity_alert_secs: app_config.arbitrage.no_opportunity_alert_secs,
+            post_execution_pool_cooldown_secs: app_config.arbitrage.post_execution_pool_cooldown_secs,
+            profit_deviation_alert_usd: profit_deviation_alert,
+            max_spot_deviation_pct: max_spot_deviation,
+            native_denomination,
+            preferred_flash_pools,
+            flash_fee_table: Self::flash_fee_table(app_config),
+            min_profit_overrides: Self::resolve_min_profit_overrides(
+                &config_db, chain_id, chain_name, &app_config.arbitrage.min_profit_by_pair,
+            ).await,
+            paper_trading: false,
+            stable_swap_pools_enabled: app_config.arbitrage.stable_swap_pools_enabled,
         };
 
-        // 使用链特定的合约配置创建扫描器
-        let event_scanner = Arc::new(EventDrivenScanner::with_chain_config(
-            scanner_config,
-            provider,
-            price_service,
-            chain_contracts,
-        ));
+        // A/B 对比候选组: 同一套配置改用固定门槛，不走 Gas 分档和按代币对覆盖
+        let ab_candidate_config = app_config.arbitrage.ab_candidate_min_profit_usd.map(|min_profit| {
+            EventDrivenScannerConfig {
+                enable_dynamic_profit: false,
+                min_profit_usd: Decimal::from_f64_retain(min_profit).unwrap_or_default(),
+                min_profit_overrides: Default::default(),
+                ..scanner_config.clone()
+            }
+        });
+
+        // 使用链特定的合约配置创建扫描器 (池子状态按链隔离，价格/代币元数据共享)
+        let event_scanner = Arc::new(
+            EventDrivenScanner::with_chain_config(
+                scanner_config,
+                provider.clone(),
+                price_service,
+                chain_contracts,
+            )
+            .with_token_metadata(token_metadata)
+            .with_event_sink(get_event_sink())
+            .with_database(Database::from_pool(database.pool().clone())),
+        );
+
+        // 合约 Wrapped Native 缓冲维护 (可选，wrap 交易与套利执行共用扫描器的 nonce 分配器)
+        if let (Some(contract), Some(w)) = (arbitrage_contract.filter(|_| !detection_only), wallet.as_ref()) {
+            Self::spawn_wrapped_native_buffer(
+                chain_config,
+                app_config.arbitrage.wrapped_native_check_secs,
+                provider,
+                w.clone().with_chain_id(chain_id),
+                contract,
+                dry_run,
+                event_scanner.nonce_allocator(),
+            );
+        }
+
+        Self::spawn_rpc_stats_snapshots(
+            chain_config,
+            app_config.database.rpc_stats_snapshot_secs,
+            event_scanner.clone(),
+            Database::from_pool(database.pool().clone()),
+        );
+
+        // 交易黑名单需在加载池子和路径之前设置
+        event_scanner
+            .set_blocklist(strategies::TradingBlocklist::from_addresses(
+                &chain_config.token_blocklist,
+                &chain_config.pool_blocklist,
+            ))
+            .await;
 
         // 加载代币配置到 scanner
         match config_db.get_enabled_tokens(chain_id).await {
@@ -554,6 +1002,10 @@ impl Application {
             }
         }
 
+        // 加载套利池子到扫描器 (需在路径映射之前，路径校验依赖池子代币)
+        let pool_count = Self::load_pools_to_scanner_generic(&event_scanner, pools).await;
+        info!("[{}] 事件驱动扫描器已加载 {} 个套利池子", chain_name, pool_count);
+
         // 加载池子-路径映射配置到扫描器
         info!("[{}] 从数据库加载池子-路径映射配置...", chain_name);
         match config_db.get_all_pool_paths(chain_id).await {
@@ -583,12 +1035,37 @@ impl Application {
                         Err(_) => { skipped += 1; continue; }
                     };
 
+                    // 三个池子都填写了才做校验，否则由扫描器按代币动态选池
+                    let pools = match (path.pool1.parse(), path.pool2.parse(), path.pool3.parse()) {
+                        (Ok(p1), Ok(p2), Ok(p3)) => Some([p1, p2, p3]),
+                        _ => None,
+                    };
+
+                    // 未填写时使用默认合约；只允许本链配置过的合约，避免发到未校验的地址
+                    let path_contract = match path.arbitrage_contract.parse::<ethers::types::Address>() {
+                        Ok(contract) if path_contracts.contains(&contract) => Some(contract),
+                        Ok(contract) => {
+                            warn!("[{}] ⚠️ 路径 {} 指定的套利合约 {:?} 未在本链配置，改用默认合约",
+                                chain_name, path.path_name, contract);
+                            None
+                        }
+                        Err(_) => None,
+                    };
+
                     let path_config = strategies::PoolPathConfig {
                         path_name: path.path_name,
                         triangle_name: path.triangle_name,
                         token_a,
                         token_b,
                         token_c,
+                        pools,
+                        // 未填写时为空字符串，解析失败即自动选择
+                        flash_pool: path.flash_pool.parse().ok(),
+                        // 未填写或为 0 时按触发 swap 金额计算
+                        fixed_input_amount: ethers::types::U256::from_dec_str(&path.fixed_input_amount)
+                            .ok()
+                            .filter(|amount| !amount.is_zero()),
+                        arbitrage_contract: path_contract,
                         priority: path.priority,
                     };
 
@@ -615,16 +1092,52 @@ impl Application {
             }
         }
 
-        // 加载套利池子到扫描器
-        let pool_count = Self::load_pools_to_scanner_generic(&event_scanner, pools).await;
-        info!("[{}] 事件驱动扫描器已加载 {} 个套利池子", chain_name, pool_count);
+        // 映射只覆盖了部分池子时，其余池子触发会走开销大、噪声多的动态枚举 (关闭回退时直接忽略)
+        let unmapped_pools = event_scanner.pools_without_path_mappings().await;
+        if !unmapped_pools.is_empty() {
+            warn!(
+                "[{}] ⚠️ {} 个启用的池子没有路径映射，触发时将{}: {:?}",
+                chain_name,
+                unmapped_pools.len(),
+                if chain_config.legacy_enumeration_enabled { "回退到动态枚举" } else { "被忽略 (动态枚举已关闭)" },
+                unmapped_pools
+            );
+        }
+
+        // 合约自己的 minProfitThreshold 比链下门槛严格时，利润介于两者之间的机会发出去必然 revert
+        if auto_execute {
+            for contract in &path_contracts {
+                match event_scanner.contract_min_profit_conflicts(*contract).await {
+                    Ok(conflicts) => {
+                        for c in conflicts {
+                            warn!(
+                                "[{}] ⚠️ 路径 {} 的链下利润门槛 {} ({} {} wei) 低于合约 {:?} 的 minProfitThreshold ({} wei)，\
+                                 利润介于两者之间的机会会被合约 revert",
+                                chain_name, c.path_name, c.off_chain_min_profit, c.off_chain_wei, c.token_symbol,
+                                contract, c.on_chain_wei
+                            );
+                        }
+                    }
+                    Err(e) => warn!("[{}] ⚠️ 无法读取合约 {:?} 的利润阈值: {}", chain_name, contract, e),
+                }
+            }
+        }
 
-        // 如果启用了自动执行并且有钱包，设置钱包到扫描器
-        if app_config.arbitrage.auto_execute.unwrap_or(false) {
-            if let (Some(w), Some(pk)) = (wallet, app_config.wallet.private_key.clone()) {
+        // 候选组复制已加载的代币/路径配置，需在加载完成之后创建
+        if let Some(candidate_config) = ab_candidate_config {
+            let candidate_name = format!("min_profit=${}", candidate_config.min_profit_usd);
+            let candidate = Arc::new(event_scanner.paper_trading_replica(candidate_config).await);
+            event_scanner
+                .set_ab_harness(Arc::new(strategies::AbHarness::new("线上门槛", &candidate_name, candidate)))
+                .await;
+        }
+
+        // 如果启用了自动执行 (且合约接口校验通过) 并且有钱包，设置钱包到扫描器
+        if auto_execute {
+            if let Some(w) = wallet {
                 // 克隆钱包并设置正确的 chain_id
                 let chain_wallet = w.with_chain_id(chain_id);
-                event_scanner.set_wallet(chain_wallet, pk).await;
+                event_scanner.set_wallet(chain_wallet).await;
                 info!("[{}] ✅ 钱包已设置到扫描器，自动执行已启用", chain_name);
             } else {
                 warn!("[{}] ⚠️ 自动执行已启用但钱包未配置，将以干运行模式运行", chain_name);
@@ -639,7 +1152,90 @@ impl Application {
             }
         });
 
-        (Some(block_handle), Some(scanner_handle))
+        (Some(block_handle), Some(scanner_handle), Some(event_scanner))
+    }
+
+    /// 定期检查合约的 Wrapped Native 余额，低于缓冲值时用合约里的 ETH 补足 (未配置缓冲时不启动)
+    fn spawn_wrapped_native_buffer(
+        chain_config: &ChainConfig,
+        check_secs: u64,
+        provider: Arc<Provider<Http>>,
+        wallet: LocalWallet,
+        contract: ethers::types::Address,
+        dry_run: bool,
+        nonces: Arc<executor::NonceAllocator>,
+    ) {
+        let chain_name = chain_config.name.clone();
+        if chain_config.wrapped_native_buffer <= 0.0 {
+            return;
+        }
+        let weth: ethers::types::Address = match chain_config.contracts.wrapped_native.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                warn!("[{}] ⚠️ Wrapped Native 地址无效，跳过 WETH 缓冲维护", chain_name);
+                return;
+            }
+        };
+        let target_buffer = match ethers::utils::parse_ether(chain_config.wrapped_native_buffer) {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!("[{}] ⚠️ WRAPPED_NATIVE_BUFFER 无效: {}，跳过 WETH 缓冲维护", chain_name, e);
+                return;
+            }
+        };
+
+        info!(
+            "[{}] 🔁 WETH 缓冲维护已启用: 目标 {} {}, 每 {} 秒检查{}",
+            chain_name, chain_config.wrapped_native_buffer, chain_config.native_token, check_secs,
+            if dry_run { " (干运行，仅告警)" } else { "" }
+        );
+        let signer = Arc::new(SignerMiddleware::new(provider, wallet));
+        let manager = executor::WethBufferManager::new(
+            contract,
+            signer,
+            executor::WethBufferConfig { weth, target_buffer, dry_run },
+        )
+        .with_nonce_allocator(nonces);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(check_secs.max(1)));
+            loop {
+                interval.tick().await;
+                if let Err(e) = manager.rebalance().await {
+                    warn!("[{}] ⚠️ WETH 缓冲检查失败: {}", chain_name, e);
+                }
+            }
+        });
+    }
+
+    /// 定期把扫描器的 RPC 区间用量写入 rpc_stats_snapshots 表 (间隔为 0 时不启动)
+    fn spawn_rpc_stats_snapshots(
+        chain_config: &ChainConfig,
+        interval_secs: u64,
+        scanner: Arc<EventDrivenScanner<Provider<Http>>>,
+        database: Database,
+    ) {
+        if interval_secs == 0 {
+            return;
+        }
+        let chain_id = chain_config.chain_id;
+        let chain_name = chain_config.name.clone();
+        info!("[{}] 📈 RPC 用量快照已启用: 每 {} 秒写入 rpc_stats_snapshots", chain_name, interval_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            // 第一次 tick 立即返回，跳过它让每个快照都覆盖完整区间
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let usage = scanner.take_rpc_usage();
+                if usage.is_empty() {
+                    continue;
+                }
+                // 写入失败时该区间的数据丢弃，不影响后续区间
+                if let Err(e) = database.insert_rpc_usage(chain_id, interval_secs, &usage).await {
+                    warn!("[{}] ⚠️ RPC 用量快照写入失败: {}", chain_name, e);
+                }
+            }
+        });
     }
 
     /// 加载套利池子到扫描器 (泛型版本)
@@ -656,6 +1252,7 @@ impl Application {
                 "uniswap_v2" => DexType::UniswapV2,
                 "sushiswap_v2" => DexType::SushiSwapV2,
                 "pancakeswap_v2" => DexType::PancakeSwapV2,
+                "pancakeswap_stable" => DexType::PancakeSwapStable,
                 _ => continue,
             };
 
@@ -670,7 +1267,11 @@ impl Application {
                 sqrt_price_x96: None,
                 liquidity: None,
                 tick: None,
+                unlocked: true,
+                stable_state: None,
                 last_block: 0,
+                last_swap_block: 0,
+                local_accuracy: Default::default(),
                 last_updated: std::time::Instant::now(),
             };
             scanner.add_pool(pool_state).await;
@@ -704,6 +1305,7 @@ impl Application {
                 "uniswap_v2" => DexType::UniswapV2,
                 "sushiswap_v2" => DexType::SushiSwapV2,
                 "pancakeswap_v2" => DexType::PancakeSwapV2,
+                "pancakeswap_stable" => DexType::PancakeSwapStable,
                 _ => continue,
             };
 
@@ -718,7 +1320,11 @@ impl Application {
                 sqrt_price_x96: None,
                 liquidity: None,
                 tick: None,
+                unlocked: true,
+                stable_state: None,
                 last_block: 0,
+                last_swap_block: 0,
+                local_accuracy: Default::default(),
                 last_updated: std::time::Instant::now(),
             };
             scanner.add_pool(pool_state).await;
@@ -734,11 +1340,12 @@ impl Application {
         wallet: Option<LocalWallet>,
     ) -> Arc<ArbitrageStrategyManager<Provider<StatsHttp>>> {
         let executor_settings = ExecutorSettings {
-            arbitrage_contract: config
-                .wallet
-                .arbitrage_contract_address
-                .as_ref()
-                .and_then(|s| s.parse().ok()),
+            // 策略管理器只跑以太坊主网
+            arbitrage_contract: match config.chains.get(&1) {
+                Some(chain) => chain.arbitrage_contract_or(config.wallet.arbitrage_contract_address.as_deref()),
+                None => config.wallet.arbitrage_contract_address.as_deref(),
+            }
+            .and_then(|s| s.parse().ok()),
             max_gas_price_gwei: config.arbitrage.max_gas_price_gwei.unwrap_or(100.0),
             use_flashbots: config.mev.use_flashbots,
             flashbots_rpc_url: if config.mev.use_flashbots {
@@ -755,9 +1362,11 @@ impl Application {
             use_public_mempool: config.mev.use_public_mempool,
             dry_run: config.arbitrage.dry_run.unwrap_or(true),
             priority_fee_gwei: config.mev.priority_fee_gwei.unwrap_or(2.0),
+            abort_if_block_advanced: config.chains.get(&1).map(|chain| chain.abort_if_block_advanced).unwrap_or(true),
+            flash_fee_table: Self::flash_fee_table(config),
         };
 
-        let auto_execute = config.arbitrage.auto_execute.unwrap_or(false);
+        let auto_execute = config.arbitrage.auto_execute.unwrap_or(false) && !config.arbitrage.detection_only;
 
         let manager = Arc::new(ArbitrageStrategyManager::new(
             database.pool().clone(),
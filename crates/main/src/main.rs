@@ -1,19 +1,54 @@
 mod bootstrap;
+mod pool_config;
+mod selftest;
+mod startup_checks;
+mod wallet;
 
 use anyhow::Result;
+use config_crate::{AppConfig, LogConfig};
 use tracing::info;
-use utils::LoggerManager;
+use utils::{LogRotationConfig, LogRotationPeriod, LoggerManager, OtlpConfig};
 
 use crate::bootstrap::{setup_panic_hook, Application};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // 初始化日志系统
-    let _logger = LoggerManager::init();
+    // 初始化日志系统 (按配置滚动并清理旧日志)
+    let log_config = LogConfig::from_env();
+    let otlp = log_config.otlp_endpoint.clone().map(|endpoint| OtlpConfig {
+        endpoint,
+        service_name: log_config.otlp_service_name.clone(),
+    });
+    let _logger = LoggerManager::init_with_otlp(
+        LogRotationConfig {
+            period: LogRotationPeriod::parse(&log_config.rotation),
+            max_size_mb: log_config.max_size_mb,
+            max_files: log_config.max_files,
+        },
+        otlp,
+    );
 
     // 设置 panic hook
     setup_panic_hook();
 
+    // 上线前自检: 检查各链连通性和配置，输出结果表格，有失败项时非零退出
+    if std::env::args().nth(1).as_deref() == Some("selftest") {
+        let config = AppConfig::load()?;
+        let report = selftest::run(&config).await;
+        println!("{}", report);
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // 池子/路径配置 CSV 导入导出
+    if std::env::args().nth(1).as_deref() == Some("pool-config") {
+        let config = AppConfig::load()?;
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        return pool_config::run(&config, &args).await;
+    }
+
     info!("========================================");
     info!("  DEX 套利机器人系统启动");
     info!("========================================");
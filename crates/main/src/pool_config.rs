@@ -0,0 +1,36 @@
+//! 池子/路径配置导入导出子命令
+//!
+//! 用法:
+//!   chainfusion_arbitrage pool-config export <目录>   导出所有启用链的配置
+//!   chainfusion_arbitrage pool-config import <目录>   校验并导入 (重启后生效)
+
+use anyhow::{anyhow, Result};
+use config_crate::AppConfig;
+use services::{export_pool_config, import_pool_config, ArbitrageConfigDb, Database};
+use std::path::PathBuf;
+
+const USAGE: &str = "用法: pool-config <export|import> <目录>";
+
+pub async fn run(config: &AppConfig, args: &[String]) -> Result<()> {
+    let (action, dir) = match args {
+        [action, dir] => (action.as_str(), PathBuf::from(dir)),
+        _ => return Err(anyhow!(USAGE)),
+    };
+
+    let database = Database::new(&config.database.url, 1).await?;
+    database.initialize_tables().await?;
+    let db = ArbitrageConfigDb::new(database.pool().clone());
+
+    match action {
+        "export" => {
+            let (pools, paths) = export_pool_config(&db, &config.enabled_chains, &dir).await?;
+            println!("已导出 {} 个池子、{} 条路径到 {}", pools, paths, dir.display());
+        }
+        "import" => {
+            let (pools, paths) = import_pool_config(&db, &dir).await?;
+            println!("已导入 {} 个池子、{} 条路径，重启后扫描器加载新配置", pools, paths);
+        }
+        _ => return Err(anyhow!(USAGE)),
+    }
+    Ok(())
+}
@@ -0,0 +1,291 @@
+//! 上线前自检 (`chainfusion_arbitrage selftest`)
+//!
+//! 对每条启用的链依次检查 RPC、WebSocket、Quoter、套利合约和 owner、原生代币价格，
+//! 最后检查数据库。全部检查跑完后输出通过/失败表格，任一项失败时进程以非零状态码退出
+
+use config_crate::{AppConfig, ChainConfig};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use services::{Database, PriceService, PriceServiceConfig};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use strategies::ChainContractsConfig;
+
+use crate::wallet;
+
+/// 单项检查超时
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 单项检查结果: Ok(说明) / Err(失败原因)
+pub type CheckOutcome = Result<String, String>;
+
+/// 一项检查的记录
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// 链名称 (全局检查为 "-")
+    pub chain: String,
+    pub name: &'static str,
+    pub outcome: CheckOutcome,
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    pub fn record(&mut self, chain: &str, name: &'static str, outcome: CheckOutcome) {
+        self.results.push(CheckResult { chain: chain.to_string(), name, outcome });
+    }
+
+    /// 失败的检查
+    pub fn failures(&self) -> Vec<&CheckResult> {
+        self.results.iter().filter(|r| r.outcome.is_err()).collect()
+    }
+
+    /// 是否全部通过
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.outcome.is_ok())
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<12} {:<14} {:<6} 说明", "链", "检查项", "结果")?;
+        for result in &self.results {
+            let (status, detail) = match result.outcome {
+                Ok(ref detail) => ("✅ 通过", detail),
+                Err(ref reason) => ("❌ 失败", reason),
+            };
+            writeln!(f, "{:<12} {:<14} {:<6} {}", result.chain, result.name, status, detail)?;
+        }
+        let failed = self.failures().len();
+        write!(f, "共 {} 项, 失败 {} 项", self.results.len(), failed)
+    }
+}
+
+/// 带超时执行一项检查
+async fn with_timeout<F: Future<Output = CheckOutcome>>(check: F) -> CheckOutcome {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("超时 ({}s)", CHECK_TIMEOUT.as_secs())))
+}
+
+/// RPC 可达: 能取到最新区块号
+pub async fn check_rpc<M: Middleware>(provider: &M) -> CheckOutcome {
+    provider
+        .get_block_number()
+        .await
+        .map(|block| format!("最新区块 {}", block))
+        .map_err(|e| format!("RPC 请求失败: {}", e))
+}
+
+/// WebSocket 可达: 能建立连接并取到区块号
+pub async fn check_ws(ws_url: &str) -> CheckOutcome {
+    if ws_url.is_empty() {
+        return Err("未配置 WebSocket URL".to_string());
+    }
+    let ws = Ws::connect(ws_url).await.map_err(|e| format!("WebSocket 连接失败: {}", e))?;
+    Provider::new(ws)
+        .get_block_number()
+        .await
+        .map(|block| format!("最新区块 {}", block))
+        .map_err(|e| format!("WebSocket 请求失败: {}", e))
+}
+
+/// Quoter 响应: factory() 返回非零地址
+pub async fn check_quoter<M: Middleware>(provider: &M, quoter: Address) -> CheckOutcome {
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(quoter)
+        .data(ethers::utils::id("factory()").to_vec())
+        .into();
+    let output = provider
+        .call(&tx, None)
+        .await
+        .map_err(|e| format!("调用 Quoter {:?} 失败: {}", quoter, e))?;
+
+    match ethers::abi::decode(&[ethers::abi::ParamType::Address], &output)
+        .ok()
+        .and_then(|tokens| tokens.into_iter().next()?.into_address())
+    {
+        Some(factory) if !factory.is_zero() => Ok(format!("{:?} (factory {:?})", quoter, factory)),
+        _ => Err(format!("Quoter {:?} 没有返回 factory 地址", quoter)),
+    }
+}
+
+/// 套利合约存在: 地址上有合约代码
+pub async fn check_contract_code<M: Middleware>(provider: &M, contract: Option<Address>) -> CheckOutcome {
+    let contract = contract.ok_or_else(|| "未配置套利合约地址".to_string())?;
+    let code = provider
+        .get_code(contract, None)
+        .await
+        .map_err(|e| format!("读取合约代码失败: {}", e))?;
+    if code.is_empty() {
+        return Err(format!("{:?} 上没有合约代码", contract));
+    }
+    Ok(format!("{:?} ({} 字节)", contract, code.len()))
+}
+
+/// 钱包是套利合约的 owner
+pub async fn check_contract_owner<M: Middleware + 'static>(
+    provider: Arc<M>,
+    contract: Option<Address>,
+    wallet: Option<Address>,
+) -> CheckOutcome {
+    let contract = contract.ok_or_else(|| "未配置套利合约地址".to_string())?;
+    let wallet = wallet.ok_or_else(|| "未配置钱包".to_string())?;
+    let owner = executor::FlashArbitrageContract::new(contract, provider)
+        .owner()
+        .call()
+        .await
+        .map_err(|e| format!("读取合约 owner 失败: {}", e))?;
+    if owner != wallet {
+        return Err(format!("合约 owner 为 {:?}，当前钱包为 {:?}", owner, wallet));
+    }
+    Ok(format!("owner {:?}", owner))
+}
+
+/// 价格服务能返回原生代币价格
+pub async fn check_native_price(price_service: &PriceService, symbol: &str) -> CheckOutcome {
+    match price_service.fetch_price_now(symbol).await {
+        Ok(price) if price > rust_decimal::Decimal::ZERO => Ok(format!("{} = ${}", symbol, price)),
+        Ok(price) => Err(format!("{} 价格无效: {}", symbol, price)),
+        Err(e) => Err(format!("获取 {} 价格失败: {}", symbol, e)),
+    }
+}
+
+/// 数据库可连接
+pub async fn check_database(url: &str) -> CheckOutcome {
+    let database = Database::new(url, 1).await.map_err(|e| format!("连接数据库失败: {}", e))?;
+    sqlx::query("SELECT 1")
+        .execute(database.pool())
+        .await
+        .map(|_| "SELECT 1 成功".to_string())
+        .map_err(|e| format!("数据库查询失败: {}", e))
+}
+
+/// 单条 EVM 链上需要 RPC 的检查: RPC、Quoter、合约代码、合约 owner
+pub async fn check_evm_chain<M: Middleware + 'static>(
+    report: &mut SelfTestReport,
+    chain_name: &str,
+    provider: Arc<M>,
+    quoter: Option<Address>,
+    contract: Option<Address>,
+    wallet: Option<Address>,
+) {
+    report.record(chain_name, "RPC", with_timeout(check_rpc(provider.as_ref())).await);
+    let quoter_outcome = match quoter {
+        Some(quoter) => with_timeout(check_quoter(provider.as_ref(), quoter)).await,
+        None => Err("不支持的链，没有 Quoter 配置".to_string()),
+    };
+    report.record(chain_name, "Quoter", quoter_outcome);
+
+    let code_outcome = with_timeout(check_contract_code(provider.as_ref(), contract)).await;
+    let owner_outcome = if code_outcome.is_ok() {
+        with_timeout(check_contract_owner(provider, contract, wallet)).await
+    } else {
+        Err("合约不存在，跳过".to_string())
+    };
+    report.record(chain_name, "套利合约", code_outcome);
+    report.record(chain_name, "合约 owner", owner_outcome);
+}
+
+/// 单条链使用的套利合约地址 (链级别配置优先)
+fn chain_contract(chain: &ChainConfig, config: &AppConfig) -> Option<Address> {
+    chain
+        .arbitrage_contract_or(config.wallet.arbitrage_contract_address.as_deref())
+        .and_then(|s| s.parse().ok())
+}
+
+/// 对所有启用的链和数据库执行自检
+pub async fn run(config: &AppConfig) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let wallet_address = match wallet::load_wallet(&config.wallet) {
+        Ok(wallet) => wallet.map(|(w, _)| w.address()),
+        Err(e) => {
+            report.record("-", "钱包", Err(e.to_string()));
+            None
+        }
+    };
+    let price_service = PriceService::new(PriceServiceConfig::default());
+
+    for chain_id in &config.enabled_chains {
+        let Some(chain) = config.chains.get(chain_id).filter(|c| c.enabled) else {
+            continue;
+        };
+        let name = chain.name.as_str();
+
+        match Provider::<Http>::try_from(chain.rpc_url.as_str()) {
+            Ok(provider) => {
+                let quoter = ChainContractsConfig::for_chain(*chain_id).map(|c| c.quoter_address);
+                check_evm_chain(
+                    &mut report,
+                    name,
+                    Arc::new(provider),
+                    quoter,
+                    chain_contract(chain, config),
+                    wallet_address,
+                )
+                .await;
+            }
+            Err(e) => report.record(name, "RPC", Err(format!("RPC URL 无效: {}", e))),
+        }
+        report.record(name, "WebSocket", with_timeout(check_ws(&chain.ws_url)).await);
+        report.record(name, "原生代币价格", with_timeout(check_native_price(&price_service, &chain.native_token)).await);
+    }
+
+    report.record("-", "数据库", with_timeout(check_database(&config.database.url)).await);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+    use executor::testing::MockMiddleware;
+
+    const QUOTER: Address = Address::repeat_byte(0x0a);
+    const CONTRACT: Address = Address::repeat_byte(0x0c);
+    const OWNER: Address = Address::repeat_byte(0x0e);
+
+    /// Quoter 和套利合约都已部署，Quoter.factory() 和合约.owner() 各返回一个地址 (区块号固定为 100)
+    fn fake_chain() -> Arc<MockMiddleware> {
+        let chain = MockMiddleware::new();
+        for deployed in [QUOTER, CONTRACT] {
+            chain.set_code(deployed, Bytes::from(vec![0x60, 0x80]));
+        }
+        chain.on_call("factory()", &[Token::Address(Address::repeat_byte(0xfa))]);
+        chain.on_call("owner()", &[Token::Address(OWNER)]);
+        Arc::new(chain)
+    }
+
+    #[tokio::test]
+    async fn test_selftest_reports_wrong_contract() {
+        // 配置正确: 全部通过
+        let mut report = SelfTestReport::default();
+        check_evm_chain(&mut report, "Ethereum", fake_chain(), Some(QUOTER), Some(CONTRACT), Some(OWNER)).await;
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.results.len(), 4);
+
+        // 合约地址配错: 只有合约相关的检查失败，并指出是哪一项
+        let wrong = Address::repeat_byte(0x0d);
+        let mut report = SelfTestReport::default();
+        check_evm_chain(&mut report, "Ethereum", fake_chain(), Some(QUOTER), Some(wrong), Some(OWNER)).await;
+        assert!(!report.passed());
+        let failed: Vec<&str> = report.failures().iter().map(|r| r.name).collect();
+        assert_eq!(failed, vec!["套利合约", "合约 owner"]);
+        let text = report.to_string();
+        assert!(text.contains(&format!("{:?} 上没有合约代码", wrong)));
+        assert!(text.contains("失败 2 项"));
+
+        // 钱包不是 owner
+        let mut report = SelfTestReport::default();
+        check_evm_chain(&mut report, "Ethereum", fake_chain(), Some(QUOTER), Some(CONTRACT), Some(QUOTER)).await;
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].name, "合约 owner");
+    }
+}
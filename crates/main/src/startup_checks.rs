@@ -0,0 +1,105 @@
+//! 启动时的配置检查
+//!
+//! `ENABLED_CHAINS` 为空或某条链在数据库里没有池子/路径时，程序能正常启动但什么都不做，
+//! 很容易被误以为在工作。这里在启动阶段把这些情况明确报出来: 没有任何可运行的链时直接报错，
+//! 启用的链缺少池子或路径时按链名告警
+
+use anyhow::{bail, Result};
+use config_crate::AppConfig;
+use services::ArbitrageConfigDb;
+use tracing::warn;
+
+/// 实际会启动的 EVM 链: 出现在 ENABLED_CHAINS 中且有对应的链配置
+pub fn active_evm_chains(config: &AppConfig) -> Vec<u64> {
+    config
+        .enabled_chains
+        .iter()
+        .copied()
+        .filter(|id| config.chains.get(id).is_some_and(|c| c.enabled))
+        .collect()
+}
+
+/// 没有任何可运行的链 (EVM 和 Solana 都未启用) 时报错
+pub fn ensure_chains_enabled(active_evm_chains: &[u64], solana_enabled: bool) -> Result<()> {
+    if active_evm_chains.is_empty() && !solana_enabled {
+        bail!(
+            "没有启用任何链: ENABLED_CHAINS 为空或不包含支持的链 ID (如 ENABLED_CHAINS=1,56)，\
+             且 SOLANA_ENABLED 未开启"
+        );
+    }
+    Ok(())
+}
+
+/// 启用的链缺少池子或路径配置时返回告警信息
+pub fn chain_config_warning(chain_name: &str, chain_id: u64, pools: usize, paths: usize) -> Option<String> {
+    match (pools, paths) {
+        (0, 0) => Some(format!(
+            "[{}] chain_id={} 已启用，但 arbitrage_pools 和 arbitrage_pool_paths 中都没有该链的配置，\
+             扫描器不会检测任何套利。请用 `pool-config import <dir>` 导入池子和路径，或从 ENABLED_CHAINS 中移除该链",
+            chain_name, chain_id
+        )),
+        (0, _) => Some(format!(
+            "[{}] chain_id={} 已启用，但 arbitrage_pools 中没有启用的池子，{} 条路径都无法触发。请检查池子配置",
+            chain_name, chain_id, paths
+        )),
+        (_, 0) => Some(format!(
+            "[{}] chain_id={} 已启用，已配置 {} 个池子，但 arbitrage_pool_paths 中没有路径，扫描器不会检测任何套利。\
+             请为该链添加池子-路径映射",
+            chain_name, chain_id, pools
+        )),
+        _ => None,
+    }
+}
+
+/// 逐链检查数据库中的池子和路径配置，缺失时告警
+pub async fn warn_unconfigured_chains(config: &AppConfig, config_db: &ArbitrageConfigDb) {
+    for chain_id in active_evm_chains(config) {
+        let chain_name = config.chains.get(&chain_id).map(|c| c.name.as_str()).unwrap_or("unknown");
+        let pools = match config_db.get_enabled_pools(chain_id).await {
+            Ok(pools) => pools.len(),
+            Err(e) => {
+                warn!("[{}] 启动检查: 读取池子配置失败: {}", chain_name, e);
+                continue;
+            }
+        };
+        let paths = match config_db.get_all_pool_paths(chain_id).await {
+            Ok(paths) => paths.len(),
+            Err(e) => {
+                warn!("[{}] 启动检查: 读取路径配置失败: {}", chain_name, e);
+                continue;
+            }
+        };
+        if let Some(message) = chain_config_warning(chain_name, chain_id, pools, paths) {
+            warn!("⚠️  {}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_enabled_chains_is_an_error() {
+        let err = ensure_chains_enabled(&[], false).unwrap_err();
+        assert!(err.to_string().contains("ENABLED_CHAINS"));
+        // 只跑 Solana 也算有链
+        assert!(ensure_chains_enabled(&[], true).is_ok());
+        assert!(ensure_chains_enabled(&[56], false).is_ok());
+    }
+
+    #[test]
+    fn test_chain_without_paths_warns_with_chain_name() {
+        let warning = chain_config_warning("BSC", 56, 12, 0).unwrap();
+        assert!(warning.contains("[BSC]"));
+        assert!(warning.contains("chain_id=56"));
+        assert!(warning.contains("arbitrage_pool_paths"));
+
+        let warning = chain_config_warning("Ethereum", 1, 0, 0).unwrap();
+        assert!(warning.contains("[Ethereum]"));
+        assert!(warning.contains("pool-config import"));
+
+        assert!(chain_config_warning("Ethereum", 1, 0, 5).unwrap().contains("arbitrage_pools"));
+        assert!(chain_config_warning("Ethereum", 1, 3, 5).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+//! 交易钱包加载
+//!
+//! 支持明文私钥 (PRIVATE_KEY) 和加密的 V3 keystore (KEYSTORE_PATH + KEYSTORE_PASSWORD)。
+//! keystore 只在内存中解密成 LocalWallet，解密出的私钥不会写回任何配置或日志
+
+use anyhow::{Context, Result};
+use config_crate::WalletConfig;
+use ethers::signers::{LocalWallet, Signer};
+
+/// 钱包来源 (只用于日志)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletSource {
+    Keystore,
+    PrivateKey,
+}
+
+/// 按配置加载钱包: 配置了 keystore 时优先解密 keystore，否则解析明文私钥
+///
+/// 两者都未配置时返回 Ok(None) (只读模式)
+pub fn load_wallet(config: &WalletConfig) -> Result<Option<(LocalWallet, WalletSource)>> {
+    if let Some(ref path) = config.keystore_path {
+        let password = config
+            .keystore_password
+            .as_deref()
+            .context("已配置 KEYSTORE_PATH 但未配置 KEYSTORE_PASSWORD")?;
+        // 错误信息只带路径，不带密码
+        let wallet = LocalWallet::decrypt_keystore(path, password)
+            .map_err(|e| anyhow::anyhow!("解密 keystore 失败 ({}): {}", path, e))?;
+        return Ok(Some((wallet, WalletSource::Keystore)));
+    }
+
+    match config.private_key {
+        Some(ref private_key) => {
+            let wallet = private_key
+                .parse::<LocalWallet>()
+                .map_err(|e| anyhow::anyhow!("解析私钥失败: {}", e))?;
+            Ok(Some((wallet, WalletSource::PrivateKey)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 钱包地址 (日志里只输出地址)
+pub fn wallet_address(wallet: &LocalWallet) -> String {
+    format!("{:?}", wallet.address())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::rand::thread_rng;
+    use std::path::PathBuf;
+
+    /// Hardhat/Anvil 默认账户 #0
+    const TEST_PRIVATE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const TEST_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+    fn wallet_config(keystore_path: Option<String>, password: Option<&str>) -> WalletConfig {
+        WalletConfig {
+            private_key: None,
+            keystore_path,
+            keystore_password: password.map(|p| p.to_string()),
+            arbitrage_contract_address: None,
+        }
+    }
+
+    /// 在临时目录生成 keystore，返回 (目录, 文件路径)
+    fn write_keystore(password: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("chainfusion-keystore-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = TEST_PRIVATE_KEY.parse::<LocalWallet>().unwrap().signer().to_bytes();
+        LocalWallet::encrypt_keystore(&dir, &mut thread_rng(), key, password, Some("wallet.json")).unwrap();
+        (dir.clone(), dir.join("wallet.json"))
+    }
+
+    #[test]
+    fn test_load_wallet_from_keystore() {
+        let (dir, path) = write_keystore("correct horse");
+        let config = wallet_config(Some(path.to_string_lossy().to_string()), Some("correct horse"));
+
+        let (wallet, source) = load_wallet(&config).unwrap().unwrap();
+        assert_eq!(source, WalletSource::Keystore);
+        assert_eq!(wallet_address(&wallet), TEST_ADDRESS);
+
+        // 配置结构和日志里都不能出现明文私钥或密码
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains(TEST_PRIVATE_KEY));
+        assert!(!debug.contains("correct horse"));
+        assert!(!format!("{:?}", wallet).contains(TEST_PRIVATE_KEY));
+
+        // 密码错误或缺失时报错
+        let wrong = wallet_config(config.keystore_path.clone(), Some("wrong"));
+        let err = load_wallet(&wrong).unwrap_err().to_string();
+        assert!(err.contains("解密 keystore 失败"));
+        assert!(!err.contains("wrong"));
+        assert!(load_wallet(&wallet_config(config.keystore_path.clone(), None)).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_private_key_fallback_and_redaction() {
+        let mut config = wallet_config(None, None);
+        assert!(load_wallet(&config).unwrap().is_none());
+
+        config.private_key = Some(TEST_PRIVATE_KEY.to_string());
+        let (wallet, source) = load_wallet(&config).unwrap().unwrap();
+        assert_eq!(source, WalletSource::PrivateKey);
+        assert_eq!(wallet_address(&wallet), TEST_ADDRESS);
+        assert!(!format!("{:?}", config).contains(TEST_PRIVATE_KEY));
+    }
+}
@@ -69,6 +69,22 @@ pub struct ArbitrageOpportunity {
     pub profit_percentage: Decimal,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub block_number: u64,
+    /// 各跳中最大的价格影响 (%)，相对交易前现货价格扣除手续费后计算
+    #[serde(default)]
+    pub max_price_impact: Decimal,
+    /// 盈亏平衡 gas 价格 (Gwei): gas 价格涨到这里时毛利润正好被 gas 吃完
+    #[serde(default)]
+    pub break_even_gas_price_gwei: Decimal,
+    /// 链上报价的各跳输出 (按 input_amount 逐跳报价)，执行时据此计算每跳最小输出；未知时为空
+    #[serde(default)]
+    pub hop_amounts_out: Vec<U256>,
+    /// 原生代币计价模式下的净利润 (原生代币数量)；按 USD 计价时为 None。
+    /// 此时 USD 字段按原生代币的实时价格折算，价格未知时为 0
+    #[serde(default)]
+    pub net_profit_native: Option<Decimal>,
+    /// 原生代币计价模式下的 gas 成本 (原生代币数量)；按 USD 计价时为 None
+    #[serde(default)]
+    pub gas_cost_native: Option<Decimal>,
 }
 
 impl ArbitrageOpportunity {
@@ -76,6 +92,27 @@ impl ArbitrageOpportunity {
     pub fn is_profitable(&self, min_profit_threshold: Decimal) -> bool {
         self.net_profit_usd > min_profit_threshold
     }
+
+    /// 同一扫描器内比较机会用的净利润: 原生代币计价模式下为原生代币数量，否则为 USD
+    pub fn ranking_net_profit(&self) -> Decimal {
+        self.net_profit_native.unwrap_or(self.net_profit_usd)
+    }
+
+    /// 与 `ranking_net_profit` 同一计价单位的 gas 成本
+    pub fn ranking_gas_cost(&self) -> Decimal {
+        self.gas_cost_native.unwrap_or(self.gas_cost_usd)
+    }
+}
+
+/// 计算盈亏平衡 gas 价格 (Gwei) = 毛利润 (折算成原生代币) / gas 用量
+///
+/// gas 用量为 0、原生代币价格未知或毛利润不为正时返回 0
+pub fn break_even_gas_price_gwei(gross_profit_usd: Decimal, gas_used: U256, native_price_usd: Decimal) -> Decimal {
+    if gas_used.is_zero() || gas_used > U256::from(u64::MAX) || native_price_usd <= Decimal::ZERO || gross_profit_usd <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    let gross_profit_gwei = gross_profit_usd / native_price_usd * Decimal::from(1_000_000_000u64);
+    (gross_profit_gwei / Decimal::from(gas_used.as_u64())).round_dp(4)
 }
 
 /// 套利执行结果
@@ -158,3 +195,24 @@ impl PriceDeviation {
         (diff / avg) * Decimal::from(100)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_break_even_gas_price_from_gross_profit_and_gas() {
+        // 毛利润 $30, ETH $3000 -> 0.01 ETH = 1e7 Gwei, gas 300k -> 33.3333 Gwei
+        let gwei = break_even_gas_price_gwei(Decimal::from(30), U256::from(300_000u64), Decimal::from(3000));
+        assert_eq!(gwei, Decimal::new(333_333, 4));
+
+        // 毛利润 $6, gas 200k -> 0.002 ETH / 200k = 10 Gwei
+        let gwei = break_even_gas_price_gwei(Decimal::from(6), U256::from(200_000u64), Decimal::from(3000));
+        assert_eq!(gwei, Decimal::from(10));
+
+        // 无法计算时为 0
+        assert_eq!(break_even_gas_price_gwei(Decimal::from(30), U256::zero(), Decimal::from(3000)), Decimal::ZERO);
+        assert_eq!(break_even_gas_price_gwei(Decimal::from(30), U256::from(300_000u64), Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(break_even_gas_price_gwei(Decimal::from(-5), U256::from(300_000u64), Decimal::from(3000)), Decimal::ZERO);
+    }
+}
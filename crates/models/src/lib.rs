@@ -9,3 +9,6 @@ pub use pool::*;
 pub use arbitrage::*;
 pub use transaction::*;
 pub use strategy::*;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
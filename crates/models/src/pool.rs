@@ -12,6 +12,8 @@ pub enum DexType {
     Curve,
     PancakeSwapV2,
     PancakeSwapV3,
+    /// PancakeSwap StableSwap (Curve 风格稳定币池)
+    PancakeSwapStable,
     SushiSwap,
     SushiSwapV2,
     SushiSwapV3,
@@ -26,6 +28,7 @@ impl DexType {
             DexType::Curve => "Curve",
             DexType::PancakeSwapV2 => "PancakeSwap V2",
             DexType::PancakeSwapV3 => "PancakeSwap V3",
+            DexType::PancakeSwapStable => "PancakeSwap StableSwap",
             DexType::SushiSwap => "SushiSwap",
             DexType::SushiSwapV2 => "SushiSwap V2",
             DexType::SushiSwapV3 => "SushiSwap V3",
@@ -41,6 +44,11 @@ impl DexType {
     pub fn is_v2_style(&self) -> bool {
         matches!(self, DexType::UniswapV2 | DexType::PancakeSwapV2 | DexType::SushiSwap | DexType::SushiSwapV2)
     }
+
+    /// 是否是 StableSwap 类型的 DEX (放大系数不变量)
+    pub fn is_stable_swap(&self) -> bool {
+        matches!(self, DexType::Curve | DexType::PancakeSwapStable)
+    }
 }
 
 /// 流动性池基础信息
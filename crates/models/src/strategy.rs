@@ -85,6 +85,10 @@ pub struct TradeRecord {
     pub gas_price_gwei: Decimal,
     pub gas_cost_usd: Decimal,
     pub net_profit_usd: Decimal,
+    /// 原生代币计价模式下的净利润 (原生代币数量)
+    pub net_profit_native: Option<Decimal>,
+    /// 原生代币计价模式下的 gas 成本 (原生代币数量)
+    pub gas_cost_native: Option<Decimal>,
     pub status: String,
     pub error_message: Option<String>,
     pub block_number: i64,
@@ -104,3 +108,112 @@ pub struct PriceMonitorRecord {
     pub block_number: i64,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// 单笔成功交易的预估利润与实际利润对比
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfitDelta {
+    /// 检测时预估的利润 (USD，未扣 gas)
+    pub expected_profit_usd: Decimal,
+    /// 按执行前后合约余额差计算的实际利润 (USD)
+    pub realized_profit_usd: Decimal,
+}
+
+impl ProfitDelta {
+    pub fn new(expected_profit_usd: Decimal, realized_profit_usd: Decimal) -> Self {
+        Self { expected_profit_usd, realized_profit_usd }
+    }
+
+    /// 实际 - 预估 (负数表示预估偏高)
+    pub fn delta_usd(&self) -> Decimal {
+        self.realized_profit_usd - self.expected_profit_usd
+    }
+
+    /// 偏差占预估利润的百分比 (预估利润为 0 时无意义)
+    pub fn error_pct(&self) -> Option<Decimal> {
+        if self.expected_profit_usd.is_zero() {
+            return None;
+        }
+        Some(self.delta_usd() / self.expected_profit_usd.abs() * Decimal::from(100))
+    }
+}
+
+/// 单类 RPC 在一个统计区间内的用量 (定期写入 rpc_stats_snapshots 表)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcUsage {
+    /// RPC 类型名
+    pub call_type: String,
+    pub calls: u64,
+    pub failed_calls: u64,
+    /// 区间内调用的总耗时 (毫秒)
+    pub total_duration_ms: u64,
+}
+
+impl RpcUsage {
+    /// 平均耗时 (毫秒)
+    pub fn avg_latency_ms(&self) -> u64 {
+        if self.calls == 0 {
+            return 0;
+        }
+        self.total_duration_ms / self.calls
+    }
+}
+
+/// 单条链一次运行的会话汇总 (关闭时输出，可选写入 session_summaries 表)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub chain_id: u64,
+    pub chain_name: String,
+    pub ended_at: chrono::DateTime<chrono::Utc>,
+    /// 运行时长 (秒)
+    pub uptime_secs: u64,
+    pub opportunities_found: u64,
+    pub total_executions: u64,
+    pub successful_executions: u64,
+    pub failed_executions: u64,
+    pub dry_run_executions: u64,
+    pub total_profit_usd: Decimal,
+    /// 各类型 RPC 调用次数 (类型名, 次数)
+    pub rpc_calls: Vec<(String, u64)>,
+}
+
+impl SessionSummary {
+    /// 成功率 (%)，干运行不计入分母
+    pub fn success_rate(&self) -> f64 {
+        let real_executions = self.successful_executions + self.failed_executions;
+        if real_executions == 0 {
+            return 0.0;
+        }
+        self.successful_executions as f64 / real_executions as f64 * 100.0
+    }
+
+    pub fn total_rpc_calls(&self) -> u64 {
+        self.rpc_calls.iter().map(|(_, calls)| calls).sum()
+    }
+
+    /// 多行文本报告 (用于关闭时的日志)
+    pub fn format_report(&self) -> String {
+        let mut lines = vec![
+            format!("📋 [{}] 会话汇总 (chain_id={})", self.chain_name, self.chain_id),
+            "─".repeat(60),
+            format!(
+                "运行时间: {}h {}m {}s",
+                self.uptime_secs / 3600,
+                self.uptime_secs / 60 % 60,
+                self.uptime_secs % 60
+            ),
+            format!("发现机会: {}", self.opportunities_found),
+            format!(
+                "执行次数: {} (成功 {}, 失败 {}, 干运行 {})",
+                self.total_executions, self.successful_executions, self.failed_executions, self.dry_run_executions
+            ),
+            format!("成功率: {:.1}%", self.success_rate()),
+            format!("累计利润: ${:.2}", self.total_profit_usd),
+            format!("RPC 调用: {} 次", self.total_rpc_calls()),
+        ];
+        for (name, calls) in self.rpc_calls.iter().filter(|(_, calls)| *calls > 0) {
+            lines.push(format!("  {:<20} {:>10}", name, calls));
+        }
+        lines.push("─".repeat(60));
+        lines.join("\n")
+    }
+}
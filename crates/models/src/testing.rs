@@ -0,0 +1,94 @@
+//! 测试夹具: 各 crate 测试共用的套利机会构造器
+//!
+//! 仅在本 crate 测试或启用 `testing` feature 时编译
+
+use crate::{ArbitrageOpportunity, ArbitragePath, DexType, SwapHop};
+use ethers::types::{Address, U256};
+use rust_decimal::Decimal;
+
+/// 套利机会构造器
+///
+/// 默认: 输入/输出 1e18，净利润 $10，无 Gas 成本，区块 100，空路径；
+/// 预期利润始终为净利润 + Gas 成本
+pub struct OpportunityBuilder {
+    opportunity: ArbitrageOpportunity,
+}
+
+/// 以给定 id 开始构造一个套利机会
+pub fn opportunity(id: impl Into<String>) -> OpportunityBuilder {
+    OpportunityBuilder {
+        opportunity: ArbitrageOpportunity {
+            id: id.into(),
+            path: ArbitragePath::new(Address::zero(), 1),
+            input_amount: U256::exp10(18),
+            expected_output: U256::exp10(18),
+            expected_profit: U256::zero(),
+            expected_profit_usd: Decimal::from(10),
+            gas_estimate: U256::zero(),
+            gas_cost_usd: Decimal::ZERO,
+            net_profit_usd: Decimal::from(10),
+            profit_percentage: Decimal::ZERO,
+            timestamp: chrono::Utc::now(),
+            block_number: 100,
+            max_price_impact: Decimal::ZERO,
+            break_even_gas_price_gwei: Decimal::ZERO,
+            hop_amounts_out: Vec::new(),
+            net_profit_native: None,
+            gas_cost_native: None,
+        },
+    }
+}
+
+/// 由 (池子, tokenIn, tokenOut) 列表组成的 UniswapV3 (0.05%) 路径，起始代币为第一跳的 tokenIn
+pub fn v3_path(hops: &[(Address, Address, Address)]) -> ArbitragePath {
+    let start_token = hops.first().map(|&(_, token_in, _)| token_in).unwrap_or_default();
+    let mut path = ArbitragePath::new(start_token, 1);
+    for &(pool_address, token_in, token_out) in hops {
+        path.add_hop(SwapHop { pool_address, dex_type: DexType::UniswapV3, token_in, token_out, fee: 500 });
+    }
+    path
+}
+
+impl OpportunityBuilder {
+    pub fn path(mut self, path: ArbitragePath) -> Self {
+        self.opportunity.path = path;
+        self
+    }
+
+    /// 路径经过的池子 (代币地址均为零地址)，用于只关心池子冲突的场景
+    pub fn pools(self, pools: &[Address]) -> Self {
+        let hops: Vec<_> = pools.iter().map(|&pool| (pool, Address::zero(), Address::zero())).collect();
+        self.path(v3_path(&hops))
+    }
+
+    pub fn input_amount(mut self, input_amount: U256) -> Self {
+        self.opportunity.input_amount = input_amount;
+        self
+    }
+
+    pub fn net_profit_usd(mut self, net_profit_usd: Decimal) -> Self {
+        self.opportunity.net_profit_usd = net_profit_usd;
+        self.opportunity.expected_profit_usd = net_profit_usd + self.opportunity.gas_cost_usd;
+        self
+    }
+
+    pub fn gas_cost_usd(mut self, gas_cost_usd: Decimal) -> Self {
+        self.opportunity.gas_cost_usd = gas_cost_usd;
+        self.opportunity.expected_profit_usd = self.opportunity.net_profit_usd + gas_cost_usd;
+        self
+    }
+
+    pub fn gas_estimate(mut self, gas_estimate: u64) -> Self {
+        self.opportunity.gas_estimate = U256::from(gas_estimate);
+        self
+    }
+
+    pub fn block_number(mut self, block_number: u64) -> Self {
+        self.opportunity.block_number = block_number;
+        self
+    }
+
+    pub fn build(self) -> ArbitrageOpportunity {
+        self.opportunity
+    }
+}
@@ -1,5 +1,7 @@
-use ethers::types::Address;
+use ethers::types::{Address, U256};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Token 信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,3 +74,181 @@ pub mod bsc_tokens {
         pub static ref CAKE: Address = Address::from_str("0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82").unwrap();
     }
 }
+
+/// Decimal 最多支持的小数位数
+const DECIMAL_MAX_SCALE: u8 = 28;
+
+/// 带精度的代币数量
+///
+/// 链上原始数量 (最小单位) 与人类可读数量、USD 价值之间的换算统一走这里，
+/// 不要再手写 `10^decimals`: 6 位精度的 USDC 和 18 位精度的 WETH 混用时很容易差出 10^12 倍
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    raw: U256,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn new(raw: U256, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// 链上原始数量
+    pub fn raw(&self) -> U256 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// 人类可读数量 -> 原始数量 (向下取整到最小单位)，负数或溢出返回 None
+    pub fn from_decimal(amount: Decimal, decimals: u8) -> Option<Self> {
+        if amount.is_sign_negative() {
+            return None;
+        }
+        // amount = mantissa / 10^scale -> raw = mantissa * 10^decimals / 10^scale
+        let mantissa = U256::from(amount.mantissa().unsigned_abs());
+        let raw = mantissa.checked_mul(unit(decimals)?)? / U256::exp10(amount.scale() as usize);
+        Some(Self::new(raw, decimals))
+    }
+
+    /// 按 USD 金额和代币单价换算数量，价格无效 (<= 0) 时返回 None
+    pub fn from_usd(usd: Decimal, price_usd: Decimal, decimals: u8) -> Option<Self> {
+        if price_usd <= Decimal::ZERO {
+            return None;
+        }
+        Self::from_decimal(usd.checked_div(price_usd)?, decimals)
+    }
+
+    /// 人类可读数量 (超过 28 位的小数被截断)，整数部分超出 Decimal 范围时返回 None
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        let unit = unit(self.decimals)?;
+        let integer = Decimal::from_str(&(self.raw / unit).to_string()).ok()?;
+
+        let remainder = self.raw % unit;
+        let (fraction, scale) = if self.decimals > DECIMAL_MAX_SCALE {
+            (remainder / U256::exp10((self.decimals - DECIMAL_MAX_SCALE) as usize), DECIMAL_MAX_SCALE)
+        } else {
+            (remainder, self.decimals)
+        };
+        let fraction = Decimal::from_i128_with_scale(fraction.as_u128() as i128, scale as u32);
+
+        integer.checked_add(fraction)
+    }
+
+    /// USD 价值，数量超出 Decimal 范围时返回 0
+    pub fn to_usd(&self, price_usd: Decimal) -> Decimal {
+        self.to_decimal()
+            .map(|amount| amount.saturating_mul(price_usd))
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// 日志展示: 大于 1 时最多保留 4 位小数 (截断)，小于 1 时保留全部小数
+    pub fn display(&self) -> String {
+        let amount_str = self.raw.to_string();
+        let decimals = self.decimals as usize;
+
+        if decimals == 0 {
+            amount_str
+        } else if amount_str.len() <= decimals {
+            let zeros = "0".repeat(decimals - amount_str.len());
+            format!("0.{}{}", zeros, amount_str)
+        } else {
+            let (integer, fraction) = amount_str.split_at(amount_str.len() - decimals);
+            format!("{}.{}", integer, &fraction[..fraction.len().min(4)])
+        }
+    }
+}
+
+/// 10^decimals，超出 U256 时返回 None
+fn unit(decimals: u8) -> Option<U256> {
+    U256::from(10u64).checked_pow(U256::from(decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const DECIMALS: [u8; 3] = [6, 8, 18];
+    /// Decimal 乘除法的舍入误差
+    const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 15);
+
+    #[test]
+    fn test_usdc_and_weth_sized_by_their_own_decimals() {
+        // 同样 $3000: USDC (6 位) 是 3000 * 10^6，WETH (18 位) @ $3000 是 1 * 10^18
+        let usdc = TokenAmount::from_usd(Decimal::from(3000), Decimal::ONE, 6).unwrap();
+        let weth = TokenAmount::from_usd(Decimal::from(3000), Decimal::from(3000), 18).unwrap();
+        assert_eq!(usdc.raw(), U256::from(3_000_000_000u64));
+        assert_eq!(weth.raw(), U256::exp10(18));
+        assert_eq!(usdc.to_usd(Decimal::ONE), weth.to_usd(Decimal::from(3000)));
+
+        // 用错精度会差出 10^12 倍
+        assert_ne!(TokenAmount::new(usdc.raw(), 18).to_usd(Decimal::ONE), Decimal::from(3000));
+
+        // 价格未知时不能给出数量 (不能默认成 "N 个代币"，WETH 和 USDC 价值相差上千倍)
+        assert!(TokenAmount::from_usd(Decimal::from(3000), Decimal::ZERO, 18).is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TokenAmount::new(U256::from(1_234_567u64), 6).display(), "1.2345");
+        assert_eq!(TokenAmount::new(U256::from(5u64), 6).display(), "0.000005");
+        assert_eq!(TokenAmount::new(U256::from(1_500u64), 6).display(), "0.001500");
+        assert_eq!(TokenAmount::new(U256::exp10(18) * 12, 18).display(), "12.0000");
+        assert_eq!(TokenAmount::new(U256::from(42u64), 0).display(), "42");
+    }
+
+    #[test]
+    fn test_large_decimals_do_not_overflow() {
+        let amount = TokenAmount::new(U256::exp10(30) * 3 / 2, 30);
+        assert_eq!(amount.to_decimal(), Some(Decimal::new(15, 1)));
+        assert!(TokenAmount::from_decimal(Decimal::ONE, 78).is_none());
+    }
+
+    proptest! {
+        /// 原始数量 -> 人类可读 -> 原始数量 保持不变
+        #[test]
+        fn prop_raw_round_trip(raw in 0u128..(1u128 << 90), idx in 0usize..DECIMALS.len()) {
+            let decimals = DECIMALS[idx];
+            let amount = TokenAmount::new(U256::from(raw), decimals);
+            let human = amount.to_decimal().unwrap();
+            prop_assert_eq!(TokenAmount::from_decimal(human, decimals), Some(amount));
+        }
+
+        /// USD -> 数量 -> USD 的误差不超过一个最小单位的价值
+        #[test]
+        fn prop_usd_round_trip(
+            usd_cents in 1u64..10_000_000_000,
+            price_cents in 1u64..10_000_000,
+            idx in 0usize..DECIMALS.len(),
+        ) {
+            let decimals = DECIMALS[idx];
+            let usd = Decimal::new(usd_cents as i64, 2);
+            let price = Decimal::new(price_cents as i64, 2);
+
+            let amount = TokenAmount::from_usd(usd, price, decimals).unwrap();
+            let back = amount.to_usd(price);
+            let one_unit_usd = TokenAmount::new(U256::one(), decimals).to_usd(price);
+
+            // 除法结果在第 28 位有效数字处舍入，可能多出或少掉一个最小单位
+            prop_assert!((usd - back).abs() <= one_unit_usd + TOLERANCE);
+        }
+
+        /// 同样的 USD 金额，不同精度的代币换算出的价值一致
+        #[test]
+        fn prop_value_independent_of_decimals(usd_cents in 1u64..10_000_000_000, price_cents in 1u64..10_000_000) {
+            let usd = Decimal::new(usd_cents as i64, 2);
+            let price = Decimal::new(price_cents as i64, 2);
+            let values: Vec<Decimal> = DECIMALS
+                .iter()
+                .map(|d| TokenAmount::from_usd(usd, price, *d).unwrap().to_usd(price))
+                .collect();
+            let coarsest_unit = TokenAmount::new(U256::one(), 6).to_usd(price);
+            for value in &values {
+                prop_assert!((*value - values[0]).abs() <= coarsest_unit + TOLERANCE);
+            }
+        }
+    }
+}
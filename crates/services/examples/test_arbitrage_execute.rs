@@ -20,7 +20,7 @@ use anyhow::Result;
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use executor::{
-    ArbitrageExecutor, ExecutorConfig, FlashbotsConfig, GasStrategy, SendMode,
+    ArbitrageExecutor, ExecutionOutcome, ExecutorConfig, FlashbotsConfig, GasStrategy, SendMode,
     ArbitrageParamsBuilder, RevertDecoder,
 };
 use rust_decimal::Decimal;
@@ -207,34 +207,44 @@ async fn main() -> Result<()> {
         chain_id: chain_id.as_u64(),
         max_block_retries: 3,           // 尝试 3 个区块
         signer_key: None,               // 使用交易私钥作为签名密钥
+        builders: Vec::new(),           // 只发送到 Flashbots 中继
+        privacy: Default::default(),
+        submit_timeout_secs: 30,
     };
 
     let executor_config = ExecutorConfig {
         contract_address: contract_addr,
         chain_id: chain_id.as_u64(),
-        gas_strategy: GasStrategy {
+        execution_gas: GasStrategy {
             gas_price_multiplier: 1.2,  // Gas 价格 +20%
             max_gas_price_gwei: 0.06,   // 最大 0.06 Gwei (当前低 Gas 环境)
             gas_limit_multiplier: 1.3,  // Gas Limit +30%
             use_eip1559: true,
             priority_fee_gwei: 0.001,   // 优先费 0.001 Gwei
             fixed_gas_limit: Some(500_000),  // 固定 Gas Limit，跳过估算直接发送 Flashbots
+            max_gas_cost_usd: None,          // 不限制单笔 gas 费
         },
+        simulation_gas: GasStrategy::simulation(),  // 已关闭模拟，不会用到
         confirmation_timeout_secs: 120,  // 2 分钟超时
+        send_timeout_secs: 30,           // 发送 RPC 30 秒无响应则放弃
         confirmations: 1,
         simulate_before_execute: false,  // 关闭模拟，直接通过 Flashbots 发送测试
+        abort_if_block_advanced: false, // 未模拟且走 Flashbots，不做区块检查
+        max_slippage: Decimal::new(5, 3), // 每跳 0.5% 滑点 (仅在提供逐跳报价时生效)
         private_key: Some(private_key.clone()),
+        wallet: None,
         send_mode: SendMode::Flashbots,   // Flashbots 模式，防止 MEV 三明治攻击
         flashbots_config,
+        state_overrides: None,
     };
 
     info!("   合约地址: {:?}", executor_config.contract_address);
     info!("   Gas 策略:");
-    info!("     - Gas Price 倍数: {:.1}x", executor_config.gas_strategy.gas_price_multiplier);
-    info!("     - 最大 Gas Price: {} Gwei", executor_config.gas_strategy.max_gas_price_gwei);
-    info!("     - Gas Limit 倍数: {:.1}x", executor_config.gas_strategy.gas_limit_multiplier);
-    info!("     - 使用 EIP-1559: {}", executor_config.gas_strategy.use_eip1559);
-    info!("     - 优先费: {} Gwei", executor_config.gas_strategy.priority_fee_gwei);
+    info!("     - Gas Price 倍数: {:.1}x", executor_config.execution_gas.gas_price_multiplier);
+    info!("     - 最大 Gas Price: {} Gwei", executor_config.execution_gas.max_gas_price_gwei);
+    info!("     - Gas Limit 倍数: {:.1}x", executor_config.execution_gas.gas_limit_multiplier);
+    info!("     - 使用 EIP-1559: {}", executor_config.execution_gas.use_eip1559);
+    info!("     - 优先费: {} Gwei", executor_config.execution_gas.priority_fee_gwei);
     info!("   模拟执行: {}", executor_config.simulate_before_execute);
     info!("   发送模式: {:?} (防 MEV 攻击)", executor_config.send_mode);
     info!("   Flashbots 配置:");
@@ -287,7 +297,10 @@ async fn main() -> Result<()> {
     let start_time = std::time::Instant::now();
 
     match executor.execute(params.clone()).await {
-        Ok(result) => {
+        Ok(ExecutionOutcome::SimulatedOnly(sim)) => {
+            info!("🧪 仅模拟模式，Bundle 未发送: 目标区块 {}, gas_used={}, 结果: {:?}", sim.target_block, sim.gas_used, sim.revert);
+        }
+        Ok(ExecutionOutcome::Executed(result)) => {
             let elapsed = start_time.elapsed();
 
             println!("\n");
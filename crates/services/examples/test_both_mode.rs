@@ -16,7 +16,7 @@ use anyhow::Result;
 use ethers::prelude::*;
 use ethers::types::{Address, U256};
 use executor::{
-    ArbitrageExecutor, ExecutorConfig, FlashbotsConfig, GasStrategy, SendMode,
+    ArbitrageExecutor, ExecutionOutcome, ExecutorConfig, FlashbotsConfig, GasStrategy, SendMode,
     ArbitrageParamsBuilder, RevertDecoder,
 };
 use rust_decimal::Decimal;
@@ -192,25 +192,39 @@ async fn main() -> Result<()> {
         chain_id: chain_id.as_u64(),
         max_block_retries: 3,
         signer_key: None,
+        builders: Vec::new(),
+        privacy: Default::default(),
+        submit_timeout_secs: 30,
     };
 
     let executor_config = ExecutorConfig {
         contract_address: contract_addr,
         chain_id: chain_id.as_u64(),
-        gas_strategy: GasStrategy {
+        execution_gas: GasStrategy {
             gas_price_multiplier: 1.2,
             max_gas_price_gwei: 0.1,        // 最大 0.1 Gwei
             gas_limit_multiplier: 1.3,
             use_eip1559: true,
             priority_fee_gwei: 0.05,       // 优先费 0.005 Gwei
             fixed_gas_limit: Some(500_000), // 固定 Gas Limit
+            max_gas_cost_usd: None,
         },
+        simulation_gas: GasStrategy::simulation(),  // 已关闭模拟，不会用到
         confirmation_timeout_secs: 180,     // 3 分钟超时 (Both 模式需要更长时间)
+        send_timeout_secs: 30,              // 发送 RPC 30 秒无响应则放弃
         confirmations: 1,
         simulate_before_execute: false,     // 跳过模拟，直接发送
+        // 与主程序一致，按 ETH_ABORT_IF_BLOCK_ADVANCED 决定模拟后出新区块时是否放弃 mempool 发送
+        abort_if_block_advanced: std::env::var("ETH_ABORT_IF_BLOCK_ADVANCED")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true),
+        max_slippage: Decimal::new(5, 3), // 每跳 0.5% 滑点 (仅在提供逐跳报价时生效)
         private_key: Some(private_key.clone()),
+        wallet: None,
         send_mode: SendMode::Both,          // 🔥 Both 模式: 同时发送到两个渠道
         flashbots_config,
+        state_overrides: None,
     };
 
     println!("\n");
@@ -229,9 +243,9 @@ async fn main() -> Result<()> {
     info!("   ║    - Nonce: {} (后发送)", current_nonce + 1);
     info!("   ║ ");
     info!("   ║ ⛽ Gas 配置:");
-    info!("   ║    - 最大 Gas Price: {} Gwei", executor_config.gas_strategy.max_gas_price_gwei);
-    info!("   ║    - 优先费: {} Gwei", executor_config.gas_strategy.priority_fee_gwei);
-    info!("   ║    - 固定 Gas Limit: {:?}", executor_config.gas_strategy.fixed_gas_limit);
+    info!("   ║    - 最大 Gas Price: {} Gwei", executor_config.execution_gas.max_gas_price_gwei);
+    info!("   ║    - 优先费: {} Gwei", executor_config.execution_gas.priority_fee_gwei);
+    info!("   ║    - 固定 Gas Limit: {:?}", executor_config.execution_gas.fixed_gas_limit);
     println!("   ╚═══════════════════════════════════════════════════════════╝");
     println!("\n");
 
@@ -291,7 +305,10 @@ async fn main() -> Result<()> {
     let start_time = std::time::Instant::now();
 
     match executor.execute(params.clone()).await {
-        Ok(result) => {
+        Ok(ExecutionOutcome::SimulatedOnly(sim)) => {
+            info!("🧪 仅模拟模式，Bundle 未发送: 目标区块 {}, gas_used={}, 结果: {:?}", sim.target_block, sim.gas_used, sim.revert);
+        }
+        Ok(ExecutionOutcome::Executed(result)) => {
             let elapsed = start_time.elapsed();
 
             println!("\n");
@@ -1,10 +1,13 @@
 use anyhow::Result;
 use ethers::prelude::*;
 use ethers::types::{Address, H256};
+use futures_util::Stream;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, RwLock};
 use tracing::{info, error};
-use ::utils::{record_ws_block, record_ws_swap};
+use ::utils::{record_swap_stream, record_ws_block, record_ws_swap};
 
 /// 区块订阅配置
 #[derive(Debug, Clone)]
@@ -14,6 +17,85 @@ pub struct BlockSubscriberConfig {
     pub reconnect_delay_secs: u64,
     /// 监控的池子地址列表（只订阅这些池子的事件）
     pub monitored_pools: Vec<Address>,
+    /// Swap 订阅看门狗阈值 (秒)，0 表示关闭
+    pub swap_stall_timeout_secs: u64,
+    /// Swap 事件广播通道容量 (接收端落后超过该数量时最旧的事件被覆盖)
+    pub swap_channel_capacity: usize,
+    /// 订阅并解析的 Swap 日志格式
+    pub swap_log_formats: Vec<SwapLogFormat>,
+}
+
+/// Swap 日志格式 (V2 / V3 的事件签名和数据布局不同，都解析成统一的 SwapEvent)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapLogFormat {
+    /// Swap(address indexed sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address indexed to)
+    V2,
+    /// Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 sqrtPriceX96, uint128 liquidity, int24 tick)
+    V3,
+}
+
+impl SwapLogFormat {
+    /// 解析配置值 (v2 / v3，不区分大小写)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "v2" => Some(SwapLogFormat::V2),
+            "v3" => Some(SwapLogFormat::V3),
+            _ => None,
+        }
+    }
+
+    /// 事件签名 (topic0)
+    pub fn signature(&self) -> H256 {
+        let event = match self {
+            SwapLogFormat::V2 => "Swap(address,uint256,uint256,uint256,uint256,address)",
+            SwapLogFormat::V3 => "Swap(address,address,int256,int256,uint160,uint128,int24)",
+        };
+        H256::from(ethers::utils::keccak256(event))
+    }
+}
+
+/// Swap 订阅看门狗
+///
+/// 区块和 Swap 日志是两条独立的 WebSocket 订阅，Swap 订阅可能在区块仍正常到达时静默失效。
+/// 每个新区块到达时检查距上次 Swap 的时间，超过阈值即判定 Swap 订阅失效。
+#[derive(Debug, Clone)]
+pub struct SwapStreamWatchdog {
+    timeout: Duration,
+    last_swap_at: Instant,
+}
+
+impl SwapStreamWatchdog {
+    pub fn new(timeout_secs: u64) -> Self {
+        Self {
+            timeout: Duration::from_secs(timeout_secs),
+            last_swap_at: Instant::now(),
+        }
+    }
+
+    /// 是否启用
+    pub fn enabled(&self) -> bool {
+        !self.timeout.is_zero()
+    }
+
+    /// 记录收到 Swap 事件 (重新订阅后也调用，重新开始计时)
+    pub fn record_swap(&mut self) {
+        self.last_swap_at = Instant::now();
+    }
+
+    /// 距离上次 Swap 的时间
+    pub fn elapsed(&self) -> Duration {
+        self.last_swap_at.elapsed()
+    }
+
+    /// 在给定时刻 Swap 订阅是否已停滞
+    pub fn is_stalled_at(&self, now: Instant) -> bool {
+        self.enabled() && now.saturating_duration_since(self.last_swap_at) >= self.timeout
+    }
+
+    /// Swap 订阅是否已停滞
+    pub fn is_stalled(&self) -> bool {
+        self.is_stalled_at(Instant::now())
+    }
 }
 
 /// 新区块事件
@@ -27,7 +109,7 @@ pub struct NewBlockEvent {
     pub gas_limit: U256,
 }
 
-/// Swap 事件 (Uniswap V3)
+/// Swap 事件 (V2/V3 统一格式，V2 事件没有价格状态)
 #[derive(Debug, Clone)]
 pub struct SwapEvent {
     pub pool_address: Address,
@@ -57,12 +139,16 @@ pub struct BlockSubscriber {
     running: RwLock<bool>,
     /// 当前区块号
     current_block: RwLock<u64>,
+    /// 最后一次收到 Swap 事件的时间
+    last_swap_at: RwLock<Option<Instant>>,
+    /// 看门狗触发的 Swap 重新订阅次数
+    swap_resubscribe_count: AtomicU64,
 }
 
 impl BlockSubscriber {
     pub fn new(config: BlockSubscriberConfig) -> Self {
         let (block_tx, _) = broadcast::channel(100);
-        let (swap_tx, _) = broadcast::channel(1000);
+        let (swap_tx, _) = broadcast::channel(config.swap_channel_capacity.max(1));
 
         Self {
             config,
@@ -70,6 +156,8 @@ impl BlockSubscriber {
             swap_tx,
             running: RwLock::new(false),
             current_block: RwLock::new(0),
+            last_swap_at: RwLock::new(None),
+            swap_resubscribe_count: AtomicU64::new(0),
         }
     }
 
@@ -88,6 +176,16 @@ impl BlockSubscriber {
         *self.current_block.read().await
     }
 
+    /// 距离上次收到 Swap 事件的秒数 (尚未收到过则返回 None)
+    pub async fn seconds_since_last_swap(&self) -> Option<u64> {
+        self.last_swap_at.read().await.map(|t| t.elapsed().as_secs())
+    }
+
+    /// 看门狗触发的 Swap 重新订阅次数
+    pub fn swap_resubscribe_count(&self) -> u64 {
+        self.swap_resubscribe_count.load(Ordering::Relaxed)
+    }
+
     /// 启动订阅
     pub async fn start(&self) -> Result<()> {
         let mut running = self.running.write().await;
@@ -144,16 +242,15 @@ impl BlockSubscriber {
 
         info!("WebSocket 已连接");
 
-        // Uniswap V3 Swap 事件签名 (只监控 V3)
-        let swap_v3_signature = H256::from_slice(&ethers::utils::keccak256(
-            "Swap(address,address,int256,int256,uint160,uint128,int24)"
-        ));
+        // 订阅的 Swap 事件签名 (V2/V3 共用一个订阅)
+        let formats = self.config.swap_log_formats.clone();
+        let swap_signatures: Vec<H256> = formats.iter().map(|f| f.signature()).collect();
 
         // 订阅新区块
-        let mut block_stream = provider.subscribe_blocks().await?;
+        let block_stream = provider.subscribe_blocks().await?;
 
         // 检查是否配置了监控池子
-        let has_pools = !self.config.monitored_pools.is_empty();
+        let has_pools = !self.config.monitored_pools.is_empty() && !formats.is_empty();
 
         info!("区块订阅器配置 has_pools: {:?}", has_pools);
 
@@ -163,18 +260,20 @@ impl BlockSubscriber {
             self.config.monitored_pools
         );
 
-        // 订阅 V3 Swap 事件 - 只针对监控的池子地址
+        // 只订阅我们监控的池子的 Swap 事件
+        let v3_filter = Filter::new()
+            .topic0(ValueOrArray::Array(swap_signatures))
+            .address(self.config.monitored_pools.clone());
+
+        // 订阅 Swap 事件 - 只针对监控的池子地址
         // 如果没有配置池子，则不订阅任何 Swap 事件（只订阅区块）
-        let mut v3_log_stream = if has_pools {
-            // 只订阅我们监控的池子的 V3 Swap 事件
-            let v3_filter = Filter::new()
-                .topic0(swap_v3_signature)
-                .address(self.config.monitored_pools.clone());
+        let v3_log_stream = if has_pools {
             let v3_stream = provider.subscribe_logs(&v3_filter).await?;
 
             info!(
-                "已订阅 {} 个池子的 V3 Swap 事件 (只监控指定池子)",
-                self.config.monitored_pools.len()
+                "已订阅 {} 个池子的 Swap 事件 {:?} (只监控指定池子)",
+                self.config.monitored_pools.len(),
+                formats
             );
 
             Some(v3_stream)
@@ -183,6 +282,39 @@ impl BlockSubscriber {
             None
         };
 
+        let provider = &provider;
+        let v3_filter = &v3_filter;
+        self.run_event_loop(block_stream, v3_log_stream, move || async move {
+            Ok(provider.subscribe_logs(v3_filter).await?)
+        })
+        .await
+    }
+
+    /// 接收区块和 Swap 事件直到连接断开
+    ///
+    /// `resubscribe` 在看门狗判定 Swap 订阅失效时重建日志订阅
+    async fn run_event_loop<B, L, F, Fut>(
+        &self,
+        mut block_stream: B,
+        mut v3_log_stream: Option<L>,
+        mut resubscribe: F,
+    ) -> Result<()>
+    where
+        B: Stream<Item = Block<H256>> + Unpin,
+        L: Stream<Item = Log> + Unpin,
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<L>>,
+    {
+        let has_pools = v3_log_stream.is_some();
+        let formats = &self.config.swap_log_formats;
+        let mut watchdog = SwapStreamWatchdog::new(self.config.swap_stall_timeout_secs);
+        if has_pools && watchdog.enabled() {
+            info!(
+                "Swap 订阅看门狗已启用: 超过 {}s 未收到 Swap 事件将重新订阅",
+                self.config.swap_stall_timeout_secs
+            );
+        }
+
         info!("开始接收事件 (纯 WebSocket, 无额外 RPC 调用)...");
 
         loop {
@@ -224,9 +356,36 @@ impl BlockSubscriber {
 
                     // 广播区块事件
                     let _ = self.block_tx.send(event);
+
+                    // 区块仍在推进但 Swap 长时间没有到达 -> Swap 订阅可能已静默失效
+                    if has_pools && watchdog.is_stalled() {
+                        error!(
+                            "🚨 [chain_id={}] Swap 订阅已 {}s 未收到事件 (阈值 {}s)，区块仍在推进，重新订阅 Swap 日志...",
+                            self.config.chain_id,
+                            watchdog.elapsed().as_secs(),
+                            self.config.swap_stall_timeout_secs
+                        );
+
+                        // 先丢弃旧订阅 (drop 时会取消订阅)
+                        drop(v3_log_stream.take());
+                        v3_log_stream = Some(resubscribe().await?);
+
+                        let count = self.swap_resubscribe_count.fetch_add(1, Ordering::Relaxed) + 1;
+                        info!("✅ Swap 日志订阅已重建 (累计重新订阅 {} 次)", count);
+                        watchdog.record_swap();
+                    }
+
+                    // 更新 RPC 统计中的 Swap 订阅状态
+                    if has_pools {
+                        record_swap_stream(
+                            self.config.chain_id,
+                            self.seconds_since_last_swap().await,
+                            self.swap_resubscribe_count(),
+                        );
+                    }
                 }
 
-                // 处理 V3 Swap 事件 (直接从 WebSocket 收到)
+                // 处理 Swap 事件 (直接从 WebSocket 收到)
                 Some(log) = async {
                     match &mut v3_log_stream {
                         Some(stream) => stream.next().await,
@@ -235,9 +394,11 @@ impl BlockSubscriber {
                 } => {
                     // 记录 WebSocket Swap 事件统计
                     record_ws_swap();
+                    watchdog.record_swap();
+                    *self.last_swap_at.write().await = Some(Instant::now());
 
                     let block_number = log.block_number.map(|n| n.as_u64()).unwrap_or(0);
-                    if let Some(event) = self.parse_swap_v3_log(&log, block_number) {
+                    if let Some(event) = Self::decode_swap_log(&log, formats, block_number) {
                         let _ = self.swap_tx.send(event);
                     }
                 }
@@ -252,9 +413,41 @@ impl BlockSubscriber {
         Ok(())
     }
 
+    /// 按 topic0 识别日志格式并解析 (未启用的格式返回 None)
+    pub fn decode_swap_log(log: &Log, formats: &[SwapLogFormat], block_number: u64) -> Option<SwapEvent> {
+        let topic0 = *log.topics.first()?;
+        match formats.iter().find(|format| format.signature() == topic0)? {
+            SwapLogFormat::V2 => Self::parse_swap_v2_log(log, block_number),
+            SwapLogFormat::V3 => Self::parse_swap_v3_log(log, block_number),
+        }
+    }
+
+    /// 解析 V2 Swap 日志 (amount 本身就是 in/out 形式，没有价格状态)
+    fn parse_swap_v2_log(log: &Log, block_number: u64) -> Option<SwapEvent> {
+        // 需要 3 个 topic: signature, sender, to；数据为 4 个 uint256
+        if log.topics.len() < 3 || log.data.len() < 128 {
+            return None;
+        }
+        let word = |i: usize| U256::from_big_endian(&log.data[i * 32..(i + 1) * 32]);
+
+        Some(SwapEvent {
+            pool_address: log.address,
+            sender: Address::from_slice(&log.topics[1].as_bytes()[12..]),
+            amount0_in: word(0),
+            amount1_in: word(1),
+            amount0_out: word(2),
+            amount1_out: word(3),
+            block_number,
+            tx_hash: log.transaction_hash.unwrap_or_default(),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        })
+    }
+
     /// 解析 V3 Swap 日志
     /// V3 Swap: Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 sqrtPriceX96, uint128 liquidity, int24 tick)
-    fn parse_swap_v3_log(&self, log: &Log, block_number: u64) -> Option<SwapEvent> {
+    fn parse_swap_v3_log(log: &Log, block_number: u64) -> Option<SwapEvent> {
         // 需要至少 3 个 topic: signature, sender, recipient
         if log.topics.len() < 3 {
             return None;
@@ -390,3 +583,146 @@ fn i256_from_bytes(bytes: &[u8; 32]) -> i128 {
         i128::from_be_bytes(result_bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_stalls_when_swaps_stop() {
+        let watchdog = SwapStreamWatchdog::new(60);
+        let start = Instant::now();
+
+        // 区块持续推进，但没有新的 Swap
+        assert!(!watchdog.is_stalled_at(start + Duration::from_secs(30)));
+        assert!(watchdog.is_stalled_at(start + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn test_watchdog_reset_on_swap() {
+        let mut watchdog = SwapStreamWatchdog::new(60);
+        watchdog.record_swap();
+        let now = Instant::now();
+        assert!(!watchdog.is_stalled_at(now + Duration::from_secs(59)));
+        assert!(watchdog.is_stalled_at(now + Duration::from_secs(120)));
+    }
+
+    fn swap_log(format: SwapLogFormat, data: Vec<u8>) -> Log {
+        Log {
+            address: Address::repeat_byte(0x01),
+            topics: vec![format.signature(), H256::from(Address::repeat_byte(0x0a)), H256::from(Address::repeat_byte(0x0b))],
+            data: data.into(),
+            transaction_hash: Some(H256::repeat_byte(0x11)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_v2_swap_log() {
+        use ethers::abi::{encode, Token};
+
+        // 卖出 2 个 token0，得到 3000 个 token1
+        let amounts = [U256::exp10(18) * 2, U256::zero(), U256::zero(), U256::exp10(6) * 3000];
+        let log = swap_log(SwapLogFormat::V2, encode(&amounts.map(Token::Uint)));
+        let formats = [SwapLogFormat::V2, SwapLogFormat::V3];
+
+        let event = BlockSubscriber::decode_swap_log(&log, &formats, 100).unwrap();
+        assert_eq!(event.pool_address, Address::repeat_byte(0x01));
+        assert_eq!(event.sender, Address::repeat_byte(0x0a));
+        assert_eq!(
+            [event.amount0_in, event.amount1_in, event.amount0_out, event.amount1_out],
+            amounts
+        );
+        assert_eq!((event.block_number, event.tx_hash), (100, H256::repeat_byte(0x11)));
+        assert!(event.sqrt_price_x96.is_none() && event.liquidity.is_none());
+
+        // 未启用 V2 时忽略
+        assert!(BlockSubscriber::decode_swap_log(&log, &[SwapLogFormat::V3], 100).is_none());
+        // 数据不完整
+        let truncated = swap_log(SwapLogFormat::V2, vec![0u8; 96]);
+        assert!(BlockSubscriber::decode_swap_log(&truncated, &formats, 100).is_none());
+    }
+
+    #[test]
+    fn test_decode_v3_swap_log_by_topic() {
+        use ethers::abi::{encode, Token};
+
+        // amount0 = +1e18 (进入池子)，amount1 = -2500e6 (离开池子)
+        let amount1 = U256::MAX - U256::from(2_500_000_000u64) + 1;
+        let data = encode(&[
+            Token::Int(U256::exp10(18)),
+            Token::Int(amount1),
+            Token::Uint(U256::from(2u64).pow(U256::from(96u64))),
+            Token::Uint(U256::from(7_000u64)),
+            Token::Int(U256::from(60u64)),
+        ]);
+        let log = swap_log(SwapLogFormat::V3, data);
+
+        let event = BlockSubscriber::decode_swap_log(&log, &[SwapLogFormat::V2, SwapLogFormat::V3], 7).unwrap();
+        assert_eq!((event.amount0_in, event.amount1_out), (U256::exp10(18), U256::from(2_500_000_000u64)));
+        assert_eq!((event.liquidity, event.tick), (Some(7_000), Some(60)));
+        assert_eq!(SwapLogFormat::parse(" V2 "), Some(SwapLogFormat::V2));
+        assert_eq!(SwapLogFormat::parse("v4"), None);
+    }
+
+    fn test_subscriber(chain_id: u64, swap_stall_timeout_secs: u64) -> BlockSubscriber {
+        BlockSubscriber::new(BlockSubscriberConfig {
+            ws_url: String::new(),
+            chain_id,
+            reconnect_delay_secs: 0,
+            monitored_pools: vec![Address::repeat_byte(0x01)],
+            swap_stall_timeout_secs,
+            swap_channel_capacity: 16,
+            swap_log_formats: vec![SwapLogFormat::V3],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_stalled_swap_stream_is_resubscribed() {
+        use futures_util::stream::{self, BoxStream};
+        use std::sync::atomic::AtomicUsize;
+
+        const CHAIN: u64 = 990_843;
+        let subscriber = test_subscriber(CHAIN, 1);
+        *subscriber.running.write().await = true;
+
+        // 区块持续到达，Swap 订阅一直没有事件 (静默失效)
+        let blocks = stream::iter(1..=3u64)
+            .then(|n| async move {
+                if n == 3 {
+                    tokio::time::sleep(Duration::from_millis(1100)).await;
+                }
+                Block::<H256> { number: Some(n.into()), ..Default::default() }
+            })
+            .boxed();
+        let resubscribes = AtomicUsize::new(0);
+        subscriber
+            .run_event_loop(blocks, Some(stream::empty::<Log>().boxed()), || async {
+                resubscribes.fetch_add(1, Ordering::Relaxed);
+                Ok::<BoxStream<'static, Log>, anyhow::Error>(stream::empty().boxed())
+            })
+            .await
+            .unwrap();
+
+        // 超过阈值后的新区块触发一次重新订阅
+        assert_eq!(resubscribes.load(Ordering::Relaxed), 1);
+        assert_eq!(subscriber.swap_resubscribe_count(), 1);
+        assert_eq!(subscriber.current_block().await, 3);
+        assert_eq!(subscriber.seconds_since_last_swap().await, None);
+
+        // 状态同步到 RPC 统计
+        let stream = ::utils::get_api_stats()
+            .swap_streams
+            .into_iter()
+            .find(|s| s.chain_id == CHAIN)
+            .unwrap();
+        assert_eq!((stream.seconds_since_last_swap, stream.resubscribe_count), (None, 1));
+    }
+
+    #[test]
+    fn test_watchdog_disabled() {
+        let watchdog = SwapStreamWatchdog::new(0);
+        assert!(!watchdog.enabled());
+        assert!(!watchdog.is_stalled_at(Instant::now() + Duration::from_secs(3600)));
+    }
+}
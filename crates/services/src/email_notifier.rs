@@ -109,6 +109,35 @@ pub struct WalletBalance {
     pub usd_value: Decimal,
 }
 
+/// 利润多币种换算 (原生代币、参考稳定币)
+///
+/// 价格缺失的币种在展示时省略
+#[derive(Clone, Debug, Default)]
+pub struct ProfitCurrencies {
+    /// 原生代币符号 (ETH/BNB 等)
+    pub native_symbol: String,
+    /// 原生代币价格 (USD)
+    pub native_price_usd: Option<Decimal>,
+    /// 参考稳定币符号 (USDC/USDT 等)
+    pub stablecoin_symbol: String,
+    /// 参考稳定币价格 (USD)
+    pub stablecoin_price_usd: Option<Decimal>,
+}
+
+impl ProfitCurrencies {
+    /// 格式化为 "$5.20 / 0.0021 ETH / 5.19 USDC"
+    pub fn format(&self, usd: Decimal) -> String {
+        let mut parts = vec![format!("${:.2}", usd)];
+        if let Some(price) = self.native_price_usd.filter(|p| *p > Decimal::ZERO) {
+            parts.push(format!("{:.4} {}", (usd / price).round_dp(4), self.native_symbol));
+        }
+        if let Some(price) = self.stablecoin_price_usd.filter(|p| *p > Decimal::ZERO) {
+            parts.push(format!("{:.2} {}", (usd / price).round_dp(2), self.stablecoin_symbol));
+        }
+        parts.join(" / ")
+    }
+}
+
 /// 套利执行信息
 #[derive(Clone, Debug)]
 pub struct ArbitrageExecutionInfo {
@@ -136,6 +165,15 @@ pub struct ArbitrageExecutionInfo {
     pub block_number: u64,
     /// 错误信息 (如果失败)
     pub error_message: Option<String>,
+    /// 利润换算用的币种及价格
+    pub profit_currencies: ProfitCurrencies,
+}
+
+impl ArbitrageExecutionInfo {
+    /// 净利润 (有实际利润时使用实际利润，否则为预期利润)
+    pub fn net_profit_usd(&self) -> Decimal {
+        self.actual_profit_usd.unwrap_or(self.expected_profit_usd)
+    }
 }
 
 /// 邮件通知器
@@ -320,135 +358,7 @@ impl EmailNotifier {
         balances_before: &[WalletBalance],
         balances_after: &[WalletBalance],
     ) -> Result<()> {
-        let is_success = execution_info.status == "Confirmed" || execution_info.status == "Success";
-        let status_emoji = if is_success { "✅" } else { "❌" };
-        let status_class = if is_success { "success-box" } else { "error-box" };
-
-        // 构建余额前后对比表格
-        let mut balance_rows = String::new();
-        for before in balances_before {
-            let after = balances_after.iter()
-                .find(|b| b.symbol == before.symbol)
-                .cloned()
-                .unwrap_or(before.clone());
-
-            let change = after.usd_value - before.usd_value;
-            let change_class = if change >= Decimal::ZERO { "profit" } else { "loss" };
-            let change_sign = if change >= Decimal::ZERO { "+" } else { "" };
-
-            balance_rows.push_str(&format!(
-                r#"<tr>
-                    <td>{}</td>
-                    <td>{}</td>
-                    <td>{}</td>
-                    <td class="{}">{}{:.4}</td>
-                </tr>"#,
-                before.symbol,
-                before.balance,
-                after.balance,
-                change_class,
-                change_sign,
-                change
-            ));
-        }
-
-        // 计算总 USD 变化
-        let total_before: Decimal = balances_before.iter().map(|b| b.usd_value).sum();
-        let total_after: Decimal = balances_after.iter().map(|b| b.usd_value).sum();
-        let total_change = total_after - total_before;
-        let total_change_class = if total_change >= Decimal::ZERO { "profit" } else { "loss" };
-        let total_change_sign = if total_change >= Decimal::ZERO { "+" } else { "" };
-
-        let actual_profit_html = if let Some(profit) = execution_info.actual_profit_usd {
-            format!("<p><strong>实际利润:</strong> <span class=\"profit\">${:.4}</span></p>", profit)
-        } else {
-            String::new()
-        };
-
-        let error_html = if let Some(ref err) = execution_info.error_message {
-            format!(
-                r#"<div class="error-box">
-                    <strong>错误信息:</strong><br>
-                    <code>{}</code>
-                </div>"#,
-                err
-            )
-        } else {
-            String::new()
-        };
-
-        let tx_hash_html = if let Some(ref hash) = execution_info.tx_hash {
-            format!("<p><strong>交易哈希:</strong> <code>{}</code></p>", hash)
-        } else {
-            String::new()
-        };
-
-        let content = format!(
-            r#"
-            <div class="{}">
-                <h3>{} 套利执行{}</h3>
-            </div>
-
-            <div class="info-box">
-                <h4>执行信息</h4>
-                <p><strong>链:</strong> {}</p>
-                <p><strong>机会 ID:</strong> {}</p>
-                <p><strong>区块:</strong> {}</p>
-                <p><strong>路径:</strong> {}</p>
-                <p><strong>输入:</strong> {} {}</p>
-                <p><strong>预期利润:</strong> ${:.4}</p>
-                {}
-                <p><strong>Gas 费用:</strong> ${:.4}</p>
-                {}
-                {}
-            </div>
-
-            <h4>钱包余额变化</h4>
-            <table class="balance-table">
-                <thead>
-                    <tr>
-                        <th>代币</th>
-                        <th>套利前</th>
-                        <th>套利后</th>
-                        <th>变化 (USD)</th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {}
-                    <tr style="font-weight: bold; background: #f8f9fa;">
-                        <td colspan="3">总计 (USD)</td>
-                        <td class="{}">{}{:.4}</td>
-                    </tr>
-                </tbody>
-            </table>
-            "#,
-            status_class,
-            status_emoji,
-            if is_success { "成功" } else { "失败" },
-            execution_info.chain_name,
-            execution_info.opportunity_id,
-            execution_info.block_number,
-            execution_info.path_description,
-            execution_info.input_amount,
-            execution_info.input_token,
-            execution_info.expected_profit_usd,
-            actual_profit_html,
-            execution_info.gas_cost_usd,
-            tx_hash_html,
-            error_html,
-            balance_rows,
-            total_change_class,
-            total_change_sign,
-            total_change
-        );
-
-        let title = format!(
-            "{} {} 套利 - {}",
-            status_emoji,
-            execution_info.chain_name,
-            if is_success { "成功" } else { "失败" }
-        );
-
+        let (title, content) = render_arbitrage_notification(execution_info, balances_before, balances_after);
         self.send_html_notification(&title, &content).await
     }
 
@@ -487,6 +397,29 @@ impl EmailNotifier {
         self.send_html_notification(&format!("⚠️ {}", error_title), &content).await
     }
 
+    /// 发送高优先级告警 (需要人工立即介入的情况，如实际利润与预估严重偏离)
+    pub async fn send_alert_notification(
+        &self,
+        alert_title: &str,
+        alert_message: &str,
+        details: &str,
+    ) -> Result<()> {
+        let content = format!(
+            r#"<div class="error-box">
+                <div style="color: #dc3545; font-weight: bold; font-size: 16px; margin-bottom: 10px;">
+                    {}
+                </div>
+            </div>
+            <div class="info-box">
+                <pre style="margin: 0; white-space: pre-wrap; word-break: break-word;">{}</pre>
+            </div>"#,
+            alert_message,
+            details
+        );
+
+        self.send_html_notification(&format!("🚨 [高优先级] {}", alert_title), &content).await
+    }
+
     /// 发送成功通知
     pub async fn send_success_notification(
         &self,
@@ -506,6 +439,150 @@ impl EmailNotifier {
     }
 }
 
+/// 渲染套利执行通知，返回 (标题, HTML 内容)
+fn render_arbitrage_notification(
+    execution_info: &ArbitrageExecutionInfo,
+    balances_before: &[WalletBalance],
+    balances_after: &[WalletBalance],
+) -> (String, String) {
+    let is_success = execution_info.status == "Confirmed" || execution_info.status == "Success";
+    let status_emoji = if is_success { "✅" } else { "❌" };
+    let status_class = if is_success { "success-box" } else { "error-box" };
+
+    // 构建余额前后对比表格
+    let mut balance_rows = String::new();
+    for before in balances_before {
+        let after = balances_after.iter()
+            .find(|b| b.symbol == before.symbol)
+            .cloned()
+            .unwrap_or(before.clone());
+
+        let change = after.usd_value - before.usd_value;
+        let change_class = if change >= Decimal::ZERO { "profit" } else { "loss" };
+        let change_sign = if change >= Decimal::ZERO { "+" } else { "" };
+
+        balance_rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td class="{}">{}{:.4}</td>
+            </tr>"#,
+            before.symbol,
+            before.balance,
+            after.balance,
+            change_class,
+            change_sign,
+            change
+        ));
+    }
+
+    // 计算总 USD 变化
+    let total_before: Decimal = balances_before.iter().map(|b| b.usd_value).sum();
+    let total_after: Decimal = balances_after.iter().map(|b| b.usd_value).sum();
+    let total_change = total_after - total_before;
+    let total_change_class = if total_change >= Decimal::ZERO { "profit" } else { "loss" };
+    let total_change_sign = if total_change >= Decimal::ZERO { "+" } else { "" };
+
+    let currencies = &execution_info.profit_currencies;
+    let actual_profit_html = if let Some(profit) = execution_info.actual_profit_usd {
+        format!("<p><strong>实际利润:</strong> <span class=\"profit\">${:.4}</span></p>", profit)
+    } else {
+        String::new()
+    };
+    let net_profit = execution_info.net_profit_usd();
+    let net_profit_class = if net_profit >= Decimal::ZERO { "profit" } else { "loss" };
+
+    let error_html = if let Some(ref err) = execution_info.error_message {
+        format!(
+            r#"<div class="error-box">
+                <strong>错误信息:</strong><br>
+                <code>{}</code>
+            </div>"#,
+            err
+        )
+    } else {
+        String::new()
+    };
+
+    let tx_hash_html = if let Some(ref hash) = execution_info.tx_hash {
+        format!("<p><strong>交易哈希:</strong> <code>{}</code></p>", hash)
+    } else {
+        String::new()
+    };
+
+    let content = format!(
+        r#"
+        <div class="{}">
+            <h3>{} 套利执行{}</h3>
+        </div>
+
+        <div class="info-box">
+            <h4>执行信息</h4>
+            <p><strong>链:</strong> {}</p>
+            <p><strong>机会 ID:</strong> {}</p>
+            <p><strong>区块:</strong> {}</p>
+            <p><strong>路径:</strong> {}</p>
+            <p><strong>输入:</strong> {} {}</p>
+            <p><strong>预期利润:</strong> ${:.4}</p>
+            {}
+            <p><strong>Gas 费用:</strong> ${:.4}</p>
+            <p><strong>净利润:</strong> <span class="{}">{}</span></p>
+            {}
+            {}
+        </div>
+
+        <h4>钱包余额变化</h4>
+        <table class="balance-table">
+            <thead>
+                <tr>
+                    <th>代币</th>
+                    <th>套利前</th>
+                    <th>套利后</th>
+                    <th>变化 (USD)</th>
+                </tr>
+            </thead>
+            <tbody>
+                {}
+                <tr style="font-weight: bold; background: #f8f9fa;">
+                    <td colspan="3">总计 (USD)</td>
+                    <td class="{}">{}{:.4}</td>
+                </tr>
+            </tbody>
+        </table>
+        "#,
+        status_class,
+        status_emoji,
+        if is_success { "成功" } else { "失败" },
+        execution_info.chain_name,
+        execution_info.opportunity_id,
+        execution_info.block_number,
+        execution_info.path_description,
+        execution_info.input_amount,
+        execution_info.input_token,
+        execution_info.expected_profit_usd,
+        actual_profit_html,
+        execution_info.gas_cost_usd,
+        net_profit_class,
+        currencies.format(net_profit),
+        tx_hash_html,
+        error_html,
+        balance_rows,
+        total_change_class,
+        total_change_sign,
+        total_change
+    );
+
+    let title = format!(
+        "{} {} 套利 - {}",
+        status_emoji,
+        execution_info.chain_name,
+        if is_success { "成功" } else { "失败" }
+    );
+
+    (title, content)
+}
+
 impl Clone for EmailNotifier {
     fn clone(&self) -> Self {
         Self {
@@ -533,3 +610,51 @@ pub fn get_email_notifier() -> Option<Arc<EmailNotifier>> {
         }
     }).clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn sample_execution_info() -> ArbitrageExecutionInfo {
+        ArbitrageExecutionInfo {
+            chain_name: "Ethereum".to_string(),
+            opportunity_id: "opp-1".to_string(),
+            path_description: "WETH -> USDC -> USDT -> WETH".to_string(),
+            input_token: "WETH".to_string(),
+            input_amount: "1000000000000000000".to_string(),
+            expected_profit_usd: Decimal::from_str("5.20").unwrap(),
+            actual_profit_usd: Some(Decimal::from_str("5.20").unwrap()),
+            gas_cost_usd: Decimal::from_str("1.10").unwrap(),
+            tx_hash: None,
+            status: "Confirmed".to_string(),
+            block_number: 100,
+            error_message: None,
+            profit_currencies: ProfitCurrencies {
+                native_symbol: "ETH".to_string(),
+                native_price_usd: Some(Decimal::from_str("2476.19").unwrap()),
+                stablecoin_symbol: "USDC".to_string(),
+                stablecoin_price_usd: Some(Decimal::from_str("1.002").unwrap()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_notification_shows_profit_in_all_currencies() {
+        let (title, content) = render_arbitrage_notification(&sample_execution_info(), &[], &[]);
+
+        assert!(title.contains("成功"));
+        assert!(content.contains("<strong>净利润:</strong> <span class=\"profit\">$5.20 / 0.0021 ETH / 5.19 USDC</span>"));
+    }
+
+    #[test]
+    fn test_profit_currencies_skip_missing_prices() {
+        let currencies = ProfitCurrencies {
+            native_symbol: "BNB".to_string(),
+            native_price_usd: None,
+            stablecoin_symbol: "USDT".to_string(),
+            stablecoin_price_usd: Some(Decimal::ONE),
+        };
+        assert_eq!(currencies.format(Decimal::from_str("5.2").unwrap()), "$5.20 / 5.20 USDT");
+    }
+}
@@ -0,0 +1,177 @@
+//! Gas 价格来源
+//!
+//! 默认直接用节点的 eth_gasPrice；部分链上专门的 gas oracle API (Blocknative 风格)
+//! 对下一个区块的估计更准，也可以只取最新区块的 base fee。
+//! 非节点来源失败时由调用方回退到节点
+
+use anyhow::{anyhow, Context, Result};
+use ethers::types::U256;
+use std::time::Duration;
+
+/// oracle 请求超时 (gas price 在热路径上，宁可回退节点也不能等太久)
+const GAS_ORACLE_TIMEOUT: Duration = Duration::from_secs(2);
+/// 默认取 99% 置信度的估计
+const DEFAULT_ORACLE_CONFIDENCE: u32 = 99;
+
+/// Gas 价格来源
+#[derive(Debug, Clone, Default)]
+pub enum GasPriceSource {
+    /// 节点 eth_gasPrice
+    #[default]
+    Node,
+    /// 最新区块的 base fee
+    BaseFee,
+    /// 外部 gas oracle API
+    Oracle(GasOracleClient),
+}
+
+impl GasPriceSource {
+    /// 从配置解析: `node` / `base_fee` / `oracle` (oracle 需要配置 URL)
+    pub fn from_config(kind: &str, oracle_url: Option<&str>, api_key: Option<&str>) -> Result<Self> {
+        match kind.trim().to_lowercase().as_str() {
+            "" | "node" => Ok(Self::Node),
+            "base_fee" | "basefee" => Ok(Self::BaseFee),
+            "oracle" => {
+                let url = oracle_url
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .ok_or_else(|| anyhow!("gas price 来源为 oracle 但未配置 oracle URL"))?;
+                Ok(Self::Oracle(GasOracleClient::new(url, api_key)))
+            }
+            other => Err(anyhow!("未知的 gas price 来源: {} (可选 node / base_fee / oracle)", other)),
+        }
+    }
+
+    /// 来源名称 (日志用)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::BaseFee => "base_fee",
+            Self::Oracle(_) => "oracle",
+        }
+    }
+}
+
+/// Blocknative 风格的 gas oracle 客户端
+///
+/// 响应格式: `{"blockPrices": [{"estimatedPrices": [{"confidence": 99, "price": 12.5, ...}]}]}`，价格单位 Gwei
+#[derive(Debug, Clone)]
+pub struct GasOracleClient {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    confidence: u32,
+}
+
+impl GasOracleClient {
+    pub fn new(url: &str, api_key: Option<&str>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(GAS_ORACLE_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            url: url.to_string(),
+            api_key: api_key.map(|k| k.to_string()).filter(|k| !k.is_empty()),
+            confidence: DEFAULT_ORACLE_CONFIDENCE,
+        }
+    }
+
+    /// 请求 oracle 获取下一个区块的 gas price (wei)
+    pub async fn fetch_gas_price(&self) -> Result<U256> {
+        let mut request = self.client.get(&self.url);
+        if let Some(ref api_key) = self.api_key {
+            request = request.header("Authorization", api_key);
+        }
+
+        let body: serde_json::Value = request
+            .send()
+            .await
+            .context("gas oracle 请求失败")?
+            .error_for_status()
+            .context("gas oracle 返回错误状态")?
+            .json()
+            .await
+            .context("gas oracle 响应不是合法 JSON")?;
+
+        parse_blocknative_gas_price(&body, self.confidence)
+            .ok_or_else(|| anyhow!("gas oracle 响应中没有可用的价格"))
+    }
+}
+
+/// 从 Blocknative 风格响应中取出 gas price (wei)
+///
+/// 选置信度不低于 `confidence` 的估计里最便宜的一个，都达不到时取置信度最高的
+pub fn parse_blocknative_gas_price(body: &serde_json::Value, confidence: u32) -> Option<U256> {
+    let estimates = body
+        .get("blockPrices")?
+        .as_array()?
+        .first()?
+        .get("estimatedPrices")?
+        .as_array()?;
+
+    let prices: Vec<(u64, f64)> = estimates
+        .iter()
+        .filter_map(|e| Some((e.get("confidence")?.as_u64()?, e.get("price")?.as_f64()?)))
+        .filter(|(_, price)| *price > 0.0)
+        .collect();
+
+    let qualified = prices
+        .iter()
+        .filter(|(c, _)| *c >= confidence as u64)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let (_, price_gwei) = qualified.or_else(|| prices.iter().max_by_key(|(c, _)| *c))?;
+
+    Some(gwei_to_wei(*price_gwei))
+}
+
+/// Gwei (可带小数) -> wei
+fn gwei_to_wei(gwei: f64) -> U256 {
+    // 精确到 0.001 Gwei
+    U256::from((gwei * 1000.0).round() as u64) * U256::from(1_000_000u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_blocknative_gas_price() {
+        let body = json!({
+            "blockPrices": [{
+                "blockNumber": 100,
+                "estimatedPrices": [
+                    {"confidence": 99, "price": 12.5, "maxPriorityFeePerGas": 1.2, "maxFeePerGas": 24.1},
+                    {"confidence": 95, "price": 11, "maxPriorityFeePerGas": 1.0, "maxFeePerGas": 23.0},
+                    {"confidence": 70, "price": 10, "maxPriorityFeePerGas": 0.5, "maxFeePerGas": 22.0}
+                ]
+            }]
+        });
+
+        assert_eq!(parse_blocknative_gas_price(&body, 99), Some(U256::from(12_500_000_000u64)));
+        assert_eq!(parse_blocknative_gas_price(&body, 90), Some(U256::from(11_000_000_000u64)));
+
+        // 没有达到置信度要求的估计时取置信度最高的
+        let low = json!({"blockPrices": [{"estimatedPrices": [{"confidence": 80, "price": 0.05}]}]});
+        assert_eq!(parse_blocknative_gas_price(&low, 99), Some(U256::from(50_000_000u64)));
+
+        assert_eq!(parse_blocknative_gas_price(&json!({"blockPrices": []}), 99), None);
+        assert_eq!(parse_blocknative_gas_price(&json!({"error": "unauthorized"}), 99), None);
+    }
+
+    #[test]
+    fn test_gas_price_source_from_config() {
+        assert!(matches!(GasPriceSource::from_config("node", None, None).unwrap(), GasPriceSource::Node));
+        assert!(matches!(GasPriceSource::from_config("", None, None).unwrap(), GasPriceSource::Node));
+        assert!(matches!(GasPriceSource::from_config("BASE_FEE", None, None).unwrap(), GasPriceSource::BaseFee));
+
+        let oracle = GasPriceSource::from_config("oracle", Some("https://api.blocknative.com/gasprices/blockprices"), Some("key"))
+            .unwrap();
+        assert_eq!(oracle.name(), "oracle");
+
+        // oracle 必须配置 URL
+        assert!(GasPriceSource::from_config("oracle", None, None).is_err());
+        assert!(GasPriceSource::from_config("etherscan", None, None).is_err());
+    }
+}
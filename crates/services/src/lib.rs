@@ -1,15 +1,25 @@
 mod database;
+mod migrations;
 mod price_fetcher;
 mod gas_estimator;
 mod pool_syncer;
 mod price_service;
 mod block_subscriber;
 mod email_notifier;
+mod token_metadata;
+mod event_sink;
+mod gas_price_source;
+mod pool_config_csv;
 
 pub use database::*;
+pub use migrations::*;
 pub use price_fetcher::*;
 pub use gas_estimator::*;
 pub use pool_syncer::*;
 pub use price_service::*;
 pub use block_subscriber::*;
 pub use email_notifier::*;
+pub use token_metadata::*;
+pub use event_sink::*;
+pub use gas_price_source::*;
+pub use pool_config_csv::*;
@@ -330,6 +330,7 @@ fn dex_type_to_string(dex_type: DexType) -> String {
         DexType::Curve => "curve".to_string(),
         DexType::PancakeSwapV2 => "pancakeswap_v2".to_string(),
         DexType::PancakeSwapV3 => "pancakeswap_v3".to_string(),
+        DexType::PancakeSwapStable => "pancakeswap_stable".to_string(),
         DexType::SushiSwap => "sushiswap".to_string(),
         DexType::SushiSwapV2 => "sushiswap_v2".to_string(),
         DexType::SushiSwapV3 => "sushiswap_v3".to_string(),
@@ -344,6 +345,7 @@ fn parse_dex_type(s: &str) -> DexType {
         "curve" => DexType::Curve,
         "pancakeswap_v2" | "pancakeswapv2" => DexType::PancakeSwapV2,
         "pancakeswap_v3" | "pancakeswapv3" => DexType::PancakeSwapV3,
+        "pancakeswap_stable" | "pancakeswapstable" => DexType::PancakeSwapStable,
         "sushiswap" => DexType::SushiSwap,
         "sushiswap_v2" | "sushiswapv2" => DexType::SushiSwapV2,
         "sushiswap_v3" | "sushiswapv3" => DexType::SushiSwapV3,
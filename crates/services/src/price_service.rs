@@ -1,9 +1,10 @@
 use anyhow::Result;
 use ethers::types::Address;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
 use tracing::{info, warn, debug};
@@ -49,10 +50,37 @@ pub struct PriceService {
     prices: RwLock<HashMap<String, TokenPrice>>,
     /// address -> symbol 映射
     address_to_symbol: RwLock<HashMap<Address, String>>,
+    /// 各链扫描器额外注册的价格符号 (去重，多条链注册同一符号只请求一次)
+    extra_symbols: RwLock<BTreeSet<String>>,
+    /// 上游 (币安) 价格请求累计次数
+    upstream_fetches: AtomicU64,
     /// 是否正在运行
     running: RwLock<bool>,
 }
 
+/// 内置的交易对 (相对 USDT)
+const BUILTIN_PAIRS: &[(&str, &str)] = &[
+    // 主流代币
+    ("ETHUSDT", "ETH"),
+    ("BTCUSDT", "BTC"),
+    ("BNBUSDT", "BNB"),
+    ("DAIUSDT", "DAI"),
+    // DeFi 代币
+    ("UNIUSDT", "UNI"),
+    ("LINKUSDT", "LINK"),
+    ("AAVEUSDT", "AAVE"),
+    ("MKRUSDT", "MKR"),
+    ("CRVUSDT", "CRV"),
+    ("LDOUSDT", "LDO"),
+    ("APEUSDT", "APE"),
+    // Meme 币
+    ("PEPEUSDT", "PEPE"),
+    ("SHIBUSDT", "SHIB"),
+];
+
+/// 固定价格 / 与其他代币挂钩的符号，不需要请求上游
+const NON_FETCHED_SYMBOLS: &[&str] = &["USDT", "USDC", "BUSD", "stETH"];
+
 impl PriceService {
     pub fn new(config: PriceServiceConfig) -> Self {
         Self {
@@ -63,6 +91,8 @@ impl PriceService {
                 .unwrap_or_default(),
             prices: RwLock::new(HashMap::new()),
             address_to_symbol: RwLock::new(HashMap::new()),
+            extra_symbols: RwLock::new(BTreeSet::new()),
+            upstream_fetches: AtomicU64::new(0),
             running: RwLock::new(false),
         }
     }
@@ -202,27 +232,45 @@ impl PriceService {
         *running = false;
     }
 
+    /// 注册需要报价的符号 (各链扫描器加载代币配置时调用)
+    ///
+    /// 内置符号、稳定币和已注册的符号会被忽略
+    pub async fn register_symbol(&self, symbol: &str) {
+        let symbol = symbol.trim();
+        if symbol.is_empty()
+            || NON_FETCHED_SYMBOLS.contains(&symbol)
+            || BUILTIN_PAIRS.iter().any(|(_, s)| *s == symbol)
+        {
+            return;
+        }
+
+        let mut extra = self.extra_symbols.write().await;
+        if extra.insert(symbol.to_string()) {
+            debug!("注册价格符号: {}", symbol);
+        }
+    }
+
+    /// 本轮需要请求的交易对: (交易对, 符号)，每个符号只出现一次
+    async fn fetch_plan(&self) -> Vec<(String, String)> {
+        let mut plan: Vec<(String, String)> = BUILTIN_PAIRS
+            .iter()
+            .map(|(pair, symbol)| (pair.to_string(), symbol.to_string()))
+            .collect();
+
+        let extra = self.extra_symbols.read().await;
+        plan.extend(extra.iter().map(|symbol| (format!("{}USDT", symbol), symbol.clone())));
+        plan
+    }
+
+    /// 上游价格请求累计次数
+    pub fn upstream_fetch_count(&self) -> u64 {
+        self.upstream_fetches.load(Ordering::Relaxed)
+    }
+
     /// 更新所有价格 (使用币安现货 API)
     async fn update_all_prices(&self) -> Result<()> {
         // 需要获取价格的交易对 (相对 USDT)
-        let symbols = vec![
-            // 主流代币
-            ("ETHUSDT", "ETH"),
-            ("BTCUSDT", "BTC"),
-            ("BNBUSDT", "BNB"),
-            ("DAIUSDT", "DAI"),
-            // DeFi 代币
-            ("UNIUSDT", "UNI"),
-            ("LINKUSDT", "LINK"),
-            ("AAVEUSDT", "AAVE"),
-            ("MKRUSDT", "MKR"),
-            ("CRVUSDT", "CRV"),
-            ("LDOUSDT", "LDO"),
-            ("APEUSDT", "APE"),
-            // Meme 币
-            ("PEPEUSDT", "PEPE"),
-            ("SHIBUSDT", "SHIB"),
-        ];
+        let symbols = self.fetch_plan().await;
 
         // stETH 价格等于 ETH (Liquid Staking 代币)
         let eth_linked_tokens = vec!["stETH"];
@@ -255,7 +303,7 @@ impl PriceService {
             match self.fetch_binance_price(pair).await {
                 Ok(price) => {
                     // 保存 ETH 价格用于关联代币
-                    if *symbol == "ETH" {
+                    if symbol == "ETH" {
                         eth_price = price;
                     }
                     prices.insert(
@@ -286,12 +334,18 @@ impl PriceService {
             );
         }
 
-        info!("价格更新完成, {} 个代币", prices.len());
+        info!(
+            "价格更新完成, {} 个代币 (本轮请求 {} 个交易对, 累计上游请求 {} 次)",
+            prices.len(),
+            symbols.len(),
+            self.upstream_fetch_count()
+        );
         Ok(())
     }
 
     /// 从币安获取单个交易对价格
     async fn fetch_binance_price(&self, symbol: &str) -> Result<Decimal> {
+        self.upstream_fetches.fetch_add(1, Ordering::Relaxed);
         let url = format!(
             "{}/api/v3/ticker/price?symbol={}",
             self.config.binance_api_url, symbol
@@ -304,6 +358,11 @@ impl PriceService {
         Ok(price)
     }
 
+    /// 立即向币安请求一次 `{symbol}USDT` 的价格 (不写入缓存，自检用)
+    pub async fn fetch_price_now(&self, symbol: &str) -> Result<Decimal> {
+        self.fetch_binance_price(&format!("{}USDT", symbol.trim().to_uppercase())).await
+    }
+
     /// 获取 ETH 价格
     pub async fn get_eth_price(&self) -> Decimal {
         let prices = self.prices.read().await;
@@ -322,6 +381,27 @@ impl PriceService {
             .unwrap_or(Decimal::from(300))
     }
 
+    /// 获取链原生代币 (ETH/BNB/MATIC/AVAX) 的价格，还没有实时价格时使用该代币自己的兜底价
+    pub async fn get_native_token_price(&self, symbol: &str) -> Decimal {
+        if let Some(price) = self.get_price_by_symbol(symbol).await.filter(|price| *price > Decimal::ZERO) {
+            return price;
+        }
+        match symbol {
+            "BNB" => self.get_bnb_price().await,
+            "MATIC" | "POL" => Decimal::new(5, 1),
+            "AVAX" => Decimal::from(25),
+            _ => self.get_eth_price().await,
+        }
+    }
+
+    /// 直接写入一个价格 (测试及手动覆盖用，下一轮更新时会被币安价格覆盖)
+    pub async fn set_price(&self, symbol: &str, price_usd: Decimal) {
+        self.prices.write().await.insert(
+            symbol.to_string(),
+            TokenPrice { symbol: symbol.to_string(), price_usd, last_updated: chrono::Utc::now() },
+        );
+    }
+
     /// 获取代币价格 (通过 symbol)
     pub async fn get_price_by_symbol(&self, symbol: &str) -> Option<Decimal> {
         let prices = self.prices.read().await;
@@ -357,3 +437,41 @@ pub type SharedPriceService = Arc<PriceService>;
 pub fn create_price_service(config: PriceServiceConfig) -> SharedPriceService {
     Arc::new(PriceService::new(config))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_plan_dedups_symbols_across_chains() {
+        let service = create_price_service(PriceServiceConfig::default());
+        let builtin = service.fetch_plan().await.len();
+
+        // ETH 链和 Arbitrum 链都注册了 ARB，BSC 注册了内置的 BNB 和稳定币
+        for symbol in ["ARB", "ETH", "USDT"] {
+            service.register_symbol(symbol).await;
+        }
+        for symbol in ["ARB", "BNB", "CAKE"] {
+            service.register_symbol(symbol).await;
+        }
+
+        let plan = service.fetch_plan().await;
+        assert_eq!(plan.len(), builtin + 2);
+        assert_eq!(plan.iter().filter(|(_, s)| s == "ARB").count(), 1);
+        assert_eq!(plan.iter().filter(|(_, s)| s == "ETH").count(), 1);
+        assert!(plan.iter().any(|(pair, s)| pair == "CAKEUSDT" && s == "CAKE"));
+        assert_eq!(service.upstream_fetch_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_native_token_price_falls_back_per_token() {
+        let service = create_price_service(PriceServiceConfig::default());
+        service.set_price("AVAX", Decimal::from(30)).await;
+
+        assert_eq!(service.get_native_token_price("AVAX").await, Decimal::from(30));
+        // 没有实时价格: 各自的兜底价，不是 ETH 价格
+        assert_eq!(service.get_native_token_price("MATIC").await, Decimal::new(5, 1));
+        assert_eq!(service.get_native_token_price("BNB").await, Decimal::from(300));
+        assert_eq!(service.get_native_token_price("ETH").await, Decimal::from(2000));
+    }
+}
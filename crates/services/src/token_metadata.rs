@@ -0,0 +1,94 @@
+//! 跨链共享的代币元数据缓存
+//!
+//! 单进程同时运行多条链时，所有链的扫描器共用一份缓存，
+//! 按 (chain_id, address) 区分，不同链上相同地址的代币互不覆盖
+
+use ethers::types::Address;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 代币元数据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub symbol: String,
+    pub decimals: u8,
+    /// 价格服务中使用的符号 (如 WETH -> ETH)
+    pub price_symbol: String,
+    pub is_stable: bool,
+}
+
+/// 代币元数据缓存
+#[derive(Debug, Default)]
+pub struct TokenMetadataCache {
+    /// (chain_id, address) -> 元数据
+    entries: RwLock<HashMap<(u64, Address), TokenMetadata>>,
+}
+
+impl TokenMetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入单个代币元数据
+    pub async fn insert(&self, chain_id: u64, address: Address, metadata: TokenMetadata) {
+        let mut entries = self.entries.write().await;
+        entries.insert((chain_id, address), metadata);
+    }
+
+    /// 查询代币元数据
+    pub async fn get(&self, chain_id: u64, address: Address) -> Option<TokenMetadata> {
+        let entries = self.entries.read().await;
+        entries.get(&(chain_id, address)).cloned()
+    }
+
+    /// 某条链已缓存的代币数量
+    pub async fn count_for_chain(&self, chain_id: u64) -> usize {
+        let entries = self.entries.read().await;
+        entries.keys().filter(|(id, _)| *id == chain_id).count()
+    }
+
+    /// 全部已缓存的代币数量
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+}
+
+/// 可共享的代币元数据缓存
+pub type SharedTokenMetadataCache = Arc<TokenMetadataCache>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(symbol: &str, decimals: u8) -> TokenMetadata {
+        TokenMetadata {
+            symbol: symbol.to_string(),
+            decimals,
+            price_symbol: symbol.to_string(),
+            is_stable: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chains_share_cache_without_collision() {
+        let cache: SharedTokenMetadataCache = Arc::new(TokenMetadataCache::new());
+        let eth_view = cache.clone();
+        let bsc_view = cache.clone();
+
+        // 同一地址在两条链上是不同的代币 (精度不同)
+        let addr = Address::repeat_byte(0x11);
+        eth_view.insert(1, addr, metadata("USDT", 6)).await;
+        bsc_view.insert(56, addr, metadata("USDT", 18)).await;
+
+        assert_eq!(cache.len().await, 2);
+        assert_eq!(cache.get(1, addr).await.unwrap().decimals, 6);
+        assert_eq!(cache.get(56, addr).await.unwrap().decimals, 18);
+        assert_eq!(cache.count_for_chain(56).await, 1);
+        assert!(cache.get(137, addr).await.is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! 利润门槛 A/B 对比
+//!
+//! 同一条 Swap 事件流同时喂给两套扫描配置: A 组 (对照) 是线上扫描器，按原配置检测并执行，
+//! B 组 (候选) 只做纸面交易，记录"如果用这套门槛会执行哪些机会"，
+//! 会话结束后输出两组的对比报告，用于调 min_profit 等阈值。
+//! 线上扫描器通过 `with_ab_harness` 挂载，每个 Swap 事件在 A 组检测的同时交给 B 组
+
+use async_trait::async_trait;
+use ethers::prelude::Middleware;
+use models::ArbitrageOpportunity;
+use rust_decimal::Decimal;
+use services::SwapEvent;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, info_span, Instrument};
+
+use crate::event_driven_scanner::{EventDrivenScanner, EventDrivenScannerConfig};
+
+/// 可参与 A/B 对比的机会检测方
+#[async_trait]
+pub trait OpportunityDetector: Send + Sync {
+    /// 处理一个 Swap 事件，返回达到门槛的机会 (即会执行的机会)
+    async fn detect(&self, event: SwapEvent) -> Option<ArbitrageOpportunity>;
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> OpportunityDetector for EventDrivenScanner<M> {
+    async fn detect(&self, event: SwapEvent) -> Option<ArbitrageOpportunity> {
+        self.handle_swap_event(event).await
+    }
+}
+
+/// 把配置改成纸面交易 (只检测不执行，不写机会日志、不推送、不计入统计)，用于 B 组扫描器
+pub fn paper_trading_config(mut config: EventDrivenScannerConfig) -> EventDrivenScannerConfig {
+    config.executor_config.auto_execute = false;
+    config.paper_trading = true;
+    config
+}
+
+/// 单个事件的对比结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbOutcome {
+    /// 两组都会执行
+    Both,
+    /// 只有 A 组会执行
+    ControlOnly,
+    /// 只有 B 组会执行
+    CandidateOnly,
+    /// 两组都不执行
+    Neither,
+}
+
+/// A/B 会话对比报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AbReport {
+    pub control_name: String,
+    pub candidate_name: String,
+    /// 处理的事件数
+    pub events: u64,
+    pub both: u64,
+    pub control_only: u64,
+    pub candidate_only: u64,
+    /// A 组会执行的机会的预期净利润合计 (USD)
+    pub control_net_profit_usd: Decimal,
+    /// B 组会执行的机会的预期净利润合计 (USD)
+    pub candidate_net_profit_usd: Decimal,
+    /// 只有 B 组会执行的机会的预期净利润合计 (B 组门槛多抓到的部分)
+    pub candidate_only_net_profit_usd: Decimal,
+}
+
+impl AbReport {
+    /// A 组会执行的机会数
+    pub fn control_executions(&self) -> u64 {
+        self.both + self.control_only
+    }
+
+    /// B 组会执行的机会数
+    pub fn candidate_executions(&self) -> u64 {
+        self.both + self.candidate_only
+    }
+
+    /// 记录一个事件两组的检测结果
+    pub fn record(
+        &mut self,
+        control: Option<&ArbitrageOpportunity>,
+        candidate: Option<&ArbitrageOpportunity>,
+    ) -> AbOutcome {
+        self.events += 1;
+        if let Some(opp) = control {
+            self.control_net_profit_usd += opp.net_profit_usd;
+        }
+        if let Some(opp) = candidate {
+            self.candidate_net_profit_usd += opp.net_profit_usd;
+        }
+
+        match (control, candidate) {
+            (Some(_), Some(_)) => {
+                self.both += 1;
+                AbOutcome::Both
+            }
+            (Some(_), None) => {
+                self.control_only += 1;
+                AbOutcome::ControlOnly
+            }
+            (None, Some(opp)) => {
+                self.candidate_only += 1;
+                self.candidate_only_net_profit_usd += opp.net_profit_usd;
+                AbOutcome::CandidateOnly
+            }
+            (None, None) => AbOutcome::Neither,
+        }
+    }
+}
+
+impl fmt::Display for AbReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "A/B 对比报告 ({} 个事件)", self.events)?;
+        writeln!(
+            f,
+            "  A [{}] 执行 {} 次, 预期净利润 ${:.2}",
+            self.control_name,
+            self.control_executions(),
+            self.control_net_profit_usd
+        )?;
+        writeln!(
+            f,
+            "  B [{}] 执行 {} 次, 预期净利润 ${:.2} (纸面交易)",
+            self.candidate_name,
+            self.candidate_executions(),
+            self.candidate_net_profit_usd
+        )?;
+        write!(
+            f,
+            "  两组都执行 {} | 仅 A {} | 仅 B {} (仅 B 预期净利润 ${:.2})",
+            self.both, self.control_only, self.candidate_only, self.candidate_only_net_profit_usd
+        )
+    }
+}
+
+/// A/B 会话: A 组检测一个事件的同时把它交给 B 组，累计对比结果
+///
+/// B 组扫描器应使用 [`paper_trading_config`] 创建 (见 `EventDrivenScanner::paper_trading_replica`)，
+/// 保证只有 A 组会真正下单、写机会日志和推送
+pub struct AbHarness {
+    candidate: Arc<dyn OpportunityDetector>,
+    report: Mutex<AbReport>,
+}
+
+impl AbHarness {
+    pub fn new(control_name: &str, candidate_name: &str, candidate: Arc<dyn OpportunityDetector>) -> Self {
+        Self {
+            candidate,
+            report: Mutex::new(AbReport {
+                control_name: control_name.to_string(),
+                candidate_name: candidate_name.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// 处理一个事件: `control` 是 A 组对同一事件的检测，与 B 组并发进行；
+    /// 返回 A 组的检测结果，结果不一致时记录日志
+    pub async fn compare<F>(&self, event: SwapEvent, control: F) -> Option<ArbitrageOpportunity>
+    where
+        F: Future<Output = Option<ArbitrageOpportunity>>,
+    {
+        let pool = event.pool_address;
+        let block = event.block_number;
+        let candidate_span = info_span!("ab_candidate", pool = ?pool, block = block);
+        let (control, candidate) = tokio::join!(
+            control,
+            self.candidate.detect(event).instrument(candidate_span)
+        );
+
+        let mut report = self.report.lock().await;
+        let outcome = report.record(control.as_ref(), candidate.as_ref());
+        match outcome {
+            AbOutcome::ControlOnly => info!(
+                "🅰️ A/B 分歧: 仅 [{}] 会执行 | 池子={:?} 区块={} 净利润=${:.2}",
+                report.control_name, pool, block,
+                control.as_ref().map(|o| o.net_profit_usd).unwrap_or_default()
+            ),
+            AbOutcome::CandidateOnly => info!(
+                "🅱️ A/B 分歧: 仅 [{}] 会执行 | 池子={:?} 区块={} 净利润=${:.2}",
+                report.candidate_name, pool, block,
+                candidate.as_ref().map(|o| o.net_profit_usd).unwrap_or_default()
+            ),
+            AbOutcome::Both | AbOutcome::Neither => {}
+        }
+        control
+    }
+
+    /// 当前累计的对比报告
+    pub async fn report(&self) -> AbReport {
+        self.report.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Address, H256, U256};
+    use models::testing::opportunity;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    /// 脚本化检测方: 每个事件 (按 tx_hash) 有固定的预期净利润，按自己的门槛过滤
+    struct ScriptedDetector {
+        min_profit_usd: Decimal,
+        profits: HashMap<H256, Decimal>,
+    }
+
+    #[async_trait]
+    impl OpportunityDetector for ScriptedDetector {
+        async fn detect(&self, event: SwapEvent) -> Option<ArbitrageOpportunity> {
+            let net_profit_usd = *self.profits.get(&event.tx_hash)?;
+            if net_profit_usd < self.min_profit_usd {
+                return None;
+            }
+            Some(opportunity(format!("{:?}", event.tx_hash))
+                    .net_profit_usd(net_profit_usd)
+                    .gas_estimate(300_000)
+                    .block_number(event.block_number)
+                    .build())
+        }
+    }
+
+    fn swap_event(n: u8) -> SwapEvent {
+        SwapEvent {
+            pool_address: Address::repeat_byte(0x11),
+            sender: Address::zero(),
+            amount0_in: U256::exp10(18),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::exp10(18),
+            block_number: 100 + n as u64,
+            tx_hash: H256::repeat_byte(n),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lower_min_profit_candidate_report() {
+        // 事件 1..=5 的预期净利润，事件 6 没有机会
+        let profits: HashMap<H256, Decimal> = [
+            (1, dec!(12)),
+            (2, dec!(3)),
+            (3, dec!(0.8)),
+            (4, dec!(25)),
+            (5, dec!(1.5)),
+        ]
+        .into_iter()
+        .map(|(n, p)| (H256::repeat_byte(n), p))
+        .collect();
+
+        let control = ScriptedDetector { min_profit_usd: dec!(10), profits: profits.clone() };
+        let candidate = Arc::new(ScriptedDetector { min_profit_usd: dec!(1), profits });
+        let harness = AbHarness::new("min_profit=$10", "min_profit=$1", candidate);
+
+        for n in 1..=6 {
+            let event = swap_event(n);
+            let expected = control.detect(event.clone()).await.map(|o| o.id);
+            // 返回的是 A 组自己的检测结果
+            let returned = harness.compare(event.clone(), control.detect(event)).await.map(|o| o.id);
+            assert_eq!(returned, expected);
+        }
+
+        let report = harness.report().await;
+        assert_eq!(report.events, 6);
+        assert_eq!(report.both, 2);
+        assert_eq!(report.control_only, 0);
+        assert_eq!(report.candidate_only, 2);
+        assert_eq!(report.control_executions(), 2);
+        assert_eq!(report.candidate_executions(), 4);
+        assert_eq!(report.control_net_profit_usd, dec!(37));
+        assert_eq!(report.candidate_net_profit_usd, dec!(41.5));
+        assert_eq!(report.candidate_only_net_profit_usd, dec!(4.5));
+
+        let text = report.to_string();
+        assert!(text.contains("min_profit=$1"));
+        assert!(text.contains("仅 B 2"));
+    }
+
+    #[test]
+    fn test_paper_trading_config_disables_execution() {
+        let mut config = EventDrivenScannerConfig::default();
+        config.executor_config.auto_execute = true;
+        let config = paper_trading_config(config);
+        assert!(!config.executor_config.auto_execute);
+        assert!(config.paper_trading);
+    }
+}
@@ -332,8 +332,8 @@ impl<M: Middleware + 'static, S: EthersSigner + Clone + 'static> ArbitrageExecut
                 let fee_factor = U256::from(1_000_000 - fee);
                 amount_in * fee_factor / U256::from(1_000_000)
             }
-            DexType::Curve => {
-                // Curve: 假设 0.04% fee
+            DexType::Curve | DexType::PancakeSwapStable => {
+                // Curve / StableSwap: 假设 0.04% fee
                 amount_in * U256::from(9996) / U256::from(10000)
             }
         }
@@ -782,6 +782,7 @@ fn dex_type_to_u8(dex_type: DexType) -> u8 {
         DexType::PancakeSwapV2 => 4,
         DexType::PancakeSwapV3 => 5,
         DexType::Curve => 6,
+        DexType::PancakeSwapStable => 8,
     }
 }
 
@@ -800,5 +801,10 @@ fn create_placeholder_opportunity(id: String) -> ArbitrageOpportunity {
         profit_percentage: Decimal::ZERO,
         timestamp: chrono::Utc::now(),
         block_number: 0,
+        max_price_impact: Decimal::ZERO,
+        break_even_gas_price_gwei: Decimal::ZERO,
+        hop_amounts_out: Vec::new(),
+        net_profit_native: None,
+        gas_cost_native: None,
     }
 }
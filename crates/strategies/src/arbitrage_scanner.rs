@@ -195,6 +195,11 @@ impl<M: Middleware + 'static> ArbitrageScanner<M> {
             profit_percentage: analysis.profit_percentage,
             timestamp: chrono::Utc::now(),
             block_number,
+            max_price_impact: Decimal::ZERO,
+            break_even_gas_price_gwei: Decimal::ZERO,
+            hop_amounts_out: Vec::new(),
+            net_profit_native: None,
+            gas_cost_native: None,
         };
 
         info!(
@@ -538,6 +543,12 @@ impl<M: Middleware + 'static> ArbitrageScanner<M> {
             profit_percentage: analysis.profit_percentage,
             timestamp: chrono::Utc::now(),
             block_number: self.provider.get_block_number().await.unwrap_or_default().as_u64(),
+            max_price_impact: opportunity.max_price_impact,
+            break_even_gas_price_gwei: opportunity.break_even_gas_price_gwei,
+            // 输出已按新报价更新，原来的逐跳报价不再对应
+            hop_amounts_out: Vec::new(),
+            net_profit_native: None,
+            gas_cost_native: None,
         };
 
         Ok(Some(updated_opportunity))
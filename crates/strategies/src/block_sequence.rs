@@ -0,0 +1,93 @@
+//! 新区块事件去重
+//!
+//! 部分 RPC 会重复推送或乱序推送区块头，每条都触发一次全量池子刷新代价很高。
+//! 这里记录最近处理过的区块 (高度 + 哈希)，高度不大于已处理最高区块的事件一律忽略；
+//! 例外是同一高度出现了不同的哈希，说明发生了链重组，需要照常刷新
+
+use ethers::types::H256;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 保留的最近区块数 (超出窗口的旧区块无法判断是否重组，按重复忽略)
+const RECENT_BLOCK_WINDOW: usize = 64;
+
+/// 新区块事件的分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockEventKind {
+    /// 高于已处理的最高区块
+    New,
+    /// 已处理过的高度出现了不同哈希
+    Reorg,
+    /// 重复或过期的区块，忽略
+    Stale,
+}
+
+/// 最近处理过的区块
+#[derive(Debug, Default)]
+pub struct BlockSequenceGuard {
+    recent: Mutex<VecDeque<(u64, H256)>>,
+}
+
+impl BlockSequenceGuard {
+    /// 判断区块事件是否需要处理，需要处理时记为已处理
+    pub fn observe(&self, block_number: u64, block_hash: H256) -> BlockEventKind {
+        let mut recent = self.recent.lock().unwrap();
+        let kind = match recent.back() {
+            Some((latest, _)) if block_number <= *latest => {
+                match recent.iter().find(|(number, _)| *number == block_number) {
+                    Some((_, hash)) if *hash != block_hash => BlockEventKind::Reorg,
+                    _ => BlockEventKind::Stale,
+                }
+            }
+            _ => BlockEventKind::New,
+        };
+
+        match kind {
+            BlockEventKind::Stale => {}
+            BlockEventKind::Reorg => {
+                // 重组后该高度之上的区块作废
+                recent.retain(|(number, _)| *number < block_number);
+                recent.push_back((block_number, block_hash));
+            }
+            BlockEventKind::New => {
+                if recent.len() == RECENT_BLOCK_WINDOW {
+                    recent.pop_front();
+                }
+                recent.push_back((block_number, block_hash));
+            }
+        }
+        kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replayed_and_older_blocks_are_stale() {
+        let guard = BlockSequenceGuard::default();
+        assert_eq!(guard.observe(10, H256::repeat_byte(10)), BlockEventKind::New);
+        assert_eq!(guard.observe(11, H256::repeat_byte(11)), BlockEventKind::New);
+
+        assert_eq!(guard.observe(11, H256::repeat_byte(11)), BlockEventKind::Stale);
+        assert_eq!(guard.observe(10, H256::repeat_byte(10)), BlockEventKind::Stale);
+        // 窗口之外的旧区块无从比对，同样忽略
+        assert_eq!(guard.observe(3, H256::repeat_byte(3)), BlockEventKind::Stale);
+        // 跳号的新区块照常处理
+        assert_eq!(guard.observe(13, H256::repeat_byte(13)), BlockEventKind::New);
+    }
+
+    #[test]
+    fn test_same_height_with_new_hash_is_reorg() {
+        let guard = BlockSequenceGuard::default();
+        for n in 10..=12u64 {
+            guard.observe(n, H256::repeat_byte(n as u8));
+        }
+
+        assert_eq!(guard.observe(11, H256::repeat_byte(0xee)), BlockEventKind::Reorg);
+        // 重组后的新分支: 重复推送忽略，后续高度 (含原先的 12) 重新处理
+        assert_eq!(guard.observe(11, H256::repeat_byte(0xee)), BlockEventKind::Stale);
+        assert_eq!(guard.observe(12, H256::repeat_byte(0xef)), BlockEventKind::New);
+    }
+}
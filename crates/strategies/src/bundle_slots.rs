@@ -0,0 +1,234 @@
+//! Flashbots Bundle 在途数量限制
+//!
+//! 机会密集时同时提交的多个 Bundle 争抢同一个 nonce 和区块，最多只有一个能上链，
+//! 其余白白占用签名、模拟和中继请求。这里限制同时在途的 Bundle 数，名额占满时排队；
+//! 名额空出来时交给排队中价值最高的 Bundle，而不是先到先得。
+//! 排队期间出了新区块的 Bundle 目标区块已过，直接移出队列
+
+use rust_decimal::Decimal;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+#[derive(Debug)]
+struct Waiter {
+    id: u64,
+    /// Bundle 的预期净利润 (USD)
+    value: Decimal,
+    /// 机会被检测到的区块
+    block_number: u64,
+    grant: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    in_flight: usize,
+    waiters: Vec<Waiter>,
+    next_id: u64,
+    /// 最近一次 drop_stale 看到的区块
+    current_block: u64,
+}
+
+/// 单条链的 Bundle 在途名额
+#[derive(Debug)]
+pub struct BundleSlots {
+    /// 同时在途的 Bundle 上限 (0 = 不限制)
+    max_in_flight: usize,
+    state: Mutex<SlotState>,
+}
+
+impl BundleSlots {
+    /// max_in_flight 为 0 时不限制
+    pub fn new(max_in_flight: usize) -> Self {
+        Self { max_in_flight, state: Mutex::new(SlotState::default()) }
+    }
+
+    /// 名额是否已占满 (新的 Bundle 需要排队)
+    pub fn is_full(&self) -> bool {
+        self.max_in_flight > 0 && self.state.lock().unwrap().in_flight >= self.max_in_flight
+    }
+
+    /// 正在排队的 Bundle 数
+    pub fn queued(&self) -> usize {
+        self.state.lock().unwrap().waiters.len()
+    }
+
+    /// 取得一个在途名额，名额占满时排队，按 value 从高到低依次分配
+    ///
+    /// 机会所在区块已落后 (或排队期间出了新区块) 时返回 None
+    pub async fn acquire(&self, value: Decimal, block_number: u64) -> Option<BundleSlot<'_>> {
+        let mut pending = {
+            let mut state = self.state.lock().unwrap();
+            if block_number < state.current_block {
+                return None;
+            }
+            if self.max_in_flight == 0 || state.in_flight < self.max_in_flight {
+                state.in_flight += 1;
+                return Some(BundleSlot { slots: self });
+            }
+            let (grant, rx) = oneshot::channel();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.waiters.push(Waiter { id, value, block_number, grant });
+            PendingSlot { slots: self, id, rx, granted: false }
+        };
+
+        // 发送端被丢弃 (未转交名额) 说明已被 drop_stale 移出队列
+        pending.granted = (&mut pending.rx).await.is_ok();
+        pending.granted.then(|| BundleSlot { slots: self })
+    }
+
+    /// 出新区块时调用: 移出检测区块落后于 current_block 的排队 Bundle
+    pub fn drop_stale(&self, current_block: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.current_block = state.current_block.max(current_block);
+        state.waiters.retain(|w| w.block_number >= current_block);
+    }
+
+    /// 归还一个名额: 有排队的 Bundle 时直接转交给价值最高的
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        while let Some(index) = state
+            .waiters
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.value.cmp(&b.value).then(b.id.cmp(&a.id)))
+            .map(|(i, _)| i)
+        {
+            let waiter = state.waiters.swap_remove(index);
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+        state.in_flight -= 1;
+    }
+}
+
+/// 一个在途名额，释放时归还
+#[derive(Debug)]
+pub struct BundleSlot<'a> {
+    slots: &'a BundleSlots,
+}
+
+impl Drop for BundleSlot<'_> {
+    fn drop(&mut self) {
+        self.slots.release();
+    }
+}
+
+/// 排队中的名额请求 (等待被取消时移出队列)
+struct PendingSlot<'a> {
+    slots: &'a BundleSlots,
+    id: u64,
+    rx: oneshot::Receiver<()>,
+    granted: bool,
+}
+
+impl Drop for PendingSlot<'_> {
+    fn drop(&mut self) {
+        if self.granted {
+            return;
+        }
+        let already_granted = {
+            let mut state = self.slots.state.lock().unwrap();
+            state.waiters.retain(|w| w.id != self.id);
+            self.rx.try_recv().is_ok()
+        };
+        // 已被转交名额但还没来得及使用，交还给下一个
+        if already_granted {
+            self.slots.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    async fn wait_until_queued(slots: &BundleSlots, n: usize) {
+        while slots.queued() < n {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_high_value_bundle_preempts_queued_lower_value() {
+        let slots = Arc::new(BundleSlots::new(1));
+        let in_flight = slots.acquire(dec!(10), 1).await;
+        assert!(slots.is_full());
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        let submit = |value: Decimal| {
+            let slots = slots.clone();
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _slot = slots.acquire(value, 1).await.unwrap();
+                order_tx.send(value).unwrap();
+                tokio::task::yield_now().await;
+            })
+        };
+
+        // 先到的低价值 Bundle 排队，随后到达的高价值 Bundle 插到它前面
+        let low = submit(dec!(5));
+        wait_until_queued(&slots, 1).await;
+        let high = submit(dec!(50));
+        wait_until_queued(&slots, 2).await;
+
+        drop(in_flight);
+        low.await.unwrap();
+        high.await.unwrap();
+        assert_eq!(order_rx.recv().await, Some(dec!(50)));
+        assert_eq!(order_rx.recv().await, Some(dec!(5)));
+        assert!(!slots.is_full());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_hold_slot() {
+        let slots = Arc::new(BundleSlots::new(1));
+        let in_flight = slots.acquire(dec!(10), 1).await;
+
+        let waiter = {
+            let slots = slots.clone();
+            tokio::spawn(async move {
+                let _slot = slots.acquire(dec!(100), 1).await;
+            })
+        };
+        wait_until_queued(&slots, 1).await;
+        waiter.abort();
+        let _ = waiter.await;
+        assert_eq!(slots.queued(), 0);
+
+        drop(in_flight);
+        assert!(!slots.is_full());
+        // 不限制时从不排队
+        let unlimited = BundleSlots::new(0);
+        let _a = unlimited.acquire(dec!(1), 1).await;
+        let _b = unlimited.acquire(dec!(1), 1).await;
+        assert!(!unlimited.is_full());
+    }
+
+    #[tokio::test]
+    async fn test_stale_waiters_dropped_on_new_block() {
+        let slots = Arc::new(BundleSlots::new(1));
+        let in_flight = slots.acquire(dec!(10), 100).await;
+
+        let waiter = |block_number: u64| {
+            let slots = slots.clone();
+            tokio::spawn(async move { slots.acquire(dec!(50), block_number).await.is_some() })
+        };
+        let stale = waiter(100);
+        let fresh = waiter(101);
+        wait_until_queued(&slots, 2).await;
+
+        // 区块 101 到达: 区块 100 的 Bundle 出队，名额留给区块 101 的
+        slots.drop_stale(101);
+        assert!(!stale.await.unwrap());
+        assert_eq!(slots.queued(), 1);
+        drop(in_flight);
+        assert!(fresh.await.unwrap());
+
+        // 已落后的机会不再排队
+        assert!(slots.acquire(dec!(50), 100).await.is_none());
+    }
+}
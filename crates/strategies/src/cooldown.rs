@@ -0,0 +1,209 @@
+//! 冷却状态
+//!
+//! 两类冷却共用同一个类型:
+//! - 路径连续失败冷却: 池子被抽干之类的情况下，同一组池子会连续执行失败，在本地状态追上链上之前
+//!   每次都白白消耗 gas 和 RPC。连续失败达到阈值后暂停这组池子一段时间 (同样代币的其它池子组合不受影响)；
+//!   冷却结束后重新放行: 成功 (含干运行) 则清零恢复，再次失败则立即重新进入冷却
+//! - 执行后的池子冷却: 我们自己的成交会短暂扰动涉及的池子，本地状态追上链上之前马上重新检测，
+//!   往往会把自己造成的价差当成新机会。成功执行后直接让路径上的每个池子冷却一小段时间
+
+use ethers::types::Address;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 路径标识: 按跳顺序的三个池子地址
+pub type PathKey = [Address; 3];
+
+#[derive(Debug, Default)]
+struct CooldownState {
+    consecutive_failures: u32,
+    suspended_until: Option<Instant>,
+}
+
+impl CooldownState {
+    fn is_suspended_at(&self, now: Instant) -> bool {
+        self.suspended_until.is_some_and(|until| now < until)
+    }
+}
+
+/// 按键 (路径、池子) 记录的冷却状态
+#[derive(Debug)]
+pub struct Cooldown<K> {
+    /// 连续失败多少次后暂停 (0 = 失败不暂停，只有 `suspend` 能让键进入冷却)
+    threshold: u32,
+    /// 暂停时长 (0 = 不冷却)
+    cooldown: Duration,
+    entries: Mutex<HashMap<K, CooldownState>>,
+}
+
+impl<K: Copy + Eq + Hash + std::fmt::Debug> Cooldown<K> {
+    pub fn new(threshold: u32, cooldown_secs: u64) -> Self {
+        Self {
+            threshold,
+            cooldown: Duration::from_secs(cooldown_secs),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 键当前是否处于冷却中
+    pub fn is_suspended(&self, key: &K) -> bool {
+        self.first_suspended(std::slice::from_ref(key)).is_some()
+    }
+
+    /// 返回第一个仍在冷却中的键
+    pub fn first_suspended(&self, keys: &[K]) -> Option<K> {
+        self.first_suspended_at(keys, Instant::now())
+    }
+
+    fn first_suspended_at(&self, keys: &[K], now: Instant) -> Option<K> {
+        let entries = self.entries.lock().unwrap();
+        keys.iter()
+            .find(|key| entries.get(key).is_some_and(|state| state.is_suspended_at(now)))
+            .copied()
+    }
+
+    /// 直接让这些键进入冷却 (不看失败计数)，如成功执行后涉及的池子
+    pub fn suspend(&self, keys: &[K]) {
+        self.suspend_at(keys, Instant::now());
+    }
+
+    fn suspend_at(&self, keys: &[K], now: Instant) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        // 顺带清理已到期且没有失败计数的记录
+        entries.retain(|_, state| state.consecutive_failures > 0 || state.is_suspended_at(now));
+        for key in keys {
+            entries.entry(*key).or_default().suspended_until = Some(now + self.cooldown);
+        }
+    }
+
+    /// 记录一次执行失败，返回本次失败是否使键进入冷却
+    pub fn record_failure(&self, key: &K) -> bool {
+        self.record_failure_at(key, Instant::now())
+    }
+
+    fn record_failure_at(&self, key: &K, now: Instant) -> bool {
+        if self.threshold == 0 {
+            return false;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let state = entries.entry(*key).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures < self.threshold {
+            return false;
+        }
+        state.suspended_until = Some(now + self.cooldown);
+        true
+    }
+
+    /// 记录一次成功 (含干运行)，清除失败计数和冷却
+    pub fn record_success(&self, key: &K) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    /// 当前处于冷却中的键数
+    pub fn suspended_count(&self) -> usize {
+        let now = Instant::now();
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|state| state.is_suspended_at(now))
+            .count()
+    }
+}
+
+/// 路径连续失败冷却
+pub type PathCooldown = Cooldown<PathKey>;
+
+/// 执行后的池子冷却
+pub type PoolCooldown = Cooldown<Address>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> PathKey {
+        [Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03)]
+    }
+
+    impl<K: Copy + Eq + Hash + std::fmt::Debug> Cooldown<K> {
+        fn is_suspended_at(&self, key: &K, now: Instant) -> bool {
+            self.first_suspended_at(std::slice::from_ref(key), now).is_some()
+        }
+    }
+
+    #[test]
+    fn test_suspended_after_threshold_then_retried() {
+        let cooldown = PathCooldown::new(3, 60);
+        let start = Instant::now();
+
+        assert!(!cooldown.record_failure_at(&path(), start));
+        assert!(!cooldown.record_failure_at(&path(), start));
+        assert!(!cooldown.is_suspended_at(&path(), start));
+
+        // 第 3 次失败进入冷却，换了任一池子的组合不受影响
+        assert!(cooldown.record_failure_at(&path(), start));
+        assert!(cooldown.is_suspended_at(&path(), start + Duration::from_secs(59)));
+        let other_pools = [Address::repeat_byte(0x04), path()[1], path()[2]];
+        assert!(!cooldown.is_suspended_at(&other_pools, start));
+
+        // 冷却结束后重新放行，再失败一次立即重新冷却
+        let retry_at = start + Duration::from_secs(60);
+        assert!(!cooldown.is_suspended_at(&path(), retry_at));
+        assert!(cooldown.record_failure_at(&path(), retry_at));
+        assert!(cooldown.is_suspended_at(&path(), retry_at + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let cooldown = PathCooldown::new(2, 60);
+        let now = Instant::now();
+
+        assert!(!cooldown.record_failure_at(&path(), now));
+        cooldown.record_success(&path());
+        assert!(!cooldown.record_failure_at(&path(), now));
+
+        assert!(cooldown.record_failure_at(&path(), now));
+        assert_eq!(cooldown.suspended_count(), 1);
+        // 冷却中复查成功 (如干运行) 立即恢复
+        cooldown.record_success(&path());
+        assert!(!cooldown.is_suspended(&path()));
+        assert_eq!(cooldown.suspended_count(), 0);
+    }
+
+    #[test]
+    fn test_zero_threshold_never_suspends() {
+        let cooldown = PathCooldown::new(0, 60);
+        for _ in 0..10 {
+            assert!(!cooldown.record_failure(&path()));
+        }
+        assert!(!cooldown.is_suspended(&path()));
+    }
+
+    #[test]
+    fn test_suspended_pools_cool_down_then_expire() {
+        let cooldown = PoolCooldown::new(0, 12);
+        let (used, other) = (Address::repeat_byte(0x01), Address::repeat_byte(0x09));
+        let start = Instant::now();
+
+        cooldown.suspend_at(&[used, Address::repeat_byte(0x02)], start);
+        // 共用任一池子都算冷却中，其它池子不受影响
+        assert_eq!(cooldown.first_suspended_at(&[other, used], start + Duration::from_secs(11)), Some(used));
+        assert_eq!(cooldown.first_suspended_at(&[other], start), None);
+        // 到期后放行
+        assert_eq!(cooldown.first_suspended_at(&[used], start + Duration::from_secs(12)), None);
+    }
+
+    #[test]
+    fn test_zero_cooldown_never_suspends() {
+        let cooldown = PoolCooldown::new(0, 0);
+        let pool = Address::repeat_byte(0x01);
+        cooldown.suspend(&[pool]);
+        assert_eq!(cooldown.first_suspended(&[pool]), None);
+    }
+}
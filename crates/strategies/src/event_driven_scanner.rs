@@ -6,27 +6,42 @@
 
 use anyhow::Result;
 use ethers::prelude::*;
+use ethers::providers::call_raw::spoof;
 use ethers::signers::LocalWallet;
 use ethers::types::{Address, U256};
-use models::{ArbitrageOpportunity, ArbitragePath, DexType, SwapHop};
+use models::{break_even_gas_price_gwei, ArbitrageOpportunity, ArbitragePath, DexType, ProfitDelta, SwapHop, TokenAmount};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromStr;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::sync::{broadcast, RwLock, Semaphore};
-use tracing::{info, debug, warn, error};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tokio::sync::{broadcast, RwLock, Semaphore, SemaphorePermit};
+use tracing::{info, debug, warn, error, info_span, Instrument};
 
-use services::{SwapEvent, NewBlockEvent, SharedPriceService, get_email_notifier, ArbitrageExecutionInfo, WalletBalance};
+use dex::{sqrt_price_x96_to_price, PancakeStableSwapPool, StableSwapPool};
+use services::{Database, SwapEvent, NewBlockEvent, SharedPriceService, SharedTokenMetadataCache, TokenMetadata, TokenMetadataCache, get_email_notifier, ArbitrageExecutionInfo, ProfitCurrencies, WalletBalance, NoopEventSink, SharedEventSink, GasPriceSource};
 use ::utils::utc_to_shanghai_str;
 
 // 使用新的执行器和闪电贷池选择器
 use executor::{
-    ArbitrageExecutor as RealExecutor, ExecutorConfig, GasStrategy, SendMode,
-    ArbitrageParamsBuilder, FlashbotsConfig, RevertDecoder,
+    ArbitrageExecutor as RealExecutor, ExecutorConfig, ExecutionError, ExecutionOutcome, GasStrategy, SendMode,
+    ArbitrageParamsBuilder, BundlePrivacy, FlashbotsConfig, RevertDecoder, calculate_flash_fee,
+    FlashFeeTable, FlashLoanProvider, NonceAllocator,
 };
 
+use crate::ab_harness::{paper_trading_config, AbHarness, AbReport};
+use crate::opportunity_scorer::OpportunityScorer;
+use crate::cooldown::{PathCooldown, PathKey, PoolCooldown};
+use crate::profit_tracker::{ProfitReport, ProfitTracker};
+use crate::startup_warmup::StartupWarmup;
+use crate::block_sequence::{BlockEventKind, BlockSequenceGuard};
+use crate::min_profit_overrides::MinProfitOverrides;
+use crate::opportunity_watchdog::{OpportunityWatchdog, QuietPeriod};
+use crate::bundle_slots::{BundleSlot, BundleSlots};
+use crate::rebasing_tokens::rebasing_token_reason;
+use crate::strategy_runner::{ExecutionControl, ManualExecutionError};
+
 // ERC20 ABI for balance queries
 abigen!(
     IERC20Balance,
@@ -60,10 +75,34 @@ abigen!(
             ],
             "stateMutability": "nonpayable",
             "type": "function"
+        },
+        {
+            "inputs": [
+                {"name": "path", "type": "bytes"},
+                {"name": "amountIn", "type": "uint256"}
+            ],
+            "name": "quoteExactInput",
+            "outputs": [
+                {"name": "amountOut", "type": "uint256"},
+                {"name": "sqrtPriceX96AfterList", "type": "uint160[]"},
+                {"name": "initializedTicksCrossedList", "type": "uint32[]"},
+                {"name": "gasEstimate", "type": "uint256"}
+            ],
+            "stateMutability": "nonpayable",
+            "type": "function"
         }
     ]"#
 );
 
+// Uniswap V3 QuoterV1 ABI (部分链/分叉只部署了 V1: 平铺参数，不返回 gas 估算)
+abigen!(
+    UniswapV3QuoterV1,
+    r#"[
+        function quoteExactInputSingle(address tokenIn, address tokenOut, uint24 fee, uint256 amountIn, uint160 sqrtPriceLimitX96) external returns (uint256 amountOut)
+        function quoteExactInput(bytes path, uint256 amountIn) external returns (uint256 amountOut)
+    ]"#
+);
+
 // Uniswap V3 Pool ABI (用于查询 slot0)
 abigen!(
     IUniswapV3Pool,
@@ -83,14 +122,37 @@ abigen!(
     ]"#
 );
 
-/// Multicall3 合约地址 (在大多数链上都是这个地址)
-const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
-
 /// 缓存过期阈值 (允许的最大过期区块数)
 /// 注意：现在每个新区块都会刷新所有池子，此常量仅作为备用参考
 #[allow(dead_code)]
 const MAX_STALE_BLOCKS: u64 = 2;
 
+/// 等待执行后余额快照确认时的轮询间隔
+const BALANCE_SNAPSHOT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+/// 等待执行后余额快照确认的最长时间
+const BALANCE_SNAPSHOT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// QuoterV1 不返回 gas 估算，按 V3 单跳交换约 130k gas 计
+const QUOTER_V1_HOP_GAS_ESTIMATE: u64 = 130_000;
+
+/// 优先通道在普通执行许可之外保留的许可数
+const PRIORITY_LANE_PERMITS: usize = 1;
+
+/// 保留多少个最近检测到的机会供手动执行
+const RECENT_OPPORTUNITY_CAPACITY: usize = 200;
+
+/// 分发者最多连续处理的批次数，之后交出分发权 (剩余机会由下一个到达的任务处理)
+const MAX_DISPATCH_ROUNDS: usize = 4;
+
+/// 链上 Quoter 合约接口版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoterInterface {
+    /// QuoterV2: tuple 参数，返回 gas 估算
+    V2,
+    /// QuoterV1: 平铺参数，只返回 amountOut
+    V1,
+}
+
 /// 单次报价结果
 #[derive(Debug, Clone)]
 pub struct QuoteResult {
@@ -99,12 +161,28 @@ pub struct QuoteResult {
 }
 
 /// 套利模拟结果
+///
+/// 原生代币计价模式下 `net_profit_usd` / `gas_cost_usd` 的数值为原生代币数量，
+/// 构建机会时由 `denominate_opportunity` 分开存放
 #[derive(Debug, Clone)]
 pub struct ArbitrageSimResult {
     pub net_profit_usd: Decimal,
     pub amount_out: U256,
     pub total_gas_used: U256,
     pub gas_cost_usd: Decimal,
+    /// 各跳中最大的价格影响 (%)
+    pub max_price_impact: Decimal,
+    /// 链上报价的各跳输出 [A->B, B->C, C->A] (逐跳报价，未经多跳交叉验证)
+    pub hop_outputs: [U256; 3],
+}
+
+/// 链上报价的输入金额来源
+#[derive(Debug, Clone, Copy)]
+enum InputSizing {
+    /// 按触发 swap 的美金金额换算 (不超过三个池子能承受的量)
+    SwapUsd(Decimal),
+    /// 路径配置的固定输入 (起始代币最小单位)
+    Fixed(U256),
 }
 
 /// 池子本地状态缓存
@@ -125,16 +203,73 @@ pub struct PoolState {
     pub liquidity: Option<u128>,
     /// V3 tick
     pub tick: Option<i32>,
+    /// slot0.unlocked: false 表示池子正处于回调中 (重入锁已上)，此时的状态和报价不可信
+    pub unlocked: bool,
+    /// StableSwap 池状态 (余额 + 放大系数，仅 PancakeSwapStable)
+    pub stable_state: Option<StableSwapPool>,
     /// 最后更新的区块
     pub last_block: u64,
+    /// 最后收到 Swap 事件的区块 (0 表示尚未收到，首次刷新时以当前区块为起点)
+    pub last_swap_block: u64,
+    /// 本地估算与链上报价的偏差统计
+    pub local_accuracy: LocalQuoteAccuracy,
     /// 最后更新时间
     pub last_updated: std::time::Instant,
 }
 
+/// 本地估算与链上报价的偏差统计 (指数移动平均)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalQuoteAccuracy {
+    /// 已记录的样本数
+    pub samples: u64,
+    /// 平均偏差 (基点)
+    pub avg_error_bps: f64,
+}
+
+impl LocalQuoteAccuracy {
+    /// 新样本权重
+    const EWMA_ALPHA: f64 = 0.2;
+
+    /// 记录一次本地估算 (未打折) 与链上报价的偏差
+    pub fn record(&mut self, local_amount_out: U256, chain_amount_out: U256) {
+        if chain_amount_out.is_zero() {
+            return;
+        }
+        let diff = if local_amount_out > chain_amount_out {
+            local_amount_out - chain_amount_out
+        } else {
+            chain_amount_out - local_amount_out
+        };
+        let error_bps = (diff.saturating_mul(U256::from(10_000u64)) / chain_amount_out)
+            .min(U256::from(10_000u64))
+            .as_u64() as f64;
+
+        self.avg_error_bps = if self.samples == 0 {
+            error_bps
+        } else {
+            Self::EWMA_ALPHA * error_bps + (1.0 - Self::EWMA_ALPHA) * self.avg_error_bps
+        };
+        self.samples += 1;
+    }
+
+    /// 按偏差调整跳过本地计算的阈值
+    ///
+    /// 样本不足或平均偏差在容忍度内时使用基础阈值；否则按 容忍度/偏差 等比例降低，
+    /// 偏差越大越早改用链上计算。tolerance_bps 为 0 表示不调整
+    pub fn adjusted_threshold(&self, base: Decimal, tolerance_bps: u32, min_samples: u64) -> Decimal {
+        if tolerance_bps == 0 || self.samples < min_samples.max(1) || self.avg_error_bps <= tolerance_bps as f64 {
+            return base;
+        }
+        let ratio = Decimal::from_f64_retain(tolerance_bps as f64 / self.avg_error_bps).unwrap_or(Decimal::ONE);
+        (base * ratio).round_dp(2)
+    }
+}
+
 impl PoolState {
     /// 从 Swap 事件更新价格状态
     pub fn update_from_swap(&mut self, event: &SwapEvent) {
         self.last_block = event.block_number;
+        self.last_swap_block = event.block_number;
         self.last_updated = std::time::Instant::now();
 
         // 更新 V3 价格状态 (如果事件中包含)
@@ -149,16 +284,408 @@ impl PoolState {
         }
     }
 
+    /// 部分 WebSocket 节点解码出的 Swap 事件不带 sqrtPrice/liquidity，
+    /// 这类事件无法更新 V3 价格状态；V2 Swap 事件本身不带储备，总要重新读取 getReserves
+    /// (StableSwap 池子不从事件取价)
+    pub fn swap_lacks_price_data(&self, event: &SwapEvent) -> bool {
+        self.is_v2()
+            || (self.dex_type.is_v3_style() && (event.sqrt_price_x96.is_none() || event.liquidity.is_none()))
+    }
+
     /// 检查缓存是否足够新鲜
     /// max_stale_blocks: 允许的最大过期区块数
     pub fn is_fresh(&self, current_block: u64, max_stale_blocks: u64) -> bool {
         current_block.saturating_sub(self.last_block) <= max_stale_blocks
     }
 
+    /// 是否已连续 dead_after_blocks 个区块没有 Swap 事件 (0 表示不检测)
+    pub fn is_dead(&self, current_block: u64, dead_after_blocks: u64) -> bool {
+        dead_after_blocks > 0
+            && self.last_swap_block > 0
+            && current_block.saturating_sub(self.last_swap_block) >= dead_after_blocks
+    }
+
     /// 检查是否有有效的 V3 价格数据
     pub fn has_v3_price_data(&self) -> bool {
         self.sqrt_price_x96.is_some() && self.liquidity.is_some()
     }
+
+    /// 是否是 StableSwap 池子
+    pub fn is_stable_swap(&self) -> bool {
+        self.dex_type == DexType::PancakeSwapStable
+    }
+
+    /// 是否是 V2 (恒定乘积) 池子
+    pub fn is_v2(&self) -> bool {
+        self.dex_type.is_v2_style()
+    }
+
+    /// 检查是否有可用于本地计算的价格数据
+    pub fn has_price_data(&self) -> bool {
+        if self.is_stable_swap() {
+            self.stable_state.as_ref().is_some_and(|s| !s.amp.is_zero())
+        } else if self.is_v2() {
+            !self.reserve0.is_zero() && !self.reserve1.is_zero()
+        } else {
+            self.has_v3_price_data()
+        }
+    }
+
+    /// V2 恒定乘积公式计算输出 (fee 与 V3 一样以 1e6 为基数: 3000 = 0.3%, 2500 = 0.25%)，没有储备时返回 None
+    pub fn v2_amount_out(&self, token_in: Address, amount_in: U256) -> Option<U256> {
+        let (reserve_in, reserve_out) = if token_in == self.token0 {
+            (self.reserve0, self.reserve1)
+        } else {
+            (self.reserve1, self.reserve0)
+        };
+        if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+            return None;
+        }
+        let amount_in_with_fee = amount_in.checked_mul(U256::from(1_000_000u64.checked_sub(self.fee as u64)?))?;
+        let numerator = amount_in_with_fee.checked_mul(reserve_out)?;
+        let denominator = reserve_in.checked_mul(U256::from(1_000_000u64))?.checked_add(amount_in_with_fee)?;
+        Some(numerator / denominator)
+    }
+}
+
+/// 挑选本区块需要刷新的池子
+///
+/// 返回 (需要刷新的池子, 死池数量)。死池只在区块号是 refresh_interval 的整数倍时刷新，
+/// refresh_interval 为 0 时不再刷新。尚未收到 Swap 事件的池子以当前区块作为观察起点
+pub fn select_pools_for_refresh(
+    states: &mut HashMap<Address, PoolState>,
+    current_block: u64,
+    dead_after_blocks: u64,
+    refresh_interval: u64,
+) -> (Vec<Address>, usize) {
+    let mut selected = Vec::with_capacity(states.len());
+    let mut dead_count = 0;
+
+    for (addr, pool) in states.iter_mut() {
+        if pool.last_swap_block == 0 {
+            pool.last_swap_block = current_block;
+        }
+        if pool.is_dead(current_block, dead_after_blocks) {
+            dead_count += 1;
+            if refresh_interval == 0 || !current_block.is_multiple_of(refresh_interval) {
+                continue;
+            }
+        }
+        selected.push(*addr);
+    }
+
+    (selected, dead_count)
+}
+
+/// 校验路径配置的三个池子确实连接了该路径的代币
+///
+/// pool1 须包含 (token_a, token_b)，pool2 须包含 (token_b, token_c)，pool3 须包含 (token_c, token_a)。
+/// 未配置池子、或池子不在监控列表中 (无法得知其代币) 时不做判断
+pub fn validate_path_pools(path: &PoolPathConfig, states: &HashMap<Address, PoolState>) -> Result<(), String> {
+    validate_distinct_tokens(path.token_a, path.token_b, path.token_c)?;
+    let Some(pools) = path.pools else {
+        return Ok(());
+    };
+    let hops = [
+        (path.token_a, path.token_b),
+        (path.token_b, path.token_c),
+        (path.token_c, path.token_a),
+    ];
+
+    for (i, (pool_address, (token_in, token_out))) in pools.iter().zip(hops).enumerate() {
+        let Some(pool) = states.get(pool_address) else {
+            continue;
+        };
+        let connects = (pool.token0 == token_in && pool.token1 == token_out)
+            || (pool.token0 == token_out && pool.token1 == token_in);
+        if !connects {
+            return Err(format!(
+                "pool{} {:?} 的代币为 ({:?}, {:?})，不包含 ({:?}, {:?})",
+                i + 1, pool_address, pool.token0, pool.token1, token_in, token_out
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 三角路径的三个代币必须互不相同 (配置错误时报价没有意义，只会浪费 RPC)
+pub fn validate_distinct_tokens(token_a: Address, token_b: Address, token_c: Address) -> Result<(), String> {
+    if token_a == token_b || token_b == token_c || token_c == token_a {
+        return Err(format!(
+            "配置错误: 路径代币必须互不相同 (token_a={:?}, token_b={:?}, token_c={:?})",
+            token_a, token_b, token_c
+        ));
+    }
+    Ok(())
+}
+
+/// 检测阶段估算闪电贷池费率 (与池子 fee 同单位，百万分之一)
+///
+/// 规则与执行时的闪电贷池选择一致: 路径固定了闪电贷池时用它；否则在本地缓存中找
+/// 借入代币与 token_b/token_c 配对、不在 swap 路径里的 V3 池子，流动性高者优先、
+/// 其次费率低。缓存中没有候选时用第一跳池子的费率近似 (同交易对通常有多个费率档位)
+pub fn estimate_flash_pool_fee<'a>(
+    cached_pools: impl IntoIterator<Item = &'a PoolState>,
+    tokens: [Address; 3],
+    swap_pools: [&PoolState; 3],
+    pinned_flash_pool: Option<Address>,
+) -> u32 {
+    let [token_a, token_b, token_c] = tokens;
+    let swap_addresses = swap_pools.map(|p| p.address);
+    let mut candidates = cached_pools.into_iter().filter(|p| !p.is_stable_swap());
+    let selected = match pinned_flash_pool {
+        Some(pinned) => candidates.find(|p| p.address == pinned),
+        None => candidates
+            .filter(|p| !swap_addresses.contains(&p.address))
+            .filter(|p| {
+                (p.token0 == token_a && (p.token1 == token_b || p.token1 == token_c))
+                    || (p.token1 == token_a && (p.token0 == token_b || p.token0 == token_c))
+            })
+            .min_by_key(|p| (std::cmp::Reverse(p.liquidity.unwrap_or(0)), p.fee)),
+    };
+    selected.map_or(swap_pools[0].fee, |p| p.fee)
+}
+
+/// 计算单跳的价格影响 (%)
+///
+/// 以交易前 sqrtPriceX96 为现货价格，扣除池子手续费后与实际成交价比较。
+/// 没有 V3 价格数据的池子 (如 StableSwap) 返回 None
+pub fn hop_price_impact_pct(pool: &PoolState, token_in: Address, amount_in: U256, amount_out: U256) -> Option<Decimal> {
+    let sqrt_price = pool.sqrt_price_x96.filter(|p| !p.is_zero())?;
+    if !pool.has_v3_price_data() || amount_in.is_zero() {
+        return None;
+    }
+
+    // 原始单位下 token1/token0 的价格
+    let spot_1_per_0 = sqrt_price_x96_to_price(sqrt_price, 0, 0);
+    let spot_out_per_in = if token_in == pool.token0 { spot_1_per_0 } else { 1.0 / spot_1_per_0 };
+    let fee_factor = 1.0 - pool.fee as f64 / 1_000_000.0;
+
+    let amount_in_f = amount_in.to_string().parse::<f64>().ok()?;
+    let amount_out_f = amount_out.to_string().parse::<f64>().ok()?;
+    let expected_out = amount_in_f * spot_out_per_in * fee_factor;
+    if !expected_out.is_finite() || expected_out <= 0.0 {
+        return None;
+    }
+
+    let impact = ((1.0 - amount_out_f / expected_out) * 100.0).max(0.0);
+    Decimal::from_f64_retain(impact).map(|d| d.round_dp(4))
+}
+
+/// 路径各跳 (池子, 输入代币, 输入金额, 输出金额) 中最大的价格影响 (%)
+pub fn max_price_impact_pct(hops: &[(&PoolState, Address, U256, U256)]) -> Decimal {
+    hops.iter()
+        .filter_map(|(pool, token_in, amount_in, amount_out)| {
+            hop_price_impact_pct(pool, *token_in, *amount_in, *amount_out)
+        })
+        .max()
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// 单跳实际成交价相对价格服务现货价的偏离 (%)
+///
+/// 输入、输出分别按 (decimals, USD 价格) 折算为美元价值后比较，手续费和价格影响也计入偏离。
+/// 任一侧价格未知时返回 None
+pub fn hop_spot_deviation_pct(
+    amount_in: U256,
+    token_in: (u8, Decimal),
+    amount_out: U256,
+    token_out: (u8, Decimal),
+) -> Option<Decimal> {
+    let usd_value = |amount: U256, (decimals, price): (u8, Decimal)| {
+        (price > Decimal::ZERO).then(|| TokenAmount::new(amount, decimals).to_usd(price))
+    };
+    let value_in = usd_value(amount_in, token_in).filter(|v| *v > Decimal::ZERO)?;
+    let value_out = usd_value(amount_out, token_out)?;
+    let ratio = value_out.checked_div(value_in)?;
+    Some(((ratio - Decimal::ONE).abs() * dec!(100)).round_dp(4))
+}
+
+/// 估算池子深度 (USD): 两侧储备按 (decimals, 价格) 折算后相加
+///
+/// V3 用当前价格下的虚拟储备 (x = L/√P, y = L·√P)，StableSwap 用池内余额，其他池子用 reserve0/reserve1。
+/// 没有储备数据或价格都未知时返回 None
+pub fn pool_depth_usd(pool: &PoolState, tokens: [(u8, Decimal); 2]) -> Option<Decimal> {
+    let raw_reserves = if let Some(ref stable) = pool.stable_state {
+        stable.balances
+    } else if let (Some(sqrt_price), Some(liquidity)) = (pool.sqrt_price_x96.filter(|p| !p.is_zero()), pool.liquidity) {
+        // x = L·2^96 / sqrtPriceX96, y = L·sqrtPriceX96 / 2^96 (乘积可能超过 256 位，用 U512 中转)
+        let liquidity = U256::from(liquidity);
+        let reserve0 = (liquidity << 96) / sqrt_price;
+        let reserve1 = U256::try_from(liquidity.full_mul(sqrt_price) >> 96).unwrap_or(U256::MAX);
+        [reserve0, reserve1]
+    } else {
+        [pool.reserve0, pool.reserve1]
+    };
+
+    let depth = raw_reserves
+        .into_iter()
+        .zip(tokens)
+        .map(|(raw, (decimals, price))| TokenAmount::new(raw, decimals).to_usd(price))
+        .fold(Decimal::ZERO, |total, value| total.saturating_add(value));
+    (depth > Decimal::ZERO).then(|| depth.round_dp(2))
+}
+
+/// 输入金额 (USD) 不超过路径最浅池子深度的 fraction 倍 (fraction 为 0 或深度未知时不限制)
+pub fn clamp_to_liquidity(swap_usd: Decimal, min_depth_usd: Option<Decimal>, fraction: Decimal) -> Decimal {
+    match min_depth_usd {
+        Some(depth) if fraction > Decimal::ZERO => swap_usd.min(depth * fraction),
+        _ => swap_usd,
+    }
+}
+
+/// 价格影响是否超过上限 (上限为 0 表示不限制)
+pub fn price_impact_exceeded(max_price_impact: Decimal, ceiling_pct: Decimal) -> bool {
+    ceiling_pct > Decimal::ZERO && max_price_impact > ceiling_pct
+}
+
+/// 交易黑名单: 命中的池子不刷新、不报价、不执行
+#[derive(Debug, Clone, Default)]
+pub struct TradingBlocklist {
+    pub tokens: HashSet<Address>,
+    pub pools: HashSet<Address>,
+}
+
+impl TradingBlocklist {
+    /// 从地址字符串创建 (无法解析的地址记录警告后忽略)
+    pub fn from_addresses(tokens: &[String], pools: &[String]) -> Self {
+        fn parse_all(values: &[String]) -> HashSet<Address> {
+            values
+                .iter()
+                .filter_map(|s| match s.parse::<Address>() {
+                    Ok(addr) => Some(addr),
+                    Err(_) => {
+                        warn!("忽略无效的黑名单地址: {}", s);
+                        None
+                    }
+                })
+                .collect()
+        }
+        Self {
+            tokens: parse_all(tokens),
+            pools: parse_all(pools),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && self.pools.is_empty()
+    }
+
+    /// 池子本身或其任一代币在黑名单中
+    pub fn blocks_pool(&self, pool: &PoolState) -> bool {
+        self.pools.contains(&pool.address)
+            || self.tokens.contains(&pool.token0)
+            || self.tokens.contains(&pool.token1)
+    }
+
+    /// 路径经过黑名单代币，或配置的池子在黑名单中
+    pub fn blocks_path(&self, path: &PoolPathConfig) -> bool {
+        [path.token_a, path.token_b, path.token_c].iter().any(|t| self.tokens.contains(t))
+            || path.pools.is_some_and(|pools| pools.iter().any(|p| self.pools.contains(p)))
+    }
+
+    /// 返回机会路径中第一个命中黑名单的池子或代币
+    pub fn blocked_hop_address(&self, path: &ArbitragePath) -> Option<Address> {
+        path.hops.iter().find_map(|hop| {
+            [hop.pool_address, hop.token_in, hop.token_out]
+                .into_iter()
+                .find(|addr| self.pools.contains(addr) || self.tokens.contains(addr))
+        })
+    }
+}
+
+/// 原生代币计价模式 (价格服务不可用时的降级方案)
+///
+/// 启用后扫描器以 Wrapped Native 为计价单位: 它的 "价格" 固定为 1，其他代币的价格按
+/// USD 价格除以原生代币 USD 价格折算 (任一未知则视为未知)，gas 成本直接按原生代币计。
+/// 利润门槛和小额交易过滤改用这里的原生代币数值，以 Wrapped Native 为起点的路径
+/// 在没有任何 USD 价格时仍能检测和执行
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeDenomination {
+    /// Wrapped Native 代币地址 (WETH/WBNB 等)
+    pub wrapped_native: Address,
+    /// 最小净利润 (原生代币，如 0.001 ETH)，替代动态 USD 门槛
+    pub min_profit: Decimal,
+    /// 触发 swap 的最小价值 (原生代币)
+    pub min_swap_value: Decimal,
+}
+
+/// 原生代币计价模式下代币的相对价格 (1 个代币值多少原生代币)，未知时返回 0
+pub fn native_relative_price(is_wrapped_native: bool, token_usd: Decimal, native_usd: Option<Decimal>) -> Decimal {
+    if is_wrapped_native {
+        return Decimal::ONE;
+    }
+    match native_usd {
+        Some(native_usd) if native_usd > Decimal::ZERO && token_usd > Decimal::ZERO => token_usd / native_usd,
+        _ => Decimal::ZERO,
+    }
+}
+
+/// 按代币对限制报价的费率档位，减少对不合理费率池子的报价
+///
+/// 查找顺序: 指定代币对 -> 稳定币对/非稳定币对的默认档位；列表为空表示不限制
+#[derive(Debug, Clone, Default)]
+pub struct FeeTierWhitelist {
+    /// 两边都是稳定币的代币对允许的费率 (如 100, 500)
+    pub stable_pair_fees: Vec<u32>,
+    /// 其他代币对允许的费率 (如 500, 3000)
+    pub volatile_pair_fees: Vec<u32>,
+    /// 指定代币对允许的费率 (键为排序后的地址对)
+    pub pairs: HashMap<(Address, Address), Vec<u32>>,
+}
+
+impl FeeTierWhitelist {
+    /// 从配置创建，代币对条目格式为 `0xTokenA/0xTokenB:100|500` (无法解析的条目记录警告后忽略)
+    pub fn from_config(pair_entries: &[String], stable_pair_fees: &[u32], volatile_pair_fees: &[u32]) -> Self {
+        let mut pairs = HashMap::new();
+        for entry in pair_entries {
+            match Self::parse_pair_entry(entry) {
+                Some((key, fees)) => {
+                    pairs.insert(key, fees);
+                }
+                None => warn!("忽略无效的费率白名单条目: {}", entry),
+            }
+        }
+        Self {
+            stable_pair_fees: stable_pair_fees.to_vec(),
+            volatile_pair_fees: volatile_pair_fees.to_vec(),
+            pairs,
+        }
+    }
+
+    fn parse_pair_entry(entry: &str) -> Option<((Address, Address), Vec<u32>)> {
+        let (tokens, fees) = entry.split_once(':')?;
+        let (token_a, token_b) = tokens.split_once('/')?;
+        let token_a = token_a.trim().parse::<Address>().ok()?;
+        let token_b = token_b.trim().parse::<Address>().ok()?;
+        let fees = fees
+            .split('|')
+            .map(|f| f.trim().parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        if fees.is_empty() {
+            return None;
+        }
+        Some((Self::pair_key(token_a, token_b), fees))
+    }
+
+    /// 与方向无关的代币对键
+    pub fn pair_key(token_a: Address, token_b: Address) -> (Address, Address) {
+        if token_a < token_b { (token_a, token_b) } else { (token_b, token_a) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stable_pair_fees.is_empty() && self.volatile_pair_fees.is_empty() && self.pairs.is_empty()
+    }
+
+    /// 该代币对是否允许报价该费率档位
+    pub fn allows(&self, token0: Address, token1: Address, fee: u32, stable_pair: bool) -> bool {
+        let allowed = match self.pairs.get(&Self::pair_key(token0, token1)) {
+            Some(fees) => fees,
+            None if stable_pair => &self.stable_pair_fees,
+            None => &self.volatile_pair_fees,
+        };
+        allowed.is_empty() || allowed.contains(&fee)
+    }
 }
 
 /// 动态利润门槛配置 - 根据 Gas 价格自动调整最小利润要求
@@ -189,6 +716,22 @@ impl Default for DynamicProfitConfig {
     }
 }
 
+impl DynamicProfitConfig {
+    /// 各 Gas 分档中最低的门槛
+    pub fn lowest(&self) -> Decimal {
+        [
+            self.ultra_low_gas_min_profit,
+            self.low_gas_min_profit,
+            self.normal_gas_min_profit,
+            self.high_gas_min_profit,
+            self.very_high_gas_min_profit,
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or_default()
+    }
+}
+
 /// 执行数量策略 - 决定使用多少资金进行套利
 #[derive(Debug, Clone)]
 pub enum ExecutionAmountStrategy {
@@ -210,6 +753,27 @@ impl Default for ExecutionAmountStrategy {
 }
 
 impl ExecutionAmountStrategy {
+    /// 解析策略描述: "full" / "pct:0.8" / "max_usd:5000" / "pct_max_usd:0.8:5000"
+    pub fn parse(s: &str) -> Option<Self> {
+        let parts: Vec<&str> = s.trim().split(':').map(|p| p.trim()).collect();
+        match parts.as_slice() {
+            [kind] if kind.eq_ignore_ascii_case("full") => Some(ExecutionAmountStrategy::FullAmount),
+            [kind, pct] if kind.eq_ignore_ascii_case("pct") => {
+                pct.parse().ok().map(ExecutionAmountStrategy::Percentage)
+            }
+            [kind, max_usd] if kind.eq_ignore_ascii_case("max_usd") => {
+                Decimal::from_str(max_usd).ok().map(ExecutionAmountStrategy::MaxUsd)
+            }
+            [kind, pct, max_usd] if kind.eq_ignore_ascii_case("pct_max_usd") => {
+                Some(ExecutionAmountStrategy::PercentageWithMaxUsd {
+                    percentage: pct.parse().ok()?,
+                    max_usd: Decimal::from_str(max_usd).ok()?,
+                })
+            }
+            _ => None,
+        }
+    }
+
     /// 根据策略计算实际执行金额
     pub fn calculate_amount(
         &self,
@@ -225,40 +789,26 @@ impl ExecutionAmountStrategy {
                 optimal_input * pct_u256 / U256::from(1000u64)
             }
             ExecutionAmountStrategy::MaxUsd(max_usd) => {
-                // 计算 optimal_input 的 USD 价值
-                let divisor = Decimal::from(10u64.pow(token_decimals as u32));
-                let input_dec = decimal_from_str(&optimal_input.to_string()).unwrap_or(Decimal::ZERO);
-                let input_usd = (input_dec / divisor) * token_price_usd;
-
-                if input_usd <= *max_usd {
-                    optimal_input
-                } else {
-                    // 限制为 max_usd 对应的代币数量
-                    let max_tokens = (*max_usd / token_price_usd) * divisor;
-                    let max_str = max_tokens.floor().to_string();
-                    U256::from_dec_str(&max_str).unwrap_or(optimal_input)
-                }
+                Self::cap_to_max_usd(optimal_input, *max_usd, token_decimals, token_price_usd)
             }
             ExecutionAmountStrategy::PercentageWithMaxUsd { percentage, max_usd } => {
-                // 先应用百分比
+                // 先应用百分比，再检查是否超过 max_usd
                 let pct_u256 = U256::from((*percentage * 1000.0) as u64);
                 let after_pct = optimal_input * pct_u256 / U256::from(1000u64);
-
-                // 再检查是否超过 max_usd
-                let divisor = Decimal::from(10u64.pow(token_decimals as u32));
-                let input_dec = decimal_from_str(&after_pct.to_string()).unwrap_or(Decimal::ZERO);
-                let input_usd = (input_dec / divisor) * token_price_usd;
-
-                if input_usd <= *max_usd {
-                    after_pct
-                } else {
-                    let max_tokens = (*max_usd / token_price_usd) * divisor;
-                    let max_str = max_tokens.floor().to_string();
-                    U256::from_dec_str(&max_str).unwrap_or(after_pct)
-                }
+                Self::cap_to_max_usd(after_pct, *max_usd, token_decimals, token_price_usd)
             }
         }
     }
+
+    /// 超过 max_usd 时限制为 max_usd 对应的代币数量
+    fn cap_to_max_usd(amount: U256, max_usd: Decimal, token_decimals: u8, token_price_usd: Decimal) -> U256 {
+        if TokenAmount::new(amount, token_decimals).to_usd(token_price_usd) <= max_usd {
+            return amount;
+        }
+        TokenAmount::from_usd(max_usd, token_price_usd, token_decimals)
+            .map(|capped| capped.raw())
+            .unwrap_or(amount)
+    }
 }
 
 /// 执行器配置 (用于事件驱动扫描器)
@@ -274,16 +824,107 @@ pub struct ScannerExecutorConfig {
     pub use_flashbots: bool,
     /// Flashbots RPC URL
     pub flashbots_rpc_url: Option<String>,
+    /// 额外的 builder RPC 地址 (Bundle 同时发送到这些 builder)
+    pub flashbots_builders: Vec<String>,
+    /// Bundle 默认隐私级别 (单次执行可通过 ArbitrageParams::bundle_privacy 覆盖)
+    pub flashbots_privacy: BundlePrivacy,
+    /// Bundle 签名私钥 (None 时中继使用交易钱包签名)
+    pub flashbots_signer_key: Option<String>,
     /// 是否同时使用公开 mempool（Both 模式）
     pub use_public_mempool: bool,
+    /// Flashbots 仅模拟: 签名 Bundle 后只提交中继模拟 (eth_callBundle)，不真实发送
+    pub flashbots_simulate_only: bool,
     /// 是否为干运行模式 (不实际执行交易)
     pub dry_run: bool,
+    /// 仅检测模式: 记录机会后直接结束，不入执行队列 (不选闪电贷池、不构建参数)
+    pub detection_only: bool,
     /// 优先费 (Gwei) - 支持小数，如 0.005
     pub priority_fee_gwei: f64,
-    /// 执行数量策略
+    /// 执行数量策略 (全局默认)
     pub amount_strategy: ExecutionAmountStrategy,
+    /// 按起始代币覆盖的执行数量策略
+    pub token_amount_strategies: HashMap<Address, ExecutionAmountStrategy>,
     /// 执行前是否模拟
     pub simulate_before_execute: bool,
+    /// 模拟后到 mempool 发送前出现新区块时放弃发送 (只影响 Both 模式的公开 mempool 发送)
+    pub abort_if_block_advanced: bool,
+    /// 固定 gas limit (None 表示动态估算)
+    pub fixed_gas_limit: Option<u64>,
+    /// 链是否支持 EIP-1559 (false 时发送 legacy 交易)
+    pub use_eip1559: bool,
+    /// 模拟执行的 gas limit 倍数 (发送时固定为 1.3)
+    pub simulation_gas_limit_multiplier: f64,
+    /// 发送交易超时 (秒)
+    pub send_timeout_secs: u64,
+    /// 单笔交易 gas 费上限 (USD，None 表示不限制)
+    pub max_gas_cost_usd: Option<f64>,
+    /// 执行后余额快照的确认区块数 (0 表示收到回执后立即按最新区块读取)
+    pub balance_snapshot_confirmations: u64,
+    /// 执行前后是否查询钱包余额 (关闭后不统计实际盈亏)
+    pub fetch_balances: bool,
+    /// 同时进行的余额查询任务上限 (执行前、执行后各算一个任务)
+    pub max_concurrent_balance_fetches: usize,
+    /// 本链同时在途的套利执行上限 (每笔执行占用 nonce 和资金，检测不受此限制，0 表示不限制)
+    pub max_concurrent_executions: usize,
+    /// 净利润 (USD) 不低于该值的机会跳过执行队列，可使用保留许可 (None 表示关闭优先通道)
+    pub priority_min_profit_usd: Option<f64>,
+    /// 同时在途的 Flashbots Bundle 上限，占满时按净利润排队 (0 = 不限制)
+    pub max_in_flight_bundles: usize,
+    /// 模拟执行时的状态覆盖 (预置调用方/合约余额，避免假阴性)
+    pub simulation_state_overrides: Option<spoof::State>,
+    /// 利润结算代币 (None 表示保留起始代币)
+    pub profit_token: Option<Address>,
+    /// 利润转换池费率 (None 表示自动选择)
+    pub profit_convert_fee: Option<u32>,
+    /// 闪电贷借入代币 (None 表示借路径起始代币)
+    pub flash_token: Option<Address>,
+    /// 借入代币转换池费率 (None 表示自动选择)
+    pub flash_convert_fee: Option<u32>,
+}
+
+impl ScannerExecutorConfig {
+    /// 获取起始代币对应的执行数量策略，未单独配置时使用全局默认
+    pub fn amount_strategy_for(&self, start_token: Address) -> &ExecutionAmountStrategy {
+        self.token_amount_strategies
+            .get(&start_token)
+            .unwrap_or(&self.amount_strategy)
+    }
+
+    /// 该链执行器使用的 Flashbots 配置 (中继 URL、签名私钥都来自本链的执行器配置)
+    pub fn flashbots_config(&self, chain_id: u64) -> FlashbotsConfig {
+        FlashbotsConfig {
+            enabled: self.use_flashbots,
+            relay_url: self.flashbots_rpc_url.clone().unwrap_or_default(),
+            chain_id,
+            signer_key: self.flashbots_signer_key.clone(),
+            builders: self.flashbots_builders.clone(),
+            privacy: self.flashbots_privacy,
+            ..Default::default()
+        }
+    }
+
+    /// 发送模式:
+    /// - Both: 同时使用 Flashbots 和公开 mempool
+    /// - Flashbots: 仅使用 Flashbots
+    /// - Normal: 仅使用公开 mempool
+    ///
+    /// 仅模拟优先于以上模式: 任何组合下都不会真实发送
+    pub fn send_mode(&self) -> SendMode {
+        if self.flashbots_simulate_only {
+            SendMode::FlashbotsSimulateOnly
+        } else if self.use_flashbots && self.use_public_mempool {
+            SendMode::Both
+        } else if self.use_flashbots {
+            SendMode::Flashbots
+        } else {
+            SendMode::Normal
+        }
+    }
+
+    /// 执行时是否会提交 Flashbots Bundle (干运行、仅模拟不提交，不占在途名额)
+    fn submits_bundles(&self) -> bool {
+        !self.dry_run && !self.detection_only && matches!(self.send_mode(), SendMode::Flashbots | SendMode::Both)
+    }
 }
 
 impl Default for ScannerExecutorConfig {
@@ -294,11 +935,34 @@ impl Default for ScannerExecutorConfig {
             max_gas_price_gwei: 100.0,
             use_flashbots: false,
             flashbots_rpc_url: Some("https://relay.flashbots.net".to_string()),
+            flashbots_builders: Vec::new(),
+            flashbots_privacy: BundlePrivacy::default(),
+            flashbots_signer_key: None,
             use_public_mempool: false,
+            flashbots_simulate_only: false,
             dry_run: true,
+            detection_only: false,
             priority_fee_gwei: 2.0,
             amount_strategy: ExecutionAmountStrategy::default(),
+            token_amount_strategies: HashMap::new(),
             simulate_before_execute: true,
+            abort_if_block_advanced: true,
+            fixed_gas_limit: None,
+            use_eip1559: true,
+            simulation_gas_limit_multiplier: 1.0,
+            send_timeout_secs: 30,
+            max_gas_cost_usd: None,
+            balance_snapshot_confirmations: 0,
+            fetch_balances: true,
+            max_concurrent_balance_fetches: 2,
+            max_concurrent_executions: 0,
+            priority_min_profit_usd: None,
+            max_in_flight_bundles: 0,
+            simulation_state_overrides: None,
+            profit_token: None,
+            profit_convert_fee: None,
+            flash_token: None,
+            flash_convert_fee: None,
         }
     }
 }
@@ -326,10 +990,81 @@ pub struct EventDrivenScannerConfig {
     pub min_swap_value_usd: Decimal,
     /// 跳过本地计算阈值 (USD) - 超过该值直接用链上计算，避免大资金跨 Tick 时本地估算不准
     pub skip_local_calc_threshold_usd: Decimal,
+    /// 本地估算允许的平均偏差 (基点)，池子偏差超过时按比例降低跳过本地计算阈值 (0 = 固定阈值)
+    pub local_calc_error_tolerance_bps: u32,
+    /// 池子至少积累多少个偏差样本后才调整阈值
+    pub local_calc_min_samples: u64,
     /// 执行器配置
     pub executor_config: ScannerExecutorConfig,
     /// 最大并发处理事件数量 (防止资源耗尽)
     pub max_concurrent_handlers: usize,
+    /// 是否用 QuoterV2.quoteExactInput 多跳报价交叉验证逐跳链式报价
+    pub multi_hop_cross_check: bool,
+    /// 多跳报价与链式报价的允许偏差 (基点)，超过则采用多跳报价
+    pub multi_hop_tolerance_bps: u32,
+    /// 执行批次收集窗口 (毫秒)，窗口内到达的机会统一评分排序后再执行 (0 = 不等待)
+    pub execution_batch_window_ms: u64,
+    /// 连续多少个区块没有 Swap 事件视为死池 (0 = 不检测)
+    pub dead_pool_after_blocks: u64,
+    /// 死池降频后的刷新间隔 (区块数，0 = 不再刷新)
+    pub dead_pool_refresh_interval: u64,
+    /// 启动预热: 至少成功刷新多少个区块的池子状态后才自动执行 (与 startup_warmup_secs 都为 0 时不预热)
+    pub startup_warmup_blocks: u64,
+    /// 启动预热: 启动后至少等待的秒数
+    pub startup_warmup_secs: u64,
+    /// 原生代币符号 (利润换算展示用)
+    pub native_token_symbol: String,
+    /// 参考稳定币符号 (利润换算展示用)
+    pub reference_stablecoin: String,
+    /// 单跳价格影响上限 (%)，0 表示不限制
+    pub max_price_impact_pct: Decimal,
+    /// Swap 通道积压丢事件后，清空积压并且每个池子只处理最新的事件
+    pub swap_lag_newest_only: bool,
+    /// 报价遇到临时错误 (超时、限流) 时的最多重试次数，0 表示不重试
+    pub quote_retry_attempts: u32,
+    /// 报价重试间隔 (毫秒)
+    pub quote_retry_delay_ms: u64,
+    /// 按代币对限制报价的费率档位 (为空不限制)
+    pub fee_tier_whitelist: FeeTierWhitelist,
+    /// 单个 Swap 事件的路径检测时间预算 (毫秒)，超出后不再检查剩余路径，0 表示不限制
+    pub path_eval_budget_ms: u64,
+    /// 单个 Swap 事件同时检查的候选路径数上限 (1 = 逐条检查)
+    pub path_eval_concurrency: usize,
+    /// 预定义路径是否同时检查反方向 (A->C->B->A)，两个方向都有利润时取净利润高的；报价次数翻倍
+    pub evaluate_both_directions: bool,
+    /// 没有路径映射的池子触发时是否回退到动态枚举 (关闭后直接忽略该池子)
+    pub legacy_enumeration_enabled: bool,
+    /// 动态枚举 (无路径映射) 时每个方向最多报价的第三池数量，按深度取最深的，0 表示不限制
+    pub legacy_max_pool3_candidates: usize,
+    /// Gas 价格来源 (节点 / base fee / gas oracle)，非节点来源失败时回退到节点
+    pub gas_price_source: GasPriceSource,
+    /// 输入金额上限: 路径上最浅池子深度 (USD) 的比例，0 表示不限制
+    pub max_liquidity_fraction: Decimal,
+    /// 路径连续执行失败多少次后暂停 (0 = 不暂停)
+    pub path_failure_threshold: u32,
+    /// 路径暂停时长 (秒)，到期后重新放行
+    pub path_failure_cooldown_secs: u64,
+    /// 成功执行后涉及池子的冷却时长 (秒)，期间涉及这些池子的机会推迟到冷却结束后重新报价，0 表示不冷却
+    pub post_execution_pool_cooldown_secs: u64,
+    /// 实际利润与预估利润相差超过该值 (USD) 时发送高优先级告警，0 表示不告警
+    pub profit_deviation_alert_usd: Decimal,
+    /// 单跳成交价相对价格服务现货价的最大偏离 (%)，超过则视为池子被操纵或状态过期，0 表示不检查
+    pub max_spot_deviation_pct: Decimal,
+    /// 原生代币计价模式 (None 表示按 USD 计价)；启用后 "USD" 字段的数值均为原生代币
+    pub native_denomination: Option<NativeDenomination>,
+    /// 借入代币 → 首选闪电贷池，路径没有固定闪电贷池时优先于自动选择
+    pub preferred_flash_pools: HashMap<Address, Address>,
+    /// 闪电贷费率表 (检测时估算费用和构建执行参数共用)
+    pub flash_fee_table: FlashFeeTable,
+    /// 按代币对 / 三角覆盖最小利润门槛 (USD)，命中的路径不使用 Gas 分档门槛
+    pub min_profit_overrides: MinProfitOverrides,
+    /// 正常出块但超过该时长 (秒) 没有发现任何机会时发送告警，0 表示不检查
+    pub no_opportunity_alert_secs: u64,
+    /// 纸面交易 (A/B 对比的候选组): 发现的机会只返回给对比报告，不写机会日志、不推送、不计入统计
+    pub paper_trading: bool,
+    /// 加载 PancakeSwap StableSwap 池子 (仅检测: 套利合约没有 StableSwap 的 swap 分支，
+    /// 稳定池也不发出订阅的 Swap 事件)，默认关闭
+    pub stable_swap_pools_enabled: bool,
 }
 
 impl Default for EventDrivenScannerConfig {
@@ -345,8 +1080,43 @@ impl Default for EventDrivenScannerConfig {
             enable_dynamic_profit: true, // 默认启用动态门槛
             min_swap_value_usd: dec!(1), // 默认 $1，小于该值的交易不进行套利评估
             skip_local_calc_threshold_usd: dec!(5000), // 默认 $5000，超过此值跳过本地计算直接链上计算
+            local_calc_error_tolerance_bps: 0,
+            local_calc_min_samples: 5,
             executor_config: ScannerExecutorConfig::default(),
             max_concurrent_handlers: 5, // 默认最多同时处理 5 个 swap 事件
+            multi_hop_cross_check: false,
+            multi_hop_tolerance_bps: 10, // 0.1%
+            execution_batch_window_ms: 0,
+            dead_pool_after_blocks: 0,
+            dead_pool_refresh_interval: 10,
+            startup_warmup_blocks: 0,
+            startup_warmup_secs: 0,
+            native_token_symbol: "ETH".to_string(),
+            reference_stablecoin: "USDC".to_string(),
+            max_price_impact_pct: Decimal::ZERO,
+            swap_lag_newest_only: false,
+            quote_retry_attempts: 2,
+            quote_retry_delay_ms: 100,
+            fee_tier_whitelist: FeeTierWhitelist::default(),
+            path_eval_budget_ms: 0,
+            path_eval_concurrency: 1,
+            evaluate_both_directions: false,
+            legacy_enumeration_enabled: true,
+            legacy_max_pool3_candidates: 0,
+            gas_price_source: GasPriceSource::Node,
+            max_liquidity_fraction: Decimal::ZERO,
+            path_failure_threshold: 0,
+            path_failure_cooldown_secs: 300,
+            post_execution_pool_cooldown_secs: 0,
+            profit_deviation_alert_usd: Decimal::ZERO,
+            max_spot_deviation_pct: Decimal::ZERO,
+            native_denomination: None,
+            preferred_flash_pools: HashMap::new(),
+            flash_fee_table: FlashFeeTable::default(),
+            min_profit_overrides: MinProfitOverrides::default(),
+            no_opportunity_alert_secs: 0,
+            paper_trading: false,
+            stable_swap_pools_enabled: false,
         }
     }
 }
@@ -364,19 +1134,43 @@ pub enum RpcCallType {
     MulticallRefreshPools,
     /// QuoterV2 链上报价
     QuoterV2Quote,
+    /// QuoterV2 多跳报价 (交叉验证)
+    QuoterV2MultiHopQuote,
+    /// QuoterV1 链上报价 (未部署 QuoterV2 的链)
+    QuoterV1Quote,
     /// 获取 Gas Price
     GetGasPrice,
     /// ERC20 余额查询
     Erc20BalanceOf,
+    /// Multicall 批量余额查询
+    MulticallBalances,
+    /// StableSwap get_dy 报价
+    StableSwapQuote,
 }
 
 impl RpcCallType {
+    /// 所有类型 (统计输出顺序)
+    const ALL: [RpcCallType; 8] = [
+        RpcCallType::MulticallRefreshPools,
+        RpcCallType::QuoterV2Quote,
+        RpcCallType::QuoterV2MultiHopQuote,
+        RpcCallType::QuoterV1Quote,
+        RpcCallType::GetGasPrice,
+        RpcCallType::Erc20BalanceOf,
+        RpcCallType::MulticallBalances,
+        RpcCallType::StableSwapQuote,
+    ];
+
     fn name(&self) -> &'static str {
         match self {
             RpcCallType::MulticallRefreshPools => "Multicall刷新池子",
             RpcCallType::QuoterV2Quote => "QuoterV2报价",
+            RpcCallType::QuoterV2MultiHopQuote => "QuoterV2多跳报价",
+            RpcCallType::QuoterV1Quote => "QuoterV1报价",
             RpcCallType::GetGasPrice => "Gas Price查询",
             RpcCallType::Erc20BalanceOf => "ERC20余额查询",
+            RpcCallType::MulticallBalances => "Multicall余额查询",
+            RpcCallType::StableSwapQuote => "StableSwap报价",
         }
     }
 }
@@ -404,6 +1198,8 @@ pub struct RpcStats {
     start_time: std::time::Instant,
     /// 当前分钟开始时间
     current_minute_start: std::sync::RwLock<std::time::Instant>,
+    /// 上次导出区间用量时的累计值
+    last_snapshot: std::sync::Mutex<HashMap<RpcCallType, RpcTypeStats>>,
 }
 
 impl RpcStats {
@@ -412,6 +1208,7 @@ impl RpcStats {
             stats: std::sync::RwLock::new(HashMap::new()),
             start_time: std::time::Instant::now(),
             current_minute_start: std::sync::RwLock::new(std::time::Instant::now()),
+            last_snapshot: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
@@ -427,6 +1224,11 @@ impl RpcStats {
         }
     }
 
+    /// 某类 RPC 启动以来的总调用次数
+    pub fn total_calls(&self, call_type: RpcCallType) -> u64 {
+        self.stats.read().unwrap().get(&call_type).map_or(0, |s| s.total_calls)
+    }
+
     /// 切换到新的一分钟 (在每个新区块时检查)
     pub fn maybe_rotate_minute(&self) {
         let mut minute_start = self.current_minute_start.write().unwrap();
@@ -441,6 +1243,42 @@ impl RpcStats {
         }
     }
 
+    /// 启动以来的秒数
+    pub fn uptime_secs(&self) -> u64 {
+        self.start_time.elapsed().as_secs()
+    }
+
+    /// 各类型启动以来的总调用次数 (按固定顺序)
+    pub fn calls_by_type(&self) -> Vec<(String, u64)> {
+        let stats = self.stats.read().unwrap();
+        RpcCallType::ALL
+            .iter()
+            .map(|t| (t.name().to_string(), stats.get(t).map_or(0, |s| s.total_calls)))
+            .collect()
+    }
+
+    /// 自上次导出以来各类型的调用量 (按固定顺序，区间内没有调用的类型不输出)
+    pub fn take_interval_usage(&self) -> Vec<models::RpcUsage> {
+        let stats = self.stats.read().unwrap().clone();
+        let mut last = self.last_snapshot.lock().unwrap();
+        let usage = RpcCallType::ALL
+            .iter()
+            .filter_map(|t| {
+                let current = stats.get(t)?;
+                let previous = last.get(t).cloned().unwrap_or_default();
+                let calls = current.total_calls - previous.total_calls;
+                (calls > 0).then(|| models::RpcUsage {
+                    call_type: t.name().to_string(),
+                    calls,
+                    failed_calls: current.failed_calls - previous.failed_calls,
+                    total_duration_ms: current.total_duration_ms - previous.total_duration_ms,
+                })
+            })
+            .collect();
+        *last = stats;
+        usage
+    }
+
     /// 获取统计摘要
     pub fn get_summary(&self) -> String {
         let stats = self.stats.read().unwrap();
@@ -460,18 +1298,11 @@ impl RpcStats {
         ));
         lines.push("─".repeat(60));
 
-        let call_types = [
-            RpcCallType::MulticallRefreshPools,
-            RpcCallType::QuoterV2Quote,
-            RpcCallType::GetGasPrice,
-            RpcCallType::Erc20BalanceOf,
-        ];
-
         let mut total_calls = 0u64;
         let mut total_last_min = 0u64;
         let mut total_current_min = 0u64;
 
-        for call_type in &call_types {
+        for call_type in &RpcCallType::ALL {
             let type_stats = stats.get(call_type).cloned().unwrap_or_default();
             let avg_ms = if type_stats.total_calls > 0 {
                 type_stats.total_duration_ms / type_stats.total_calls
@@ -522,6 +1353,31 @@ pub struct TokenConfig {
     pub optimal_input_amount: U256,
 }
 
+/// 钱包余额查询的上下文 (执行前后的余额快照共用，可移入 tokio::spawn)
+struct BalanceFetcher<M> {
+    provider: Arc<M>,
+    price_service: SharedPriceService,
+    token_configs: HashMap<Address, TokenConfig>,
+    multicall_address: Address,
+    /// 查询余额的钱包 (套利合约)
+    contract_address: Address,
+    rpc_stats: Option<Arc<RpcStats>>,
+}
+
+// 手动实现: derive 会要求 M: Clone，而这里只克隆 Arc<M>
+impl<M> Clone for BalanceFetcher<M> {
+    fn clone(&self) -> Self {
+        Self {
+            provider: self.provider.clone(),
+            price_service: self.price_service.clone(),
+            token_configs: self.token_configs.clone(),
+            multicall_address: self.multicall_address,
+            contract_address: self.contract_address,
+            rpc_stats: self.rpc_stats.clone(),
+        }
+    }
+}
+
 /// 三角套利组合配置 (从数据库加载) - 保留用于向后兼容
 #[derive(Debug, Clone)]
 pub struct TriangleConfig {
@@ -533,6 +1389,18 @@ pub struct TriangleConfig {
     pub category: String,
 }
 
+/// 链下利润门槛比合约 minProfitThreshold 宽松的路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinProfitConflict {
+    pub path_name: String,
+    /// 起始代币 (合约按它计算利润)
+    pub token_symbol: String,
+    /// 链下门槛 (USD，原生代币计价模式下为原生代币)
+    pub off_chain_min_profit: Decimal,
+    pub off_chain_wei: U256,
+    pub on_chain_wei: U256,
+}
+
 /// 池子触发的套利路径配置 (从数据库加载)
 #[derive(Debug, Clone)]
 pub struct PoolPathConfig {
@@ -541,9 +1409,42 @@ pub struct PoolPathConfig {
     pub token_a: Address,
     pub token_b: Address,
     pub token_c: Address,
+    /// 配置的三跳池子 [A->B, B->C, C->A] (未配置时为 None)
+    pub pools: Option<[Address; 3]>,
+    /// 固定使用的闪电贷池 (未配置时自动选择)
+    pub flash_pool: Option<Address>,
+    /// 固定输入数量 (token_a 最小单位)，设置后不再按触发 swap 金额换算
+    pub fixed_input_amount: Option<U256>,
+    /// 执行该路径使用的套利合约 (新旧合约灰度并行，未配置时使用链默认合约)
+    pub arbitrage_contract: Option<Address>,
     pub priority: i32,
 }
 
+impl PoolPathConfig {
+    /// 同一三角的反方向路径: A->C->B->A，配置的池子按相反顺序使用，其余设置不变
+    pub fn reversed(&self) -> Self {
+        Self {
+            path_name: format!("{} (反向)", self.path_name),
+            token_b: self.token_c,
+            token_c: self.token_b,
+            pools: self.pools.map(|[p1, p2, p3]| [p3, p2, p1]),
+            ..self.clone()
+        }
+    }
+
+    /// 是否与另一条路径走同一路线 (代币方向与池子配置都一致)
+    fn same_route(&self, other: &PoolPathConfig) -> bool {
+        [self.token_a, self.token_b, self.token_c] == [other.token_a, other.token_b, other.token_c]
+            && self.pools == other.pools
+    }
+
+    /// 是否为该方向的路径 (代币一致，且配置了池子时三跳池子也一致)
+    fn matches(&self, tokens: [Address; 3], swap_pools: &[Address]) -> bool {
+        [self.token_a, self.token_b, self.token_c] == tokens
+            && self.pools.is_none_or(|pools| pools[..] == *swap_pools)
+    }
+}
+
 /// 链合约地址配置 (用于扫描器)
 #[derive(Debug, Clone)]
 pub struct ChainContractsConfig {
@@ -601,6 +1502,24 @@ impl ChainContractsConfig {
         }
     }
 
+    /// Optimism 主网配置
+    pub fn optimism() -> Self {
+        Self {
+            quoter_address: "0x61fFE014bA17989E743c5F6cB21bF9697530B21e".parse().unwrap(),
+            multicall_address: "0xcA11bde05977b3631167028862bE2a173976CA11".parse().unwrap(),
+            chain_name: "Optimism".to_string(),
+        }
+    }
+
+    /// Avalanche C-Chain 配置
+    pub fn avalanche() -> Self {
+        Self {
+            quoter_address: "0xbe0F5544EC67e9B3b2D979aaA43f18Fd87E6257F".parse().unwrap(),
+            multicall_address: "0xcA11bde05977b3631167028862bE2a173976CA11".parse().unwrap(),
+            chain_name: "Avalanche".to_string(),
+        }
+    }
+
     /// 根据 chain_id 获取配置
     pub fn for_chain(chain_id: u64) -> Option<Self> {
         match chain_id {
@@ -609,6 +1528,8 @@ impl ChainContractsConfig {
             137 => Some(Self::polygon()),
             42161 => Some(Self::arbitrum()),
             8453 => Some(Self::base()),
+            10 => Some(Self::optimism()),
+            43114 => Some(Self::avalanche()),
             _ => None,
         }
     }
@@ -629,17 +1550,20 @@ pub struct EventDrivenScanner<M: Middleware> {
     config: EventDrivenScannerConfig,
     /// 链上 provider
     provider: Arc<M>,
-    /// 价格服务
+    /// 价格服务 (多链共享)
     price_service: SharedPriceService,
+    /// 代币元数据缓存 (多链共享，按 chain_id 隔离)
+    token_metadata: SharedTokenMetadataCache,
     /// Quoter 合约地址
     quoter_address: Address,
+    /// Quoter 接口版本 (首次报价成功时检测)
+    quoter_interface: RwLock<Option<QuoterInterface>>,
     /// Multicall3 合约地址
-    #[allow(dead_code)]
     multicall_address: Address,
     /// 链名称 (用于日志)
     chain_name: String,
-    /// 池子状态缓存: address -> PoolState
-    pool_states: RwLock<HashMap<Address, PoolState>>,
+    /// 池子状态缓存: address -> PoolState (A/B 候选组与线上扫描器共用，由线上扫描器刷新)
+    pool_states: Arc<RwLock<HashMap<Address, PoolState>>>,
     /// 代币配置缓存: address -> TokenConfig
     token_configs: RwLock<HashMap<Address, TokenConfig>>,
     /// 三角套利组合配置缓存 (保留用于向后兼容)
@@ -654,12 +1578,14 @@ pub struct EventDrivenScanner<M: Middleware> {
     gas_price_cache: RwLock<Option<GasPriceCache>>,
     /// 当前区块号 (用于检查缓存新鲜度)
     current_block: AtomicU64,
-    /// 钱包 (用于执行交易)
+    /// 最近处理过的新区块事件 (忽略重复/乱序推送)
+    block_sequence: BlockSequenceGuard,
+    /// 钱包 (用于执行交易，也直接交给执行器签名)
     wallet: RwLock<Option<LocalWallet>>,
-    /// 私钥字符串 (用于创建执行器)
-    private_key: RwLock<Option<String>>,
-    /// 执行统计
-    execution_stats: RwLock<ExecutionStats>,
+    /// 执行统计 (后台余额对比任务也会更新)
+    execution_stats: Arc<RwLock<ExecutionStats>>,
+    /// 交易结果数据库 (记录预估/实际利润偏差，未设置时只统计不落库)
+    trade_db: Option<Arc<Database>>,
     /// 并发控制信号量
     handler_semaphore: Arc<Semaphore>,
     /// 已执行的机会记录 (路径签名 -> 执行记录)，用于去重
@@ -670,11 +1596,70 @@ pub struct EventDrivenScanner<M: Middleware> {
     processed_tx_hashes: RwLock<HashMap<H256, std::time::Instant>>,
     /// RPC 调用统计
     rpc_stats: Arc<RpcStats>,
+    /// 机会评分器 (同批机会排序、排除池子冲突)
+    scorer: OpportunityScorer,
+    /// 待执行机会队列
+    pending_executions: RwLock<Vec<ArbitrageOpportunity>>,
+    /// 因池子冷却而推迟的机会 (冷却结束后重新报价)
+    deferred_executions: RwLock<Vec<ArbitrageOpportunity>>,
+    /// 执行许可 (每条链一个扫描器，各链独立限制同时在途的执行数)
+    execution_permits: Semaphore,
+    /// 优先通道保留的执行许可，只有高价值机会可以使用
+    priority_execution_permits: Semaphore,
+    /// Flashbots Bundle 在途名额 (名额占满时高价值 Bundle 优先)
+    bundle_slots: BundleSlots,
+    /// 钱包 nonce 分配器: 并发执行共用，每次执行创建的执行器都从这里分配 nonce
+    nonce_allocator: Arc<NonceAllocator>,
+    /// 排队机会的检测 span (机会 ID -> span)，执行 span 挂在其下，使检测和执行属于同一条 trace
+    opportunity_spans: RwLock<HashMap<String, tracing::Span>>,
+    /// 是否已有任务在分发执行队列
+    execution_dispatching: AtomicBool,
+    /// 启动预热状态 (预热结束前不自动执行)
+    warmup: StartupWarmup,
+    /// 机会/执行结果推送 (未配置时为空实现)
+    event_sink: SharedEventSink,
+    /// 交易黑名单
+    blocklist: RwLock<TradingBlocklist>,
+    /// 余额查询任务许可 (None 表示不查询余额)
+    balance_fetch_permits: Option<Arc<Semaphore>>,
+    /// 自动执行是否被暂停 (策略停止时设置，检测照常进行)
+    execution_paused: AtomicBool,
+    /// 连续失败路径的冷却状态 (冷却中的路径不报价、不执行)
+    path_cooldown: PathCooldown,
+    /// 刚成功执行过的池子 (冷却中不执行涉及这些池子的机会，避免追逐自己的成交)
+    pool_cooldown: PoolCooldown,
+    /// 长时间没有发现机会时告警 (检测可能已失灵)
+    opportunity_watchdog: OpportunityWatchdog,
+    /// 最近检测到的机会 (供运营人员审核后手动执行)
+    recent_opportunities: RwLock<VecDeque<ArbitrageOpportunity>>,
+    /// A/B 对比会话 (None 表示不对比)，每个 Swap 事件同时交给候选组检测
+    ab_harness: RwLock<Option<Arc<AbHarness>>>,
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware + 'static> ExecutionControl for EventDrivenScanner<M> {
+    fn set_execution_paused(&self, paused: bool) {
+        EventDrivenScanner::set_execution_paused(self, paused);
+    }
+
+    async fn profit_report(&self) -> ProfitReport {
+        self.execution_stats.read().await.profit_tracker.report(self.config.chain_id, &self.chain_name)
+    }
+
+    async fn execute_recent_opportunity(&self, id: &str) -> Result<models::ArbitrageResult, ManualExecutionError> {
+        EventDrivenScanner::execute_recent_opportunity(self, id).await
+    }
+
+    async fn execute_opportunity(&self, opportunity: ArbitrageOpportunity) -> Result<models::ArbitrageResult, ManualExecutionError> {
+        self.requote_and_execute(opportunity).await
+    }
 }
 
 /// 执行统计
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionStats {
+    /// 发现的机会数
+    pub opportunities_found: u64,
     /// 总执行次数
     pub total_executions: u64,
     /// 成功次数
@@ -685,14 +1670,44 @@ pub struct ExecutionStats {
     pub dry_run_executions: u64,
     /// 总利润 (USD)
     pub total_profit_usd: Decimal,
+    /// 原生代币计价模式下的总利润 (原生代币数量)
+    pub total_profit_native: Decimal,
     /// 当前正在处理的事件数
     pub active_handlers: u64,
-    /// 被丢弃的事件数 (并发数已满时)
+    /// 被丢弃的 Swap 事件数 (通道积压被覆盖，或过载时被同池子更新的事件取代)
     pub dropped_events: u64,
     /// 重复事件被跳过的次数
     pub duplicates_skipped: u64,
     /// 因池子正在执行而跳过的次数
     pub pool_busy_skipped: u64,
+    /// 因池子刚执行过、仍在冷却而推迟的次数
+    pub pool_cooldown_deferred: u64,
+    /// 同批中与更优机会共用池子而放弃的次数
+    pub conflict_skipped: u64,
+    /// 排队期间已有新区块、检测区块过期而放弃的次数
+    pub stale_skipped: u64,
+    /// 已对比预估/实际利润的成功交易数
+    pub profit_delta_samples: u64,
+    /// 实际利润 - 预估利润 的累计值 (USD)
+    pub total_profit_delta_usd: Decimal,
+    /// 利润直方图、最大回撤、滚动胜率
+    pub profit_tracker: ProfitTracker,
+}
+
+impl ExecutionStats {
+    /// 记录一笔成功交易的利润偏差
+    pub fn record_profit_delta(&mut self, delta: &ProfitDelta) {
+        self.profit_delta_samples += 1;
+        self.total_profit_delta_usd += delta.delta_usd();
+    }
+
+    /// 平均利润偏差 (USD)，负数表示系统性高估
+    pub fn avg_profit_delta_usd(&self) -> Decimal {
+        if self.profit_delta_samples == 0 {
+            return Decimal::ZERO;
+        }
+        self.total_profit_delta_usd / Decimal::from(self.profit_delta_samples)
+    }
 }
 
 /// Uniswap V3 QuoterV2 地址 (Ethereum Mainnet) - 返回 gas 估算
@@ -720,6 +1735,13 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         chain_contracts: ChainContractsConfig,
     ) -> Self {
         let max_concurrent = config.max_concurrent_handlers;
+        let warmup = StartupWarmup::new(config.startup_warmup_blocks, config.startup_warmup_secs);
+        let balance_fetch_permits = balance_fetch_permits(&config.executor_config);
+        let execution_permits = execution_permits(&config.executor_config);
+        let path_cooldown = PathCooldown::new(config.path_failure_threshold, config.path_failure_cooldown_secs);
+        let pool_cooldown = PoolCooldown::new(0, config.post_execution_pool_cooldown_secs);
+        let opportunity_watchdog = OpportunityWatchdog::new(config.no_opportunity_alert_secs);
+        let bundle_slots = BundleSlots::new(config.executor_config.max_in_flight_bundles);
         info!("[{}] 创建事件驱动扫描器, chain_id={}, quoter={:?}, auto_execute={}, max_concurrent={}",
               chain_contracts.chain_name, config.chain_id, chain_contracts.quoter_address,
               config.executor_config.auto_execute, max_concurrent);
@@ -728,10 +1750,12 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             config,
             provider,
             price_service,
+            token_metadata: Arc::new(TokenMetadataCache::new()),
             quoter_address: chain_contracts.quoter_address,
+            quoter_interface: RwLock::new(None),
             multicall_address: chain_contracts.multicall_address,
             chain_name: chain_contracts.chain_name,
-            pool_states: RwLock::new(HashMap::new()),
+            pool_states: Arc::new(RwLock::new(HashMap::new())),
             token_configs: RwLock::new(HashMap::new()),
             triangle_configs: RwLock::new(Vec::new()),
             pool_path_mappings: RwLock::new(HashMap::new()),
@@ -739,28 +1763,57 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             running: RwLock::new(false),
             gas_price_cache: RwLock::new(None),
             current_block: AtomicU64::new(0),
+            block_sequence: BlockSequenceGuard::default(),
             wallet: RwLock::new(None),
-            private_key: RwLock::new(None),
-            execution_stats: RwLock::new(ExecutionStats::default()),
+            execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
+            trade_db: None,
             executed_opportunities: RwLock::new(HashMap::new()),
             executing_pools: RwLock::new(std::collections::HashSet::new()),
             processed_tx_hashes: RwLock::new(HashMap::new()),
             rpc_stats: Arc::new(RpcStats::new()),
+            scorer: OpportunityScorer::default(),
+            pending_executions: RwLock::new(Vec::new()),
+            deferred_executions: RwLock::new(Vec::new()),
+            execution_permits,
+            priority_execution_permits: Semaphore::new(PRIORITY_LANE_PERMITS),
+            bundle_slots,
+            nonce_allocator: Arc::new(NonceAllocator::new()),
+            opportunity_spans: RwLock::new(HashMap::new()),
+            execution_dispatching: AtomicBool::new(false),
+            warmup,
+            event_sink: Arc::new(NoopEventSink),
+            blocklist: RwLock::new(TradingBlocklist::default()),
+            balance_fetch_permits,
+            execution_paused: AtomicBool::new(false),
+            path_cooldown,
+            pool_cooldown,
+            opportunity_watchdog,
+            recent_opportunities: RwLock::new(VecDeque::new()),
+            ab_harness: RwLock::new(None),
         }
     }
 
     /// 使用自定义 Quoter 地址创建 (保持向后兼容)
     pub fn with_quoter(config: EventDrivenScannerConfig, provider: Arc<M>, price_service: SharedPriceService, quoter_address: Address) -> Self {
         let max_concurrent = config.max_concurrent_handlers;
+        let warmup = StartupWarmup::new(config.startup_warmup_blocks, config.startup_warmup_secs);
+        let balance_fetch_permits = balance_fetch_permits(&config.executor_config);
+        let execution_permits = execution_permits(&config.executor_config);
+        let path_cooldown = PathCooldown::new(config.path_failure_threshold, config.path_failure_cooldown_secs);
+        let pool_cooldown = PoolCooldown::new(0, config.post_execution_pool_cooldown_secs);
+        let opportunity_watchdog = OpportunityWatchdog::new(config.no_opportunity_alert_secs);
+        let bundle_slots = BundleSlots::new(config.executor_config.max_in_flight_bundles);
         Self {
             handler_semaphore: Arc::new(Semaphore::new(max_concurrent)),
             config,
             provider,
             price_service,
+            token_metadata: Arc::new(TokenMetadataCache::new()),
             quoter_address,
+            quoter_interface: RwLock::new(None),
             multicall_address: DEFAULT_MULTICALL3.parse().unwrap(),
             chain_name: "Unknown".to_string(),
-            pool_states: RwLock::new(HashMap::new()),
+            pool_states: Arc::new(RwLock::new(HashMap::new())),
             token_configs: RwLock::new(HashMap::new()),
             triangle_configs: RwLock::new(Vec::new()),
             pool_path_mappings: RwLock::new(HashMap::new()),
@@ -768,25 +1821,60 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             running: RwLock::new(false),
             gas_price_cache: RwLock::new(None),
             current_block: AtomicU64::new(0),
+            block_sequence: BlockSequenceGuard::default(),
             wallet: RwLock::new(None),
-            private_key: RwLock::new(None),
-            execution_stats: RwLock::new(ExecutionStats::default()),
+            execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
+            trade_db: None,
             executed_opportunities: RwLock::new(HashMap::new()),
             executing_pools: RwLock::new(std::collections::HashSet::new()),
             processed_tx_hashes: RwLock::new(HashMap::new()),
             rpc_stats: Arc::new(RpcStats::new()),
+            scorer: OpportunityScorer::default(),
+            pending_executions: RwLock::new(Vec::new()),
+            deferred_executions: RwLock::new(Vec::new()),
+            execution_permits,
+            priority_execution_permits: Semaphore::new(PRIORITY_LANE_PERMITS),
+            bundle_slots,
+            nonce_allocator: Arc::new(NonceAllocator::new()),
+            opportunity_spans: RwLock::new(HashMap::new()),
+            execution_dispatching: AtomicBool::new(false),
+            warmup,
+            event_sink: Arc::new(NoopEventSink),
+            blocklist: RwLock::new(TradingBlocklist::default()),
+            balance_fetch_permits,
+            execution_paused: AtomicBool::new(false),
+            path_cooldown,
+            pool_cooldown,
+            opportunity_watchdog,
+            recent_opportunities: RwLock::new(VecDeque::new()),
+            ab_harness: RwLock::new(None),
+        }
+    }
+
+    /// 暂停或恢复自动执行
+    pub fn set_execution_paused(&self, paused: bool) {
+        if self.execution_paused.swap(paused, Ordering::AcqRel) != paused {
+            info!("[{}] {} 自动执行", self.chain_name, if paused { "⏸️ 暂停" } else { "▶️ 恢复" });
         }
     }
 
+    /// 自动执行是否已暂停
+    pub fn is_execution_paused(&self) -> bool {
+        self.execution_paused.load(Ordering::Acquire)
+    }
+
     /// 设置钱包 (用于执行交易)
-    pub async fn set_wallet(&self, wallet: LocalWallet, private_key: String) {
+    pub async fn set_wallet(&self, wallet: LocalWallet) {
         let mut w = self.wallet.write().await;
         *w = Some(wallet);
-        let mut pk = self.private_key.write().await;
-        *pk = Some(private_key);
         info!("[{}] 钱包已设置", self.chain_name);
     }
 
+    /// 钱包 nonce 分配器 (同一钱包的其他发送方需要共用，如 WETH 缓冲维护)
+    pub fn nonce_allocator(&self) -> Arc<NonceAllocator> {
+        self.nonce_allocator.clone()
+    }
+
     /// 获取执行统计
     pub async fn get_execution_stats(&self) -> ExecutionStats {
         self.execution_stats.read().await.clone()
@@ -797,6 +1885,29 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         self.rpc_stats.clone()
     }
 
+    /// 本次运行的会话汇总 (执行统计 + RPC 统计)
+    pub async fn session_summary(&self) -> models::SessionSummary {
+        let stats = self.execution_stats.read().await.clone();
+        models::SessionSummary {
+            chain_id: self.config.chain_id,
+            chain_name: self.chain_name.clone(),
+            ended_at: chrono::Utc::now(),
+            uptime_secs: self.rpc_stats.uptime_secs(),
+            opportunities_found: stats.opportunities_found,
+            total_executions: stats.total_executions,
+            successful_executions: stats.successful_executions,
+            failed_executions: stats.failed_executions,
+            dry_run_executions: stats.dry_run_executions,
+            total_profit_usd: stats.total_profit_usd,
+            rpc_calls: self.rpc_stats.calls_by_type(),
+        }
+    }
+
+    /// 自上次调用以来的 RPC 区间用量 (定期写入数据库)
+    pub fn take_rpc_usage(&self) -> Vec<models::RpcUsage> {
+        self.rpc_stats.take_interval_usage()
+    }
+
     /// 打印 RPC 统计摘要
     pub fn print_rpc_stats(&self) {
         info!("\n{}", self.rpc_stats.get_summary());
@@ -812,8 +1923,61 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         self.config.chain_id
     }
 
+    /// 设置交易结果数据库 (成功交易的利润偏差写入 trade_profit_deltas)
+    pub fn with_database(mut self, database: Database) -> Self {
+        self.trade_db = Some(Arc::new(database));
+        self
+    }
+
+    /// 设置机会/执行结果推送 (如 NATS)
+    pub fn with_event_sink(mut self, event_sink: SharedEventSink) -> Self {
+        self.event_sink = event_sink;
+        self
+    }
+
+    /// 挂载 A/B 对比会话 (候选组由 [`Self::paper_trading_replica`] 创建)
+    pub async fn set_ab_harness(&self, harness: Arc<AbHarness>) {
+        *self.ab_harness.write().await = Some(harness);
+        info!("[{}] A/B 对比已启用，候选组纸面交易", self.chain_name);
+    }
+
+    /// A/B 对比报告 (未挂载对比会话时为 None)
+    pub async fn ab_report(&self) -> Option<AbReport> {
+        let harness = self.ab_harness.read().await.clone()?;
+        Some(harness.report().await)
+    }
+
+    /// 创建 A/B 对比的候选组: 复制已加载的代币、路径和黑名单配置，与本扫描器共用池子状态，
+    /// 按 `config` 的门槛纸面交易 (不执行、不写机会日志、不推送、不落库)
+    pub async fn paper_trading_replica(&self, config: EventDrivenScannerConfig) -> Self {
+        let mut replica = Self::with_quoter(
+            paper_trading_config(config),
+            self.provider.clone(),
+            self.price_service.clone(),
+            self.quoter_address,
+        );
+        replica.multicall_address = self.multicall_address;
+        replica.chain_name = self.chain_name.clone();
+        replica.token_metadata = self.token_metadata.clone();
+        replica.pool_states = self.pool_states.clone();
+        *replica.quoter_interface.get_mut() = *self.quoter_interface.read().await;
+        *replica.token_configs.get_mut() = self.token_configs.read().await.clone();
+        *replica.triangle_configs.get_mut() = self.triangle_configs.read().await.clone();
+        *replica.pool_path_mappings.get_mut() = self.pool_path_mappings.read().await.clone();
+        *replica.blocklist.get_mut() = self.blocklist.read().await.clone();
+        replica
+    }
+
+    /// 使用多链共享的代币元数据缓存
+    pub fn with_token_metadata(mut self, token_metadata: SharedTokenMetadataCache) -> Self {
+        self.token_metadata = token_metadata;
+        self
+    }
+
     /// 添加代币配置
     pub async fn add_token_config(&self, config: TokenConfig) {
+        self.publish_token_metadata(&config).await;
+        self.block_rebasing_tokens(std::slice::from_ref(&config)).await;
         let mut configs = self.token_configs.write().await;
         info!("添加代币配置: {} ({:?}), decimals={}, optimal_input={}",
               config.symbol, config.address, config.decimals, config.optimal_input_amount);
@@ -822,6 +1986,10 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
     /// 批量添加代币配置
     pub async fn add_token_configs(&self, configs: Vec<TokenConfig>) {
+        for config in &configs {
+            self.publish_token_metadata(config).await;
+        }
+        self.block_rebasing_tokens(&configs).await;
         let mut token_configs = self.token_configs.write().await;
         for config in configs {
             info!("添加代币配置: {} ({:?})", config.symbol, config.address);
@@ -829,6 +1997,42 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
     }
 
+    /// rebasing / aToken 类代币加入黑名单: 余额会自行变化，报价和利润核算都不可靠
+    async fn block_rebasing_tokens(&self, configs: &[TokenConfig]) {
+        let flagged: Vec<Address> = configs
+            .iter()
+            .filter_map(|config| {
+                let reason = rebasing_token_reason(config.address, &config.symbol)?;
+                warn!("[{}] ⚠️ 代币 {} ({:?}) 为 {}，已排除出套利路径",
+                      self.chain_name, config.symbol, config.address, reason);
+                Some(config.address)
+            })
+            .collect();
+        if flagged.is_empty() {
+            return;
+        }
+        self.blocklist.write().await.tokens.extend(flagged);
+        self.purge_blocklisted().await;
+    }
+
+    /// 把代币写入共享元数据缓存，并向共享价格服务注册价格符号
+    async fn publish_token_metadata(&self, config: &TokenConfig) {
+        self.token_metadata.insert(self.config.chain_id, config.address, TokenMetadata {
+            symbol: config.symbol.clone(),
+            decimals: config.decimals,
+            price_symbol: config.price_symbol.clone(),
+            is_stable: config.is_stable,
+        }).await;
+
+        if config.price_symbol.is_empty() {
+            return;
+        }
+        if !config.is_stable {
+            self.price_service.register_symbol(&config.price_symbol).await;
+        }
+        self.price_service.add_token_mapping(config.address, config.price_symbol.clone()).await;
+    }
+
     /// 批量添加三角套利组合配置 (保留用于向后兼容)
     pub async fn add_triangle_configs(&self, configs: Vec<TriangleConfig>) {
         let mut triangle_configs = self.triangle_configs.write().await;
@@ -849,6 +2053,11 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
     /// trigger_pool: 触发池子地址
     /// paths: 该池子触发时应检查的所有路径
     pub async fn add_pool_path_mapping(&self, trigger_pool: Address, paths: Vec<PoolPathConfig>) {
+        if self.blocklist.read().await.pools.contains(&trigger_pool) {
+            info!("[{}] 🚫 跳过黑名单触发池子的路径映射: {:?}", self.chain_name, trigger_pool);
+            return;
+        }
+        let paths = self.retain_valid_paths(paths).await;
         let mut mappings = self.pool_path_mappings.write().await;
         let path_count = paths.len();
 
@@ -868,12 +2077,23 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
     /// 批量添加池子-路径映射配置
     pub async fn add_pool_path_mappings(&self, mappings_list: Vec<(Address, Vec<PoolPathConfig>)>) {
+        let mut validated = Vec::with_capacity(mappings_list.len());
+        for (trigger_pool, paths) in mappings_list {
+            if self.blocklist.read().await.pools.contains(&trigger_pool) {
+                continue;
+            }
+            let paths = self.retain_valid_paths(paths).await;
+            if !paths.is_empty() {
+                validated.push((trigger_pool, paths));
+            }
+        }
+
         let mut mappings = self.pool_path_mappings.write().await;
-        let pool_count = mappings_list.len();
+        let pool_count = validated.len();
         let mut total_paths = 0;
 
         info!("开始加载池子-路径映射...");
-        for (trigger_pool, paths) in mappings_list {
+        for (trigger_pool, paths) in validated {
             let path_count = paths.len();
             total_paths += path_count;
             debug!("   加载触发池子 {:?} -> {} 条路径", trigger_pool, path_count);
@@ -888,6 +2108,122 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         info!("📋 触发池子列表: {:?}", loaded_pools);
     }
 
+    /// 过滤掉池子与代币不匹配的路径 (需在池子加载之后调用)
+    async fn retain_valid_paths(&self, paths: Vec<PoolPathConfig>) -> Vec<PoolPathConfig> {
+        let blocklist = self.blocklist.read().await;
+        let states = self.pool_states.read().await;
+        paths
+            .into_iter()
+            .filter(|path| !blocklist.blocks_path(path))
+            .filter(|path| match validate_path_pools(path, &states) {
+                Ok(()) => true,
+                Err(reason) => {
+                    warn!("[{}] ⚠️ 禁用路径 {}: {}", self.chain_name, path.path_name, reason);
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// 设置交易黑名单，并立即移除已加载的黑名单池子和路径
+    pub async fn set_blocklist(&self, mut blocklist: TradingBlocklist) {
+        // 已识别的 rebasing 代币不随黑名单替换而解除
+        blocklist.tokens.extend(
+            self.token_configs
+                .read()
+                .await
+                .values()
+                .filter(|config| rebasing_token_reason(config.address, &config.symbol).is_some())
+                .map(|config| config.address),
+        );
+        if !blocklist.is_empty() {
+            info!("[{}] 🚫 交易黑名单: {} 个代币, {} 个池子",
+                  self.chain_name, blocklist.tokens.len(), blocklist.pools.len());
+        }
+        *self.blocklist.write().await = blocklist;
+        self.purge_blocklisted().await;
+    }
+
+    /// 把池子加入黑名单 (立即停止检测)
+    pub async fn block_pool(&self, pool_address: Address) {
+        self.blocklist.write().await.pools.insert(pool_address);
+        self.purge_blocklisted().await;
+    }
+
+    /// 把代币加入黑名单 (包含该代币的池子和路径立即停止检测)
+    pub async fn block_token(&self, token: Address) {
+        self.blocklist.write().await.tokens.insert(token);
+        self.purge_blocklisted().await;
+    }
+
+    /// 从池子缓存和路径映射中移除黑名单命中项
+    async fn purge_blocklisted(&self) {
+        let blocklist = self.blocklist.read().await.clone();
+
+        let mut removed_pools = Vec::new();
+        {
+            let mut states = self.pool_states.write().await;
+            states.retain(|addr, pool| {
+                let blocked = blocklist.blocks_pool(pool);
+                if blocked {
+                    removed_pools.push(*addr);
+                }
+                !blocked
+            });
+        }
+
+        let mut mappings = self.pool_path_mappings.write().await;
+        for paths in mappings.values_mut() {
+            paths.retain(|path| !blocklist.blocks_path(path));
+        }
+        mappings.retain(|trigger_pool, paths| {
+            !paths.is_empty() && !blocklist.pools.contains(trigger_pool) && !removed_pools.contains(trigger_pool)
+        });
+
+        for addr in &removed_pools {
+            info!("[{}] 🚫 黑名单池子已移出监控: {:?}", self.chain_name, addr);
+        }
+    }
+
+    /// 在匹配的路径配置中查找某项设置 (代币一致，且配置了池子时三跳池子也一致)
+    ///
+    /// 启用双向检查时，反方向的机会同样使用原路径的设置
+    async fn path_setting<T>(
+        &self,
+        tokens: [Address; 3],
+        swap_pools: &[Address],
+        setting: impl Fn(&PoolPathConfig) -> Option<T>,
+    ) -> Option<T> {
+        let mappings = self.pool_path_mappings.read().await;
+        mappings
+            .values()
+            .flatten()
+            .filter(|path| {
+                path.matches(tokens, swap_pools)
+                    || (self.config.evaluate_both_directions && path.reversed().matches(tokens, swap_pools))
+            })
+            .find_map(setting)
+    }
+
+    /// 查找路径配置中固定的闪电贷池
+    async fn pinned_flash_pool(&self, tokens: [Address; 3], swap_pools: &[Address]) -> Option<Address> {
+        self.path_setting(tokens, swap_pools, |path| path.flash_pool).await
+    }
+
+    /// 执行该机会使用的执行器配置: 路径指定了套利合约时替换默认合约
+    async fn executor_config_for(&self, opportunity: &ArbitrageOpportunity) -> ScannerExecutorConfig {
+        let mut exec_config = self.config.executor_config.clone();
+        let hops = &opportunity.path.hops;
+        if hops.len() == 3 {
+            let tokens = [hops[0].token_in, hops[0].token_out, hops[1].token_out];
+            let swap_pools: Vec<Address> = hops.iter().map(|h| h.pool_address).collect();
+            if let Some(contract) = self.path_setting(tokens, &swap_pools, |path| path.arbitrage_contract).await {
+                exec_config.arbitrage_contract = Some(contract);
+            }
+        }
+        exec_config
+    }
+
     /// 获取池子-路径映射数量
     pub async fn pool_path_mapping_count(&self) -> (usize, usize) {
         let mappings = self.pool_path_mappings.read().await;
@@ -896,6 +2232,21 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         (pool_count, path_count)
     }
 
+    /// 已监控但没有任何路径映射的池子 (触发时会回退到动态枚举)
+    pub async fn pools_without_path_mappings(&self) -> Vec<Address> {
+        let mappings = self.pool_path_mappings.read().await;
+        let mut unmapped: Vec<Address> = self
+            .pool_states
+            .read()
+            .await
+            .keys()
+            .filter(|addr| !mappings.contains_key(*addr))
+            .copied()
+            .collect();
+        unmapped.sort();
+        unmapped
+    }
+
     /// 获取指定池子触发时应检查的路径
     async fn get_paths_for_pool(&self, pool_address: Address) -> Vec<PoolPathConfig> {
         let mappings = self.pool_path_mappings.read().await;
@@ -955,13 +2306,172 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         configs.get(&address).cloned()
     }
 
-    /// 调用链上 QuoterV2 获取真实报价和 gas 估算
+    /// 费率白名单是否允许报价该池子 (StableSwap 池子没有费率档位，不受限制)
+    async fn fee_tier_allowed(&self, pool: &PoolState) -> bool {
+        let whitelist = &self.config.fee_tier_whitelist;
+        if whitelist.is_empty() || pool.is_stable_swap() {
+            return true;
+        }
+        let stable_pair = {
+            let configs = self.token_configs.read().await;
+            let is_stable = |token: &Address| configs.get(token).is_some_and(|c| c.is_stable);
+            is_stable(&pool.token0) && is_stable(&pool.token1)
+        };
+        whitelist.allows(pool.token0, pool.token1, pool.fee, stable_pair)
+    }
+
+    /// 可参与选池的池子状态快照: slot0 显示上锁 (正处于回调中) 的池子不报价也不选用
+    async fn selectable_pool_states(&self) -> Vec<PoolState> {
+        let states = self.pool_states.read().await;
+        states
+            .values()
+            .filter(|pool| {
+                if !pool.unlocked {
+                    debug!("      ⏭️ 池子 {:?} 处于回调中 (slot0.unlocked = false)，跳过", pool.address);
+                }
+                pool.unlocked
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// 路径三跳可用的池子: 只保留这三个代币对的池子，并去掉费率白名单之外的档位
+    async fn fee_whitelisted_pools(&self, all_pools: &[PoolState], tokens: [Address; 3]) -> Vec<PoolState> {
+        let [token_a, token_b, token_c] = tokens;
+        let pairs = [
+            FeeTierWhitelist::pair_key(token_a, token_b),
+            FeeTierWhitelist::pair_key(token_b, token_c),
+            FeeTierWhitelist::pair_key(token_c, token_a),
+        ];
+
+        let mut allowed = Vec::new();
+        for pool in all_pools {
+            if !pairs.contains(&FeeTierWhitelist::pair_key(pool.token0, pool.token1)) {
+                continue;
+            }
+            if self.fee_tier_allowed(pool).await {
+                allowed.push(pool.clone());
+            } else {
+                debug!("      ⏭️ 费率 {} 不在白名单内，不报价: {:?}", pool.fee, pool.address);
+            }
+        }
+        allowed
+    }
+
+    /// 按路径流动性限制输入金额 (USD)
+    ///
+    /// 每一跳取候选池子中最深的一个，整条路径能承受的量由最浅的那一跳决定
+    async fn liquidity_bounded_usd(&self, swap_usd: Decimal, hops: &[Vec<&PoolState>]) -> Decimal {
+        let fraction = self.config.max_liquidity_fraction;
+        if fraction <= Decimal::ZERO {
+            return swap_usd;
+        }
+
+        let mut min_depth: Option<Decimal> = None;
+        for candidates in hops {
+            let mut hop_depth: Option<Decimal> = None;
+            for pool in candidates {
+                let token0 = self.get_token_info(pool.token0).await;
+                let token1 = self.get_token_info(pool.token1).await;
+                let depth = pool_depth_usd(pool, [(token0.decimals, token0.price_usd), (token1.decimals, token1.price_usd)]);
+                hop_depth = hop_depth.max(depth);
+            }
+            if let Some(depth) = hop_depth {
+                min_depth = Some(min_depth.map_or(depth, |m| m.min(depth)));
+            }
+        }
+
+        let bounded = clamp_to_liquidity(swap_usd, min_depth, fraction);
+        if bounded < swap_usd {
+            info!(
+                "      🌊 输入按流动性限制: ${:.2} -> ${:.2} (最浅池子深度 ${:.2} x {})",
+                swap_usd, bounded, min_depth.unwrap_or_default(), fraction
+            );
+        }
+        bounded
+    }
+
+    /// 调用链上 Quoter 获取真实报价和 gas 估算
+    ///
+    /// `block` 为 None 时按最新区块报价，指定时按该区块的历史状态报价 (需要归档节点)。
+    /// 接口版本未确定时先按 QuoterV2 调用，失败再按 QuoterV1 调用，成功的版本会被记住
     async fn quote_exact_input(
         &self,
         token_in: Address,
         token_out: Address,
         fee: u32,
         amount_in: U256,
+        block: Option<BlockId>,
+    ) -> Result<QuoteResult> {
+        let quote_v2 = move || self.with_quote_retry(move || self.quote_exact_input_v2(token_in, token_out, fee, amount_in, block));
+        let quote_v1 = move || self.with_quote_retry(move || self.quote_exact_input_v1(token_in, token_out, fee, amount_in, block));
+
+        let interface = *self.quoter_interface.read().await;
+        match interface {
+            Some(QuoterInterface::V2) => quote_v2().await,
+            Some(QuoterInterface::V1) => quote_v1().await,
+            None => match quote_v2().await {
+                Ok(quote) => {
+                    self.set_quoter_interface(QuoterInterface::V2).await;
+                    Ok(quote)
+                }
+                Err(v2_err) => match quote_v1().await {
+                    Ok(quote) => {
+                        self.set_quoter_interface(QuoterInterface::V1).await;
+                        Ok(quote)
+                    }
+                    // 两种接口都失败时通常是池子本身无法报价，保持未确定状态
+                    Err(_) => Err(v2_err),
+                },
+            },
+        }
+    }
+
+    /// 报价遇到临时错误时按配置重试，合约 revert 等确定性错误直接返回
+    async fn with_quote_retry<F, Fut>(&self, mut quote: F) -> Result<QuoteResult>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<QuoteResult>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match quote().await {
+                Ok(result) => return Ok(result),
+                Err(e) if attempt < self.config.quote_retry_attempts && is_transient_quote_error(&format!("{:#}", e)) => {
+                    attempt += 1;
+                    debug!(
+                        "[{}] 报价临时失败，{}ms 后第 {}/{} 次重试: {}",
+                        self.chain_name, self.config.quote_retry_delay_ms, attempt, self.config.quote_retry_attempts, e
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(self.config.quote_retry_delay_ms)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// 记录本链 Quoter 的接口版本
+    async fn set_quoter_interface(&self, interface: QuoterInterface) {
+        let mut current = self.quoter_interface.write().await;
+        if current.is_none() {
+            *current = Some(interface);
+            info!("[{}] Quoter {:?} 接口检测结果: {:?}", self.chain_name, self.quoter_address, interface);
+        }
+    }
+
+    /// 本链 Quoter 的接口版本 (尚未成功报价时为 None)
+    pub async fn quoter_interface(&self) -> Option<QuoterInterface> {
+        *self.quoter_interface.read().await
+    }
+
+    /// 调用 QuoterV2.quoteExactInputSingle (tuple 参数，返回 gas 估算)
+    async fn quote_exact_input_v2(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+        amount_in: U256,
+        block: Option<BlockId>,
     ) -> Result<QuoteResult> {
         let quoter = UniswapV3QuoterV2::new(self.quoter_address, self.provider.clone());
 
@@ -975,11 +2485,13 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         );
 
         // 执行 RPC 调用并计时
+        let mut call = quoter.quote_exact_input_single(params);
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+
         let rpc_start = std::time::Instant::now();
-        let call_result = quoter
-            .quote_exact_input_single(params)
-            .call()
-            .await;
+        let call_result = call.call().await;
         let rpc_elapsed = rpc_start.elapsed();
 
         match call_result {
@@ -1008,11 +2520,227 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
     }
 
+    /// 调用 QuoterV1.quoteExactInputSingle (平铺参数，不返回 gas 估算)
+    async fn quote_exact_input_v1(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+        amount_in: U256,
+        block: Option<BlockId>,
+    ) -> Result<QuoteResult> {
+        let quoter = UniswapV3QuoterV1::new(self.quoter_address, self.provider.clone());
+
+        let mut call = quoter.quote_exact_input_single(token_in, token_out, fee, amount_in, U256::zero());
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+
+        let rpc_start = std::time::Instant::now();
+        let call_result = call.call().await;
+        self.rpc_stats.record_call(
+            RpcCallType::QuoterV1Quote,
+            rpc_start.elapsed().as_millis() as u64,
+            call_result.is_ok()
+        );
+
+        Ok(QuoteResult {
+            amount_out: call_result?,
+            gas_estimate: U256::from(QUOTER_V1_HOP_GAS_ESTIMATE),
+        })
+    }
+
+    /// 调用链上 Quoter.quoteExactInput 获取整条路径的多跳报价
+    async fn quote_exact_input_path(
+        &self,
+        tokens: &[Address],
+        fees: &[u32],
+        amount_in: U256,
+        block: Option<BlockId>,
+    ) -> Result<QuoteResult> {
+        let path = encode_v3_path(tokens, fees);
+
+        // 已检测为 QuoterV1 的链: 选择器相同但只返回 amountOut
+        if self.quoter_interface().await == Some(QuoterInterface::V1) {
+            let quoter = UniswapV3QuoterV1::new(self.quoter_address, self.provider.clone());
+            let mut call = quoter.quote_exact_input(path, amount_in);
+            if let Some(block) = block {
+                call = call.block(block);
+            }
+
+            let rpc_start = std::time::Instant::now();
+            let call_result = call.call().await;
+            self.rpc_stats.record_call(
+                RpcCallType::QuoterV2MultiHopQuote,
+                rpc_start.elapsed().as_millis() as u64,
+                call_result.is_ok()
+            );
+            return Ok(QuoteResult {
+                amount_out: call_result?,
+                gas_estimate: U256::from(QUOTER_V1_HOP_GAS_ESTIMATE * fees.len() as u64),
+            });
+        }
+
+        let quoter = UniswapV3QuoterV2::new(self.quoter_address, self.provider.clone());
+
+        let mut call = quoter.quote_exact_input(path, amount_in);
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+
+        let rpc_start = std::time::Instant::now();
+        let call_result = call.call().await;
+        let rpc_elapsed = rpc_start.elapsed();
+
+        self.rpc_stats.record_call(
+            RpcCallType::QuoterV2MultiHopQuote,
+            rpc_elapsed.as_millis() as u64,
+            call_result.is_ok()
+        );
+
+        // result: (amountOut, sqrtPriceX96AfterList, initializedTicksCrossedList, gasEstimate)
+        let result = call_result?;
+        Ok(QuoteResult {
+            amount_out: result.0,
+            gas_estimate: result.3,
+        })
+    }
+
+    /// 按指定历史区块调用 QuoterV2 单跳报价 (调试用，复现历史机会的报价)
+    pub async fn quote_at_block(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+        amount_in: U256,
+        block: u64,
+    ) -> Result<QuoteResult> {
+        self.quote_exact_input(token_in, token_out, fee, amount_in, Some(BlockId::from(block))).await
+    }
+
+    /// 按指定历史区块调用 QuoterV2 多跳报价 (调试用)
+    pub async fn quote_path_at_block(
+        &self,
+        tokens: &[Address],
+        fees: &[u32],
+        amount_in: U256,
+        block: u64,
+    ) -> Result<QuoteResult> {
+        self.quote_exact_input_path(tokens, fees, amount_in, Some(BlockId::from(block))).await
+    }
+
+    /// 单跳链上报价: V3 池子走 QuoterV2，StableSwap 池子直接调用池子的 get_dy
+    async fn quote_hop(
+        &self,
+        pool: &PoolState,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> Result<QuoteResult> {
+        // V2 池子: QuoterV2 只能报价 V3 池子，按本区块刚刷新的储备用恒定乘积公式计算 (与合约结果一致)
+        if pool.is_v2() {
+            let amount_out = pool
+                .v2_amount_out(token_in, amount_in)
+                .ok_or_else(|| anyhow::anyhow!("V2 池子 {:?} 没有储备数据", pool.address))?;
+            return Ok(QuoteResult {
+                amount_out,
+                // V2 单跳约 100k gas
+                gas_estimate: U256::from(100_000u64),
+            });
+        }
+
+        if !pool.is_stable_swap() {
+            let quote = self.quote_exact_input(token_in, token_out, pool.fee, amount_in, None).await?;
+            self.record_local_accuracy(pool, token_in, amount_in, quote.amount_out).await;
+            return Ok(quote);
+        }
+
+        // StableSwap 两币池: coins(0) = token0, coins(1) = token1
+        let (i, j) = if pool.token0 == token_in { (0u64, 1u64) } else { (1u64, 0u64) };
+        let contract = PancakeStableSwapPool::new(pool.address, self.provider.clone());
+
+        let rpc_start = std::time::Instant::now();
+        let call_result = contract
+            .get_dy(U256::from(i), U256::from(j), amount_in)
+            .call()
+            .await;
+        self.rpc_stats.record_call(
+            RpcCallType::StableSwapQuote,
+            rpc_start.elapsed().as_millis() as u64,
+            call_result.is_ok()
+        );
+
+        let amount_out = call_result?;
+        self.record_local_accuracy(pool, token_in, amount_in, amount_out).await;
+        Ok(QuoteResult {
+            amount_out,
+            // StableSwap 两币交换约 150k gas
+            gas_estimate: U256::from(150_000u64),
+        })
+    }
+
+    /// 用多跳报价交叉验证逐跳链式报价，偏差超过容忍度时采用多跳报价
+    async fn cross_check_multi_hop(
+        &self,
+        tokens: [Address; 4],
+        fees: [u32; 3],
+        input_amount: U256,
+        chained_amount_out: U256,
+        symbol: &str,
+    ) -> U256 {
+        let router_amount_out = match self.quote_exact_input_path(&tokens, &fees, input_amount, None).await {
+            Ok(result) => result.amount_out,
+            Err(e) => {
+                debug!("         ⚠️ 多跳报价失败，沿用链式报价: {}", e);
+                return chained_amount_out;
+            }
+        };
+
+        let check = reconcile_multi_hop_quote(
+            chained_amount_out,
+            router_amount_out,
+            self.config.multi_hop_tolerance_bps,
+        );
+        if check.diverged {
+            warn!(
+                "         ⚠️ 多跳报价与链式报价偏差 {}bp (容忍 {}bp): 链式={} {} | 多跳={} {} | 采用多跳报价",
+                check.divergence_bps, self.config.multi_hop_tolerance_bps,
+                chained_amount_out, symbol, router_amount_out, symbol
+            );
+        }
+        check.amount_out
+    }
+
     /// 添加池子到监控列表
-    pub async fn add_pool(&self, pool: PoolState) {
+    pub async fn add_pool(&self, mut pool: PoolState) {
+        if pool.is_stable_swap() && !self.config.stable_swap_pools_enabled {
+            warn!(
+                "[{}] ⚠️ 跳过 StableSwap 池子 {:?}: 仅支持检测 (合约无法执行、不触发 Swap 事件)，设置 STABLE_SWAP_POOLS_ENABLED=true 启用",
+                self.chain_name, pool.address
+            );
+            return;
+        }
+
+        // StableSwap 池子需要代币精度来统一余额，余额/A 值在刷新时写入
+        if pool.is_stable_swap() && pool.stable_state.is_none() {
+            let decimals0 = self.get_token_config(pool.token0).await.map(|c| c.decimals).unwrap_or(18);
+            let decimals1 = self.get_token_config(pool.token1).await.map(|c| c.decimals).unwrap_or(18);
+            pool.stable_state = Some(StableSwapPool::new(
+                [U256::zero(), U256::zero()],
+                U256::zero(),
+                U256::zero(),
+                [decimals0, decimals1],
+            ));
+        }
+
+        if self.blocklist.read().await.blocks_pool(&pool) {
+            info!("[{}] 🚫 跳过黑名单池子: {:?}", self.chain_name, pool.address);
+            return;
+        }
+
         let mut states = self.pool_states.write().await;
-        info!("添加池子到事件监控: {:?}, token0={:?}, token1={:?}",
-              pool.address, pool.token0, pool.token1);
+        info!("添加池子到事件监控: {:?} ({}), token0={:?}, token1={:?}",
+              pool.address, pool.dex_type.name(), pool.token0, pool.token1);
         states.insert(pool.address, pool);
     }
 
@@ -1032,14 +2760,55 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return Ok(());
         }
 
-        let multicall_addr: Address = MULTICALL3_ADDRESS.parse()?;
-        let multicall = Multicall3::new(multicall_addr, self.provider.clone());
+        // zkSync 等链的 Multicall3 不在通用地址，使用链配置中的地址
+        let multicall = Multicall3::new(self.multicall_address, self.provider.clone());
+
+        // 区分 StableSwap 池子 (balances + A + fee)、V2 池子 (getReserves) 和 V3 池子 (slot0 + liquidity)
+        let (stable_pools, v2_pools): (std::collections::HashSet<Address>, std::collections::HashSet<Address>) = {
+            let states = self.pool_states.read().await;
+            let matching = |matches: fn(&PoolState) -> bool| {
+                stale_pool_addrs.iter()
+                    .filter(|addr| states.get(addr).is_some_and(matches))
+                    .cloned()
+                    .collect()
+            };
+            (matching(PoolState::is_stable_swap), matching(PoolState::is_v2))
+        };
 
-        // 构建 slot0() 和 liquidity() 调用
-        // 每个池子需要 2 个调用
+        // 构建调用列表，记录每个池子的起始下标
+        // V3 池子 2 个调用，V2 池子 1 个调用，StableSwap 池子 4 个调用
         let mut calls: Vec<multicall_3::Call3> = Vec::new();
+        let mut call_offsets: Vec<usize> = Vec::with_capacity(stale_pool_addrs.len());
 
         for &pool_addr in stale_pool_addrs {
+            call_offsets.push(calls.len());
+
+            if v2_pools.contains(&pool_addr) {
+                calls.push(multicall_3::Call3 {
+                    target: pool_addr,
+                    allow_failure: true,
+                    call_data: Bytes::from(ethers::utils::id("getReserves()").to_vec()),
+                });
+                continue;
+            }
+
+            if stable_pools.contains(&pool_addr) {
+                let pool = PancakeStableSwapPool::new(pool_addr, self.provider.clone());
+                for call_data in [
+                    pool.balances(U256::zero()).calldata(),
+                    pool.balances(U256::one()).calldata(),
+                    pool.a().calldata(),
+                    pool.fee().calldata(),
+                ] {
+                    calls.push(multicall_3::Call3 {
+                        target: pool_addr,
+                        allow_failure: true,
+                        call_data: call_data.unwrap_or_default(),
+                    });
+                }
+                continue;
+            }
+
             let pool = IUniswapV3Pool::new(pool_addr, self.provider.clone());
 
             // slot0() 调用
@@ -1104,10 +2873,63 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let mut log_entries: Vec<(Address, Address, Address, u32, U256, i32, u128)> = Vec::new();
 
         for (i, pool_addr) in stale_pool_addrs.iter().enumerate() {
-            let slot0_idx = i * 2;
-            let liquidity_idx = i * 2 + 1;
-
-            if slot0_idx >= results.len() || liquidity_idx >= results.len() {
+            if v2_pools.contains(pool_addr) {
+                // getReserves: (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
+                let Some(result) = results.get(call_offsets[i]).filter(|r| r.0 && r.1.len() >= 64) else {
+                    debug!("V2 池子 {:?} 的 getReserves 调用失败", pool_addr);
+                    continue;
+                };
+                let reserve0 = U256::from_big_endian(&result.1[0..32]);
+                let reserve1 = U256::from_big_endian(&result.1[32..64]);
+                if let Some(pool) = states.get_mut(pool_addr) {
+                    pool.reserve0 = reserve0;
+                    pool.reserve1 = reserve1;
+                    pool.last_block = current_block;
+                    pool.last_updated = std::time::Instant::now();
+                    debug!("   ✅ V2 [{:?}]: reserves=({}, {})", pool_addr, reserve0, reserve1);
+                }
+                continue;
+            }
+
+            if stable_pools.contains(pool_addr) {
+                let base = call_offsets[i];
+                if base + 3 >= results.len() {
+                    continue;
+                }
+                let words: Vec<Option<U256>> = results[base..base + 4]
+                    .iter()
+                    .map(|r| (r.0 && r.1.len() >= 32).then(|| U256::from_big_endian(&r.1[0..32])))
+                    .collect();
+                let (balance0, balance1, amp, fee) = match (words[0], words[1], words[2], words[3]) {
+                    (Some(b0), Some(b1), Some(a), Some(f)) => (b0, b1, a, f),
+                    _ => {
+                        debug!("StableSwap 池子 {:?} 的 balances/A/fee 调用失败", pool_addr);
+                        continue;
+                    }
+                };
+
+                if let Some(pool) = states.get_mut(pool_addr) {
+                    if let Some(stable) = pool.stable_state.as_mut() {
+                        stable.balances = [balance0, balance1];
+                        stable.amp = amp;
+                        stable.fee = fee;
+                    }
+                    pool.reserve0 = balance0;
+                    pool.reserve1 = balance1;
+                    pool.last_block = current_block;
+                    pool.last_updated = std::time::Instant::now();
+                    debug!(
+                        "   ✅ StableSwap [{:?}]: balances=({}, {}), A={}, fee={}",
+                        pool_addr, balance0, balance1, amp, fee
+                    );
+                }
+                continue;
+            }
+
+            let slot0_idx = call_offsets[i];
+            let liquidity_idx = slot0_idx + 1;
+
+            if slot0_idx >= results.len() || liquidity_idx >= results.len() {
                 continue;
             }
 
@@ -1130,10 +2952,13 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                 // tick 在第二个 32 字节槽位，是 int24
                 let tick_bytes: [u8; 4] = slot0_data[60..64].try_into().unwrap_or([0; 4]);
                 tick = i32::from_be_bytes(tick_bytes);
+                // unlocked 在第 7 个槽位 (部分复刻池子只返回前两个字段，视为未上锁)
+                let unlocked = slot0_data.get(192..224).is_none_or(|word| word[31] != 0);
 
                 if let Some(pool) = states.get_mut(pool_addr) {
                     pool.sqrt_price_x96 = Some(sqrt_price_x96);
                     pool.tick = Some(tick);
+                    pool.unlocked = unlocked;
                     pool.last_block = current_block;
                     pool.last_updated = std::time::Instant::now();
                 }
@@ -1202,10 +3027,18 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
     /// 每个新区块批量刷新所有池子状态 (一次 Multicall)
     /// 这样本地计算时总是使用当前区块的最新数据
+    ///
+    /// 长时间没有 Swap 事件的死池按 dead_pool_refresh_interval 降频刷新
     async fn refresh_all_pools(&self) -> Result<()> {
-        let all_pool_addrs: Vec<Address> = {
-            let states = self.pool_states.read().await;
-            states.keys().cloned().collect()
+        let current_block = self.current_block.load(Ordering::Relaxed);
+        let (all_pool_addrs, dead_count) = {
+            let mut states = self.pool_states.write().await;
+            select_pools_for_refresh(
+                &mut states,
+                current_block,
+                self.config.dead_pool_after_blocks,
+                self.config.dead_pool_refresh_interval,
+            )
         };
 
         if all_pool_addrs.is_empty() {
@@ -1213,9 +3046,10 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
 
         debug!(
-            "[{}] 🔄 新区块刷新所有 {} 个池子状态",
+            "[{}] 🔄 新区块刷新 {} 个池子状态 (死池 {} 个已降频)",
             self.chain_name,
-            all_pool_addrs.len()
+            all_pool_addrs.len(),
+            dead_count
         );
 
         // 复用现有的批量刷新逻辑
@@ -1274,10 +3108,95 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             intermediate.checked_mul(q96)?.checked_div(sqrt_price_x96)?
         };
 
+        Some(amount_out)
+    }
+
+    /// 本地计算单跳输出 (按池子类型选择 V3 或 StableSwap 公式)
+    fn local_amount_out(&self, pool: &PoolState, token_in: Address, amount_in: U256) -> Option<U256> {
+        let amount_out = self.local_amount_out_raw(pool, token_in, amount_in)?;
+        if pool.is_stable_swap() || pool.is_v2() {
+            return Some(amount_out);
+        }
         // 应用滑点保护：本地计算可能不精确，打个 95% 折扣
         Some(amount_out * U256::from(95u64) / U256::from(100u64))
     }
 
+    /// 本地计算单跳输出 (不打折，用于和链上报价比较偏差)
+    fn local_amount_out_raw(&self, pool: &PoolState, token_in: Address, amount_in: U256) -> Option<U256> {
+        let zero_for_one = pool.token0 == token_in;
+        if pool.is_stable_swap() {
+            let (i, j) = if zero_for_one { (0, 1) } else { (1, 0) };
+            return pool.stable_state.as_ref()?.get_amount_out(i, j, amount_in);
+        }
+        if pool.is_v2() {
+            return pool.v2_amount_out(token_in, amount_in);
+        }
+
+        self.calculate_amount_out_local(pool.sqrt_price_x96?, pool.liquidity?, amount_in, zero_for_one, pool.fee)
+    }
+
+    /// 用链上报价更新池子的本地估算偏差统计
+    async fn record_local_accuracy(&self, pool: &PoolState, token_in: Address, amount_in: U256, chain_amount_out: U256) {
+        if !pool.has_price_data() {
+            return;
+        }
+        let Some(local_amount_out) = self.local_amount_out_raw(pool, token_in, amount_in) else {
+            return;
+        };
+        if let Some(state) = self.pool_states.write().await.get_mut(&pool.address) {
+            state.local_accuracy.record(local_amount_out, chain_amount_out);
+        }
+    }
+
+    /// 路径的跳过本地计算阈值: 取三对代币所有候选池子调整后阈值的最小值
+    fn skip_local_calc_threshold(&self, all_pools: &[PoolState], tokens: [Address; 3]) -> Decimal {
+        let base = self.config.skip_local_calc_threshold_usd;
+        let pairs = [(tokens[0], tokens[1]), (tokens[1], tokens[2]), (tokens[2], tokens[0])];
+        all_pools
+            .iter()
+            .filter(|p| pairs.iter().any(|(x, y)| {
+                (p.token0 == *x && p.token1 == *y) || (p.token0 == *y && p.token1 == *x)
+            }))
+            .map(|p| p.local_accuracy.adjusted_threshold(
+                base,
+                self.config.local_calc_error_tolerance_bps,
+                self.config.local_calc_min_samples,
+            ))
+            .fold(base, Decimal::min)
+    }
+
+    /// 执行金额对应的逐跳链上报价 (三跳路径)
+    ///
+    /// 检测时的逐跳报价仍对应执行金额时直接使用，否则 (执行数量策略调整了金额) 按执行金额重新逐跳报价；
+    /// 任一跳报价失败时返回 None (不设每跳最小输出)
+    async fn execution_hop_quotes(&self, opportunity: &ArbitrageOpportunity) -> Option<[U256; 3]> {
+        if let Ok(quotes) = <[U256; 3]>::try_from(opportunity.hop_amounts_out.as_slice()) {
+            return Some(quotes);
+        }
+
+        let hops = &opportunity.path.hops;
+        if hops.len() != 3 {
+            return None;
+        }
+        let pools: Vec<PoolState> = {
+            let states = self.pool_states.read().await;
+            hops.iter().map(|hop| states.get(&hop.pool_address).cloned()).collect::<Option<_>>()?
+        };
+        let mut quotes = [U256::zero(); 3];
+        let mut amount = opportunity.input_amount;
+        for (i, (hop, pool)) in hops.iter().zip(&pools).enumerate() {
+            amount = match self.quote_hop(pool, hop.token_in, hop.token_out, amount).await {
+                Ok(quote) => quote.amount_out,
+                Err(e) => {
+                    warn!("[{}] ⚠️ 第 {} 跳重新报价失败，不设每跳最小输出: {}", self.chain_name, i + 1, e);
+                    return None;
+                }
+            };
+            quotes[i] = amount;
+        }
+        Some(quotes)
+    }
+
     /// 本地快速估算三角套利利润
     ///
     /// 用于快速筛选，替代链上 QuoterV2 调用
@@ -1295,29 +3214,19 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         token_b: Address,
         token_c: Address,
     ) -> Option<U256> {
-        // 检查所有池子是否有 V3 价格数据
-        if !pool1.has_v3_price_data() || !pool2.has_v3_price_data() || !pool3.has_v3_price_data() {
+        // 检查所有池子是否有价格数据
+        if !pool1.has_price_data() || !pool2.has_price_data() || !pool3.has_price_data() {
             return None;
         }
 
-        let sqrt_price1 = pool1.sqrt_price_x96?;
-        let liquidity1 = pool1.liquidity?;
-        let sqrt_price2 = pool2.sqrt_price_x96?;
-        let liquidity2 = pool2.liquidity?;
-        let sqrt_price3 = pool3.sqrt_price_x96?;
-        let liquidity3 = pool3.liquidity?;
-
         // Step 1: A -> B
-        let zero_for_one1 = pool1.token0 == token_a;
-        let out1 = self.calculate_amount_out_local(sqrt_price1, liquidity1, input_amount, zero_for_one1, pool1.fee)?;
+        let out1 = self.local_amount_out(pool1, token_a, input_amount)?;
 
         // Step 2: B -> C
-        let zero_for_one2 = pool2.token0 == token_b;
-        let out2 = self.calculate_amount_out_local(sqrt_price2, liquidity2, out1, zero_for_one2, pool2.fee)?;
+        let out2 = self.local_amount_out(pool2, token_b, out1)?;
 
         // Step 3: C -> A
-        let zero_for_one3 = pool3.token0 == token_c;
-        let out3 = self.calculate_amount_out_local(sqrt_price3, liquidity3, out2, zero_for_one3, pool3.fee)?;
+        let out3 = self.local_amount_out(pool3, token_c, out2)?;
 
         // 检查是否盈利
         if out3 > input_amount {
@@ -1336,15 +3245,15 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         self.current_block.store(event.block_number, Ordering::Relaxed);
 
         // 1. 检查是否是我们监控的池子
-        let (pool_updated, pool_info, token0, token1) = {
+        let (pool_updated, pool_info, token0, token1, lacks_price_data) = {
             let mut states = self.pool_states.write().await;
             if let Some(pool) = states.get_mut(&event.pool_address) {
                 pool.update_from_swap(&event);
                 let info = format!("{:?}", pool.dex_type);
-                (true, Some(info), pool.token0, pool.token1)
+                (true, Some(info), pool.token0, pool.token1, pool.swap_lacks_price_data(&event))
             } else {
                 // 不是我们监控的池子
-                (false, None, Address::zero(), Address::zero())
+                (false, None, Address::zero(), Address::zero(), false)
             }
         };
 
@@ -1353,6 +3262,14 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return None;
         }
 
+        // 事件缺少价格数据: 立即单独刷新这个池子，不等下一个区块的批量刷新
+        if lacks_price_data {
+            debug!("Swap 事件缺少 sqrtPrice/liquidity，立即刷新池子 {:?}", event.pool_address);
+            if let Err(e) = self.refresh_stale_pools(&[event.pool_address]).await {
+                warn!("刷新池子 {:?} 失败: {}", event.pool_address, e);
+            }
+        }
+
         // 获取代币信息 (从价格服务)
         let token0_info = self.get_token_info(token0).await;
         let token1_info = self.get_token_info(token1).await;
@@ -1375,21 +3292,26 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let usd_out = self.calculate_usd_value(amount_out, &token_out);
         let swap_usd = if usd_in > Decimal::ZERO { usd_in } else { usd_out };
 
-        // 输出详细日志 (包含代币价格)
-        info!("┌─────────────────────────────────────────────────────────────────────────────┐");
-        info!("│ 🔍 触发套利检测 - Swap 事件详情");
-        info!("├─────────────────────────────────────────────────────────────────────────────┤");
-        info!("│ 📊 交易对: {} -> {}", token_in.symbol, token_out.symbol);
-        info!("│ 💰 输入: {} {} @ ${:.4}/个 = ${:.2}",
-            amount_in_fmt, token_in.symbol, token_in.price_usd, usd_in);
-        info!("│ 💰 输出: {} {} @ ${:.4}/个 = ${:.2}",
-            amount_out_fmt, token_out.symbol, token_out.price_usd, usd_out);
-        info!("│ 🏊 池子: {:?} ({})", event.pool_address, pool_info.as_deref().unwrap_or("?"));
-        info!("│ 📦 区块: #{}", event.block_number);
-        info!("└─────────────────────────────────────────────────────────────────────────────┘");
+        // 输出详细日志 (包含代币价格)，纸面交易的同一事件线上扫描器已经输出过
+        if !self.config.paper_trading {
+            info!("┌─────────────────────────────────────────────────────────────────────────────┐");
+            info!("│ 🔍 触发套利检测 - Swap 事件详情");
+            info!("├─────────────────────────────────────────────────────────────────────────────┤");
+            info!("│ 📊 交易对: {} -> {}", token_in.symbol, token_out.symbol);
+            info!("│ 💰 输入: {} {} @ ${:.4}/个 = ${:.2}",
+                amount_in_fmt, token_in.symbol, token_in.price_usd, usd_in);
+            info!("│ 💰 输出: {} {} @ ${:.4}/个 = ${:.2}",
+                amount_out_fmt, token_out.symbol, token_out.price_usd, usd_out);
+            info!("│ 🏊 池子: {:?} ({})", event.pool_address, pool_info.as_deref().unwrap_or("?"));
+            info!("│ 📦 区块: #{}", event.block_number);
+            info!("└─────────────────────────────────────────────────────────────────────────────┘");
+        }
 
         // 过滤小额交易：资金 < 配置阈值 不进行套利评估
-        let min_swap_value = self.config.min_swap_value_usd;
+        let min_swap_value = match self.config.native_denomination {
+            Some(ref native) => native.min_swap_value,
+            None => self.config.min_swap_value_usd,
+        };
         if swap_usd < min_swap_value {
             let elapsed = start_time.elapsed();
             info!("⏭️ 跳过小额交易: ${:.2} < ${} | 耗时: {:.2}ms", swap_usd, min_swap_value, elapsed.as_secs_f64() * 1000.0);
@@ -1397,14 +3319,33 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
 
         // 2. 检测涉及该池子的套利机会（传递真实交易量用于本地估算）
+        let detection_span = info_span!(
+            "arbitrage_detection",
+            chain_id = self.config.chain_id,
+            chain = %self.chain_name,
+            pool = ?event.pool_address,
+            block = event.block_number,
+            swap_usd = %swap_usd,
+        );
         let detect_start = std::time::Instant::now();
-        let result = self.detect_arbitrage_for_pool(event.pool_address, swap_usd).await;
+        let result = self
+            .detect_arbitrage_for_pool(event.pool_address, swap_usd)
+            .instrument(detection_span.clone())
+            .await;
         let detect_elapsed = detect_start.elapsed();
 
         // 计算总耗时
         let total_elapsed = start_time.elapsed();
 
         match &result {
+            // 纸面交易: 机会只交给 A/B 对比报告
+            Some(opp) if self.config.paper_trading => {
+                debug!(
+                    "📝 [纸面交易] 发现套利机会 净利润=${:.2} | 检测耗时: {:.2}ms",
+                    opp.net_profit_usd,
+                    detect_elapsed.as_secs_f64() * 1000.0
+                );
+            }
             Some(opp) => {
                 info!(
                     target: "arbitrage_opportunity",
@@ -1416,30 +3357,19 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
                 // 写入专用套利机会日志
                 self.log_opportunity(opp, &event, &token_in, &token_out, swap_usd).await;
-
-                // 如果启用了自动执行，立即执行套利
-                if self.config.executor_config.auto_execute {
-                    let exec_start = std::time::Instant::now();
-                    match self.execute_arbitrage(opp.clone()).await {
-                        Ok(exec_result) => {
-                            let exec_elapsed = exec_start.elapsed();
-                            info!(
-                                target: "arbitrage_execution",
-                                "🚀 套利执行完成: status={:?}, tx_hash={:?} | 执行耗时: {:.2}ms",
-                                exec_result.status,
-                                exec_result.tx_hash,
-                                exec_elapsed.as_secs_f64() * 1000.0
-                            );
-                        }
-                        Err(e) => {
-                            let exec_elapsed = exec_start.elapsed();
-                            error!(
-                                target: "arbitrage_execution",
-                                "❌ 套利执行失败: {} | 执行耗时: {:.2}ms",
-                                e,
-                                exec_elapsed.as_secs_f64() * 1000.0
-                            );
-                        }
+                self.event_sink.publish_opportunity(self.config.chain_id, opp).await;
+                self.execution_stats.write().await.opportunities_found += 1;
+                self.opportunity_watchdog.record_opportunity();
+                self.remember_opportunity(opp.clone()).await;
+
+                // 如果启用了自动执行，放入执行队列 (同批机会评分排序后执行)
+                if self.config.executor_config.auto_execute && !self.config.executor_config.detection_only {
+                    if self.is_execution_paused() {
+                        info!("[{}] ⏸️ 自动执行已暂停，仅记录机会", self.chain_name);
+                    } else if self.warmup_complete().await {
+                        self.enqueue_for_execution(opp.clone(), detection_span).await;
+                    } else {
+                        info!("[{}] ⏳ 启动预热中，仅记录机会不自动执行", self.chain_name);
                     }
                 }
             }
@@ -1455,18 +3385,396 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         result
     }
 
+    /// 利润换算用的原生代币、参考稳定币价格 (价格服务中缺失时为 None)
+    async fn profit_currencies(&self) -> ProfitCurrencies {
+        let native_symbol = self.config.native_token_symbol.clone();
+        let stablecoin_symbol = self.config.reference_stablecoin.clone();
+        ProfitCurrencies {
+            native_price_usd: self.price_service.get_price_by_symbol(&native_symbol).await,
+            stablecoin_price_usd: self.price_service.get_price_by_symbol(&stablecoin_symbol).await,
+            native_symbol,
+            stablecoin_symbol,
+        }
+    }
+
+    /// 启动预热是否结束 (池子已成功刷新且价格服务已有价格)
+    async fn warmup_complete(&self) -> bool {
+        if self.warmup.is_complete() {
+            return true;
+        }
+        let prices_ready = !self.price_service.get_all_prices().await.is_empty();
+        self.warmup.check(prices_ready)
+    }
+
+    /// 将机会加入执行队列
+    ///
+    /// 第一个到达的任务成为分发者: 等待一个收集窗口，然后对队列中的机会评分排序，
+    /// 按评分从高到低执行互不共用池子的子集；执行期间新到达的机会进入下一轮。
+    /// 分发者占用着事件处理许可，最多处理 `MAX_DISPATCH_ROUNDS` 轮就交出分发权；
+    /// 检测区块已落后于当前区块的机会直接放弃
+    async fn enqueue_for_execution(&self, opportunity: ArbitrageOpportunity, detection_span: tracing::Span) {
+        self.opportunity_spans.write().await.insert(opportunity.id.clone(), detection_span);
+
+        // 高价值机会不等收集窗口和前一批执行，普通许可被占满时用保留许可
+        // (与同批执行并行发送时 nonce 由共享的分配器串行分配)
+        if self.is_priority_opportunity(&opportunity) {
+            let Some(opportunity) = self.select_priority_against_pending(opportunity).await else {
+                return;
+            };
+            info!(
+                "[{}] 🚀 高价值机会 {} (净利润 ${:.2}) 走优先通道",
+                self.chain_name, opportunity.id, opportunity.net_profit_usd
+            );
+            self.dispatch_execution(opportunity, ExecutionLane::Priority).await;
+            return;
+        }
+
+        self.pending_executions.write().await.push(opportunity);
+
+        if self.execution_dispatching.swap(true, Ordering::AcqRel) {
+            // 已有分发者在处理
+            return;
+        }
+
+        if self.config.execution_batch_window_ms > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(
+                self.config.execution_batch_window_ms
+            )).await;
+        }
+
+        for round in 0.. {
+            if round == MAX_DISPATCH_ROUNDS {
+                // 剩余机会留在队列里，由下一个到达的任务成为分发者时处理 (届时过期的会被放弃)
+                self.execution_dispatching.store(false, Ordering::Release);
+                break;
+            }
+            let batch = std::mem::take(&mut *self.pending_executions.write().await);
+            let batch = self.discard_stale(batch).await;
+            if batch.is_empty() {
+                self.execution_dispatching.store(false, Ordering::Release);
+                // 释放标记前可能有新机会入队，重新抢占分发权
+                if self.pending_executions.read().await.is_empty()
+                    || self.execution_dispatching.swap(true, Ordering::AcqRel)
+                {
+                    break;
+                }
+                continue;
+            }
+
+            let batch_size = batch.len();
+            let (selected, conflicted) = self.scorer.select_non_conflicting(batch);
+            if batch_size > 1 {
+                info!(
+                    target: "arbitrage_execution",
+                    "📋 同批 {} 个机会评分排序: 执行 {} 个, 因池子冲突放弃 {} 个",
+                    batch_size, selected.len(), conflicted.len()
+                );
+            }
+            self.discard_conflicted(&conflicted).await;
+
+            // 同批选中的机会互不共用池子，可以并发执行；同时在途的执行数受本链执行许可限制
+            futures_util::future::join_all(
+                selected.into_iter().map(|opp| self.dispatch_execution(opp, ExecutionLane::Batch)),
+            )
+            .await;
+        }
+    }
+
+    /// 优先通道的机会同样参与池子冲突选择: 与排队中的同批机会一起评分，
+    /// 共用池子时评分低的一方被放弃；返回 None 表示优先机会本身被放弃
+    async fn select_priority_against_pending(&self, opportunity: ArbitrageOpportunity) -> Option<ArbitrageOpportunity> {
+        let priority_id = opportunity.id.clone();
+        let (priority, conflicted) = {
+            let mut pending = self.pending_executions.write().await;
+            let mut candidates = std::mem::take(&mut *pending);
+            candidates.push(opportunity);
+            let (mut selected, conflicted) = self.scorer.select_non_conflicting(candidates);
+            let priority = selected
+                .iter()
+                .position(|opp| opp.id == priority_id)
+                .map(|index| selected.remove(index));
+            *pending = selected;
+            (priority, conflicted)
+        };
+        self.discard_conflicted(&conflicted).await;
+        priority
+    }
+
+    /// 放弃检测区块落后于当前区块的机会 (池子状态已变化，报价不再可信)，返回仍然有效的机会
+    async fn discard_stale(&self, batch: Vec<ArbitrageOpportunity>) -> Vec<ArbitrageOpportunity> {
+        let current_block = self.current_block.load(Ordering::Relaxed);
+        let (fresh, stale): (Vec<_>, Vec<_>) = batch
+            .into_iter()
+            .partition(|opp| opp.block_number >= current_block);
+        if stale.is_empty() {
+            return fresh;
+        }
+        for opp in &stale {
+            debug!(
+                "   ⏭️ 放弃过期机会: {} | 检测区块={} 当前区块={}",
+                opp.id, opp.block_number, current_block
+            );
+            self.opportunity_spans.write().await.remove(&opp.id);
+        }
+        let mut stats = self.execution_stats.write().await;
+        stats.stale_skipped += stale.len() as u64;
+        fresh
+    }
+
+    /// 放弃因池子冲突落选的机会
+    async fn discard_conflicted(&self, conflicted: &[ArbitrageOpportunity]) {
+        if conflicted.is_empty() {
+            return;
+        }
+        for opp in conflicted {
+            debug!("   ⏭️ 放弃冲突机会: {} | 净利润=${:.2}", opp.id, opp.net_profit_usd);
+            self.opportunity_spans.write().await.remove(&opp.id);
+        }
+        let mut stats = self.execution_stats.write().await;
+        stats.conflict_skipped += conflicted.len() as u64;
+    }
+
+    /// 记录最近检测到的机会，超出容量时丢弃最旧的
+    async fn remember_opportunity(&self, opportunity: ArbitrageOpportunity) {
+        let mut recent = self.recent_opportunities.write().await;
+        if recent.len() == RECENT_OPPORTUNITY_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(opportunity);
+    }
+
+    /// 手动执行最近检测到的机会
+    pub async fn execute_recent_opportunity(&self, id: &str) -> Result<models::ArbitrageResult, ManualExecutionError> {
+        let opportunity = self
+            .recent_opportunities
+            .read()
+            .await
+            .iter()
+            .find(|opp| opp.id == id)
+            .cloned()
+            .ok_or_else(|| ManualExecutionError::NotFound(id.to_string()))?;
+        self.requote_and_execute(opportunity).await
+    }
+
+    /// 手动执行一个机会
+    ///
+    /// 检测时的报价可能已经过期: 只用原来的三个池子、按原输入金额重新报价，仍然满足利润门槛才执行；
+    /// 自动执行暂停、未开启或仅检测模式下一律拒绝
+    async fn requote_and_execute(&self, opportunity: ArbitrageOpportunity) -> Result<models::ArbitrageResult, ManualExecutionError> {
+        let id = opportunity.id.as_str();
+        let hops = &opportunity.path.hops;
+        let pool_addresses: Vec<Address> = hops.iter().map(|hop| hop.pool_address).collect();
+        let path_pools: Vec<PoolState> = {
+            let states = self.pool_states.read().await;
+            pool_addresses.iter().filter_map(|addr| states.get(addr).cloned()).collect()
+        };
+        // 路径上的池子不归该扫描器监控 (如其他 DEX 组合的扫描器)
+        if opportunity.path.chain_id != self.config.chain_id || path_pools.len() != pool_addresses.len() {
+            return Err(ManualExecutionError::NotFound(id.to_string()));
+        }
+        if hops.len() != 3 {
+            return Err(ManualExecutionError::Failed(format!("不支持的路径长度: {}", hops.len())));
+        }
+
+        let exec_config = &self.config.executor_config;
+        if self.is_execution_paused() {
+            return Err(ManualExecutionError::ExecutionDisabled(format!("{} 的自动执行已暂停", self.chain_name)));
+        }
+        if !exec_config.auto_execute || exec_config.detection_only {
+            return Err(ManualExecutionError::ExecutionDisabled(format!("{} 未开启自动执行", self.chain_name)));
+        }
+
+        info!("[{}] 🖐️ 手动执行机会 {}: 按原输入重新报价中", self.chain_name, id);
+        let fresh = self
+            .requote_on_pools(&opportunity, &path_pools, "manual")
+            .await
+            .ok_or_else(|| ManualExecutionError::NoLongerProfitable(id.to_string()))?;
+        info!(
+            "[{}] 🖐️ 机会 {} 重新报价: 净利润 ${:.2} (检测时 ${:.2})",
+            self.chain_name, id, fresh.net_profit_usd, opportunity.net_profit_usd
+        );
+
+        self.execute_arbitrage(fresh)
+            .await
+            .map_err(|e| ManualExecutionError::Failed(e.to_string()))
+    }
+
+    /// 只用机会原来的三个池子、按原输入金额重新报价 (`path_pools` 与机会的三跳一一对应)
+    async fn requote_on_pools(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        path_pools: &[PoolState],
+        origin: &str,
+    ) -> Option<ArbitrageOpportunity> {
+        let hops = &opportunity.path.hops;
+        let path_config = PoolPathConfig {
+            path_name: format!("{}:{}", origin, opportunity.id),
+            triangle_name: String::new(),
+            token_a: hops[0].token_in,
+            token_b: hops[0].token_out,
+            token_c: hops[1].token_out,
+            pools: Some([path_pools[0].address, path_pools[1].address, path_pools[2].address]),
+            flash_pool: None,
+            fixed_input_amount: Some(opportunity.input_amount),
+            arbitrage_contract: None,
+            priority: 0,
+        };
+
+        // 固定输入时 check_static_path 按输入金额的 USD 价值选择本地/链上报价
+        let swap_usd = self.calculate_usd_value(opportunity.input_amount, &self.get_token_info(hops[0].token_in).await);
+        self.check_static_path(&path_config, path_pools, swap_usd).await
+    }
+
+    /// 推迟一个涉及冷却中池子的机会，同一组池子只保留最新的一个
+    async fn defer_execution(&self, opportunity: ArbitrageOpportunity) {
+        let pools = self.get_path_pools(&opportunity);
+        let mut deferred = self.deferred_executions.write().await;
+        deferred.retain(|opp| self.get_path_pools(opp) != pools);
+        deferred.push(opportunity);
+    }
+
+    /// 重新检查推迟的机会: 池子冷却结束后按当前池子状态重新报价，仍然有利润才放入执行队列
+    pub async fn retry_deferred_executions(&self) {
+        let ready: Vec<ArbitrageOpportunity> = {
+            let mut deferred = self.deferred_executions.write().await;
+            let (ready, waiting) = std::mem::take(&mut *deferred)
+                .into_iter()
+                .partition(|opp| self.pool_cooldown.first_suspended(&self.get_path_pools(opp)).is_none());
+            *deferred = waiting;
+            ready
+        };
+        if ready.is_empty() || self.is_execution_paused() {
+            return;
+        }
+
+        for opportunity in ready {
+            let path_pools: Vec<PoolState> = {
+                let states = self.pool_states.read().await;
+                opportunity.path.hops.iter().filter_map(|hop| states.get(&hop.pool_address).cloned()).collect()
+            };
+            if path_pools.len() != 3 {
+                continue;
+            }
+            match self.requote_on_pools(&opportunity, &path_pools, "deferred").await {
+                Some(fresh) => {
+                    info!(
+                        "[{}] ⏳ 推迟的机会 {} 冷却结束，重新报价净利润 ${:.2}，放入执行队列",
+                        self.chain_name, opportunity.id, fresh.net_profit_usd
+                    );
+                    self.enqueue_for_execution(fresh, tracing::Span::current()).await;
+                }
+                None => debug!("[{}] 推迟的机会 {} 冷却结束后已无利润，放弃", self.chain_name, opportunity.id),
+            }
+        }
+    }
+
+    /// 净利润达到优先通道门槛的机会
+    fn is_priority_opportunity(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        self.config
+            .executor_config
+            .priority_min_profit_usd
+            .and_then(Decimal::from_f64_retain)
+            .is_some_and(|threshold| opportunity.net_profit_usd >= threshold)
+    }
+
+    /// 取得一个执行许可: 同批机会用普通许可，优先通道的机会普通许可占满时可以用保留许可
+    async fn acquire_execution_permit(&self, lane: ExecutionLane) -> SemaphorePermit<'_> {
+        match lane {
+            ExecutionLane::Batch => self.execution_permits.acquire().await.expect("执行许可不会被关闭"),
+            ExecutionLane::Priority => {
+                acquire_priority_permit(&self.execution_permits, &self.priority_execution_permits).await
+            }
+        }
+    }
+
+    /// 执行一个已出队的机会并记录结果
+    ///
+    /// 先取得 Bundle 名额再取得执行许可: 执行许可只有 1 个时，排队的 Bundle 也按价值排序，
+    /// 而不是堵在执行许可上先到先得
+    async fn dispatch_execution(&self, opp: ArbitrageOpportunity, lane: ExecutionLane) {
+        let detection_span = self.opportunity_spans.write().await.remove(&opp.id);
+        // 排队期间策略被停止: 剩余机会全部放弃
+        if self.is_execution_paused() {
+            info!("[{}] ⏸️ 自动执行已暂停，放弃排队中的机会 {}", self.chain_name, opp.id);
+            return;
+        }
+        let exec_config = self.executor_config_for(&opp).await;
+        let Some(bundle_slot) = self.acquire_bundle_slot(&exec_config, &opp).await else {
+            return;
+        };
+        let _permit = self.acquire_execution_permit(lane).await;
+        let exec_span = info_span!(
+            parent: detection_span.as_ref().and_then(|s| s.id()),
+            "execute_opportunity",
+            opportunity_id = %opp.id,
+            chain_id = self.config.chain_id,
+            net_profit_usd = %opp.net_profit_usd,
+        );
+        let exec_start = std::time::Instant::now();
+        match self.execute_arbitrage_in_slot(opp, &exec_config, bundle_slot).instrument(exec_span).await {
+            Ok(exec_result) => {
+                let exec_elapsed = exec_start.elapsed();
+                self.event_sink.publish_execution(self.config.chain_id, &exec_result).await;
+                info!(
+                    target: "arbitrage_execution",
+                    "🚀 套利执行完成: status={:?}, tx_hash={:?} | 执行耗时: {:.2}ms",
+                    exec_result.status,
+                    exec_result.tx_hash,
+                    exec_elapsed.as_secs_f64() * 1000.0
+                );
+            }
+            Err(e) => {
+                let exec_elapsed = exec_start.elapsed();
+                error!(
+                    target: "arbitrage_execution",
+                    "❌ 套利执行失败: {} | 执行耗时: {:.2}ms",
+                    e,
+                    exec_elapsed.as_secs_f64() * 1000.0
+                );
+            }
+        }
+    }
+
     /// 生成套利路径的唯一签名 (用于去重)
+    ///
+    /// 静态路径和旧版动态枚举可能以不同方向或起点找到同一个三角 (A→B→C→A 与 A→C→B→A)，
+    /// 所以池子按地址排序后再拼接，同一组池子在同一区块只执行一次
     fn generate_path_signature(&self, opportunity: &ArbitrageOpportunity) -> String {
-        // 签名格式: chain_id:start_token:pool1:pool2:pool3:block_number
-        let mut sig = format!("{}:{:?}", self.config.chain_id, opportunity.path.start_token);
-        for hop in &opportunity.path.hops {
-            sig.push_str(&format!(":{:?}", hop.pool_address));
+        // 签名格式: chain_id:pool_a:pool_b:pool_c:block_number (池子地址升序)
+        let mut pools: Vec<Address> = opportunity.path.hops.iter().map(|hop| hop.pool_address).collect();
+        pools.sort();
+        let mut sig = self.config.chain_id.to_string();
+        for pool in pools {
+            sig.push_str(&format!(":{:?}", pool));
         }
         // 加入区块号，同一区块内的相同路径视为重复
         sig.push_str(&format!(":{}", opportunity.block_number));
         sig
     }
 
+    /// 路径冷却的标识 (按跳顺序的三个池子)
+    fn path_cooldown_key(opportunity: &ArbitrageOpportunity) -> Option<PathKey> {
+        match opportunity.path.hops.as_slice() {
+            [first, second, third] => Some([first.pool_address, second.pool_address, third.pool_address]),
+            _ => None,
+        }
+    }
+
+    /// 记录路径执行结果: 失败累计到阈值后暂停，成功 (含干运行) 清零
+    fn record_path_outcome(&self, opportunity: &ArbitrageOpportunity, success: bool) {
+        let Some(key) = Self::path_cooldown_key(opportunity) else {
+            return;
+        };
+        if success {
+            self.path_cooldown.record_success(&key);
+        } else if self.path_cooldown.record_failure(&key) {
+            warn!(
+                "[{}] 🧊 路径 {:?} 连续执行失败，{} 秒内不再报价和执行",
+                self.chain_name, key, self.config.path_failure_cooldown_secs
+            );
+        }
+    }
+
     /// 获取套利路径涉及的所有池子地址
     fn get_path_pools(&self, opportunity: &ArbitrageOpportunity) -> Vec<Address> {
         opportunity.path.hops.iter().map(|hop| hop.pool_address).collect()
@@ -1483,13 +3791,70 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
     }
 
     /// 执行套利交易 (带去重检查)
-    async fn execute_arbitrage(&self, mut opportunity: ArbitrageOpportunity) -> Result<models::ArbitrageResult> {
-        let exec_config = &self.config.executor_config;
+    async fn execute_arbitrage(&self, opportunity: ArbitrageOpportunity) -> Result<models::ArbitrageResult> {
+        let exec_config = self.executor_config_for(&opportunity).await;
+        let Some(bundle_slot) = self.acquire_bundle_slot(&exec_config, &opportunity).await else {
+            return Err(anyhow::anyhow!("排队期间已出新区块，机会过期"));
+        };
+        self.execute_arbitrage_in_slot(opportunity, &exec_config, bundle_slot).await
+    }
+
+    /// 取得 Flashbots Bundle 在途名额 (不提交 Bundle 时为 Some(None))
+    ///
+    /// 名额占满时排队，排队期间出了新区块则返回 None
+    async fn acquire_bundle_slot(
+        &self,
+        exec_config: &ScannerExecutorConfig,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Option<Option<BundleSlot<'_>>> {
+        if !exec_config.submits_bundles() {
+            return Some(None);
+        }
+        if self.bundle_slots.is_full() {
+            info!(
+                "[{}] ⏳ 在途 Bundle 已达上限 {}，机会 {} (净利润 ${:.2}) 排队等待",
+                self.chain_name, exec_config.max_in_flight_bundles, opportunity.id, opportunity.net_profit_usd
+            );
+        }
+        match self.bundle_slots.acquire(opportunity.ranking_net_profit(), opportunity.block_number).await {
+            Some(slot) => Some(Some(slot)),
+            None => {
+                info!(
+                    "[{}] ⌛ 机会 {} (区块 #{}) 排队期间已出新区块，放弃提交 Bundle",
+                    self.chain_name, opportunity.id, opportunity.block_number
+                );
+                None
+            }
+        }
+    }
+
+    /// 在已取得的 Bundle 名额内执行套利 (名额在交易提交完成后归还)
+    async fn execute_arbitrage_in_slot(
+        &self,
+        mut opportunity: ArbitrageOpportunity,
+        exec_config: &ScannerExecutorConfig,
+        bundle_slot: Option<BundleSlot<'_>>,
+    ) -> Result<models::ArbitrageResult> {
+        // 仅检测模式兜底: 不占用池子锁、不统计执行次数，也不发出任何 RPC
+        if self.config.executor_config.detection_only {
+            return Err(anyhow::anyhow!("仅检测模式，不执行"));
+        }
+
 
         // 生成路径签名
         let path_signature = self.generate_path_signature(&opportunity);
         let path_pools = self.get_path_pools(&opportunity);
 
+        // 黑名单兜底检查 (机会可能在加入黑名单之前就已入队)
+        if let Some(blocked) = self.blocklist.read().await.blocked_hop_address(&opportunity.path) {
+            return Err(anyhow::anyhow!("路径包含黑名单地址 {:?}", blocked));
+        }
+
+        // 排队期间路径可能已进入冷却
+        if Self::path_cooldown_key(&opportunity).is_some_and(|key| self.path_cooldown.is_suspended(&key)) {
+            return Err(anyhow::anyhow!("路径连续失败，冷却中"));
+        }
+
         // ========== 去重检查 ==========
 
         // 1. 检查是否在时间窗口内已执行过相同路径
@@ -1542,7 +3907,27 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             }
         }
 
-        // 3. 标记池子为正在执行
+        // 3. 检查相关池子是否刚执行过 (本地状态可能还反映着我们自己的成交)，冷却结束后重新报价
+        if let Some(pool) = self.pool_cooldown.first_suspended(&path_pools) {
+            self.defer_execution(opportunity.clone()).await;
+            let mut stats = self.execution_stats.write().await;
+            stats.pool_cooldown_deferred += 1;
+            info!(
+                "[{}] ⏳ 推迟套利: 池子 {:?} 刚执行过，冷却结束后重新报价, 累计推迟={}",
+                self.chain_name, pool, stats.pool_cooldown_deferred
+            );
+            return Ok(models::ArbitrageResult {
+                opportunity: opportunity.clone(),
+                tx_hash: None,
+                status: models::ArbitrageStatus::Pending,
+                actual_profit: None,
+                actual_gas_used: None,
+                error_message: Some(format!("池子 {:?} 执行后冷却中，已推迟", pool)),
+                executed_at: chrono::Utc::now(),
+            });
+        }
+
+        // 4. 标记池子为正在执行
         {
             let mut executing = self.executing_pools.write().await;
             for pool in &path_pools {
@@ -1579,6 +3964,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                 }
             }
 
+            // 干运行视为复查成功，清除该路径的失败计数
+            self.record_path_outcome(&opportunity, true);
+
             let mut stats = self.execution_stats.write().await;
             stats.dry_run_executions += 1;
 
@@ -1593,20 +3981,19 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             });
         }
 
-        // 获取钱包和私钥
-        let (wallet, private_key_str) = {
+        // 获取钱包
+        let wallet = {
             let w = self.wallet.read().await;
-            let pk = self.private_key.read().await;
-            match (&*w, &*pk) {
-                (Some(wallet), Some(pk)) => (wallet.clone(), pk.clone()),
-                _ => {
+            match &*w {
+                Some(wallet) => wallet.clone(),
+                None => {
                     // 清理池子锁
                     let mut executing = self.executing_pools.write().await;
                     for pool in &path_pools {
                         executing.remove(pool);
                     }
-                    error!("[{}] ❌ 无法执行: 钱包或私钥未配置", self.chain_name);
-                    return Err(anyhow::anyhow!("钱包或私钥未配置"));
+                    error!("[{}] ❌ 无法执行: 钱包未配置", self.chain_name);
+                    return Err(anyhow::anyhow!("钱包未配置"));
                 }
             }
         };
@@ -1622,6 +4009,16 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return Err(anyhow::anyhow!("套利合约地址未配置"));
         }
 
+        // FlashArbitrage 合约只实现了 V3 swap 回调，含 StableSwap / V2 池子的路径只做检测不执行
+        if let Some(hop) = opportunity.path.hops.iter().find(|h| !h.dex_type.is_v3_style()) {
+            let mut executing = self.executing_pools.write().await;
+            for pool in &path_pools {
+                executing.remove(pool);
+            }
+            warn!("[{}] ⚠️ 无法执行: 路径包含 {} 池子，套利合约暂不支持", self.chain_name, hop.dex_type.name());
+            return Err(anyhow::anyhow!("套利合约不支持 {} 池子", hop.dex_type.name()));
+        }
+
         // 应用执行数量策略
         let start_token_config = {
             let configs = self.token_configs.read().await;
@@ -1632,7 +4029,8 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             let token_price = self.price_service.get_price_by_symbol(&token_config.price_symbol).await
                 .unwrap_or(Decimal::ZERO);
             if token_price > Decimal::ZERO {
-                let adjusted_amount = exec_config.amount_strategy.calculate_amount(
+                let amount_strategy = exec_config.amount_strategy_for(opportunity.path.start_token);
+                let adjusted_amount = amount_strategy.calculate_amount(
                     opportunity.input_amount,
                     token_config.decimals,
                     token_price,
@@ -1640,13 +4038,16 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
                 if adjusted_amount != opportunity.input_amount {
                     info!(
-                        "[{}] 📊 应用执行数量策略: {} -> {} (策略: {:?})",
+                        "[{}] 📊 应用执行数量策略: {} -> {} ({} 策略: {:?})",
                         self.chain_name,
                         opportunity.input_amount,
                         adjusted_amount,
-                        exec_config.amount_strategy
+                        token_config.symbol,
+                        amount_strategy
                     );
                     opportunity.input_amount = adjusted_amount;
+                    // 检测时的逐跳报价对应原来的金额
+                    opportunity.hop_amounts_out.clear();
                 }
             }
         }
@@ -1673,27 +4074,23 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let hops = &opportunity.path.hops;
         let swap_pools: Vec<Address> = hops.iter().map(|h| h.pool_address).collect();
 
-        // 计算 min_profit (将 USD 转换为 tokenA 的 wei 单位)
-        let start_token = hops[0].token_in;
-        let token_info = self.get_token_info(start_token).await;
-        let min_profit_usd = self.get_dynamic_min_profit().await;
-        let min_profit_wei = if token_info.price_usd > Decimal::ZERO {
-            let token_amount = min_profit_usd / token_info.price_usd;
-            let wei_amount = token_amount * Decimal::from(10u64.pow(token_info.decimals as u32));
-            U256::from_dec_str(&wei_amount.floor().to_string()).unwrap_or(U256::zero())
-        } else {
-            U256::zero() // 价格未知时不设限制
-        };
-        info!(
-            "[{}] 💰 最小利润阈值: ${} USD = {} {} (wei)",
-            self.chain_name, min_profit_usd, min_profit_wei, token_info.symbol
-        );
+        let min_profit_wei = self
+            .contract_min_profit([hops[0].token_in, hops[0].token_out, hops[1].token_out])
+            .await;
 
-        // 使用闪电贷池选择器自动选择最优池
+        // 路径配置了固定闪电贷池时直接使用，否则由选择器自动选择最优池
+        let pinned_flash_pool = self
+            .pinned_flash_pool([hops[0].token_in, hops[0].token_out, hops[1].token_out], &swap_pools)
+            .await;
         let params_builder = ArbitrageParamsBuilder::new(self.provider.clone(), self.config.chain_id)
-            .with_min_profit(min_profit_wei);
-
-        let arb_params = match params_builder
+            .with_min_profit(min_profit_wei)
+            .with_flash_pool(pinned_flash_pool)
+            .with_preferred_flash_pools(self.config.preferred_flash_pools.clone())
+            .with_flash_fee_table(self.config.flash_fee_table)
+            .with_profit_token(self.config.executor_config.profit_token, self.config.executor_config.profit_convert_fee)
+            .with_flash_token(self.config.executor_config.flash_token, self.config.executor_config.flash_convert_fee);
+
+        let mut arb_params = match params_builder
             .build_manual(
                 hops[0].token_in,   // token_a
                 hops[0].token_out,  // token_b
@@ -1727,6 +4124,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             }
         };
 
+        // 执行金额的逐跳链上报价，执行器据此扣除滑点得到每跳最小输出
+        arb_params.hop_quotes = self.execution_hop_quotes(&opportunity).await;
+
         info!(
             "[{}] 🎯 闪电贷池自动选择: {:?}, 费率: {} ({:.4}%)",
             self.chain_name,
@@ -1736,49 +4136,49 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         );
 
         // 构建执行器配置
-        // 根据配置决定发送模式:
-        // - Both: 同时使用 Flashbots 和公开 mempool
-        // - Flashbots: 仅使用 Flashbots
-        // - Normal: 仅使用公开 mempool
-        let send_mode = if exec_config.use_flashbots && exec_config.use_public_mempool {
-            SendMode::Both
-        } else if exec_config.use_flashbots {
-            SendMode::Flashbots
-        } else {
-            SendMode::Normal
-        };
+        let send_mode = exec_config.send_mode();
 
         let executor_config = ExecutorConfig {
             contract_address: exec_config.arbitrage_contract.unwrap(),
             chain_id: self.config.chain_id,
-            gas_strategy: GasStrategy {
+            execution_gas: GasStrategy {
                 gas_price_multiplier: 1.2,
                 max_gas_price_gwei: exec_config.max_gas_price_gwei,
                 gas_limit_multiplier: 1.3,
-                use_eip1559: true,
+                use_eip1559: exec_config.use_eip1559,
                 priority_fee_gwei: exec_config.priority_fee_gwei,
-                fixed_gas_limit: None, // 动态估算
+                fixed_gas_limit: exec_config.fixed_gas_limit, // None 时动态估算
+                // USD 上限依赖原生代币价格，原生代币计价模式下不使用
+                max_gas_cost_usd: exec_config.max_gas_cost_usd.filter(|_| self.config.native_denomination.is_none()),
+            },
+            // 模拟不加价: 只需要确认能执行，估算值留给发送阶段按上面的倍数加价
+            simulation_gas: GasStrategy {
+                gas_limit_multiplier: exec_config.simulation_gas_limit_multiplier,
+                fixed_gas_limit: exec_config.fixed_gas_limit,
+                ..GasStrategy::simulation()
             },
             confirmation_timeout_secs: 120,
+            send_timeout_secs: exec_config.send_timeout_secs,
             confirmations: 1,
             simulate_before_execute: exec_config.simulate_before_execute,
-            private_key: Some(private_key_str.clone()),
+            abort_if_block_advanced: exec_config.abort_if_block_advanced,
+            max_slippage: self.config.max_slippage,
+            private_key: None,
+            wallet: Some(wallet.clone()),
             send_mode,
-            flashbots_config: FlashbotsConfig {
-                enabled: exec_config.use_flashbots,
-                relay_url: exec_config.flashbots_rpc_url.clone().unwrap_or_default(),
-                chain_id: self.config.chain_id,
-                ..Default::default()
-            },
+            flashbots_config: exec_config.flashbots_config(self.config.chain_id),
+            state_overrides: exec_config.simulation_state_overrides.clone(),
         };
 
         // 创建带签名的 provider (SignerMiddleware)
         let signer = SignerMiddleware::new(self.provider.clone(), wallet);
         let signer = Arc::new(signer);
 
-        // 创建执行器 (带 price_service 以正确显示代币价格)
+        // 创建执行器 (带 price_service 以正确显示代币价格，nonce 从扫描器共享的分配器分配)
         let executor = match RealExecutor::new(executor_config, signer) {
-            Ok(e) => e.with_price_service(self.price_service.clone()),
+            Ok(e) => e
+                .with_price_service(self.price_service.clone())
+                .with_nonce_allocator(self.nonce_allocator.clone()),
             Err(e) => {
                 let mut executing = self.executing_pools.write().await;
                 for pool in &path_pools {
@@ -1945,22 +4345,26 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
 
         // ========== 并行获取执行前余额 (不阻塞套利执行) ==========
-        let provider_for_before = self.provider.clone();
-        let price_service_for_before = self.price_service.clone();
-        let token_configs_for_before = self.token_configs.read().await.clone();
+        let balance_fetcher = BalanceFetcher {
+            provider: self.provider.clone(),
+            price_service: self.price_service.clone(),
+            token_configs: self.token_configs.read().await.clone(),
+            multicall_address: self.multicall_address,
+            contract_address,
+            rpc_stats: Some(self.rpc_stats.clone()),
+        };
+        let fetcher_for_before = balance_fetcher.clone();
         let token_addresses_clone = token_addresses.clone();
         let chain_name_clone = self.chain_name.clone();
-        let rpc_stats_for_before = Some(self.rpc_stats.clone());
+        let permits_for_before = self.balance_fetch_permits.clone();
 
-        // 启动异步任务获取执行前余额
+        // 启动异步任务获取执行前余额 (受余额查询并发上限约束)
         let balances_before_handle = tokio::spawn(async move {
-            let balances = Self::get_balances_async(
-                provider_for_before,
-                price_service_for_before,
-                &token_configs_for_before,
-                contract_address,
+            let balances = Self::get_balances_limited(
+                permits_for_before,
+                &fetcher_for_before,
                 &token_addresses_clone,
-                rpc_stats_for_before,
+                None,
             ).await;
             info!(
                 target: "arbitrage_execution",
@@ -1973,6 +4377,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // 直接执行套利，不等待余额获取完成
         let exec_result = executor.execute(arb_params.clone()).await;
+        drop(bundle_slot);
 
         // ========== 执行完成后清理 ==========
 
@@ -1996,9 +4401,25 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         // 定期清理过期记录 (简单策略：每次执行后检查)
         self.cleanup_executed_records().await;
 
+        let executed_successfully = matches!(&exec_result, Ok(ExecutionOutcome::Executed(res)) if res.success);
+        // 仅模拟: 中继模拟通过与干运行一样视为复查成功，但没有上链，不启动池子冷却；
+        // RPC、钱包、Gas 上限之类与路径无关的错误不计入路径失败
+        let path_outcome = match &exec_result {
+            Ok(ExecutionOutcome::Executed(res)) => Some(res.success),
+            Ok(ExecutionOutcome::SimulatedOnly(sim)) => Some(sim.succeeded()),
+            Err(e) if e.is_path_failure() => Some(false),
+            Err(_) => None,
+        };
+        if let Some(success) = path_outcome {
+            self.record_path_outcome(&opportunity, success);
+        }
+        if executed_successfully {
+            self.pool_cooldown.suspend(&path_pools);
+        }
+
         // 将执行结果转换为 ArbitrageResult
         let result: Result<models::ArbitrageResult> = match exec_result {
-            Ok(res) => {
+            Ok(ExecutionOutcome::Executed(res)) => {
                 let status = if res.success {
                     models::ArbitrageStatus::Confirmed
                 } else {
@@ -2014,6 +4435,25 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                     executed_at: chrono::Utc::now(),
                 })
             }
+            Ok(ExecutionOutcome::SimulatedOnly(sim)) => {
+                let outcome = sim.revert.clone().unwrap_or_else(|| "成功".to_string());
+                info!(
+                    target: "arbitrage_execution",
+                    "[{}] 🧪 Flashbots 仅模拟 (未发送): 目标区块 {}, gas_used={}, coinbase_diff={}, 结果: {}",
+                    self.chain_name, sim.target_block, sim.gas_used, sim.coinbase_diff, outcome
+                );
+                self.execution_stats.write().await.dry_run_executions += 1;
+
+                Ok(models::ArbitrageResult {
+                    opportunity: opportunity.clone(),
+                    tx_hash: None,
+                    status: models::ArbitrageStatus::Pending,
+                    actual_profit: None,
+                    actual_gas_used: Some(U256::from(sim.gas_used)),
+                    error_message: Some(format!("Flashbots 仅模拟: {}", outcome)),
+                    executed_at: chrono::Utc::now(),
+                })
+            }
             Err(e) => {
                 // 使用 RevertDecoder 解析详细错误信息
                 let error_str = format!("{:?}", e);
@@ -2044,35 +4484,54 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                         models::ArbitrageStatus::Confirmed => {
                             stats.successful_executions += 1;
                             stats.total_profit_usd += opportunity.net_profit_usd;
+                            stats.total_profit_native += opportunity.net_profit_native.unwrap_or_default();
+                            stats.profit_tracker.record(opportunity.net_profit_usd, true);
                         }
-                        models::ArbitrageStatus::Failed | models::ArbitrageStatus::Reverted => {
+                        models::ArbitrageStatus::Reverted => {
                             stats.failed_executions += 1;
+                            // 上链 revert 白付 gas
+                            stats.profit_tracker.record(-opportunity.gas_cost_usd, false);
+                        }
+                        models::ArbitrageStatus::Failed => {
+                            stats.failed_executions += 1;
+                            stats.profit_tracker.record(Decimal::ZERO, false);
                         }
                         _ => {}
                     }
                 }
 
                 // 异步获取执行后余额并发送邮件通知 (不阻塞主流程)
-                let provider = self.provider.clone();
-                let price_service = self.price_service.clone();
-                let token_configs = self.token_configs.read().await.clone();
                 let chain_name = self.chain_name.clone();
                 let opportunity_clone = opportunity.clone();
                 let exec_result_clone = exec_result.clone();
-                let rpc_stats_for_after = Some(self.rpc_stats.clone());
+                let profit_currencies = self.profit_currencies().await;
+                let execution_stats = self.execution_stats.clone();
+                let trade_db = self.trade_db.clone();
+                let chain_id = self.config.chain_id;
+                let profit_deviation_alert_usd = self.config.profit_deviation_alert_usd;
+                let snapshot_confirmations = exec_config.balance_snapshot_confirmations;
+                let permits_for_after = self.balance_fetch_permits.clone();
 
                 tokio::spawn(async move {
                     // 等待执行前余额获取完成
                     let balances_before = balances_before_handle.await.unwrap_or_default();
 
-                    // 获取执行后余额
-                    let balances_after = Self::get_balances_async(
-                        provider,
-                        price_service,
-                        &token_configs,
-                        contract_address,
+                    // 获取执行后余额 (配置了确认数时等交易所在区块确认后，固定在该区块读取)
+                    let snapshot_block = match exec_result_clone.tx_hash {
+                        Some(tx_hash) => Self::settled_snapshot_block(
+                            balance_fetcher.provider.as_ref(),
+                            tx_hash,
+                            snapshot_confirmations,
+                            BALANCE_SNAPSHOT_POLL_INTERVAL,
+                            BALANCE_SNAPSHOT_TIMEOUT,
+                        ).await,
+                        None => None,
+                    };
+                    let balances_after = Self::get_balances_limited(
+                        permits_for_after,
+                        &balance_fetcher,
                         &token_addresses,
-                        rpc_stats_for_after,
+                        snapshot_block.map(BlockId::from),
                     ).await;
 
                     info!(
@@ -2085,13 +4544,52 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                     // 计算盈亏
                     let total_before: Decimal = balances_before.iter().map(|b| b.usd_value).sum();
                     let total_after: Decimal = balances_after.iter().map(|b| b.usd_value).sum();
-                    let pnl = total_after - total_before;
+                    let pnl = realized_profit_from_balances(&balances_before, &balances_after);
                     info!(
                         target: "arbitrage_execution",
-                        "[{}] 💰 套利盈亏: 执行前=${:.4}, 执行后=${:.4}, 盈亏=${:.4}",
-                        chain_name, total_before, total_after, pnl
+                        "[{}] 💰 套利盈亏: 执行前=${:.4}, 执行后=${:.4}, 盈亏={}",
+                        chain_name, total_before, total_after, profit_currencies.format(pnl)
                     );
 
+                    // 对比预估利润 (任一侧余额获取失败时差值不可信，跳过)
+                    let balances_complete = !balances_before.is_empty() && !balances_after.is_empty();
+                    if exec_result_clone.status == models::ArbitrageStatus::Confirmed && balances_complete {
+                        let delta = ProfitDelta::new(opportunity_clone.expected_profit_usd, pnl);
+                        let avg_delta = {
+                            let mut stats = execution_stats.write().await;
+                            stats.record_profit_delta(&delta);
+                            stats.avg_profit_delta_usd()
+                        };
+                        info!(
+                            target: "arbitrage_execution",
+                            "[{}] 🎯 利润预估偏差: 预估=${:.4}, 实际=${:.4}, 偏差=${:.4} ({}), 平均偏差=${:.4}",
+                            chain_name, delta.expected_profit_usd, delta.realized_profit_usd, delta.delta_usd(),
+                            delta.error_pct().map(|p| format!("{:.2}%", p)).unwrap_or_else(|| "-".to_string()),
+                            avg_delta
+                        );
+
+                        if let Some(alert) = profit_deviation_alert(
+                            &chain_name,
+                            &opportunity_clone,
+                            exec_result_clone.tx_hash,
+                            &delta,
+                            profit_deviation_alert_usd,
+                        ) {
+                            error!(target: "arbitrage_execution", "[{}] 🚨 {}\n{}", chain_name, alert.message, alert.details);
+                            if let Some(notifier) = get_email_notifier() {
+                                if let Err(e) = notifier.send_alert_notification(&alert.title, &alert.message, &alert.details).await {
+                                    warn!("[{}] 发送利润偏差告警失败: {}", chain_name, e);
+                                }
+                            }
+                        }
+
+                        if let (Some(db), Some(tx_hash)) = (&trade_db, exec_result_clone.tx_hash) {
+                            if let Err(e) = db.insert_profit_delta(chain_id, &format!("{:?}", tx_hash), &delta).await {
+                                warn!("[{}] 写入利润偏差失败: {}", chain_name, e);
+                            }
+                        }
+                    }
+
                     // 发送邮件通知 (包含前后余额对比)
                     Self::send_email_with_comparison(
                         &chain_name,
@@ -2099,6 +4597,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                         &exec_result_clone,
                         balances_before,
                         balances_after,
+                        profit_currencies,
                     ).await;
                 });
 
@@ -2185,11 +4684,39 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
     /// 处理新区块事件
     pub async fn handle_new_block(&self, event: NewBlockEvent) {
-        // 更新当前区块号
+        // 重复或乱序推送的旧区块不再触发全量刷新
+        match self.block_sequence.observe(event.block_number, event.block_hash) {
+            BlockEventKind::Stale => {
+                debug!("[{}] 忽略重复/过期的区块事件 #{} ({:?})", self.chain_name, event.block_number, event.block_hash);
+                return;
+            }
+            BlockEventKind::Reorg => {
+                warn!("[{}] ⚠️ 区块 #{} 哈希变化，疑似链重组，重新刷新池子", self.chain_name, event.block_number);
+            }
+            BlockEventKind::New => {}
+        }
+
+        // 更新当前区块号，排队中的上一区块 Bundle 已过期
         self.current_block.store(event.block_number, Ordering::Relaxed);
+        self.bundle_slots.drop_stale(event.block_number);
+
+        if let Some(quiet) = self.opportunity_watchdog.on_block() {
+            let alert = no_opportunity_alert(&self.chain_name, quiet, event.block_number);
+            warn!("[{}] 🚨 {}\n{}", self.chain_name, alert.message, alert.details);
+            if let Some(notifier) = get_email_notifier() {
+                let chain_name = self.chain_name.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = notifier.send_alert_notification(&alert.title, &alert.message, &alert.details).await {
+                        warn!("[{}] 发送无机会告警失败: {}", chain_name, e);
+                    }
+                });
+            }
+        }
 
         // 更新 gas price 缓存 (从区块头获取，避免额外 RPC)
-        if let Some(base_fee) = event.base_fee {
+        // 配置了 gas oracle 时以 oracle 为准，不用 base fee 覆盖
+        let oracle_source = matches!(self.config.gas_price_source, GasPriceSource::Oracle(_));
+        if let Some(base_fee) = event.base_fee.filter(|_| !oracle_source) {
             let mut cache = self.gas_price_cache.write().await;
             *cache = Some(GasPriceCache {
                 price_wei: base_fee,
@@ -2204,8 +4731,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // ========== 关键：每个新区块刷新所有池子状态 ==========
         // 这样本地计算时总是使用最新数据，无延迟
-        if let Err(e) = self.refresh_all_pools().await {
-            warn!("[{}] 刷新池子状态失败: {}", self.chain_name, e);
+        match self.refresh_all_pools().await {
+            Ok(()) => self.warmup.record_refresh(),
+            Err(e) => warn!("[{}] 刷新池子状态失败: {}", self.chain_name, e),
         }
 
         // 检查并切换 RPC 统计的分钟计数
@@ -2249,20 +4777,24 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         // 获取该池子触发时应检查的路径
         let paths = self.get_paths_for_pool(pool_address).await;
 
-        // 如果没有配置路径映射，回退到旧的动态枚举方式
+        // 如果没有配置路径映射，回退到旧的动态枚举方式 (本链关闭回退时忽略)
         if paths.is_empty() {
+            if !self.config.legacy_enumeration_enabled {
+                debug!("池子 {:?} 没有配置路径映射，动态枚举已关闭，忽略", pool_address);
+                return None;
+            }
             debug!("池子 {:?} 没有配置路径映射，使用动态枚举", pool_address);
             return self.detect_arbitrage_for_pool_legacy(pool_address, swap_usd).await;
         }
 
-        // 获取所有池子状态
-        let all_pools: Vec<PoolState> = {
-            let states = self.pool_states.read().await;
-            states.values().cloned().collect()
-        };
+        // 获取所有可用的池子状态 (跳过处于回调中的池子)
+        let all_pools = self.selectable_pool_states().await;
 
         // 获取代币符号用于日志
-        let trigger_pool = all_pools.iter().find(|p| p.address == pool_address)?;
+        let Some(trigger_pool) = all_pools.iter().find(|p| p.address == pool_address) else {
+            debug!("触发池 {:?} 不可用 (未知或处于回调中)，跳过检测", pool_address);
+            return None;
+        };
         let token0_info = self.get_token_info(trigger_pool.token0).await;
         let token1_info = self.get_token_info(trigger_pool.token1).await;
 
@@ -2271,48 +4803,82 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             pool_address, token0_info.symbol, token1_info.symbol, paths.len()
         );
 
-        let mut best_opportunity: Option<ArbitrageOpportunity> = None;
-        let mut paths_checked = 0u32;
-        let mut valid_paths = 0u32;
+        // 按优先级并发检查预定义的路径，超出时间预算后不再发起剩余路径
+        let budget = (self.config.path_eval_budget_ms > 0)
+            .then(|| std::time::Duration::from_millis(self.config.path_eval_budget_ms));
+        let outcome = evaluate_paths_within_budget(&paths, budget, self.config.path_eval_concurrency, |path_config| {
+            self.evaluate_static_path(path_config, &paths, &all_pools, swap_usd)
+        })
+        .await;
 
-        // 按优先级遍历预定义的路径
-        for path_config in &paths {
-            paths_checked += 1;
+        if outcome.skipped > 0 {
+            warn!(
+                "[{}] ⏱️ 路径检测超出时间预算 {}ms: 已检查 {} 条, 跳过 {} 条, 返回当前最佳结果",
+                self.chain_name, self.config.path_eval_budget_ms, outcome.checked, outcome.skipped
+            );
+        }
 
-            let token_a_info = self.get_token_info(path_config.token_a).await;
-            let token_b_info = self.get_token_info(path_config.token_b).await;
-            let token_c_info = self.get_token_info(path_config.token_c).await;
+        info!(
+            "📊 套利检测完成 | 检查路径数={} | 有效路径数={} | 跳过路径数={} | 最佳机会={:?}",
+            outcome.checked, outcome.found, outcome.skipped,
+            outcome.best.as_ref().map(|o| format!("${:.2}", o.net_profit_usd))
+        );
 
-            info!(
-                "   🔄 检查路径: {} | {} -> {} -> {} -> {}",
-                path_config.path_name,
-                token_a_info.symbol, token_b_info.symbol, token_c_info.symbol, token_a_info.symbol
-            );
+        outcome.best
+    }
 
-            // 检查该路径的套利机会（传递真实交易量）
-            if let Some(opp) = self.check_static_path(
-                path_config,
-                &all_pools,
-                swap_usd,
-            ).await {
-                valid_paths += 1;
-                info!(
-                    "   💰 路径 {} 发现机会: 净利润=${:.4}",
-                    path_config.path_name, opp.net_profit_usd
-                );
-                if best_opportunity.as_ref().map_or(true, |b| opp.net_profit_usd > b.net_profit_usd) {
-                    best_opportunity = Some(opp);
-                }
-            }
+    /// 检查单条预定义路径 (带日志)，启用双向检查时两个方向取净利润高的
+    ///
+    /// 反方向本身也是 `configured` 中的一条路径时由它自己检查，这里不重复报价
+    async fn evaluate_static_path(
+        &self,
+        path_config: &PoolPathConfig,
+        configured: &[PoolPathConfig],
+        all_pools: &[PoolState],
+        swap_usd: Decimal,
+    ) -> Option<ArbitrageOpportunity> {
+        let forward = self.evaluate_static_direction(path_config, all_pools, swap_usd).await;
+        if !self.config.evaluate_both_directions {
+            return forward;
+        }
+        let reversed = path_config.reversed();
+        if configured.iter().any(|path| path.same_route(&reversed)) {
+            return forward;
         }
 
+        let reverse = self.evaluate_static_direction(&reversed, all_pools, swap_usd).await;
+        match (forward, reverse) {
+            (Some(forward), Some(reverse)) if reverse.ranking_net_profit() > forward.ranking_net_profit() => Some(reverse),
+            (Some(forward), _) => Some(forward),
+            (None, reverse) => reverse,
+        }
+    }
+
+    /// 检查预定义路径的一个方向
+    async fn evaluate_static_direction(
+        &self,
+        path_config: &PoolPathConfig,
+        all_pools: &[PoolState],
+        swap_usd: Decimal,
+    ) -> Option<ArbitrageOpportunity> {
+        let token_a_info = self.get_token_info(path_config.token_a).await;
+        let token_b_info = self.get_token_info(path_config.token_b).await;
+        let token_c_info = self.get_token_info(path_config.token_c).await;
+
         info!(
-            "📊 套利检测完成 | 检查路径数={} | 有效路径数={} | 最佳机会={:?}",
-            paths_checked, valid_paths,
-            best_opportunity.as_ref().map(|o| format!("${:.2}", o.net_profit_usd))
+            "   🔄 检查路径: {} | {} -> {} -> {} -> {}",
+            path_config.path_name,
+            token_a_info.symbol, token_b_info.symbol, token_c_info.symbol, token_a_info.symbol
         );
 
-        best_opportunity
+        // 检查该路径的套利机会（传递真实交易量）
+        let quote_span = info_span!("quote_path", path = %path_config.path_name, swap_usd = %swap_usd);
+        let opp = self.check_static_path(path_config, all_pools, swap_usd).instrument(quote_span).await?;
+        info!(
+            "   💰 路径 {} 发现机会: 净利润=${:.4}",
+            path_config.path_name, opp.net_profit_usd
+        );
+        Some(opp)
     }
 
     /// 检查静态定义的套利路径 (直接链上验证，按实际输出选择最优池子)
@@ -2326,35 +4892,68 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let token_b = path_config.token_b;
         let token_c = path_config.token_c;
 
+        // 加载时已校验，这里兜底
+        if let Err(reason) = validate_distinct_tokens(token_a, token_b, token_c) {
+            warn!("[{}] ⚠️ 跳过路径 {}: {}", self.chain_name, path_config.path_name, reason);
+            return None;
+        }
+
+        // 固定了池子的路径冷却中时直接跳过，不发起报价
+        if path_config.pools.is_some_and(|pools| self.path_cooldown.is_suspended(&pools)) {
+            debug!("[{}] 🧊 路径 {} 冷却中，跳过", self.chain_name, path_config.path_name);
+            return None;
+        }
+
         // 获取代币信息
         let token_a_info = self.get_token_info(token_a).await;
         let token_b_info = self.get_token_info(token_b).await;
         let token_c_info = self.get_token_info(token_c).await;
 
-        // 将 swap USD 转换为代币数量作为输入
-        let input_amount = self.usd_to_token_amount(swap_usd, &token_a_info);
+        // 只在白名单费率档位中选池子
+        let path_pools = self.fee_whitelisted_pools(all_pools, [token_a, token_b, token_c]).await;
+
+        // 将 swap USD 转换为代币数量作为输入 (不超过路径流动性能承受的量)
+        let hops: Vec<Vec<&PoolState>> = [(token_a, token_b), (token_b, token_c), (token_c, token_a)]
+            .iter()
+            .map(|&(x, y)| {
+                path_pools
+                    .iter()
+                    .filter(|p| (p.token0 == x && p.token1 == y) || (p.token0 == y && p.token1 == x))
+                    .collect()
+            })
+            .collect();
+        let (input_amount, swap_usd) = match path_config.fixed_input_amount {
+            // 固定输入: 其 USD 价值只用于选择本地/链上计算模式
+            Some(amount) => (amount, self.calculate_usd_value(amount, &token_a_info)),
+            None => {
+                let swap_usd = self.liquidity_bounded_usd(swap_usd, &hops).await;
+                (self.usd_to_token_amount(swap_usd, &token_a_info), swap_usd)
+            }
+        };
         if input_amount.is_zero() {
             return None;
         }
 
-        // 检查是否需要跳过本地计算（大资金跨 Tick 时本地估算不准）
-        let skip_local_calc = swap_usd >= self.config.skip_local_calc_threshold_usd;
+        // 检查是否需要跳过本地计算（大资金跨 Tick 时本地估算不准；本地估算偏差大的池子阈值更低）
+        let skip_threshold = self.skip_local_calc_threshold(all_pools, [token_a, token_b, token_c]);
+        let skip_local_calc = swap_usd >= skip_threshold;
 
         let (pool1, pool2, pool3) = if skip_local_calc {
             // ========== 大资金模式：直接用 RPC 选择池子 ==========
             info!(
-                "      💰 大资金模式 (${:.0} >= ${}): 跳过本地计算，直接链上选择池子",
-                swap_usd, self.config.skip_local_calc_threshold_usd
+                "      💰 大资金模式 (${:.0} >= ${}{}): 跳过本地计算，直接链上选择池子",
+                swap_usd, skip_threshold,
+                if skip_threshold < self.config.skip_local_calc_threshold_usd { ", 因本地估算偏差已下调" } else { "" }
             );
 
             // 使用链上 RPC 报价选择最优池子
-            let p1 = self.find_best_pool_by_output_rpc(all_pools, token_a, token_b, input_amount).await?;
-            let quote1 = self.quote_exact_input(token_a, token_b, p1.fee, input_amount).await.ok()?;
+            let p1 = self.find_best_pool_by_output_rpc(&path_pools, token_a, token_b, input_amount).await?;
+            let quote1 = self.quote_hop(&p1, token_a, token_b, input_amount).await.ok()?;
 
-            let p2 = self.find_best_pool_by_output_rpc(all_pools, token_b, token_c, quote1.amount_out).await?;
-            let quote2 = self.quote_exact_input(token_b, token_c, p2.fee, quote1.amount_out).await.ok()?;
+            let p2 = self.find_best_pool_by_output_rpc(&path_pools, token_b, token_c, quote1.amount_out).await?;
+            let quote2 = self.quote_hop(&p2, token_b, token_c, quote1.amount_out).await.ok()?;
 
-            let p3 = self.find_best_pool_by_output_rpc(all_pools, token_c, token_a, quote2.amount_out).await?;
+            let p3 = self.find_best_pool_by_output_rpc(&path_pools, token_c, token_a, quote2.amount_out).await?;
 
             info!(
                 "      池子选择(RPC): {} ({}bp) -> {} ({}bp) -> {} ({}bp)",
@@ -2368,29 +4967,19 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             // ========== 普通模式：使用本地计算选择池子和估算输出 (零 RPC) ==========
 
             // 查找 A->B 的最优池子 (本地计算)
-            let p1 = self.find_best_pool_by_output_local(all_pools, token_a, token_b, input_amount)?;
+            let p1 = self.find_best_pool_by_output_local(&path_pools, token_a, token_b, input_amount)?;
 
             // 本地计算第一跳的输出
-            let hop1_output = {
-                let sqrt_price = p1.sqrt_price_x96?;
-                let liquidity = p1.liquidity?;
-                let zero_for_one = p1.token0 == token_a;
-                self.calculate_amount_out_local(sqrt_price, liquidity, input_amount, zero_for_one, p1.fee)?
-            };
+            let hop1_output = self.local_amount_out(&p1, token_a, input_amount)?;
 
             // 查找 B->C 的最优池子 (本地计算)
-            let p2 = self.find_best_pool_by_output_local(all_pools, token_b, token_c, hop1_output)?;
+            let p2 = self.find_best_pool_by_output_local(&path_pools, token_b, token_c, hop1_output)?;
 
             // 本地计算第二跳的输出
-            let hop2_output = {
-                let sqrt_price = p2.sqrt_price_x96?;
-                let liquidity = p2.liquidity?;
-                let zero_for_one = p2.token0 == token_b;
-                self.calculate_amount_out_local(sqrt_price, liquidity, hop1_output, zero_for_one, p2.fee)?
-            };
+            let hop2_output = self.local_amount_out(&p2, token_b, hop1_output)?;
 
             // 查找 C->A 的最优池子 (本地计算)
-            let p3 = self.find_best_pool_by_output_local(all_pools, token_c, token_a, hop2_output)?;
+            let p3 = self.find_best_pool_by_output_local(&path_pools, token_c, token_a, hop2_output)?;
 
             info!(
                 "      池子选择(本地): {} ({}bp) -> {} ({}bp) -> {} ({}bp)",
@@ -2402,6 +4991,11 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             (p1, p2, p3)
         };
 
+        if self.path_cooldown.is_suspended(&[pool1.address, pool2.address, pool3.address]) {
+            debug!("[{}] 🧊 路径 {} 选出的池子组合冷却中，跳过", self.chain_name, path_config.path_name);
+            return None;
+        }
+
         // 检查总手续费
         let total_fee_rate = pool1.fee + pool2.fee + pool3.fee;
         if total_fee_rate > 10000 {
@@ -2416,8 +5010,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // 使用链上 QuoterV2 精确验证（确保执行前的最终确认）
         info!("      🔗 调用链上 Quoter 验证...");
+        let sizing = path_config.fixed_input_amount.map_or(InputSizing::SwapUsd(swap_usd), InputSizing::Fixed);
         let (optimal_input, sim_result) = match self.find_optimal_input(
-            token_a, token_b, token_c, &pool1, &pool2, &pool3, swap_usd
+            token_a, token_b, token_c, &pool1, &pool2, &pool3, sizing
         ).await {
             Some(result) => result,
             None => {
@@ -2434,8 +5029,20 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             sim_result.net_profit_usd
         );
 
-        // 使用动态利润门槛
-        let dynamic_min_profit = self.get_dynamic_min_profit().await;
+        // 报价出的 "利润" 可能来自被操纵或状态过期的池子，逐跳对照现货价
+        if let Err(reason) = self
+            .sanity_check_prices([token_a, token_b, token_c], optimal_input, sim_result.hop_outputs)
+            .await
+        {
+            warn!(
+                "[{}] ⚠️ 路径 {} 价格校验未通过，放弃: {}",
+                self.chain_name, path_config.path_name, reason
+            );
+            return None;
+        }
+
+        // 使用动态利润门槛 (代币对 / 三角覆盖优先)
+        let dynamic_min_profit = self.min_profit_for_path([token_a, token_b, token_c]).await;
         if sim_result.net_profit_usd < dynamic_min_profit {
             info!(
                 "      ⚠️ 利润不足动态阈值: ${:.4} < ${:.2}",
@@ -2484,12 +5091,15 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             Decimal::ZERO
         };
 
+        let break_even_gas_price = break_even_gas_price_gwei(
+            profit_usd, sim_result.total_gas_used, self.native_unit_price().await
+        );
         info!(
-            "      ✅ 发现套利机会: {} | 净利润=${:.2} | 利润率={:.4}%",
-            path_config.path_name, sim_result.net_profit_usd, profit_percentage
+            "      ✅ 发现套利机会: {} | 净利润=${:.2} | 利润率={:.4}% | 盈亏平衡 gas={:.4} Gwei",
+            path_config.path_name, sim_result.net_profit_usd, profit_percentage, break_even_gas_price
         );
 
-        Some(ArbitrageOpportunity {
+        let mut opportunity = ArbitrageOpportunity {
             id: uuid::Uuid::new_v4().to_string(),
             path,
             input_amount: optimal_input,
@@ -2502,7 +5112,14 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             profit_percentage,
             timestamp: chrono::Utc::now(),
             block_number: self.current_block.load(Ordering::Relaxed),
-        })
+            max_price_impact: sim_result.max_price_impact,
+            break_even_gas_price_gwei: break_even_gas_price,
+            hop_amounts_out: sim_result.hop_outputs.to_vec(),
+            net_profit_native: None,
+            gas_cost_native: None,
+        };
+        self.denominate_opportunity(&mut opportunity).await;
+        Some(opportunity)
     }
 
     /// 查找代币对的最优池子 (手续费最低的) - 已废弃，保留备用
@@ -2550,31 +5167,13 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let mut best_output = U256::zero();
 
         for pool in matching_pools {
-            // 检查池子是否有 V3 价格数据
-            if !pool.has_v3_price_data() {
+            // 检查池子是否有价格数据 (V3 或 StableSwap)
+            if !pool.has_price_data() {
                 continue;
             }
 
-            let sqrt_price = match pool.sqrt_price_x96 {
-                Some(p) => p,
-                None => continue,
-            };
-            let liquidity = match pool.liquidity {
-                Some(l) => l,
-                None => continue,
-            };
-
-            // 确定交换方向
-            let zero_for_one = pool.token0 == token_in;
-
             // 本地计算输出
-            if let Some(output) = self.calculate_amount_out_local(
-                sqrt_price,
-                liquidity,
-                amount_in,
-                zero_for_one,
-                pool.fee,
-            ) {
+            if let Some(output) = self.local_amount_out(pool, token_in, amount_in) {
                 if output > best_output {
                     best_output = output;
                     best_pool = Some(pool.clone());
@@ -2615,7 +5214,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         let mut best_output = U256::zero();
 
         for pool in matching_pools {
-            match self.quote_exact_input(token_in, token_out, pool.fee, amount_in).await {
+            match self.quote_hop(pool, token_in, token_out, amount_in).await {
                 Ok(result) if result.amount_out > best_output => {
                     best_output = result.amount_out;
                     best_pool = Some(pool.clone());
@@ -2636,17 +5235,13 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
     /// swap_usd: 触发交易的真实 USD 金额
     async fn detect_arbitrage_for_pool_legacy(&self, pool_address: Address, swap_usd: Decimal) -> Option<ArbitrageOpportunity> {
         // 先获取数据的拷贝，然后释放锁，避免死锁
-        let (pool_clone, other_pools, pool_count) = {
-            let states = self.pool_states.read().await;
-            let pool = states.get(&pool_address)?.clone();
-            let others: Vec<PoolState> = states.values()
-                .filter(|p| p.address != pool_address)
-                .cloned()
-                .collect();
-            let count = states.len();
-            (pool, others, count)
+        let all_pools = self.selectable_pool_states().await;
+        let pool_count = all_pools.len();
+        let Some(pool_clone) = all_pools.iter().find(|p| p.address == pool_address).cloned() else {
+            debug!("触发池 {:?} 不可用 (未知或处于回调中)，跳过检测", pool_address);
+            return None;
         };
-        // 读锁已释放
+        let other_pools: Vec<PoolState> = all_pools.into_iter().filter(|p| p.address != pool_address).collect();
 
         // 获取代币符号用于日志
         let token0_info = self.get_token_info(pool_clone.token0).await;
@@ -2670,9 +5265,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             paths_checked += 1;
             if let Some(opp) = self.check_triangular_path_directed(
                 pool_clone.token0, pool_clone.token1, &pool_clone, other_pool, &other_pools, swap_usd
-            ).await {
+            ).instrument(info_span!("quote_path", path = "legacy", pool2 = ?other_pool.address, direction = 1)).await {
                 valid_paths += 1;
-                if best_opportunity.as_ref().map_or(true, |b| opp.net_profit_usd > b.net_profit_usd) {
+                if best_opportunity.as_ref().map_or(true, |b| opp.ranking_net_profit() > b.ranking_net_profit()) {
                     best_opportunity = Some(opp);
                 }
             }
@@ -2681,9 +5276,9 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             paths_checked += 1;
             if let Some(opp) = self.check_triangular_path_directed(
                 pool_clone.token1, pool_clone.token0, &pool_clone, other_pool, &other_pools, swap_usd
-            ).await {
+            ).instrument(info_span!("quote_path", path = "legacy", pool2 = ?other_pool.address, direction = 2)).await {
                 valid_paths += 1;
-                if best_opportunity.as_ref().map_or(true, |b| opp.net_profit_usd > b.net_profit_usd) {
+                if best_opportunity.as_ref().map_or(true, |b| opp.ranking_net_profit() > b.ranking_net_profit()) {
                     best_opportunity = Some(opp);
                 }
             }
@@ -2719,8 +5314,12 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return None;
         };
 
-        // token_c 不能等于 token_a（否则就是两跳，不是三角）
-        if token_c == token_a {
+        // 三个代币必须互不相同 (token_c == token_a 时就是两跳，不是三角)
+        if validate_distinct_tokens(token_a, token_b, token_c).is_err() {
+            return None;
+        }
+
+        if !self.fee_tier_allowed(pool2).await {
             return None;
         }
 
@@ -2745,7 +5344,8 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                 p.address != pool1.address &&
                 p.address != pool2.address &&
                 ((p.token0 == token_c && p.token1 == token_a) ||
-                 (p.token0 == token_a && p.token1 == token_c))
+                 (p.token0 == token_a && p.token1 == token_c)) &&
+                !self.path_cooldown.is_suspended(&[pool1.address, pool2.address, p.address])
             })
             .collect();
 
@@ -2795,6 +5395,11 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                 continue;
             }
 
+            if !self.fee_tier_allowed(pool3).await {
+                debug!("      ⏭️ 费率 {} 不在白名单内，跳过候选池: {:?}", pool3.fee, pool3.address);
+                continue;
+            }
+
             // 直接加入候选池，由链上 QuoterV2 精确验证
             candidate_pool3s.push(*pool3);
         }
@@ -2804,10 +5409,30 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return None;
         }
 
+        // 每个候选池都要 3 次链上报价，只报价最深的若干个，限制最坏情况下的 RPC 数量
+        let max_candidates = self.config.legacy_max_pool3_candidates;
+        if max_candidates > 0 && candidate_pool3s.len() > max_candidates {
+            candidate_pool3s.sort_by_cached_key(|pool3| {
+                let [info0, info1] = if pool3.token0 == token_a {
+                    [&token_a_info, &token_c_info]
+                } else {
+                    [&token_c_info, &token_a_info]
+                };
+                let depth = pool_depth_usd(pool3, [(info0.decimals, info0.price_usd), (info1.decimals, info1.price_usd)]);
+                // 深度未知 (缺价格) 时按 V3 liquidity 排序
+                (std::cmp::Reverse(depth), std::cmp::Reverse(pool3.liquidity.unwrap_or(0)))
+            });
+            info!(
+                "      ✂️ 候选pool3 {} 个，只报价最深的 {} 个",
+                candidate_pool3s.len(), max_candidates
+            );
+            candidate_pool3s.truncate(max_candidates);
+        }
+
         // 对候选池使用链上 QuoterV2 精确确认
         for pool3 in candidate_pool3s {
             if let Some((optimal_input, sim_result)) =
-                self.find_optimal_input(token_a, token_b, token_c, pool1, pool2, pool3, swap_usd).await
+                self.find_optimal_input(token_a, token_b, token_c, pool1, pool2, pool3, InputSizing::SwapUsd(swap_usd)).await
             {
                 info!(
                     "      📈 pool3={:?} | 最优输入={} | 输出={} | 净利润=${:.4} | gas=${:.4}",
@@ -2822,8 +5447,8 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         let (input_amount, sim_result, pool3) = best_result?;
 
-        // 使用动态利润门槛
-        let dynamic_min_profit = self.get_dynamic_min_profit().await;
+        // 使用动态利润门槛 (代币对 / 三角覆盖优先)
+        let dynamic_min_profit = self.min_profit_for_path([token_a, token_b, token_c]).await;
         if sim_result.net_profit_usd < dynamic_min_profit {
             info!(
                 "   ⚠️ 利润不足动态阈值: ${:.4} < ${:.2} (Gas动态门槛)",
@@ -2872,12 +5497,16 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             Decimal::ZERO
         };
 
+        let break_even_gas_price = break_even_gas_price_gwei(
+            profit_usd, sim_result.total_gas_used, self.native_unit_price().await
+        );
         info!(
-            "发现套利机会: {:?} -> {:?} -> {:?} -> {:?}, 净利润=${:.2}, 利润率={:.4}%, gas={}",
-            token_a, token_b, token_c, token_a, sim_result.net_profit_usd, profit_percentage, sim_result.total_gas_used
+            "发现套利机会: {:?} -> {:?} -> {:?} -> {:?}, 净利润=${:.2}, 利润率={:.4}%, gas={}, 盈亏平衡 gas={:.4} Gwei",
+            token_a, token_b, token_c, token_a, sim_result.net_profit_usd, profit_percentage, sim_result.total_gas_used,
+            break_even_gas_price
         );
 
-        Some(ArbitrageOpportunity {
+        let mut opportunity = ArbitrageOpportunity {
             id: uuid::Uuid::new_v4().to_string(),
             path,
             input_amount,
@@ -2890,7 +5519,14 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             profit_percentage,
             timestamp: chrono::Utc::now(),
             block_number: self.current_block.load(Ordering::Relaxed),
-        })
+            max_price_impact: sim_result.max_price_impact,
+            break_even_gas_price_gwei: break_even_gas_price,
+            hop_amounts_out: sim_result.hop_outputs.to_vec(),
+            net_profit_native: None,
+            gas_cost_native: None,
+        };
+        self.denominate_opportunity(&mut opportunity).await;
+        Some(opportunity)
     }
 
 
@@ -2904,11 +5540,28 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         pool1: &PoolState,
         pool2: &PoolState,
         pool3: &PoolState,
-        swap_usd: Decimal,
+        sizing: InputSizing,
     ) -> Option<(U256, ArbitrageSimResult)> {
-        // 获取代币信息，将 swap USD 金额转换为代币数量
         let token_a_info = self.get_token_info(token_a).await;
-        let input_amount = self.usd_to_token_amount(swap_usd, &token_a_info);
+        let input_amount = match sizing {
+            InputSizing::Fixed(amount) => {
+                info!(
+                    "         📌 使用路径固定输入: {} {}",
+                    format_token_amount(amount, token_a_info.decimals), token_a_info.symbol
+                );
+                amount
+            }
+            InputSizing::SwapUsd(swap_usd) => {
+                // 将 swap USD 金额转换为代币数量 (不超过三个池子能承受的量)
+                let swap_usd = self.liquidity_bounded_usd(swap_usd, &[vec![pool1], vec![pool2], vec![pool3]]).await;
+                let amount = self.usd_to_token_amount(swap_usd, &token_a_info);
+                info!(
+                    "         📊 使用 swap 真实金额: ${:.2} -> {} {}",
+                    swap_usd, format_token_amount(amount, token_a_info.decimals), token_a_info.symbol
+                );
+                amount
+            }
+        };
 
         // 防止输入金额为 0
         if input_amount.is_zero() {
@@ -2916,11 +5569,6 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             return None;
         }
 
-        info!(
-            "         📊 使用 swap 真实金额: ${:.2} -> {} {}",
-            swap_usd, format_token_amount(input_amount, token_a_info.decimals), token_a_info.symbol
-        );
-
         // 直接用 swap 金额进行链上报价评估
         let result = self.simulate_and_calculate_profit(
             input_amount, token_a, token_b, token_c, pool1, pool2, pool3
@@ -2971,7 +5619,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // Step 1: A -> B (真实报价 + gas 估算)
         let input_fmt = format_token_amount(input_amount, token_a_info.decimals);
-        let quote1 = match self.quote_exact_input(token_a, token_b, pool1.fee, input_amount).await {
+        let quote1 = match self.quote_hop(pool1, token_a, token_b, input_amount).await {
             Ok(result) => result,
             Err(e) => {
                 info!("         ❌ Step1 报价失败: {} {} -> {} | 错误: {}", input_fmt, token_a_info.symbol, token_b_info.symbol, e);
@@ -2993,7 +5641,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // Step 2: B -> C (真实报价 + gas 估算)
         let quote2_start = std::time::Instant::now();
-        let quote2 = match self.quote_exact_input(token_b, token_c, pool2.fee, quote1.amount_out).await {
+        let quote2 = match self.quote_hop(pool2, token_b, token_c, quote1.amount_out).await {
             Ok(result) => result,
             Err(e) => {
                 info!("         ❌ Step2 报价失败: {} {} -> {} | 错误: {}", out1_fmt, token_b_info.symbol, token_c_info.symbol, e);
@@ -3015,7 +5663,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
         // Step 3: C -> A (真实报价 + gas 估算)
         let quote3_start = std::time::Instant::now();
-        let quote3 = match self.quote_exact_input(token_c, token_a, pool3.fee, quote2.amount_out).await {
+        let quote3 = match self.quote_hop(pool3, token_c, token_a, quote2.amount_out).await {
             Ok(result) => result,
             Err(e) => {
                 info!("         ❌ Step3 报价失败: {} {} -> {} | 错误: {}", out2_fmt, token_c_info.symbol, token_a_info.symbol, e);
@@ -3023,13 +5671,29 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             }
         };
         let quote3_elapsed = quote3_start.elapsed();
-        let out3_fmt = format_token_amount(quote3.amount_out, token_a_info.decimals);
         if quote3.amount_out.is_zero() {
             info!("         ❌ Step3 输出为0: {} {} -> {} | fee={}bp", out2_fmt, token_c_info.symbol, token_a_info.symbol, pool3.fee / 100);
             return None;
         }
-        if quote3.amount_out <= input_amount {
-            let loss = input_amount - quote3.amount_out;
+
+        // 逐跳报价的中间金额取整与 exactInput 多跳不同，可选用多跳报价交叉验证
+        // (QuoterV2 只能走 V3 池子，路径含 StableSwap / V2 池子时跳过)
+        let all_v3 = [pool1, pool2, pool3].iter().all(|p| p.dex_type.is_v3_style());
+        let final_amount_out = if self.config.multi_hop_cross_check && all_v3 {
+            self.cross_check_multi_hop(
+                [token_a, token_b, token_c, token_a],
+                [pool1.fee, pool2.fee, pool3.fee],
+                input_amount,
+                quote3.amount_out,
+                &token_a_info.symbol,
+            ).await
+        } else {
+            quote3.amount_out
+        };
+        let out3_fmt = format_token_amount(final_amount_out, token_a_info.decimals);
+
+        if final_amount_out <= input_amount {
+            let loss = input_amount - final_amount_out;
             let loss_usd = self.calculate_profit_usd(loss, token_a).await;
             info!(
                 "         ❌ 亏损 ${:.2} | 输入: {} {} | 输出: {} {} | 路径: {}->{}->{}->{}",
@@ -3051,33 +5715,118 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             total_quote_elapsed.as_secs_f64() * 1000.0
         );
 
+        // 自己的交易造成的价格影响 (StableSwap 池子无现货价格，不计入)
+        let max_price_impact = max_price_impact_pct(&[
+            (pool1, token_a, input_amount, quote1.amount_out),
+            (pool2, token_b, quote1.amount_out, quote2.amount_out),
+            (pool3, token_c, quote2.amount_out, quote3.amount_out),
+        ]);
+        if price_impact_exceeded(max_price_impact, self.config.max_price_impact_pct) {
+            info!(
+                "         ❌ 价格影响过大: {:.4}% > {}% | 输入: {} {}",
+                max_price_impact, self.config.max_price_impact_pct, input_fmt, token_a_info.symbol
+            );
+            return None;
+        }
+
         // 添加额外开销 (闪电贷回调、合约调用等) 约 50,000 gas
         total_gas_estimate += U256::from(50_000);
 
         // 计算真实 gas 成本
         let gas_cost_usd = self.calculate_gas_cost_usd(total_gas_estimate).await;
 
-        let profit = quote3.amount_out.saturating_sub(input_amount);
+        let profit = final_amount_out.saturating_sub(input_amount);
         let profit_usd = self.calculate_profit_usd(profit, token_a).await;
-        let net_profit_usd = profit_usd - gas_cost_usd;
+        // 闪电贷费用在检测阶段就扣除，动态利润门槛据此判断，避免执行时才发现被费用吃掉
+        let (flash_fee_tier, flash_fee_usd) = self
+            .estimated_flash_fee_usd(input_amount, [token_a, token_b, token_c], [pool1, pool2, pool3])
+            .await;
+        let net_profit_usd = profit_usd - gas_cost_usd - flash_fee_usd;
 
         info!(
-            "         ✅ 套利模拟完成: 输入={} {} | 输出={} {} | 毛利润={} ({} ${:.4}) | gas={} (${:.4}) | 净利润=${:.4}",
+            "         ✅ 套利模拟完成: 输入={} {} | 输出={} {} | 毛利润={} ({} ${:.4}) | gas={} (${:.4}) | 闪电贷费={}bp (${:.4}) | 净利润=${:.4} | 最大价格影响={:.4}%",
             input_amount, token_a_info.symbol,
-            quote3.amount_out, token_a_info.symbol,
+            final_amount_out, token_a_info.symbol,
             profit, token_a_info.symbol, profit_usd,
             total_gas_estimate, gas_cost_usd,
-            net_profit_usd
+            flash_fee_tier / 100, flash_fee_usd,
+            net_profit_usd, max_price_impact
         );
 
         Some(ArbitrageSimResult {
             net_profit_usd,
-            amount_out: quote3.amount_out,
+            amount_out: final_amount_out,
             total_gas_used: total_gas_estimate,
             gas_cost_usd,
+            max_price_impact,
+            hop_outputs: [quote1.amount_out, quote2.amount_out, quote3.amount_out],
         })
     }
 
+    /// 逐跳比较报价成交价与价格服务现货价，偏离超过配置上限时返回原因
+    ///
+    /// 价格未知的代币所在的跳不检查
+    async fn sanity_check_prices(
+        &self,
+        tokens: [Address; 3],
+        input_amount: U256,
+        hop_outputs: [U256; 3],
+    ) -> Result<(), String> {
+        let max_deviation = self.config.max_spot_deviation_pct;
+        if max_deviation <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let mut infos = Vec::with_capacity(3);
+        for token in tokens {
+            infos.push(self.get_token_info(token).await);
+        }
+        let amounts_in = [input_amount, hop_outputs[0], hop_outputs[1]];
+        for hop in 0..3 {
+            let (info_in, info_out) = (&infos[hop], &infos[(hop + 1) % 3]);
+            let deviation = hop_spot_deviation_pct(
+                amounts_in[hop],
+                (info_in.decimals, info_in.price_usd),
+                hop_outputs[hop],
+                (info_out.decimals, info_out.price_usd),
+            );
+            if let Some(deviation) = deviation.filter(|d| *d > max_deviation) {
+                return Err(format!(
+                    "第 {} 跳 {} -> {} 成交价偏离现货价 {:.2}% > {}%",
+                    hop + 1, info_in.symbol, info_out.symbol, deviation, max_deviation
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 按借入金额估算闪电贷费用 (起始代币计价)，返回 (费率, USD)
+    async fn estimated_flash_fee_usd(
+        &self,
+        input_amount: U256,
+        tokens: [Address; 3],
+        swap_pools: [&PoolState; 3],
+    ) -> (u32, Decimal) {
+        let swap_addresses = swap_pools.map(|p| p.address);
+        let pinned = match self.pinned_flash_pool(tokens, &swap_addresses).await {
+            Some(pool) => Some(pool),
+            // 与执行时一致: 首选池子不在 swap 路径中才会被选用
+            None => self.config.preferred_flash_pools.get(&tokens[0]).copied().filter(|p| !swap_addresses.contains(p)),
+        };
+        let fee_tier = {
+            let states = self.pool_states.read().await;
+            estimate_flash_pool_fee(states.values(), tokens, swap_pools, pinned)
+        };
+        // 合约使用 Uniswap V3 闪电贷，费率即借贷池 fee tier
+        let flash_fee = calculate_flash_fee(
+            &self.config.flash_fee_table,
+            FlashLoanProvider::UniswapV3,
+            input_amount,
+            fee_tier,
+        );
+        (fee_tier, self.calculate_profit_usd(flash_fee, tokens[0]).await)
+    }
+
     /// 获取缓存的 gas price (30秒更新一次，减少 RPC 调用)
     async fn get_cached_gas_price(&self) -> U256 {
         const CACHE_DURATION_SECS: u64 = 30;
@@ -3092,7 +5841,16 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             }
         }
 
-        // 缓存过期或不存在，从链上获取
+        // 缓存过期或不存在，先按配置的来源获取，失败时回退到节点
+        if let Some(price) = self.source_gas_price().await {
+            let mut cache = self.gas_price_cache.write().await;
+            *cache = Some(GasPriceCache {
+                price_wei: price,
+                last_updated: std::time::Instant::now(),
+            });
+            return price;
+        }
+
         let rpc_start = std::time::Instant::now();
         let gas_price_wei = match self.provider.get_gas_price().await {
             Ok(price) => {
@@ -3134,25 +5892,61 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         gas_price_wei
     }
 
+    /// 从配置的非节点来源获取 gas price (节点来源或获取失败时返回 None)
+    async fn source_gas_price(&self) -> Option<U256> {
+        match &self.config.gas_price_source {
+            GasPriceSource::Node => None,
+            GasPriceSource::Oracle(oracle) => match oracle.fetch_gas_price().await {
+                Ok(price) => {
+                    debug!("[{}] gas oracle 价格: {} gwei", self.chain_name, price / U256::from(1_000_000_000u64));
+                    Some(price)
+                }
+                Err(e) => {
+                    warn!("[{}] gas oracle 获取失败，回退到节点: {:#}", self.chain_name, e);
+                    None
+                }
+            },
+            GasPriceSource::BaseFee => {
+                let rpc_start = std::time::Instant::now();
+                let block = self.provider.get_block(BlockNumber::Latest).await;
+                self.rpc_stats.record_call(
+                    RpcCallType::GetGasPrice,
+                    rpc_start.elapsed().as_millis() as u64,
+                    block.is_ok()
+                );
+                match block {
+                    Ok(Some(block)) => block.base_fee_per_gas,
+                    Ok(None) => None,
+                    Err(e) => {
+                        debug!("[{}] 获取最新区块 base fee 失败，回退到 eth_gasPrice: {}", self.chain_name, e);
+                        None
+                    }
+                }
+            }
+        }
+    }
+
     /// 根据 gas 用量计算 USD 成本 (使用缓存的 gas price)
     async fn calculate_gas_cost_usd(&self, gas_used: U256) -> Decimal {
-        // 从价格服务获取 ETH 价格
-        let eth_price = self.price_service.get_eth_price().await;
+        // 从价格服务获取 ETH 价格 (原生代币计价模式下为 1)
+        let eth_price = self.native_unit_price().await;
 
         // 使用缓存的 gas price (30秒更新一次)
         let gas_price_wei = self.get_cached_gas_price().await;
 
         // gas_cost_eth = gas_used * gas_price_wei / 10^18
         let gas_cost_wei = gas_used * gas_price_wei;
-        let gas_cost_eth = decimal_from_str(&gas_cost_wei.to_string())
-            .unwrap_or(Decimal::ZERO) / dec!(1_000_000_000_000_000_000);
-
-        gas_cost_eth * eth_price
+        TokenAmount::new(gas_cost_wei, 18).to_usd(eth_price)
     }
 
     /// 根据当前 Gas 价格获取动态最小利润门槛
     /// 低 gas 时使用较低门槛，高 gas 时使用较高门槛
     pub async fn get_dynamic_min_profit(&self) -> Decimal {
+        // 原生代币计价模式: 门槛直接按原生代币配置
+        if let Some(ref native) = self.config.native_denomination {
+            return native.min_profit;
+        }
+
         // 如果未启用动态门槛，返回静态配置值
         if !self.config.enable_dynamic_profit {
             return self.config.min_profit_usd;
@@ -3185,6 +5979,34 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         min_profit
     }
 
+    /// 路径的最小利润门槛: 配置了代币对 / 三角覆盖时优先使用覆盖值，否则为动态门槛
+    ///
+    /// 覆盖值按 USD 配置，原生代币计价模式下不生效
+    pub async fn min_profit_for_path(&self, path_tokens: [Address; 3]) -> Decimal {
+        if self.config.native_denomination.is_none() {
+            if let Some(min_profit) = self.config.min_profit_overrides.resolve(&path_tokens) {
+                debug!("路径 {:?} 使用覆盖的最小利润门槛: ${}", path_tokens, min_profit);
+                return min_profit;
+            }
+        }
+        self.get_dynamic_min_profit().await
+    }
+
+    /// 路径在任何 Gas 分档下可能用到的最低门槛 (覆盖值优先，动态门槛取各档中最低的一档)
+    fn lowest_min_profit_for_path(&self, path_tokens: [Address; 3]) -> Decimal {
+        if let Some(ref native) = self.config.native_denomination {
+            return native.min_profit;
+        }
+        if let Some(min_profit) = self.config.min_profit_overrides.resolve(&path_tokens) {
+            return min_profit;
+        }
+        if self.config.enable_dynamic_profit {
+            self.config.dynamic_profit_config.lowest()
+        } else {
+            self.config.min_profit_usd
+        }
+    }
+
     /// 获取最优输入金额 (优先从配置缓存获取，支持多链，已停用)
     #[allow(dead_code)]
     async fn get_optimal_input_async(&self, token: Address) -> U256 {
@@ -3234,32 +6056,134 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
     /// 计算利润 (USD) - 使用价格服务获取代币价格
     async fn calculate_profit_usd(&self, profit: U256, token: Address) -> Decimal {
         let token_info = self.get_token_info(token).await;
-        let profit_dec = decimal_from_str(&profit.to_string()).unwrap_or(Decimal::ZERO);
-        let divisor = Decimal::from(10u64.pow(token_info.decimals as u32));
-        (profit_dec / divisor) * token_info.price_usd
+        self.calculate_usd_value(profit, &token_info)
     }
 
     /// 将 USD 金额转换为代币数量
+    ///
+    /// 价格未知时返回 0 (调用方跳过该路径)；不能回退成固定的代币个数，
+    /// 同样 "1000 个" 对 USDC 是 $1000，对 WETH 是几百万美元
     fn usd_to_token_amount(&self, usd_amount: Decimal, token_info: &TokenInfo) -> U256 {
-        if token_info.price_usd <= Decimal::ZERO {
-            // 价格无效，使用默认值
-            return U256::from(1000) * U256::exp10(token_info.decimals as usize);
+        TokenAmount::from_usd(usd_amount, token_info.price_usd, token_info.decimals)
+            .map(|amount| amount.raw())
+            .unwrap_or_else(|| {
+                debug!("{} 价格未知，无法把 ${} 换算为代币数量", token_info.symbol, usd_amount);
+                U256::zero()
+            })
+    }
+
+    /// 合约的 min_profit: 把路径的利润门槛换算为起始代币的 wei 单位 (价格未知时为 0，不设限制)
+    async fn contract_min_profit(&self, path_tokens: [Address; 3]) -> U256 {
+        let token_info = self.get_token_info(path_tokens[0]).await;
+        let min_profit_usd = self.min_profit_for_path(path_tokens).await;
+        let min_profit_wei = TokenAmount::from_usd(min_profit_usd, token_info.price_usd, token_info.decimals)
+            .map(|amount| amount.raw())
+            .unwrap_or_else(U256::zero);
+        info!(
+            "[{}] 💰 最小利润阈值: {}{} = {} {} (wei)",
+            self.chain_name,
+            min_profit_usd,
+            if self.config.native_denomination.is_some() { " (原生代币)" } else { " USD" },
+            min_profit_wei,
+            token_info.symbol
+        );
+        min_profit_wei
+    }
+
+    /// 读取套利合约的 minProfitThreshold，找出链下门槛比它宽松的预定义路径
+    ///
+    /// 只检查使用该合约执行的路径 (路径未指定合约时使用链默认合约)；
+    /// 门槛按最低的 Gas 分档计算 (启动时的 Gas 不代表之后)，起始代币暂无价格的路径无法换算，跳过；
+    /// 合约阈值为 0 时没有冲突
+    pub async fn contract_min_profit_conflicts(&self, contract: Address) -> Result<Vec<MinProfitConflict>, ExecutionError> {
+        let on_chain_wei = executor::read_contract_min_profit(contract, self.provider.clone()).await?;
+        if on_chain_wei.is_zero() {
+            return Ok(Vec::new());
+        }
+
+        let paths: Vec<PoolPathConfig> = self.pool_path_mappings.read().await.values().flatten().cloned().collect();
+        let default_contract = self.config.executor_config.arbitrage_contract;
+        let mut checked = HashSet::new();
+        let mut conflicts = Vec::new();
+        for path in paths {
+            if path.arbitrage_contract.or(default_contract) != Some(contract) || !checked.insert(path.path_name.clone()) {
+                continue;
+            }
+            let token_info = self.get_token_info(path.token_a).await;
+            let min_profit_usd = self.lowest_min_profit_for_path([path.token_a, path.token_b, path.token_c]);
+            let Some(off_chain_wei) = TokenAmount::from_usd(min_profit_usd, token_info.price_usd, token_info.decimals)
+                .map(|amount| amount.raw())
+            else {
+                debug!(
+                    "[{}] 路径 {} 的起始代币 {} 暂无价格，跳过合约利润阈值检查",
+                    self.chain_name, path.path_name, token_info.symbol
+                );
+                continue;
+            };
+            if off_chain_wei < on_chain_wei {
+                conflicts.push(MinProfitConflict {
+                    path_name: path.path_name,
+                    token_symbol: token_info.symbol,
+                    off_chain_min_profit: min_profit_usd,
+                    off_chain_wei,
+                    on_chain_wei,
+                });
+            }
+        }
+        Ok(conflicts)
+    }
+
+    /// 一个原生代币的计价: USD 模式为本链原生代币 (ETH/BNB/MATIC/AVAX) 的价格，原生代币计价模式为 1
+    ///
+    /// 价格服务还没有该代币价格时使用该代币自己的兜底价
+    async fn native_unit_price(&self) -> Decimal {
+        if self.config.native_denomination.is_some() {
+            return Decimal::ONE;
         }
+        self.price_service.get_native_token_price(&self.config.native_token_symbol).await
+    }
 
-        // token_amount = usd_amount / price_usd
-        let token_amount = usd_amount / token_info.price_usd;
+    /// 原生代币计价模式下把模拟得到的原生代币数额移到 `*_native` 字段，
+    /// USD 字段改为按原生代币实时价格折算的值 (价格未知时为 0)；USD 计价模式下不做任何事
+    async fn denominate_opportunity(&self, opportunity: &mut ArbitrageOpportunity) {
+        if self.config.native_denomination.is_none() {
+            return;
+        }
+        let native_usd = self
+            .price_service
+            .get_price_by_symbol(&self.config.native_token_symbol)
+            .await
+            .filter(|price| *price > Decimal::ZERO)
+            .unwrap_or(Decimal::ZERO);
+        opportunity.net_profit_native = Some(opportunity.net_profit_usd);
+        opportunity.gas_cost_native = Some(opportunity.gas_cost_usd);
+        opportunity.expected_profit_usd *= native_usd;
+        opportunity.gas_cost_usd *= native_usd;
+        opportunity.net_profit_usd *= native_usd;
+    }
 
-        // 转换为带小数位的原始数量
-        let multiplier = Decimal::from(10u64.pow(token_info.decimals as u32));
-        let raw_amount = token_amount * multiplier;
+    /// 获取代币信息，原生代币计价模式下价格折算为原生代币
+    async fn get_token_info(&self, address: Address) -> TokenInfo {
+        let mut info = self.get_token_info_usd(address).await;
+        if let Some(ref native) = self.config.native_denomination {
+            // 只信任价格服务的实时价格，get_eth_price 的兜底值在这里没有意义
+            let native_usd = self.price_service.get_price_by_symbol(&self.config.native_token_symbol).await;
+            info.price_usd = native_relative_price(address == native.wrapped_native, info.price_usd, native_usd);
+        }
+        info
+    }
 
-        // 转换为 U256
-        let raw_str = raw_amount.floor().to_string();
-        U256::from_dec_str(&raw_str).unwrap_or(U256::zero())
+    /// 没有实时价格的稳定币按该链参考稳定币的价格估值，参考稳定币也没有价格时为 $1
+    async fn reference_stable_price_usd(&self) -> Decimal {
+        self.price_service
+            .get_price_by_symbol(&self.config.reference_stablecoin)
+            .await
+            .filter(|price| *price > Decimal::ZERO)
+            .unwrap_or(dec!(1))
     }
 
     /// 获取代币信息 (优先从配置缓存获取，然后从价格服务获取实时价格)
-    async fn get_token_info(&self, address: Address) -> TokenInfo {
+    async fn get_token_info_usd(&self, address: Address) -> TokenInfo {
         // 从价格服务获取价格
         let price_usd = self.price_service.get_price_by_address(&address).await
             .unwrap_or(Decimal::ZERO);
@@ -3269,7 +6193,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             let final_price = if price_usd > Decimal::ZERO {
                 price_usd
             } else if config.is_stable {
-                dec!(1)
+                self.reference_stable_price_usd().await
             } else if config.price_symbol == "ETH" {
                 self.price_service.get_eth_price().await
             } else {
@@ -3283,6 +6207,24 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             };
         }
 
+        // 其次查共享的元数据缓存
+        if let Some(metadata) = self.token_metadata.get(self.config.chain_id, address).await {
+            let final_price = if price_usd > Decimal::ZERO {
+                price_usd
+            } else if metadata.is_stable {
+                self.reference_stable_price_usd().await
+            } else {
+                self.price_service.get_price_by_symbol(&metadata.price_symbol).await
+                    .unwrap_or(Decimal::ZERO)
+            };
+
+            return TokenInfo {
+                symbol: metadata.symbol,
+                decimals: metadata.decimals,
+                price_usd: final_price,
+            };
+        }
+
         // 回退到硬编码映射 (保持向后兼容)
         let addr_str = format!("{:?}", address).to_lowercase();
 
@@ -3327,22 +6269,58 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
     /// 计算代币的美金价值
     fn calculate_usd_value(&self, amount: U256, token_info: &TokenInfo) -> Decimal {
-        let amount_dec = decimal_from_str(&amount.to_string()).unwrap_or(Decimal::ZERO);
-        let divisor = Decimal::from(10u64.pow(token_info.decimals as u32));
-        (amount_dec / divisor) * token_info.price_usd
+        TokenAmount::new(amount, token_info.decimals).to_usd(token_info.price_usd)
     }
 
-    /// 异步获取余额 (静态方法，用于 tokio::spawn，不阻塞主套利流程)
+    /// 在余额查询并发上限内获取余额，未启用余额查询时直接返回空
+    ///
+    /// 突发执行时多余的查询在这里排队，而不是和发送、报价同时打到节点
+    async fn get_balances_limited(
+        permits: Option<Arc<Semaphore>>,
+        fetcher: &BalanceFetcher<M>,
+        token_addresses: &[Address],
+        block: Option<BlockId>,
+    ) -> Vec<WalletBalance> {
+        let Some(permits) = permits else {
+            return Vec::new();
+        };
+        let _permit = match permits.acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => return Vec::new(),
+        };
+        Self::get_balances_async(fetcher, token_addresses, block).await
+    }
+
+    /// 异步获取余额 (静态方法，用于 tokio::spawn，不阻塞主套利流程)
+    ///
+    /// 优先用 Multicall3 一次查询所有代币余额；Multicall 不可用时逐个 balanceOf
     async fn get_balances_async(
-        provider: Arc<M>,
-        price_service: SharedPriceService,
-        token_configs: &HashMap<Address, TokenConfig>,
-        contract_address: Address,
+        fetcher: &BalanceFetcher<M>,
         token_addresses: &[Address],
-        rpc_stats: Option<Arc<RpcStats>>,
+        block: Option<BlockId>,
     ) -> Vec<WalletBalance> {
-        let mut balances = Vec::new();
-        for &token_addr in token_addresses {
+        let BalanceFetcher { provider, price_service, token_configs, multicall_address, contract_address, rpc_stats } =
+            fetcher;
+        let raw_balances = match Self::fetch_balances_multicall(
+            provider.clone(),
+            *multicall_address,
+            *contract_address,
+            token_addresses,
+            rpc_stats.as_deref(),
+            block,
+        ).await {
+            Some(balances) => balances,
+            None => Self::fetch_balances_sequential(
+                provider.clone(),
+                *contract_address,
+                token_addresses,
+                rpc_stats.as_deref(),
+                block,
+            ).await,
+        };
+
+        let mut balances = Vec::with_capacity(token_addresses.len());
+        for (&token_addr, balance) in token_addresses.iter().zip(raw_balances) {
             // 获取代币信息
             let token_info = if let Some(config) = token_configs.get(&token_addr) {
                 let price_usd = price_service.get_price_by_address(&token_addr).await
@@ -3367,23 +6345,11 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                 }
             };
 
-            // 获取余额 (带 RPC 统计)
-            let erc20 = IERC20Balance::new(token_addr, provider.clone());
-            let rpc_start = std::time::Instant::now();
-            match erc20.balance_of(contract_address).call().await {
-                Ok(balance) => {
-                    // 记录成功的 RPC 调用
-                    if let Some(ref stats) = rpc_stats {
-                        stats.record_call(
-                            RpcCallType::Erc20BalanceOf,
-                            rpc_start.elapsed().as_millis() as u64,
-                            true
-                        );
-                    }
-                    let balance_str = format_token_amount(balance, token_info.decimals);
-                    let amount_dec = decimal_from_str(&balance.to_string()).unwrap_or(Decimal::ZERO);
-                    let divisor = Decimal::from(10u64.pow(token_info.decimals as u32));
-                    let usd_value = (amount_dec / divisor) * token_info.price_usd;
+            match balance {
+                Some(balance) => {
+                    let amount = TokenAmount::new(balance, token_info.decimals);
+                    let balance_str = amount.display();
+                    let usd_value = amount.to_usd(token_info.price_usd);
                     balances.push(WalletBalance {
                         symbol: token_info.symbol,
                         token_address: format!("{:?}", token_addr),
@@ -3391,16 +6357,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
                         usd_value,
                     });
                 }
-                Err(e) => {
-                    // 记录失败的 RPC 调用
-                    if let Some(ref stats) = rpc_stats {
-                        stats.record_call(
-                            RpcCallType::Erc20BalanceOf,
-                            rpc_start.elapsed().as_millis() as u64,
-                            false
-                        );
-                    }
-                    warn!("Failed to get balance for token {:?}: {:?}", token_addr, e);
+                None => {
                     balances.push(WalletBalance {
                         symbol: token_info.symbol,
                         token_address: format!("{:?}", token_addr),
@@ -3413,6 +6370,138 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         balances
     }
 
+    /// 等待交易所在区块之后再出 `confirmations` 个区块，返回读取执行后余额的区块 (即交易所在区块)
+    ///
+    /// confirmations 为 0 或拿不到回执时返回 None，按最新区块读取；等待超时时仍固定在交易所在区块
+    async fn settled_snapshot_block(
+        provider: &M,
+        tx_hash: H256,
+        confirmations: u64,
+        poll_interval: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Option<u64> {
+        if confirmations == 0 {
+            return None;
+        }
+        let receipt = provider.get_transaction_receipt(tx_hash).await.ok().flatten()?;
+        let inclusion_block = receipt.block_number?.as_u64();
+        let target_block = inclusion_block + confirmations;
+
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Ok(current) = provider.get_block_number().await {
+                if current.as_u64() >= target_block {
+                    break;
+                }
+            }
+            if std::time::Instant::now() >= deadline {
+                warn!("等待区块 {} 确认超时 (目标区块 {})，直接读取余额", inclusion_block, target_block);
+                break;
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+        Some(inclusion_block)
+    }
+
+    /// 通过 Multicall3 aggregate3 一次查询所有余额
+    ///
+    /// 返回 None 表示 Multicall 调用本身失败 (如该链未部署)，单个代币查询失败时对应项为 None
+    async fn fetch_balances_multicall(
+        provider: Arc<M>,
+        multicall_address: Address,
+        contract_address: Address,
+        token_addresses: &[Address],
+        rpc_stats: Option<&RpcStats>,
+        block: Option<BlockId>,
+    ) -> Option<Vec<Option<U256>>> {
+        if token_addresses.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let calls: Vec<multicall_3::Call3> = token_addresses
+            .iter()
+            .map(|&token_addr| multicall_3::Call3 {
+                target: token_addr,
+                allow_failure: true,
+                call_data: IERC20Balance::new(token_addr, provider.clone())
+                    .balance_of(contract_address)
+                    .calldata()
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let multicall = Multicall3::new(multicall_address, provider);
+        let mut call = multicall.aggregate_3(calls);
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+        let rpc_start = std::time::Instant::now();
+        let result = call.call().await;
+        if let Some(stats) = rpc_stats {
+            stats.record_call(
+                RpcCallType::MulticallBalances,
+                rpc_start.elapsed().as_millis() as u64,
+                result.is_ok(),
+            );
+        }
+
+        match result {
+            Ok(results) if results.len() == token_addresses.len() => Some(
+                results
+                    .iter()
+                    .map(|r| {
+                        (r.0 && r.1.len() >= 32).then(|| U256::from_big_endian(&r.1[..32]))
+                    })
+                    .collect(),
+            ),
+            Ok(results) => {
+                warn!("Multicall 余额查询返回 {} 个结果，期望 {} 个，改为逐个查询",
+                      results.len(), token_addresses.len());
+                None
+            }
+            Err(e) => {
+                warn!("Multicall 余额查询失败: {:?}，改为逐个查询", e);
+                None
+            }
+        }
+    }
+
+    /// 逐个调用 balanceOf 查询余额 (Multicall 不可用时的回退)
+    async fn fetch_balances_sequential(
+        provider: Arc<M>,
+        contract_address: Address,
+        token_addresses: &[Address],
+        rpc_stats: Option<&RpcStats>,
+        block: Option<BlockId>,
+    ) -> Vec<Option<U256>> {
+        let mut balances = Vec::with_capacity(token_addresses.len());
+        for &token_addr in token_addresses {
+            // 获取余额 (带 RPC 统计)
+            let erc20 = IERC20Balance::new(token_addr, provider.clone());
+            let mut call = erc20.balance_of(contract_address);
+            if let Some(block) = block {
+                call = call.block(block);
+            }
+            let rpc_start = std::time::Instant::now();
+            let result = call.call().await;
+            if let Some(stats) = rpc_stats {
+                stats.record_call(
+                    RpcCallType::Erc20BalanceOf,
+                    rpc_start.elapsed().as_millis() as u64,
+                    result.is_ok(),
+                );
+            }
+            match result {
+                Ok(balance) => balances.push(Some(balance)),
+                Err(e) => {
+                    warn!("Failed to get balance for token {:?}: {:?}", token_addr, e);
+                    balances.push(None);
+                }
+            }
+        }
+        balances
+    }
+
     /// 异步发送邮件 (静态方法，用于 tokio::spawn，包含前后余额对比)
     async fn send_email_with_comparison(
         chain_name: &str,
@@ -3420,6 +6509,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         exec_result: &models::ArbitrageResult,
         balances_before: Vec<WalletBalance>,
         balances_after: Vec<WalletBalance>,
+        profit_currencies: ProfitCurrencies,
     ) {
         // 获取邮件通知器
         let notifier = match get_email_notifier() {
@@ -3450,6 +6540,7 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
             status: format!("{:?}", exec_result.status),
             block_number: opportunity.block_number,
             error_message: exec_result.error_message.clone(),
+            profit_currencies,
         };
 
         // 发送邮件 (包含前后余额对比)
@@ -3462,6 +6553,118 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
         }
     }
 
+    /// 处理一次 Swap 通道接收结果，返回需要处理的事件 (通道已关闭时返回 None)
+    ///
+    /// 接收端落后时 broadcast 会跳过最旧的事件，这里把跳过的数量记入 dropped_events；
+    /// 启用 swap_lag_newest_only 时再清空当前积压，每个池子只保留最新的一条
+    async fn swap_events_from_recv(
+        &self,
+        result: Result<SwapEvent, broadcast::error::RecvError>,
+        swap_rx: &mut broadcast::Receiver<SwapEvent>,
+    ) -> Option<Vec<SwapEvent>> {
+        let skipped = match result {
+            Ok(swap_event) => return Some(vec![swap_event]),
+            Err(broadcast::error::RecvError::Closed) => return None,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => skipped,
+        };
+
+        let (events, discarded) = if self.config.swap_lag_newest_only {
+            drain_newest_per_pool(swap_rx)
+        } else {
+            (Vec::new(), 0)
+        };
+
+        let total_dropped = {
+            let mut stats = self.execution_stats.write().await;
+            stats.dropped_events += skipped + discarded;
+            stats.dropped_events
+        };
+        if self.config.swap_lag_newest_only {
+            warn!(
+                "[{}] ⚠️ Swap 事件积压: 跳过 {} 条, 清理积压 {} 条, 只处理 {} 个池子的最新事件 (累计丢弃 {})",
+                self.chain_name, skipped, discarded, events.len(), total_dropped
+            );
+        } else {
+            warn!(
+                "[{}] ⚠️ Swap 事件积压: 接收端落后, 跳过 {} 条最旧的事件 (累计丢弃 {})",
+                self.chain_name, skipped, total_dropped
+            );
+        }
+        Some(events)
+    }
+
+    /// 去重后交给并发处理任务处理单个 Swap 事件
+    async fn dispatch_swap_event(self: &Arc<Self>, swap_event: SwapEvent) {
+        let tx_hash = swap_event.tx_hash;
+
+        // 1. 基于 tx_hash 去重 - 检查是否已处理过
+        {
+            let mut processed = self.processed_tx_hashes.write().await;
+            if processed.contains_key(&tx_hash) {
+                // 已处理过，跳过
+                let mut stats = self.execution_stats.write().await;
+                stats.duplicates_skipped += 1;
+                debug!(
+                    "[{}] ⏭️ 跳过重复 swap 事件, tx_hash={:?}, pool={:?}, 累计跳过={}",
+                    self.chain_name, tx_hash, swap_event.pool_address, stats.duplicates_skipped
+                );
+                return;
+            }
+            // 标记为已处理
+            processed.insert(tx_hash, std::time::Instant::now());
+        }
+
+        // 2. 获取信号量许可 (阻塞等待，不丢弃事件)
+        let permit = self.handler_semaphore.clone().acquire_owned().await;
+        match permit {
+            Ok(permit) => {
+                // 更新活跃处理数
+                {
+                    let mut stats = self.execution_stats.write().await;
+                    stats.active_handlers += 1;
+                }
+
+                // 克隆必要的引用
+                let scanner = self.clone();
+                let pool_address = swap_event.pool_address;
+
+                // 异步处理事件
+                tokio::spawn(async move {
+                    let start_time = std::time::Instant::now();
+                    debug!("[{}] 🔄 开始并发处理 swap 事件, pool={:?}, tx_hash={:?}",
+                           scanner.chain_name, pool_address, tx_hash);
+
+                    // 处理 swap 事件 (挂载了 A/B 对比时同时交给候选组)
+                    let harness = scanner.ab_harness.read().await.clone();
+                    let result = match harness {
+                        Some(harness) => harness.compare(swap_event.clone(), scanner.handle_swap_event(swap_event)).await,
+                        None => scanner.handle_swap_event(swap_event).await,
+                    };
+                    if let Some(opportunity) = result {
+                        let mut opps = scanner.opportunities.write().await;
+                        opps.push(opportunity);
+                    }
+
+                    let elapsed = start_time.elapsed();
+                    debug!("[{}] ✅ swap 事件处理完成, 耗时={:.2}ms, pool={:?}",
+                           scanner.chain_name, elapsed.as_secs_f64() * 1000.0, pool_address);
+
+                    // 更新活跃处理数
+                    {
+                        let mut stats = scanner.execution_stats.write().await;
+                        stats.active_handlers = stats.active_handlers.saturating_sub(1);
+                    }
+
+                    // 释放信号量许可 (permit 被 drop 时自动释放)
+                    drop(permit);
+                });
+            }
+            Err(e) => {
+                error!("[{}] ❌ 获取信号量失败: {}", self.chain_name, e);
+            }
+        }
+    }
+
     /// 启动事件监听循环 (支持并发处理)
     pub async fn start(
         self: Arc<Self>,
@@ -3489,74 +6692,21 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
 
             tokio::select! {
                 // 处理 Swap 事件 (并发)
-                Ok(swap_event) = swap_rx.recv() => {
-                    let tx_hash = swap_event.tx_hash;
-
-                    // 1. 基于 tx_hash 去重 - 检查是否已处理过
-                    {
-                        let mut processed = self.processed_tx_hashes.write().await;
-                        if processed.contains_key(&tx_hash) {
-                            // 已处理过，跳过
-                            let mut stats = self.execution_stats.write().await;
-                            stats.duplicates_skipped += 1;
-                            debug!(
-                                "[{}] ⏭️ 跳过重复 swap 事件, tx_hash={:?}, pool={:?}, 累计跳过={}",
-                                self.chain_name, tx_hash, swap_event.pool_address, stats.duplicates_skipped
-                            );
-                            continue;
-                        }
-                        // 标记为已处理
-                        processed.insert(tx_hash, std::time::Instant::now());
-                    }
-
-                    // 2. 获取信号量许可 (阻塞等待，不丢弃事件)
-                    let permit = self.handler_semaphore.clone().acquire_owned().await;
-                    match permit {
-                        Ok(permit) => {
-                            // 更新活跃处理数
-                            {
-                                let mut stats = self.execution_stats.write().await;
-                                stats.active_handlers += 1;
-                            }
-
-                            // 克隆必要的引用
-                            let scanner = self.clone();
-                            let pool_address = swap_event.pool_address;
-
-                            // 异步处理事件
-                            tokio::spawn(async move {
-                                let start_time = std::time::Instant::now();
-                                debug!("[{}] 🔄 开始并发处理 swap 事件, pool={:?}, tx_hash={:?}",
-                                       scanner.chain_name, pool_address, tx_hash);
-
-                                // 处理 swap 事件
-                                if let Some(opportunity) = scanner.handle_swap_event(swap_event).await {
-                                    let mut opps = scanner.opportunities.write().await;
-                                    opps.push(opportunity);
-                                }
-
-                                let elapsed = start_time.elapsed();
-                                debug!("[{}] ✅ swap 事件处理完成, 耗时={:.2}ms, pool={:?}",
-                                       scanner.chain_name, elapsed.as_secs_f64() * 1000.0, pool_address);
-
-                                // 更新活跃处理数
-                                {
-                                    let mut stats = scanner.execution_stats.write().await;
-                                    stats.active_handlers = stats.active_handlers.saturating_sub(1);
-                                }
-
-                                // 释放信号量许可 (permit 被 drop 时自动释放)
-                                drop(permit);
-                            });
-                        }
-                        Err(e) => {
-                            error!("[{}] ❌ 获取信号量失败: {}", self.chain_name, e);
-                        }
+                swap_result = swap_rx.recv() => {
+                    let Some(events) = self.swap_events_from_recv(swap_result, &mut swap_rx).await else {
+                        warn!("[{}] Swap 事件通道已关闭，扫描器停止", self.chain_name);
+                        break;
+                    };
+                    for swap_event in events {
+                        self.dispatch_swap_event(swap_event).await;
                     }
                 }
                 // 处理新区块事件 (同步，因为需要更新全局状态)
                 Ok(block_event) = block_rx.recv() => {
                     self.handle_new_block(block_event).await;
+                    // 推迟的机会在池子状态刷新后重新报价 (执行不阻塞区块处理)
+                    let scanner = self.clone();
+                    tokio::spawn(async move { scanner.retry_deferred_executions().await });
                 }
                 // 超时（兜底）
                 _ = tokio::time::sleep(tokio::time::Duration::from_millis(
@@ -3584,6 +6734,211 @@ impl<M: Middleware + 'static> EventDrivenScanner<M> {
     }
 }
 
+/// 报价失败是否为临时错误 (超时、限流、连接中断)，合约 revert 不算
+fn is_transient_quote_error(message: &str) -> bool {
+    const TRANSIENT_PATTERNS: [&str; 10] = [
+        "timeout", "timed out", "429", "too many requests", "rate limit",
+        "connection", "502", "503", "temporarily unavailable", "unexpected eof",
+    ];
+    let message = message.to_lowercase();
+    !message.contains("revert") && TRANSIENT_PATTERNS.iter().any(|p| message.contains(p))
+}
+
+/// 执行许可的来源
+#[derive(Debug, Clone, Copy)]
+enum ExecutionLane {
+    /// 收集窗口内评分选出的同批机会
+    Batch,
+    /// 高价值机会，不等收集窗口
+    Priority,
+}
+
+/// 取得一个执行许可: 普通许可与优先通道保留的许可，哪个先空出来用哪个
+async fn acquire_priority_permit<'a>(permits: &'a Semaphore, reserved: &'a Semaphore) -> SemaphorePermit<'a> {
+    tokio::select! {
+        biased;
+        permit = permits.acquire() => permit,
+        permit = reserved.acquire() => permit,
+    }
+    .expect("执行许可不会被关闭")
+}
+
+/// 按执行器配置创建执行许可 (上限为 0 时不限制)
+fn execution_permits(config: &ScannerExecutorConfig) -> Semaphore {
+    match config.max_concurrent_executions {
+        0 => Semaphore::new(Semaphore::MAX_PERMITS),
+        limit => Semaphore::new(limit),
+    }
+}
+
+/// 按执行器配置创建余额查询许可 (关闭余额查询时为 None，上限至少为 1)
+fn balance_fetch_permits(config: &ScannerExecutorConfig) -> Option<Arc<Semaphore>> {
+    config
+        .fetch_balances
+        .then(|| Arc::new(Semaphore::new(config.max_concurrent_balance_fetches.max(1))))
+}
+
+/// 按执行前后余额计算实际利润 (USD)
+fn realized_profit_from_balances(before: &[WalletBalance], after: &[WalletBalance]) -> Decimal {
+    let total_before: Decimal = before.iter().map(|b| b.usd_value).sum();
+    let total_after: Decimal = after.iter().map(|b| b.usd_value).sum();
+    total_after - total_before
+}
+
+/// 告警邮件内容 (利润偏差、长时间无机会等)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlertContent {
+    title: String,
+    message: String,
+    details: String,
+}
+
+/// 实际利润与预估利润相差超过阈值 (USD，0 表示不告警) 时生成告警
+///
+/// 大幅偏离通常意味着估算有 bug、被 MEV 夹击或报价严重失真，需要人工排查
+fn profit_deviation_alert(
+    chain_name: &str,
+    opportunity: &ArbitrageOpportunity,
+    tx_hash: Option<H256>,
+    delta: &ProfitDelta,
+    threshold_usd: Decimal,
+) -> Option<AlertContent> {
+    if threshold_usd <= Decimal::ZERO || delta.delta_usd().abs() <= threshold_usd {
+        return None;
+    }
+
+    let hops: Vec<String> = opportunity
+        .path
+        .hops
+        .iter()
+        .map(|hop| format!("{:?} -> {:?} | 池子 {:?} | 费率 {}", hop.token_in, hop.token_out, hop.pool_address, hop.fee))
+        .collect();
+    Some(AlertContent {
+        title: format!("利润偏差告警 - {}", chain_name),
+        message: format!(
+            "实际利润 ${:.4} 与预估 ${:.4} 相差 ${:.4}，超过告警阈值 ${}",
+            delta.realized_profit_usd, delta.expected_profit_usd, delta.delta_usd(), threshold_usd
+        ),
+        details: format!(
+            "机会ID: {}\n交易哈希: {}\n区块: {}\n输入金额: {}\n偏差比例: {}\n路径:\n  {}",
+            opportunity.id,
+            tx_hash.map(|h| format!("{:?}", h)).unwrap_or_else(|| "-".to_string()),
+            opportunity.block_number,
+            opportunity.input_amount,
+            delta.error_pct().map(|p| format!("{:.2}%", p)).unwrap_or_else(|| "-".to_string()),
+            hops.join("\n  ")
+        ),
+    })
+}
+
+/// 长时间无机会告警: 链在正常出块，但检测一直没有产出
+fn no_opportunity_alert(chain_name: &str, quiet: QuietPeriod, block_number: u64) -> AlertContent {
+    let minutes = quiet.elapsed.as_secs() / 60;
+    AlertContent {
+        title: format!("长时间无套利机会 - {}", chain_name),
+        message: format!(
+            "已经 {} 分钟 ({} 个区块) 没有发现任何套利机会，检测可能已失灵",
+            minutes, quiet.blocks
+        ),
+        details: format!(
+            "当前区块: {}\n请检查: 池子状态是否在刷新、代币/路径配置是否正确、报价合约是否可用",
+            block_number
+        ),
+    }
+}
+
+/// 单个事件的路径检测结果
+#[derive(Debug, Default)]
+struct PathEvalOutcome {
+    /// 净利润最高的机会
+    best: Option<ArbitrageOpportunity>,
+    /// 已检查的路径数
+    checked: u32,
+    /// 发现机会的路径数
+    found: u32,
+    /// 因超出时间预算未检查的路径数
+    skipped: usize,
+}
+
+/// 按优先级顺序发起路径检查，最多 `concurrency` 条同时进行，保留净利润最高的机会
+///
+/// 每发起一条新路径前判断一次耗时，超过 `budget` 后剩余路径不再检查 (正在检查的路径不会被打断)；
+/// `budget` 为 None 时检查全部路径。净利润相同时取优先级靠前的路径，结果与完成顺序无关
+async fn evaluate_paths_within_budget<'a, T, F, Fut>(
+    paths: &'a [T],
+    budget: Option<std::time::Duration>,
+    concurrency: usize,
+    mut evaluate: F,
+) -> PathEvalOutcome
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: std::future::Future<Output = Option<ArbitrageOpportunity>>,
+{
+    use futures_util::stream::{FuturesUnordered, StreamExt};
+
+    let start = tokio::time::Instant::now();
+    let mut outcome = PathEvalOutcome::default();
+    let mut best_index = usize::MAX;
+    let mut pending = paths.iter().enumerate();
+    let mut in_flight = FuturesUnordered::new();
+    let mut scheduled = 0usize;
+
+    loop {
+        while in_flight.len() < concurrency.max(1) && scheduled < paths.len() {
+            if budget.is_some_and(|budget| start.elapsed() >= budget) {
+                outcome.skipped = paths.len() - scheduled;
+                scheduled = paths.len();
+                break;
+            }
+            if let Some((index, path)) = pending.next() {
+                let evaluation = evaluate(path);
+                in_flight.push(async move { (index, evaluation.await) });
+                scheduled += 1;
+                outcome.checked += 1;
+            }
+        }
+
+        let Some((index, result)) = in_flight.next().await else {
+            break;
+        };
+        if let Some(opp) = result {
+            outcome.found += 1;
+            let better = outcome.best.as_ref().is_none_or(|b| {
+                let (profit, best_profit) = (opp.ranking_net_profit(), b.ranking_net_profit());
+                profit > best_profit || (profit == best_profit && index < best_index)
+            });
+            if better {
+                best_index = index;
+                outcome.best = Some(opp);
+            }
+        }
+    }
+
+    outcome
+}
+
+/// 取出通道中当前积压的全部 Swap 事件，每个池子只保留最新的一条 (按到达顺序)
+///
+/// 返回保留的事件和被丢弃的事件数
+fn drain_newest_per_pool(swap_rx: &mut broadcast::Receiver<SwapEvent>) -> (Vec<SwapEvent>, u64) {
+    let mut events: Vec<SwapEvent> = Vec::new();
+    let mut discarded = 0u64;
+    loop {
+        match swap_rx.try_recv() {
+            Ok(swap_event) => {
+                if let Some(pos) = events.iter().position(|e| e.pool_address == swap_event.pool_address) {
+                    events.remove(pos);
+                    discarded += 1;
+                }
+                events.push(swap_event);
+            }
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => discarded += skipped,
+            Err(_) => break,
+        }
+    }
+    (events, discarded)
+}
+
 /// 辅助函数：从字符串解析 Decimal
 fn decimal_from_str(s: &str) -> Option<Decimal> {
     Decimal::from_str(s).ok()
@@ -3599,33 +6954,47 @@ struct TokenInfo {
 
 /// 格式化代币数量（带小数）
 fn format_token_amount(amount: U256, decimals: u8) -> String {
-    let amount_str = amount.to_string();
-    let decimals = decimals as usize;
+    TokenAmount::new(amount, decimals).display()
+}
 
-    if amount_str.len() <= decimals {
-        let zeros = "0".repeat(decimals - amount_str.len());
-        format!("0.{}{}", zeros, amount_str)
-    } else {
-        let (integer, decimal) = amount_str.split_at(amount_str.len() - decimals);
-        // 只显示前4位小数
-        let decimal_short = if decimal.len() > 4 { &decimal[..4] } else { decimal };
-        format!("{}.{}", integer, decimal_short)
+/// 编码 Uniswap V3 多跳路径: token0 | fee0 (uint24) | token1 | fee1 | ... | tokenN
+fn encode_v3_path(tokens: &[Address], fees: &[u32]) -> Bytes {
+    let mut path = Vec::with_capacity(tokens.len() * 20 + fees.len() * 3);
+    for (i, token) in tokens.iter().enumerate() {
+        path.extend_from_slice(token.as_bytes());
+        if let Some(fee) = fees.get(i) {
+            path.extend_from_slice(&fee.to_be_bytes()[1..]);
+        }
     }
+    Bytes::from(path)
 }
 
-/// 将 sqrtPriceX96 转换为人类可读的价格
-/// price = (sqrtPriceX96 / 2^96)^2
-/// 返回 token1/token0 的价格，考虑两个代币的精度差异
-fn sqrt_price_x96_to_price(sqrt_price_x96: U256, decimals0: u8, decimals1: u8) -> f64 {
-    // sqrtPriceX96 = sqrt(price) * 2^96
-    // price = (sqrtPriceX96 / 2^96)^2
-    let sqrt_price_f64 = sqrt_price_x96.as_u128() as f64 / (2_f64.powi(96));
-    let price_raw = sqrt_price_f64 * sqrt_price_f64;
+/// 多跳报价交叉验证结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MultiHopCheck {
+    /// 最终采用的输出金额
+    amount_out: U256,
+    /// 两种报价的偏差 (基点)
+    divergence_bps: u64,
+    /// 偏差是否超过容忍度
+    diverged: bool,
+}
 
-    // 调整精度: token1 的数量 / token0 的数量
-    // 需要乘以 10^(decimals0 - decimals1) 来获得正确的价格
-    let decimal_adjustment = 10_f64.powi(decimals0 as i32 - decimals1 as i32);
-    price_raw * decimal_adjustment
+/// 比较链式报价与多跳报价，偏差超过容忍度时采用多跳报价 (与实际执行路径一致)
+fn reconcile_multi_hop_quote(chained: U256, router: U256, tolerance_bps: u32) -> MultiHopCheck {
+    let diff = if chained > router { chained - router } else { router - chained };
+    let divergence_bps = if chained.is_zero() {
+        if router.is_zero() { 0 } else { u64::MAX }
+    } else {
+        (diff * U256::from(10_000u64) / chained).min(U256::from(u64::MAX)).as_u64()
+    };
+    let diverged = divergence_bps > tolerance_bps as u64;
+
+    MultiHopCheck {
+        amount_out: if diverged { router } else { chained },
+        divergence_bps,
+        diverged,
+    }
 }
 
 /// 格式化流动性为可读格式
@@ -3646,3 +7015,2880 @@ fn format_liquidity(liquidity: u128) -> String {
         format!("{}", liquidity)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+    use executor::testing::MockMiddleware;
+    use models::testing as fixtures;
+
+    const QUOTER_V2_SIG: &str = "quoteExactInputSingle((address,address,uint256,uint24,uint160))";
+    const QUOTER_V1_SIG: &str = "quoteExactInputSingle(address,address,uint24,uint256,uint160)";
+    const AGGREGATE3_SIG: &str = "aggregate3((address,bool,bytes)[])";
+
+    /// QuoterV2 单跳报价的参数 (tokenIn, tokenOut, amountIn, fee)
+    fn quoter_v2_args(call: &executor::testing::MockCall) -> (Address, Address, U256, u32) {
+        use ethers::abi::ParamType;
+        let args = call.decode_args(&[ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::Uint(24),
+            ParamType::Uint(160),
+        ])]);
+        let Some(Token::Tuple(fields)) = args.into_iter().next() else { unreachable!() };
+        (
+            fields[0].clone().into_address().unwrap(),
+            fields[1].clone().into_address().unwrap(),
+            fields[2].clone().into_uint().unwrap(),
+            fields[3].clone().into_uint().unwrap().as_u32(),
+        )
+    }
+
+    /// QuoterV2 单跳报价的返回值
+    fn quoter_v2_output(amount_out: U256, gas_estimate: u64) -> Vec<Token> {
+        vec![
+            Token::Uint(amount_out),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(gas_estimate)),
+        ]
+    }
+
+    #[test]
+    fn test_per_token_amount_strategy() {
+        let usdt = Address::repeat_byte(0x01);
+        let wbtc = Address::repeat_byte(0x02);
+        let weth = Address::repeat_byte(0x03);
+
+        let mut config = ScannerExecutorConfig::default();
+        config.token_amount_strategies.insert(usdt, ExecutionAmountStrategy::parse("full").unwrap());
+        config.token_amount_strategies.insert(wbtc, ExecutionAmountStrategy::parse("max_usd:5000").unwrap());
+
+        // 稳定币路径使用完整金额
+        let usdt_input = U256::from(10_000u64) * U256::exp10(6);
+        let usdt_amount = config.amount_strategy_for(usdt).calculate_amount(usdt_input, 6, dec!(1));
+        assert_eq!(usdt_amount, usdt_input);
+
+        // WBTC 路径限制在 $5000: 1 BTC @ $60000 -> 0.08333333 BTC
+        let wbtc_amount = config.amount_strategy_for(wbtc).calculate_amount(U256::exp10(8), 8, dec!(60000));
+        assert_eq!(wbtc_amount, U256::from(8_333_333u64));
+
+        // 未单独配置的代币回退到全局默认 (80%)
+        let weth_amount = config.amount_strategy_for(weth).calculate_amount(U256::exp10(18), 18, dec!(3000));
+        assert_eq!(weth_amount, U256::exp10(17) * 8);
+
+        assert!(ExecutionAmountStrategy::parse("pct_max_usd:0.5:1000").is_some());
+        assert!(ExecutionAmountStrategy::parse("bogus").is_none());
+    }
+
+    fn test_pool(address: Address) -> PoolState {
+        PoolState {
+            address,
+            token0: Address::repeat_byte(0xa0),
+            token1: Address::repeat_byte(0xb0),
+            dex_type: DexType::UniswapV3,
+            fee: 500,
+            reserve0: U256::zero(),
+            reserve1: U256::zero(),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+            unlocked: true,
+            stable_state: None,
+            last_block: 0,
+            last_swap_block: 0,
+            local_accuracy: LocalQuoteAccuracy::default(),
+            last_updated: std::time::Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stable_swap_pools_skipped_unless_enabled() {
+        let stable_pool = || PoolState {
+            dex_type: DexType::PancakeSwapStable,
+            ..test_pool(Address::repeat_byte(0x51))
+        };
+
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+        scanner.add_pool(stable_pool()).await;
+        scanner.add_pool(test_pool(Address::repeat_byte(0x52))).await;
+        assert_eq!(scanner.pool_count().await, 1);
+        assert!(!scanner.pool_states.read().await.contains_key(&Address::repeat_byte(0x51)));
+
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let config = EventDrivenScannerConfig { stable_swap_pools_enabled: true, ..Default::default() };
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        scanner.add_pool(stable_pool()).await;
+        let states = scanner.pool_states.read().await;
+        let pool = states.get(&Address::repeat_byte(0x51)).expect("启用后应加载 StableSwap 池子");
+        // 精度在加载时写入，余额等刷新后才有
+        assert!(pool.stable_state.is_some());
+        assert!(!pool.has_price_data());
+    }
+
+    #[tokio::test]
+    async fn test_stable_valuation_anchors_to_chain_reference_stablecoin() {
+        // 价格服务里 USDT 和 USDC 略有偏离，FDUSD 没有实时价格
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        price_service.set_price("USDT", dec!(0.998)).await;
+        price_service.set_price("USDC", dec!(1.001)).await;
+        let fdusd = Address::repeat_byte(0xf0);
+        let amount = U256::from(10_000u64) * U256::exp10(6);
+
+        for (chain, expected_price) in [
+            (config_crate::ChainConfig::bsc(String::new(), String::new()), dec!(0.998)),
+            (config_crate::ChainConfig::base(String::new(), String::new()), dec!(1.001)),
+        ] {
+            let config = EventDrivenScannerConfig {
+                chain_id: chain.chain_id,
+                reference_stablecoin: chain.reference_stablecoin.clone(),
+                ..Default::default()
+            };
+            let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+            let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service.clone());
+            scanner.add_token_config(stable_token(fdusd, "FDUSD")).await;
+
+            // BSC 按 USDT 估值，Base 按 USDC 估值
+            let info = scanner.get_token_info(fdusd).await;
+            assert_eq!(info.price_usd, expected_price, "{}", chain.name);
+            assert_eq!(scanner.calculate_usd_value(amount, &info), expected_price * dec!(10000), "{}", chain.name);
+        }
+    }
+
+    /// 6 位精度的稳定币配置
+    fn stable_token(address: Address, symbol: &str) -> TokenConfig {
+        TokenConfig {
+            address,
+            symbol: symbol.to_string(),
+            decimals: 6,
+            is_stable: true,
+            price_symbol: symbol.to_string(),
+            optimal_input_amount: U256::zero(),
+        }
+    }
+
+    /// 三角报价测试的扫描器脚手架: 登记三个代币，添加 a→b→c→a 三个池子 (0x01/0x02/0x03)，
+    /// 使用 QuoterV2 并固定 Gas 价格，返回三个池子
+    async fn setup_triangle<M: Middleware + 'static>(
+        scanner: &EventDrivenScanner<M>,
+        tokens: [TokenConfig; 3],
+        fee: u32,
+        gas_price_wei: U256,
+    ) -> (PoolState, PoolState, PoolState) {
+        let [a, b, c] = [tokens[0].address, tokens[1].address, tokens[2].address];
+        for token in tokens {
+            scanner.add_token_config(token).await;
+        }
+        let pool = |addr: u8, token0, token1| PoolState { token0, token1, fee, ..test_pool(Address::repeat_byte(addr)) };
+        let (p1, p2, p3) = (pool(0x01, a, b), pool(0x02, b, c), pool(0x03, c, a));
+        for p in [&p1, &p2, &p3] {
+            scanner.add_pool(p.clone()).await;
+        }
+        *scanner.quoter_interface.write().await = Some(QuoterInterface::V2);
+        *scanner.gas_price_cache.write().await = Some(GasPriceCache {
+            price_wei: gas_price_wei,
+            last_updated: std::time::Instant::now(),
+        });
+        (p1, p2, p3)
+    }
+
+    /// 模拟 100 个区块，统计每个池子被纳入 Multicall 的次数
+    fn count_refresh_inclusions(dead_after_blocks: u64, refresh_interval: u64) -> (usize, usize) {
+        let active = Address::repeat_byte(0x01);
+        let dead = Address::repeat_byte(0x02);
+        let mut states: HashMap<Address, PoolState> =
+            [(active, test_pool(active)), (dead, test_pool(dead))].into_iter().collect();
+
+        let (mut active_count, mut dead_count) = (0, 0);
+        for block in 1..=100u64 {
+            // 活跃池每个区块都有 Swap，死池只在第 1 个区块有过 Swap
+            states.get_mut(&active).unwrap().last_swap_block = block;
+            let (selected, _) = select_pools_for_refresh(&mut states, block, dead_after_blocks, refresh_interval);
+            active_count += selected.iter().filter(|a| **a == active).count();
+            dead_count += selected.iter().filter(|a| **a == dead).count();
+        }
+        (active_count, dead_count)
+    }
+
+    #[test]
+    fn test_dead_pool_refreshed_less_often() {
+        // 未启用检测: 每个区块都刷新
+        assert_eq!(count_refresh_inclusions(0, 10), (100, 100));
+
+        // 20 个区块无 Swap 后降频: 区块 1-20 正常刷新，之后只在 30, 40, ..., 100 刷新
+        assert_eq!(count_refresh_inclusions(20, 10), (100, 28));
+
+        // 刷新间隔为 0: 死池不再刷新
+        assert_eq!(count_refresh_inclusions(20, 0), (100, 20));
+    }
+
+    #[test]
+    fn test_path_with_mismatched_pool_is_flagged() {
+        let (token_a, token_b, token_c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |address: Address, token0: Address, token1: Address| PoolState {
+            token0,
+            token1,
+            ..test_pool(address)
+        };
+        let (p1, p2, p3) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let mut states: HashMap<Address, PoolState> = [
+            (p1, pool(p1, token_a, token_b)),
+            // 反向排序的代币同样有效
+            (p2, pool(p2, token_c, token_b)),
+            (p3, pool(p3, token_c, token_a)),
+        ]
+        .into_iter()
+        .collect();
+
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a,
+            token_b,
+            token_c,
+            pools: Some([p1, p2, p3]),
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 100,
+        };
+        assert!(validate_path_pools(&path, &states).is_ok());
+
+        // pool2 不包含 token_b
+        states.insert(p2, pool(p2, token_a, token_c));
+        let err = validate_path_pools(&path, &states).unwrap_err();
+        assert!(err.starts_with("pool2"), "{}", err);
+
+        // 未配置池子时不校验
+        let unchecked = PoolPathConfig { pools: None, ..path };
+        assert!(validate_path_pools(&unchecked, &states).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_path_suspended_after_consecutive_failures_is_not_quoted() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let config = EventDrivenScannerConfig { path_failure_threshold: 2, ..Default::default() };
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        let (token_a, token_b, token_c) =
+            (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pools = [Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03)];
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a,
+            token_b,
+            token_c,
+            pools: Some(pools),
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 100,
+        };
+        let triangle = fixtures::v3_path(&[
+            (pools[0], token_a, token_b),
+            (pools[1], token_b, token_c),
+            (pools[2], token_c, token_a),
+        ]);
+        let opportunity = fixtures::opportunity("opp-cooldown").path(triangle).build();
+
+        scanner.record_path_outcome(&opportunity, false);
+        assert!(!scanner.path_cooldown.is_suspended(&pools));
+        scanner.record_path_outcome(&opportunity, false);
+        assert!(scanner.path_cooldown.is_suspended(&pools));
+        // 只冷却这组池子，同样代币的其它池子组合照常
+        assert!(!scanner.path_cooldown.is_suspended(&[Address::repeat_byte(0x04), pools[1], pools[2]]));
+
+        // 冷却中: 不报价、不执行
+        let states = vec![test_pool(pools[0])];
+        assert!(scanner.check_static_path(&path, &states, Decimal::from(10_000)).await.is_none());
+        assert!(scanner.get_rpc_stats().calls_by_type().iter().all(|(_, count)| *count == 0));
+        assert!(scanner.execute_arbitrage(opportunity.clone()).await.is_err());
+
+        // 成功后恢复
+        scanner.record_path_outcome(&opportunity, true);
+        assert!(!scanner.path_cooldown.is_suspended(&pools));
+    }
+
+    #[tokio::test]
+    async fn test_only_path_failures_count_toward_cooldown() {
+        let pool = Address::repeat_byte;
+        let pools = [pool(0x01), pool(0x02), pool(0x03)];
+        let scanner_with = |mock: Arc<MockMiddleware>, max_gas_cost_usd: Option<f64>, max_gas_price_gwei: f64| {
+            let config = EventDrivenScannerConfig {
+                path_failure_threshold: 1,
+                executor_config: ScannerExecutorConfig { max_gas_cost_usd, max_gas_price_gwei, ..Default::default() },
+                ..Default::default()
+            };
+            live_execution_scanner_with(mock, config)
+        };
+
+        // Gas 费用超过上限: 与路径无关，不计入失败
+        let mock = Arc::new(MockMiddleware::new());
+        mock.set_estimate_gas(Ok(U256::from(500_000u64)));
+        let scanner = scanner_with(mock.clone(), Some(0.000_001), 100.0).await;
+        let opp = live_path_opportunity(&scanner, "opp-gas", pools, pool(0xf1)).await;
+        let result = scanner.execute_arbitrage(opp).await.unwrap();
+        assert_eq!(result.status, models::ArbitrageStatus::Failed);
+        assert!(mock.sent_transactions().is_empty());
+        assert!(!scanner.path_cooldown.is_suspended(&pools));
+
+        // Gas 价格超过上限: 同样与路径无关
+        let mock = Arc::new(MockMiddleware::new());
+        mock.set_gas_price(U256::from(50_000_000_000u64));
+        let scanner = scanner_with(mock.clone(), None, 1.0).await;
+        let opp = live_path_opportunity(&scanner, "opp-gas-price", pools, pool(0xf1)).await;
+        let result = scanner.execute_arbitrage(opp).await.unwrap();
+        assert_eq!(result.status, models::ArbitrageStatus::Failed);
+        assert!(result.error_message.unwrap_or_default().contains("GasPriceTooHigh"));
+        assert!(mock.sent_transactions().is_empty());
+        assert!(!scanner.path_cooldown.is_suspended(&pools));
+
+        // 上链 revert: 路径失败，进入冷却
+        let mock = Arc::new(MockMiddleware::new());
+        let scanner = scanner_with(mock.clone(), None, 100.0).await;
+        mock.set_receipt(executor::testing::receipt(H256::repeat_byte(0x42), false, U256::zero()));
+        let opp = live_path_opportunity(&scanner, "opp-revert", pools, pool(0xf1)).await;
+        scanner.execute_arbitrage(opp).await.unwrap();
+        assert_eq!(mock.sent_transactions().len(), 1);
+        assert!(scanner.path_cooldown.is_suspended(&pools));
+    }
+
+    #[test]
+    fn test_estimate_flash_pool_fee_follows_selector_rules() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |addr: u8, token0, token1, fee, liquidity| PoolState {
+            token0,
+            token1,
+            fee,
+            liquidity: Some(liquidity),
+            ..test_pool(Address::repeat_byte(addr))
+        };
+        let (p1, p2, p3) = (pool(0x01, a, b, 100, 1), pool(0x02, b, c, 100, 1), pool(0x03, c, a, 100, 1));
+        let deep_ac = pool(0x04, a, c, 3000, 1_000);
+        let shallow_ab = pool(0x05, a, b, 500, 10);
+        let unrelated = pool(0x06, b, c, 100, 1_000_000);
+        let cached = vec![p1.clone(), p2.clone(), p3.clone(), deep_ac.clone(), shallow_ab.clone(), unrelated];
+
+        // swap 路径里的池子和不含借入代币的池子不参与；流动性高者优先
+        assert_eq!(estimate_flash_pool_fee(&cached, [a, b, c], [&p1, &p2, &p3], None), 3000);
+        // 固定闪电贷池
+        assert_eq!(estimate_flash_pool_fee(&cached, [a, b, c], [&p1, &p2, &p3], Some(shallow_ab.address)), 500);
+        // 没有候选时按第一跳费率估计
+        assert_eq!(estimate_flash_pool_fee(&cached[..3], [a, b, c], [&p1, &p2, &p3], None), 100);
+    }
+
+    #[tokio::test]
+    async fn test_profit_erased_by_flash_fee_rejected_at_detection() {
+        let (provider, mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let tokens = [stable_token(a, "USDC"), stable_token(b, "USDT"), stable_token(c, "DAI")];
+        let (p1, p2, p3) = setup_triangle(&scanner, tokens, 100, U256::zero()).await;
+        scanner.add_pool(PoolState { token0: a, token1: c, ..test_pool(Address::repeat_byte(0x04)) }).await;
+
+        // 每跳都报价 10,003 (6 位精度): 10,000 输入换回 10,003，毛利润 $3
+        let quote = ethers::abi::encode(&quoter_v2_output(U256::from(10_003u64) * U256::exp10(6), 100_000));
+        for _ in 0..3 {
+            mock.push::<Bytes, _>(Bytes::from(quote.clone())).unwrap();
+        }
+
+        // 闪电贷池费率 0.05%: 借 10,000 付 $5，毛利润被吃掉
+        let input = U256::from(10_000u64) * U256::exp10(6);
+        let result = scanner.simulate_and_calculate_profit(input, a, b, c, &p1, &p2, &p3).await.unwrap();
+        assert_eq!(result.net_profit_usd, dec!(-2));
+        assert!(result.net_profit_usd <= Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_input_amount_overrides_swap_size() {
+        let (provider, mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let tokens = [stable_token(a, "USDC"), stable_token(b, "USDT"), stable_token(c, "DAI")];
+        let (p1, p2, p3) = setup_triangle(&scanner, tokens, 100, U256::zero()).await;
+
+        // 每跳报价 2,010: 2,000 输入换回 2,010
+        let quote = ethers::abi::encode(&quoter_v2_output(U256::from(2_010u64) * U256::exp10(6), 100_000));
+        for _ in 0..3 {
+            mock.push::<Bytes, _>(Bytes::from(quote.clone())).unwrap();
+        }
+
+        // 池子没有流动性数据，固定输入仍按 2,000 报价
+        let fixed = U256::from(2_000u64) * U256::exp10(6);
+        let (input, result) = scanner
+            .find_optimal_input(a, b, c, &p1, &p2, &p3, InputSizing::Fixed(fixed))
+            .await
+            .unwrap();
+        assert_eq!(input, fixed);
+        assert_eq!(result.amount_out, U256::from(2_010u64) * U256::exp10(6));
+        assert!(result.net_profit_usd > Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_min_profit_overrides_layer_on_gas_tier() {
+        let (usdc, usdt, dai) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let (wbtc, weth) = (Address::repeat_byte(0x04), Address::repeat_byte(0x05));
+        let mut overrides = MinProfitOverrides::default();
+        overrides.insert(&[usdc, usdt, dai], dec!(0.5));
+        overrides.insert(&[wbtc, weth], dec!(20));
+
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let config = EventDrivenScannerConfig { min_profit_overrides: overrides, ..Default::default() };
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        *scanner.gas_price_cache.write().await = Some(GasPriceCache {
+            price_wei: U256::exp10(10), // 10 gwei: 正常档 $5
+            last_updated: std::time::Instant::now(),
+        });
+
+        assert_eq!(scanner.get_dynamic_min_profit().await, dec!(5));
+        assert_eq!(scanner.min_profit_for_path([dai, usdc, usdt]).await, dec!(0.5));
+        assert_eq!(scanner.min_profit_for_path([usdc, wbtc, weth]).await, dec!(20));
+        // 未配置覆盖的路径仍使用 Gas 分档门槛
+        assert_eq!(scanner.min_profit_for_path([usdc, usdt, weth]).await, dec!(5));
+    }
+
+    #[tokio::test]
+    async fn test_skewed_pool_rejected_by_price_sanity_check() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let config = EventDrivenScannerConfig { max_spot_deviation_pct: dec!(10), ..Default::default() };
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        for (address, symbol, decimals) in [(a, "USDC", 6), (b, "USDT", 6), (c, "DAI", 18)] {
+            scanner.add_token_config(TokenConfig {
+                address,
+                symbol: symbol.to_string(),
+                decimals,
+                is_stable: true,
+                price_symbol: symbol.to_string(),
+                optimal_input_amount: U256::zero(),
+            }).await;
+        }
+        let usd = |n: u64, decimals: usize| U256::from(n) * U256::exp10(decimals);
+
+        // 正常路径: 每跳在 $1 附近，只差手续费
+        let input = usd(10_000, 6);
+        let sane = [usd(9_995, 6), usd(9_990, 18), usd(9_985, 6)];
+        assert!(scanner.sanity_check_prices([a, b, c], input, sane).await.is_ok());
+
+        // 被操纵的 USDT/DAI 池子: 9,995 USDT 换出 13,000 DAI，报价看似大赚
+        let skewed = [usd(9_995, 6), usd(13_000, 18), usd(12_990, 6)];
+        let reason = scanner.sanity_check_prices([a, b, c], input, skewed).await.unwrap_err();
+        assert!(reason.contains("第 2 跳 USDT -> DAI"), "{}", reason);
+
+        // 偏离上限为 0 (默认) 时不检查
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let unchecked = EventDrivenScanner::new(
+            EventDrivenScannerConfig::default(),
+            Arc::new(provider),
+            services::create_price_service(services::PriceServiceConfig::default()),
+        );
+        assert!(unchecked.sanity_check_prices([a, b, c], input, skewed).await.is_ok());
+
+        // 价格未知时不判断
+        assert_eq!(hop_spot_deviation_pct(input, (6, Decimal::ONE), skewed[0], (6, Decimal::ZERO)), None);
+        assert_eq!(hop_spot_deviation_pct(input, (6, Decimal::ONE), usd(11_000, 6), (6, Decimal::ONE)), Some(dec!(10)));
+        // 18 位精度的大额成交按整数精度折算
+        let whale = U256::exp10(15 + 18);
+        assert_eq!(
+            hop_spot_deviation_pct(whale, (18, Decimal::ONE), whale + whale / 10_000, (18, Decimal::ONE)),
+            Some(dec!(0.01))
+        );
+        assert_eq!(hop_spot_deviation_pct(U256::zero(), (6, Decimal::ONE), input, (6, Decimal::ONE)), None);
+    }
+
+    #[tokio::test]
+    async fn test_gas_priced_in_chain_native_token() {
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        price_service.set_price("ETH", dec!(3000)).await;
+        price_service.set_price("MATIC", dec!(0.5)).await;
+        let scanner_for = |symbol: &str| {
+            let config = EventDrivenScannerConfig { native_token_symbol: symbol.to_string(), ..Default::default() };
+            let (provider, _mock) = Provider::mocked();
+            EventDrivenScanner::new(config, Arc::new(provider), price_service.clone())
+        };
+
+        assert_eq!(scanner_for("ETH").native_unit_price().await, dec!(3000));
+        assert_eq!(scanner_for("MATIC").native_unit_price().await, dec!(0.5));
+        // BNB 还没有价格: 使用 BNB 的兜底价而不是 ETH 价格
+        assert_eq!(scanner_for("BNB").native_unit_price().await, dec!(300));
+    }
+
+    #[tokio::test]
+    async fn test_native_denomination_works_without_usd_prices() {
+        let weth = Address::repeat_byte(0xee);
+        let (b, c) = (Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let config = EventDrivenScannerConfig {
+            native_denomination: Some(NativeDenomination {
+                wrapped_native: weth,
+                min_profit: dec!(0.001),
+                min_swap_value: dec!(0.01),
+            }),
+            ..Default::default()
+        };
+        // 价格服务从未启动，没有任何 USD 价格
+        let (provider, mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        let volatile = |address, symbol: &str| TokenConfig { decimals: 18, is_stable: false, ..stable_token(address, symbol) };
+        let tokens = [TokenConfig { price_symbol: "ETH".to_string(), ..volatile(weth, "WETH") }, volatile(b, "TKB"), volatile(c, "TKC")];
+        // Gas 价格 1 gwei
+        let (p1, p2, p3) = setup_triangle(&scanner, tokens, 100, U256::exp10(9)).await;
+        assert_eq!(scanner.get_token_info(weth).await.price_usd, Decimal::ONE);
+        assert_eq!(scanner.get_token_info(b).await.price_usd, Decimal::ZERO);
+
+        // 1 WETH 转一圈换回 1.01 WETH，每跳 gas 100,000
+        let quote = ethers::abi::encode(&quoter_v2_output(U256::exp10(16) * 101, 100_000));
+        for _ in 0..3 {
+            mock.push::<Bytes, _>(Bytes::from(quote.clone())).unwrap();
+        }
+        let result = scanner
+            .simulate_and_calculate_profit(U256::exp10(18), weth, b, c, &p1, &p2, &p3)
+            .await
+            .unwrap();
+
+        // 毛利润 0.01 - gas 350,000 × 1 gwei - 闪电贷费 0.01% = 0.00955 WETH
+        assert_eq!(result.gas_cost_usd, dec!(0.00035));
+        assert_eq!(result.net_profit_usd, dec!(0.00955));
+        assert_eq!(scanner.get_dynamic_min_profit().await, dec!(0.001));
+        // 执行时合约 min_profit = 0.001 WETH
+        assert_eq!(scanner.contract_min_profit([weth, b, c]).await, U256::exp10(15));
+
+        assert_eq!(native_relative_price(false, dec!(3000), Some(dec!(2000))), dec!(1.5));
+        assert_eq!(native_relative_price(false, dec!(3000), None), Decimal::ZERO);
+
+        // 机会里原生代币数额存放在 *_native 字段，USD 字段没有原生代币价格时为 0 而不是原生代币数量
+        let mut opp = fixtures::opportunity("native")
+            .net_profit_usd(result.net_profit_usd)
+            .gas_cost_usd(result.gas_cost_usd)
+            .build();
+        scanner.denominate_opportunity(&mut opp).await;
+        assert_eq!(opp.net_profit_native, Some(dec!(0.00955)));
+        assert_eq!(opp.gas_cost_native, Some(dec!(0.00035)));
+        assert_eq!(opp.net_profit_usd, Decimal::ZERO);
+        assert_eq!(opp.gas_cost_usd, Decimal::ZERO);
+        assert_eq!(opp.ranking_net_profit(), dec!(0.00955));
+        let published = serde_json::to_value(&opp).unwrap();
+        assert_eq!(published["net_profit_native"].as_str().unwrap().parse::<Decimal>().unwrap(), dec!(0.00955));
+
+        // 价格恢复后 USD 字段按实时价格折算
+        scanner.price_service.set_price("ETH", dec!(2000)).await;
+        let mut opp = fixtures::opportunity("native")
+            .net_profit_usd(result.net_profit_usd)
+            .gas_cost_usd(result.gas_cost_usd)
+            .build();
+        scanner.denominate_opportunity(&mut opp).await;
+        assert_eq!(opp.net_profit_usd, dec!(19.1));
+        assert_eq!(opp.gas_cost_usd, dec!(0.7));
+        assert_eq!(opp.net_profit_native, Some(dec!(0.00955)));
+    }
+
+    #[tokio::test]
+    async fn test_pools_without_path_mappings_are_flagged() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (mapped, unmapped) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        scanner.add_pool(test_pool(mapped)).await;
+        scanner.add_pool(test_pool(unmapped)).await;
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: Address::repeat_byte(0xa0),
+            token_b: Address::repeat_byte(0xb0),
+            token_c: Address::repeat_byte(0xc0),
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 100,
+        };
+        scanner.add_pool_path_mappings(vec![(mapped, vec![path])]).await;
+
+        assert_eq!(scanner.pools_without_path_mappings().await, vec![unmapped]);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_token_path_rejected_and_never_quoted() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (token_a, token_c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xc0));
+        let trigger = Address::repeat_byte(0x01);
+        let duplicate = PoolPathConfig {
+            path_name: "A->A->C->A".to_string(),
+            triangle_name: "AAC".to_string(),
+            token_a,
+            token_b: token_a,
+            token_c,
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 100,
+        };
+        assert!(validate_distinct_tokens(token_a, token_a, token_c).unwrap_err().contains("配置错误"));
+        assert!(validate_distinct_tokens(token_a, token_c, token_c).is_err());
+        assert!(validate_distinct_tokens(token_a, Address::repeat_byte(0xb0), token_c).is_ok());
+
+        // 加载时被拒绝
+        scanner.add_pool_path_mappings(vec![(trigger, vec![duplicate.clone()])]).await;
+        assert!(scanner.get_paths_for_pool(trigger).await.is_empty());
+
+        // 直接检查也不会发起任何报价
+        let pools = vec![test_pool(trigger)];
+        assert!(scanner.check_static_path(&duplicate, &pools, Decimal::from(10_000)).await.is_none());
+        assert!(scanner.get_rpc_stats().calls_by_type().iter().all(|(_, count)| *count == 0));
+    }
+
+    #[test]
+    fn test_price_impact_above_ceiling_is_rejected() {
+        // 1:1 现货价格, 0.05% 手续费
+        let mut pool = test_pool(Address::repeat_byte(0x01));
+        pool.sqrt_price_x96 = Some(U256::one() << 96);
+        pool.liquidity = Some(1_000_000);
+        let amount_in = U256::exp10(18);
+
+        // 扣除手续费后正好按现货价成交: 无价格影响
+        let no_impact = U256::from(999_500_000_000_000_000u64);
+        assert_eq!(hop_price_impact_pct(&pool, pool.token0, amount_in, no_impact), Some(Decimal::ZERO));
+
+        // 再少拿 2%: 价格影响 2%
+        let impacted = U256::from(979_510_000_000_000_000u64);
+        let impact = max_price_impact_pct(&[
+            (&pool, pool.token0, amount_in, no_impact),
+            (&pool, pool.token1, amount_in, impacted),
+        ]);
+        assert_eq!(impact, dec!(2));
+
+        assert!(price_impact_exceeded(impact, dec!(1)));
+        assert!(!price_impact_exceeded(impact, dec!(3)));
+        // 上限为 0 不限制
+        assert!(!price_impact_exceeded(impact, Decimal::ZERO));
+
+        // 没有 V3 价格数据的池子不参与计算
+        let no_price = test_pool(Address::repeat_byte(0x02));
+        assert_eq!(hop_price_impact_pct(&no_price, no_price.token0, amount_in, impacted), None);
+    }
+
+    #[test]
+    fn test_profit_delta_from_balances() {
+        let balance = |symbol: &str, usd: Decimal| WalletBalance {
+            symbol: symbol.to_string(),
+            token_address: String::new(),
+            balance: String::new(),
+            usd_value: usd,
+        };
+        let before = vec![balance("USDC", dec!(1000)), balance("WETH", dec!(3000))];
+        let after = vec![balance("USDC", dec!(1008)), balance("WETH", dec!(3000))];
+
+        // 预估 $10，实际 $8: 高估 $2 (20%)
+        let realized = realized_profit_from_balances(&before, &after);
+        assert_eq!(realized, dec!(8));
+        let delta = ProfitDelta::new(dec!(10), realized);
+        assert_eq!(delta.delta_usd(), dec!(-2));
+        assert_eq!(delta.error_pct(), Some(dec!(-20)));
+        assert_eq!(ProfitDelta::new(Decimal::ZERO, realized).error_pct(), None);
+
+        // 汇总: 一笔高估 $2、一笔低估 $1，平均 -$0.5
+        let mut stats = ExecutionStats::default();
+        assert_eq!(stats.avg_profit_delta_usd(), Decimal::ZERO);
+        stats.record_profit_delta(&delta);
+        stats.record_profit_delta(&ProfitDelta::new(dec!(5), dec!(6)));
+        assert_eq!(stats.profit_delta_samples, 2);
+        assert_eq!(stats.avg_profit_delta_usd(), dec!(-0.5));
+    }
+
+    #[test]
+    fn test_large_profit_shortfall_triggers_alert() {
+        let balance = |usd: Decimal| WalletBalance {
+            symbol: "USDC".to_string(),
+            token_address: String::new(),
+            balance: String::new(),
+            usd_value: usd,
+        };
+        let hop = (Address::repeat_byte(0x01), Address::repeat_byte(0xa0), Address::repeat_byte(0xb0));
+        let opportunity = fixtures::opportunity("opp-shortfall").path(fixtures::v3_path(&[hop])).net_profit_usd(dec!(50)).build();
+        let tx_hash = Some(H256::repeat_byte(0x11));
+
+        // 预估 $50，余额差显示实际亏损 $5: 偏差 $55 超过 $20 阈值
+        let realized = realized_profit_from_balances(&[balance(dec!(1000))], &[balance(dec!(995))]);
+        let shortfall = ProfitDelta::new(opportunity.expected_profit_usd, realized);
+        let alert = profit_deviation_alert("Ethereum", &opportunity, tx_hash, &shortfall, dec!(20)).unwrap();
+        assert_eq!(alert.title, "利润偏差告警 - Ethereum");
+        assert!(alert.message.contains("$-55.0000"));
+        assert!(alert.details.contains("opp-shortfall"));
+        assert!(alert.details.contains(&format!("{:?}", H256::repeat_byte(0x11))));
+
+        // 阈值内的偏差、未配置阈值时不告警
+        let close = ProfitDelta::new(dec!(50), dec!(45));
+        assert!(profit_deviation_alert("Ethereum", &opportunity, tx_hash, &close, dec!(20)).is_none());
+        assert!(profit_deviation_alert("Ethereum", &opportunity, tx_hash, &shortfall, Decimal::ZERO).is_none());
+    }
+
+    fn test_swap_event(pool_address: Address, n: u8) -> SwapEvent {
+        SwapEvent {
+            pool_address,
+            sender: Address::zero(),
+            amount0_in: U256::exp10(18),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::exp10(18),
+            block_number: 100,
+            tx_hash: H256::repeat_byte(n),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_channel_overflow_counts_dropped_events() {
+        use ethers::providers::MockProvider;
+
+        let (pool_a, pool_b) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        // 容量 4 的通道一次推入 10 条事件 (两个池子交替)
+        let overflow = || {
+            let (tx, rx) = broadcast::channel(4);
+            for n in 0..10u8 {
+                tx.send(test_swap_event(if n % 2 == 0 { pool_a } else { pool_b }, n)).unwrap();
+            }
+            (tx, rx)
+        };
+        let scanner = |newest_only: bool| {
+            let (provider, _mock) = Provider::<MockProvider>::mocked();
+            let config = EventDrivenScannerConfig { swap_lag_newest_only: newest_only, ..Default::default() };
+            let price_service = services::create_price_service(services::PriceServiceConfig::default());
+            EventDrivenScanner::new(config, Arc::new(provider), price_service)
+        };
+
+        // 默认策略: 记录被跳过的 6 条，剩余 4 条照常按顺序处理
+        let (_tx, mut rx) = overflow();
+        let default_scanner = scanner(false);
+        let result = rx.recv().await;
+        assert!(matches!(result, Err(broadcast::error::RecvError::Lagged(6))));
+        let events = default_scanner.swap_events_from_recv(result, &mut rx).await.unwrap();
+        assert!(events.is_empty());
+        assert_eq!(default_scanner.execution_stats.read().await.dropped_events, 6);
+        assert_eq!(rx.recv().await.unwrap().tx_hash, H256::repeat_byte(6));
+
+        // 只处理最新事件: 清空积压，每个池子保留最后一条
+        let (_tx, mut rx) = overflow();
+        let newest_scanner = scanner(true);
+        let result = rx.recv().await;
+        let events = newest_scanner.swap_events_from_recv(result, &mut rx).await.unwrap();
+        let hashes: Vec<H256> = events.iter().map(|e| e.tx_hash).collect();
+        assert_eq!(hashes, vec![H256::repeat_byte(8), H256::repeat_byte(9)]);
+        assert_eq!(newest_scanner.execution_stats.read().await.dropped_events, 8);
+        assert!(matches!(rx.try_recv(), Err(broadcast::error::TryRecvError::Empty)));
+
+        // 通道关闭
+        let (tx, mut rx) = broadcast::channel::<SwapEvent>(4);
+        drop(tx);
+        let result = rx.recv().await;
+        assert!(default_scanner.swap_events_from_recv(result, &mut rx).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quoter_v1_fallback() {
+        // 只部署了 QuoterV1 的链
+        let provider = MockMiddleware::new();
+        provider.on_call_revert(QUOTER_V2_SIG, "");
+        provider.on_call(QUOTER_V1_SIG, &[Token::Uint(U256::from(2_999_000_000u64))]);
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+        assert_eq!(scanner.quoter_interface().await, None);
+
+        let (token_in, token_out) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0));
+        let quote = scanner.quote_exact_input(token_in, token_out, 500, U256::exp10(18), None).await.unwrap();
+        assert_eq!(quote.amount_out, U256::from(2_999_000_000u64));
+        assert_eq!(quote.gas_estimate, U256::from(QUOTER_V1_HOP_GAS_ESTIMATE));
+        assert_eq!(scanner.quoter_interface().await, Some(QuoterInterface::V1));
+
+        // 检测完成后直接走 V1，不再先尝试 V2
+        scanner.quote_exact_input(token_in, token_out, 500, U256::exp10(18), None).await.unwrap();
+        assert_eq!(scanner.rpc_stats.total_calls(RpcCallType::QuoterV2Quote), 1);
+        assert_eq!(scanner.rpc_stats.total_calls(RpcCallType::QuoterV1Quote), 2);
+    }
+
+    /// QuoterV2 单跳报价，前几次调用按脚本返回错误
+    fn flaky_quoter_v2(failures: &[&str]) -> Arc<MockMiddleware> {
+        let failures: std::sync::Mutex<std::collections::VecDeque<String>> =
+            std::sync::Mutex::new(failures.iter().map(|f| f.to_string()).collect());
+        let provider = MockMiddleware::new();
+        provider.on_call_fn(QUOTER_V2_SIG, move |_| match failures.lock().unwrap().pop_front() {
+            Some(failure) => Err(failure),
+            None => Ok(vec![
+                Token::Uint(U256::from(2_999_000_000u64)),
+                Token::Uint(U256::one() << 96),
+                Token::Uint(U256::from(1u64)),
+                Token::Uint(U256::from(85_000u64)),
+            ]),
+        });
+        Arc::new(provider)
+    }
+
+    #[tokio::test]
+    async fn test_quote_retries_transient_errors_only() {
+        let quote_with = |provider: Arc<MockMiddleware>| async move {
+            let config = EventDrivenScannerConfig { quote_retry_delay_ms: 0, ..Default::default() };
+            let price_service = services::create_price_service(services::PriceServiceConfig::default());
+            let scanner = EventDrivenScanner::new(config, provider, price_service);
+            scanner
+                .quote_exact_input(Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), 500, U256::exp10(18), None)
+                .await
+        };
+
+        // 第一次超时，重试后成功
+        let flaky = flaky_quoter_v2(&["error sending request: operation timed out"]);
+        let quote = quote_with(flaky.clone()).await.unwrap();
+        assert_eq!(quote.amount_out, U256::from(2_999_000_000u64));
+        assert_eq!(flaky.call_count(QUOTER_V2_SIG), 2);
+
+        // 429 限流连续出现: 最多重试 2 次
+        let limited = flaky_quoter_v2(&["HTTP 429 Too Many Requests"; 5]);
+        assert!(quote_with(limited.clone()).await.is_err());
+        assert_eq!(limited.call_count(QUOTER_V2_SIG), 3);
+
+        // 合约 revert 不重试
+        let reverted = flaky_quoter_v2(&["execution reverted: SPL"; 5]);
+        assert!(quote_with(reverted.clone()).await.is_err());
+        assert_eq!(reverted.call_count(QUOTER_V2_SIG), 1);
+
+        assert!(is_transient_quote_error("connection reset by peer"));
+        assert!(!is_transient_quote_error("execution reverted: timeout")); // revert 原因里的字样不算
+    }
+
+    #[test]
+    fn test_interval_usage_reports_only_new_calls() {
+        let stats = RpcStats::new();
+        stats.record_call(RpcCallType::QuoterV2Quote, 10, true);
+        stats.record_call(RpcCallType::QuoterV2Quote, 30, false);
+        stats.record_call(RpcCallType::GetGasPrice, 5, true);
+
+        let first = stats.take_interval_usage();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0], models::RpcUsage {
+            call_type: "QuoterV2报价".to_string(),
+            calls: 2,
+            failed_calls: 1,
+            total_duration_ms: 40,
+        });
+        assert_eq!(first[0].avg_latency_ms(), 20);
+
+        // 下一个区间只有新增的调用，没有调用的类型不输出
+        stats.record_call(RpcCallType::QuoterV2Quote, 8, true);
+        let second = stats.take_interval_usage();
+        assert_eq!(second.len(), 1);
+        assert_eq!((second[0].calls, second[0].failed_calls, second[0].total_duration_ms), (1, 0, 8));
+        assert!(stats.take_interval_usage().is_empty());
+        assert_eq!(stats.total_calls(RpcCallType::QuoterV2Quote), 3);
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_after_activity() {
+        use ethers::providers::MockProvider;
+
+        let (provider, _mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        // 模拟一段运行: 5 个机会, 执行 4 次 (3 成功 1 失败), 若干 RPC 调用
+        {
+            let mut stats = scanner.execution_stats.write().await;
+            stats.opportunities_found = 5;
+            stats.total_executions = 4;
+            stats.successful_executions = 3;
+            stats.failed_executions = 1;
+            stats.total_profit_usd = dec!(42.5);
+        }
+        for _ in 0..3 {
+            scanner.rpc_stats.record_call(RpcCallType::QuoterV2Quote, 10, true);
+        }
+        scanner.rpc_stats.record_call(RpcCallType::MulticallRefreshPools, 20, false);
+
+        let summary = scanner.session_summary().await;
+        assert_eq!(summary.chain_id, 1);
+        assert_eq!(summary.opportunities_found, 5);
+        assert_eq!(summary.total_executions, 4);
+        assert_eq!(summary.success_rate(), 75.0);
+        assert_eq!(summary.total_profit_usd, dec!(42.5));
+        assert_eq!(summary.total_rpc_calls(), 4);
+        assert!(summary.rpc_calls.contains(&("QuoterV2报价".to_string(), 3)));
+
+        let report = summary.format_report();
+        assert!(report.contains("发现机会: 5"));
+        assert!(report.contains("成功率: 75.0%"));
+        assert!(report.contains("累计利润: $42.50"));
+        // 没有调用过的 RPC 类型不出现在报告里
+        assert!(!report.contains("StableSwap报价"));
+    }
+
+    #[tokio::test]
+    async fn test_contract_min_profit_stricter_than_off_chain_is_flagged() {
+        let (usdc, usdt, dai) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let (contract, other_contract) = (Address::repeat_byte(0xc1), Address::repeat_byte(0xc2));
+        let path = |name: &str, token_c: Address, arbitrage_contract: Option<Address>| PoolPathConfig {
+            path_name: name.to_string(),
+            triangle_name: String::new(),
+            token_a: usdc,
+            token_b: usdt,
+            token_c,
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract,
+            priority: 0,
+        };
+
+        let conflicts_with_threshold = |threshold: u64| {
+            let paths = vec![
+                path("USDC->USDT->DAI", dai, None),
+                path("USDC->USDT->WETH", Address::repeat_byte(0xd0), None),
+                path("灰度合约路径", dai, Some(other_contract)),
+            ];
+            async move {
+                let provider = MockMiddleware::new();
+                provider.on_call("minProfitThreshold()", &[Token::Uint(U256::from(threshold))]);
+                let provider = Arc::new(provider);
+                // 静态门槛 $2，稳定币三角覆盖为 $0.5
+                let mut min_profit_overrides = MinProfitOverrides::default();
+                min_profit_overrides.insert(&[usdc, usdt, dai], dec!(0.5));
+                let config = EventDrivenScannerConfig {
+                    enable_dynamic_profit: false,
+                    min_profit_usd: dec!(2),
+                    min_profit_overrides,
+                    executor_config: ScannerExecutorConfig { arbitrage_contract: Some(contract), ..Default::default() },
+                    ..Default::default()
+                };
+                let price_service = services::create_price_service(services::PriceServiceConfig::default());
+                let scanner = EventDrivenScanner::new(config, provider, price_service);
+                scanner.add_token_config(TokenConfig {
+                    address: usdc,
+                    symbol: "USDC".to_string(),
+                    decimals: 6,
+                    is_stable: true,
+                    price_symbol: "USDC".to_string(),
+                    optimal_input_amount: U256::zero(),
+                }).await;
+                scanner.add_pool_path_mapping(Address::repeat_byte(0x01), paths).await;
+                scanner.contract_min_profit_conflicts(contract).await.unwrap()
+            }
+        };
+
+        // 合约要求 1 USDC: 只有 $0.5 门槛的稳定币三角会被合约拒绝，灰度合约的路径不参与比较
+        let conflicts = conflicts_with_threshold(1_000_000).await;
+        assert_eq!(conflicts, vec![MinProfitConflict {
+            path_name: "USDC->USDT->DAI".to_string(),
+            token_symbol: "USDC".to_string(),
+            off_chain_min_profit: dec!(0.5),
+            off_chain_wei: U256::from(500_000u64),
+            on_chain_wei: U256::from(1_000_000u64),
+        }]);
+
+        // 合约要求 3 USDC: 两条默认合约路径都比合约宽松；阈值为 0 时不限制
+        assert_eq!(conflicts_with_threshold(3_000_000).await.len(), 2);
+        assert!(conflicts_with_threshold(0).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_contract_min_profit_check_uses_lowest_gas_tier_and_skips_unpriced_paths() {
+        let (usdc, usdt, dai, unpriced) = (
+            Address::repeat_byte(0xa0),
+            Address::repeat_byte(0xb0),
+            Address::repeat_byte(0xc0),
+            Address::repeat_byte(0xe0),
+        );
+        let contract = Address::repeat_byte(0xc1);
+        let path = |name: &str, token_a: Address, token_b: Address| PoolPathConfig {
+            path_name: name.to_string(),
+            triangle_name: String::new(),
+            token_a,
+            token_b,
+            token_c: dai,
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+
+        let provider = MockMiddleware::new();
+        provider.on_call("minProfitThreshold()", &[Token::Uint(U256::from(2_000_000u64))]);
+        // 动态门槛: 启动时 Gas 很高 (当前档 $30)，但 Gas 回落后最低档只有 $1
+        let config = EventDrivenScannerConfig {
+            enable_dynamic_profit: true,
+            executor_config: ScannerExecutorConfig { arbitrage_contract: Some(contract), ..Default::default() },
+            ..Default::default()
+        };
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        *scanner.gas_price_cache.write().await = Some(GasPriceCache {
+            price_wei: U256::from(100u64) * U256::exp10(9),
+            last_updated: std::time::Instant::now(),
+        });
+        assert_eq!(scanner.get_dynamic_min_profit().await, dec!(30));
+        scanner.add_token_config(stable_token(usdc, "USDC")).await;
+        scanner
+            .add_pool_path_mapping(
+                Address::repeat_byte(0x01),
+                vec![path("USDC->USDT->DAI", usdc, usdt), path("XYZ->USDT->DAI", unpriced, usdt)],
+            )
+            .await;
+
+        // 没有价格的起始代币无法换算，不报告为冲突
+        let conflicts = scanner.contract_min_profit_conflicts(contract).await.unwrap();
+        assert_eq!(conflicts, vec![MinProfitConflict {
+            path_name: "USDC->USDT->DAI".to_string(),
+            token_symbol: "USDC".to_string(),
+            off_chain_min_profit: dec!(1),
+            off_chain_wei: U256::from(1_000_000u64),
+            on_chain_wei: U256::from(2_000_000u64),
+        }]);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_flash_pool_looked_up_by_path() {
+        use ethers::providers::MockProvider;
+
+        let (provider, _mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let tokens = [Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0)];
+        let flash_pool = Address::repeat_byte(0xf1);
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: tokens[0],
+            token_b: tokens[1],
+            token_c: tokens[2],
+            pools: None,
+            flash_pool: Some(flash_pool),
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        scanner.add_pool_path_mapping(Address::repeat_byte(0x01), vec![path]).await;
+
+        let swap_pools = [Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03)];
+        assert_eq!(scanner.pinned_flash_pool(tokens, &swap_pools).await, Some(flash_pool));
+        // 反方向的路径不使用该配置
+        let reversed = [tokens[0], tokens[2], tokens[1]];
+        assert_eq!(scanner.pinned_flash_pool(reversed, &swap_pools).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_paths_execute_against_their_configured_contract() {
+        use ethers::providers::MockProvider;
+
+        let default_contract = Address::repeat_byte(0xc1);
+        let new_contract = Address::repeat_byte(0xc2);
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig { arbitrage_contract: Some(default_contract), ..Default::default() },
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        let (a, b, c, d) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0), Address::repeat_byte(0xd0));
+        let path = |token_c, contract| PoolPathConfig {
+            path_name: "A->B->X->A".to_string(),
+            triangle_name: "ABX".to_string(),
+            token_a: a,
+            token_b: b,
+            token_c,
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: contract,
+            priority: 0,
+        };
+        scanner.add_pool_path_mapping(Address::repeat_byte(0x01), vec![path(c, None), path(d, Some(new_contract))]).await;
+
+        let opportunity = |token_c| {
+            let (p1, p2, p3) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+            let triangle = fixtures::v3_path(&[(p1, a, b), (p2, b, token_c), (p3, token_c, a)]);
+            fixtures::opportunity(format!("opp-{:?}", token_c)).path(triangle).build()
+        };
+
+        // 同一条链上的两条路径分别使用默认合约和灰度中的新合约
+        assert_eq!(scanner.executor_config_for(&opportunity(c)).await.arbitrage_contract, Some(default_contract));
+        assert_eq!(scanner.executor_config_for(&opportunity(d)).await.arbitrage_contract, Some(new_contract));
+        assert_eq!(scanner.config.executor_config.arbitrage_contract, Some(default_contract));
+    }
+
+    #[test]
+    fn test_chain_contracts_for_optimism_and_avalanche() {
+        for (chain_id, name, quoter) in [
+            (10, "Optimism", "0x61fFE014bA17989E743c5F6cB21bF9697530B21e"),
+            (43114, "Avalanche", "0xbe0F5544EC67e9B3b2D979aaA43f18Fd87E6257F"),
+        ] {
+            let contracts = ChainContractsConfig::for_chain(chain_id).unwrap();
+            assert_eq!(contracts.chain_name, name);
+            assert_eq!(contracts.quoter_address, quoter.parse::<Address>().unwrap());
+            assert_eq!(contracts.multicall_address, ChainContractsConfig::ethereum().multicall_address);
+
+            // 扫描器使用该链的 Quoter 地址
+            let config = EventDrivenScannerConfig { chain_id, ..Default::default() };
+            let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+            let price_service = services::create_price_service(services::PriceServiceConfig::default());
+            let scanner = EventDrivenScanner::with_chain_config(config, Arc::new(provider), price_service, contracts);
+            assert_eq!(scanner.chain_name(), name);
+            assert_eq!(scanner.chain_id(), chain_id);
+            assert_eq!(scanner.quoter_address, quoter.parse::<Address>().unwrap());
+        }
+        assert!(ChainContractsConfig::for_chain(250).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_path_evaluation_stops_at_time_budget() {
+        fn opportunity(net_profit_usd: u64) -> ArbitrageOpportunity {
+            fixtures::opportunity(format!("opp-{}", net_profit_usd)).net_profit_usd(Decimal::from(net_profit_usd)).build()
+        }
+
+        // 每条路径的报价都要 40ms，后面的路径利润更高
+        let paths: Vec<u64> = vec![5, 8, 20, 30, 50];
+        let slow_quote = |profit: &u64| {
+            let profit = *profit;
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+                Some(opportunity(profit))
+            }
+        };
+
+        // 100ms 预算: 检查 3 条后超时 (40/80/120ms)，剩余 2 条跳过，返回已找到的最佳结果
+        let start = tokio::time::Instant::now();
+        let outcome = evaluate_paths_within_budget(&paths, Some(std::time::Duration::from_millis(100)), 1, slow_quote).await;
+        assert_eq!(start.elapsed(), std::time::Duration::from_millis(120));
+        assert_eq!(outcome.checked, 3);
+        assert_eq!(outcome.found, 3);
+        assert_eq!(outcome.skipped, 2);
+        assert_eq!(outcome.best.unwrap().net_profit_usd, Decimal::from(20));
+
+        // 不限制时检查全部路径
+        let outcome = evaluate_paths_within_budget(&paths, None, 1, slow_quote).await;
+        assert_eq!((outcome.checked, outcome.skipped), (5, 0));
+        assert_eq!(outcome.best.unwrap().net_profit_usd, Decimal::from(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_path_evaluation_reduces_wall_time() {
+        fn opportunity(id: usize, net_profit_usd: u64) -> ArbitrageOpportunity {
+            fixtures::opportunity(format!("opp-{}", id)).net_profit_usd(Decimal::from(net_profit_usd)).build()
+        }
+
+        // 6 条路径，每条报价 50ms；
+        // 路径 1 和 4 利润相同且最高，路径 1 优先级靠前但报价更慢，后完成
+        let paths: Vec<(usize, u64, u64)> = vec![(0, 5, 50), (1, 30, 120), (2, 8, 50), (3, 20, 50), (4, 30, 50), (5, 1, 50)];
+        let slow_quote = |path: &(usize, u64, u64)| {
+            let (id, profit, latency_ms) = *path;
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(latency_ms)).await;
+                Some(opportunity(id, profit))
+            }
+        };
+
+        let start = tokio::time::Instant::now();
+        let sequential = evaluate_paths_within_budget(&paths, None, 1, slow_quote).await;
+        assert_eq!(start.elapsed(), std::time::Duration::from_millis(370));
+
+        // 并发 3 条: 150ms 完成，路径 4 先于路径 1 完成但结果仍取路径 1
+        let start = tokio::time::Instant::now();
+        let concurrent = evaluate_paths_within_budget(&paths, None, 3, slow_quote).await;
+        assert_eq!(start.elapsed(), std::time::Duration::from_millis(150));
+
+        assert_eq!((concurrent.checked, concurrent.found, concurrent.skipped), (6, 6, 0));
+        assert_eq!(concurrent.best.unwrap().id, sequential.best.unwrap().id);
+
+        // 时间预算按发起时刻判断: 并发 2 条、65ms 预算，0ms 发起路径 0/1，50ms 发起路径 2，
+        // 100ms 再有空位时已超预算，其余 3 条跳过
+        let outcome =
+            evaluate_paths_within_budget(&paths, Some(std::time::Duration::from_millis(65)), 2, slow_quote).await;
+        assert_eq!((outcome.checked, outcome.skipped), (3, 3));
+    }
+
+    #[tokio::test]
+    async fn test_fee_whitelist_excludes_high_fee_pools_from_quoting() {
+        let (usdc, usdt, weth) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let config = EventDrivenScannerConfig {
+            fee_tier_whitelist: FeeTierWhitelist::from_config(&[], &[100, 500], &[500, 3000]),
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        for (address, symbol, is_stable) in [(usdc, "USDC", true), (usdt, "USDT", true), (weth, "WETH", false)] {
+            scanner.add_token_config(TokenConfig {
+                address,
+                symbol: symbol.to_string(),
+                decimals: 18,
+                is_stable,
+                price_symbol: symbol.to_string(),
+                optimal_input_amount: U256::exp10(18),
+            }).await;
+        }
+
+        let pool = |n: u8, token0: Address, token1: Address, fee: u32| PoolState {
+            token0,
+            token1,
+            fee,
+            ..test_pool(Address::repeat_byte(n))
+        };
+        let all_pools = vec![
+            pool(0x01, usdc, usdt, 100),
+            pool(0x02, usdc, usdt, 500),
+            pool(0x03, usdc, usdt, 3000),
+            pool(0x04, usdt, weth, 3000),
+            pool(0x05, usdt, weth, 10000),
+            pool(0x06, weth, usdc, 500),
+            pool(0x07, weth, Address::repeat_byte(0xd0), 500),
+        ];
+
+        // 稳定币对只保留 100/500，非稳定币对只保留 500/3000，无关代币对不参与
+        let allowed = scanner.fee_whitelisted_pools(&all_pools, [usdc, usdt, weth]).await;
+        let allowed_addrs: Vec<Address> = allowed.iter().map(|p| p.address).collect();
+        assert_eq!(
+            allowed_addrs,
+            vec![Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x04), Address::repeat_byte(0x06)]
+        );
+        assert!(!scanner.fee_tier_allowed(&all_pools[2]).await);
+
+        // USDT->WETH 只剩 3000 档，直接选中，不再逐个报价 (mock 没有报价返回值，报价会失败)
+        let best = scanner.find_best_pool_by_output_rpc(&allowed, usdt, weth, U256::exp10(18)).await.unwrap();
+        assert_eq!(best.fee, 3000);
+        assert!(scanner.find_best_pool_by_output_rpc(&all_pools, usdt, weth, U256::exp10(18)).await.is_none());
+
+        // 指定代币对的配置优先于分类默认值
+        let whitelist = FeeTierWhitelist::from_config(
+            &[format!("{:?}/{:?}:3000", usdt, usdc), "bad-entry".to_string()],
+            &[100, 500],
+            &[],
+        );
+        assert_eq!(whitelist.pairs.len(), 1);
+        assert!(whitelist.allows(usdc, usdt, 3000, true));
+        assert!(!whitelist.allows(usdc, usdt, 100, true));
+        assert!(whitelist.allows(usdt, weth, 10000, false));
+    }
+
+    #[tokio::test]
+    async fn test_blocklisted_pool_is_never_quoted() {
+        use ethers::providers::{MockError, MockProvider};
+
+        let (provider, mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let trigger = Address::repeat_byte(0x01);
+        let blocked = Address::repeat_byte(0x02);
+        // 黑名单池子是 C->A 一跳
+        let blocked_pool = PoolState {
+            token0: Address::repeat_byte(0xc0),
+            token1: Address::repeat_byte(0xa0),
+            ..test_pool(blocked)
+        };
+        scanner.add_pool(test_pool(trigger)).await;
+        scanner.add_pool(blocked_pool.clone()).await;
+
+        let path = |pools: [Address; 3]| PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: Address::repeat_byte(0xa0),
+            token_b: Address::repeat_byte(0xb0),
+            token_c: Address::repeat_byte(0xc0),
+            pools: Some(pools),
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        scanner.add_pool_path_mappings(vec![
+            (trigger, vec![path([trigger, Address::repeat_byte(0x03), blocked])]),
+            (blocked, vec![path([trigger, Address::repeat_byte(0x03), blocked])]),
+        ]).await;
+        assert_eq!(scanner.pool_path_mapping_count().await, (2, 2));
+
+        // 加入黑名单后立即移出监控，经过它的路径也被移除
+        scanner.block_pool(blocked).await;
+        assert_eq!(scanner.pool_count().await, 1);
+        assert_eq!(scanner.pool_path_mapping_count().await, (0, 0));
+
+        // 重新加载池子也不会恢复
+        scanner.add_pool(blocked_pool).await;
+        assert_eq!(scanner.pool_count().await, 1);
+
+        // 黑名单池子的 Swap 事件不触发检测
+        let event = SwapEvent {
+            pool_address: blocked,
+            sender: Address::zero(),
+            amount0_in: U256::exp10(18),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::exp10(18),
+            block_number: 100,
+            tx_hash: H256::repeat_byte(0x11),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        };
+        assert!(scanner.handle_swap_event(event).await.is_none());
+
+        // 全程没有发出任何 RPC 请求 (包括报价)
+        assert!(matches!(mock.assert_request("eth_call", ()), Err(MockError::EmptyRequests)));
+    }
+
+    #[tokio::test]
+    async fn test_swap_without_price_data_refreshes_pool_immediately() {
+        use ethers::abi::{self, Token};
+        use ethers::providers::MockProvider;
+
+        let (provider, mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+        let pool = Address::repeat_byte(0x01);
+        scanner.add_pool(test_pool(pool)).await;
+
+        // Multicall 返回: slot0 (sqrtPriceX96, tick) + liquidity
+        let sqrt_price = U256::from(2u64).pow(U256::from(96u64));
+        let slot0 = abi::encode(&[Token::Uint(sqrt_price), Token::Int(U256::from(60u64))]);
+        let liquidity = abi::encode(&[Token::Uint(U256::from(5_000_000u64))]);
+        let results = vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(slot0)]),
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(liquidity)]),
+        ];
+        mock.push::<Bytes, _>(Bytes::from(abi::encode(&[Token::Array(results)]))).unwrap();
+
+        let event = SwapEvent {
+            pool_address: pool,
+            sender: Address::zero(),
+            amount0_in: U256::exp10(18),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::exp10(18),
+            block_number: 100,
+            tx_hash: H256::repeat_byte(0x11),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        };
+        scanner.handle_swap_event(event.clone()).await;
+
+        // 同一个事件处理过程中就拿到了价格状态，只发出一次单池子 Multicall
+        assert_eq!(scanner.get_rpc_stats().total_calls(RpcCallType::MulticallRefreshPools), 1);
+        let state = scanner.pool_states.read().await.get(&pool).cloned().unwrap();
+        assert_eq!(state.sqrt_price_x96, Some(sqrt_price));
+        assert_eq!(state.tick, Some(60));
+        assert_eq!(state.liquidity, Some(5_000_000));
+
+        // 事件自带价格数据时不额外刷新
+        let with_price = SwapEvent { sqrt_price_x96: Some(sqrt_price), liquidity: Some(1), tick: Some(60), ..event };
+        scanner.handle_swap_event(with_price).await;
+        assert_eq!(scanner.get_rpc_stats().total_calls(RpcCallType::MulticallRefreshPools), 1);
+    }
+
+    #[tokio::test]
+    async fn test_locked_pool_from_slot0_is_skipped_in_selection() {
+        use ethers::abi::{self, Token};
+        use ethers::providers::MockProvider;
+
+        let (provider, mock) = Provider::<MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+        let pool = Address::repeat_byte(0x01);
+        scanner.add_pool(test_pool(pool)).await;
+
+        // 完整的 slot0 返回值: sqrtPriceX96, tick, observationIndex, observationCardinality,
+        // observationCardinalityNext, feeProtocol, unlocked
+        let multicall_result = |unlocked: bool| {
+            let slot0 = abi::encode(&[
+                Token::Uint(U256::from(2u64).pow(U256::from(96u64))),
+                Token::Int(U256::from(60u64)),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::one()),
+                Token::Uint(U256::one()),
+                Token::Uint(U256::zero()),
+                Token::Bool(unlocked),
+            ]);
+            let liquidity = abi::encode(&[Token::Uint(U256::from(5_000_000u64))]);
+            let results = vec![
+                Token::Tuple(vec![Token::Bool(true), Token::Bytes(slot0)]),
+                Token::Tuple(vec![Token::Bool(true), Token::Bytes(liquidity)]),
+            ];
+            Bytes::from(abi::encode(&[Token::Array(results)]))
+        };
+
+        mock.push::<Bytes, _>(multicall_result(false)).unwrap();
+        scanner.refresh_stale_pools(&[pool]).await.unwrap();
+        assert!(!scanner.pool_states.read().await[&pool].unlocked);
+        assert!(scanner.selectable_pool_states().await.is_empty());
+        assert!(scanner.detect_arbitrage_for_pool(pool, Decimal::from(10_000)).await.is_none());
+        assert_eq!(scanner.get_rpc_stats().total_calls(RpcCallType::QuoterV2Quote), 0);
+
+        // 回调结束后再次刷新，池子重新参与选池
+        mock.push::<Bytes, _>(multicall_result(true)).unwrap();
+        scanner.refresh_stale_pools(&[pool]).await.unwrap();
+        let selectable: Vec<Address> = scanner.selectable_pool_states().await.iter().map(|p| p.address).collect();
+        assert_eq!(selectable, vec![pool]);
+    }
+
+    /// Multicall3 aggregate3 的返回值: 每个 balanceOf 都成功
+    fn balance_results(balances: &[U256]) -> Vec<Token> {
+        let results = balances
+            .iter()
+            .map(|b| Token::Tuple(vec![Token::Bool(true), Token::Bytes(ethers::abi::encode(&[Token::Uint(*b)]))]))
+            .collect();
+        vec![Token::Array(results)]
+    }
+
+    /// 查询合约 0xcc 余额、没有代币配置的余额查询上下文
+    fn test_balance_fetcher<M: Middleware>(provider: Arc<M>, rpc_stats: Option<Arc<RpcStats>>) -> BalanceFetcher<M> {
+        BalanceFetcher {
+            provider,
+            price_service: services::create_price_service(services::PriceServiceConfig::default()),
+            token_configs: HashMap::new(),
+            multicall_address: DEFAULT_MULTICALL3.parse().unwrap(),
+            contract_address: Address::repeat_byte(0xcc),
+            rpc_stats,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balances_fetched_with_single_multicall() {
+        use ethers::providers::MockProvider;
+
+        let (provider, mock) = Provider::<MockProvider>::mocked();
+        let tokens: Vec<Address> = (1..=4u8).map(Address::repeat_byte).collect();
+        let amounts: Vec<U256> = (1..=4u64).map(|i| U256::from(i) * U256::exp10(18)).collect();
+        mock.push::<Bytes, _>(Bytes::from(ethers::abi::encode(&balance_results(&amounts)))).unwrap();
+
+        let stats = Arc::new(RpcStats::new());
+        let fetcher = test_balance_fetcher(Arc::new(provider), Some(stats.clone()));
+        let balances = EventDrivenScanner::get_balances_async(&fetcher, &tokens, None).await;
+
+        // 4 个代币只发一次 Multicall
+        assert_eq!(stats.total_calls(RpcCallType::MulticallBalances), 1);
+        assert_eq!(stats.total_calls(RpcCallType::Erc20BalanceOf), 0);
+        let fetched: Vec<String> = balances.iter().map(|b| b.balance.clone()).collect();
+        let expected: Vec<String> = amounts.iter().map(|a| format_token_amount(*a, 18)).collect();
+        assert_eq!(fetched, expected);
+    }
+
+    #[tokio::test]
+    async fn test_after_balances_read_at_confirmed_block() {
+        // 回执在区块 100，区块号依次推进
+        let tx_hash = H256::repeat_byte(0x77);
+        let provider = MockMiddleware::new();
+        provider.set_block_numbers(&[100, 101, 102, 103]);
+        provider.set_receipt(TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(U64::from(100)),
+            ..Default::default()
+        });
+        provider.on_call(AGGREGATE3_SIG, &balance_results(&[U256::exp10(18)]));
+        let provider = Arc::new(provider);
+        let poll = std::time::Duration::from_millis(1);
+        let timeout = std::time::Duration::from_secs(5);
+
+        // 不要求确认: 不查回执，按最新区块读取
+        let immediate = EventDrivenScanner::settled_snapshot_block(provider.as_ref(), tx_hash, 0, poll, timeout).await;
+        assert_eq!(immediate, None);
+
+        // 2 个确认: 等到区块 102 才返回，并固定在交易所在的区块 100 读取
+        let block = EventDrivenScanner::settled_snapshot_block(provider.as_ref(), tx_hash, 2, poll, timeout).await;
+        assert_eq!(block, Some(100));
+        assert_eq!(provider.block_number_count(), 3);
+
+        let fetcher = test_balance_fetcher(provider.clone(), None);
+        let balances =
+            EventDrivenScanner::get_balances_async(&fetcher, &[Address::repeat_byte(0x01)], block.map(BlockId::from))
+                .await;
+        assert_eq!(balances[0].balance, format_token_amount(U256::exp10(18), 18));
+        let call_blocks: Vec<Option<BlockId>> = provider.calls(AGGREGATE3_SIG).iter().map(|c| c.block).collect();
+        assert_eq!(call_blocks, vec![Some(BlockId::from(100u64))]);
+    }
+
+    #[tokio::test]
+    async fn test_balance_fetches_bounded_by_semaphore() {
+        // 每次 eth_call 耗时 20ms
+        let provider = MockMiddleware::new();
+        provider.set_call_delay(std::time::Duration::from_millis(20));
+        provider.on_call(AGGREGATE3_SIG, &balance_results(&[U256::exp10(18)]));
+        let provider = Arc::new(provider);
+        let fetch_burst = |permits: Option<Arc<Semaphore>>, stats: Arc<RpcStats>| {
+            (0..6)
+                .map(|_| {
+                    let (fetcher, permits) = (test_balance_fetcher(provider.clone(), Some(stats.clone())), permits.clone());
+                    tokio::spawn(async move {
+                        EventDrivenScanner::get_balances_limited(permits, &fetcher, &[Address::repeat_byte(0x01)], None)
+                            .await
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 上限 2: 6 个并发执行的余额查询全部完成，但同时在途的 RPC 不超过 2 个
+        let config = ScannerExecutorConfig { max_concurrent_balance_fetches: 2, ..Default::default() };
+        let stats = Arc::new(RpcStats::new());
+        for handle in fetch_burst(balance_fetch_permits(&config), stats.clone()) {
+            assert_eq!(handle.await.unwrap().len(), 1);
+        }
+        assert_eq!(stats.total_calls(RpcCallType::MulticallBalances), 6);
+        assert_eq!(provider.max_concurrent_calls(), 2);
+
+        // 关闭余额查询: 不发任何 RPC
+        let disabled = ScannerExecutorConfig { fetch_balances: false, ..Default::default() };
+        assert!(balance_fetch_permits(&disabled).is_none());
+        let stats = Arc::new(RpcStats::new());
+        for handle in fetch_burst(balance_fetch_permits(&disabled), stats.clone()) {
+            assert!(handle.await.unwrap().is_empty());
+        }
+        assert_eq!(stats.total_calls(RpcCallType::MulticallBalances), 0);
+    }
+
+    #[tokio::test]
+    async fn test_execution_permits_cap_in_flight_sends_not_detections() {
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig { max_concurrent_executions: 2, ..Default::default() },
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        #[derive(Default)]
+        struct InFlight {
+            current: AtomicU64,
+            max: AtomicU64,
+        }
+        impl InFlight {
+            async fn hold(&self) {
+                let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+                self.current.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let sends = InFlight::default();
+        let detections = InFlight::default();
+        let executions = futures_util::future::join_all((0..6).map(|_| async {
+            let _permit = scanner.acquire_execution_permit(ExecutionLane::Batch).await;
+            sends.hold().await
+        }));
+        let detecting = futures_util::future::join_all((0..6).map(|_| detections.hold()));
+        let (done, _) = tokio::join!(executions, detecting);
+
+        assert_eq!(done.len(), 6);
+        assert_eq!(sends.max.load(Ordering::SeqCst), 2);
+        assert_eq!(detections.max.load(Ordering::SeqCst), 6);
+    }
+
+    /// 真实执行流程的测试脚手架: 钱包为合约 owner，模拟和回执都成功，闪电贷池 (token0 = 起始代币) 流动性充足；
+    /// 通过公开 mempool 发送，不查询余额
+    async fn live_execution_scanner(
+        mock: Arc<MockMiddleware>,
+        executor_config: ScannerExecutorConfig,
+    ) -> EventDrivenScanner<MockMiddleware> {
+        live_execution_scanner_with(mock, EventDrivenScannerConfig { executor_config, ..Default::default() }).await
+    }
+
+    /// 同 live_execution_scanner，但可以调整扫描器配置
+    async fn live_execution_scanner_with(
+        mock: Arc<MockMiddleware>,
+        config: EventDrivenScannerConfig,
+    ) -> EventDrivenScanner<MockMiddleware> {
+        use executor::testing::{receipt, EXECUTE_ARBITRAGE_SIG, TEST_PRIVATE_KEY};
+        let wallet = TEST_PRIVATE_KEY.parse::<LocalWallet>().unwrap().with_chain_id(1u64);
+        let tx_hash = H256::repeat_byte(0x42);
+        let profit = U256::exp10(16);
+        mock.on_call("owner()", &[Token::Address(wallet.address())]);
+        mock.on_call(EXECUTE_ARBITRAGE_SIG, &[Token::Uint(profit)]);
+        mock.on_call("balanceOf(address)", &[Token::Uint(U256::exp10(24))]);
+        mock.on_call("token0()", &[Token::Address(Address::repeat_byte(0xa0))]);
+        mock.on_call("token1()", &[Token::Address(Address::repeat_byte(0xb0))]);
+        mock.on_call("fee()", &[Token::Uint(U256::from(100u64))]);
+        mock.on_call("liquidity()", &[Token::Uint(U256::exp10(24))]);
+        mock.set_send_hash(tx_hash);
+        mock.set_receipt(receipt(tx_hash, true, profit));
+
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig {
+                auto_execute: true,
+                dry_run: false,
+                use_flashbots: false,
+                use_public_mempool: true,
+                fetch_balances: false,
+                arbitrage_contract: Some(Address::repeat_byte(0xcc)),
+                ..config.executor_config
+            },
+            ..config
+        };
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, mock, price_service);
+        scanner.set_wallet(wallet).await;
+        scanner
+    }
+
+    /// 在 live_execution_scanner 上登记一条 A→B→C→A 静态路径 (固定闪电贷池)，返回该路径上的机会
+    async fn live_path_opportunity(
+        scanner: &EventDrivenScanner<MockMiddleware>,
+        id: &str,
+        pools: [Address; 3],
+        flash_pool: Address,
+    ) -> ArbitrageOpportunity {
+        let [a, b, c] = [Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0)];
+        let path = PoolPathConfig {
+            path_name: id.to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: a,
+            token_b: b,
+            token_c: c,
+            pools: Some(pools),
+            flash_pool: Some(flash_pool),
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        scanner.add_pool_path_mapping(pools[0], vec![path]).await;
+        fixtures::opportunity(id)
+            .path(fixtures::v3_path(&[(pools[0], a, b), (pools[1], b, c), (pools[2], c, a)]))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_caps_executions_and_allocates_distinct_nonces() {
+        let mock = Arc::new(MockMiddleware::new());
+        mock.set_nonce(U256::from(7));
+        mock.set_call_delay(std::time::Duration::from_millis(10));
+        let scanner = live_execution_scanner(
+            mock.clone(),
+            ScannerExecutorConfig { max_concurrent_executions: 2, ..Default::default() },
+        )
+        .await;
+        let mut opportunities = Vec::new();
+        for i in 0..4u8 {
+            let pools = [Address::repeat_byte(3 * i + 1), Address::repeat_byte(3 * i + 2), Address::repeat_byte(3 * i + 3)];
+            let flash_pool = Address::repeat_byte(0xf0 + i);
+            opportunities.push(live_path_opportunity(&scanner, &format!("opp-{}", i), pools, flash_pool).await);
+        }
+
+        // 同一收集窗口内四个互不冲突的机会，由分发者并发执行
+        futures_util::future::join_all(
+            opportunities.into_iter().map(|opp| scanner.enqueue_for_execution(opp, tracing::Span::none())),
+        )
+        .await;
+
+        // 节点 pending nonce 一直是 7 (交易都还没被打包)，每笔交易仍然拿到不同的 nonce
+        let mut nonces: Vec<U256> = mock.sent_transactions().iter().filter_map(|tx| tx.nonce().copied()).collect();
+        nonces.sort();
+        assert_eq!(nonces, (7..11u64).map(U256::from).collect::<Vec<_>>());
+        assert_eq!(scanner.get_execution_stats().await.total_executions, 4);
+        // 同时在途的执行不超过 2 个: 每个执行最多并发 4 个 eth_call (读取闪电贷池信息)
+        assert!(mock.max_concurrent_calls() <= 8, "{}", mock.max_concurrent_calls());
+    }
+
+    /// 发出的 executeArbitrage 交易里的每跳最小输出 (minOut1..3)
+    fn sent_hop_minimums(tx: &ethers::types::transaction::eip2718::TypedTransaction) -> Vec<U256> {
+        use ethers::abi::ParamType;
+        let mut fields = vec![ParamType::Address; 4];
+        fields.extend([ParamType::Uint(24), ParamType::Uint(24), ParamType::Uint(24)]);
+        fields.extend([ParamType::Uint(256), ParamType::Uint(256), ParamType::Address, ParamType::Uint(24)]);
+        fields.extend([ParamType::Uint(256), ParamType::Uint(256), ParamType::Uint(256)]);
+        let data = tx.data().unwrap();
+        let Some(Token::Tuple(params)) = ethers::abi::decode(&[ParamType::Tuple(fields)], &data[4..]).unwrap().pop() else {
+            unreachable!()
+        };
+        params[11..].iter().map(|token| token.clone().into_uint().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pools_from_successful_execution_defer_new_opportunities() {
+        let mock = Arc::new(MockMiddleware::new());
+        let config = EventDrivenScannerConfig { post_execution_pool_cooldown_secs: 12, ..Default::default() };
+        let scanner = live_execution_scanner_with(mock.clone(), config).await;
+        let pool = Address::repeat_byte;
+
+        // 真实执行成功后，路径上的池子进入冷却
+        let executed = live_path_opportunity(&scanner, "opp-1", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+        let result = scanner.execute_arbitrage(executed.clone()).await.unwrap();
+        assert_eq!(result.status, models::ArbitrageStatus::Confirmed);
+        assert_eq!(scanner.pool_cooldown.first_suspended(&scanner.get_path_pools(&executed)), Some(pool(0x01)));
+
+        // 紧接着检测到的、共用池子 0x02 的机会被推迟，不占用池子锁、不发交易
+        let next = live_path_opportunity(&scanner, "opp-2", [pool(0x04), pool(0x02), pool(0x05)], pool(0xf2)).await;
+        let result = scanner.execute_arbitrage(next).await.unwrap();
+        assert_eq!(result.status, models::ArbitrageStatus::Pending);
+        assert!(result.error_message.unwrap().contains("推迟"));
+        assert_eq!(scanner.get_execution_stats().await.pool_cooldown_deferred, 1);
+        assert!(scanner.executing_pools.read().await.is_empty());
+        assert_eq!(mock.sent_transactions().len(), 1);
+
+        // 冷却结束前重试不会重新报价，机会继续等待
+        scanner.retry_deferred_executions().await;
+        assert_eq!(scanner.deferred_executions.read().await.len(), 1);
+        assert_eq!(mock.call_count(QUOTER_V2_SIG), 0);
+
+        // 没有冷却的池子照常执行
+        let unrelated = live_path_opportunity(&scanner, "opp-3", [pool(0x06), pool(0x07), pool(0x08)], pool(0xf3)).await;
+        scanner.execute_arbitrage(unrelated).await.unwrap();
+        assert_eq!(mock.sent_transactions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execution_derives_hop_minimums_from_detected_quotes() {
+        let mock = Arc::new(MockMiddleware::new());
+        let scanner = live_execution_scanner(mock.clone(), ScannerExecutorConfig::default()).await;
+        let pool = Address::repeat_byte;
+        let mut opp = live_path_opportunity(&scanner, "opp", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+        opp.hop_amounts_out = vec![U256::from(2_000_000u64), U256::from(4_000_000u64), U256::from(10_000_000u64)];
+
+        scanner.execute_arbitrage(opp).await.unwrap();
+
+        // 每跳各自按链上报价扣除 0.5% 滑点，不会逐跳累积折扣
+        let sent = mock.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(
+            sent_hop_minimums(&sent[0]),
+            vec![U256::from(1_990_000u64), U256::from(3_980_000u64), U256::from(9_950_000u64)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execution_converts_profit_to_configured_token() {
+        use ethers::abi::ParamType;
+        let usdc = Address::repeat_byte(0xd1);
+        let mock = Arc::new(MockMiddleware::new());
+        mock.on_call("getPool(address,address,uint24)", &[Token::Address(Address::repeat_byte(0xe5))]);
+        let scanner = live_execution_scanner(
+            mock.clone(),
+            ScannerExecutorConfig { profit_token: Some(usdc), profit_convert_fee: Some(500), ..Default::default() },
+        )
+        .await;
+        let pool = Address::repeat_byte;
+        let opp = live_path_opportunity(&scanner, "opp", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+
+        scanner.execute_arbitrage(opp).await.unwrap();
+
+        let sent = mock.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let mut fields = vec![ParamType::Address; 4];
+        fields.extend([ParamType::Uint(24), ParamType::Uint(24), ParamType::Uint(24)]);
+        fields.extend([ParamType::Uint(256), ParamType::Uint(256), ParamType::Address, ParamType::Uint(24)]);
+        fields.extend([ParamType::Uint(256), ParamType::Uint(256), ParamType::Uint(256)]);
+        let data = sent[0].data().unwrap();
+        let Some(Token::Tuple(params)) = ethers::abi::decode(&[ParamType::Tuple(fields)], &data[4..]).unwrap().pop() else {
+            unreachable!()
+        };
+        assert_eq!(params[9], Token::Address(usdc));
+        assert_eq!(params[10], Token::Uint(U256::from(500)));
+    }
+
+    #[tokio::test]
+    async fn test_execution_requotes_hops_when_detected_quotes_do_not_apply() {
+        let provider = MockMiddleware::new();
+        // 每跳输出为输入的 2 倍
+        provider.on_call_fn(QUOTER_V2_SIG, |call| {
+            let (_, _, amount_in, _) = quoter_v2_args(call);
+            Ok(quoter_v2_output(amount_in * 2, 100_000))
+        });
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+        let tokens = [Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0)];
+        let (p1, p2, p3) = setup_triangle(
+            &scanner,
+            tokens.map(|token| stable_token(token, "TKN")),
+            500,
+            U256::exp10(9),
+        )
+        .await;
+        let [a, b, c] = tokens;
+        let opp = fixtures::opportunity("opp")
+            .path(fixtures::v3_path(&[(p1.address, a, b), (p2.address, b, c), (p3.address, c, a)]))
+            .input_amount(U256::from(1_000u64))
+            .build();
+
+        // 检测时没有逐跳报价 (或执行金额已调整): 按执行金额逐跳链上报价
+        assert_eq!(
+            scanner.execution_hop_quotes(&opp).await,
+            Some([U256::from(2_000u64), U256::from(4_000u64), U256::from(8_000u64)])
+        );
+    }
+
+    /// 按推送顺序记录执行结果对应的机会 id
+    #[derive(Default)]
+    struct RecordingSink(std::sync::Mutex<Vec<String>>);
+
+    #[async_trait::async_trait]
+    impl services::EventSink for RecordingSink {
+        async fn publish_opportunity(&self, _chain_id: u64, _opportunity: &ArbitrageOpportunity) {}
+
+        async fn publish_execution(&self, _chain_id: u64, result: &models::ArbitrageResult) {
+            self.0.lock().unwrap().push(result.opportunity.id.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_queues_bundles_by_value_before_execution_permit() {
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig {
+                max_concurrent_executions: 1,
+                max_in_flight_bundles: 1,
+                use_flashbots: true,
+                dry_run: false,
+                arbitrage_contract: Some(Address::repeat_byte(0xcc)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let sink = Arc::new(RecordingSink::default());
+        let scanner = Arc::new(
+            EventDrivenScanner::new(config, Arc::new(provider), price_service).with_event_sink(sink.clone()),
+        );
+
+        // 在途的 Bundle 占着唯一的 Bundle 名额和执行许可；它的池子仍被锁定，
+        // 后续机会拿到名额和许可后因池子冲突直接返回，推送顺序即执行顺序
+        let pool = Address::repeat_byte(0x01);
+        scanner.executing_pools.write().await.insert(pool);
+        let dispatch = |id: &str, net_profit_usd: u64, block_number: u64| {
+            let scanner = scanner.clone();
+            let opp = fixtures::opportunity(id)
+                .pools(&[pool])
+                .net_profit_usd(Decimal::from(net_profit_usd))
+                .block_number(block_number)
+                .build();
+            tokio::spawn(async move { scanner.dispatch_execution(opp, ExecutionLane::Batch).await })
+        };
+        let wait_until_queued = |n: usize| {
+            let scanner = scanner.clone();
+            async move {
+                while scanner.bundle_slots.queued() < n {
+                    tokio::task::yield_now().await;
+                }
+            }
+        };
+
+        let slot = scanner.bundle_slots.acquire(Decimal::ONE, 100).await.unwrap();
+        let permit = scanner.execution_permits.acquire().await.unwrap();
+        let low = dispatch("low", 5, 100);
+        wait_until_queued(1).await;
+        let high = dispatch("high", 50, 100);
+        // 两个都在 Bundle 队列里排队，而不是堵在执行许可上
+        wait_until_queued(2).await;
+
+        drop(slot);
+        drop(permit);
+        low.await.unwrap();
+        high.await.unwrap();
+        assert_eq!(*sink.0.lock().unwrap(), vec!["high".to_string(), "low".to_string()]);
+
+        // 排队期间出了新区块: 上一区块的机会出队，不再执行
+        let slot = scanner.bundle_slots.acquire(Decimal::ONE, 100).await.unwrap();
+        let stale = dispatch("stale", 80, 100);
+        wait_until_queued(1).await;
+        scanner
+            .handle_new_block(NewBlockEvent {
+                block_number: 101,
+                block_hash: H256::repeat_byte(0x01),
+                timestamp: 0,
+                base_fee: None,
+                gas_used: U256::zero(),
+                gas_limit: U256::zero(),
+            })
+            .await;
+        stale.await.unwrap();
+        drop(slot);
+        assert_eq!(scanner.bundle_slots.queued(), 0);
+        assert_eq!(sink.0.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_priority_lane_not_starved_by_saturated_permits() {
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig {
+                max_concurrent_executions: 2,
+                priority_min_profit_usd: Some(100.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        // 低价值执行占满普通许可
+        let _busy = scanner.execution_permits.acquire_many(2).await.unwrap();
+        let wait = std::time::Duration::from_millis(20);
+        assert!(tokio::time::timeout(wait, scanner.execution_permits.acquire()).await.is_err());
+
+        // 高价值机会立即拿到保留许可
+        let permit = tokio::time::timeout(
+            wait,
+            acquire_priority_permit(&scanner.execution_permits, &scanner.priority_execution_permits),
+        )
+        .await;
+        assert!(permit.is_ok());
+
+        let mut opp = fixtures::opportunity("high")
+            .gas_cost_usd(Decimal::ONE)
+            .net_profit_usd(Decimal::from(500))
+            .gas_estimate(300_000)
+            .block_number(1)
+            .build();
+        assert!(scanner.is_priority_opportunity(&opp));
+        opp.net_profit_usd = Decimal::from(3);
+        assert!(!scanner.is_priority_opportunity(&opp));
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_drops_opportunities_from_older_blocks() {
+        let mock = Arc::new(MockMiddleware::new());
+        let scanner = live_execution_scanner(mock.clone(), ScannerExecutorConfig::default()).await;
+        let pool = Address::repeat_byte;
+        let opp = live_path_opportunity(&scanner, "old", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+        // 机会在区块 100 检测到，排队期间已经到了区块 101
+        scanner.current_block.store(opp.block_number + 1, Ordering::Relaxed);
+
+        scanner.enqueue_for_execution(opp, tracing::Span::none()).await;
+
+        let stats = scanner.get_execution_stats().await;
+        assert_eq!(stats.stale_skipped, 1);
+        assert_eq!(stats.total_executions, 0);
+        assert!(mock.sent_transactions().is_empty());
+        assert!(scanner.opportunity_spans.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_hands_off_after_bounded_rounds() {
+        let mock = Arc::new(MockMiddleware::new());
+        let scanner = live_execution_scanner(mock.clone(), ScannerExecutorConfig::default()).await;
+        let pool = Address::repeat_byte;
+        let opp = live_path_opportunity(&scanner, "first", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+        // 执行期间持续有新机会入队，分发者不能一直处理下去
+        mock.set_call_delay(std::time::Duration::from_millis(5));
+        let feeder = async {
+            for i in 0u64.. {
+                // 上一个机会被取走后再入队下一个
+                while !scanner.pending_executions.read().await.is_empty() {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+                // 每个机会用自己的池子，不会因池子冲突或冷却被跳过
+                let pools = [1, 2, 3].map(|hop| Address::from_low_u64_be(0x1000 + i * 4 + hop));
+                let next = live_path_opportunity(&scanner, &format!("next-{}", i), pools, pool(0xf1)).await;
+                scanner.pending_executions.write().await.push(next);
+            }
+        };
+        let dispatched = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            tokio::select! {
+                biased;
+                _ = scanner.enqueue_for_execution(opp.clone(), tracing::Span::none()) => {}
+                _ = feeder => unreachable!(),
+            }
+        })
+        .await;
+
+        assert!(dispatched.is_ok(), "分发者没有交出分发权");
+        assert!(!scanner.execution_dispatching.load(Ordering::Acquire));
+        let stats = scanner.get_execution_stats().await;
+        assert_eq!(stats.total_executions, MAX_DISPATCH_ROUNDS as u64);
+        // 剩余机会留给下一个分发者
+        assert!(!scanner.pending_executions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_priority_lane_joins_conflict_selection_and_shares_nonces() {
+        let mock = Arc::new(MockMiddleware::new());
+        mock.set_nonce(U256::from(7));
+        let scanner = live_execution_scanner_with(
+            mock.clone(),
+            EventDrivenScannerConfig {
+                execution_batch_window_ms: 50,
+                executor_config: ScannerExecutorConfig {
+                    max_concurrent_executions: 1,
+                    priority_min_profit_usd: Some(100.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await;
+        let pool = Address::repeat_byte;
+        let low = live_path_opportunity(&scanner, "low", [pool(0x01), pool(0x02), pool(0x03)], pool(0xf1)).await;
+        let mut high = live_path_opportunity(&scanner, "high", [pool(0x04), pool(0x05), pool(0x06)], pool(0xf2)).await;
+        high.net_profit_usd = Decimal::from(500);
+        // 与高价值机会共用池子的低价值机会
+        let conflict = ArbitrageOpportunity { id: "conflict".to_string(), net_profit_usd: Decimal::from(10), ..high.clone() };
+
+        // low 成为分发者等待收集窗口，conflict 入队后 high 走优先通道
+        tokio::join!(
+            scanner.enqueue_for_execution(low, tracing::Span::none()),
+            scanner.enqueue_for_execution(conflict, tracing::Span::none()),
+            scanner.enqueue_for_execution(high, tracing::Span::none()),
+        );
+
+        let stats = scanner.get_execution_stats().await;
+        assert_eq!(stats.conflict_skipped, 1);
+        assert_eq!(stats.total_executions, 2);
+        // 优先通道与同批执行并行，nonce 仍然不重复
+        let mut nonces: Vec<U256> = mock.sent_transactions().iter().filter_map(|tx| tx.nonce().copied()).collect();
+        nonces.sort();
+        assert_eq!(nonces, vec![U256::from(7), U256::from(8)]);
+    }
+
+    #[tokio::test]
+    async fn test_manual_execution_requires_known_and_still_profitable_opportunity() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let mut config = EventDrivenScannerConfig::default();
+        config.executor_config.auto_execute = true;
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        assert_eq!(
+            scanner.execute_recent_opportunity("missing").await.unwrap_err(),
+            ManualExecutionError::NotFound("missing".to_string())
+        );
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let (p1, p2, p3) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let opportunity = |id: &str| {
+            fixtures::opportunity(id)
+                .path(fixtures::v3_path(&[(p1, a, b), (p2, b, c), (p3, c, a)]))
+                .gas_cost_usd(Decimal::ONE)
+                .net_profit_usd(Decimal::from(19))
+                .gas_estimate(300_000)
+                .block_number(1)
+                .build()
+        };
+
+        // 池子不归该扫描器监控: 交给其他扫描器
+        assert_eq!(
+            scanner.requote_and_execute(opportunity("42")).await.unwrap_err(),
+            ManualExecutionError::NotFound("42".to_string())
+        );
+
+        let tokens = [stable_token(a, "USDC"), stable_token(b, "USDT"), stable_token(c, "DAI")];
+        setup_triangle(&scanner, tokens, 100, U256::zero()).await;
+        scanner.remember_opportunity(opportunity("opp-1")).await;
+
+        // 自动执行暂停时手动执行同样被拒绝
+        scanner.set_execution_paused(true);
+        assert!(matches!(
+            scanner.execute_recent_opportunity("opp-1").await.unwrap_err(),
+            ManualExecutionError::ExecutionDisabled(_)
+        ));
+        scanner.set_execution_paused(false);
+
+        // 报价失败 (节点没有返回报价)，按当前状态报价不出利润: 拒绝且不发送交易
+        assert_eq!(
+            scanner.execute_recent_opportunity("opp-1").await.unwrap_err(),
+            ManualExecutionError::NoLongerProfitable("opp-1".to_string())
+        );
+        assert_eq!(scanner.execution_stats.read().await.total_executions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_manual_execution_rejected_without_auto_execute() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let tokens = [stable_token(a, "USDC"), stable_token(b, "USDT"), stable_token(c, "DAI")];
+        let (p1, p2, p3) = setup_triangle(&scanner, tokens, 100, U256::zero()).await;
+        let opportunity = fixtures::opportunity("7")
+            .path(fixtures::v3_path(&[(p1.address, a, b), (p2.address, b, c), (p3.address, c, a)]))
+            .build();
+
+        assert!(matches!(
+            scanner.requote_and_execute(opportunity).await.unwrap_err(),
+            ManualExecutionError::ExecutionDisabled(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_same_triangle_from_both_detection_paths_executes_once() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let (pool_ab, pool_bc, pool_ca) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let triangle =
+            |hops: &[(Address, Address, Address)]| fixtures::opportunity(format!("opp-{}", hops.len())).path(fixtures::v3_path(hops)).build();
+
+        // 静态路径: A→B→C→A；旧版动态枚举: A→C→B→A (同一组池子，方向相反)
+        let static_opp = triangle(&[(pool_ab, a, b), (pool_bc, b, c), (pool_ca, c, a)]);
+        let legacy_opp = triangle(&[(pool_ca, a, c), (pool_bc, c, b), (pool_ab, b, a)]);
+        assert_eq!(scanner.generate_path_signature(&static_opp), scanner.generate_path_signature(&legacy_opp));
+
+        let first = scanner.execute_arbitrage(static_opp).await.unwrap();
+        assert_eq!(first.status, models::ArbitrageStatus::Pending);
+        let second = scanner.execute_arbitrage(legacy_opp).await.unwrap();
+        assert_eq!(second.status, models::ArbitrageStatus::Failed);
+        assert!(second.error_message.unwrap().contains("重复套利"));
+
+        let stats = scanner.execution_stats.read().await;
+        assert_eq!((stats.dry_run_executions, stats.duplicates_skipped), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_detection_only_never_prepares_execution() {
+        let config = EventDrivenScannerConfig {
+            executor_config: ScannerExecutorConfig {
+                auto_execute: true,
+                dry_run: false,
+                detection_only: true,
+                arbitrage_contract: Some(Address::repeat_byte(0xcc)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let (p1, p2, p3) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let path = fixtures::v3_path(&[(p1, a, b), (p2, b, c), (p3, c, a)]);
+        let opportunity = fixtures::opportunity("opp-detection-only").path(path).build();
+
+        let err = scanner.execute_arbitrage(opportunity).await.unwrap_err();
+        assert!(err.to_string().contains("仅检测模式"));
+
+        // 没有选池、报价、gas 等任何 RPC，也没有占用池子锁或计入执行次数
+        assert!(scanner.get_rpc_stats().calls_by_type().iter().all(|(_, count)| *count == 0));
+        assert!(scanner.executing_pools.read().await.is_empty());
+        assert_eq!(scanner.execution_stats.read().await.total_executions, 0);
+    }
+
+    #[test]
+    fn test_each_chain_executor_uses_its_own_relay() {
+        let mainnet = ScannerExecutorConfig {
+            use_flashbots: true,
+            flashbots_rpc_url: Some("https://relay.flashbots.net".to_string()),
+            flashbots_signer_key: Some("0x01".to_string()),
+            ..Default::default()
+        };
+        let bsc = ScannerExecutorConfig {
+            use_flashbots: true,
+            flashbots_rpc_url: Some("https://bsc-relay.example".to_string()),
+            flashbots_signer_key: Some("0x02".to_string()),
+            ..Default::default()
+        };
+
+        let mainnet_fb = mainnet.flashbots_config(1);
+        let bsc_fb = bsc.flashbots_config(56);
+        assert_eq!(mainnet_fb.relay_url, "https://relay.flashbots.net");
+        assert_eq!(mainnet_fb.signer_key.as_deref(), Some("0x01"));
+        assert_eq!(mainnet_fb.chain_id, 1);
+        assert_eq!(bsc_fb.relay_url, "https://bsc-relay.example");
+        assert_eq!(bsc_fb.signer_key.as_deref(), Some("0x02"));
+        assert_eq!(bsc_fb.chain_id, 56);
+        assert!(mainnet_fb.enabled && bsc_fb.enabled);
+    }
+
+    #[tokio::test]
+    async fn test_balances_fall_back_to_sequential_without_multicall() {
+        use ethers::providers::MockProvider;
+
+        // 未配置任何返回值: Multicall 和逐个查询都会失败
+        let (provider, _mock) = Provider::<MockProvider>::mocked();
+        let tokens: Vec<Address> = (1..=3u8).map(Address::repeat_byte).collect();
+
+        let stats = Arc::new(RpcStats::new());
+        let fetcher = test_balance_fetcher(Arc::new(provider), Some(stats.clone()));
+        let balances = EventDrivenScanner::get_balances_async(&fetcher, &tokens, None).await;
+
+        assert_eq!(stats.total_calls(RpcCallType::MulticallBalances), 1);
+        assert_eq!(stats.total_calls(RpcCallType::Erc20BalanceOf), 3);
+        assert!(balances.iter().all(|b| b.balance == "N/A"));
+    }
+
+    #[test]
+    fn test_blocklisted_token_blocks_pools_and_paths() {
+        let token = Address::repeat_byte(0xb0);
+        let blocklist = TradingBlocklist::from_addresses(&[format!("{:?}", token)], &["not-an-address".to_string()]);
+        assert!(blocklist.pools.is_empty());
+        assert!(blocklist.blocks_pool(&test_pool(Address::repeat_byte(0x01))));
+
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: Address::repeat_byte(0xa0),
+            token_b: token,
+            token_c: Address::repeat_byte(0xc0),
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        assert!(blocklist.blocks_path(&path));
+        assert!(!TradingBlocklist::default().blocks_path(&path));
+    }
+
+    #[tokio::test]
+    async fn test_whale_swap_input_clamped_by_shallow_pool() {
+        let (usdc, usdt, dai) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let config = EventDrivenScannerConfig { max_liquidity_fraction: dec!(0.1), ..Default::default() };
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, Arc::new(provider), price_service);
+        for (address, symbol) in [(usdc, "USDC"), (usdt, "USDT"), (dai, "DAI")] {
+            scanner.add_token_config(TokenConfig {
+                address,
+                symbol: symbol.to_string(),
+                decimals: 6,
+                is_stable: true,
+                price_symbol: symbol.to_string(),
+                optimal_input_amount: U256::exp10(6),
+            }).await;
+        }
+
+        // 价格 1:1 的 V3 池子，两侧虚拟储备都等于 L
+        let pool = |n: u8, token0: Address, token1: Address, liquidity: u128| PoolState {
+            token0,
+            token1,
+            sqrt_price_x96: Some(U256::one() << 96),
+            liquidity: Some(liquidity),
+            ..test_pool(Address::repeat_byte(n))
+        };
+        let deep1 = pool(0x01, usdc, usdt, 1_000_000_000_000); // 每侧 100 万，深度 $2M
+        let deep2 = pool(0x02, usdt, dai, 1_000_000_000_000);
+        let shallow = pool(0x03, dai, usdc, 10_000_000_000); // 每侧 1 万，深度 $20000
+        assert_eq!(pool_depth_usd(&shallow, [(6, dec!(1)), (6, dec!(1))]), Some(dec!(20000)));
+
+        // $100 万的鲸鱼交易被限制到最浅池子深度的 10%
+        let hops = [vec![&deep1], vec![&deep2], vec![&shallow]];
+        assert_eq!(scanner.liquidity_bounded_usd(dec!(1_000_000), &hops).await, dec!(2000));
+        // 本来就够小的交易不受影响
+        assert_eq!(scanner.liquidity_bounded_usd(dec!(500), &hops).await, dec!(500));
+
+        // 同一跳有更深的候选池子时按最深的计算
+        let hops = [vec![&deep1], vec![&deep2], vec![&shallow, &deep1]];
+        assert_eq!(scanner.liquidity_bounded_usd(dec!(1_000_000), &hops).await, dec!(200000));
+
+        // 深度未知或未配置比例时不限制
+        assert_eq!(clamp_to_liquidity(dec!(1_000_000), None, dec!(0.1)), dec!(1_000_000));
+        assert_eq!(clamp_to_liquidity(dec!(1_000_000), Some(dec!(20000)), Decimal::ZERO), dec!(1_000_000));
+    }
+
+    #[test]
+    fn test_pool_depth_usd_is_exact() {
+        // 18 位精度的巨量储备: 超过 f64 的有效位数，分位也不能丢
+        let whale = PoolState {
+            reserve0: U256::exp10(33) + U256::exp10(16),
+            reserve1: U256::zero(),
+            dex_type: DexType::UniswapV2,
+            ..test_pool(Address::repeat_byte(0x01))
+        };
+        assert_eq!(pool_depth_usd(&whale, [(18, dec!(1)), (6, dec!(1))]), Some(dec!(1000000000000000.01)));
+
+        // V3 价格 4 (√P = 2): x = L/2, y = 2L
+        let v3 = PoolState {
+            sqrt_price_x96: Some(U256::from(2) << 96),
+            liquidity: Some(1_000_000_000_000),
+            ..test_pool(Address::repeat_byte(0x02))
+        };
+        assert_eq!(pool_depth_usd(&v3, [(6, dec!(4)), (6, dec!(1))]), Some(dec!(4000000)));
+
+        // 没有储备或价格都未知
+        assert_eq!(pool_depth_usd(&test_pool(Address::repeat_byte(0x03)), [(6, dec!(1)), (6, dec!(1))]), None);
+        assert_eq!(pool_depth_usd(&v3, [(6, Decimal::ZERO), (6, Decimal::ZERO)]), None);
+    }
+
+    #[tokio::test]
+    async fn test_atoken_paths_rejected() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(EventDrivenScannerConfig::default(), Arc::new(provider), price_service);
+
+        // test_pool 的 token1 (0xb0) 配置成 aToken
+        let a_token = Address::repeat_byte(0xb0);
+        let trigger = Address::repeat_byte(0x01);
+        scanner.add_pool(test_pool(trigger)).await;
+        let path = PoolPathConfig {
+            path_name: "A->aUSDC->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: Address::repeat_byte(0xa0),
+            token_b: a_token,
+            token_c: Address::repeat_byte(0xc0),
+            pools: None,
+            flash_pool: None,
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        scanner.add_pool_path_mappings(vec![(trigger, vec![path.clone()])]).await;
+        assert_eq!(scanner.pool_path_mapping_count().await, (1, 1));
+
+        let token = |address: Address, symbol: &str| TokenConfig {
+            address,
+            symbol: symbol.to_string(),
+            decimals: 6,
+            is_stable: false,
+            price_symbol: "USDC".to_string(),
+            optimal_input_amount: U256::exp10(6),
+        };
+        scanner.add_token_configs(vec![token(Address::repeat_byte(0xa0), "USDC"), token(a_token, "aUSDC")]).await;
+
+        // 含 aToken 的池子和路径被移除
+        assert_eq!(scanner.pool_count().await, 0);
+        assert_eq!(scanner.pool_path_mapping_count().await, (0, 0));
+        assert!(scanner.blocklist.read().await.blocks_path(&path));
+
+        // 替换黑名单后标记仍然保留
+        scanner.set_blocklist(TradingBlocklist::default()).await;
+        assert!(scanner.blocklist.read().await.tokens.contains(&a_token));
+        assert!(!scanner.blocklist.read().await.tokens.contains(&Address::repeat_byte(0xa0)));
+    }
+
+    #[test]
+    fn test_inaccurate_pool_lowers_skip_local_threshold() {
+        let base = dec!(5000);
+        let chain_quote = U256::from(1_000_000u64);
+
+        // 深池: 本地估算与链上报价偏差 10bp
+        let mut deep = LocalQuoteAccuracy::default();
+        // 浅池: 跨 tick 导致本地估算高估 2%
+        let mut shallow = LocalQuoteAccuracy::default();
+        for _ in 0..10 {
+            deep.record(U256::from(1_001_000u64), chain_quote);
+            shallow.record(U256::from(1_020_000u64), chain_quote);
+        }
+        assert_eq!(shallow.avg_error_bps.round(), 200.0);
+
+        let deep_threshold = deep.adjusted_threshold(base, 50, 5);
+        let shallow_threshold = shallow.adjusted_threshold(base, 50, 5);
+        assert_eq!(deep_threshold, base);
+        assert_eq!(shallow_threshold, dec!(1250));
+
+        // $2000 的 swap: 浅池改用链上计算，深池仍用本地计算
+        let swap_usd = dec!(2000);
+        assert!(swap_usd >= shallow_threshold);
+        assert!(swap_usd < deep_threshold);
+
+        // 样本不足或关闭自适应时保持基础阈值
+        let mut few = LocalQuoteAccuracy::default();
+        few.record(U256::from(1_020_000u64), chain_quote);
+        assert_eq!(few.adjusted_threshold(base, 50, 5), base);
+        assert_eq!(shallow.adjusted_threshold(base, 0, 5), base);
+    }
+
+    #[test]
+    fn test_encode_v3_path() {
+        let a = Address::repeat_byte(0x11);
+        let b = Address::repeat_byte(0x22);
+        let path = encode_v3_path(&[a, b, a], &[500, 3000]);
+
+        assert_eq!(path.len(), 20 * 3 + 3 * 2);
+        assert_eq!(&path[20..23], &[0x00, 0x01, 0xf4]);
+        assert_eq!(&path[43..46], &[0x00, 0x0b, 0xb8]);
+        assert_eq!(&path[46..66], a.as_bytes());
+    }
+
+    /// 只响应一次请求的 HTTP 服务端，返回 (URL, 服务端任务)
+    async fn serve_json_once(body: &'static str) -> (String, tokio::task::JoinHandle<()>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/gasprices/blockprices", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_gas_oracle_source_with_node_fallback() {
+        use ethers::providers::MockProvider;
+
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner_with = |source: GasPriceSource| {
+            let (provider, mock) = Provider::<MockProvider>::mocked();
+            let config = EventDrivenScannerConfig { gas_price_source: source, ..Default::default() };
+            (EventDrivenScanner::new(config, Arc::new(provider), price_service.clone()), mock)
+        };
+
+        // oracle 可用: gas 成本按 oracle 价格估算，不请求节点
+        let (url, server) = serve_json_once(
+            r#"{"blockPrices":[{"estimatedPrices":[{"confidence":99,"price":12.5},{"confidence":70,"price":9}]}]}"#,
+        )
+        .await;
+        let (scanner, _mock) = scanner_with(GasPriceSource::from_config("oracle", Some(&url), None).unwrap());
+        assert_eq!(scanner.get_cached_gas_price().await, U256::from(12_500_000_000u64));
+        server.await.unwrap();
+        assert_eq!(scanner.rpc_stats.total_calls(RpcCallType::GetGasPrice), 0);
+
+        // 新区块的 base fee 不覆盖 oracle 价格
+        scanner
+            .handle_new_block(NewBlockEvent {
+                block_number: 1,
+                block_hash: H256::zero(),
+                timestamp: 0,
+                base_fee: Some(U256::from(1u64)),
+                gas_used: U256::zero(),
+                gas_limit: U256::zero(),
+            })
+            .await;
+        assert_eq!(scanner.get_cached_gas_price().await, U256::from(12_500_000_000u64));
+
+        // oracle 不可用: 回退到节点 eth_gasPrice
+        let closed = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/gasprices/blockprices", listener.local_addr().unwrap())
+        };
+        let (scanner, mock) = scanner_with(GasPriceSource::from_config("oracle", Some(&closed), None).unwrap());
+        mock.push::<U256, _>(U256::from(7_000_000_000u64)).unwrap();
+        assert_eq!(scanner.get_cached_gas_price().await, U256::from(7_000_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_block_event_does_not_refresh() {
+        let (provider, _mock) = Provider::<ethers::providers::MockProvider>::mocked();
+        let scanner = EventDrivenScanner::new(
+            EventDrivenScannerConfig::default(),
+            Arc::new(provider),
+            services::create_price_service(services::PriceServiceConfig::default()),
+        );
+        let block = |number: u64, hash: u8| NewBlockEvent {
+            block_number: number,
+            block_hash: H256::repeat_byte(hash),
+            timestamp: 0,
+            base_fee: None,
+            gas_used: U256::zero(),
+            gas_limit: U256::zero(),
+        };
+
+        scanner.handle_new_block(block(100, 1)).await;
+        scanner.handle_new_block(block(101, 2)).await;
+        assert_eq!(scanner.warmup.refreshed_blocks(), 2);
+
+        // 重放和更旧的区块被忽略，当前区块号不回退
+        scanner.handle_new_block(block(101, 2)).await;
+        scanner.handle_new_block(block(99, 9)).await;
+        assert_eq!(scanner.warmup.refreshed_blocks(), 2);
+        assert_eq!(scanner.get_current_block(), 101);
+
+        // 同一高度换了哈希 (重组) 照常刷新
+        scanner.handle_new_block(block(101, 3)).await;
+        assert_eq!(scanner.warmup.refreshed_blocks(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_uses_configured_multicall_address() {
+        let custom_multicall = Address::repeat_byte(0x5a);
+        let contracts = ChainContractsConfig { multicall_address: custom_multicall, ..ChainContractsConfig::ethereum() };
+        // 调用一律失败，只记录目标地址
+        let provider = Arc::new(MockMiddleware::new());
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner =
+            EventDrivenScanner::with_chain_config(EventDrivenScannerConfig::default(), provider.clone(), price_service, contracts);
+        let pool = Address::repeat_byte(0x01);
+        scanner.add_pool(test_pool(pool)).await;
+
+        let _ = scanner.refresh_stale_pools(&[pool]).await;
+        let targets: Vec<Option<Address>> = provider.all_calls().iter().map(|c| c.to).collect();
+        assert_eq!(targets, vec![Some(custom_multicall)]);
+    }
+
+    /// 所有 QuoterV2 单跳报价都返回 1 wei
+    fn one_wei_quoter_v2() -> Arc<MockMiddleware> {
+        let provider = MockMiddleware::new();
+        provider.on_call(QUOTER_V2_SIG, &quoter_v2_output(U256::one(), 100_000));
+        Arc::new(provider)
+    }
+
+    #[tokio::test]
+    async fn test_legacy_pool3_candidates_capped_by_depth() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |byte: u8, token0: Address, token1: Address, fee: u32, liquidity: u128| PoolState {
+            token0,
+            token1,
+            fee,
+            liquidity: Some(liquidity),
+            ..test_pool(Address::repeat_byte(byte))
+        };
+        let pool1 = pool(0x01, a, b, 500, 1);
+        let pool2 = pool(0x02, b, c, 500, 1);
+        // 10 个都能完成 C -> A 的第三池，费率各不相同，liquidity 随费率递增
+        let pool3s: Vec<PoolState> =
+            (1..=10u32).map(|i| pool(0x10 + i as u8, c, a, i * 100, i as u128 * 1_000)).collect();
+
+        let quote_run = |max_candidates: usize| {
+            let pool3s = pool3s.clone();
+            let (pool1, pool2) = (pool1.clone(), pool2.clone());
+            async move {
+                let provider = one_wei_quoter_v2();
+                let config = EventDrivenScannerConfig { legacy_max_pool3_candidates: max_candidates, ..Default::default() };
+                let price_service = services::create_price_service(services::PriceServiceConfig::default());
+                let scanner = EventDrivenScanner::new(config, provider.clone(), price_service);
+                // A 为稳定币，没有价格服务时按 $1 估值
+                scanner.add_token_config(TokenConfig {
+                    address: a,
+                    symbol: "USDC".to_string(),
+                    decimals: 6,
+                    is_stable: true,
+                    price_symbol: "USDC".to_string(),
+                    optimal_input_amount: U256::zero(),
+                }).await;
+
+                let opportunity = scanner
+                    .check_triangular_path_directed(a, b, &pool1, &pool2, &pool3s, dec!(1000))
+                    .await;
+                assert!(opportunity.is_none());
+                let mut pool3_fees: Vec<u32> = provider
+                    .calls(QUOTER_V2_SIG)
+                    .iter()
+                    .map(quoter_v2_args)
+                    .filter(|(token_in, ..)| *token_in == c)
+                    .map(|(.., fee)| fee)
+                    .collect();
+                pool3_fees.sort();
+                (scanner.rpc_stats.total_calls(RpcCallType::QuoterV2Quote), pool3_fees)
+            }
+        };
+
+        // 上限 3: 只报价 liquidity 最大的 3 个第三池 (每个 3 跳)
+        let (calls, fees) = quote_run(3).await;
+        assert_eq!(calls, 9);
+        assert_eq!(fees, vec![800, 900, 1000]);
+
+        // 不限制: 10 个候选池全部报价
+        let (calls, fees) = quote_run(0).await;
+        assert_eq!(calls, 30);
+        assert_eq!(fees.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_pool_ignored_when_legacy_enumeration_disabled() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |byte: u8, token0: Address, token1: Address| PoolState {
+            token0,
+            token1,
+            ..test_pool(Address::repeat_byte(byte))
+        };
+
+        let quotes_for_unmapped_trigger = |legacy_enumeration_enabled: bool| async move {
+            let config = EventDrivenScannerConfig { legacy_enumeration_enabled, ..Default::default() };
+            let price_service = services::create_price_service(services::PriceServiceConfig::default());
+            let scanner = EventDrivenScanner::new(config, one_wei_quoter_v2(), price_service);
+            scanner.add_token_config(TokenConfig {
+                address: a,
+                symbol: "USDC".to_string(),
+                decimals: 6,
+                is_stable: true,
+                price_symbol: "USDC".to_string(),
+                optimal_input_amount: U256::zero(),
+            }).await;
+            // 三个池子都没有路径映射
+            scanner.add_pool(pool(0x01, a, b)).await;
+            scanner.add_pool(pool(0x02, b, c)).await;
+            scanner.add_pool(pool(0x03, c, a)).await;
+
+            assert!(scanner.detect_arbitrage_for_pool(Address::repeat_byte(0x01), dec!(1000)).await.is_none());
+            scanner.rpc_stats.total_calls(RpcCallType::QuoterV2Quote)
+        };
+
+        assert!(quotes_for_unmapped_trigger(true).await > 0);
+        assert_eq!(quotes_for_unmapped_trigger(false).await, 0);
+    }
+
+    /// QuoterV2 单跳报价按 (tokenIn, tokenOut) 的兑换比例 (万分比，默认 1:1) 返回输出
+    fn rate_quoter_v2(rates_bps: HashMap<(Address, Address), u64>) -> Arc<MockMiddleware> {
+        let provider = MockMiddleware::new();
+        provider.on_call_fn(QUOTER_V2_SIG, move |call| {
+            let (token_in, token_out, amount_in, _) = quoter_v2_args(call);
+            let rate = rates_bps.get(&(token_in, token_out)).copied().unwrap_or(10_000);
+            Ok(quoter_v2_output(amount_in * rate / 10_000, 100_000))
+        });
+        Arc::new(provider)
+    }
+
+    #[tokio::test]
+    async fn test_v2_swap_triggers_detection_with_reserve_quote() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let v2_pool = Address::repeat_byte(0x01);
+        let pool = |byte: u8, token0: Address, token1: Address| PoolState {
+            token0,
+            token1,
+            ..test_pool(Address::repeat_byte(byte))
+        };
+
+        // C -> A 赚 3%，足以覆盖 V2 池子 0.3% 的手续费和价格影响
+        let provider = rate_quoter_v2(HashMap::from([((c, a), 10_300)]));
+        // V2 池子储备各 100 万 (6 位精度)，Swap 之后通过 getReserves 读取
+        let reserves = ethers::abi::encode(&[
+            Token::Uint(U256::exp10(12)),
+            Token::Uint(U256::exp10(12)),
+            Token::Uint(U256::from(1_700_000_000u64)),
+        ]);
+        provider.on_call(AGGREGATE3_SIG, &[Token::Array(vec![Token::Tuple(vec![Token::Bool(true), Token::Bytes(reserves)])])]);
+
+        let config = EventDrivenScannerConfig { enable_dynamic_profit: false, ..Default::default() };
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let scanner = EventDrivenScanner::new(config, provider.clone(), price_service);
+        scanner.add_token_config(stable_token(a, "USDC")).await;
+        scanner.add_pool(PoolState { dex_type: DexType::UniswapV2, fee: 3000, ..pool(0x01, a, b) }).await;
+        scanner.add_pool(pool(0x02, b, c)).await;
+        scanner.add_pool(pool(0x03, c, a)).await;
+        scanner.add_pool_path_mapping(v2_pool, vec![PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: a,
+            token_b: b,
+            token_c: c,
+            pools: None,
+            flash_pool: Some(Address::repeat_byte(0xf1)),
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        }]).await;
+
+        // V2 Swap 事件不带 sqrtPrice/liquidity
+        let opportunity = scanner.handle_swap_event(SwapEvent {
+            pool_address: v2_pool,
+            sender: Address::zero(),
+            amount0_in: U256::from(10_000_000_000u64),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::from(9_900_000_000u64),
+            block_number: 100,
+            tx_hash: H256::repeat_byte(0x11),
+            sqrt_price_x96: None,
+            liquidity: None,
+            tick: None,
+        }).await.expect("V2 Swap 应触发套利检测");
+
+        assert_eq!(opportunity.path.hops[0].pool_address, v2_pool);
+        assert_eq!(opportunity.path.hops[0].dex_type, DexType::UniswapV2);
+        assert!(opportunity.net_profit_usd > Decimal::ZERO);
+        let reserves = scanner.pool_states.read().await[&v2_pool].reserve0;
+        assert_eq!(reserves, U256::exp10(12));
+        // V2 跳按储备本地报价，QuoterV2 只报价 V3 池子
+        let quoted_pairs: Vec<(Address, Address)> = provider
+            .calls(QUOTER_V2_SIG)
+            .iter()
+            .map(|call| {
+                let (token_in, token_out, _, _) = quoter_v2_args(call);
+                (token_in, token_out)
+            })
+            .collect();
+        assert!(!quoted_pairs.is_empty());
+        assert!(!quoted_pairs.contains(&(a, b)), "{:?}", quoted_pairs);
+    }
+
+    #[tokio::test]
+    async fn test_static_path_picks_profitable_direction() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |byte: u8, token0: Address, token1: Address| PoolState {
+            token0,
+            token1,
+            ..test_pool(Address::repeat_byte(byte))
+        };
+        let pools = vec![pool(0x01, a, b), pool(0x02, b, c), pool(0x03, c, a)];
+        let flash_pool = Address::repeat_byte(0xf1);
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: a,
+            token_b: b,
+            token_c: c,
+            pools: None,
+            flash_pool: Some(flash_pool),
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+
+        let scanner_for = |evaluate_both_directions: bool| {
+            let path = path.clone();
+            async move {
+                // 不对称的池子: C -> A 亏 1%，B -> A 赚 1%，只有反方向 A -> C -> B -> A 有利润
+                let provider = rate_quoter_v2(HashMap::from([((c, a), 9_900), ((b, a), 10_100)]));
+                let config = EventDrivenScannerConfig { evaluate_both_directions, ..Default::default() };
+                let price_service = services::create_price_service(services::PriceServiceConfig::default());
+                let scanner = EventDrivenScanner::new(config, provider, price_service);
+                scanner.add_token_config(TokenConfig {
+                    address: a,
+                    symbol: "USDC".to_string(),
+                    decimals: 6,
+                    is_stable: true,
+                    price_symbol: "USDC".to_string(),
+                    optimal_input_amount: U256::zero(),
+                }).await;
+                scanner.add_pool_path_mapping(Address::repeat_byte(0x01), vec![path]).await;
+                scanner
+            }
+        };
+
+        // $10000 走链上报价模式；只检查配置方向时没有机会
+        let scanner = scanner_for(false).await;
+        assert!(scanner.evaluate_static_path(&path, std::slice::from_ref(&path), &pools, dec!(10000)).await.is_none());
+        let one_direction_quotes = scanner.provider.call_count(QUOTER_V2_SIG);
+        assert!(one_direction_quotes > 0);
+
+        // 反方向已单独配置为一条路径时由它自己检查，正方向不再替它报价
+        let reverse_path = PoolPathConfig { path_name: "A->C->B->A".to_string(), ..path.reversed() };
+        let configured = [path.clone(), reverse_path];
+        let scanner = scanner_for(true).await;
+        assert!(scanner.evaluate_static_path(&path, &configured, &pools, dec!(10000)).await.is_none());
+        assert_eq!(scanner.provider.call_count(QUOTER_V2_SIG), one_direction_quotes);
+
+        let scanner = scanner_for(true).await;
+        let opportunity = scanner.evaluate_static_path(&path, std::slice::from_ref(&path), &pools, dec!(10000)).await.unwrap();
+        let route: Vec<(Address, Address)> =
+            opportunity.path.hops.iter().map(|h| (h.token_in, h.token_out)).collect();
+        assert_eq!(route, vec![(a, c), (c, b), (b, a)]);
+        let swap_pools: Vec<Address> = opportunity.path.hops.iter().map(|h| h.pool_address).collect();
+        assert_eq!(swap_pools, vec![Address::repeat_byte(0x03), Address::repeat_byte(0x02), Address::repeat_byte(0x01)]);
+        assert!(opportunity.net_profit_usd > Decimal::ZERO);
+        // 反方向的机会沿用原路径配置的闪电贷池
+        assert_eq!(scanner.pinned_flash_pool([a, c, b], &swap_pools).await, Some(flash_pool));
+    }
+
+    /// 记录推送过的机会 ID
+    #[derive(Default)]
+    struct OpportunitySink(std::sync::Mutex<Vec<String>>);
+
+    #[async_trait::async_trait]
+    impl services::EventSink for OpportunitySink {
+        async fn publish_opportunity(&self, _chain_id: u64, opportunity: &ArbitrageOpportunity) {
+            self.0.lock().unwrap().push(opportunity.id.clone());
+        }
+
+        async fn publish_execution(&self, _chain_id: u64, _result: &models::ArbitrageResult) {}
+    }
+
+    #[tokio::test]
+    async fn test_ab_candidate_paper_trades_same_swap_stream() {
+        let (a, b, c) = (Address::repeat_byte(0xa0), Address::repeat_byte(0xb0), Address::repeat_byte(0xc0));
+        let pool = |byte: u8, token0: Address, token1: Address| PoolState {
+            token0,
+            token1,
+            ..test_pool(Address::repeat_byte(byte))
+        };
+        let path = PoolPathConfig {
+            path_name: "A->B->C->A".to_string(),
+            triangle_name: "ABC".to_string(),
+            token_a: a,
+            token_b: b,
+            token_c: c,
+            pools: None,
+            flash_pool: Some(Address::repeat_byte(0xf1)),
+            fixed_input_amount: None,
+            arbitrage_contract: None,
+            priority: 0,
+        };
+        let threshold = |min_profit_usd: Decimal| EventDrivenScannerConfig {
+            enable_dynamic_profit: false,
+            min_profit_usd,
+            ..Default::default()
+        };
+
+        // C -> A 赚 1%: $10000 的触发交易约有 $100 毛利；线上门槛 $100000 不会执行，候选门槛 $0 会
+        let provider = rate_quoter_v2(HashMap::from([((c, a), 10_100)]));
+        let price_service = services::create_price_service(services::PriceServiceConfig::default());
+        let live_sink = Arc::new(OpportunitySink::default());
+        let control = EventDrivenScanner::new(threshold(dec!(100000)), provider, price_service)
+            .with_event_sink(live_sink.clone());
+        control.add_token_config(stable_token(a, "USDC")).await;
+        for p in [pool(0x01, a, b), pool(0x02, b, c), pool(0x03, c, a)] {
+            control.add_pool(p).await;
+        }
+        control.add_pool_path_mapping(Address::repeat_byte(0x01), vec![path]).await;
+
+        let candidate_sink = Arc::new(OpportunitySink::default());
+        let candidate = Arc::new(
+            control.paper_trading_replica(threshold(dec!(0))).await.with_event_sink(candidate_sink.clone()),
+        );
+        let harness = Arc::new(AbHarness::new("min_profit=$100000", "min_profit=$0", candidate.clone()));
+        control.set_ab_harness(harness.clone()).await;
+        let control = Arc::new(control);
+
+        let sqrt_price = U256::from(2u64).pow(U256::from(96u64));
+        control.dispatch_swap_event(SwapEvent {
+            pool_address: Address::repeat_byte(0x01),
+            sender: Address::zero(),
+            amount0_in: U256::from(10_000_000_000u64),
+            amount1_in: U256::zero(),
+            amount0_out: U256::zero(),
+            amount1_out: U256::from(10_000_000_000u64),
+            block_number: 100,
+            tx_hash: H256::repeat_byte(0x11),
+            sqrt_price_x96: Some(sqrt_price),
+            liquidity: Some(U256::exp10(24).as_u128()),
+            tick: Some(0),
+        }).await;
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while harness.report().await.events == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let report = control.ab_report().await.unwrap();
+        assert_eq!(report.events, 1);
+        assert_eq!(report.candidate_only, 1);
+        assert_eq!(report.control_executions(), 0);
+        assert!(report.candidate_only_net_profit_usd > Decimal::ZERO);
+
+        // 候选组的机会不推送、不计入任何一方的统计，也不能被手动执行
+        assert!(live_sink.0.lock().unwrap().is_empty());
+        assert!(candidate_sink.0.lock().unwrap().is_empty());
+        assert_eq!(control.get_execution_stats().await.opportunities_found, 0);
+        assert_eq!(candidate.get_execution_stats().await.opportunities_found, 0);
+        assert!(candidate.recent_opportunities.read().await.is_empty());
+        assert!(control.take_opportunities().await.is_empty());
+        // 候选组与线上扫描器共用池子状态
+        assert_eq!(candidate.pool_count().await, 3);
+    }
+
+    #[test]
+    fn test_reconcile_multi_hop_quote() {
+        // 偏差 5bp，在容忍度内，保留链式报价
+        let check = reconcile_multi_hop_quote(U256::from(1_000_000u64), U256::from(999_500u64), 10);
+        assert!(!check.diverged);
+        assert_eq!(check.divergence_bps, 5);
+        assert_eq!(check.amount_out, U256::from(1_000_000u64));
+
+        // 偏差 50bp，采用多跳报价
+        let check = reconcile_multi_hop_quote(U256::from(1_000_000u64), U256::from(995_000u64), 10);
+        assert!(check.diverged);
+        assert_eq!(check.divergence_bps, 50);
+        assert_eq!(check.amount_out, U256::from(995_000u64));
+    }
+}
@@ -4,14 +4,38 @@ mod path_finder;
 mod profit_calculator;
 mod strategy_runner;
 mod event_driven_scanner;
+mod opportunity_scorer;
+mod startup_warmup;
+mod cooldown;
+mod ab_harness;
+mod rebasing_tokens;
+mod profit_tracker;
+mod block_sequence;
+mod min_profit_overrides;
+mod opportunity_watchdog;
+mod bundle_slots;
 
 pub use arbitrage_scanner::*;
 pub use arbitrage_executor::*;
 pub use path_finder::*;
 pub use profit_calculator::*;
-pub use strategy_runner::{ArbitrageStrategyManager, ArbitrageStrategyRunner, ExecutorSettings, StrategyConfig};
+pub use strategy_runner::{
+    ArbitrageStrategyManager, ArbitrageStrategyRunner, ExecutionControl, ExecutorSettings, ManualExecutionError,
+    StrategyConfig, StrategyControl,
+};
 pub use event_driven_scanner::{
-    EventDrivenScanner, EventDrivenScannerConfig, DynamicProfitConfig, PoolState,
+    EventDrivenScanner, EventDrivenScannerConfig, DynamicProfitConfig, PoolState, LocalQuoteAccuracy,
     TokenConfig, TriangleConfig, PoolPathConfig, ChainContractsConfig,
-    ScannerExecutorConfig, ExecutionAmountStrategy, ExecutionStats,
+    ScannerExecutorConfig, ExecutionAmountStrategy, ExecutionStats, validate_path_pools, TradingBlocklist, FeeTierWhitelist, QuoteResult, QuoterInterface,
+    NativeDenomination, MinProfitConflict,
 };
+pub use opportunity_scorer::{OpportunityScorer, OpportunityScorerConfig};
+pub use startup_warmup::StartupWarmup;
+pub use cooldown::{Cooldown, PathCooldown, PathKey, PoolCooldown};
+pub use ab_harness::{AbHarness, AbOutcome, AbReport, OpportunityDetector, paper_trading_config};
+pub use rebasing_tokens::{rebasing_token_reason, RebasingReason};
+pub use profit_tracker::{ProfitBucket, ProfitReport, ProfitTracker};
+pub use block_sequence::{BlockEventKind, BlockSequenceGuard};
+pub use min_profit_overrides::MinProfitOverrides;
+pub use opportunity_watchdog::{OpportunityWatchdog, QuietPeriod};
+pub use bundle_slots::{BundleSlot, BundleSlots};
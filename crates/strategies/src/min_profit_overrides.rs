@@ -0,0 +1,70 @@
+//! 按代币对 / 三角路径覆盖最小利润门槛
+//!
+//! Gas 分档的动态门槛对所有路径一视同仁，但深度稳定币三角值得更低的门槛，
+//! 波动大的路径 (如含 WBTC) 则应该要求更多利润。这里按代币集合配置覆盖值:
+//! 两个代币表示代币对 (路径包含这两个代币即命中)，三个代币表示完整三角
+
+use ethers::types::Address;
+use rust_decimal::Decimal;
+
+/// 最小利润覆盖表
+#[derive(Debug, Clone, Default)]
+pub struct MinProfitOverrides {
+    /// (代币集合, 最小净利润 USD)，代币集合不区分顺序
+    entries: Vec<(Vec<Address>, Decimal)>,
+}
+
+impl MinProfitOverrides {
+    /// 添加一条覆盖 (2 个代币 = 代币对，3 个代币 = 三角)，同一代币集合重复添加时后者生效
+    pub fn insert(&mut self, tokens: &[Address], min_profit_usd: Decimal) {
+        let mut key = tokens.to_vec();
+        key.sort();
+        key.dedup();
+        self.entries.retain(|(existing, _)| *existing != key);
+        self.entries.push((key, min_profit_usd));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 路径适用的覆盖门槛，没有命中时为 None (使用 Gas 分档门槛)
+    ///
+    /// 代币越多的覆盖越具体，三角覆盖优先于代币对；同样具体的多条覆盖
+    /// 同时命中时 (如稳定币对和 WBTC 对都在路径里) 取最高的门槛
+    pub fn resolve(&self, path_tokens: &[Address]) -> Option<Decimal> {
+        self.entries
+            .iter()
+            .filter(|(tokens, _)| tokens.iter().all(|t| path_tokens.contains(t)))
+            .max_by(|(a_tokens, a_min), (b_tokens, b_min)| {
+                a_tokens.len().cmp(&b_tokens.len()).then(a_min.cmp(b_min))
+            })
+            .map(|(_, min_profit)| *min_profit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_triangle_override_beats_pairs() {
+        let (usdc, usdt, dai, weth) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3), Address::repeat_byte(4));
+        let mut overrides = MinProfitOverrides::default();
+        overrides.insert(&[usdc, usdt], dec!(2));
+        overrides.insert(&[usdc, weth], dec!(5));
+        overrides.insert(&[dai, usdt, usdc], dec!(0.5));
+
+        // 顺序无关: 三角覆盖比代币对更具体
+        assert_eq!(overrides.resolve(&[usdt, dai, usdc]), Some(dec!(0.5)));
+        // 两条代币对同时命中取较高者
+        assert_eq!(overrides.resolve(&[usdc, usdt, weth]), Some(dec!(5)));
+        assert_eq!(overrides.resolve(&[dai, weth, Address::repeat_byte(5)]), None);
+
+        // 重复配置同一代币对时替换旧值
+        overrides.insert(&[usdt, usdc], dec!(1));
+        assert_eq!(overrides.resolve(&[usdc, usdt, Address::repeat_byte(5)]), Some(dec!(1)));
+    }
+}
@@ -0,0 +1,122 @@
+//! 套利机会评分与排序
+//!
+//! 同一批到达的多个机会按评分从高到低挑选，
+//! 共用池子的路径只执行评分最高的一条 (同一池子的第二笔交易价格已经变化，大概率回滚)
+
+use ethers::types::Address;
+use models::ArbitrageOpportunity;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+
+/// 评分权重配置
+#[derive(Debug, Clone)]
+pub struct OpportunityScorerConfig {
+    /// 净利润 (USD) 权重
+    pub net_profit_weight: Decimal,
+    /// 利润/Gas 成本比权重
+    pub profit_gas_ratio_weight: Decimal,
+}
+
+impl Default for OpportunityScorerConfig {
+    fn default() -> Self {
+        Self {
+            net_profit_weight: dec!(1),
+            profit_gas_ratio_weight: dec!(0.5),
+        }
+    }
+}
+
+/// 套利机会评分器
+#[derive(Debug, Clone, Default)]
+pub struct OpportunityScorer {
+    config: OpportunityScorerConfig,
+}
+
+impl OpportunityScorer {
+    pub fn new(config: OpportunityScorerConfig) -> Self {
+        Self { config }
+    }
+
+    /// 计算单个机会的评分
+    ///
+    /// score = 净利润 * w1 + (净利润 / gas 成本) * w2 (原生代币计价模式下按原生代币数量计)
+    pub fn score(&self, opportunity: &ArbitrageOpportunity) -> Decimal {
+        let net_profit = opportunity.ranking_net_profit();
+        let gas_cost = opportunity.ranking_gas_cost();
+        let ratio = if gas_cost > Decimal::ZERO {
+            net_profit / gas_cost
+        } else {
+            // gas 成本未知时只按净利润评分
+            Decimal::ZERO
+        };
+
+        net_profit * self.config.net_profit_weight + ratio * self.config.profit_gas_ratio_weight
+    }
+
+    /// 按评分排序并挑选互不共用池子的机会子集
+    ///
+    /// 返回 (选中的机会, 因池子冲突被放弃的机会)，选中列表按评分从高到低排列
+    pub fn select_non_conflicting(
+        &self,
+        opportunities: Vec<ArbitrageOpportunity>,
+    ) -> (Vec<ArbitrageOpportunity>, Vec<ArbitrageOpportunity>) {
+        let mut scored: Vec<(Decimal, ArbitrageOpportunity)> = opportunities
+            .into_iter()
+            .map(|opp| (self.score(&opp), opp))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        let mut used_pools: HashSet<Address> = HashSet::new();
+        let mut selected = Vec::new();
+        let mut conflicted = Vec::new();
+
+        for (_, opp) in scored {
+            let pools: Vec<Address> = opp.path.hops.iter().map(|h| h.pool_address).collect();
+            if pools.iter().any(|p| used_pools.contains(p)) {
+                conflicted.push(opp);
+                continue;
+            }
+            used_pools.extend(pools);
+            selected.push(opp);
+        }
+
+        (selected, conflicted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::testing::opportunity;
+
+    fn create_opportunity(id: &str, pools: &[u8], net_profit: Decimal, gas_cost: Decimal) -> ArbitrageOpportunity {
+        let pools: Vec<Address> = pools.iter().map(|p| Address::repeat_byte(*p)).collect();
+        opportunity(id).pools(&pools).gas_cost_usd(gas_cost).net_profit_usd(net_profit).block_number(1).build()
+    }
+
+    #[test]
+    fn test_select_highest_scoring_non_conflicting() {
+        let scorer = OpportunityScorer::default();
+        // a 与 b 共用池子 2，b 评分更高；c 与两者都不冲突
+        let a = create_opportunity("a", &[1, 2, 3], dec!(10), dec!(2));
+        let b = create_opportunity("b", &[2, 4, 5], dec!(20), dec!(2));
+        let c = create_opportunity("c", &[6, 7, 8], dec!(5), dec!(1));
+
+        let (selected, conflicted) = scorer.select_non_conflicting(vec![a, b, c]);
+        let ids: Vec<&str> = selected.iter().map(|o| o.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["b", "c"]);
+        assert_eq!(conflicted.len(), 1);
+        assert_eq!(conflicted[0].id, "a");
+    }
+
+    #[test]
+    fn test_score_prefers_profit_gas_ratio() {
+        let scorer = OpportunityScorer::default();
+        // 净利润相同，gas 成本更低的评分更高
+        let cheap = create_opportunity("cheap", &[1], dec!(10), dec!(1));
+        let expensive = create_opportunity("expensive", &[2], dec!(10), dec!(10));
+        assert!(scorer.score(&cheap) > scorer.score(&expensive));
+    }
+}
@@ -0,0 +1,143 @@
+//! 长时间无机会告警
+//!
+//! 检测如果悄无声息地坏掉 (配置错误、池子状态不再刷新)，活跃的链上可能几个小时
+//! 一个机会都没有也没人发现。这里按链记录最近一次发现机会的时间，新区块到来时检查:
+//! 出块正常但超过窗口没有任何机会就告警，之后每过一个窗口重复一次，直到再次发现机会
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct WatchdogState {
+    /// 最近一次发现机会的时间 (启动时视为刚发现)
+    last_opportunity_at: Instant,
+    /// 最近一次告警的时间
+    last_alert_at: Option<Instant>,
+    /// 最近一次发现机会后处理过的区块数
+    blocks_since_opportunity: u64,
+    /// 最近一次处理区块的时间
+    last_block_at: Option<Instant>,
+}
+
+/// 一次告警的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietPeriod {
+    /// 距离上次发现机会的时长
+    pub elapsed: Duration,
+    /// 期间处理过的区块数
+    pub blocks: u64,
+}
+
+/// 单条链的无机会看门狗
+#[derive(Debug)]
+pub struct OpportunityWatchdog {
+    /// 告警窗口 (None = 不检查)
+    window: Option<Duration>,
+    state: Mutex<WatchdogState>,
+}
+
+impl OpportunityWatchdog {
+    /// window_secs 为 0 时不检查
+    pub fn new(window_secs: u64) -> Self {
+        Self::new_at(window_secs, Instant::now())
+    }
+
+    fn new_at(window_secs: u64, now: Instant) -> Self {
+        Self {
+            window: (window_secs > 0).then(|| Duration::from_secs(window_secs)),
+            state: Mutex::new(WatchdogState {
+                last_opportunity_at: now,
+                last_alert_at: None,
+                blocks_since_opportunity: 0,
+                last_block_at: None,
+            }),
+        }
+    }
+
+    /// 记录发现了一个机会
+    pub fn record_opportunity(&self) {
+        self.record_opportunity_at(Instant::now());
+    }
+
+    fn record_opportunity_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.last_opportunity_at = now;
+        state.last_alert_at = None;
+        state.blocks_since_opportunity = 0;
+    }
+
+    /// 处理一个新区块，需要告警时返回这段安静期
+    pub fn on_block(&self) -> Option<QuietPeriod> {
+        self.on_block_at(Instant::now())
+    }
+
+    fn on_block_at(&self, now: Instant) -> Option<QuietPeriod> {
+        let window = self.window?;
+        let mut state = self.state.lock().unwrap();
+        // 区块本身中断超过一个窗口 (订阅断开等) 不算检测失灵，从恢复出块时重新计时
+        if state.last_block_at.is_some_and(|at| now.duration_since(at) >= window) {
+            state.last_opportunity_at = now;
+            state.last_alert_at = None;
+            state.blocks_since_opportunity = 0;
+        }
+        state.last_block_at = Some(now);
+        state.blocks_since_opportunity += 1;
+
+        let elapsed = now.duration_since(state.last_opportunity_at);
+        let since_alert = state.last_alert_at.map(|at| now.duration_since(at));
+        if elapsed < window || since_alert.is_some_and(|d| d < window) {
+            return None;
+        }
+        state.last_alert_at = Some(now);
+        Some(QuietPeriod { elapsed, blocks: state.blocks_since_opportunity })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_period_beyond_window_alerts_once_per_window() {
+        let start = Instant::now();
+        let watchdog = OpportunityWatchdog::new_at(600, start);
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // 窗口内出块不告警
+        for secs in (12..600).step_by(12) {
+            assert_eq!(watchdog.on_block_at(at(secs)), None);
+        }
+        let alert = watchdog.on_block_at(at(612)).unwrap();
+        assert_eq!(alert.elapsed, Duration::from_secs(612));
+        assert_eq!(alert.blocks, 50);
+
+        // 告警后同一窗口内不重复，下一个窗口再提醒一次
+        assert_eq!(watchdog.on_block_at(at(900)), None);
+        assert!(watchdog.on_block_at(at(1212)).is_some());
+
+        // 发现机会后重新计时
+        watchdog.record_opportunity_at(at(1300));
+        assert_eq!(watchdog.on_block_at(at(1500)), None);
+        assert_eq!(watchdog.on_block_at(at(1800)), None);
+        assert_eq!(watchdog.on_block_at(at(1920)).map(|q| q.blocks), Some(3));
+    }
+
+    #[test]
+    fn test_block_outage_does_not_count_as_quiet() {
+        let start = Instant::now();
+        let watchdog = OpportunityWatchdog::new_at(600, start);
+        assert_eq!(watchdog.on_block_at(start + Duration::from_secs(12)), None);
+        // 区块停了一小时后恢复: 从恢复时重新计时
+        assert_eq!(watchdog.on_block_at(start + Duration::from_secs(3_612)), None);
+        assert_eq!(watchdog.on_block_at(start + Duration::from_secs(3_900)), None);
+        let alert = watchdog.on_block_at(start + Duration::from_secs(4_212)).unwrap();
+        assert_eq!((alert.elapsed, alert.blocks), (Duration::from_secs(600), 3));
+    }
+
+    #[test]
+    fn test_zero_window_disables_watchdog() {
+        let start = Instant::now();
+        let watchdog = OpportunityWatchdog::new_at(0, start);
+        assert_eq!(watchdog.on_block_at(start + Duration::from_secs(86_400)), None);
+    }
+}
@@ -244,8 +244,8 @@ impl ProfitCalculator {
                 DexType::UniswapV3 | DexType::PancakeSwapV3 | DexType::SushiSwapV3 => {
                     self.config.max_slippage * dec!(0.7) // V3 通常滑点更低
                 }
-                // Curve: 稳定币优化，滑点很低
-                DexType::Curve => {
+                // Curve / StableSwap: 稳定币优化，滑点很低
+                DexType::Curve | DexType::PancakeSwapStable => {
                     self.config.max_slippage * dec!(0.3)
                 }
                 // V4: 更高效
@@ -325,6 +325,7 @@ impl ProfitCalculator {
                 DexType::UniswapV3 | DexType::SushiSwapV3 => 180000,  // V3 tick 跨越可能消耗更多
                 DexType::PancakeSwapV3 => 170000,
                 DexType::Curve => 250000,       // Curve 复杂池
+                DexType::PancakeSwapStable => 150000, // 两币 StableSwap
                 DexType::UniswapV4 => 100000,   // V4 更高效
             };
         }
@@ -0,0 +1,150 @@
+//! 本次运行的收益分布
+//!
+//! 总利润和成功率看不出策略是否健康: 一笔大赚可能掩盖一串小亏。这里按执行结果
+//! 维护利润直方图、累计盈亏曲线上的最大回撤，以及最近若干笔的滚动胜率
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// 直方图桶边界 (USD): (<0) [0,1) [1,5) [5,20) [20,100) [100,∞)
+const BUCKET_BOUNDS_USD: [i64; 5] = [0, 1, 5, 20, 100];
+/// 滚动胜率的窗口大小 (笔)
+const ROLLING_WINDOW: usize = 50;
+
+/// 收益分布统计 (每条链一份，随 ExecutionStats 维护)
+#[derive(Debug, Clone, Default)]
+pub struct ProfitTracker {
+    bucket_counts: [u64; BUCKET_BOUNDS_USD.len() + 1],
+    samples: u64,
+    cumulative_pnl_usd: Decimal,
+    peak_pnl_usd: Decimal,
+    max_drawdown_usd: Decimal,
+    recent_wins: VecDeque<bool>,
+}
+
+impl ProfitTracker {
+    /// 记录一笔执行结果: pnl_usd 为这笔的盈亏 (revert 时为负的 gas 费)，won 为是否成功
+    pub fn record(&mut self, pnl_usd: Decimal, won: bool) {
+        let bucket = BUCKET_BOUNDS_USD
+            .iter()
+            .position(|bound| pnl_usd < Decimal::from(*bound))
+            .unwrap_or(BUCKET_BOUNDS_USD.len());
+        self.bucket_counts[bucket] += 1;
+        self.samples += 1;
+
+        self.cumulative_pnl_usd += pnl_usd;
+        self.peak_pnl_usd = self.peak_pnl_usd.max(self.cumulative_pnl_usd);
+        self.max_drawdown_usd = self.max_drawdown_usd.max(self.peak_pnl_usd - self.cumulative_pnl_usd);
+
+        if self.recent_wins.len() == ROLLING_WINDOW {
+            self.recent_wins.pop_front();
+        }
+        self.recent_wins.push_back(won);
+    }
+
+    /// 累计盈亏曲线从高点回落的最大幅度 (USD)
+    pub fn max_drawdown_usd(&self) -> Decimal {
+        self.max_drawdown_usd
+    }
+
+    /// 最近 ROLLING_WINDOW 笔的胜率 (%)，没有样本时为 None
+    pub fn rolling_win_rate_pct(&self) -> Option<f64> {
+        if self.recent_wins.is_empty() {
+            return None;
+        }
+        let wins = self.recent_wins.iter().filter(|won| **won).count();
+        Some(wins as f64 / self.recent_wins.len() as f64 * 100.0)
+    }
+
+    /// 导出快照 (API 输出用)
+    pub fn report(&self, chain_id: u64, chain_name: &str) -> ProfitReport {
+        let histogram = self
+            .bucket_counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| ProfitBucket { label: bucket_label(i), count: *count })
+            .collect();
+        ProfitReport {
+            chain_id,
+            chain_name: chain_name.to_string(),
+            samples: self.samples,
+            cumulative_pnl_usd: self.cumulative_pnl_usd,
+            max_drawdown_usd: self.max_drawdown_usd,
+            rolling_win_rate_pct: self.rolling_win_rate_pct(),
+            histogram,
+        }
+    }
+}
+
+fn bucket_label(index: usize) -> String {
+    match index {
+        0 => format!("< ${}", BUCKET_BOUNDS_USD[0]),
+        i if i == BUCKET_BOUNDS_USD.len() => format!(">= ${}", BUCKET_BOUNDS_USD[i - 1]),
+        i => format!("${}-{}", BUCKET_BOUNDS_USD[i - 1], BUCKET_BOUNDS_USD[i]),
+    }
+}
+
+/// 直方图的一个桶
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfitBucket {
+    pub label: String,
+    pub count: u64,
+}
+
+/// 单条链的收益分布快照
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfitReport {
+    pub chain_id: u64,
+    pub chain_name: String,
+    /// 已记录的执行笔数
+    pub samples: u64,
+    pub cumulative_pnl_usd: Decimal,
+    pub max_drawdown_usd: Decimal,
+    /// 最近 50 笔的胜率 (%)
+    pub rolling_win_rate_pct: Option<f64>,
+    pub histogram: Vec<ProfitBucket>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_drawdown_and_histogram_from_known_sequence() {
+        let mut tracker = ProfitTracker::default();
+        // 累计盈亏: 10 → 6 → 0 → 3 → 23，高点 10 回落到 0
+        for (pnl, won) in [(dec!(10), true), (dec!(-4), false), (dec!(-6), false), (dec!(3), true), (dec!(20), true)] {
+            tracker.record(pnl, won);
+        }
+
+        assert_eq!(tracker.max_drawdown_usd(), dec!(10));
+        assert_eq!(tracker.rolling_win_rate_pct(), Some(60.0));
+
+        let report = tracker.report(1, "Ethereum");
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.cumulative_pnl_usd, dec!(23));
+        let counts: Vec<(&str, u64)> = report.histogram.iter().map(|b| (b.label.as_str(), b.count)).collect();
+        assert_eq!(
+            counts,
+            vec![("< $0", 2), ("$0-1", 0), ("$1-5", 1), ("$5-20", 1), ("$20-100", 1), (">= $100", 0)]
+        );
+    }
+
+    #[test]
+    fn test_rolling_win_rate_only_counts_recent_window() {
+        let mut tracker = ProfitTracker::default();
+        assert_eq!(tracker.rolling_win_rate_pct(), None);
+
+        for _ in 0..ROLLING_WINDOW {
+            tracker.record(dec!(-1), false);
+        }
+        for _ in 0..ROLLING_WINDOW / 2 {
+            tracker.record(dec!(2), true);
+        }
+        assert_eq!(tracker.rolling_win_rate_pct(), Some(50.0));
+        // 回撤在最低点之后不会被后续盈利抹掉
+        assert_eq!(tracker.max_drawdown_usd(), Decimal::from(ROLLING_WINDOW as u64));
+    }
+}
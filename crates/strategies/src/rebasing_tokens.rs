@@ -0,0 +1,94 @@
+//! 余额会自动变化的代币识别
+//!
+//! aToken、stETH、AMPL 这类代币的余额在没有转账的情况下也会变化 (计息或 rebase)，
+//! 池子储备和执行前后的余额差都不再可靠。这里按已知地址和符号特征识别它们，
+//! 扫描器把识别出的代币加入黑名单，不参与路径
+
+use ethers::types::Address;
+
+/// 已知的 rebasing / 计息包装代币 (Ethereum 主网)
+const KNOWN_REBASING_TOKENS: &[(&str, &str)] = &[
+    ("0x98C23E9d8f34FEFb1B7BD6a91B7FF122F4e16F5c", "aEthUSDC"),
+    ("0x23878914EFE38d27C4D67Ab83ed1b93A74D4AbA8", "aEthUSDT"),
+    ("0x4d5F47FA6A74757f35C14fD3a6Ef8E3C9BC514E8", "aEthWETH"),
+    ("0x018008bfb33d285247A21d44E50697654f754e63", "aEthDAI"),
+    ("0xBcca60bB61934080951369a648Fb03DF4F96263C", "aUSDC (Aave V2)"),
+    ("0x030bA81f1c18d280636F32af80b9AAd02Cf0854e", "aWETH (Aave V2)"),
+    ("0xae7ab96520DE3A18E5e111B5EaAc269d0508d6C3", "stETH"),
+    ("0xD46bA6D942050d489DBd938a2C909A5d5039A161", "AMPL"),
+    ("0x2A8e1E676Ec238d8A992307B495b45B3fEAa5e86", "OUSD"),
+];
+
+/// 符号完全匹配即视为 rebasing 的代币
+const REBASING_SYMBOLS: &[&str] = &["STETH", "AMPL", "OUSD", "OETH", "USDM", "RAI"];
+
+/// 识别原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebasingReason {
+    /// 在已知列表中
+    KnownToken(&'static str),
+    /// 符号符合 aToken / rebasing 代币特征
+    SymbolPattern(String),
+}
+
+impl std::fmt::Display for RebasingReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KnownToken(name) => write!(f, "已知 rebasing 代币 {}", name),
+            Self::SymbolPattern(symbol) => write!(f, "符号 {} 符合 aToken/rebasing 特征", symbol),
+        }
+    }
+}
+
+/// 判断代币是否为 rebasing / 计息包装代币，返回识别原因
+pub fn rebasing_token_reason(address: Address, symbol: &str) -> Option<RebasingReason> {
+    if let Some((_, name)) = KNOWN_REBASING_TOKENS
+        .iter()
+        .find(|(addr, _)| addr.parse::<Address>().ok() == Some(address))
+    {
+        return Some(RebasingReason::KnownToken(name));
+    }
+
+    if is_rebasing_symbol(symbol) {
+        return Some(RebasingReason::SymbolPattern(symbol.to_string()));
+    }
+    None
+}
+
+/// 符号特征: Aave aToken (小写 a + 大写开头，如 aUSDC、aEthWETH、aArbWETH) 或已知 rebasing 符号
+fn is_rebasing_symbol(symbol: &str) -> bool {
+    let symbol = symbol.trim();
+    if REBASING_SYMBOLS.iter().any(|s| s.eq_ignore_ascii_case(symbol)) {
+        return true;
+    }
+
+    let mut chars = symbol.chars();
+    matches!(
+        (chars.next(), chars.next()),
+        (Some('a'), Some(second)) if second.is_ascii_uppercase()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_and_pattern_rebasing_tokens_flagged() {
+        let a_eth_usdc: Address = "0x98C23E9d8f34FEFb1B7BD6a91B7FF122F4e16F5c".parse().unwrap();
+        assert_eq!(
+            rebasing_token_reason(a_eth_usdc, "aEthUSDC"),
+            Some(RebasingReason::KnownToken("aEthUSDC"))
+        );
+
+        // 不在列表中但符号符合特征
+        let unknown = Address::repeat_byte(0x42);
+        assert!(matches!(rebasing_token_reason(unknown, "aArbWETH"), Some(RebasingReason::SymbolPattern(_))));
+        assert!(rebasing_token_reason(unknown, "stETH").is_some());
+
+        // 普通代币不受影响 (AAVE 治理代币、agEUR 不是 aToken)
+        for symbol in ["USDC", "WETH", "AAVE", "agEUR", "wstETH", "a"] {
+            assert_eq!(rebasing_token_reason(unknown, symbol), None, "{}", symbol);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! 启动预热
+//!
+//! 刚启动时池子缓存为空或已过期，价格服务也可能尚未拉到价格，
+//! 这时算出的机会不可信。预热结束前只检测、记录机会，不自动执行
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// 启动预热状态
+#[derive(Debug)]
+pub struct StartupWarmup {
+    /// 需要完成的成功刷新区块数
+    warmup_blocks: u64,
+    /// 启动后至少等待的时长
+    warmup_duration: Duration,
+    started_at: Instant,
+    /// 已成功完成的池子刷新次数
+    refreshed_blocks: AtomicU64,
+    completed: AtomicBool,
+}
+
+impl StartupWarmup {
+    /// 区块数和秒数都为 0 时不预热 (也不等待价格服务)，直接视为已结束
+    pub fn new(warmup_blocks: u64, warmup_secs: u64) -> Self {
+        Self {
+            warmup_blocks,
+            warmup_duration: Duration::from_secs(warmup_secs),
+            started_at: Instant::now(),
+            refreshed_blocks: AtomicU64::new(0),
+            completed: AtomicBool::new(warmup_blocks == 0 && warmup_secs == 0),
+        }
+    }
+
+    /// 记录一次成功的池子刷新
+    pub fn record_refresh(&self) {
+        self.refreshed_blocks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 已成功完成的池子刷新次数
+    pub fn refreshed_blocks(&self) -> u64 {
+        self.refreshed_blocks.load(Ordering::Relaxed)
+    }
+
+    /// 预热是否已经结束 (不做新的判断)
+    pub fn is_complete(&self) -> bool {
+        self.completed.load(Ordering::Acquire)
+    }
+
+    /// 判断预热是否结束，结束后保持结束状态
+    ///
+    /// 条件: 成功刷新区块数达到要求、启动时长达到要求、价格服务已有价格
+    pub fn check(&self, prices_ready: bool) -> bool {
+        if self.is_complete() {
+            return true;
+        }
+
+        let refreshed = self.refreshed_blocks.load(Ordering::Relaxed);
+        let ready = refreshed >= self.warmup_blocks
+            && self.started_at.elapsed() >= self.warmup_duration
+            && prices_ready;
+
+        if ready && !self.completed.swap(true, Ordering::AcqRel) {
+            info!(
+                "🔥 启动预热完成: 已刷新 {} 个区块, 耗时 {:.1}s, 开始自动执行",
+                refreshed,
+                self.started_at.elapsed().as_secs_f64()
+            );
+        }
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_until_first_refresh_and_prices() {
+        let warmup = StartupWarmup::new(1, 0);
+
+        // 尚未刷新池子
+        assert!(!warmup.check(true));
+
+        // 已刷新但价格服务为空
+        warmup.record_refresh();
+        assert!(!warmup.check(false));
+
+        // 刷新 + 价格就绪，之后保持结束状态
+        assert!(warmup.check(true));
+        assert!(warmup.check(false));
+        assert!(warmup.is_complete());
+    }
+
+    #[test]
+    fn test_requires_configured_blocks_and_duration() {
+        let by_blocks = StartupWarmup::new(3, 0);
+        by_blocks.record_refresh();
+        by_blocks.record_refresh();
+        assert!(!by_blocks.check(true));
+        by_blocks.record_refresh();
+        assert!(by_blocks.check(true));
+
+        // 时长未到时即使已刷新也不执行
+        let by_secs = StartupWarmup::new(1, 3600);
+        by_secs.record_refresh();
+        assert!(!by_secs.check(true));
+    }
+
+    #[test]
+    fn test_disabled_when_blocks_and_secs_are_zero() {
+        let disabled = StartupWarmup::new(0, 0);
+        assert!(disabled.is_complete());
+        assert!(disabled.check(false));
+
+        // 只配置时长: 不要求刷新，但仍要等到时长
+        let by_secs_only = StartupWarmup::new(0, 3600);
+        assert!(!by_secs_only.is_complete());
+        assert!(!by_secs_only.check(true));
+    }
+}
@@ -4,7 +4,8 @@
 
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
-use std::collections::VecDeque;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tracing::info;
@@ -88,6 +89,8 @@ pub struct ApiStats {
     ws_blocks: TimeWindowCounter,
     /// WebSocket Swap 事件统计
     ws_swaps: TimeWindowCounter,
+    /// 各链 Swap 订阅状态 (由区块订阅器每个新区块更新)
+    swap_streams: RwLock<HashMap<u64, SwapStreamSnapshot>>,
 }
 
 impl ApiStats {
@@ -97,6 +100,7 @@ impl ApiStats {
             rpc: TimeWindowCounter::new(),
             ws_blocks: TimeWindowCounter::new(),
             ws_swaps: TimeWindowCounter::new(),
+            swap_streams: RwLock::new(HashMap::new()),
         }
     }
 
@@ -115,6 +119,11 @@ impl ApiStats {
         self.ws_swaps.record();
     }
 
+    /// 更新某条链的 Swap 订阅状态
+    pub fn record_swap_stream(&self, snapshot: SwapStreamSnapshot) {
+        self.swap_streams.write().insert(snapshot.chain_id, snapshot);
+    }
+
     /// 获取统计快照
     pub fn get_stats(&self) -> ApiStatsSnapshot {
         let uptime = self.start_time.elapsed().as_secs();
@@ -127,6 +136,9 @@ impl ApiStats {
         let ws_block_avg = if uptime > 0 { ws_block_total as f64 / uptime as f64 } else { 0.0 };
         let ws_swap_avg = if uptime > 0 { ws_swap_total as f64 / uptime as f64 } else { 0.0 };
 
+        let mut swap_streams: Vec<SwapStreamSnapshot> = self.swap_streams.read().values().cloned().collect();
+        swap_streams.sort_by_key(|s| s.chain_id);
+
         ApiStatsSnapshot {
             uptime_seconds: uptime,
             rpc: CounterSnapshot {
@@ -150,6 +162,7 @@ impl ApiStats {
                 total: ws_swap_total,
                 avg_per_sec: ws_swap_avg,
             },
+            swap_streams,
         }
     }
 
@@ -169,6 +182,16 @@ impl ApiStats {
             ws_swap_total = s.ws_swaps.total,
             "API统计"
         );
+
+        for stream in &s.swap_streams {
+            info!(
+                target: "rpc_stats",
+                chain_id = stream.chain_id,
+                seconds_since_last_swap = ?stream.seconds_since_last_swap,
+                swap_resubscribe_count = stream.resubscribe_count,
+                "Swap订阅状态"
+            );
+        }
     }
 }
 
@@ -188,6 +211,16 @@ pub struct CounterSnapshot {
     pub avg_per_sec: f64,
 }
 
+/// 单条链的 Swap 订阅状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SwapStreamSnapshot {
+    pub chain_id: u64,
+    /// 距离上次收到 Swap 事件的秒数 (尚未收到过则为 None)
+    pub seconds_since_last_swap: Option<u64>,
+    /// 看门狗触发的 Swap 重新订阅次数
+    pub resubscribe_count: u64,
+}
+
 /// API 统计快照
 #[derive(Debug, Clone)]
 pub struct ApiStatsSnapshot {
@@ -195,6 +228,7 @@ pub struct ApiStatsSnapshot {
     pub rpc: CounterSnapshot,
     pub ws_blocks: CounterSnapshot,
     pub ws_swaps: CounterSnapshot,
+    pub swap_streams: Vec<SwapStreamSnapshot>,
 }
 
 impl std::fmt::Display for ApiStatsSnapshot {
@@ -207,7 +241,15 @@ impl std::fmt::Display for ApiStatsSnapshot {
             self.rpc.avg_per_sec,
             self.ws_blocks.total,
             self.ws_swaps.total,
-        )
+        )?;
+        for stream in &self.swap_streams {
+            match stream.seconds_since_last_swap {
+                Some(secs) => write!(f, " | 链{} Swap: {}s前", stream.chain_id, secs)?,
+                None => write!(f, " | 链{} Swap: 未收到", stream.chain_id)?,
+            }
+            write!(f, "(重订阅{}次)", stream.resubscribe_count)?;
+        }
+        Ok(())
     }
 }
 
@@ -229,6 +271,15 @@ pub fn record_ws_swap() {
     API_STATS.record_ws_swap();
 }
 
+/// 更新某条链的 Swap 订阅状态
+pub fn record_swap_stream(chain_id: u64, seconds_since_last_swap: Option<u64>, resubscribe_count: u64) {
+    API_STATS.record_swap_stream(SwapStreamSnapshot {
+        chain_id,
+        seconds_since_last_swap,
+        resubscribe_count,
+    });
+}
+
 /// 获取统计快照
 pub fn get_api_stats() -> ApiStatsSnapshot {
     API_STATS.get_stats()
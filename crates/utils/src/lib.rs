@@ -1,14 +1,18 @@
 mod logger;
+mod log_rotation;
 mod api_stats;
 mod stats_provider;
+mod telemetry;
 pub mod time_utils;
 
 pub use logger::LoggerManager;
+pub use log_rotation::{LogRotationConfig, LogRotationPeriod, RotatingWriter, SizeRollingAppender};
 pub use api_stats::{
-    record_rpc_request, record_ws_block, record_ws_swap,
-    get_api_stats, log_api_stats, ApiStatsSnapshot, CounterSnapshot,
+    record_rpc_request, record_ws_block, record_ws_swap, record_swap_stream,
+    get_api_stats, log_api_stats, ApiStatsSnapshot, CounterSnapshot, SwapStreamSnapshot,
 };
 pub use stats_provider::{RpcStatsProvider, StatsHttp};
+pub use telemetry::{flush_tracer, otlp_tracer, OtlpConfig};
 pub use time_utils::{
     now_shanghai, now_shanghai_str, now_local, now_local_str,
     utc_to_shanghai, utc_to_shanghai_str, utc_to_shanghai_format,
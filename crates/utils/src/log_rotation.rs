@@ -0,0 +1,245 @@
+//! 日志文件滚动与保留策略
+//!
+//! - 按时间滚动: 基于 tracing-appender 的 RollingFileAppender (hourly/daily/never)
+//! - 按大小滚动: 文件超过 max_size_mb 后重命名为 xxx.log.1, xxx.log.2 ...
+//! - 两种方式都只保留最近 max_files 个历史文件，更早的自动删除
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// 按时间滚动的周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotationPeriod {
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl LogRotationPeriod {
+    /// 解析配置字符串 (hourly/daily/never)，未知值按 daily 处理
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "hourly" => LogRotationPeriod::Hourly,
+            "never" => LogRotationPeriod::Never,
+            _ => LogRotationPeriod::Daily,
+        }
+    }
+
+    fn to_rotation(self) -> Rotation {
+        match self {
+            LogRotationPeriod::Hourly => Rotation::HOURLY,
+            LogRotationPeriod::Daily => Rotation::DAILY,
+            LogRotationPeriod::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// 日志滚动配置
+#[derive(Debug, Clone)]
+pub struct LogRotationConfig {
+    /// 按时间滚动的周期 (max_size_mb > 0 时不生效)
+    pub period: LogRotationPeriod,
+    /// 单个文件最大大小 (MB)，0 表示不按大小滚动
+    pub max_size_mb: u64,
+    /// 每类日志保留的历史文件数量
+    pub max_files: usize,
+}
+
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        Self {
+            period: LogRotationPeriod::Daily,
+            max_size_mb: 0,
+            max_files: 14,
+        }
+    }
+}
+
+/// 日志写入器 (按时间或按大小滚动)
+pub enum RotatingWriter {
+    Time(RollingFileAppender),
+    Size(SizeRollingAppender),
+}
+
+impl RotatingWriter {
+    /// 根据配置为单个日志文件创建写入器
+    pub fn new(dir: &str, file_name: &str, config: &LogRotationConfig) -> Self {
+        if config.max_size_mb > 0 {
+            match SizeRollingAppender::new(dir, file_name, config.max_size_mb * 1024 * 1024, config.max_files) {
+                Ok(appender) => return RotatingWriter::Size(appender),
+                Err(e) => eprintln!("创建按大小滚动日志失败 {}/{}: {}，改为按时间滚动", dir, file_name, e),
+            }
+        }
+
+        let appender = RollingFileAppender::builder()
+            .rotation(config.period.to_rotation())
+            .filename_prefix(file_name)
+            .max_log_files(config.max_files.max(1))
+            .build(dir)
+            .unwrap_or_else(|_| tracing_appender::rolling::daily(dir, file_name));
+        RotatingWriter::Time(appender)
+    }
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            RotatingWriter::Time(w) => w.write(buf),
+            RotatingWriter::Size(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            RotatingWriter::Time(w) => w.flush(),
+            RotatingWriter::Size(w) => w.flush(),
+        }
+    }
+}
+
+/// 按大小滚动的文件写入器
+///
+/// 当前文件写满后: xxx.log -> xxx.log.1, xxx.log.1 -> xxx.log.2, ...
+/// 超过 max_files 的最旧文件被删除
+pub struct SizeRollingAppender {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl SizeRollingAppender {
+    pub fn new(dir: impl AsRef<Path>, file_name: &str, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        let path = dir.as_ref().join(file_name);
+        let file = Self::open(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written,
+        })
+    }
+
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    /// 第 n 个历史文件路径 (xxx.log.n)
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            // 不保留历史文件，直接清空
+            self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (1..self.max_files).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = Self::open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRollingAppender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_rolling_retention() {
+        let dir = std::env::temp_dir().join(format!("log_rotation_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut appender = SizeRollingAppender::new(&dir, "app.log", 100, 2).unwrap();
+        let line = [b'x'; 60];
+        for _ in 0..5 {
+            appender.write_all(&line).unwrap();
+        }
+        appender.flush().unwrap();
+
+        // 每次写入都会超过 100 字节阈值，触发滚动
+        assert_eq!(fs::metadata(dir.join("app.log")).unwrap().len(), 60);
+        assert!(dir.join("app.log.1").exists());
+        assert!(dir.join("app.log.2").exists());
+        assert!(!dir.join("app.log.3").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_time_rolling_retention() {
+        let dir = std::env::temp_dir().join(format!("log_rotation_time_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // 之前运行留下的 5 个按天滚动的历史文件，以及不属于该日志的文件
+        for day in 1..=5 {
+            fs::write(dir.join(format!("app.log.2020-01-0{}", day)), b"old").unwrap();
+        }
+        fs::write(dir.join("api.log.2020-01-01"), b"other").unwrap();
+
+        let config = LogRotationConfig { period: LogRotationPeriod::Daily, max_size_mb: 0, max_files: 3 };
+        let mut writer = RotatingWriter::new(dir.to_str().unwrap(), "app.log", &config);
+        assert!(matches!(writer, RotatingWriter::Time(_)));
+        writer.write_all(b"new").unwrap();
+        writer.flush().unwrap();
+
+        // 加上当前文件共保留 max_files 个，其他日志不受影响
+        let app_logs = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.starts_with("app.log"))
+            .count();
+        assert_eq!(app_logs, 3);
+        assert!(dir.join("api.log.2020-01-01").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_rotation_period() {
+        assert_eq!(LogRotationPeriod::parse("hourly"), LogRotationPeriod::Hourly);
+        assert_eq!(LogRotationPeriod::parse("NEVER"), LogRotationPeriod::Never);
+        assert_eq!(LogRotationPeriod::parse("unknown"), LogRotationPeriod::Daily);
+    }
+}
@@ -2,12 +2,17 @@ use std::fs;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, fmt, Layer};
 use tracing_subscriber::filter::{LevelFilter, FilterFn};
 use tracing_subscriber::fmt::time::OffsetTime;
-use tracing_appender::{non_blocking, rolling};
+use tracing_appender::non_blocking;
 use time::macros::offset;
 
+use crate::log_rotation::{LogRotationConfig, RotatingWriter};
+use crate::telemetry::{flush_tracer, otlp_tracer, OtlpConfig};
+
 /// 日志管理器 - 基于target分类的日志系统
 pub struct LoggerManager {
     _guards: Vec<non_blocking::WorkerGuard>,
+    /// 启用 OTLP 导出时的 tracer，退出时刷新剩余 span
+    tracer: Option<opentelemetry_sdk::trace::Tracer>,
 }
 
 impl LoggerManager {
@@ -21,6 +26,20 @@ impl LoggerManager {
     /// - api.log: API请求日志
     /// - opportunity.log: 套利机会发现日志 (盈利机会专用)
     pub fn init() -> Self {
+        Self::init_with(LogRotationConfig::default())
+    }
+
+    /// 使用指定的滚动/保留策略初始化日志系统
+    ///
+    /// 每类日志文件独立滚动，各自保留 max_files 个历史文件
+    pub fn init_with(rotation: LogRotationConfig) -> Self {
+        Self::init_with_otlp(rotation, None)
+    }
+
+    /// 初始化日志系统，并可选地把 span 导出到 OTLP collector
+    ///
+    /// 需要在 tokio 运行时内调用 (OTLP 批量导出任务运行在 tokio 上)
+    pub fn init_with_otlp(rotation: LogRotationConfig, otlp: Option<OtlpConfig>) -> Self {
         let mut guards = Vec::new();
 
         // 创建日志目录
@@ -45,7 +64,7 @@ impl LoggerManager {
 
         // 2. 通用应用日志 (app.log)
         let (app_writer, app_guard) = {
-            let appender = rolling::daily("logs", "app.log");
+            let appender = RotatingWriter::new("logs", "app.log", &rotation);
             non_blocking(appender)
         };
         guards.push(app_guard);
@@ -66,7 +85,7 @@ impl LoggerManager {
 
         // 3. RPC 统计日志 (rpc_stats.log)
         let (rpc_stats_writer, rpc_stats_guard) = {
-            let appender = rolling::daily("logs", "rpc_stats.log");
+            let appender = RotatingWriter::new("logs", "rpc_stats.log", &rotation);
             non_blocking(appender)
         };
         guards.push(rpc_stats_guard);
@@ -83,7 +102,7 @@ impl LoggerManager {
 
         // 4. 策略执行日志 (strategy.log)
         let (strategy_writer, strategy_guard) = {
-            let appender = rolling::daily("logs", "strategy.log");
+            let appender = RotatingWriter::new("logs", "strategy.log", &rotation);
             non_blocking(appender)
         };
         guards.push(strategy_guard);
@@ -100,7 +119,7 @@ impl LoggerManager {
 
         // 5. 交易执行日志 (trade.log)
         let (trade_writer, trade_guard) = {
-            let appender = rolling::daily("logs", "trade.log");
+            let appender = RotatingWriter::new("logs", "trade.log", &rotation);
             non_blocking(appender)
         };
         guards.push(trade_guard);
@@ -117,7 +136,7 @@ impl LoggerManager {
 
         // 6. API请求日志 (api.log)
         let (api_writer, api_guard) = {
-            let appender = rolling::daily("logs", "api.log");
+            let appender = RotatingWriter::new("logs", "api.log", &rotation);
             non_blocking(appender)
         };
         guards.push(api_guard);
@@ -134,7 +153,7 @@ impl LoggerManager {
 
         // 7. 套利机会日志 (opportunity.log) - 发现盈利机会及执行日志
         let (opportunity_writer, opportunity_guard) = {
-            let appender = rolling::daily("logs", "opportunity.log");
+            let appender = RotatingWriter::new("logs", "opportunity.log", &rotation);
             non_blocking(appender)
         };
         guards.push(opportunity_guard);
@@ -150,6 +169,16 @@ impl LoggerManager {
                 matches!(metadata.target(), "arbitrage_opportunity" | "arbitrage_execution")
             }));
 
+        // 8. OpenTelemetry 链路追踪 (仅导出 span，日志事件仍写入上面的文件)
+        let (tracer, otlp_error) = match otlp.as_ref().map(otlp_tracer) {
+            Some(Ok(tracer)) => (Some(tracer), None),
+            Some(Err(e)) => (None, Some(e)),
+            None => (None, None),
+        };
+        let otel_layer = tracer
+            .clone()
+            .map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+
         // 初始化tracing订阅器
         tracing_subscriber::registry()
             .with(console_layer)
@@ -159,8 +188,23 @@ impl LoggerManager {
             .with(trade_layer)
             .with(api_layer)
             .with(opportunity_layer)
+            .with(otel_layer)
             .init();
 
-        Self { _guards: guards }
+        if let (Some(config), Some(e)) = (otlp.as_ref(), otlp_error) {
+            tracing::warn!("⚠️ OTLP 导出初始化失败 ({}): {}，仅输出本地日志", config.endpoint, e);
+        } else if let Some(config) = otlp.as_ref() {
+            tracing::info!("📡 OTLP 链路追踪已启用: {} (service={})", config.endpoint, config.service_name);
+        }
+
+        Self { _guards: guards, tracer }
+    }
+}
+
+impl Drop for LoggerManager {
+    fn drop(&mut self) {
+        if let Some(tracer) = &self.tracer {
+            flush_tracer(tracer);
+        }
     }
 }
@@ -0,0 +1,125 @@
+//! OpenTelemetry 链路追踪导出 (OTLP/HTTP)
+//!
+//! 配置 OTLP 地址后，tracing 的 span (检测 → 报价 → 执行) 会同时导出到 OTel 后端，
+//! 按机会关联各阶段耗时；未配置时不创建导出器，不影响日志输出
+
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::Tracer;
+use opentelemetry_sdk::Resource;
+
+/// OTLP 导出配置
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// traces 接收地址，如 http://localhost:4318/v1/traces
+    pub endpoint: String,
+    /// 上报的 service.name
+    pub service_name: String,
+}
+
+/// 创建批量导出到 OTLP 的 tracer (需要在 tokio 运行时内调用)
+pub fn otlp_tracer(config: &OtlpConfig) -> Result<Tracer, TraceError> {
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(config.endpoint.clone()),
+        )
+        .with_trace_config(
+            opentelemetry_sdk::trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+}
+
+/// 把尚未导出的 span 立即发送出去 (退出前调用，避免丢失最后一批)
+pub fn flush_tracer(tracer: &Tracer) {
+    if let Some(provider) = tracer.provider() {
+        for result in provider.force_flush() {
+            if let Err(e) = result {
+                eprintln!("OTLP 导出失败: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// 极简 OTLP/HTTP collector: 记录所有请求体并返回 200
+    async fn mock_collector() -> (String, Arc<parking_lot::Mutex<Vec<u8>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let received = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let sink = sink.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    loop {
+                        let n = match socket.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        buf.extend_from_slice(&chunk[..n]);
+                        let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        let headers = String::from_utf8_lossy(&buf[..header_end]).to_ascii_lowercase();
+                        let content_length = headers
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        let body_start = header_end + 4;
+                        if buf.len() < body_start + content_length {
+                            continue;
+                        }
+                        sink.lock().extend_from_slice(&buf[body_start..body_start + content_length]);
+                        buf.drain(..body_start + content_length);
+                        let _ = socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await;
+                    }
+                });
+            }
+        });
+        (endpoint, received)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_opportunity_trace_exported_with_child_spans() {
+        let (endpoint, received) = mock_collector().await;
+        let tracer = otlp_tracer(&OtlpConfig {
+            endpoint,
+            service_name: "arb-test".to_string(),
+        })
+        .unwrap();
+
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(tracer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let detection = tracing::info_span!("arbitrage_detection", chain_id = 1u64, pool = "0x01");
+            let _entered = detection.enter();
+            tracing::info_span!("quote_path", path = "USDC-WETH-USDT").in_scope(|| {});
+            tracing::info_span!("execute_opportunity", opportunity_id = "opp-1").in_scope(|| {});
+        });
+
+        tokio::task::spawn_blocking(move || flush_tracer(&tracer)).await.unwrap();
+
+        let body = received.lock().clone();
+        let contains = |needle: &str| body.windows(needle.len()).any(|w| w == needle.as_bytes());
+        for expected in ["arb-test", "arbitrage_detection", "quote_path", "execute_opportunity", "chain_id", "opp-1"] {
+            assert!(contains(expected), "collector 未收到 {}", expected);
+        }
+    }
+}
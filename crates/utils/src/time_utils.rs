@@ -46,12 +46,13 @@ pub fn now_local_str() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Timelike;
 
     #[test]
     fn test_now_shanghai() {
         let shanghai_time = now_shanghai();
         println!("上海时间: {}", shanghai_time);
-        assert!(shanghai_time.to_string().contains("+08:00"));
+        assert_eq!(shanghai_time.format("%:z").to_string(), "+08:00");
     }
 
     #[test]